@@ -0,0 +1,42 @@
+//! Probes the compiler the driver is built with for the entry points `src/bins/compat.rs` picks between,
+//! so that it builds on the nightlies just before the one in `rust-toolchain` too.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+/// A cfg to set when the compiler cannot build the probe, and the probe.
+const PROBES: &[(&str, &str)] = &[(
+    "taint_no_early_error_handler",
+    "extern crate rustc_session; pub use rustc_session::EarlyErrorHandler;",
+)];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("cargo sets `OUT_DIR`"));
+    for (cfg, source) in PROBES {
+        if !compiles(&rustc, &out_dir, cfg, source) {
+            println!("cargo:rustc-cfg={}", cfg);
+        }
+    }
+}
+
+fn compiles(rustc: &str, out_dir: &Path, name: &str, source: &str) -> bool {
+    let path = out_dir.join(format!("{}.rs", name));
+    let source = format!("#![feature(rustc_private)]\n{}\n", source);
+    if fs::write(&path, source).is_err() {
+        return true;
+    }
+    Command::new(rustc)
+        .args(["--crate-type=lib", "--emit=metadata", "--crate-name", name])
+        .arg("--out-dir")
+        .arg(out_dir)
+        .arg(&path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_or(true, |status| status.success())
+}
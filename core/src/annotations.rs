@@ -0,0 +1,174 @@
+//! The roles a crate gives to its functions and fields, in a form the analysis of the crates depending on it
+//! can load, for `--taint-crate-interfaces`.
+//!
+//! Annotations are attributes of the crate which declares them, which the analysis of another crate
+//! does not read. Each is written with the path of what it annotates, and the label it was given if any:
+//!
+//! ```json
+//! {
+//!     "version": 1,
+//!     "annotations": [
+//!         { "path": "demo::Request::body", "role": "source field", "label": "body" },
+//!         { "path": "demo::execute", "role": "sink", "label": null }
+//!     ]
+//! }
+//! ```
+
+use std::{fs, path::Path};
+
+use serde_json::{json, Value};
+
+use crate::error::Error;
+
+/// The version of the annotations format, bumped on incompatible changes.
+pub const VERSION: u64 = 1;
+
+/// The role an annotation gives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Source,
+    /// A field whose every read produces tainted data.
+    SourceField,
+    Sink,
+    /// A function which must not return tainted data, marked `#[taint::sink(return)]`.
+    ReturnSink,
+    Sanitizer,
+}
+
+impl Role {
+    pub const ALL: [Role; 5] = [
+        Role::Source,
+        Role::SourceField,
+        Role::Sink,
+        Role::ReturnSink,
+        Role::Sanitizer,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::Source => "source",
+            Role::SourceField => "source field",
+            Role::Sink => "sink",
+            Role::ReturnSink => "sink on return",
+            Role::Sanitizer => "sanitizer",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Role> {
+        Role::ALL.iter().copied().find(|role| role.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    /// The path of the function or field, like `my_crate::Request::body`.
+    pub path: String,
+    pub role: Role,
+    /// The label given with `label = "..."`, if any.
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Annotations {
+    pub annotations: Vec<Annotation>,
+}
+
+impl Annotations {
+    pub fn read(path: impl AsRef<Path>) -> Result<Annotations, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        Annotations::from_json(&text)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        fs::write(path, self.to_json()).map_err(|e| Error::io(path, e))
+    }
+
+    pub fn to_json(&self) -> String {
+        let annotations = self
+            .annotations
+            .iter()
+            .map(|annotation| {
+                json!({
+                    "path": annotation.path,
+                    "role": annotation.role.name(),
+                    "label": annotation.label,
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({ "version": VERSION, "annotations": annotations }).to_string()
+    }
+
+    pub fn from_json(text: &str) -> Result<Annotations, Error> {
+        let value: Value =
+            serde_json::from_str(text).map_err(|e| Error::format("annotations", e))?;
+        if value["version"] != VERSION {
+            return Err(Error::format(
+                "annotations",
+                format!(
+                    "unsupported version {}, expected {}",
+                    value["version"], VERSION
+                ),
+            ));
+        }
+
+        let annotations = value["annotations"]
+            .as_array()
+            .ok_or_else(|| Error::format("annotations", "missing `annotations`"))?
+            .iter()
+            .map(annotation_from_json)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::format("annotations", "malformed annotation"))?;
+        Ok(Annotations { annotations })
+    }
+}
+
+fn annotation_from_json(value: &Value) -> Option<Annotation> {
+    Some(Annotation {
+        path: value["path"].as_str()?.to_owned(),
+        role: Role::from_name(value["role"].as_str()?)?,
+        label: match &value["label"] {
+            Value::Null => None,
+            label => Some(label.as_str()?.to_owned()),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trips() {
+        let annotations = Annotations {
+            annotations: vec![
+                Annotation {
+                    path: "demo::Request::body".to_owned(),
+                    role: Role::SourceField,
+                    label: Some("body".to_owned()),
+                },
+                Annotation {
+                    path: "demo::execute".to_owned(),
+                    role: Role::Sink,
+                    label: None,
+                },
+            ],
+        };
+
+        assert_eq!(
+            Annotations::from_json(&annotations.to_json()),
+            Ok(annotations)
+        );
+    }
+
+    #[test]
+    fn unknown_roles_are_rejected() {
+        let text = r#"{ "version": 1, "annotations": [{ "path": "demo::f", "role": "filter", "label": null }] }"#;
+
+        assert_eq!(
+            Annotations::from_json(text),
+            Err(Error::format("annotations", "malformed annotation"))
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Cancelling an analysis which is still running.
+//!
+//! Tools which run the analysis in process, like an editor server or a watch mode, start it over
+//! when a file changes. They cancel the run in progress through the [`Cancellation`] of its
+//! [`Options`](crate::options::Options), from another thread, and the analysis stops promptly:
+//! the fixpoint of every function checks it once per block, and no callee is summarized once it is set.
+//! A cancelled run reports [`Error::Cancelled`](crate::error::Error::Cancelled) instead of its findings.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Shared between the analysis and the tools which may cancel it, so clones cancel the same run.
+#[derive(Debug, Default, Clone)]
+pub struct Cancellation {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Cancellation {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_cancel_the_same_run() {
+        let cancellation = Cancellation::default();
+        let handle = cancellation.clone();
+        assert!(!cancellation.is_cancelled());
+
+        std::thread::spawn(move || handle.cancel()).join().unwrap();
+        assert!(cancellation.is_cancelled());
+    }
+}
@@ -0,0 +1,219 @@
+//! The parts of the sources which changed, for `--taint-changed-only`.
+//!
+//! They are read from `git diff` against `HEAD`, which covers both staged and unstaged changes, along with
+//! the files git does not track yet, or from a file listing the paths which changed, one per line.
+//! A listed file counts as changed as a whole, while the diff says which lines changed.
+
+use std::{
+    fs,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::error::Error;
+
+/// The files which changed, by their canonical path, and the lines of each which did,
+/// or `None` if the whole file is to be taken as changed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Changes {
+    files: Vec<(PathBuf, Option<Vec<RangeInclusive<usize>>>)>,
+}
+
+impl Changes {
+    /// The changes of the working tree of the repository `dir` is in, since `HEAD`.
+    pub fn from_git(dir: &Path) -> Result<Changes, Error> {
+        let root = git(dir, &["rev-parse", "--show-toplevel"])?;
+        let root = PathBuf::from(root.trim_end());
+        let diff = git(
+            dir,
+            &[
+                "-c",
+                "core.quotepath=off",
+                "diff",
+                "HEAD",
+                "--unified=0",
+                "--no-color",
+                "--no-ext-diff",
+            ],
+        )?;
+        let mut changes = Changes::from_diff(&root, &diff);
+        let untracked = git(
+            dir,
+            &["ls-files", "--others", "--exclude-standard", "--full-name"],
+        )?;
+        for file in untracked.lines() {
+            changes.add(&root.join(file), None);
+        }
+        Ok(changes)
+    }
+
+    /// The files listed in the file at `path`, one per line, relative to the current directory.
+    pub fn read(path: impl AsRef<Path>) -> Result<Changes, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        let mut changes = Changes::default();
+        for file in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            changes.add(Path::new(file), None);
+        }
+        Ok(changes)
+    }
+
+    /// The lines added or replaced by the unified diff `diff`, whose paths are relative to `root`.
+    /// A hunk which only removes lines counts as a change of the line it removed them after.
+    /// Files the diff deletes are left out, since there is nothing left of them to analyze.
+    pub fn from_diff(root: &Path, diff: &str) -> Changes {
+        let mut changes = Changes::default();
+        let mut file = None;
+        let mut lines = vec![];
+        for line in diff.lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                if let Some(file) = file.take() {
+                    changes.add(&root.join(file), Some(std::mem::take(&mut lines)));
+                }
+                lines.clear();
+                file = path.strip_prefix("b/");
+            } else if let Some(hunk) = line.strip_prefix("@@ ") {
+                if let Some(range) = hunk_lines(hunk) {
+                    lines.push(range);
+                }
+            }
+        }
+        if let Some(file) = file {
+            changes.add(&root.join(file), Some(lines));
+        }
+        changes
+    }
+
+    /// Whether any of `lines` of `file` changed.
+    pub fn touches(&self, file: &Path, lines: RangeInclusive<usize>) -> bool {
+        let file = canonical(file);
+        self.files.iter().any(|(changed, ranges)| {
+            *changed == file
+                && ranges.as_ref().map_or(true, |ranges| {
+                    ranges
+                        .iter()
+                        .any(|range| range.start() <= lines.end() && lines.start() <= range.end())
+                })
+        })
+    }
+
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    fn add(&mut self, file: &Path, lines: Option<Vec<RangeInclusive<usize>>>) {
+        self.files.push((canonical(file), lines));
+    }
+}
+
+/// The lines of the new side of a hunk header, like `-10,2 +10,3 @@ fn main() {`.
+/// A hunk which only deletes lines has none, and gives the lines on either side of the deletion,
+/// so that deleting an attribute changes the item below it.
+fn hunk_lines(hunk: &str) -> Option<RangeInclusive<usize>> {
+    let added = hunk.split(' ').find_map(|part| part.strip_prefix('+'))?;
+    let (start, count): (usize, usize) = match added.split_once(',') {
+        Some((start, count)) => (start.parse().ok()?, count.parse().ok()?),
+        None => (added.parse().ok()?, 1),
+    };
+    Some(match count {
+        0 => start.max(1)..=start + 1,
+        count => start..=start + count - 1,
+    })
+}
+
+/// `path` with symbolic links resolved, or as it is if it does not exist.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_owned())
+}
+
+/// The output of `git` run with `args` in `dir`.
+fn git(dir: &Path, args: &[&str]) -> Result<String, Error> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| Error::io("git", e))?;
+    if !output.status.success() {
+        return Err(Error::format(
+            "git repository",
+            String::from_utf8_lossy(&output.stderr).trim(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/main.rs b/src/main.rs
+index 1c2d3e4..5f6a7b8 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -4,0 +5,2 @@ fn main() {
++    let name = input();
++    render(&name);
+@@ -20 +22 @@ fn render(text: &str) {
+-    println!(\"{}\", text);
++    print!(\"{}\", text);
+@@ -31,3 +33,0 @@ fn helper() {
+-    a();
+-    b();
+-    c();
+diff --git a/src/gone.rs b/src/gone.rs
+deleted file mode 100644
+--- a/src/gone.rs
++++ /dev/null
+@@ -1,2 +0,0 @@
+-fn gone() {}
+-
+";
+
+    #[test]
+    fn diffs_give_the_lines_of_the_new_side() {
+        let changes = Changes::from_diff(Path::new("/repo"), DIFF);
+        let main = Path::new("/repo/src/main.rs");
+        assert!(changes.touches(main, 1..=5));
+        assert!(changes.touches(main, 6..=6));
+        assert!(!changes.touches(main, 7..=21));
+        assert!(changes.touches(main, 22..=22));
+        assert!(changes.touches(main, 33..=33));
+        assert!(changes.touches(main, 34..=40));
+        assert!(!changes.touches(main, 35..=40));
+        assert!(!changes.touches(Path::new("/repo/src/other.rs"), 1..=100));
+        assert!(!changes.touches(Path::new("/repo/src/gone.rs"), 1..=2));
+    }
+
+    #[test]
+    fn deleting_an_attribute_changes_the_item_below_it() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -9 +8,0 @@ pub fn render(text: &str) {
+-#[taint::sanitizer]
+";
+        let changes = Changes::from_diff(Path::new("/repo"), diff);
+        let lib = Path::new("/repo/src/lib.rs");
+        assert!(changes.touches(lib, 9..=11));
+        assert!(!changes.touches(lib, 1..=7));
+        assert!(!changes.touches(lib, 10..=20));
+    }
+
+    #[test]
+    fn listed_files_change_as_a_whole() {
+        let dir = std::env::temp_dir().join(format!("taint-changes-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let list = dir.join("changed.txt");
+        let file = dir.join("lib.rs");
+        fs::write(&file, "").unwrap();
+        fs::write(&list, format!("{}\n\n", file.display())).unwrap();
+        let changes = Changes::read(&list).unwrap();
+        assert!(changes.touches(&file, 1000..=1001));
+        assert!(!changes.touches(&dir.join("main.rs"), 1..=1));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
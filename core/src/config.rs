@@ -0,0 +1,951 @@
+//! The policy of a project, kept in `taint.json` next to where the driver is run,
+//! which is the root of the workspace under `cargo taint`.
+//! Another file can be used with `--taint-config=<path>` or `TAINT_CONFIG=<path>`, in which case
+//! `taint.json` is not read.
+//!
+//! ```json
+//! {
+//!     "version": 1,
+//!     "labels": {
+//!         "user-input": ["query-param", "header"]
+//!     },
+//!     "propagation": {
+//!         "my_crate::concat": { "result_from": [0, 1] },
+//!         "my_crate::store": { "writes": { "0": [1] } }
+//!     },
+//!     "statics": {
+//!         "my_crate::ffi::INPUT_BUF": { "label": "user-input" }
+//!     },
+//!     "trusted_unsafe": ["core::str::from_utf8_unchecked"],
+//!     "aliases": {
+//!         "engine::exec::run_query": ["facade::query", "facade::prelude::query"]
+//!     },
+//!     "tracked_types": ["string", "bytes", "user"],
+//!     "sanitizer_modes": {
+//!         "my_crate::escape": { "arg": 1, "modes": { "my_crate::Mode::Html": ["xss"] } },
+//!         "my_crate::quote": { "arg": 1, "modes": { "\"sql\"": ["sqli"] } }
+//!     },
+//!     "hashing": {
+//!         "labels": { "user-input": "clear", "secret": "propagate" },
+//!         "functions": ["my_crate::fingerprint"]
+//!     },
+//!     "clones": ["secret"],
+//!     "profiles": {
+//!         "web": ["--taint-library", "--taint-sink-packs=process"],
+//!         "admin": ["--taint-mode=precise"]
+//!     },
+//!     "owners": {
+//!         "crate::payments::*": "team-payments",
+//!         "crate::payments::refunds::*": "team-refunds"
+//!     },
+//!     "modules": {
+//!         "my_crate::generated::grpc": { "role": "source_boundary", "label": "grpc" },
+//!         "my_crate::vendored": { "role": "trusted" }
+//!     }
+//! }
+//! ```
+//!
+//! `labels` declares which labels subsume which: a sink checking `user-input`
+//! also reports values labeled `query-param` or `header`.
+//!
+//! `propagation` describes how data flows through functions which are not analyzed:
+//! the result of `concat` carries the labels of its first two arguments,
+//! and `store` writes the labels of its second argument into what its first one points to.
+//! Calls to them follow the rule instead of analyzing the callee.
+//!
+//! `statics` marks statics as sources, like those declared in `extern` blocks which foreign code
+//! writes to: every read of `INPUT_BUF` produces data labeled `user-input`, or `taint` without a `label`.
+//!
+//! `trusted_unsafe` lists the unsafe functions which the `unsafe` sink pack lets tainted data into.
+//!
+//! `aliases` lists the other paths an item can be named by, like those a facade crate re-exports it under,
+//! by the path it is defined at. Paths given anywhere else, in the config or on the command line,
+//! can then be any of them.
+//!
+//! `tracked_types` restricts taint to values which may hold data of these kinds, for projects which only
+//! care about injection: a variable holding only numbers or booleans, like the length of tainted input,
+//! is then never tainted. `user` stands for the types of other crates than the standard library,
+//! and values of generic or opaque types are always tracked. Every kind is tracked without the key.
+//!
+//! `sanitizer_modes` makes what a function sanitizes depend on a constant argument, the second one here:
+//! `escape(s, Mode::Html)` removes `xss`, and the labels it subsumes, from `s`, and `quote(s, "sql")`
+//! removes `sqli`. Modes are written as the path of a variant without fields, `true` or `false`,
+//! an integer, or a string in quotes. A call passing anything else, or a value only known at run time,
+//! is handled as if the function had no modes.
+//!
+//! `hashing` decides which labels the digest of tainted data keeps: hashing removes the risk of injection,
+//! so `user-input`, and the labels it subsumes, is cleared from what hash functions return, but a digest
+//! of a secret can still leak it, so `secret` propagates. Labels not listed propagate, as they do without the key.
+//! Common hash APIs, like `Hasher::finish` or `Digest::digest`, are recognized, and `functions` lists others.
+//!
+//! `clones` lists the labels whose copies are tracked: each call to `Clone::clone` in the crate on a value
+//! labeled `secret`, or a label it subsumes, is reported as a T0013 warning, whether or not the copy reaches
+//! a sink, to show where secrets sprawl. What the clone carries is decided like without the key.
+//!
+//! `profiles` names sets of options which the entry points listed with `--taint-entries` can be analyzed with,
+//! on top of those of the command line, so that an HTTP handler can be held to a stricter policy than
+//! an admin tool in the same run. Findings are tagged with the profile they were found with.
+//!
+//! `owners` assigns findings to the teams owning the functions they are found in, for routing them to
+//! whoever triages them. A pattern is the path of a function, or of a module followed by `::*` for everything
+//! inside it, where `crate` stands for the crate analyzed. The longest pattern matching a function decides
+//! its owner, so that `refunds` belongs to `team-refunds` and the rest of `payments` to `team-payments`.
+//!
+//! `modules` gives a role to every function defined in a module of the crate, like `#![taint::source_boundary]`
+//! and `#![taint::trusted]` do from inside it: each function of `grpc` is a source of data labeled `grpc`,
+//! like the messages it decodes, and calls to those of `vendored` return clean data. Modules are named
+//! by their path, which can start with the name of the crate or `crate`, or leave it out.
+
+use std::{fs, path::Path};
+
+use serde_json::Value;
+
+use crate::{
+    error::Error,
+    summaries::{FunctionSummary, Summaries},
+};
+
+/// The version of the config format, bumped on incompatible changes.
+pub const VERSION: u64 = 1;
+
+/// The file the config is read from when there is one.
+pub const FILE_NAME: &str = "taint.json";
+
+/// The most labels an analysis can tell apart, `taint` included.
+pub const MAX_LABELS: usize = 64;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Labels and the labels they directly subsume.
+    pub labels: Vec<(String, Vec<String>)>,
+    /// How data flows through the functions with a propagation rule.
+    pub propagation: Summaries,
+    /// Paths of statics which are sources, and the label they put on their data if they have one.
+    pub statics: Vec<(String, Option<String>)>,
+    /// Paths of the unsafe functions the `unsafe` sink pack does not check.
+    pub trusted_unsafe: Vec<String>,
+    /// Paths items are defined at, and the other paths they are re-exported under.
+    pub aliases: Vec<(String, Vec<String>)>,
+    /// The kinds of data which can carry taint, or `None` for all of them.
+    pub tracked_types: Option<Vec<TypeClass>>,
+    /// Sanitizers whose effect depends on the constant passed as one of their arguments.
+    pub sanitizer_modes: Vec<SanitizerModes>,
+    /// What hash functions do with each label, and the hash functions besides those recognized.
+    pub hashing: Hashing,
+    /// Labels whose clones are reported.
+    pub clones: Vec<String>,
+    /// Names of profiles, and the options they add to those of the command line.
+    pub profiles: Vec<(String, Vec<String>)>,
+    /// Patterns of paths, and the team owning the functions they match.
+    pub owners: Vec<(String, String)>,
+    /// Modules whose every function is given a role.
+    pub modules: Vec<ModuleRole>,
+}
+
+/// A role given to every function defined in a module, and in the modules inside it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleRole {
+    pub module: String,
+    pub kind: ModuleKind,
+    /// The label the functions of a source boundary put on their data, if any.
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleKind {
+    /// Each function is a source, like the decoders of generated code.
+    SourceBoundary,
+    /// Calls to each function return clean data, like the code of a vetted dependency.
+    Trusted,
+}
+
+impl ModuleKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ModuleKind::SourceBoundary => "source_boundary",
+            ModuleKind::Trusted => "trusted",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ModuleKind> {
+        [ModuleKind::SourceBoundary, ModuleKind::Trusted]
+            .iter()
+            .copied()
+            .find(|kind| kind.name() == name)
+    }
+}
+
+/// What a sanitizer removes under each of the constants one of its arguments can be.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizerModes {
+    pub function: String,
+    /// The index of the argument selecting the mode.
+    pub arg: usize,
+    /// The constants the argument can be, and the labels the sanitizer removes when it is.
+    pub modes: Vec<(String, Vec<String>)>,
+}
+
+/// How the labels of tainted data go through hashing.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Hashing {
+    /// Labels, and whether the digests of data carrying them still do.
+    pub labels: Vec<(String, HashPolicy)>,
+    /// Paths of the functions returning a digest besides the common hash APIs.
+    pub functions: Vec<String>,
+}
+
+/// What the digest of data carrying a label carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashPolicy {
+    /// The digest carries the label, like one of a secret which can be brute-forced.
+    Propagate,
+    /// The digest is clean of the label, and of those it subsumes, like one of input which could inject.
+    Clear,
+}
+
+impl HashPolicy {
+    pub fn name(self) -> &'static str {
+        match self {
+            HashPolicy::Propagate => "propagate",
+            HashPolicy::Clear => "clear",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<HashPolicy> {
+        [HashPolicy::Propagate, HashPolicy::Clear]
+            .iter()
+            .copied()
+            .find(|policy| policy.name() == name)
+    }
+}
+
+/// A kind of data `tracked_types` can restrict taint to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TypeClass {
+    /// Strings, characters, paths and OS strings.
+    String,
+    /// Slices, arrays and vectors of bytes.
+    Bytes,
+    /// Integers and floats.
+    Number,
+    Bool,
+    /// Types defined outside of the standard library.
+    User,
+}
+
+impl TypeClass {
+    pub const ALL: [TypeClass; 5] = [
+        TypeClass::String,
+        TypeClass::Bytes,
+        TypeClass::Number,
+        TypeClass::Bool,
+        TypeClass::User,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TypeClass::String => "string",
+            TypeClass::Bytes => "bytes",
+            TypeClass::Number => "number",
+            TypeClass::Bool => "bool",
+            TypeClass::User => "user",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<TypeClass> {
+        TypeClass::ALL
+            .iter()
+            .copied()
+            .find(|class| class.name() == name)
+    }
+}
+
+/// How specific the pattern of `owners` is if it matches `function`, a path as findings report it,
+/// in the crate named `krate`: the length of what it names.
+pub fn owner_match(pattern: &str, krate: &str, function: &str) -> Option<usize> {
+    // The methods of trait impls are reported like `<payments::Card as Display>::fmt`.
+    let function = function.trim_start_matches('<');
+    let local = format!("{}::", krate);
+    let pattern = pattern
+        .strip_prefix("crate::")
+        .or_else(|| pattern.strip_prefix(&local))
+        .unwrap_or(pattern);
+    let matches = match pattern.strip_suffix('*') {
+        Some(module) => function.starts_with(module),
+        None => function == pattern,
+    };
+    matches.then_some(pattern.len())
+}
+
+impl Config {
+    /// Reads the config at `path` if one was given, which must exist.
+    /// Otherwise reads `taint.json` from the current directory, or returns an empty config if there is none.
+    pub fn discover(path: Option<&Path>) -> Result<Config, Error> {
+        if let Some(path) = path {
+            return Config::read(path);
+        }
+        let path = Path::new(FILE_NAME);
+        if path.is_file() {
+            Config::read(path)
+        } else {
+            Ok(Config::default())
+        }
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Config, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        Config::from_json(&text)
+    }
+
+    pub fn from_json(text: &str) -> Result<Config, Error> {
+        let value: Value = serde_json::from_str(text).map_err(|e| Error::format("config", e))?;
+        if value["version"] != VERSION {
+            return Err(Error::format(
+                "config",
+                format!(
+                    "unsupported version {}, expected {}",
+                    value["version"], VERSION
+                ),
+            ));
+        }
+
+        let mut config = Config::default();
+        if let Some(labels) = value.get("labels") {
+            let labels = labels
+                .as_object()
+                .ok_or_else(|| Error::format("config", "`labels` must be an object"))?;
+            for (label, subsumed) in labels {
+                let subsumed = strings(subsumed).ok_or_else(|| {
+                    Error::format(
+                        "config",
+                        format!("the labels subsumed by `{}` must be strings", label),
+                    )
+                })?;
+                config.labels.push((label.clone(), subsumed));
+            }
+        }
+        if let Some(rules) = value.get("propagation") {
+            let rules = rules
+                .as_object()
+                .ok_or_else(|| Error::format("config", "`propagation` must be an object"))?;
+            for (function, rule) in rules {
+                let summary = propagation_rule(function, rule).ok_or_else(|| {
+                    Error::format(
+                        "config",
+                        format!("malformed propagation rule for `{}`", function),
+                    )
+                })?;
+                config.propagation.summaries.push(summary);
+            }
+        }
+        if let Some(statics) = value.get("statics") {
+            let statics = statics
+                .as_object()
+                .ok_or_else(|| Error::format("config", "`statics` must be an object"))?;
+            for (path, source) in statics {
+                let label = match source.get("label") {
+                    Some(label) => label.as_str().map(|label| Some(label.to_owned())),
+                    None => source.is_object().then_some(None),
+                };
+                let label = label.ok_or_else(|| {
+                    Error::format("config", format!("malformed source static `{}`", path))
+                })?;
+                config.statics.push((path.clone(), label));
+            }
+        }
+        if let Some(trusted) = value.get("trusted_unsafe") {
+            let malformed = || Error::format("config", "`trusted_unsafe` must be a list of paths");
+            for path in trusted.as_array().ok_or_else(malformed)? {
+                let path = path.as_str().ok_or_else(malformed)?;
+                config.trusted_unsafe.push(path.to_owned());
+            }
+        }
+        if let Some(aliases) = value.get("aliases") {
+            let aliases = aliases
+                .as_object()
+                .ok_or_else(|| Error::format("config", "`aliases` must be an object"))?;
+            for (path, others) in aliases {
+                let others = strings(others).ok_or_else(|| {
+                    Error::format(
+                        "config",
+                        format!("the aliases of `{}` must be strings", path),
+                    )
+                })?;
+                config.aliases.push((path.clone(), others));
+            }
+        }
+        if let Some(tracked) = value.get("tracked_types") {
+            let classes = strings(tracked).and_then(|names| {
+                names
+                    .iter()
+                    .map(|name| TypeClass::from_name(name))
+                    .collect()
+            });
+            let classes = classes.ok_or_else(|| {
+                Error::format(
+                    "config",
+                    "`tracked_types` must be a list of `string`, `bytes`, `number`, `bool` or `user`",
+                )
+            })?;
+            config.tracked_types = Some(classes);
+        }
+        if let Some(sanitizers) = value.get("sanitizer_modes") {
+            let sanitizers = sanitizers
+                .as_object()
+                .ok_or_else(|| Error::format("config", "`sanitizer_modes` must be an object"))?;
+            for (function, modes) in sanitizers {
+                let modes = sanitizer_modes(function, modes).ok_or_else(|| {
+                    Error::format(
+                        "config",
+                        format!("malformed sanitizer modes for `{}`", function),
+                    )
+                })?;
+                config.sanitizer_modes.push(modes);
+            }
+        }
+        if let Some(hashing) = value.get("hashing") {
+            config.hashing = self::hashing(hashing).ok_or_else(|| {
+                Error::format(
+                    "config",
+                    "`hashing` must map labels to `propagate` or `clear`, and list `functions` as paths",
+                )
+            })?;
+        }
+        if let Some(clones) = value.get("clones") {
+            config.clones = strings(clones)
+                .ok_or_else(|| Error::format("config", "`clones` must list labels"))?;
+        }
+        if let Some(profiles) = value.get("profiles") {
+            let profiles = profiles
+                .as_object()
+                .ok_or_else(|| Error::format("config", "`profiles` must be an object"))?;
+            for (name, options) in profiles {
+                let options = strings(options).ok_or_else(|| {
+                    Error::format(
+                        "config",
+                        format!("the options of profile `{}` must be strings", name),
+                    )
+                })?;
+                config.profiles.push((name.clone(), options));
+            }
+        }
+        if let Some(owners) = value.get("owners") {
+            let owners = owners
+                .as_object()
+                .ok_or_else(|| Error::format("config", "`owners` must be an object"))?;
+            for (pattern, owner) in owners {
+                let owner = owner.as_str().ok_or_else(|| {
+                    Error::format(
+                        "config",
+                        format!("the owner of `{}` must be a string", pattern),
+                    )
+                })?;
+                config.owners.push((pattern.clone(), owner.to_owned()));
+            }
+        }
+        if let Some(modules) = value.get("modules") {
+            let modules = modules
+                .as_object()
+                .ok_or_else(|| Error::format("config", "`modules` must be an object"))?;
+            for (module, role) in modules {
+                let role = module_role(module, role).ok_or_else(|| {
+                    Error::format(
+                        "config",
+                        format!(
+                            "the role of module `{}` must be `source_boundary`, with an optional `label`, or `trusted`",
+                            module
+                        ),
+                    )
+                })?;
+                config.modules.push(role);
+            }
+        }
+        let declared = config.label_names().len();
+        if declared >= MAX_LABELS {
+            return Err(Error::format(
+                "config",
+                format!(
+                    "{} labels are declared, but at most {} can be told apart besides `taint`",
+                    declared,
+                    MAX_LABELS - 1
+                ),
+            ));
+        }
+        Ok(config)
+    }
+
+    /// The names of the labels the config declares or mentions, besides `taint`, each once.
+    fn label_names(&self) -> Vec<&str> {
+        let hierarchy = self
+            .labels
+            .iter()
+            .flat_map(|(label, subsumed)| std::iter::once(label).chain(subsumed));
+        let statics = self.statics.iter().filter_map(|(_, label)| label.as_ref());
+        let modes = self
+            .sanitizer_modes
+            .iter()
+            .flat_map(|sanitizer| sanitizer.modes.iter().flat_map(|(_, labels)| labels));
+        let hashing = self.hashing.labels.iter().map(|(label, _)| label);
+        let modules = self.modules.iter().filter_map(|role| role.label.as_ref());
+        let mut names = hierarchy
+            .chain(statics)
+            .chain(modes)
+            .chain(hashing)
+            .chain(&self.clones)
+            .chain(modules)
+            .map(String::as_str)
+            .filter(|&name| name != "taint")
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// The team owning `function`, a path as findings report it, in the crate named `krate`:
+    /// that of the longest pattern of `owners` matching it. Items of the crate analyzed are reported
+    /// without its name, and patterns can name it or write `crate` instead.
+    pub fn owner(&self, krate: &str, function: &str) -> Option<&str> {
+        self.owners
+            .iter()
+            .filter_map(|(pattern, owner)| {
+                owner_match(pattern, krate, function)
+                    .map(|specificity| (specificity, owner.as_str()))
+            })
+            .max_by_key(|&(specificity, _)| specificity)
+            .map(|(_, owner)| owner)
+    }
+
+    /// The functions whose propagation rule, sanitizer modes or trust as an unsafe function differ between
+    /// the configs, if nothing else does. Such changes only matter to the code calling these functions,
+    /// whereas any other, like a change to the labels, can change what every function does.
+    pub fn changed_functions(&self, other: &Config) -> Option<Vec<String>> {
+        let rest = |config: &Config| Config {
+            propagation: Summaries::default(),
+            sanitizer_modes: vec![],
+            trusted_unsafe: vec![],
+            // Owners are only given to the findings reported, which are not carried over.
+            owners: vec![],
+            ..config.clone()
+        };
+        if rest(self) != rest(other) {
+            return None;
+        }
+
+        let mut changed = vec![];
+        for (ours, theirs) in [(self, other), (other, self)] {
+            for rule in &ours.propagation.summaries {
+                if !theirs.propagation.summaries.contains(rule) {
+                    changed.push(rule.function.clone());
+                }
+            }
+            for modes in &ours.sanitizer_modes {
+                if !theirs.sanitizer_modes.contains(modes) {
+                    changed.push(modes.function.clone());
+                }
+            }
+            for function in &ours.trusted_unsafe {
+                if !theirs.trusted_unsafe.contains(function) {
+                    changed.push(function.clone());
+                }
+            }
+        }
+        changed.sort();
+        changed.dedup();
+        Some(changed)
+    }
+}
+
+/// The summary of `function` described by `rule`, like `{ "result_from": [0], "writes": { "0": [1] } }`.
+fn propagation_rule(function: &str, rule: &Value) -> Option<FunctionSummary> {
+    let rule = rule.as_object()?;
+    let result_from = match rule.get("result_from") {
+        Some(from) => indices(from)?,
+        None => vec![],
+    };
+    let mut writes = vec![];
+    if let Some(written) = rule.get("writes") {
+        for (arg, from) in written.as_object()? {
+            writes.push((arg.parse().ok()?, indices(from)?));
+        }
+    }
+    Some(FunctionSummary::rule(function, result_from, &writes))
+}
+
+/// The modes of `function` described by `modes`, like `{ "arg": 1, "modes": { "demo::Mode::Html": ["xss"] } }`.
+fn sanitizer_modes(function: &str, modes: &Value) -> Option<SanitizerModes> {
+    Some(SanitizerModes {
+        function: function.to_owned(),
+        arg: modes.get("arg")?.as_u64()? as usize,
+        modes: modes
+            .get("modes")?
+            .as_object()?
+            .iter()
+            .map(|(mode, labels)| Some((mode.clone(), strings(labels)?)))
+            .collect::<Option<_>>()?,
+    })
+}
+
+/// How labels go through hashing as described by `hashing`,
+/// like `{ "labels": { "sqli": "clear" }, "functions": ["demo::fingerprint"] }`.
+fn hashing(hashing: &Value) -> Option<Hashing> {
+    let hashing = hashing.as_object()?;
+    let labels = match hashing.get("labels") {
+        Some(labels) => labels
+            .as_object()?
+            .iter()
+            .map(|(label, policy)| Some((label.clone(), HashPolicy::from_name(policy.as_str()?)?)))
+            .collect::<Option<_>>()?,
+        None => vec![],
+    };
+    let functions = match hashing.get("functions") {
+        Some(functions) => strings(functions)?,
+        None => vec![],
+    };
+    Some(Hashing { labels, functions })
+}
+
+/// The role of `module` described by `role`, like `{ "role": "source_boundary", "label": "grpc" }`.
+fn module_role(module: &str, role: &Value) -> Option<ModuleRole> {
+    let kind = ModuleKind::from_name(role.get("role")?.as_str()?)?;
+    let label = match role.get("label") {
+        Some(label) if kind == ModuleKind::SourceBoundary => Some(label.as_str()?.to_owned()),
+        Some(_) => return None,
+        None => None,
+    };
+    Some(ModuleRole {
+        module: module.to_owned(),
+        kind,
+        label,
+    })
+}
+
+fn indices(value: &Value) -> Option<Vec<usize>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|ix| ix.as_u64().map(|ix| ix as usize))
+        .collect()
+}
+
+fn strings(value: &Value) -> Option<Vec<String>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|value| value.as_str().map(str::to_owned))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::summaries::Flow;
+
+    #[test]
+    fn labels_are_read() {
+        let config = Config::from_json(
+            r#"{ "version": 1, "labels": { "user-input": ["query-param", "header"] } }"#,
+        );
+
+        assert_eq!(
+            config,
+            Ok(Config {
+                labels: vec![(
+                    "user-input".to_owned(),
+                    vec!["query-param".to_owned(), "header".to_owned()]
+                )],
+                ..Config::default()
+            })
+        );
+    }
+
+    #[test]
+    fn propagation_rules_are_read_as_summaries() {
+        let config = Config::from_json(
+            r#"{ "version": 1, "propagation": { "demo::store": { "result_from": [0], "writes": { "1": [2] } } } }"#,
+        )
+        .unwrap();
+        let flow = |from: Vec<usize>| Flow {
+            from,
+            labels: vec![],
+        };
+
+        assert_eq!(
+            config.propagation.summaries,
+            vec![FunctionSummary {
+                function: "demo::store".to_owned(),
+                returns: flow(vec![0]),
+                args: vec![flow(vec![0]), flow(vec![1, 2])],
+                points_into: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn source_statics_are_read() {
+        let config = Config::from_json(
+            r#"{ "version": 1, "statics": { "demo::INPUT": {}, "demo::HEADER": { "label": "header" } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.statics,
+            vec![
+                ("demo::HEADER".to_owned(), Some("header".to_owned())),
+                ("demo::INPUT".to_owned(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn trusted_unsafe_functions_are_read() {
+        let config = Config::from_json(
+            r#"{ "version": 1, "trusted_unsafe": ["core::str::from_utf8_unchecked"] }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.trusted_unsafe,
+            vec!["core::str::from_utf8_unchecked"]
+        );
+        assert_eq!(
+            Config::from_json(r#"{ "version": 1, "trusted_unsafe": [1] }"#),
+            Err(Error::format(
+                "config",
+                "`trusted_unsafe` must be a list of paths"
+            ))
+        );
+    }
+
+    #[test]
+    fn aliases_are_read() {
+        let config = Config::from_json(
+            r#"{ "version": 1, "aliases": { "engine::run_query": ["facade::query"] } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.aliases,
+            vec![(
+                "engine::run_query".to_owned(),
+                vec!["facade::query".to_owned()]
+            )]
+        );
+        assert_eq!(
+            Config::from_json(
+                r#"{ "version": 1, "aliases": { "engine::run_query": "facade::query" } }"#
+            ),
+            Err(Error::format(
+                "config",
+                "the aliases of `engine::run_query` must be strings"
+            ))
+        );
+    }
+
+    #[test]
+    fn tracked_types_are_read() {
+        let config =
+            Config::from_json(r#"{ "version": 1, "tracked_types": ["string", "user"] }"#).unwrap();
+
+        assert_eq!(
+            config.tracked_types,
+            Some(vec![TypeClass::String, TypeClass::User])
+        );
+        assert_eq!(Config::default().tracked_types, None);
+        assert_eq!(
+            Config::from_json(r#"{ "version": 1, "tracked_types": ["integer"] }"#),
+            Err(Error::format(
+                "config",
+                "`tracked_types` must be a list of `string`, `bytes`, `number`, `bool` or `user`"
+            ))
+        );
+    }
+
+    #[test]
+    fn sanitizer_modes_are_read() {
+        let config = Config::from_json(
+            r#"{ "version": 1, "sanitizer_modes": { "demo::escape": { "arg": 1, "modes": { "demo::Mode::Html": ["xss"] } } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.sanitizer_modes,
+            vec![SanitizerModes {
+                function: "demo::escape".to_owned(),
+                arg: 1,
+                modes: vec![("demo::Mode::Html".to_owned(), vec!["xss".to_owned()])],
+            }]
+        );
+        assert_eq!(
+            Config::from_json(
+                r#"{ "version": 1, "sanitizer_modes": { "demo::escape": { "modes": {} } } }"#
+            ),
+            Err(Error::format(
+                "config",
+                "malformed sanitizer modes for `demo::escape`"
+            ))
+        );
+    }
+
+    #[test]
+    fn profiles_are_read() {
+        let config = Config::from_json(
+            r#"{ "version": 1, "profiles": { "web": ["--taint-library"], "admin": [] } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.profiles,
+            vec![
+                ("admin".to_owned(), vec![]),
+                ("web".to_owned(), vec!["--taint-library".to_owned()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn hashing_policies_are_read() {
+        let config = Config::from_json(
+            r#"{ "version": 1, "hashing": { "labels": { "sqli": "clear", "secret": "propagate" }, "functions": ["demo::fingerprint"] } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.hashing,
+            Hashing {
+                labels: vec![
+                    ("secret".to_owned(), HashPolicy::Propagate),
+                    ("sqli".to_owned(), HashPolicy::Clear),
+                ],
+                functions: vec!["demo::fingerprint".to_owned()],
+            }
+        );
+        assert!(Config::from_json(
+            r#"{ "version": 1, "hashing": { "labels": { "sqli": "forget" } } }"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn tracked_clones_are_read() {
+        let config =
+            Config::from_json(r#"{ "version": 1, "clones": ["secret", "token"] }"#).unwrap();
+
+        assert_eq!(config.clones, vec!["secret".to_owned(), "token".to_owned()]);
+        assert!(Config::from_json(r#"{ "version": 1, "clones": "secret" }"#).is_err());
+    }
+
+    #[test]
+    fn module_roles_are_read() {
+        let config = Config::from_json(
+            r#"{ "version": 1, "modules": { "demo::grpc": { "role": "source_boundary", "label": "grpc" }, "demo::vendored": { "role": "trusted" } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.modules,
+            vec![
+                ModuleRole {
+                    module: "demo::grpc".to_owned(),
+                    kind: ModuleKind::SourceBoundary,
+                    label: Some("grpc".to_owned()),
+                },
+                ModuleRole {
+                    module: "demo::vendored".to_owned(),
+                    kind: ModuleKind::Trusted,
+                    label: None,
+                },
+            ]
+        );
+        assert!(Config::from_json(
+            r#"{ "version": 1, "modules": { "demo::vendored": { "role": "trusted", "label": "grpc" } } }"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn owners_are_found_by_the_longest_pattern() {
+        let config = Config::from_json(
+            r#"{ "version": 1, "owners": { "crate::payments::*": "team-payments", "demo::payments::refunds::*": "team-refunds", "demo::admin::purge": "team-platform" } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.owner("demo", "payments::charge"),
+            Some("team-payments")
+        );
+        assert_eq!(
+            config.owner("demo", "payments::refunds::issue"),
+            Some("team-refunds")
+        );
+        assert_eq!(
+            config.owner("demo", "<payments::Card as std::fmt::Display>::fmt"),
+            Some("team-payments")
+        );
+        assert_eq!(config.owner("demo", "admin::purge"), Some("team-platform"));
+        assert_eq!(config.owner("demo", "admin::purge_all"), None);
+        assert_eq!(
+            config.owner("other", "payments::refunds::issue"),
+            Some("team-payments")
+        );
+        assert!(Config::from_json(r#"{ "version": 1, "owners": { "crate::*": 1 } }"#).is_err());
+    }
+
+    #[test]
+    fn changes_to_the_rules_of_functions_are_told_apart_from_others() {
+        let config = Config::from_json(
+            r#"{ "version": 1, "propagation": { "demo::concat": { "result_from": [0, 1] } }, "trusted_unsafe": ["demo::raw"] }"#,
+        )
+        .unwrap();
+        let rules = Config::from_json(
+            r#"{ "version": 1, "propagation": { "demo::concat": { "result_from": [0] }, "demo::store": { "writes": { "0": [1] } } }, "trusted_unsafe": ["demo::raw"] }"#,
+        )
+        .unwrap();
+        let labels = Config::from_json(
+            r#"{ "version": 1, "labels": { "user-input": ["header"] }, "propagation": { "demo::concat": { "result_from": [0, 1] } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.changed_functions(&config), Some(vec![]));
+        assert_eq!(
+            config.changed_functions(&rules),
+            Some(vec!["demo::concat".to_owned(), "demo::store".to_owned()])
+        );
+        assert_eq!(config.changed_functions(&labels), None);
+    }
+
+    #[test]
+    fn too_many_labels_are_rejected() {
+        let labels = |count: usize| {
+            let names = (0..count)
+                .map(|ix| format!("\"label-{}\": []", ix))
+                .collect::<Vec<_>>();
+            format!(
+                r#"{{ "version": 1, "labels": {{ {} }} }}"#,
+                names.join(", ")
+            )
+        };
+
+        assert!(Config::from_json(&labels(MAX_LABELS - 1)).is_ok());
+        assert_eq!(
+            Config::from_json(&labels(MAX_LABELS)),
+            Err(Error::format(
+                "config",
+                "64 labels are declared, but at most 63 can be told apart besides `taint`"
+            ))
+        );
+    }
+
+    #[test]
+    fn malformed_labels_are_rejected() {
+        let config = Config::from_json(r#"{ "version": 1, "labels": { "user-input": [1] } }"#);
+
+        assert_eq!(
+            config,
+            Err(Error::format(
+                "config",
+                "the labels subsumed by `user-input` must be strings"
+            ))
+        );
+    }
+}
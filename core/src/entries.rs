@@ -0,0 +1,89 @@
+//! The entry points to analyze, for `--taint-entries`.
+//!
+//! By default, the analysis starts from `main`, or from the public functions of a crate without one.
+//! With `--taint-entries=<file>`, it starts from the functions `file` lists instead, one per line by path,
+//! each followed by the name of a profile of the config if it is to be analyzed with its options.
+//! Blank lines and lines starting with `#` are skipped.
+//!
+//! ```text
+//! # Reached by anyone over HTTP.
+//! app::http::handle web
+//! app::admin::run admin
+//! app::jobs::cleanup
+//! ```
+//!
+//! The same file can list the entry points of every crate of a workspace: each crate is analyzed
+//! from those of its own functions the file lists, if any.
+
+use std::{fs, path::Path};
+
+use crate::error::Error;
+
+/// An entry point listed in the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The path of the function, with or without the name of its crate.
+    pub path: String,
+    /// The profile of the config to analyze it with, or `None` for the options of the command line alone.
+    pub profile: Option<String>,
+}
+
+/// The entry points listed in the file at `path`.
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<Entry>, Error> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+    parse(&text)
+}
+
+/// The entry points listed in `text`.
+pub fn parse(text: &str) -> Result<Vec<Entry>, Error> {
+    let lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+    lines
+        .map(|line| {
+            let mut words = line.split_whitespace();
+            let path = words.next().unwrap_or_default().to_owned();
+            let profile = words.next().map(str::to_owned);
+            if words.next().is_some() {
+                return Err(Error::format(
+                    "entries",
+                    format!("expected a path and a profile, found `{}`", line),
+                ));
+            }
+            Ok(Entry { path, profile })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_listed_with_their_profile() {
+        let entries = parse("# Handlers\napp::http::handle web\n\n  app::jobs::cleanup\n").unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                Entry {
+                    path: "app::http::handle".to_owned(),
+                    profile: Some("web".to_owned()),
+                },
+                Entry {
+                    path: "app::jobs::cleanup".to_owned(),
+                    profile: None,
+                },
+            ]
+        );
+        assert_eq!(
+            parse("app::run web admin"),
+            Err(Error::format(
+                "entries",
+                "expected a path and a profile, found `app::run web admin`"
+            ))
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Errors which are not about the analyzed code, like an invalid option or a file we cannot read.
+//!
+//! Problems with the analyzed code are reported as diagnostics with spans instead,
+//! and true invariants of the analysis still panic.
+
+use std::{fmt, io, path::PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A `--taint-*` option we do not know, or with a value we do not understand.
+    Option { option: String, message: String },
+    /// A file could not be read or written.
+    Io { path: PathBuf, message: String },
+    /// A file we read, or the text given for it, is not in the format we expect.
+    Format { what: &'static str, message: String },
+    /// The analysis was cancelled through its [`Cancellation`](crate::cancellation::Cancellation)
+    /// before it was done.
+    Cancelled,
+}
+
+impl Error {
+    pub fn option(option: &str, message: impl Into<String>) -> Self {
+        Error::Option {
+            option: option.to_owned(),
+            message: message.into(),
+        }
+    }
+
+    pub fn io(path: impl Into<PathBuf>, error: io::Error) -> Self {
+        Error::Io {
+            path: path.into(),
+            message: error.to_string(),
+        }
+    }
+
+    pub fn format(what: &'static str, message: impl fmt::Display) -> Self {
+        Error::Format {
+            what,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Option { option, message } => {
+                write!(f, "invalid option `{}`: {}", option, message)
+            }
+            Error::Io { path, message } => {
+                write!(f, "could not access `{}`: {}", path.display(), message)
+            }
+            Error::Format { what, message } => write!(f, "invalid {}: {}", what, message),
+            Error::Cancelled => write!(f, "the analysis was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
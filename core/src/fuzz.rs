@@ -0,0 +1,156 @@
+//! The sink call sites which received tainted input, as targets for fuzzers.
+//!
+//! With `--taint-fuzz-targets=<file>`, which is experimental, the driver writes the call sites
+//! of sinks it would have reported to `file` instead of reporting them as errors.
+//! Fuzzing harnesses can use them to pick which functions to drive, or to check at runtime that
+//! the arguments listed stay within bounds, bridging what the analysis found into dynamic testing.
+//!
+//! ```json
+//! {
+//!     "version": 1,
+//!     "targets": [{
+//!         "fingerprint": "4f1c2b7d9e0a6c35",
+//!         "function": "demo::handle",
+//!         "sink": "demo::execute",
+//!         "label": "taint",
+//!         "arguments": [0],
+//!         "file": "src/main.rs",
+//!         "line": 12,
+//!         "column": 5
+//!     }]
+//! }
+//! ```
+//!
+//! `arguments` are the positions of the arguments of the call which were tainted, starting at 0.
+//! Sinks which do not check arguments, like macros or functions marked `#[taint::sink(return)]`,
+//! have none.
+
+use std::{fs, path::Path};
+
+use serde_json::{json, Value};
+
+use crate::error::Error;
+
+/// The version of the fuzz targets format, bumped on incompatible changes.
+pub const VERSION: u64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    /// The fingerprint of the finding, as written with `--taint-findings`.
+    pub fingerprint: String,
+    /// The path of the function the sink is called in.
+    pub function: String,
+    /// The sink which received tainted input: a function path, or a macro name followed by `!`.
+    pub sink: String,
+    /// The label the sink received, like `taint`.
+    pub label: String,
+    /// The positions of the tainted arguments of the call.
+    pub arguments: Vec<usize>,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Targets {
+    pub targets: Vec<Target>,
+}
+
+impl Targets {
+    pub fn read(path: impl AsRef<Path>) -> Result<Targets, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        Targets::from_json(&text)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        fs::write(path, self.to_json()).map_err(|e| Error::io(path, e))
+    }
+
+    pub fn to_json(&self) -> String {
+        let targets = self
+            .targets
+            .iter()
+            .map(|target| {
+                json!({
+                    "fingerprint": target.fingerprint,
+                    "function": target.function,
+                    "sink": target.sink,
+                    "label": target.label,
+                    "arguments": target.arguments,
+                    "file": target.file,
+                    "line": target.line,
+                    "column": target.column,
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({ "version": VERSION, "targets": targets }).to_string()
+    }
+
+    pub fn from_json(text: &str) -> Result<Targets, Error> {
+        let value: Value =
+            serde_json::from_str(text).map_err(|e| Error::format("fuzz targets", e))?;
+        if value["version"] != VERSION {
+            return Err(Error::format(
+                "fuzz targets",
+                format!(
+                    "unsupported version {}, expected {}",
+                    value["version"], VERSION
+                ),
+            ));
+        }
+
+        let targets = value["targets"]
+            .as_array()
+            .ok_or_else(|| Error::format("fuzz targets", "missing `targets`"))?
+            .iter()
+            .map(target_from_json)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::format("fuzz targets", "malformed target"))?;
+        Ok(Targets { targets })
+    }
+}
+
+fn target_from_json(value: &Value) -> Option<Target> {
+    let string = |key: &str| value[key].as_str().map(str::to_owned);
+    let number = |value: &Value| value.as_u64().map(|n| n as usize);
+
+    Some(Target {
+        fingerprint: string("fingerprint")?,
+        function: string("function")?,
+        sink: string("sink")?,
+        label: string("label")?,
+        arguments: value["arguments"]
+            .as_array()?
+            .iter()
+            .map(number)
+            .collect::<Option<_>>()?,
+        file: string("file")?,
+        line: number(&value["line"])?,
+        column: number(&value["column"])?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trips() {
+        let targets = Targets {
+            targets: vec![Target {
+                fingerprint: "4f1c2b7d9e0a6c35".to_owned(),
+                function: "demo::handle".to_owned(),
+                sink: "demo::execute".to_owned(),
+                label: "taint".to_owned(),
+                arguments: vec![0, 2],
+                file: "src/main.rs".to_owned(),
+                line: 12,
+                column: 5,
+            }],
+        };
+
+        assert_eq!(Targets::from_json(&targets.to_json()), Ok(targets));
+    }
+}
@@ -0,0 +1,265 @@
+//! The findings of past runs, kept so that they can be triaged and compared without running the analysis
+//! again or parsing the diagnostics.
+//!
+//! With `--taint-findings-db=<file>`, or `--taint-findings-db` for `taint-findings.jsonl`, the driver
+//! appends a line to `file` for every crate it analyzes: a run, numbered after the last one,
+//! with the name of the crate and its findings as written with `--taint-findings`.
+//!
+//! ```json
+//! {"version": 1, "run": 3, "crate": "demo", "findings": [{ "fingerprint": "4f1c2b7d9e0a6c35", ... }]}
+//! ```
+//!
+//! `taint findings list`, `show` and `diff` read the file. Findings are named by their fingerprint,
+//! or any prefix of it, which stays the same from one run to the next.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    error::Error,
+    report::{self, Finding},
+};
+
+/// The version of the format of runs, bumped on incompatible changes.
+pub const VERSION: u64 = 1;
+
+/// The file runs are kept in when no other is given.
+pub const FILE_NAME: &str = "taint-findings.jsonl";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    /// The number of the run, starting at 1.
+    pub id: u64,
+    /// The name of the crate analyzed.
+    pub krate: String,
+    pub findings: Vec<Finding>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct History {
+    /// Every run, oldest first.
+    pub runs: Vec<Run>,
+}
+
+/// How the findings of a run differ from those of an earlier one, matched by fingerprint.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Diff<'a> {
+    /// Findings of the later run which the earlier one does not have.
+    pub added: Vec<&'a Finding>,
+    /// Findings of the earlier run which the later one does not have.
+    pub removed: Vec<&'a Finding>,
+    /// The number of findings both runs have.
+    pub unchanged: usize,
+}
+
+impl History {
+    /// Reads the runs kept in `path`, none if the file does not exist yet.
+    pub fn read(path: impl AsRef<Path>) -> Result<History, Error> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(text) => History::from_jsonl(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(History::default()),
+            Err(e) => Err(Error::io(path, e)),
+        }
+    }
+
+    pub fn from_jsonl(text: &str) -> Result<History, Error> {
+        let runs = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(run_from_json)
+            .collect::<Result<_, _>>()?;
+        Ok(History { runs })
+    }
+
+    /// Appends a run on `krate` with `findings` to `path`, numbered after the runs already kept there,
+    /// and returns its number.
+    pub fn append(
+        path: impl AsRef<Path>,
+        krate: &str,
+        findings: Vec<Finding>,
+    ) -> Result<u64, Error> {
+        let path = path.as_ref();
+        let last = History::read(path)?.runs.last().map_or(0, |run| run.id);
+        let run = Run {
+            id: last + 1,
+            krate: krate.to_owned(),
+            findings,
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| Error::io(path, e))?;
+        writeln!(file, "{}", run.to_json()).map_err(|e| Error::io(path, e))?;
+        Ok(run.id)
+    }
+
+    pub fn run(&self, id: u64) -> Option<&Run> {
+        self.runs.iter().find(|run| run.id == id)
+    }
+
+    pub fn latest(&self) -> Option<&Run> {
+        self.runs.last()
+    }
+
+    /// The latest run with a finding whose fingerprint starts with `id`, and that finding.
+    pub fn find(&self, id: &str) -> Option<(&Run, &Finding)> {
+        self.runs.iter().rev().find_map(|run| {
+            run.findings
+                .iter()
+                .find(|finding| finding.fingerprint.starts_with(id))
+                .map(|finding| (run, finding))
+        })
+    }
+}
+
+impl Run {
+    pub fn to_json(&self) -> String {
+        let findings = self
+            .findings
+            .iter()
+            .map(report::finding_to_json)
+            .collect::<Vec<_>>();
+        json!({ "version": VERSION, "run": self.id, "crate": self.krate, "findings": findings })
+            .to_string()
+    }
+
+    /// How the findings of this run differ from those of `earlier`.
+    pub fn diff<'a>(&'a self, earlier: &'a Run) -> Diff<'a> {
+        let has = |run: &Run, finding: &Finding| {
+            run.findings
+                .iter()
+                .any(|other| other.fingerprint == finding.fingerprint)
+        };
+        Diff {
+            added: self
+                .findings
+                .iter()
+                .filter(|finding| !has(earlier, finding))
+                .collect(),
+            removed: earlier
+                .findings
+                .iter()
+                .filter(|finding| !has(self, finding))
+                .collect(),
+            unchanged: self
+                .findings
+                .iter()
+                .filter(|finding| has(earlier, finding))
+                .count(),
+        }
+    }
+}
+
+fn run_from_json(line: &str) -> Result<Run, Error> {
+    let value: Value =
+        serde_json::from_str(line).map_err(|e| Error::format("findings database", e))?;
+    if value["version"] != VERSION {
+        return Err(Error::format(
+            "findings database",
+            format!(
+                "unsupported version {}, expected {}",
+                value["version"], VERSION
+            ),
+        ));
+    }
+    let run = || {
+        Some(Run {
+            id: value["run"].as_u64()?,
+            krate: value["crate"].as_str()?.to_owned(),
+            findings: value["findings"]
+                .as_array()?
+                .iter()
+                .map(report::finding_from_json)
+                .collect::<Option<_>>()?,
+        })
+    };
+    run().ok_or_else(|| Error::format("findings database", "malformed run"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{query::Position, report::Confidence};
+
+    fn finding(fingerprint: &str) -> Finding {
+        Finding {
+            fingerprint: fingerprint.to_owned(),
+            code: "T0001".to_owned(),
+            message: "function `log` received tainted input".to_owned(),
+            context: vec![],
+            function: "demo::main".to_owned(),
+            sink: "demo::log".to_owned(),
+            label: "taint".to_owned(),
+            profiles: vec![],
+            family: None,
+            owner: None,
+            confidence: Confidence::certain(),
+            file: "src/main.rs".to_owned(),
+            start: Position { line: 3, column: 5 },
+            end: Position {
+                line: 3,
+                column: 14,
+            },
+        }
+    }
+
+    #[test]
+    fn runs_round_trip() {
+        let run = Run {
+            id: 2,
+            krate: "demo".to_owned(),
+            findings: vec![finding("00000000000000aa")],
+        };
+        let history = History::from_jsonl(&format!("{}\n", run.to_json())).unwrap();
+
+        assert_eq!(history.runs, vec![run]);
+        assert_eq!(
+            History::from_jsonl(r#"{ "version": 1, "run": 1 }"#),
+            Err(Error::format("findings database", "malformed run"))
+        );
+    }
+
+    #[test]
+    fn findings_are_found_by_a_prefix_of_their_fingerprint_in_the_latest_run() {
+        let history = History {
+            runs: vec![
+                Run {
+                    id: 1,
+                    krate: "demo".to_owned(),
+                    findings: vec![finding("00000000000000aa")],
+                },
+                Run {
+                    id: 2,
+                    krate: "demo".to_owned(),
+                    findings: vec![finding("00000000000000aa"), finding("00000000000000bb")],
+                },
+            ],
+        };
+
+        assert_eq!(history.find("00000000000000a").unwrap().0.id, 2);
+        assert_eq!(history.find("cc"), None);
+    }
+
+    #[test]
+    fn diffs_match_findings_by_fingerprint() {
+        let run = |id, fingerprints: &[&str]| Run {
+            id,
+            krate: "demo".to_owned(),
+            findings: fingerprints.iter().map(|fp| finding(fp)).collect(),
+        };
+        let before = run(1, &["00000000000000aa", "00000000000000bb"]);
+        let after = run(2, &["00000000000000bb", "00000000000000cc"]);
+        let diff = after.diff(&before);
+
+        assert_eq!(diff.added, vec![&after.findings[1]]);
+        assert_eq!(diff.removed, vec![&before.findings[0]]);
+        assert_eq!(diff.unchanged, 1);
+    }
+}
@@ -0,0 +1,246 @@
+//! Classifying call sites with a script of the team's own, for `--taint-policy-hook=<script>`.
+//!
+//! Teams which cannot rebuild the analysis to add models can give functions a role from a [Rhai] script,
+//! which the analysis embeds. The script is compiled once per crate analyzed, and defines a function
+//! `classify(path, args)` which is called for each function called without a role of its own:
+//!
+//! ```rhai
+//! fn classify(path, args) {
+//!     if path == "db::escape" && args[1] == "\"sql\"" { "sanitizer" } else { () }
+//! }
+//! ```
+//!
+//! `args` holds each argument of the call as a Rust literal, or `()` if it is not a constant.
+//! The role is one of `source`, `sink`, `sanitizer` or `propagate`, or `()` to analyze the callee as usual.
+//! Scripts cannot reach the file system, the network or other programs, and a question which takes more
+//! than a million operations of the script is reported as an error, rather than leaving the compilation
+//! waiting on it. What the script prints goes to stderr, apart from the output of the compiler.
+//!
+//! [Rhai]: https://rhai.rs
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use rhai::{
+    module_resolvers::DummyModuleResolver, Array, CallFnOptions, Dynamic, Engine, Scope, AST,
+};
+
+use crate::error::Error;
+
+/// How many operations of the script answering a question may take.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// How deep the calls of the script answering a question may nest.
+const MAX_CALL_LEVELS: usize = 64;
+
+/// The function of the script which gives roles.
+const CLASSIFY: &str = "classify";
+
+/// The role a hook gives to a call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Source,
+    Sink,
+    Sanitizer,
+    /// The result carries the labels of the arguments, without analyzing the callee.
+    Propagate,
+}
+
+impl Role {
+    fn from_name(name: &str) -> Option<Role> {
+        match name {
+            "source" => Some(Role::Source),
+            "sink" => Some(Role::Sink),
+            "sanitizer" => Some(Role::Sanitizer),
+            "propagate" => Some(Role::Propagate),
+            _ => None,
+        }
+    }
+}
+
+/// A compiled hook script.
+pub struct PolicyHook {
+    script: PathBuf,
+    engine: Engine,
+    ast: AST,
+}
+
+impl fmt::Debug for PolicyHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PolicyHook")
+            .field("script", &self.script)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PolicyHook {
+    /// Reads and compiles the script at `script`.
+    pub fn load(script: impl AsRef<Path>) -> Result<PolicyHook, Error> {
+        let script = script.as_ref();
+        let source = fs::read_to_string(script).map_err(|e| Error::io(script, e))?;
+        PolicyHook::compile(script, &source)
+    }
+
+    /// Compiles `source`, the text of the script at `script`, which must define `classify(path, args)`.
+    fn compile(script: &Path, source: &str) -> Result<PolicyHook, Error> {
+        let mut engine = Engine::new();
+        // Modules would be read from files.
+        engine
+            .set_module_resolver(DummyModuleResolver::new())
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(MAX_CALL_LEVELS)
+            .on_print(|text| eprintln!("{}", text));
+        let ast = engine
+            .compile(source)
+            .map_err(|e| Error::format("policy hook", format!("`{}`: {}", script.display(), e)))?;
+        let classifies = ast
+            .iter_functions()
+            .any(|function| function.name == CLASSIFY && function.params.len() == 2);
+        if !classifies {
+            return Err(Error::format(
+                "policy hook",
+                format!(
+                    "`{}` does not define `{}(path, args)`",
+                    script.display(),
+                    CLASSIFY
+                ),
+            ));
+        }
+        Ok(PolicyHook {
+            script: script.to_owned(),
+            engine,
+            ast,
+        })
+    }
+
+    /// The role the hook gives to a call to the function at `path` with `args`, each a literal if it is
+    /// a constant, or `None` if it leaves the call to the analysis.
+    pub fn classify(&self, path: &str, args: &[Option<String>]) -> Result<Option<Role>, Error> {
+        let args = args
+            .iter()
+            .map(|arg| arg.clone().map_or(Dynamic::UNIT, Dynamic::from))
+            .collect::<Array>();
+        // The statements outside of functions are not run again for each question.
+        let options = CallFnOptions::new().eval_ast(false);
+        let answer = self
+            .engine
+            .call_fn_with_options::<Dynamic>(
+                options,
+                &mut Scope::new(),
+                &self.ast,
+                CLASSIFY,
+                (path.to_owned(), args),
+            )
+            .map_err(|e| {
+                Error::format(
+                    "policy hook answer",
+                    format!("`{}` failed on `{}`: {}", self.script.display(), path, e),
+                )
+            })?;
+        if answer.is_unit() {
+            return Ok(None);
+        }
+        let answer = answer.into_string().map_err(|type_name| {
+            Error::format(
+                "policy hook answer",
+                format!("expected a role, found a value of type `{}`", type_name),
+            )
+        })?;
+        Role::from_name(&answer).map(Some).ok_or_else(|| {
+            Error::format(
+                "policy hook answer",
+                format!(
+                    "unknown role `{}`, expected `source`, `sink`, `sanitizer`, `propagate` or `()`",
+                    answer
+                ),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    fn hook(source: &str) -> Result<PolicyHook, Error> {
+        PolicyHook::compile(Path::new("policy.rhai"), source)
+    }
+
+    #[test]
+    fn hooks_give_roles_to_the_calls_they_are_asked_about() {
+        let hook = hook(
+            r#"
+            fn classify(path, args) {
+                if path == "read_input" { return "source"; }
+                if path == "escape" && args[0] == "\"STRICT\"" { return "sanitizer"; }
+                if path == "broken" { return "filter"; }
+                if path == "counted" { return 1; }
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(hook.classify("read_input", &[]), Ok(Some(Role::Source)));
+        let args = [Some("\"STRICT\"".to_owned()), None];
+        assert_eq!(hook.classify("escape", &args), Ok(Some(Role::Sanitizer)));
+        assert_eq!(hook.classify("escape", &[None, None]), Ok(None));
+        assert_eq!(
+            hook.classify("broken", &[]),
+            Err(Error::format(
+                "policy hook answer",
+                "unknown role `filter`, expected `source`, `sink`, `sanitizer`, `propagate` or `()`"
+            ))
+        );
+        assert_eq!(
+            hook.classify("counted", &[]),
+            Err(Error::format(
+                "policy hook answer",
+                "expected a role, found a value of type `i64`"
+            ))
+        );
+    }
+
+    #[test]
+    fn scripts_without_classify_are_rejected() {
+        assert_eq!(
+            hook("fn role(path) { () }").unwrap_err(),
+            Error::format(
+                "policy hook",
+                "`policy.rhai` does not define `classify(path, args)`"
+            )
+        );
+        assert!(hook("fn classify(path, args) {").is_err());
+    }
+
+    #[test]
+    fn hooks_which_do_not_stop_are_errors() {
+        let hook = hook("fn classify(path, args) { loop {} }").unwrap();
+
+        let error = hook.classify("read_input", &[]).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`policy.rhai` failed on `read_input`"));
+    }
+
+    #[test]
+    fn scripts_cannot_read_files() {
+        let module = env::temp_dir().join(format!("taint-hook-module-{}", process::id()));
+        fs::write(
+            module.with_extension("rhai"),
+            "export const ROLE = \"sink\";",
+        )
+        .unwrap();
+        let source = format!(
+            "fn classify(path, args) {{ import {:?} as secrets; secrets::ROLE }}",
+            module.display().to_string()
+        );
+        let hook = hook(&source).unwrap();
+
+        assert!(hook.classify("read_input", &[]).is_err());
+        fs::remove_file(module.with_extension("rhai")).unwrap();
+    }
+}
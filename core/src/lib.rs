@@ -0,0 +1,24 @@
+//! The parts of the taint analysis which do not need the compiler: its options and config,
+//! and the files it reads and writes, like findings, summaries and query results.
+//!
+//! They build on stable Rust, so that tools reading the output of the analysis, and the tests
+//! of these formats, do not need the nightly toolchain the analysis itself is pinned to.
+//! The `taint` crate re-exports every module, so most users do not need to depend on this one.
+
+pub mod annotations;
+pub mod cancellation;
+pub mod changes;
+pub mod config;
+pub mod entries;
+pub mod error;
+pub mod fuzz;
+pub mod history;
+pub mod hook;
+pub mod metrics;
+pub mod options;
+pub mod query;
+pub mod report;
+pub mod reporters;
+pub mod status;
+pub mod summaries;
+pub mod warm_start;
@@ -0,0 +1,292 @@
+//! Metrics of a run in the OpenMetrics text format, for dashboards tracking crates over time.
+//!
+//! With `--taint-metrics=<file>`, the driver writes what a run on a crate found and went through,
+//! which a Prometheus node exporter's textfile collector, or any scraper of the format, can pick up:
+//!
+//! ```text
+//! # TYPE taint_findings gauge
+//! # HELP taint_findings Findings of the last run, by rule, severity and label.
+//! taint_findings{crate="demo",rule="T0001",severity="error",label="taint"} 2
+//! # TYPE taint_coverage_ratio gauge
+//! # UNIT taint_coverage_ratio ratio
+//! # HELP taint_coverage_ratio Share of the called functions whose body was analyzed, rather than following --taint-unknown-calls.
+//! taint_coverage_ratio{crate="demo"} 0.9500
+//! # EOF
+//! ```
+//!
+//! Every sample is labeled with the crate it is about. The file keeps the samples of the other crates
+//! it holds, so that the runs on every crate of a workspace, like under `cargo taint`, add up to one file.
+
+use std::{fs, io, path::Path};
+
+use crate::{
+    error::Error,
+    status::{Reported, Status},
+};
+
+/// A family of metrics: its name, its type, its unit if it has one, and what it measures.
+struct Family {
+    name: &'static str,
+    kind: &'static str,
+    unit: Option<&'static str>,
+    help: &'static str,
+}
+
+const FAMILIES: [Family; 6] = [
+    Family {
+        name: "taint_findings",
+        kind: "gauge",
+        unit: None,
+        help: "Findings of the last run, by rule, severity and label.",
+    },
+    Family {
+        name: "taint_errors",
+        kind: "gauge",
+        unit: None,
+        help: "Errors the last run reported, findings and invalid annotations alike.",
+    },
+    Family {
+        name: "taint_warnings",
+        kind: "gauge",
+        unit: None,
+        help: "Warnings the last run reported.",
+    },
+    Family {
+        name: "taint_entry_points",
+        kind: "gauge",
+        unit: None,
+        help: "Entry points the last run analyzed.",
+    },
+    Family {
+        name: "taint_coverage_ratio",
+        kind: "gauge",
+        unit: Some("ratio"),
+        help: "Share of the called functions whose body was analyzed, rather than following --taint-unknown-calls.",
+    },
+    Family {
+        name: "taint_duration_seconds",
+        kind: "gauge",
+        unit: Some("seconds"),
+        help: "Time the last run took.",
+    },
+];
+
+/// What a run on a crate measured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metrics {
+    pub krate: String,
+    pub status: Status,
+    pub findings: Vec<Reported>,
+    /// The functions whose body was analyzed, in any context.
+    pub analyzed: usize,
+    /// The functions called without a body to analyze, like foreign functions or those of dependencies
+    /// whose MIR was not encoded.
+    pub without_mir: usize,
+}
+
+impl Metrics {
+    /// The samples of the run, by family, each as a line of the text format.
+    fn samples(&self) -> Vec<(&'static str, String)> {
+        let krate = format!("crate=\"{}\"", escape(&self.krate));
+        let mut findings: Vec<(&str, &str, &str, usize)> = vec![];
+        for Reported { finding, error } in &self.findings {
+            let severity = if *error { "error" } else { "warning" };
+            let key = (finding.code.as_str(), severity, finding.label.as_str());
+            match findings
+                .iter_mut()
+                .find(|(code, severity, label, _)| (*code, *severity, *label) == key)
+            {
+                Some((.., count)) => *count += 1,
+                None => findings.push((key.0, key.1, key.2, 1)),
+            }
+        }
+        findings.sort();
+
+        let mut samples = findings
+            .into_iter()
+            .map(|(code, severity, label, count)| {
+                let line = format!(
+                    "taint_findings{{{},rule=\"{}\",severity=\"{}\",label=\"{}\"}} {}",
+                    krate,
+                    escape(code),
+                    severity,
+                    escape(label),
+                    count
+                );
+                ("taint_findings", line)
+            })
+            .collect::<Vec<_>>();
+        let called = self.analyzed + self.without_mir;
+        let coverage = if called == 0 {
+            1.0
+        } else {
+            self.analyzed as f64 / called as f64
+        };
+        let values = [
+            ("taint_errors", self.status.errors.to_string()),
+            ("taint_warnings", self.status.warnings.to_string()),
+            ("taint_entry_points", self.status.entry_points.to_string()),
+            ("taint_coverage_ratio", format!("{:.4}", coverage)),
+            (
+                "taint_duration_seconds",
+                format!("{:.3}", self.status.seconds),
+            ),
+        ];
+        for (name, value) in values {
+            samples.push((name, format!("{}{{{}}} {}", name, krate, value)));
+        }
+        samples
+    }
+
+    /// The metrics of the run, after `kept`, the samples of other runs, in the OpenMetrics text format.
+    fn render(&self, kept: Vec<(&'static str, String)>) -> String {
+        let mut samples = kept;
+        samples.extend(self.samples());
+        let mut text = String::new();
+        for family in &FAMILIES {
+            text.push_str(&format!("# TYPE {} {}\n", family.name, family.kind));
+            if let Some(unit) = family.unit {
+                text.push_str(&format!("# UNIT {} {}\n", family.name, unit));
+            }
+            text.push_str(&format!("# HELP {} {}\n", family.name, family.help));
+            let mut lines = samples
+                .iter()
+                .filter(|(name, _)| *name == family.name)
+                .map(|(_, line)| line.as_str())
+                .collect::<Vec<_>>();
+            lines.sort_unstable();
+            for line in lines {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+        text.push_str("# EOF\n");
+        text
+    }
+
+    /// The metrics of the run in the OpenMetrics text format.
+    pub fn openmetrics(&self) -> String {
+        self.render(vec![])
+    }
+
+    /// Writes the metrics of the run to `path`, keeping the samples it holds about other crates.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let existing = match fs::read_to_string(path) {
+            Ok(existing) => existing,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(Error::io(path, error)),
+        };
+        let ours = format!("{{crate=\"{}\"", escape(&self.krate));
+        let kept = existing
+            .lines()
+            .filter_map(|line| {
+                let (family, labels) = FAMILIES.iter().find_map(|family| {
+                    let labels = line.strip_prefix(family.name)?;
+                    labels.starts_with('{').then_some((family.name, labels))
+                })?;
+                (!labels.starts_with(&ours)).then(|| (family, line.to_owned()))
+            })
+            .collect();
+        fs::write(path, self.render(kept)).map_err(|e| Error::io(path, e))
+    }
+}
+
+/// `value` with the characters a label value gives a meaning to escaped.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        query::Position,
+        report::{Confidence, Finding},
+    };
+
+    fn reported(code: &str, label: &str, error: bool) -> Reported {
+        let finding = Finding {
+            fingerprint: "0123456789abcdef".to_owned(),
+            code: code.to_owned(),
+            message: "function `output` received tainted input".to_owned(),
+            context: vec![],
+            function: "demo::main".to_owned(),
+            sink: "output".to_owned(),
+            label: label.to_owned(),
+            profiles: vec![],
+            family: None,
+            owner: None,
+            confidence: Confidence::certain(),
+            file: "src/main.rs".to_owned(),
+            start: Position { line: 5, column: 5 },
+            end: Position {
+                line: 5,
+                column: 20,
+            },
+        };
+        Reported { finding, error }
+    }
+
+    fn metrics(krate: &str) -> Metrics {
+        Metrics {
+            krate: krate.to_owned(),
+            status: Status {
+                errors: 2,
+                warnings: 1,
+                entry_points: 3,
+                seconds: 0.5,
+            },
+            findings: vec![
+                reported("T0001", "taint", true),
+                reported("T0001", "taint", true),
+                reported("T0006", "pii", false),
+            ],
+            analyzed: 19,
+            without_mir: 1,
+        }
+    }
+
+    #[test]
+    fn findings_are_counted_by_rule_severity_and_label() {
+        let text = metrics("demo").openmetrics();
+
+        assert!(text.starts_with("# TYPE taint_findings gauge\n# HELP taint_findings "));
+        assert!(text.contains(
+            "taint_findings{crate=\"demo\",rule=\"T0001\",severity=\"error\",label=\"taint\"} 2\n"
+        ));
+        assert!(text.contains(
+            "taint_findings{crate=\"demo\",rule=\"T0006\",severity=\"warning\",label=\"pii\"} 1\n"
+        ));
+        assert!(text.contains("taint_errors{crate=\"demo\"} 2\n"));
+        assert!(text.contains(
+            "# UNIT taint_coverage_ratio ratio\n# HELP taint_coverage_ratio Share of the called functions"
+        ));
+        assert!(text.contains("taint_coverage_ratio{crate=\"demo\"} 0.9500\n"));
+        assert!(text.contains("taint_duration_seconds{crate=\"demo\"} 0.500\n"));
+        assert!(text.ends_with("taint_duration_seconds{crate=\"demo\"} 0.500\n# EOF\n"));
+    }
+
+    #[test]
+    fn writing_keeps_the_samples_of_other_crates() {
+        let path = std::env::temp_dir().join("taint-metrics-unit.prom");
+        let _ = fs::remove_file(&path);
+        metrics("demo").write(&path).unwrap();
+        metrics("other").write(&path).unwrap();
+        let mut rerun = metrics("demo");
+        rerun.findings.clear();
+        rerun.write(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+
+        assert!(!text.contains("taint_findings{crate=\"demo\""));
+        assert!(text.contains(
+            "taint_findings{crate=\"other\",rule=\"T0001\",severity=\"error\",label=\"taint\"} 2\n"
+        ));
+        assert_eq!(text.matches("taint_entry_points{").count(), 2);
+        assert_eq!(text.matches("# TYPE taint_errors gauge").count(), 1);
+        assert_eq!(text.matches("# EOF").count(), 1);
+    }
+}
@@ -0,0 +1,1008 @@
+//! Options controlling the analysis.
+//!
+//! They are passed to the driver as `--taint-*` flags, which are taken out of the arguments
+//! before the rest are handed over to rustc. Every argument after a `--` is an option too,
+//! with or without the prefix, so that `taint main.rs -- --library` analyzes `main.rs` as a library.
+//! A flag the analysis does not know is an error rather than being passed on, and [`help`] lists those it does.
+//!
+//! Some can also be set in the environment, which is easier to change in CI than the command line.
+//! Flags take precedence over the environment: an option given both ways takes the value of the flag,
+//! and lists like the sink packs hold the entries of both.
+//!
+//! | Variable | Flag |
+//! | --- | --- |
+//! | `TAINT_CONFIG` | `--taint-config` |
+//! | `TAINT_SINK_PACKS` | `--taint-sink-packs` |
+//! | `TAINT_INCLUDE_TESTS` | `--taint-include-tests` |
+//! | `TAINT_CRATE_INTERFACES` | `--taint-crate-interfaces` |
+//! | `TAINT_DEPENDENCY_CACHE` | `--taint-dependency-cache` |
+//! | `TAINT_STATUS_LINE` | `--taint-status-line` |
+//! | `TAINT_FAIL_FAST` | `--taint-fail-fast` |
+
+use std::{path::PathBuf, sync::Arc};
+
+use crate::{
+    cancellation::Cancellation,
+    error::Error,
+    history,
+    reporters::{self, Reporters},
+    warm_start::WarmStart,
+};
+
+const PREFIX: &str = "--taint-";
+
+/// The separator after which every argument is an option.
+const SEPARATOR: &str = "--";
+
+/// The flags the driver takes, and what they do, as `--taint-help` prints them.
+pub const FLAGS: &[(&str, &str)] = &[
+    (
+        "--taint-summary-report",
+        "print which function summaries were shared between entry points",
+    ),
+    (
+        "--taint-coverage-report",
+        "print how many sources, findings and sanitizers each label has",
+    ),
+    (
+        "--taint-entries=<file>",
+        "analyze the entry points `file` lists",
+    ),
+    (
+        "--taint-library",
+        "analyze every public function as an entry point with tainted arguments",
+    ),
+    (
+        "--taint-include-tests",
+        "analyze tests and code under `#[cfg(test)]`",
+    ),
+    (
+        "--taint-report-in-deps",
+        "report findings at the sinks they reached inside dependencies",
+    ),
+    (
+        "--taint-lint-clean-sanitizers",
+        "warn about sanitizers whose input is never tainted",
+    ),
+    (
+        "--taint-lint-unzeroized-secrets",
+        "warn about secrets a function creates and drops without zeroizing them",
+    ),
+    (
+        "--taint-list-annotations",
+        "print every function or field with a role instead of analyzing",
+    ),
+    (
+        "--taint-black-box-barrier",
+        "take `std::hint::black_box` to be a barrier",
+    ),
+    (
+        "--taint-emit=policy-doc,policy-check",
+        "print a page documenting the policy, or checking its paths, instead of analyzing",
+    ),
+    (
+        "--taint-config=<file>",
+        "read the config from `file` instead of `taint.json`",
+    ),
+    (
+        "--taint-add-source=<path>",
+        "make the function at `path` a source",
+    ),
+    (
+        "--taint-add-sink=<path>",
+        "make the function at `path` a sink",
+    ),
+    (
+        "--taint-add-sanitizer=<path>",
+        "make the function at `path` a sanitizer",
+    ),
+    (
+        "--taint-policy-hook=<script>",
+        "ask the Rhai `script` for the roles of the functions called",
+    ),
+    (
+        "--taint-sink-macros=<macros>",
+        "make the comma-separated macros sinks",
+    ),
+    (
+        "--taint-sink-packs=allocation,pointer,process,resource,unsafe,index,sql",
+        "enable built-in sets of sinks",
+    ),
+    (
+        "--taint-source-packs=wasm-bindgen,pyo3",
+        "enable built-in sets of sources",
+    ),
+    (
+        "--taint-results=<file>",
+        "write the taint of every assignment to `file`",
+    ),
+    (
+        "--taint-findings=<file>",
+        "write every finding to `file` as JSON",
+    ),
+    (
+        "--taint-findings-db[=<file>]",
+        "append the findings of the run to `file`",
+    ),
+    (
+        "--taint-status-line=yes|no",
+        "print the errors, warnings, entry points and time of the run",
+    ),
+    (
+        "--taint-junit=<file>",
+        "write the outcome of the run to `file` as JUnit XML",
+    ),
+    (
+        "--taint-metrics=<file>",
+        "write counts of findings, coverage and duration to `file` as OpenMetrics",
+    ),
+    (
+        "--taint-report-url=<url>",
+        "post the findings to `url` as JSON",
+    ),
+    (
+        "--taint-changed-only[=<file>]",
+        "only analyze the entry points reaching changed functions",
+    ),
+    (
+        "--taint-baseline=<file>",
+        "keep the findings of `file` in the functions not analyzed again",
+    ),
+    (
+        "--taint-fuzz-targets=<file>",
+        "write the sinks which received tainted input as fuzz targets",
+    ),
+    (
+        "--taint-slice=<fingerprint>",
+        "print the statements the finding flows through",
+    ),
+    (
+        "--taint-reproducers=<dir>",
+        "write a reduced program reproducing each finding to `dir`",
+    ),
+    (
+        "--taint-export-summaries=<file>",
+        "write a summary of every function analyzed to `file`",
+    ),
+    (
+        "--taint-import-summaries=<file>",
+        "use the summaries in `file` instead of analyzing their functions",
+    ),
+    (
+        "--taint-crate-interfaces=yes|no",
+        "share the summaries and annotations of libraries with their dependents",
+    ),
+    (
+        "--taint-dependency-cache=<dir>",
+        "reuse the summaries of the functions of dependencies across builds, in `dir`",
+    ),
+    (
+        "--taint-resume=<dir>",
+        "checkpoint long runs to `dir`, and resume from there a run interrupted before it was done",
+    ),
+    (
+        "--taint-summary-packs=yes|no",
+        "whether to use the summaries bundled for dependencies",
+    ),
+    (
+        "--taint-progress=json",
+        "print the progress of the analysis to stderr",
+    ),
+    (
+        "--taint-mir=built|promoted|optimized",
+        "which stage of MIR to analyze",
+    ),
+    (
+        "--taint-integration=callbacks|query",
+        "how the analysis is hooked into the compiler",
+    ),
+    (
+        "--taint-explain=<function>",
+        "print the label changes of each statement of `function`",
+    ),
+    (
+        "--taint-debug-invariants",
+        "check that labels only grow while analyzing blocks again",
+    ),
+    (
+        "--taint-debug-stable-mir",
+        "check the lowering from stable MIR against the one from MIR",
+    ),
+    (
+        "--taint-debug-hash-order=<n>",
+        "analyze `n` times with other hash seeds and check the findings agree",
+    ),
+    (
+        "--taint-strict-mir=yes|no",
+        "report the constructs of MIR the analysis does not model as errors",
+    ),
+    (
+        "--taint-mode=strict|precise",
+        "assume the worst of unknown code, or only report what was seen",
+    ),
+    (
+        "--taint-fail-level=all|high-confidence",
+        "which findings are errors rather than warnings",
+    ),
+    (
+        "--taint-fail-fast",
+        "stop at the first finding which is an error",
+    ),
+    (
+        "--taint-unknown-calls=clean|propagate",
+        "what calls to functions without a body do",
+    ),
+    (
+        "--taint-max-arity=<n>",
+        "merge the arguments of functions with more than `n` of them",
+    ),
+    (
+        "--taint-max-points-to=<n>",
+        "take locals pointing to more than `n` locals to point anywhere",
+    ),
+    (
+        "--taint-block-summaries=<n>",
+        "summarize the blocks of functions with more than `n` statements",
+    ),
+    (
+        "--taint-weak-updates=yes|no",
+        "whether assignments add to the labels of a place",
+    ),
+];
+
+/// The flags the driver takes, each followed by what it does, as `--taint-help` prints them.
+pub fn help() -> String {
+    let mut help = String::from(
+        "Options of the analysis, given before a `--` with the `--taint-` prefix, or after it with or without it.\n\
+         Every other argument before the `--` is passed on to rustc.\n\n",
+    );
+    for (flag, description) in FLAGS {
+        help.push_str(&format!("  {}\n      {}\n", flag, description));
+    }
+    help
+}
+
+/// Whether `args` ask for the [help] of the options, with `--taint-help`, or `--help` after a `--`.
+pub fn asks_for_help(args: &[String]) -> bool {
+    let separator = args.iter().position(|arg| arg == SEPARATOR);
+    args.iter().enumerate().any(|(ix, arg)| {
+        arg == "--taint-help" || separator.map_or(false, |at| ix > at && arg == "--help")
+    })
+}
+
+/// The number of arguments above which a function is analyzed with the labels of all of its arguments
+/// on each of them, unless `--taint-max-arity` says otherwise.
+pub const DEFAULT_MAX_ARITY: usize = 12;
+
+/// The number of locals a local may point to before it is taken to point anywhere,
+/// unless `--taint-max-points-to` says otherwise.
+pub const DEFAULT_MAX_POINTS_TO: usize = 64;
+
+/// The number of statements above which the blocks of a function are summarized before they are analyzed,
+/// unless `--taint-block-summaries` says otherwise.
+pub const DEFAULT_BLOCK_SUMMARIES: usize = 10_000;
+
+/// The environment variables read, and the options they set.
+pub const ENV: [(&str, &str); 7] = [
+    ("TAINT_CONFIG", "config"),
+    ("TAINT_SINK_PACKS", "sink-packs"),
+    ("TAINT_INCLUDE_TESTS", "include-tests"),
+    ("TAINT_CRATE_INTERFACES", "crate-interfaces"),
+    ("TAINT_DEPENDENCY_CACHE", "dependency-cache"),
+    ("TAINT_STATUS_LINE", "status-line"),
+    ("TAINT_FAIL_FAST", "fail-fast"),
+];
+
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Print which function summaries were shared between entry points.
+    pub summary_report: bool,
+    /// Print, for each label, how many calls to sources put it on data, how many findings it reached
+    /// sinks in, and how many calls to each sanitizer removed it.
+    pub coverage_report: bool,
+    /// Analyze every public function as an entry point called by untrusted code,
+    /// with all of its arguments tainted, even if the crate has a `main` function.
+    pub library: bool,
+    /// Analyze `#[test]` functions and code under `#[cfg(test)]` when compiled with `--test`,
+    /// which is otherwise left out.
+    pub include_tests: bool,
+    /// Report findings at the sinks they reached in the code of dependencies, like a combinator of `std`
+    /// calling a sink passed to it, rather than where the crate called into that code.
+    pub report_in_deps: bool,
+    /// Warn about calls to sanitizers whose input is never tainted, in any context they were analyzed in,
+    /// which may be dead defensive code or sanitization in the wrong place.
+    pub lint_clean_sanitizers: bool,
+    /// Warn about the secrets a function creates which it drops before it returns, neither zeroized
+    /// nor moved out, so that they stay in memory once freed.
+    pub lint_unzeroized_secrets: bool,
+    /// Take `std::hint::black_box` to be a barrier, whose result carries no labels, like `taint_runtime::barrier`.
+    pub black_box_barrier: bool,
+    /// Print the functions with a role, and where the role was declared, instead of analyzing.
+    pub list_annotations: bool,
+    /// Macros whose formatted arguments must not be tainted, like `println` or `tracing::info`.
+    pub sink_macros: Vec<String>,
+    /// Where to write the taint of every assignment, for use with [`crate::query`].
+    pub results: Option<PathBuf>,
+    /// Where to write the findings, for use with [`crate::report`].
+    pub findings: Option<PathBuf>,
+    /// The file to append the findings of the run to, for use with [`crate::history`].
+    pub findings_db: Option<PathBuf>,
+    /// Print the status line of the run to stderr once the crate is analyzed, for use with [`crate::status`].
+    pub status_line: bool,
+    /// Where to write the outcome of the run as JUnit XML, for use with [`crate::status`].
+    pub junit: Option<PathBuf>,
+    /// Where to write the metrics of the run as OpenMetrics, for use with [`crate::metrics`].
+    pub metrics: Option<PathBuf>,
+    /// Where to write the call sites of sinks which received tainted input, for use with [`crate::fuzz`],
+    /// instead of reporting them as errors.
+    pub fuzz_targets: Option<PathBuf>,
+    /// What calls to functions without a body to analyze do.
+    pub unknown_calls: UnknownCalls,
+    /// Which findings are reported as errors, which fail the build, rather than warnings.
+    pub fail_level: FailLevel,
+    /// Stop analyzing at the first finding reported as an error, for a quick check of whether there is any,
+    /// and report what was found until then.
+    pub fail_fast: bool,
+    /// Assignments add to the taint of a place instead of replacing it,
+    /// so that a place which was tainted once stays tainted.
+    pub weak_updates: bool,
+    /// Functions with more arguments than this are analyzed with the labels of all of their arguments
+    /// on each of them, so that they are not analyzed again for every combination of tainted arguments.
+    /// [`DEFAULT_MAX_ARITY`] if not given.
+    pub max_arity: Option<usize>,
+    /// A local which may point to more locals than this is taken to point to any local of its function,
+    /// so that reads through it carry the labels of all of them, and writes through it add to theirs.
+    /// [`DEFAULT_MAX_POINTS_TO`] if not given.
+    pub max_points_to: Option<usize>,
+    /// The blocks of functions with more statements than this which only move labels between locals are
+    /// summarized once, and each summary is applied in one step rather than statement by statement.
+    /// [`DEFAULT_BLOCK_SUMMARIES`] if not given.
+    pub block_summaries: Option<usize>,
+    /// Built-in sets of sinks to enable.
+    pub sink_packs: Vec<SinkPack>,
+    /// Built-in sets of sources to enable.
+    pub source_packs: Vec<SourcePack>,
+    /// How to report the progress of the analysis.
+    pub progress: Progress,
+    /// Which MIR to analyze.
+    pub mir: Mir,
+    /// How the driver hooks the analysis into the compiler.
+    pub integration: Integration,
+    /// Where to write the summaries of the functions analyzed, for use with [`crate::summaries`].
+    pub export_summaries: Option<PathBuf>,
+    /// Summaries to use instead of analyzing the functions they describe.
+    pub import_summaries: Option<PathBuf>,
+    /// Do not use the summaries bundled for dependencies like `std`.
+    pub skip_summary_packs: bool,
+    /// Write the summaries and annotations of a library next to its metadata, and load those of the libraries
+    /// the crate depends on which have them, like a binary those of the library of its package.
+    /// `cargo taint` sets it, so that flows between the crates of a workspace keep their roles.
+    pub crate_interfaces: bool,
+    /// Where to keep the summaries of the functions of dependencies, by the hash of the version of their crate,
+    /// so that each version is only analyzed once across builds and the crates of a workspace.
+    pub dependency_cache: Option<PathBuf>,
+    /// Where to checkpoint the summaries computed and the findings reported so far as the run goes,
+    /// and to resume from the checkpoint of an earlier run with the same inputs.
+    pub resume: Option<PathBuf>,
+    /// Check that joins and transfer functions only move up the lattice, which is slow.
+    pub debug_invariants: bool,
+    /// Check that the stable MIR of every function analyzed lowers like its MIR does.
+    pub debug_stable_mir: bool,
+    /// Analyze the crate this many times, each with maps seeded anew, and check that every run finds the same,
+    /// so that no finding depends on the order of a map.
+    pub debug_hash_order: Option<usize>,
+    /// Report the constructs of MIR in the crate which the analysis does not model, and leaves the labels
+    /// of their destination as they were, like casts between numbers, since they may hide flows.
+    pub strict_mir: bool,
+    /// Only analyze the entry points which reach a function that changed, for pre-commit hooks.
+    pub changed_only: Option<ChangedOnly>,
+    /// The findings of a full run, for use with [`crate::report`], whose findings in the functions
+    /// `--taint-changed-only` did not analyze again are kept in those written with `--taint-findings`.
+    pub baseline: Option<PathBuf>,
+    /// Where the findings of each crate are sent once it is analyzed, like the dashboard
+    /// `--taint-report-url` posts them to.
+    pub reporters: Reporters,
+    /// The fingerprint of a finding whose slice to print.
+    pub slice: Option<String>,
+    /// The directory to write a reduced program reproducing each finding to.
+    pub reproducers: Option<PathBuf>,
+    /// The path of a function whose statements to print with the labels each changes,
+    /// every time it is analyzed.
+    pub explain: Option<String>,
+    /// Documents to print instead of analyzing.
+    pub emit: Vec<Emit>,
+    /// The config to read instead of the `taint.json` of the current directory.
+    pub config: Option<PathBuf>,
+    /// The file listing the entry points to analyze, for use with [`crate::entries`],
+    /// instead of `main` or the public functions of the crate.
+    pub entries: Option<PathBuf>,
+    /// The profile of the config these options were extended with, which the findings of the entry points
+    /// analyzed with them are tagged with. It has no flag.
+    pub profile: Option<String>,
+    /// Paths of functions which are sources, in addition to those marked with `#[taint::source]`.
+    pub add_sources: Vec<String>,
+    /// Paths of functions which are sinks, in addition to those marked with `#[taint::sink]`.
+    pub add_sinks: Vec<String>,
+    /// Paths of functions which are sanitizers, in addition to those marked with `#[taint::sanitizer]`.
+    pub add_sanitizers: Vec<String>,
+    /// A Rhai script giving roles to the functions called without one, for use with [`crate::hook`].
+    pub policy_hook: Option<PathBuf>,
+    /// Stops the analysis once cancelled, for tools running it in process. It has no flag.
+    pub cancellation: Cancellation,
+    /// Carries summaries over from one run to the next, for tools running it in process.
+    /// Every run starts from scratch without one. It has no flag.
+    pub warm_start: Option<WarmStart>,
+}
+
+/// The stage of MIR to analyze.
+///
+/// Optimizations can inline and propagate constants across flows, which hides or moves findings.
+/// Earlier stages are closer to the source, but are only kept for the crate being analyzed,
+/// so functions from other crates are still analyzed in their optimized form.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Mir {
+    /// As built from the source, before any transformation.
+    Built,
+    /// Once constants were promoted, as the borrow checker sees it.
+    Promoted,
+    /// As it is generated into code.
+    #[default]
+    Optimized,
+}
+
+/// How the analysis is hooked into the compiler.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Integration {
+    /// From the driver's `after_analysis` callback, once the compiler's own analysis is done.
+    #[default]
+    Callbacks,
+    /// As part of the compiler's `analysis` query, which the driver overrides, so that other tools
+    /// can use the callbacks of the same compiler invocation.
+    Query,
+}
+
+/// Where the changes come from, for `--taint-changed-only`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangedOnly {
+    /// `git diff` against `HEAD`, along with the files git does not track yet.
+    Git,
+    /// A file listing the paths which changed, one per line.
+    Listed(PathBuf),
+}
+
+/// How to report the progress of the analysis, which can take minutes for large crates.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// Say nothing until the analysis is done.
+    #[default]
+    Off,
+    /// Print an event as a JSON object on its own line of stderr whenever an entry point
+    /// is started or finished.
+    Json,
+}
+
+/// What calls to functions without a body to analyze, like foreign functions, do.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownCalls {
+    /// The result is clean.
+    #[default]
+    Clean,
+    /// The result is tainted if any of the arguments are.
+    Propagate,
+}
+
+/// Which findings fail the build.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FailLevel {
+    /// Every finding is an error, except for the codes which are always warnings.
+    #[default]
+    All,
+    /// Only findings whose [confidence](crate::report::Confidence) is high are errors,
+    /// and the others are warnings.
+    HighConfidence,
+}
+
+/// A built-in set of sinks for a class of vulnerabilities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SinkPack {
+    /// Sizes of allocations, like the capacity of `Vec::with_capacity` or the length in `vec![x; n]`,
+    /// which an attacker could use to exhaust memory.
+    Allocation,
+    /// Offsets of raw pointers, and the lengths unsafe code reads or writes memory with,
+    /// like the count of `ptr::copy_nonoverlapping` or the length of `slice::from_raw_parts`,
+    /// which an attacker could use to reach memory out of bounds.
+    Pointer,
+    /// The environment of the process and the program, arguments and environment of child processes,
+    /// like the value of `env::set_var` or the arguments of `Command::arg` and `Command::env`,
+    /// which an attacker could use to inject options or variables into programs we run.
+    /// Spawning a command built from tainted data is reported too.
+    Process,
+    /// The amounts of resources a call consumes: the sizes of the allocation pack, the lengths of the buffers
+    /// `Read::read_exact` fills and the durations `thread::sleep` waits for, which an attacker
+    /// could use to exhaust memory, hold up connections or stall threads.
+    Resource,
+    /// Every argument of a call to an `unsafe fn`, except those the config trusts, for codebases
+    /// where no tainted data may enter unsafe code. Findings are reported as warnings.
+    Unsafe,
+    /// Positions slices, arrays and collections are indexed at, like `i` in `data[i]` or the argument
+    /// of `get_unchecked`, which an attacker could use to make the program panic, or pick the element
+    /// it reads or writes. The element read still carries its own labels rather than those of its position.
+    Index,
+    /// The text of SQL queries, like the argument of `sqlx::query`, `diesel::sql_query` or `rusqlite::Connection::execute`,
+    /// which an attacker could use to inject SQL. The values bound to the parameters of a query, like those
+    /// of `.bind()`, are not checked, so that tainted data is only reported when it is spliced into the query.
+    Sql,
+}
+
+impl SinkPack {
+    pub const ALL: [SinkPack; 7] = [
+        SinkPack::Allocation,
+        SinkPack::Pointer,
+        SinkPack::Process,
+        SinkPack::Resource,
+        SinkPack::Unsafe,
+        SinkPack::Index,
+        SinkPack::Sql,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SinkPack::Allocation => "allocation",
+            SinkPack::Pointer => "pointer",
+            SinkPack::Process => "process",
+            SinkPack::Resource => "resource",
+            SinkPack::Unsafe => "unsafe",
+            SinkPack::Index => "index",
+            SinkPack::Sql => "sql",
+        }
+    }
+
+    fn from_name(name: &str) -> Result<SinkPack, String> {
+        match name {
+            "allocation" => Ok(SinkPack::Allocation),
+            "pointer" => Ok(SinkPack::Pointer),
+            "process" => Ok(SinkPack::Process),
+            "resource" => Ok(SinkPack::Resource),
+            "unsafe" => Ok(SinkPack::Unsafe),
+            "index" => Ok(SinkPack::Index),
+            "sql" => Ok(SinkPack::Sql),
+            _ => Err(format!(
+                "unknown sink pack `{}`, expected `allocation`, `pointer`, `process`, `resource`, `unsafe`, `index` or `sql`",
+                name
+            )),
+        }
+    }
+}
+
+/// A built-in set of sources for the functions a framework exports to another language,
+/// whose arguments come from code the analysis cannot see.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourcePack {
+    /// Functions exported to JavaScript with `#[wasm_bindgen]`, and the methods of impls marked with it.
+    WasmBindgen,
+    /// Functions exported to Python with `#[pyfunction]`, and the methods of impls marked `#[pymethods]`.
+    Pyo3,
+}
+
+impl SourcePack {
+    pub const ALL: [SourcePack; 2] = [SourcePack::WasmBindgen, SourcePack::Pyo3];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SourcePack::WasmBindgen => "wasm-bindgen",
+            SourcePack::Pyo3 => "pyo3",
+        }
+    }
+
+    /// The attribute macros exporting the functions they are put on, or the methods of the impls they are put on.
+    pub fn macros(self) -> &'static [&'static str] {
+        match self {
+            SourcePack::WasmBindgen => &["wasm_bindgen"],
+            SourcePack::Pyo3 => &["pyfunction", "pymethods"],
+        }
+    }
+
+    fn from_name(name: &str) -> Result<SourcePack, String> {
+        match name {
+            "wasm-bindgen" => Ok(SourcePack::WasmBindgen),
+            "pyo3" => Ok(SourcePack::Pyo3),
+            _ => Err(format!(
+                "unknown source pack `{}`, expected `wasm-bindgen` or `pyo3`",
+                name
+            )),
+        }
+    }
+}
+
+/// A document describing the analysis which can be printed instead of analyzing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emit {
+    /// A Markdown page describing the policy enforced: the sources, sinks, sanitizers and labels,
+    /// the built-in rules enabled, and where each was declared, for security teams to review.
+    PolicyDoc,
+    /// The paths and patterns of the config and of the command line, with what each matches in the crate graph,
+    /// for policy authors to find those which match nothing without waiting for an analysis.
+    PolicyCheck,
+}
+
+impl Emit {
+    pub const ALL: [Emit; 2] = [Emit::PolicyDoc, Emit::PolicyCheck];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Emit::PolicyDoc => "policy-doc",
+            Emit::PolicyCheck => "policy-check",
+        }
+    }
+
+    fn from_name(name: &str) -> Result<Emit, String> {
+        match name {
+            "policy-doc" => Ok(Emit::PolicyDoc),
+            "policy-check" => Ok(Emit::PolicyCheck),
+            _ => Err(format!(
+                "unknown document `{}`, expected `policy-doc` or `policy-check`",
+                name
+            )),
+        }
+    }
+}
+
+impl Options {
+    /// Splits `args` into our options and the arguments meant for rustc,
+    /// starting from the options set in the environment.
+    /// Arguments after a `--` are all options, with or without the `--taint-` prefix.
+    pub fn from_args(
+        args: impl IntoIterator<Item = String>,
+    ) -> Result<(Options, Vec<String>), Error> {
+        let mut options = Options::default();
+        let mut rustc_args = vec![];
+
+        for (variable, option) in ENV {
+            if let Ok(value) = std::env::var(variable) {
+                options
+                    .set(&format!("{}={}", option, value))
+                    .map_err(|message| Error::option(variable, message))?;
+            }
+        }
+
+        let mut separated = false;
+        for arg in args {
+            let option = match arg.strip_prefix(PREFIX) {
+                Some(option) => option,
+                None if separated => arg.strip_prefix(SEPARATOR).ok_or_else(|| {
+                    Error::option(&arg, "expected an option of the analysis after `--`")
+                })?,
+                None if arg == SEPARATOR => {
+                    separated = true;
+                    continue;
+                }
+                None => {
+                    rustc_args.push(arg);
+                    continue;
+                }
+            };
+            options
+                .set(option)
+                .map_err(|message| Error::option(&arg, message))?;
+        }
+
+        Ok((options, rustc_args))
+    }
+
+    /// Whether the labels after each statement are recorded, which the findings are then sliced with,
+    /// for `--taint-results`, `--taint-slice` and `--taint-reproducers`.
+    pub fn records_facts(&self) -> bool {
+        self.results.is_some() || self.slice.is_some() || self.reproducers.is_some()
+    }
+
+    /// These options, extended with `args`, the options of the profile `name` of the config,
+    /// which are given with the `--taint-` prefix and take precedence.
+    /// The run does not carry summaries over for the profile, whose options differ from the others.
+    pub fn with_profile(&self, name: &str, args: &[String]) -> Result<Options, Error> {
+        let mut options = Options {
+            profile: Some(name.to_owned()),
+            warm_start: None,
+            ..self.clone()
+        };
+        for arg in args {
+            let option = arg.strip_prefix(PREFIX).ok_or_else(|| {
+                Error::option(arg, format!("expected an option of profile `{}`", name))
+            })?;
+            options
+                .set(option)
+                .map_err(|message| Error::option(arg, message))?;
+        }
+        Ok(options)
+    }
+
+    /// Sets `option`, which is given without the `--taint-` prefix.
+    fn set(&mut self, option: &str) -> Result<(), String> {
+        match option {
+            "summary-report" => self.summary_report = true,
+            "coverage-report" => self.coverage_report = true,
+            "list-annotations" => self.list_annotations = true,
+            "black-box-barrier" => self.black_box_barrier = true,
+            "library" => self.library = true,
+            "include-tests" => self.include_tests = true,
+            "fail-fast" => self.fail_fast = true,
+            "report-in-deps" => self.report_in_deps = true,
+            "lint-clean-sanitizers" => self.lint_clean_sanitizers = true,
+            "lint-unzeroized-secrets" => self.lint_unzeroized_secrets = true,
+            "debug-invariants" => self.debug_invariants = true,
+            "debug-stable-mir" => self.debug_stable_mir = true,
+            "findings-db" => self.findings_db = Some(history::FILE_NAME.into()),
+            "changed-only" => self.changed_only = Some(ChangedOnly::Git),
+            _ => match option.split_once('=') {
+                Some(("sink-macros", macros)) => self
+                    .sink_macros
+                    .extend(macros.split(',').map(|name| name.trim().to_owned())),
+                Some(("results", path)) => self.results = Some(path.into()),
+                Some(("config", path)) => self.config = Some(path.into()),
+                Some(("entries", path)) => self.entries = Some(path.into()),
+                Some(("add-source", path)) => self.add_sources.push(path.to_owned()),
+                Some(("add-sink", path)) => self.add_sinks.push(path.to_owned()),
+                Some(("add-sanitizer", path)) => self.add_sanitizers.push(path.to_owned()),
+                Some(("policy-hook", path)) => self.policy_hook = Some(path.into()),
+                Some(("findings", path)) => self.findings = Some(path.into()),
+                Some(("findings-db", path)) => self.findings_db = Some(path.into()),
+                Some(("junit", path)) => self.junit = Some(path.into()),
+                Some(("metrics", path)) => self.metrics = Some(path.into()),
+                Some(("changed-only", path)) => {
+                    self.changed_only = Some(ChangedOnly::Listed(path.into()))
+                }
+                Some(("report-url", url)) => {
+                    let http = reporters::Http::new(url)?;
+                    self.reporters.0.push(Arc::new(http))
+                }
+                Some(("baseline", path)) => self.baseline = Some(path.into()),
+                Some(("fuzz-targets", path)) => self.fuzz_targets = Some(path.into()),
+                Some(("slice", fingerprint)) => self.slice = Some(fingerprint.to_owned()),
+                Some(("reproducers", dir)) => self.reproducers = Some(dir.into()),
+                Some(("explain", function)) => self.explain = Some(function.to_owned()),
+                Some(("export-summaries", path)) => self.export_summaries = Some(path.into()),
+                Some(("import-summaries", path)) => self.import_summaries = Some(path.into()),
+                Some(("mode", mode)) => self.set_mode(mode)?,
+                Some(("unknown-calls", "clean")) => self.unknown_calls = UnknownCalls::Clean,
+                Some(("unknown-calls", "propagate")) => {
+                    self.unknown_calls = UnknownCalls::Propagate
+                }
+                Some(("unknown-calls", value)) => {
+                    return Err(format!(
+                        "expected `clean` or `propagate`, found `{}`",
+                        value
+                    ))
+                }
+                Some(("fail-level", "all")) => self.fail_level = FailLevel::All,
+                Some(("fail-level", "high-confidence")) => {
+                    self.fail_level = FailLevel::HighConfidence
+                }
+                Some(("fail-level", value)) => {
+                    return Err(format!(
+                        "expected `all` or `high-confidence`, found `{}`",
+                        value
+                    ))
+                }
+                Some(("mir", "built")) => self.mir = Mir::Built,
+                Some(("mir", "promoted")) => self.mir = Mir::Promoted,
+                Some(("mir", "optimized")) => self.mir = Mir::Optimized,
+                Some(("mir", value)) => {
+                    return Err(format!(
+                        "expected `built`, `promoted` or `optimized`, found `{}`",
+                        value
+                    ))
+                }
+                Some(("integration", "callbacks")) => self.integration = Integration::Callbacks,
+                Some(("integration", "query")) => self.integration = Integration::Query,
+                Some(("integration", value)) => {
+                    return Err(format!(
+                        "expected `callbacks` or `query`, found `{}`",
+                        value
+                    ))
+                }
+                Some(("progress", "json")) => self.progress = Progress::Json,
+                Some(("progress", value)) => {
+                    return Err(format!("expected `json`, found `{}`", value))
+                }
+                Some(("weak-updates", value)) => self.weak_updates = parse_bool(value)?,
+                Some(("max-arity", value)) => {
+                    let arity = value
+                        .parse()
+                        .map_err(|_| format!("expected a number, found `{}`", value))?;
+                    self.max_arity = Some(arity)
+                }
+                Some(("max-points-to", value)) => {
+                    let max = value
+                        .parse()
+                        .map_err(|_| format!("expected a number, found `{}`", value))?;
+                    self.max_points_to = Some(max)
+                }
+                Some(("debug-hash-order", value)) => {
+                    let runs = value
+                        .parse()
+                        .map_err(|_| format!("expected a number, found `{}`", value))?;
+                    self.debug_hash_order = Some(runs)
+                }
+                Some(("block-summaries", value)) => {
+                    let statements = value
+                        .parse()
+                        .map_err(|_| format!("expected a number, found `{}`", value))?;
+                    self.block_summaries = Some(statements)
+                }
+                Some(("include-tests", value)) => self.include_tests = parse_bool(value)?,
+                Some(("summary-packs", value)) => self.skip_summary_packs = !parse_bool(value)?,
+                Some(("crate-interfaces", value)) => self.crate_interfaces = parse_bool(value)?,
+                Some(("dependency-cache", dir)) => self.dependency_cache = Some(dir.into()),
+                Some(("resume", dir)) => self.resume = Some(dir.into()),
+                Some(("status-line", value)) => self.status_line = parse_bool(value)?,
+                Some(("fail-fast", value)) => self.fail_fast = parse_bool(value)?,
+                Some(("strict-mir", value)) => self.strict_mir = parse_bool(value)?,
+                Some(("emit", documents)) => {
+                    for document in documents.split(',') {
+                        self.emit.push(Emit::from_name(document.trim())?);
+                    }
+                }
+                Some(("sink-packs", packs)) => {
+                    for pack in packs.split(',') {
+                        self.sink_packs.push(SinkPack::from_name(pack.trim())?);
+                    }
+                }
+                Some(("source-packs", packs)) => {
+                    for pack in packs.split(',') {
+                        self.source_packs.push(SourcePack::from_name(pack.trim())?);
+                    }
+                }
+                _ => return Err(unknown(option)),
+            },
+        }
+        Ok(())
+    }
+
+    /// Presets for the options trading false negatives against false positives.
+    /// Options given after the mode override it.
+    fn set_mode(&mut self, mode: &str) -> Result<(), String> {
+        match mode {
+            // Assume the worst of whatever we cannot see.
+            "strict" => {
+                self.unknown_calls = UnknownCalls::Propagate;
+                self.weak_updates = true;
+            }
+            // Only report flows through code we analyzed or have a model of.
+            "precise" => {
+                self.unknown_calls = UnknownCalls::Clean;
+                self.weak_updates = false;
+            }
+            _ => {
+                return Err(format!(
+                    "unknown mode `{}`, expected `strict` or `precise`",
+                    mode
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The error for `option`, which is not one of the [`FLAGS`], suggesting the flag it is closest to.
+fn unknown(option: &str) -> String {
+    let name = option.split_once('=').map_or(option, |(name, _)| name);
+    let closest = FLAGS
+        .iter()
+        .map(|(flag, _)| {
+            let flag = flag.trim_start_matches(PREFIX);
+            flag.split(|c| c == '=' || c == '[').next().unwrap_or(flag)
+        })
+        .map(|flag| (edit_distance(name, flag), flag))
+        .min();
+    match closest {
+        Some((distance, flag)) if distance <= 2 => {
+            format!("unknown option, did you mean `{}{}`?", PREFIX, flag)
+        }
+        _ => "unknown option, `--taint-help` lists the options".to_owned(),
+    }
+}
+
+/// The number of characters to insert, remove or replace to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let replace = previous[j] + usize::from(ca != *cb);
+            current.push(replace.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "yes" | "true" => Ok(true),
+        "no" | "false" => Ok(false),
+        _ => Err(format!("expected `yes` or `no`, found `{}`", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| (*arg).to_owned()).collect()
+    }
+
+    #[test]
+    fn every_flag_listed_is_an_option() {
+        for (flag, _) in FLAGS {
+            let flag = flag.replace("[=<file>]", "");
+            let example = match flag.split_once('=') {
+                Some((name, "<n>")) => format!("{}=1", name),
+                Some((name, "<url>")) => format!("{}=http://localhost/findings", name),
+                Some((name, value)) if value.starts_with('<') => format!("{}=value", name),
+                Some((name, values)) => format!("{}={}", name, values.split('|').next().unwrap()),
+                None => flag,
+            };
+            assert!(
+                Options::from_args(vec![example.clone()]).is_ok(),
+                "`{}` is not an option",
+                example
+            );
+        }
+    }
+
+    #[test]
+    fn arguments_after_a_separator_are_options() {
+        let (options, rustc_args) = Options::from_args(args(&[
+            "main.rs",
+            "--taint-include-tests",
+            "--",
+            "--library",
+            "--taint-fail-level=high-confidence",
+        ]))
+        .unwrap();
+        assert_eq!(rustc_args, ["main.rs"]);
+        assert!(options.include_tests && options.library);
+        assert_eq!(options.fail_level, FailLevel::HighConfidence);
+
+        assert_eq!(
+            Options::from_args(args(&["--", "main.rs"])).unwrap_err(),
+            Error::option("main.rs", "expected an option of the analysis after `--`")
+        );
+    }
+
+    #[test]
+    fn unknown_options_suggest_the_closest_flag() {
+        assert_eq!(
+            Options::from_args(args(&["--taint-libary"])).unwrap_err(),
+            Error::option(
+                "--taint-libary",
+                "unknown option, did you mean `--taint-library`?"
+            )
+        );
+        assert_eq!(
+            Options::from_args(args(&["--", "--fail-levels=all"])).unwrap_err(),
+            Error::option(
+                "--fail-levels=all",
+                "unknown option, did you mean `--taint-fail-level`?"
+            )
+        );
+        assert_eq!(
+            Options::from_args(args(&["--taint-everything"])).unwrap_err(),
+            Error::option(
+                "--taint-everything",
+                "unknown option, `--taint-help` lists the options"
+            )
+        );
+    }
+
+    #[test]
+    fn help_is_asked_for_with_the_prefix_or_after_a_separator() {
+        assert!(asks_for_help(&args(&["main.rs", "--taint-help"])));
+        assert!(asks_for_help(&args(&["main.rs", "--", "--help"])));
+        // Without a separator, `--help` is rustc's.
+        assert!(!asks_for_help(&args(&["--help"])));
+        assert!(help()
+            .contains("  --taint-fail-level=all|high-confidence\n      which findings are errors"));
+    }
+}
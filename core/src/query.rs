@@ -0,0 +1,223 @@
+//! Querying the results of a previous run, without running the analysis again.
+//!
+//! With `--taint-results=<file>`, the driver writes the taint of every value it saw assigned,
+//! keyed by the function and the source span of the assignment.
+//! Tools like IDE plugins can then read the file with [`Results::read`]
+//! and ask whether the expression at a position was tainted with [`Results::taint_at`].
+
+use std::{fs, path::Path};
+
+use serde_json::{json, Value};
+
+use crate::error::Error;
+
+/// The version of the results format, bumped on incompatible changes.
+pub const VERSION: u64 = 1;
+
+/// A position in a source file. Lines and columns start at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The taint of the value assigned by the expression at `file`, from `start` to `end`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fact {
+    /// The path of the function the expression is in, like `my_crate::handler`.
+    pub function: String,
+    pub file: String,
+    pub start: Position,
+    pub end: Position,
+    /// The labels the value carried, which are empty if it was clean in every context.
+    pub labels: Vec<String>,
+    /// The entry points whose analysis the value was tainted in.
+    pub entries: Vec<String>,
+}
+
+/// The taint of an expression, as found by [`Results::taint_at`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Taint {
+    pub labels: Vec<String>,
+    pub entries: Vec<String>,
+}
+
+impl Taint {
+    pub fn is_tainted(&self) -> bool {
+        !self.labels.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Results {
+    pub facts: Vec<Fact>,
+}
+
+impl Results {
+    pub fn read(path: impl AsRef<Path>) -> Result<Results, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        Results::from_json(&text)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        fs::write(path, self.to_json()).map_err(|e| Error::io(path, e))
+    }
+
+    /// The taint of the innermost expression of `function` at `position` in `file`,
+    /// or `None` if no expression there was analyzed.
+    pub fn taint_at(&self, function: &str, file: &str, position: Position) -> Option<Taint> {
+        let innermost = self
+            .facts
+            .iter()
+            .filter(|f| f.function == function && f.file == file)
+            .filter(|f| f.start <= position && position <= f.end)
+            .min_by_key(|f| {
+                (
+                    f.end.line - f.start.line,
+                    f.end.column.abs_diff(f.start.column),
+                )
+            })?;
+
+        // The same expression may have been recorded for several entry points.
+        let mut taint = Taint::default();
+        for fact in self.facts.iter().filter(|f| {
+            f.function == function
+                && f.file == file
+                && f.start == innermost.start
+                && f.end == innermost.end
+        }) {
+            merge(&mut taint.labels, &fact.labels);
+            merge(&mut taint.entries, &fact.entries);
+        }
+        Some(taint)
+    }
+
+    pub fn to_json(&self) -> String {
+        let facts = self
+            .facts
+            .iter()
+            .map(|fact| {
+                json!({
+                    "function": fact.function,
+                    "file": fact.file,
+                    "start": [fact.start.line, fact.start.column],
+                    "end": [fact.end.line, fact.end.column],
+                    "labels": fact.labels,
+                    "entries": fact.entries,
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({ "version": VERSION, "facts": facts }).to_string()
+    }
+
+    pub fn from_json(text: &str) -> Result<Results, Error> {
+        let value: Value = serde_json::from_str(text).map_err(|e| Error::format("results", e))?;
+        if value["version"] != VERSION {
+            return Err(Error::format(
+                "results",
+                format!(
+                    "unsupported version {}, expected {}",
+                    value["version"], VERSION
+                ),
+            ));
+        }
+
+        let facts = value["facts"]
+            .as_array()
+            .ok_or_else(|| Error::format("results", "missing `facts`"))?
+            .iter()
+            .map(fact_from_json)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::format("results", "malformed fact"))?;
+        Ok(Results { facts })
+    }
+}
+
+fn fact_from_json(value: &Value) -> Option<Fact> {
+    let position = |value: &Value| {
+        Some(Position {
+            line: value[0].as_u64()? as usize,
+            column: value[1].as_u64()? as usize,
+        })
+    };
+    let strings = |value: &Value| {
+        value
+            .as_array()?
+            .iter()
+            .map(|s| s.as_str().map(str::to_owned))
+            .collect::<Option<Vec<_>>>()
+    };
+
+    Some(Fact {
+        function: value["function"].as_str()?.to_owned(),
+        file: value["file"].as_str()?.to_owned(),
+        start: position(&value["start"])?,
+        end: position(&value["end"])?,
+        labels: strings(&value["labels"])?,
+        entries: strings(&value["entries"])?,
+    })
+}
+
+fn merge(into: &mut Vec<String>, from: &[String]) {
+    for item in from {
+        if !into.contains(item) {
+            into.push(item.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fact(start: (usize, usize), end: (usize, usize), labels: &[&str], entry: &str) -> Fact {
+        Fact {
+            function: "demo::main".to_owned(),
+            file: "src/main.rs".to_owned(),
+            start: Position {
+                line: start.0,
+                column: start.1,
+            },
+            end: Position {
+                line: end.0,
+                column: end.1,
+            },
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            entries: vec![entry.to_owned()],
+        }
+    }
+
+    #[test]
+    fn innermost_expression_is_queried() {
+        let results = Results {
+            facts: vec![
+                fact((3, 5), (3, 30), &[], "demo::main"),
+                fact((3, 13), (3, 20), &["taint"], "demo::main"),
+                fact((3, 13), (3, 20), &["taint"], "demo::other"),
+            ],
+        };
+        let at = |column| Position { line: 3, column };
+
+        let inner = results
+            .taint_at("demo::main", "src/main.rs", at(15))
+            .unwrap();
+        assert!(inner.is_tainted());
+        assert_eq!(inner.entries, vec!["demo::main", "demo::other"]);
+        assert!(!results
+            .taint_at("demo::main", "src/main.rs", at(6))
+            .unwrap()
+            .is_tainted());
+        assert_eq!(results.taint_at("demo::main", "src/main.rs", at(40)), None);
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let results = Results {
+            facts: vec![fact((1, 1), (2, 4), &["taint"], "demo::main")],
+        };
+
+        assert_eq!(Results::from_json(&results.to_json()), Ok(results));
+    }
+}
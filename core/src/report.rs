@@ -0,0 +1,566 @@
+//! The findings of a run, in a form other tools can consume.
+//!
+//! With `--taint-findings=<file>`, the driver writes every finding it reported as JSON.
+//! Each finding carries a [fingerprint](fingerprint) which does not depend on where in the file
+//! the finding is, so that findings can be matched up between runs after the code was reformatted
+//! or moved around.
+//!
+//! The report also lists choke points: the arguments of functions and sinks which the flows to
+//! several findings pass through, so that sanitizing there clears all of them at once.
+//! Together, they cover every finding, starting with the one which clears the most.
+//!
+//! Findings whose tainted input came from the same call to a source form a [family](Family),
+//! which handling the data where the source returns it usually clears at once.
+//!
+//! Each finding has a [confidence](Confidence) that its flow is real, lowered by what the analysis
+//! had to assume to find it, so that triage and `--taint-fail-level=high-confidence` can start with
+//! the findings least likely to be false positives.
+
+use std::{convert::TryFrom, fs, path::Path};
+
+use serde_json::{json, Value};
+
+use crate::{error::Error, query::Position};
+
+/// The version of the findings format, bumped on incompatible changes.
+pub const VERSION: u64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub fingerprint: String,
+    /// The error code, like `T0001`.
+    pub code: String,
+    pub message: String,
+    /// The calls through which the function was reached with tainted arguments, from the innermost one out,
+    /// like ``when `process(data)` is called with tainted `data` (from caller at src/main.rs:42)``.
+    pub context: Vec<String>,
+    /// The path of the function the sink is called in.
+    pub function: String,
+    /// The sink which received tainted input: a function path, or a macro name followed by `!`.
+    pub sink: String,
+    /// The label the sink received, like `taint`.
+    pub label: String,
+    /// The profiles of the entry points it was found from, with `--taint-entries`,
+    /// or none if they were analyzed with the options of the command line alone.
+    pub profiles: Vec<String>,
+    /// The identifier of the [family](Family) of the finding, if the call to the source
+    /// its tainted input came from was found.
+    pub family: Option<String>,
+    /// The team owning the function, from the `owners` of the [config](crate::config), if it has one.
+    pub owner: Option<String>,
+    pub confidence: Confidence,
+    pub file: String,
+    pub start: Position,
+    pub end: Position,
+}
+
+/// How confident the analysis is that the flow to a finding is real, from 0 to 100.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Confidence {
+    pub score: u8,
+    /// What lowered the score, like ``the analysis of `demo::parse` was approximated``.
+    pub doubts: Vec<String>,
+}
+
+/// The score at or above which a finding is high-confidence.
+pub const HIGH_CONFIDENCE: u8 = 80;
+
+impl Confidence {
+    /// A confidence of 100, without doubts.
+    pub fn certain() -> Confidence {
+        Confidence {
+            score: 100,
+            doubts: vec![],
+        }
+    }
+
+    /// Lowers the score by `penalty`, for `doubt`.
+    pub fn doubt(&mut self, penalty: u8, doubt: String) {
+        self.score = self.score.saturating_sub(penalty);
+        self.doubts.push(doubt);
+    }
+
+    pub fn is_high(&self) -> bool {
+        self.score >= HIGH_CONFIDENCE
+    }
+}
+
+/// An argument every flow to some findings passes through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChokePoint {
+    /// The path of a function the findings were reached through, or of the sink they are about.
+    pub function: String,
+    /// The position of the argument, starting at 0, or `None` for several arguments at once,
+    /// like those of a sink called with more than one tainted argument, or what a sink returns or formats.
+    pub argument: Option<usize>,
+    /// The fingerprints of the findings whose flows all pass through the argument.
+    pub findings: Vec<String>,
+}
+
+/// The findings whose tainted input came from the same call to a source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Family {
+    /// Identifies the family like a [fingerprint], by the function the source is called in, the source,
+    /// and how many calls to the source come before it in the function.
+    pub id: String,
+    /// The path of the source.
+    pub source: String,
+    /// The path of the function the source is called in.
+    pub function: String,
+    pub file: String,
+    pub start: Position,
+    pub end: Position,
+    /// The fingerprints of the findings of the family, in the order they are reported.
+    pub findings: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub findings: Vec<Finding>,
+    /// The fewest arguments to sanitize to clear every finding, most effective first.
+    pub choke_points: Vec<ChokePoint>,
+    /// The families of the findings, in the order of their sources.
+    pub families: Vec<Family>,
+}
+
+/// Identifies a finding by the function it is in, the sink, the label,
+/// and how many findings with the same function, sink and label come before it.
+///
+/// Spans are left out on purpose, so the fingerprint survives formatting changes and code moves.
+/// The analysis does not track which source tainted a value, so sources are not part of it.
+pub fn fingerprint(function: &str, sink: &str, label: &str, occurrence: usize) -> String {
+    hash(&[function, sink, label, &occurrence.to_string()])
+}
+
+/// Identifies a family by the function the source is called in, the source, and how many calls
+/// to the source come before it in the function. Like fingerprints, it leaves spans out.
+pub fn family_id(function: &str, source: &str, occurrence: usize) -> String {
+    hash(&["family", function, source, &occurrence.to_string()])
+}
+
+fn hash(parts: &[&str]) -> String {
+    // FNV-1a, which unlike the hasher of the standard library is guaranteed to stay the same.
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        for byte in part.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+impl Report {
+    pub fn read(path: impl AsRef<Path>) -> Result<Report, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        Report::from_json(&text)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        fs::write(path, self.to_json()).map_err(|e| Error::io(path, e))
+    }
+
+    /// The findings of a run which only analyzed the functions at the paths `analyzed`, completed with
+    /// those `baseline` has in the other functions, which that run had no reason to find again.
+    /// Choke points and families of the baseline are kept if all of their findings are.
+    pub fn with_baseline(self, baseline: Report, analyzed: &[String]) -> Report {
+        self.completed(baseline, |finding| !analyzed.contains(&finding.function))
+    }
+
+    /// The findings of a run which resumed from a checkpoint, completed with those `resumed` the checkpoint holds
+    /// which the run did not find again, like those of the entry points it did not analyze again.
+    /// Findings are told apart by where they are, so that one found by both is only reported once.
+    pub fn with_resumed(self, resumed: Report) -> Report {
+        let found = self
+            .findings
+            .iter()
+            .map(|f| (f.file.clone(), f.start, f.sink.clone(), f.label.clone()))
+            .collect::<Vec<_>>();
+        self.completed(resumed, |finding| {
+            let location = (
+                finding.file.clone(),
+                finding.start,
+                finding.sink.clone(),
+                finding.label.clone(),
+            );
+            !found.contains(&location)
+        })
+    }
+
+    /// These findings, completed with those of `other` which `keep` keeps, and the choke points and families
+    /// of `other` all of whose findings are kept.
+    fn completed(mut self, other: Report, keep: impl Fn(&Finding) -> bool) -> Report {
+        let kept = other
+            .findings
+            .into_iter()
+            .filter(|finding| keep(finding))
+            .collect::<Vec<_>>();
+        self.choke_points
+            .extend(other.choke_points.into_iter().filter(|point| {
+                point
+                    .findings
+                    .iter()
+                    .all(|fingerprint| kept.iter().any(|f| &f.fingerprint == fingerprint))
+            }));
+        self.families
+            .extend(other.families.into_iter().filter(|family| {
+                family
+                    .findings
+                    .iter()
+                    .all(|fingerprint| kept.iter().any(|f| &f.fingerprint == fingerprint))
+            }));
+        self.findings.extend(kept);
+        self
+    }
+
+    pub fn to_json(&self) -> String {
+        let findings = self
+            .findings
+            .iter()
+            .map(finding_to_json)
+            .collect::<Vec<_>>();
+        let choke_points = self
+            .choke_points
+            .iter()
+            .map(|point| {
+                json!({
+                    "function": point.function,
+                    "argument": point.argument,
+                    "findings": point.findings,
+                })
+            })
+            .collect::<Vec<_>>();
+        let families = self
+            .families
+            .iter()
+            .map(|family| {
+                json!({
+                    "id": family.id,
+                    "source": family.source,
+                    "function": family.function,
+                    "file": family.file,
+                    "start": [family.start.line, family.start.column],
+                    "end": [family.end.line, family.end.column],
+                    "findings": family.findings,
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "version": VERSION,
+            "findings": findings,
+            "choke_points": choke_points,
+            "families": families,
+        })
+        .to_string()
+    }
+
+    pub fn from_json(text: &str) -> Result<Report, Error> {
+        let value: Value = serde_json::from_str(text).map_err(|e| Error::format("findings", e))?;
+        if value["version"] != VERSION {
+            return Err(Error::format(
+                "findings",
+                format!(
+                    "unsupported version {}, expected {}",
+                    value["version"], VERSION
+                ),
+            ));
+        }
+
+        let findings = value["findings"]
+            .as_array()
+            .ok_or_else(|| Error::format("findings", "missing `findings`"))?
+            .iter()
+            .map(finding_from_json)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::format("findings", "malformed finding"))?;
+        // Reports written before choke points were computed have none.
+        let choke_points = match value.get("choke_points") {
+            Some(points) => points
+                .as_array()
+                .and_then(|points| points.iter().map(choke_point_from_json).collect())
+                .ok_or_else(|| Error::format("findings", "malformed choke point"))?,
+            None => vec![],
+        };
+        // Reports written before families were found have none.
+        let families = match value.get("families") {
+            Some(families) => families
+                .as_array()
+                .and_then(|families| families.iter().map(family_from_json).collect())
+                .ok_or_else(|| Error::format("findings", "malformed family"))?,
+            None => vec![],
+        };
+        Ok(Report {
+            findings,
+            choke_points,
+            families,
+        })
+    }
+}
+
+fn position(value: &Value) -> Option<Position> {
+    Some(Position {
+        line: value[0].as_u64()? as usize,
+        column: value[1].as_u64()? as usize,
+    })
+}
+
+fn family_from_json(value: &Value) -> Option<Family> {
+    let string = |key: &str| value[key].as_str().map(str::to_owned);
+    Some(Family {
+        id: string("id")?,
+        source: string("source")?,
+        function: string("function")?,
+        file: string("file")?,
+        start: position(&value["start"])?,
+        end: position(&value["end"])?,
+        findings: value["findings"]
+            .as_array()?
+            .iter()
+            .map(|fingerprint| fingerprint.as_str().map(str::to_owned))
+            .collect::<Option<_>>()?,
+    })
+}
+
+fn choke_point_from_json(value: &Value) -> Option<ChokePoint> {
+    Some(ChokePoint {
+        function: value["function"].as_str()?.to_owned(),
+        argument: match &value["argument"] {
+            Value::Null => None,
+            argument => Some(argument.as_u64()? as usize),
+        },
+        findings: value["findings"]
+            .as_array()?
+            .iter()
+            .map(|fingerprint| fingerprint.as_str().map(str::to_owned))
+            .collect::<Option<_>>()?,
+    })
+}
+
+pub(crate) fn finding_to_json(finding: &Finding) -> Value {
+    json!({
+        "fingerprint": finding.fingerprint,
+        "code": finding.code,
+        "message": finding.message,
+        "context": finding.context,
+        "function": finding.function,
+        "sink": finding.sink,
+        "label": finding.label,
+        "profiles": finding.profiles,
+        "family": finding.family,
+        "owner": finding.owner,
+        "confidence": {
+            "score": finding.confidence.score,
+            "doubts": finding.confidence.doubts,
+        },
+        "file": finding.file,
+        "start": [finding.start.line, finding.start.column],
+        "end": [finding.end.line, finding.end.column],
+    })
+}
+
+pub(crate) fn finding_from_json(value: &Value) -> Option<Finding> {
+    let string = |key: &str| value[key].as_str().map(str::to_owned);
+
+    Some(Finding {
+        fingerprint: string("fingerprint")?,
+        code: string("code")?,
+        message: string("message")?,
+        // Findings written before contexts were recorded have none.
+        context: match value.get("context") {
+            Some(context) => context
+                .as_array()?
+                .iter()
+                .map(|note| note.as_str().map(str::to_owned))
+                .collect::<Option<_>>()?,
+            None => vec![],
+        },
+        function: string("function")?,
+        sink: string("sink")?,
+        label: string("label")?,
+        // Findings written before profiles were recorded have none.
+        profiles: match value.get("profiles") {
+            Some(profiles) => profiles
+                .as_array()?
+                .iter()
+                .map(|profile| profile.as_str().map(str::to_owned))
+                .collect::<Option<_>>()?,
+            None => vec![],
+        },
+        // Findings written before families were found have none.
+        family: match value.get("family") {
+            None | Some(Value::Null) => None,
+            Some(family) => Some(family.as_str()?.to_owned()),
+        },
+        // Findings written before owners were given have none.
+        owner: match value.get("owner") {
+            None | Some(Value::Null) => None,
+            Some(owner) => Some(owner.as_str()?.to_owned()),
+        },
+        // Findings written before confidences were given have no doubts.
+        confidence: match value.get("confidence") {
+            Some(confidence) => Confidence {
+                score: u8::try_from(confidence["score"].as_u64()?).ok()?,
+                doubts: confidence["doubts"]
+                    .as_array()?
+                    .iter()
+                    .map(|doubt| doubt.as_str().map(str::to_owned))
+                    .collect::<Option<_>>()?,
+            },
+            None => Confidence::certain(),
+        },
+        file: string("file")?,
+        start: position(&value["start"])?,
+        end: position(&value["end"])?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprints_tell_parts_apart() {
+        let fp = fingerprint("demo::main", "demo::output", "taint", 0);
+
+        assert_eq!(fp.len(), 16);
+        assert_eq!(fp, fingerprint("demo::main", "demo::output", "taint", 0));
+        assert_ne!(fp, fingerprint("demo::main", "demo::output", "taint", 1));
+        assert_ne!(fp, fingerprint("demo::mai", "ndemo::output", "taint", 0));
+    }
+
+    fn finding(function: &str, line: usize) -> Finding {
+        Finding {
+            fingerprint: fingerprint(function, "demo::output", "taint", 0),
+            code: "T0001".to_owned(),
+            message: "function `output` received tainted input".to_owned(),
+            context: vec![],
+            function: function.to_owned(),
+            sink: "demo::output".to_owned(),
+            label: "taint".to_owned(),
+            profiles: vec![],
+            family: None,
+            owner: None,
+            confidence: Confidence::certain(),
+            file: "src/main.rs".to_owned(),
+            start: Position { line, column: 5 },
+            end: Position { line, column: 20 },
+        }
+    }
+
+    #[test]
+    fn baselines_fill_in_the_functions_not_analyzed() {
+        let baseline = Report {
+            findings: vec![finding("demo::handle", 3), finding("demo::render", 9)],
+            choke_points: vec![
+                ChokePoint {
+                    function: "demo::render".to_owned(),
+                    argument: Some(0),
+                    findings: vec![finding("demo::render", 9).fingerprint],
+                },
+                ChokePoint {
+                    function: "demo::handle".to_owned(),
+                    argument: Some(0),
+                    findings: vec![finding("demo::handle", 3).fingerprint],
+                },
+            ],
+            families: vec![],
+        };
+        let run = Report {
+            findings: vec![finding("demo::handle", 4)],
+            choke_points: vec![],
+            families: vec![],
+        };
+
+        let merged = run.with_baseline(baseline, &["demo::handle".to_owned()]);
+        assert_eq!(
+            merged.findings,
+            vec![finding("demo::handle", 4), finding("demo::render", 9)]
+        );
+        assert_eq!(merged.choke_points.len(), 1);
+        assert_eq!(merged.choke_points[0].function, "demo::render");
+    }
+
+    #[test]
+    fn resumed_findings_are_kept_unless_found_again() {
+        let resumed = Report {
+            findings: vec![finding("demo::handle", 3), finding("demo::render", 9)],
+            choke_points: vec![ChokePoint {
+                function: "demo::render".to_owned(),
+                argument: Some(0),
+                findings: vec![finding("demo::render", 9).fingerprint],
+            }],
+            families: vec![],
+        };
+        let run = Report {
+            findings: vec![finding("demo::handle", 3), finding("demo::handle", 4)],
+            choke_points: vec![],
+            families: vec![],
+        };
+
+        let merged = run.with_resumed(resumed);
+        assert_eq!(
+            merged.findings,
+            vec![
+                finding("demo::handle", 3),
+                finding("demo::handle", 4),
+                finding("demo::render", 9)
+            ]
+        );
+        assert_eq!(merged.choke_points.len(), 1);
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let report = Report {
+            findings: vec![Finding {
+                fingerprint: fingerprint("demo::main", "demo::output", "taint", 0),
+                code: "T0001".to_owned(),
+                message: "function `output` received tainted input".to_owned(),
+                context: vec![
+                    "when `process(data)` is called with tainted `data` (from caller at src/main.rs:9)"
+                        .to_owned(),
+                ],
+                function: "demo::main".to_owned(),
+                sink: "demo::output".to_owned(),
+                label: "taint".to_owned(),
+                profiles: vec!["web".to_owned()],
+                family: Some(family_id("demo::main", "demo::input", 0)),
+                owner: Some("team-payments".to_owned()),
+                confidence: Confidence {
+                    score: 70,
+                    doubts: vec![
+                        "the call to `demo::input` its input comes from does not run on every path to the sink"
+                            .to_owned(),
+                    ],
+                },
+                file: "src/main.rs".to_owned(),
+                start: Position { line: 3, column: 5 },
+                end: Position {
+                    line: 3,
+                    column: 20,
+                },
+            }],
+            choke_points: vec![ChokePoint {
+                function: "demo::process".to_owned(),
+                argument: Some(0),
+                findings: vec![fingerprint("demo::main", "demo::output", "taint", 0)],
+            }],
+            families: vec![Family {
+                id: family_id("demo::main", "demo::input", 0),
+                source: "demo::input".to_owned(),
+                function: "demo::main".to_owned(),
+                file: "src/main.rs".to_owned(),
+                start: Position { line: 2, column: 16 },
+                end: Position {
+                    line: 2,
+                    column: 23,
+                },
+                findings: vec![fingerprint("demo::main", "demo::output", "taint", 0)],
+            }],
+        };
+
+        assert_eq!(Report::from_json(&report.to_json()), Ok(report));
+    }
+}
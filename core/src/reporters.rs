@@ -0,0 +1,352 @@
+//! Places the findings of each crate analyzed are sent to, besides the diagnostics and the files
+//! written with `--taint-findings`, like the dashboard of a security team.
+//!
+//! A [`Reporter`] is given the findings of every crate once its analysis is done. Tools running the analysis
+//! in process add their own to the [`Reporters`] of its [`Options`](crate::options::Options), and
+//! `--taint-report-url=<url>` adds an [`Http`] reporter, which posts the findings to `url` as JSON:
+//!
+//! ```json
+//! {"version": 1, "crate": "demo", "findings": [{ "fingerprint": "4f1c2b7d9e0a6c35", ... }], "choke_points": [...]}
+//! ```
+//!
+//! The `Authorization` header is taken from the `TAINT_REPORT_AUTHORIZATION` environment variable when it is set,
+//! like `Bearer <token>`, so that the secret can come from CI without showing on the command line.
+//! The endpoint is reached over plain HTTP, so the header is only sent to a loopback address, like a TLS proxy
+//! running next to the analysis. Sending it in cleartext to any other host is refused, unless
+//! `TAINT_REPORT_ALLOW_CLEARTEXT=1` says the network in between is trusted.
+
+use std::{
+    fmt,
+    io::{Read, Write},
+    net::{IpAddr, TcpStream},
+    sync::Arc,
+    time::Duration,
+};
+
+use serde_json::Value;
+
+use crate::{error::Error, report::Report};
+
+/// The environment variable holding the `Authorization` header of the [`Http`] reporter.
+pub const AUTHORIZATION: &str = "TAINT_REPORT_AUTHORIZATION";
+
+/// The environment variable which, set to `1`, lets the [`Http`] reporter send the `Authorization` header
+/// in cleartext to a host other than a loopback address.
+pub const ALLOW_CLEARTEXT: &str = "TAINT_REPORT_ALLOW_CLEARTEXT";
+
+/// How long the [`Http`] reporter waits on the endpoint before giving up.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Receives the findings of each crate analyzed.
+pub trait Reporter: Send + Sync {
+    /// What the findings are sent to, for error messages.
+    fn name(&self) -> String;
+
+    /// Sends `report`, the findings of `krate`.
+    fn send(&self, krate: &str, report: &Report) -> Result<(), Error>;
+}
+
+/// The reporters of a run, which clones share.
+#[derive(Default, Clone)]
+pub struct Reporters(pub Vec<Arc<dyn Reporter>>);
+
+impl fmt::Debug for Reporters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|reporter| reporter.name()))
+            .finish()
+    }
+}
+
+/// Posts the findings as JSON to an `http://` endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Http {
+    /// The host, without the brackets of an IPv6 address.
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Http {
+    /// The reporter posting to `url`, like `http://dashboard.internal:8080/api/findings`.
+    /// Endpoints behind TLS need a proxy, as the analysis does not bundle a TLS implementation.
+    pub fn new(url: &str) -> Result<Http, String> {
+        let Some(rest) = url.strip_prefix("http://") else {
+            return Err(format!("expected an `http://` URL, found `{}`", url));
+        };
+        // The path goes into the request line as it is, which a space or a line break would end early.
+        if let Some(c) = rest.chars().find(|c| matches!(c, ' ' | '\r' | '\n')) {
+            return Err(format!("expected a URL without {:?}, found {:?}", c, url));
+        }
+        let (authority, path) = match rest.find('/') {
+            Some(slash) => rest.split_at(slash),
+            None => (rest, "/"),
+        };
+        // An IPv6 address is written in brackets, like `[::1]:8080`, as its colons would read as a port.
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(bracketed) => match bracketed.split_once(']') {
+                Some((host, "")) => (host, None),
+                Some((host, port)) => (host, Some(port.strip_prefix(':').unwrap_or(port))),
+                None => return Err(format!("expected a `]` after the host in `{}`", url)),
+            },
+            None => match authority.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            },
+        };
+        let port = match port {
+            Some(port) => port
+                .parse()
+                .map_err(|_| format!("expected a port, found `{}`", port))?,
+            None => 80,
+        };
+        if host.is_empty() {
+            return Err(format!("expected a host in `{}`", url));
+        }
+        Ok(Http {
+            host: host.to_owned(),
+            port,
+            path: path.to_owned(),
+        })
+    }
+
+    /// The host as a URL or a `Host` header writes it, with an IPv6 address in brackets.
+    fn authority(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+
+    /// Whether the host is this machine, which the request does not leave.
+    fn is_loopback(&self) -> bool {
+        self.host.eq_ignore_ascii_case("localhost")
+            || self
+                .host
+                .parse::<IpAddr>()
+                .map_or(false, |ip| ip.is_loopback())
+    }
+
+    /// The `Authorization` header to send, `authorization`, unless it would go in cleartext to another machine
+    /// and that is not `allowed`, or it holds a line break, which would end the header early.
+    fn checked_authorization(
+        &self,
+        authorization: Option<String>,
+        allowed: bool,
+    ) -> Result<Option<String>, Error> {
+        if authorization
+            .as_deref()
+            .map_or(false, |value| value.contains(['\r', '\n']))
+        {
+            return Err(Error::format(
+                "authorization",
+                format!("`{}` must hold a single line", AUTHORIZATION),
+            ));
+        }
+        if authorization.is_none() || allowed || self.is_loopback() {
+            return Ok(authorization);
+        }
+        Err(Error::format(
+            "authorization",
+            format!(
+                "`{}` is not sent in cleartext to `{}`, which is not a loopback address; post through a TLS proxy on this machine, or set `{}=1` if the network in between is trusted",
+                AUTHORIZATION,
+                self.name(),
+                ALLOW_CLEARTEXT
+            ),
+        ))
+    }
+
+    /// The request posting `body`, with `authorization` as its `Authorization` header if given.
+    fn request(&self, body: &str, authorization: Option<&str>) -> String {
+        let mut request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.path,
+            self.authority(),
+            body.len()
+        );
+        if let Some(authorization) = authorization {
+            request.push_str(&format!("Authorization: {}\r\n", authorization));
+        }
+        request.push_str("\r\n");
+        request.push_str(body);
+        request
+    }
+}
+
+impl Reporter for Http {
+    fn name(&self) -> String {
+        format!("http://{}{}", self.authority(), self.path)
+    }
+
+    fn send(&self, krate: &str, report: &Report) -> Result<(), Error> {
+        let mut body: Value = serde_json::from_str(&report.to_json()).expect("reports are JSON");
+        body["crate"] = Value::from(krate);
+        let authorization = self.checked_authorization(
+            std::env::var(AUTHORIZATION).ok(),
+            std::env::var(ALLOW_CLEARTEXT).map_or(false, |allowed| allowed == "1"),
+        )?;
+        let request = self.request(&body.to_string(), authorization.as_deref());
+
+        let io = |e| Error::io(self.name(), e);
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port)).map_err(io)?;
+        stream.set_read_timeout(Some(TIMEOUT)).map_err(io)?;
+        stream.set_write_timeout(Some(TIMEOUT)).map_err(io)?;
+        stream.write_all(request.as_bytes()).map_err(io)?;
+        // Only the status line is read as text: the body may be in any encoding.
+        let mut response = vec![];
+        stream.read_to_end(&mut response).map_err(io)?;
+
+        let line = response
+            .split(|&byte| byte == b'\n')
+            .next()
+            .unwrap_or_default();
+        let status = String::from_utf8_lossy(line);
+        let status = status.trim_end();
+        match status.split(' ').nth(1) {
+            Some(code) if code.starts_with('2') => Ok(()),
+            _ => Err(Error::format(
+                "response",
+                format!("`{}` answered `{}`", self.name(), status),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn urls_give_the_host_port_and_path() {
+        let http = Http::new("http://dashboard.internal:8080/api/findings").unwrap();
+        assert_eq!(http.name(), "http://dashboard.internal:8080/api/findings");
+        assert_eq!(
+            Http::new("http://localhost").unwrap().name(),
+            "http://localhost:80/"
+        );
+        assert!(Http::new("https://dashboard.internal").is_err());
+        assert!(Http::new("http://localhost:http/").is_err());
+    }
+
+    #[test]
+    fn urls_cannot_break_the_request_line() {
+        for url in [
+            "http://localhost/findings HTTP/1.0",
+            "http://localhost/findings\r\nX-Injected: 1",
+            "http://localhost/findings\nX-Injected: 1",
+            "http://local\rhost/",
+        ] {
+            assert!(Http::new(url).is_err(), "{:?} is accepted", url);
+        }
+        assert!(Http::new("http://localhost/find%20ings").is_ok());
+    }
+
+    #[test]
+    fn ipv6_hosts_are_written_in_brackets() {
+        let http = Http::new("http://[::1]:8080/x").unwrap();
+        assert_eq!(http.host, "::1");
+        assert_eq!(http.port, 8080);
+        assert_eq!(http.name(), "http://[::1]:8080/x");
+        assert!(http
+            .request("{}", None)
+            .contains("\r\nHost: [::1]:8080\r\n"));
+        assert_eq!(Http::new("http://[fe80::1]").unwrap().port, 80);
+        assert!(Http::new("http://[::1:8080/x").is_err());
+    }
+
+    #[test]
+    fn the_authorization_only_goes_in_cleartext_to_the_loopback() {
+        let token = || Some("Bearer 4f1c".to_owned());
+        for url in [
+            "http://localhost/",
+            "http://127.0.0.1:8080/",
+            "http://[::1]/",
+        ] {
+            let http = Http::new(url).unwrap();
+            assert_eq!(http.checked_authorization(token(), false), Ok(token()));
+        }
+        let remote = Http::new("http://dashboard.internal/findings").unwrap();
+        let error = remote.checked_authorization(token(), false).unwrap_err();
+        assert!(error.to_string().contains("is not sent in cleartext"));
+        assert_eq!(remote.checked_authorization(token(), true), Ok(token()));
+        assert_eq!(remote.checked_authorization(None, false), Ok(None));
+    }
+
+    #[test]
+    fn the_authorization_holds_a_single_line() {
+        let http = Http::new("http://localhost/").unwrap();
+        for token in [
+            "Bearer 4f1c\r\nX-Injected: 1",
+            "Bearer 4f1c\n",
+            "Bearer\r4f1c",
+        ] {
+            let error = http
+                .checked_authorization(Some(token.to_owned()), true)
+                .unwrap_err();
+            assert!(error.to_string().contains("must hold a single line"));
+        }
+    }
+
+    /// Answers a single request with `response` on a port of its own, and returns the port
+    /// and what it was sent.
+    fn serve(response: &'static [u8]) -> (u16, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![];
+            let mut buffer = [0; 1024];
+            while !String::from_utf8_lossy(&request).ends_with('}') {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream.write_all(response).unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (port, server)
+    }
+
+    #[test]
+    fn findings_are_posted_as_json() {
+        let (port, server) = serve(b"HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n");
+        let http = Http::new(&format!("http://127.0.0.1:{}/findings", port)).unwrap();
+        http.send("demo", &Report::default()).unwrap();
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /findings HTTP/1.1\r\n"));
+        assert!(request.contains("Content-Type: application/json\r\n"));
+        let body: Value = serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(body["crate"], "demo");
+        assert_eq!(body["findings"], Value::Array(vec![]));
+    }
+
+    #[test]
+    fn requests_carry_the_authorization_given() {
+        let http = Http::new("http://localhost:8080/findings").unwrap();
+        let request = http.request("{}", Some("Bearer 4f1c"));
+        assert!(request.contains("\r\nAuthorization: Bearer 4f1c\r\n"));
+        assert!(request.ends_with("\r\n\r\n{}"));
+        assert!(!http.request("{}", None).contains("Authorization"));
+    }
+
+    #[test]
+    fn responses_need_not_be_text() {
+        let (port, server) = serve(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n\xff\xfe");
+        let http = Http::new(&format!("http://127.0.0.1:{}/", port)).unwrap();
+        assert_eq!(http.send("demo", &Report::default()), Ok(()));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn failed_responses_are_errors() {
+        let (port, server) = serve(b"HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\n\r\n");
+        let http = Http::new(&format!("http://127.0.0.1:{}/", port)).unwrap();
+        let error = http.send("demo", &Report::default()).unwrap_err();
+        server.join().unwrap();
+        assert!(error
+            .to_string()
+            .contains("answered `HTTP/1.1 401 Unauthorized`"));
+    }
+}
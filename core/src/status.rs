@@ -0,0 +1,221 @@
+//! The outcome of a run, for CI status checks.
+//!
+//! With `--taint-status-line=yes`, the driver prints a status line to stderr once a crate is analyzed,
+//! which scripts can match without parsing diagnostics:
+//!
+//! ```text
+//! taint: 2 errors, 1 warnings, 3 entry points, 0.42 seconds
+//! ```
+//!
+//! With `--taint-junit=<file>`, it also writes the outcome as a JUnit XML report, which CI systems
+//! render as test results: each finding is a test case, failed if it was reported as an error, and
+//! the errors which are not about a finding, like invalid annotations, fail a test case of their own.
+
+use std::{fmt, fs, path::Path};
+
+use crate::{error::Error, report::Finding};
+
+/// What a run reported, and what it went through to report it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Status {
+    /// The errors reported, findings and invalid annotations alike.
+    pub errors: usize,
+    pub warnings: usize,
+    /// The entry points analyzed.
+    pub entry_points: usize,
+    pub seconds: f64,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "taint: {} errors, {} warnings, {} entry points, {:.2} seconds",
+            self.errors, self.warnings, self.entry_points, self.seconds
+        )
+    }
+}
+
+/// A finding of the run, and whether it was reported as an error rather than a warning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reported {
+    pub finding: Finding,
+    pub error: bool,
+}
+
+impl Status {
+    /// The JUnit XML report of the run of the analysis on `krate`, which reported `findings`.
+    pub fn junit(&self, krate: &str, findings: &[Reported]) -> String {
+        let failed = findings.iter().filter(|reported| reported.error).count();
+        // Errors are counted once per diagnostic, so the others are those which are not about a finding.
+        let others = self.errors.saturating_sub(failed);
+        let tests = findings.len() + 1;
+        let failures = failed + usize::from(others > 0);
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites name=\"taint\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            tests, failures, self.seconds
+        ));
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            escape(krate),
+            tests,
+            failures,
+            self.seconds
+        ));
+        if others > 0 {
+            xml.push_str(&format!(
+                "    <testcase name=\"analysis\" classname=\"{}\">\n",
+                escape(krate)
+            ));
+            xml.push_str(&format!(
+                "      <failure message=\"{} errors are not about a finding, like invalid annotations; see the build log\"/>\n",
+                others
+            ));
+            xml.push_str("    </testcase>\n");
+        } else {
+            xml.push_str(&format!(
+                "    <testcase name=\"analysis\" classname=\"{}\"/>\n",
+                escape(krate)
+            ));
+        }
+        for Reported { finding, error } in findings {
+            let location = format!(
+                "{}:{}:{}",
+                finding.file, finding.start.line, finding.start.column
+            );
+            xml.push_str(&format!(
+                "    <testcase name=\"{} at {}\" classname=\"{}\" file=\"{}\" line=\"{}\">\n",
+                escape(&finding.sink),
+                escape(&location),
+                escape(&finding.function),
+                escape(&finding.file),
+                finding.start.line
+            ));
+            let mut text = format!("{}: {}", location, finding.message);
+            for context in &finding.context {
+                text.push_str(&format!("\n{}", context));
+            }
+            if *error {
+                xml.push_str(&format!(
+                    "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                    escape(&finding.code),
+                    escape(&finding.message),
+                    escape(&text)
+                ));
+            } else {
+                xml.push_str(&format!(
+                    "      <system-out>warning[{}]: {}</system-out>\n",
+                    escape(&finding.code),
+                    escape(&text)
+                ));
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+
+    pub fn write_junit(
+        &self,
+        path: impl AsRef<Path>,
+        krate: &str,
+        findings: &[Reported],
+    ) -> Result<(), Error> {
+        let path = path.as_ref();
+        fs::write(path, self.junit(krate, findings)).map_err(|e| Error::io(path, e))
+    }
+}
+
+/// `text` with the characters XML gives a meaning to replaced by their entities.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{query::Position, report::Confidence};
+
+    fn finding(code: &str, sink: &str) -> Finding {
+        Finding {
+            fingerprint: "0123456789abcdef".to_owned(),
+            code: code.to_owned(),
+            message: format!("function `{}` received tainted input", sink),
+            context: vec![],
+            function: "demo::main".to_owned(),
+            sink: sink.to_owned(),
+            label: "taint".to_owned(),
+            profiles: vec![],
+            family: None,
+            owner: None,
+            confidence: Confidence::certain(),
+            file: "src/main.rs".to_owned(),
+            start: Position { line: 5, column: 5 },
+            end: Position {
+                line: 5,
+                column: 20,
+            },
+        }
+    }
+
+    #[test]
+    fn the_status_line_counts_what_the_run_reported() {
+        let status = Status {
+            errors: 2,
+            warnings: 1,
+            entry_points: 3,
+            seconds: 0.4215,
+        };
+
+        assert_eq!(
+            status.to_string(),
+            "taint: 2 errors, 1 warnings, 3 entry points, 0.42 seconds"
+        );
+    }
+
+    #[test]
+    fn error_findings_and_other_errors_fail_test_cases() {
+        let status = Status {
+            errors: 2,
+            warnings: 1,
+            entry_points: 1,
+            seconds: 1.5,
+        };
+        let findings = [
+            Reported {
+                finding: finding("T0001", "demo::<Query>::run"),
+                error: true,
+            },
+            Reported {
+                finding: finding("T0006", "std::ptr::read"),
+                error: false,
+            },
+        ];
+        let xml = status.junit("demo", &findings);
+
+        assert!(xml.contains("<testsuite name=\"demo\" tests=\"3\" failures=\"2\" time=\"1.500\">"));
+        assert!(xml.contains("<failure message=\"1 errors are not about a finding"));
+        assert!(xml.contains(
+            "<testcase name=\"demo::&lt;Query&gt;::run at src/main.rs:5:5\" classname=\"demo::main\" file=\"src/main.rs\" line=\"5\">"
+        ));
+        assert!(xml.contains(
+            "<failure type=\"T0001\" message=\"function `demo::&lt;Query&gt;::run` received tainted input\">"
+        ));
+        assert!(xml.contains(
+            "<system-out>warning[T0006]: src/main.rs:5:5: function `std::ptr::read` received tainted input</system-out>"
+        ));
+    }
+}
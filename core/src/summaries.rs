@@ -0,0 +1,243 @@
+//! Function summaries in a form other tools can produce and consume.
+//!
+//! With `--taint-export-summaries=<file>`, the driver writes what every function it analyzed does
+//! to taint, and with `--taint-import-summaries=<file>` it uses such summaries instead of
+//! analyzing the functions they describe, like functions from dependencies or without a body.
+//!
+//! ```json
+//! {
+//!     "version": 1,
+//!     "summaries": [
+//!         {
+//!             "function": "demo::concat",
+//!             "returns": { "from": [0, 1], "labels": [] },
+//!             "args": [{ "from": [0], "labels": [] }, { "from": [1], "labels": [] }],
+//!             "points_into": []
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! A summary does not depend on the context a function is called in:
+//! its result carries the labels of the arguments listed in `from`, as well as `labels`,
+//! and so does each argument when the function returns.
+//!
+//! Summaries of functions from well-known crates are bundled as [packs](PACKS),
+//! and used whenever the crate they describe is a dependency.
+
+use std::{fs, path::Path, sync::OnceLock};
+
+use serde_json::{json, Value};
+
+use crate::error::Error;
+
+/// The version of the summaries format, bumped on incompatible changes.
+pub const VERSION: u64 = 1;
+
+/// Summaries bundled with the analysis, by the crate they describe.
+///
+/// They cover functions whose MIR is not available to the analysis,
+/// so that their callers do not lose the flows through them.
+pub const PACKS: &[(&str, &str)] = &[("std", include_str!("packs/std.json"))];
+
+/// Where the labels of a value come from.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Flow {
+    /// The indices of the arguments whose labels the value carries.
+    pub from: Vec<usize>,
+    /// The labels the value carries regardless of the arguments, like `taint`.
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSummary {
+    /// The path of the function, like `my_crate::handler`.
+    pub function: String,
+    pub returns: Flow,
+    /// The labels of each argument, including those of what it points to, when the function returns.
+    pub args: Vec<Flow>,
+    /// The arguments whose referents the returned value may point into.
+    pub points_into: Vec<usize>,
+}
+
+impl FunctionSummary {
+    /// The summary of a propagation rule for `function`, whose result carries the labels of the arguments
+    /// `result_from`, and which writes the labels of other arguments into some, as `(arg, from)`.
+    /// Arguments which are not written to keep their labels.
+    pub fn rule(function: &str, result_from: Vec<usize>, writes: &[(usize, Vec<usize>)]) -> Self {
+        let mut args: Vec<Flow> = vec![];
+        for (arg, from) in writes {
+            if args.len() <= *arg {
+                args.extend((args.len()..=*arg).map(|ix| Flow {
+                    from: vec![ix],
+                    labels: vec![],
+                }));
+            }
+            args[*arg].from.extend(from);
+        }
+        FunctionSummary {
+            function: function.to_owned(),
+            returns: Flow {
+                from: result_from,
+                labels: vec![],
+            },
+            args,
+            points_into: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Summaries {
+    pub summaries: Vec<FunctionSummary>,
+}
+
+impl Summaries {
+    pub fn read(path: impl AsRef<Path>) -> Result<Summaries, Error> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        Summaries::from_json(&text)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        fs::write(path, self.to_json()).map_err(|e| Error::io(path, e))
+    }
+
+    /// The bundled pack of summaries for `krate`, if there is one.
+    /// Packs are only parsed once per process, however many crates it analyzes.
+    pub fn pack(krate: &str) -> Option<Result<Summaries, Error>> {
+        static PARSED: OnceLock<Vec<Result<Summaries, Error>>> = OnceLock::new();
+        let parsed = PARSED.get_or_init(|| {
+            PACKS
+                .iter()
+                .map(|(_, text)| Summaries::from_json(text))
+                .collect()
+        });
+        let ix = PACKS.iter().position(|(name, _)| *name == krate)?;
+        Some(parsed[ix].clone())
+    }
+
+    /// The summary of the function at `path`.
+    pub fn get(&self, path: &str) -> Option<&FunctionSummary> {
+        self.summaries
+            .iter()
+            .find(|summary| summary.function == path)
+    }
+
+    pub fn to_json(&self) -> String {
+        let flow = |flow: &Flow| json!({ "from": flow.from, "labels": flow.labels });
+        let summaries = self
+            .summaries
+            .iter()
+            .map(|summary| {
+                json!({
+                    "function": summary.function,
+                    "returns": flow(&summary.returns),
+                    "args": summary.args.iter().map(flow).collect::<Vec<_>>(),
+                    "points_into": summary.points_into,
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({ "version": VERSION, "summaries": summaries }).to_string()
+    }
+
+    pub fn from_json(text: &str) -> Result<Summaries, Error> {
+        let value: Value = serde_json::from_str(text).map_err(|e| Error::format("summaries", e))?;
+        if value["version"] != VERSION {
+            return Err(Error::format(
+                "summaries",
+                format!(
+                    "unsupported version {}, expected {}",
+                    value["version"], VERSION
+                ),
+            ));
+        }
+
+        let summaries = value["summaries"]
+            .as_array()
+            .ok_or_else(|| Error::format("summaries", "missing `summaries`"))?
+            .iter()
+            .map(summary_from_json)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::format("summaries", "malformed summary"))?;
+        Ok(Summaries { summaries })
+    }
+}
+
+fn summary_from_json(value: &Value) -> Option<FunctionSummary> {
+    Some(FunctionSummary {
+        function: value["function"].as_str()?.to_owned(),
+        returns: flow_from_json(&value["returns"])?,
+        args: value["args"]
+            .as_array()?
+            .iter()
+            .map(flow_from_json)
+            .collect::<Option<_>>()?,
+        points_into: indices(&value["points_into"])?,
+    })
+}
+
+fn flow_from_json(value: &Value) -> Option<Flow> {
+    Some(Flow {
+        from: indices(&value["from"])?,
+        labels: value["labels"]
+            .as_array()?
+            .iter()
+            .map(|label| label.as_str().map(str::to_owned))
+            .collect::<Option<_>>()?,
+    })
+}
+
+fn indices(value: &Value) -> Option<Vec<usize>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|ix| ix.as_u64().map(|ix| ix as usize))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trips() {
+        let summaries = Summaries {
+            summaries: vec![FunctionSummary {
+                function: "demo::read_header".to_owned(),
+                returns: Flow {
+                    from: vec![0],
+                    labels: vec!["taint".to_owned()],
+                },
+                args: vec![Flow {
+                    from: vec![0],
+                    labels: vec![],
+                }],
+                points_into: vec![0],
+            }],
+        };
+
+        assert_eq!(Summaries::from_json(&summaries.to_json()), Ok(summaries));
+    }
+
+    #[test]
+    fn packs_are_valid() {
+        for (krate, _) in PACKS {
+            assert!(matches!(Summaries::pack(krate), Some(Ok(_))), "{}", krate);
+        }
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let summaries = Summaries::from_json(r#"{ "version": 2, "summaries": [] }"#);
+
+        assert_eq!(
+            summaries,
+            Err(Error::format(
+                "summaries",
+                "unsupported version 2, expected 1"
+            ))
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! Starting an analysis from what the previous one found.
+//!
+//! Tools which run the analysis in process, like an editor server or a watch mode, analyze the same
+//! crate again and again as files change. They give every run the same [`WarmStart`] through its
+//! [`Options`](crate::options::Options), and each run leaves in it the summaries of the functions it
+//! analyzed, for the next one to reuse those which are still up to date instead of analyzing them again.
+//! What the analysis leaves is its own business, so it is only a value of some type it can get back.
+
+use std::{
+    any::Any,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+/// Shared between the runs of a tool, so clones carry state over to the same runs.
+#[derive(Default, Clone)]
+pub struct WarmStart {
+    left: Arc<Mutex<Option<Box<dyn Any + Send>>>>,
+}
+
+impl WarmStart {
+    /// Takes what the previous run left, if it left a `T`. Anything else it left is dropped.
+    pub fn take<T: Any + Send>(&self) -> Option<T> {
+        let left = self.left.lock().unwrap().take()?;
+        left.downcast().ok().map(|left| *left)
+    }
+
+    /// Leaves `state` for the next run, in place of anything left before.
+    pub fn leave<T: Any + Send>(&self, state: T) {
+        *self.left.lock().unwrap() = Some(Box::new(state));
+    }
+}
+
+impl fmt::Debug for WarmStart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let left = self.left.lock().unwrap().is_some();
+        f.debug_struct("WarmStart").field("left", &left).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_take_what_the_previous_one_left() {
+        let warm_start = WarmStart::default();
+        let next_run = warm_start.clone();
+        assert_eq!(next_run.take::<Vec<u32>>(), None);
+
+        warm_start.leave(vec![1u32, 2]);
+        assert_eq!(next_run.take::<String>(), None);
+        warm_start.leave(vec![1u32, 2]);
+        assert_eq!(next_run.take::<Vec<u32>>(), Some(vec![1, 2]));
+        assert_eq!(next_run.take::<Vec<u32>>(), None);
+    }
+}
@@ -0,0 +1,38 @@
+//! A backup job reading its settings from the environment of whoever runs it.
+//!
+//! Run with `TAINT_SINK_PACKS=process cargo taint`: the `process` sink pack reports the settings passed
+//! to the commands the job spawns. The config only tracks strings, bytes and types of the crate, so the
+//! number of a setting which only decides how many times to retry is not tainted.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::process::Command;
+
+fn main() {
+    let target = setting("BACKUP_TARGET");
+    let mut tar = Command::new("tar");
+    tar.arg("-czf").arg(&target).arg("/srv");
+    let _ = tar.status();
+
+    let compressor = setting("BACKUP_COMPRESSOR");
+    let _ = Command::new(allowed(&compressor)).arg("/srv").status();
+
+    let threads: u32 = setting("BACKUP_THREADS").parse().unwrap_or(1);
+    let _ = Command::new("zstd").arg(format!("-T{}", threads)).status();
+}
+
+#[taint::source]
+fn setting(name: &str) -> String {
+    std::env::var(name).unwrap_or_default()
+}
+
+/// The compressor named `name` if it is one we ship, or `gzip`.
+#[taint::sanitizer]
+fn allowed(name: &str) -> &'static str {
+    ["gzip", "xz", "zstd"]
+        .iter()
+        .copied()
+        .find(|known| *known == name)
+        .unwrap_or("gzip")
+}
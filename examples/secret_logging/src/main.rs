@@ -0,0 +1,42 @@
+//! A client logging what it does, which must never log its credentials.
+//!
+//! The config puts the `api-key` label on the key compiled into the client, and declares it and passwords
+//! as kinds of `secret`, which the log refuses. A fingerprint of a secret reveals nothing of it, which
+//! the config tells with a propagation rule whose result carries none of the labels of its argument.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+static API_KEY: &str = "0123456789abcdef";
+
+fn main() {
+    log(&format!("connecting with key {}", API_KEY));
+    log(&format!("connecting with key {}", redact(API_KEY)));
+    log(&format!("connecting with key {:x}", fingerprint(API_KEY)));
+
+    let password = prompt_password();
+    log(&format!("logging in with {}", password));
+    log(&format!("logging in with {}", redact(&password)));
+}
+
+#[taint::source(label = "password")]
+fn prompt_password() -> String {
+    String::from("hunter2")
+}
+
+#[taint::sink(label = "secret")]
+fn log(line: &str) {
+    eprintln!("{}", line);
+}
+
+#[taint::sanitizer]
+fn redact(secret: &str) -> String {
+    "*".repeat(secret.len())
+}
+
+/// A hash of `secret`, which the log can tell apart from others without revealing it.
+fn fingerprint(secret: &str) -> u64 {
+    secret.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
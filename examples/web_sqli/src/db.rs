@@ -0,0 +1,12 @@
+#[taint::sink(label = "user-input")]
+pub fn execute(sql: &str) {
+    println!("{}", sql);
+}
+
+/// Quotes `text` for the language `mode` names, like `"sql"` or `"html"`.
+pub fn quote(text: &str, mode: &str) -> String {
+    match mode {
+        "sql" => format!("'{}'", text.replace('\'', "''")),
+        _ => text.replace('<', "&lt;"),
+    }
+}
@@ -0,0 +1,25 @@
+#[derive(Default)]
+pub struct Request {
+    query: String,
+    headers: Vec<(String, String)>,
+}
+
+impl Request {
+    #[taint::source(label = "query")]
+    pub fn param(&self, name: &str) -> String {
+        self.query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+            .unwrap_or_default()
+            .to_owned()
+    }
+
+    #[taint::source(label = "header")]
+    pub fn header(&self, name: &str) -> String {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+            .unwrap_or_default()
+    }
+}
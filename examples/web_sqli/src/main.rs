@@ -0,0 +1,29 @@
+//! An HTTP handler building SQL queries from the request.
+//!
+//! The query string and the headers carry labels the config declares as kinds of `user-input`,
+//! which is what the database refuses. Quoting for SQL, and only for SQL, removes it.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+mod db;
+mod http;
+
+use http::Request;
+
+fn main() {
+    let request = Request::default();
+    handle(&request);
+}
+
+fn handle(request: &Request) {
+    let name = request.param("name");
+    db::execute(&format!("SELECT * FROM users WHERE name = '{}'", name));
+
+    let quoted = db::quote(&name, "sql");
+    db::execute(&format!("SELECT * FROM users WHERE name = {}", quoted));
+
+    let agent = request.header("User-Agent");
+    let logged = db::quote(&agent, "html");
+    db::execute(&format!("INSERT INTO visits VALUES ({})", logged));
+}
@@ -0,0 +1,11 @@
+//! Analyzes the program built from each input, and fails if the analysis panics,
+//! moves down the lattice, or finds other than it did with maps seeded anew.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use taint_fuzz::{check, Program};
+
+fuzz_target!(|program: Program| {
+    let _ = check(&program.render());
+});
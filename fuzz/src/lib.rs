@@ -0,0 +1,378 @@
+//! Small programs for fuzzing the analysis, built from a syntax constrained so that every one compiles.
+//!
+//! A program moves data between a few `String` variables, some of which start out tainted, through the shapes
+//! of MIR the transfer functions handle differently: calls to functions, generic functions, closures and trait
+//! objects, recursion, fields, references, moves and swaps, branches, loops and matches. It calls a sink and
+//! a sanitizer on some of them along the way. [`check`] analyzes the program with `--taint-debug-invariants`,
+//! which panics if a state moves down the lattice, and `--taint-debug-hash-order`, which panics if the analysis
+//! finds other than it did with its maps seeded anew, and fails if the driver panicked.
+//! Calls to functions without MIR follow `--taint-unknown-calls=propagate`, so that they do not clear
+//! the taint the checks are about.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command},
+    sync::OnceLock,
+};
+
+use arbitrary::Arbitrary;
+
+/// The number of variables of a program.
+const VARIABLES: u8 = 4;
+/// How many levels deep blocks are indented at most, below which their statements are left out.
+const MAX_DEPTH: usize = 4;
+/// The most statements a program has, beyond which the others are left out.
+const MAX_STATEMENTS: usize = 64;
+
+/// The functions the statements of `main` call.
+const ITEMS: &str = r#"
+#[taint::source]
+fn source() -> String {
+    String::from("input")
+}
+
+#[taint::sink]
+fn sink(_: &str) {}
+
+#[taint::sanitizer]
+fn sanitize(value: String) -> String {
+    value
+}
+
+fn relay(value: &str) -> String {
+    value.to_owned()
+}
+
+fn identity<T>(value: T) -> T {
+    value
+}
+
+fn recurse(value: String, depth: u8) -> String {
+    if depth == 0 {
+        value
+    } else {
+        recurse(format!("{}.", value), depth - 1)
+    }
+}
+
+struct Holder {
+    value: String,
+    other: String,
+}
+"#;
+
+/// One of the variables of a program, `v0` to `v3`.
+#[derive(Debug, Clone, Copy, Arbitrary)]
+pub struct Var(u8);
+
+impl Var {
+    fn name(self) -> String {
+        format!("v{}", self.0 % VARIABLES)
+    }
+
+    /// A copy of the value of the variable, read through a reference to it, which leaves it usable.
+    fn copied(self) -> String {
+        format!("{}.as_str().to_owned()", self.name())
+    }
+}
+
+/// A value of type `String`, computed from the variables.
+#[derive(Debug, Clone, Arbitrary)]
+pub enum Expr {
+    Source,
+    Constant,
+    Var(Var),
+    Concat(Var, Var),
+    Sanitize(Var),
+    Relay(Var),
+    Identity(Var),
+    Closure(Var),
+    TraitObject(Var),
+    Recurse(Var, u8),
+    Field(Var, Var),
+    Joined(Var, Var),
+    Or(Var, Var),
+    Reversed(Var),
+    Boxed(Var),
+}
+
+impl Expr {
+    fn render(&self) -> String {
+        match *self {
+            Expr::Source => "source()".to_owned(),
+            Expr::Constant => "String::from(\"constant\")".to_owned(),
+            Expr::Var(var) => var.copied(),
+            Expr::Concat(a, b) => format!("format!(\"{{}}{{}}\", {}, {})", a.name(), b.name()),
+            Expr::Sanitize(var) => format!("sanitize({})", var.copied()),
+            Expr::Relay(var) => format!("relay(&{})", var.name()),
+            Expr::Identity(var) => format!("identity({})", var.copied()),
+            Expr::Closure(var) => format!("(|value: &str| value.to_uppercase())(&{})", var.name()),
+            Expr::TraitObject(var) => format!(
+                "(Box::new(relay) as Box<dyn Fn(&str) -> String>)(&{})",
+                var.name()
+            ),
+            Expr::Recurse(var, depth) => format!("recurse({}, {})", var.copied(), depth % 4),
+            Expr::Field(a, b) => format!(
+                "Holder {{ value: {}, other: {} }}.other",
+                a.copied(),
+                b.copied()
+            ),
+            Expr::Joined(a, b) => format!("vec![{}, {}].concat()", a.copied(), b.copied()),
+            Expr::Or(a, b) => format!(
+                "Some({}).filter(|value| !value.is_empty()).unwrap_or({})",
+                a.copied(),
+                b.copied()
+            ),
+            Expr::Reversed(var) => format!("{}.chars().rev().collect::<String>()", var.name()),
+            Expr::Boxed(var) => format!("*Box::new({})", var.copied()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Arbitrary)]
+pub enum Stmt {
+    Assign(Var, Expr),
+    Push(Var, Var),
+    Swap(Var, Var),
+    Take(Var, Var),
+    Sink(Var),
+    SinkRef(Var),
+    If(Var, Vec<Stmt>, Vec<Stmt>),
+    Loop(Var, Vec<Stmt>),
+    Match(Var, Vec<Stmt>, Vec<Stmt>),
+}
+
+/// A program for the analysis: its `main` runs the statements, with some of the variables tainted at first.
+#[derive(Debug, Clone, Arbitrary)]
+pub struct Program {
+    tainted: [bool; VARIABLES as usize],
+    statements: Vec<Stmt>,
+}
+
+impl Program {
+    /// The source of the program.
+    pub fn render(&self) -> String {
+        let mut source = String::from(
+            "#![feature(register_tool)]\n#![register_tool(taint)]\n#![allow(unused)]\n\nfn main() {\n",
+        );
+        for (ix, &tainted) in self.tainted.iter().enumerate() {
+            let init = if tainted { "source()" } else { "String::new()" };
+            source.push_str(&format!("    let mut v{} = {};\n", ix, init));
+        }
+        let mut budget = MAX_STATEMENTS;
+        render_block(&self.statements, 1, &mut budget, &mut source);
+        source.push_str("}\n");
+        source.push_str(ITEMS);
+        source
+    }
+}
+
+/// Renders `statements` at `depth`, as long as `budget` allows.
+fn render_block(statements: &[Stmt], depth: usize, budget: &mut usize, source: &mut String) {
+    let indent = "    ".repeat(depth);
+    for statement in statements {
+        if *budget == 0 {
+            return;
+        }
+        *budget -= 1;
+        // The statements of a block, `levels` deeper than the statement it is in.
+        let nested = |body: &[Stmt], levels: usize, budget: &mut usize| {
+            let mut block = String::new();
+            if depth < MAX_DEPTH {
+                render_block(body, depth + levels, budget, &mut block);
+            }
+            block
+        };
+        let line = match statement {
+            Stmt::Assign(var, expr) => format!("{} = {};\n", var.name(), expr.render()),
+            // A variable cannot be borrowed mutably and shared at once, so what is pushed is copied first.
+            Stmt::Push(to, from) => format!(
+                "{{ let pushed = {}; {}.push_str(&pushed); }}\n",
+                from.copied(),
+                to.name()
+            ),
+            Stmt::Swap(a, b) if a.name() == b.name() => continue,
+            Stmt::Swap(a, b) => format!("std::mem::swap(&mut {}, &mut {});\n", a.name(), b.name()),
+            Stmt::Take(to, from) if to.name() == from.name() => continue,
+            Stmt::Take(to, from) => {
+                format!("{} = std::mem::take(&mut {});\n", to.name(), from.name())
+            }
+            Stmt::Sink(var) => format!("sink(&{});\n", var.name()),
+            Stmt::SinkRef(var) => {
+                format!("{{ let view = {}.as_str(); sink(view); }}\n", var.name())
+            }
+            Stmt::If(var, then, otherwise) => {
+                let then = nested(then, 1, budget);
+                let otherwise = nested(otherwise, 1, budget);
+                format!(
+                    "if {}.len() > 3 {{\n{}{}}} else {{\n{}{}}}\n",
+                    var.name(),
+                    then,
+                    indent,
+                    otherwise,
+                    indent
+                )
+            }
+            Stmt::Loop(var, body) => {
+                let body = nested(body, 1, budget);
+                format!(
+                    "for _ in 0..{}.len() % 3 {{\n{}{}}}\n",
+                    var.name(),
+                    body,
+                    indent
+                )
+            }
+            Stmt::Match(var, some, none) => {
+                let some = nested(some, 2, budget);
+                let none = nested(none, 2, budget);
+                format!(
+                    "match {}.pop() {{\n{}    Some(_) => {{\n{}{}    }}\n{}    None => {{\n{}{}    }}\n{}}}\n",
+                    var.name(),
+                    indent,
+                    some,
+                    indent,
+                    indent,
+                    none,
+                    indent,
+                    indent
+                )
+            }
+        };
+        source.push_str(&indent);
+        source.push_str(&line);
+    }
+}
+
+/// The workspace of the analysis, whose toolchain the driver was built with.
+fn workspace() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+}
+
+/// The driver: `$TAINT` if it is set, like for `cargo taint`, or the one built in the workspace.
+fn driver() -> Command {
+    let path = match env::var_os("TAINT") {
+        Some(path) => path.into(),
+        None => workspace().join("target/debug/taint"),
+    };
+    // The driver links to the compiler of its toolchain, which cargo only finds for the binaries it runs itself.
+    static LIBRARIES: OnceLock<PathBuf> = OnceLock::new();
+    let libraries = LIBRARIES.get_or_init(|| {
+        // The toolchain of the workspace, rather than the one this crate is built with.
+        let sysroot = Command::new("rustc")
+            .args(["--print", "sysroot"])
+            .current_dir(workspace())
+            .env_remove("RUSTUP_TOOLCHAIN")
+            .output()
+            .expect("rustc runs");
+        PathBuf::from(String::from_utf8_lossy(&sysroot.stdout).trim()).join("lib")
+    });
+    let mut paths = vec![libraries.clone()];
+    paths.extend(
+        env::var_os("LD_LIBRARY_PATH")
+            .iter()
+            .flat_map(env::split_paths),
+    );
+    let mut driver = Command::new(path);
+    driver.env(
+        "LD_LIBRARY_PATH",
+        env::join_paths(paths).expect("library paths can be joined"),
+    );
+    driver
+}
+
+/// Analyzes `program` and panics if the driver panicked, which the debug options have it do
+/// once the analysis breaks an invariant, or if the program did not compile, which is a bug of this crate.
+/// Findings are fine, and returns whether there were any.
+pub fn check(program: &str) -> bool {
+    let dir = env::temp_dir().join(format!("taint-fuzz-{}", process::id()));
+    fs::create_dir_all(&dir).expect("the directory of the program can be created");
+    let path = dir.join("program.rs");
+    fs::write(&path, program).expect("the program can be written");
+    let output = driver()
+        .arg(&path)
+        .args(["--edition", "2018", "--out-dir"])
+        .arg(&dir)
+        .args([
+            "--taint-debug-invariants",
+            "--taint-debug-hash-order=2",
+            "--taint-unknown-calls=propagate",
+        ])
+        .output()
+        .expect("the driver runs");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains("error[E"),
+        "the program generated does not compile:\n{}\n{}",
+        program,
+        stderr
+    );
+    // Findings make the driver exit with 1, and panics with 101, or a signal for those which abort.
+    assert!(
+        matches!(output.status.code(), Some(0 | 1)),
+        "the analysis failed on:\n{}\n{}",
+        program,
+        stderr
+    );
+    output.status.code() == Some(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use super::*;
+
+    #[test]
+    fn programs_built_from_any_bytes_compile_and_are_analyzed() {
+        // Bytes from a xorshift generator, so that the programs are the same from run to run.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..20 {
+            let bytes = (0..512)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state as u8
+                })
+                .collect::<Vec<_>>();
+            let program = Program::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            check(&program.render());
+        }
+    }
+
+    #[test]
+    fn values_read_from_tainted_variables_are_tainted() {
+        // The invariants are only worth checking on programs whose tainted data flows on, whatever it goes through.
+        let (tainted, clean) = (Var(0), Var(1));
+        let exprs = [
+            Expr::Var(tainted),
+            Expr::Concat(clean, tainted),
+            Expr::Relay(tainted),
+            Expr::Identity(tainted),
+            Expr::Closure(tainted),
+            Expr::TraitObject(tainted),
+            Expr::Recurse(tainted, 2),
+            Expr::Field(clean, tainted),
+            Expr::Joined(clean, tainted),
+            Expr::Or(tainted, clean),
+            // `Expr::Reversed` is left out: decoding chars goes through numeric casts, which the analysis
+            // does not follow.
+            Expr::Boxed(tainted),
+        ];
+        for expr in exprs {
+            let program = Program {
+                tainted: [true, false, false, false],
+                statements: vec![Stmt::Assign(Var(2), expr.clone()), Stmt::Sink(Var(2))],
+            };
+            assert!(check(&program.render()), "{:?} is not tainted", expr);
+        }
+        for expr in [Expr::Constant, Expr::Sanitize(tainted)] {
+            let program = Program {
+                tainted: [true, false, false, false],
+                statements: vec![Stmt::Assign(Var(2), expr.clone()), Stmt::Sink(Var(2))],
+            };
+            assert!(!check(&program.render()), "{:?} is tainted", expr);
+        }
+    }
+}
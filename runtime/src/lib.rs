@@ -0,0 +1,76 @@
+//! Types for programs checked by the taint analysis, which it knows the meaning of without annotations.
+//!
+//! `Sensitive<T>` holds a secret, like a password or an API token. The analysis labels the values
+//! wrapped in it `secret`, so that every sink reports a `Sensitive` it receives, and `expose` is the only way
+//! to get the secret back without the label. Formatting a `Sensitive` with `Display` or `Debug`
+//! is always reported, and prints `[redacted]` in case it runs anyway.
+//!
+//! `barrier(value)` returns `value` without the labels it carries, to silence a flow which the analysis cannot tell
+//! is harmless, like a hash of tainted data in a hot path. Unlike a sanitizer, it does not claim to make the data safe,
+//! and audits list every call to it on its own.
+//!
+//! This crate builds on stable Rust and has no dependencies, so programs can depend on it
+//! whether or not they are being analyzed.
+
+use std::fmt;
+
+/// A secret, which is only read with `expose`.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Sensitive<T> {
+    secret: T,
+}
+
+impl<T> Sensitive<T> {
+    pub fn new(secret: T) -> Self {
+        Sensitive { secret }
+    }
+
+    /// The secret, where it is meant to be used, like in the header of a request.
+    pub fn expose(&self) -> &T {
+        &self.secret
+    }
+}
+
+impl<T> From<T> for Sensitive<T> {
+    fn from(secret: T) -> Self {
+        Sensitive::new(secret)
+    }
+}
+
+impl<T> fmt::Display for Sensitive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+impl<T> fmt::Debug for Sensitive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sensitive([redacted])")
+    }
+}
+
+/// `value`, which the analysis takes to carry no labels from here on, without claiming it was sanitized.
+/// It is never inlined, so that the analysis sees the call even in optimized code.
+#[inline(never)]
+pub fn barrier<T>(value: T) -> T {
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting_does_not_print_the_secret() {
+        let token = Sensitive::new("hunter2");
+
+        assert_eq!(token.to_string(), "[redacted]");
+        assert_eq!(format!("{:?}", token), "Sensitive([redacted])");
+        assert_eq!(*token.expose(), "hunter2");
+    }
+
+    #[test]
+    fn barriers_return_their_value() {
+        assert_eq!(barrier(String::from("id")), "id");
+    }
+}
@@ -0,0 +1,55 @@
+//! Closures registered as callbacks, to be called later through a trait object.
+//!
+//! A call like `handler(request)` on a `Box<dyn Fn(Request)>` names no closure, so the analysis
+//! cannot tell which one runs. Closures are recorded when they are turned into trait objects,
+//! which is how they are stored in a field or a collection, and calls through a trait object
+//! are taken to run any of the closures recorded so far which take the same arguments.
+//! Futures boxed into a `dyn Future`, like those of `async fn`s, are recorded the same way,
+//! and polling a trait object is taken to poll any of those recorded so far with the same output.
+
+use crate::compiler::{self, DefId, GenericArgsRef, Ty, TyCtxt};
+
+#[derive(Default, Debug)]
+pub(crate) struct Callbacks<'tcx> {
+    closures: Vec<(DefId, GenericArgsRef<'tcx>)>,
+    generators: Vec<(DefId, GenericArgsRef<'tcx>)>,
+}
+
+impl<'tcx> Callbacks<'tcx> {
+    /// Records that the closure or generator `id`, instantiated with `args`, was turned into a trait object.
+    pub(crate) fn register(&mut self, tcx: TyCtxt<'tcx>, id: DefId, args: GenericArgsRef<'tcx>) {
+        let recorded = match tcx.generator_kind(id) {
+            Some(_) => &mut self.generators,
+            None => &mut self.closures,
+        };
+        if !recorded.contains(&(id, args)) {
+            recorded.push((id, args));
+        }
+    }
+
+    /// The recorded closures a call through a trait object, with arguments of the tuple type `args`, may run.
+    pub(crate) fn candidates(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        args: Ty<'tcx>,
+    ) -> Vec<(DefId, GenericArgsRef<'tcx>)> {
+        self.closures
+            .iter()
+            .copied()
+            .filter(|&(_, closure_args)| compiler::closure_accepts(tcx, closure_args, args))
+            .collect()
+    }
+
+    /// The recorded generators polling a trait object with the output type `output` may poll.
+    pub(crate) fn futures(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        output: Ty<'tcx>,
+    ) -> Vec<(DefId, GenericArgsRef<'tcx>)> {
+        self.generators
+            .iter()
+            .copied()
+            .filter(|&(_, generator_args)| compiler::generator_returns(tcx, generator_args, output))
+            .collect()
+    }
+}
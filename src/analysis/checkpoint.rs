@@ -0,0 +1,276 @@
+//! Checkpointing long runs, so that one interrupted before it was done can resume, for `--taint-resume=<dir>`.
+//!
+//! A run over a large workspace can take hours, and be killed by the OOM killer or a CI timeout before it
+//! reports anything. With the option, the analysis of a crate writes a checkpoint to the directory every few
+//! minutes, as it computes summaries, and once it stops: the summary of every context analyzed so far,
+//! the entry points whose analysis is done, and the findings reported so far, in the format of `--taint-findings`.
+//! A run with the same inputs then skips the entry points which were done, reporting their findings from the
+//! checkpoint, and uses the summaries of the other contexts instead of analyzing them again.
+//!
+//! The files are named after the crate, its strict version hash, which changes with its code, and a hash of
+//! the options, the config and the summaries bundled or imported, like those of the
+//! [dependency cache](super::dependency_cache), so that a run only resumes from what was computed the same way,
+//! and numbers the labels of the contexts it holds the same way.
+//! The same contexts are left out: those of functions with generic parameters, and of functions whose
+//! analysis did more than computing their summary, like reporting a finding, which reusing it would skip.
+//! These functions are analyzed again from the entry points which were not done, and a finding they report
+//! again is only reported once.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    process,
+    time::{Duration, Instant},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    compiler::{DefId, Diagnostics, Idx, Local, TyCtxt, LOCAL_CRATE},
+    config::Config,
+    error::Error,
+    options::Options,
+    report::Report,
+    summaries::Summaries,
+    taint_analysis::{ContextKey, InitSet, Shared, Summary},
+};
+
+use super::{
+    dependency_cache,
+    labels::{Label, Labels},
+    taint_domain::{TaintDomain, TaintState},
+};
+
+/// How long a run goes between checkpoints.
+const INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// A context by the path of its function and the labels of its arguments, which unlike its key
+/// does not depend on the session.
+type Context = (String, InitSet);
+
+/// The directory checkpoints are written to, for the inputs of this run.
+#[derive(Debug)]
+pub(crate) struct Checkpoint {
+    dir: PathBuf,
+    /// The hash of everything besides the code of the crate which decides what its summaries are.
+    inputs: u64,
+    /// Whether the run may resume from a checkpoint, which those recording facts may not,
+    /// since they need the facts of every function.
+    resumes: bool,
+    /// When the last checkpoint was written, or the run started.
+    written: Cell<Instant>,
+    /// The summaries of the checkpoint the run resumed from.
+    contexts: HashMap<Context, Summary>,
+    /// The findings of the checkpoint the run resumed from.
+    findings: Report,
+    /// The paths of the entry points whose analysis is done, in this run or the one it resumed from.
+    entries: RefCell<Vec<String>>,
+}
+
+impl Checkpoint {
+    /// The checkpoints of `options`, if they ask for them, for summaries computed with `config` and the summaries
+    /// bundled or imported, `summaries`, holding what the checkpoint an earlier run with the same inputs wrote.
+    pub(crate) fn open(
+        tcx: TyCtxt<'_>,
+        options: &Options,
+        config: &Config,
+        summaries: &[Result<Summaries, Error>],
+    ) -> Option<Checkpoint> {
+        let dir = options.resume.clone()?;
+        let mut checkpoint = Checkpoint {
+            dir,
+            inputs: dependency_cache::hash_inputs(options, config, summaries),
+            resumes: !options.records_facts(),
+            written: Cell::new(Instant::now()),
+            contexts: HashMap::new(),
+            findings: Report::default(),
+            entries: RefCell::default(),
+        };
+        if checkpoint.resumes {
+            if let Err(error) = checkpoint.read(tcx) {
+                Diagnostics::new(tcx).failure(&error);
+            }
+        }
+        Some(checkpoint)
+    }
+
+    /// The file of the checkpoint holding `what`, like `contexts`, whether it was written or not.
+    fn file(&self, tcx: TyCtxt<'_>, what: &str) -> PathBuf {
+        self.dir.join(format!(
+            "{}-{}-{:016x}.{}.json",
+            tcx.crate_name(LOCAL_CRATE),
+            tcx.crate_hash(LOCAL_CRATE).to_hex(),
+            self.inputs,
+            what
+        ))
+    }
+
+    /// Reads the checkpoint an earlier run with the same inputs wrote, if there is one.
+    fn read(&mut self, tcx: TyCtxt<'_>) -> Result<(), Error> {
+        let file = self.file(tcx, "contexts");
+        if !file.is_file() {
+            return Ok(());
+        }
+        let text = fs::read_to_string(&file).map_err(|e| Error::io(&file, e))?;
+        let (contexts, entries) = checkpoint_from_json(&text)?;
+        // The findings are written first, so those of the entry points which were done are there.
+        self.findings = Report::read(self.file(tcx, "findings"))?;
+        self.contexts = contexts;
+        self.entries = RefCell::new(entries);
+        Ok(())
+    }
+
+    /// The findings of the checkpoint the run resumed from.
+    pub(crate) fn findings(&self) -> Report {
+        self.findings.clone()
+    }
+
+    /// The summary of the context `key` the checkpoint the run resumed from holds, if it holds one.
+    pub(crate) fn resumed(&self, tcx: TyCtxt<'_>, key: &ContextKey<'_>) -> Option<Summary> {
+        let (id, args, init) = key;
+        if self.contexts.is_empty() || !args.is_empty() {
+            return None;
+        }
+        let context = (tcx.def_path_str(*id), init.clone());
+        self.contexts.get(&context).cloned()
+    }
+
+    /// Whether the analysis of the entry point `entry` is done, in the run the checkpoint was written by.
+    pub(crate) fn is_done(&self, tcx: TyCtxt<'_>, entry: DefId) -> bool {
+        self.entries.borrow().contains(&tcx.def_path_str(entry))
+    }
+
+    /// Notes that the analysis of the entry point `entry` is done, so that a run resuming from the next checkpoint
+    /// skips it.
+    pub(crate) fn done(&self, tcx: TyCtxt<'_>, entry: DefId) {
+        let path = tcx.def_path_str(entry);
+        let mut entries = self.entries.borrow_mut();
+        if !entries.contains(&path) {
+            entries.push(path);
+        }
+    }
+
+    /// Writes a checkpoint of what the run in `shared` computed so far if the last one is old enough,
+    /// or if `stopping`, once the run is done or stops before.
+    pub(crate) fn save(&self, tcx: TyCtxt<'_>, shared: &Shared<'_>, stopping: bool) {
+        if !stopping && self.written.get().elapsed() < INTERVAL {
+            return;
+        }
+        if let Err(error) = self.write(tcx, shared) {
+            Diagnostics::new(tcx).failure(&error);
+        }
+        self.written.set(Instant::now());
+    }
+
+    fn write(&self, tcx: TyCtxt<'_>, shared: &Shared<'_>) -> Result<(), Error> {
+        let side_effects = shared.side_effects.borrow();
+        let analyzed = shared.contexts.borrow();
+        // The contexts of the checkpoint before are kept, like those of the entry points this run skipped.
+        let mut contexts = self
+            .contexts
+            .iter()
+            .map(|(context, summary)| (context.clone(), summary))
+            .collect::<HashMap<_, _>>();
+        for ((id, args, init), summary) in analyzed.iter() {
+            let Some(summary) = summary else {
+                continue;
+            };
+            if args.is_empty() && !side_effects.contains(id) {
+                contexts.insert((tcx.def_path_str(*id), init.clone()), summary);
+            }
+        }
+        let mut contexts = contexts.into_iter().collect::<Vec<_>>();
+        contexts.sort_by(|((a, _), _), ((b, _), _)| a.cmp(b));
+        let findings = shared.findings.borrow().report(tcx);
+        fs::create_dir_all(&self.dir).map_err(|e| Error::io(&self.dir, e))?;
+        replace(&self.file(tcx, "findings"), |path| findings.write(path))?;
+        let text = checkpoint_to_json(&contexts, &self.entries.borrow());
+        replace(&self.file(tcx, "contexts"), |path| {
+            fs::write(path, &text).map_err(|e| Error::io(path, e))
+        })
+    }
+}
+
+/// Replaces `file` at once with what `write` writes, so that a run killed while writing it leaves the one before.
+fn replace(file: &Path, write: impl FnOnce(&Path) -> Result<(), Error>) -> Result<(), Error> {
+    let written = file.with_extension(format!("{}.tmp", process::id()));
+    write(&written)?;
+    fs::rename(&written, file).map_err(|e| Error::io(file, e))
+}
+
+fn checkpoint_to_json(contexts: &[(Context, &Summary)], entries: &[String]) -> String {
+    let labels = |labels: Labels| labels.iter().map(Label::index).collect::<Vec<_>>();
+    let contexts = contexts
+        .iter()
+        .map(|((function, init), summary)| {
+            // Callers only look at what the arguments carry once the function returns.
+            let args = (0..init.len()).map(|ix| summary.state.get_taint(Local::new(ix + 1)));
+            json!({
+                "function": function,
+                "init": init.iter().map(|&init| labels(init)).collect::<Vec<_>>(),
+                "args": args.map(labels).collect::<Vec<_>>(),
+                "returns": labels(summary.returns),
+                "points_into": summary.points_into,
+                "approximated": summary.approximated,
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({ "entries": entries, "contexts": contexts }).to_string()
+}
+
+fn checkpoint_from_json(text: &str) -> Result<(HashMap<Context, Summary>, Vec<String>), Error> {
+    let value: Value = serde_json::from_str(text).map_err(|e| Error::format("checkpoint", e))?;
+    let entries = value["entries"]
+        .as_array()
+        .and_then(|entries| {
+            entries
+                .iter()
+                .map(|entry| entry.as_str().map(str::to_owned))
+                .collect::<Option<Vec<_>>>()
+        })
+        .ok_or_else(|| Error::format("checkpoint", "malformed `entries`"))?;
+    let contexts = value["contexts"]
+        .as_array()
+        .and_then(|contexts| contexts.iter().map(context_from_json).collect())
+        .ok_or_else(|| Error::format("checkpoint", "malformed `contexts`"))?;
+    Ok((contexts, entries))
+}
+
+fn context_from_json(value: &Value) -> Option<(Context, Summary)> {
+    let labels = |value: &Value| {
+        value
+            .as_array()?
+            .iter()
+            .try_fold(Labels::EMPTY, |labels, ix| {
+                let ix = ix.as_u64()? as usize;
+                (ix < Label::MAX).then(|| labels.union(Label::new(ix).into()))
+            })
+    };
+    let many = |value: &Value| {
+        value
+            .as_array()?
+            .iter()
+            .map(labels)
+            .collect::<Option<Vec<_>>>()
+    };
+    let init = many(&value["init"])?;
+    let args = many(&value["args"])?;
+    // Only the arguments are checkpointed, as they are all callers read of the state.
+    let mut state = TaintState::new_empty(args.len() + 1);
+    for (ix, labels) in args.into_iter().enumerate() {
+        state.set_taint(Local::new(ix + 1), labels);
+    }
+    let summary = Summary {
+        state,
+        returns: labels(&value["returns"])?,
+        points_into: value["points_into"]
+            .as_array()?
+            .iter()
+            .map(|ix| ix.as_u64().map(|ix| ix as usize))
+            .collect::<Option<_>>()?,
+        approximated: value["approximated"].as_bool()?,
+    };
+    Some(((value["function"].as_str()?.to_owned(), init), summary))
+}
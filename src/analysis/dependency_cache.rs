@@ -0,0 +1,145 @@
+//! Reusing the summaries of the functions of dependencies across builds, for `--taint-dependency-cache=<dir>`.
+//!
+//! The analysis descends into the functions of dependencies whose MIR was encoded, and every crate of a workspace
+//! descends into the same ones, build after build. With the option, the analysis of a crate writes the summaries of
+//! the functions of each dependency it analyzed to a file of the directory, named after the dependency and its
+//! strict version hash, which changes with its code and the flags it was built with. The analysis of any crate
+//! depending on the same version then uses them instead of analyzing these functions again, like
+//! [imported summaries](crate::summaries), and adds those of the functions it analyzed to the file.
+//!
+//! What a function does to taint also depends on the options, the config and the summaries bundled or imported,
+//! so the name of the file holds a hash of those too, and of the version of the analysis.
+//! Only functions without generic parameters are cached, since what the others do depends on what they are
+//! instantiated with, and only those whose analysis did nothing else than computing their summary:
+//! reusing a summary skips the analysis of the function and of its callees, and with it the findings
+//! reported in them. Like those of `--taint-export-summaries`, the summaries cached join every context
+//! the function was analyzed in.
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    fs,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    process,
+};
+
+use crate::{
+    compiler::{CrateNum, DefId, TyCtxt, LOCAL_CRATE},
+    config::Config,
+    error::Error,
+    eval::attributes::AttrInfo,
+    interchange,
+    options::Options,
+    summaries::Summaries,
+    taint_analysis::Contexts,
+};
+
+/// The directory summaries are cached in, for the inputs of this run.
+#[derive(Debug)]
+pub(crate) struct DependencyCache {
+    dir: PathBuf,
+    /// The hash of everything besides the code of a dependency which decides what its summaries are.
+    inputs: u64,
+}
+
+impl DependencyCache {
+    /// The cache of `options`, if they ask for one, for summaries computed with `config` and the summaries
+    /// bundled or imported, `summaries`.
+    pub(crate) fn open(
+        options: &Options,
+        config: &Config,
+        summaries: &[Result<Summaries, Error>],
+    ) -> Option<DependencyCache> {
+        let dir = options.dependency_cache.clone()?;
+        Some(DependencyCache {
+            dir,
+            inputs: hash_inputs(options, config, summaries),
+        })
+    }
+
+    /// The file the summaries of the functions of `krate` are cached in, whether it was written or not.
+    fn file(&self, tcx: TyCtxt<'_>, krate: CrateNum) -> PathBuf {
+        self.dir.join(format!(
+            "{}-{}-{:016x}.json",
+            tcx.crate_name(krate),
+            tcx.crate_hash(krate).to_hex(),
+            self.inputs
+        ))
+    }
+
+    /// The summaries cached for the crates the crate depends on.
+    pub(crate) fn summaries(&self, tcx: TyCtxt<'_>) -> Vec<Result<Summaries, Error>> {
+        tcx.crates(())
+            .iter()
+            .map(|&krate| self.file(tcx, krate))
+            .filter(|file| file.is_file())
+            .map(Summaries::read)
+            .collect()
+    }
+
+    /// Adds the summaries of the functions of dependencies analyzed in `contexts` to the files of their crates,
+    /// but for those with generic parameters and those in `side_effects`, whose analysis did more than computing
+    /// their summary.
+    pub(crate) fn write(
+        &self,
+        tcx: TyCtxt<'_>,
+        info: &AttrInfo,
+        contexts: &Contexts<'_>,
+        side_effects: &HashSet<DefId>,
+    ) -> Result<(), Error> {
+        let mut crates = contexts
+            .keys()
+            .map(|(id, _, _)| id.krate)
+            .filter(|&krate| krate != LOCAL_CRATE)
+            .collect::<Vec<_>>();
+        crates.sort();
+        crates.dedup();
+        for krate in crates {
+            let cached = |id: DefId| {
+                id.krate == krate && tcx.generics_of(id).count() == 0 && !side_effects.contains(&id)
+            };
+            let mut analyzed = interchange::export_named(tcx, info, contexts, |id| {
+                cached(id).then(|| tcx.def_path_str(id))
+            });
+            if analyzed.summaries.is_empty() {
+                continue;
+            }
+            let file = self.file(tcx, krate);
+            // The summaries cached before are kept, like those this crate used rather than analyzing their functions.
+            if let Ok(before) = Summaries::read(&file) {
+                let kept = before
+                    .summaries
+                    .into_iter()
+                    .filter(|summary| analyzed.get(&summary.function).is_none());
+                analyzed.summaries.extend(kept.collect::<Vec<_>>());
+            }
+            // Crates are analyzed in parallel, so the file is replaced at once rather than written in place.
+            fs::create_dir_all(&self.dir).map_err(|e| Error::io(&self.dir, e))?;
+            let written = file.with_extension(format!("{}.tmp", process::id()));
+            analyzed.write(&written)?;
+            fs::rename(&written, &file).map_err(|e| Error::io(&file, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// A hash of the version of the analysis, `options`, `config` and the summaries bundled or imported, `summaries`,
+/// which decide what the summary of a function is besides its code.
+pub(crate) fn hash_inputs(
+    options: &Options,
+    config: &Config,
+    summaries: &[Result<Summaries, Error>],
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    // The options, but for the handles tools keep to control the run.
+    let options = Options {
+        cancellation: Default::default(),
+        warm_start: None,
+        ..options.clone()
+    };
+    format!("{:?}", options).hash(&mut hasher);
+    format!("{:?}", config).hash(&mut hasher);
+    format!("{:?}", summaries).hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,72 @@
+//! Recording the taint of every assignment, for `--taint-results`.
+
+use std::collections::HashMap;
+
+use crate::{
+    compiler::{self, DefId, SourceRange, Span, TyCtxt},
+    eval::attributes::AttrInfo,
+    query::{Fact, Results},
+};
+
+use super::labels::Labels;
+
+#[derive(Default, Debug)]
+pub(crate) struct Facts {
+    /// The labels of the value assigned at each span, and the entry points it was tainted for.
+    facts: HashMap<(DefId, Span), (Labels, Vec<DefId>)>,
+}
+
+impl Facts {
+    pub(crate) fn record(&mut self, function: DefId, span: Span, labels: Labels, entry: DefId) {
+        let (recorded, entries) = self.facts.entry((function, span)).or_default();
+        *recorded = recorded.union(labels);
+        if !labels.is_empty() && !entries.contains(&entry) {
+            entries.push(entry);
+        }
+    }
+
+    /// Records what `other` recorded, for the entry points of another profile.
+    pub(crate) fn absorb(&mut self, other: Facts) {
+        for (key, (labels, entries)) in other.facts {
+            let (recorded, recorded_entries) = self.facts.entry(key).or_default();
+            *recorded = recorded.union(labels);
+            for entry in entries {
+                if !recorded_entries.contains(&entry) {
+                    recorded_entries.push(entry);
+                }
+            }
+        }
+    }
+
+    /// Whether the value assigned at `span` in `function` was tainted for any entry point.
+    pub(crate) fn tainted(&self, function: DefId, span: Span) -> bool {
+        self.facts
+            .get(&(function, span))
+            .map_or(false, |(labels, _)| !labels.is_empty())
+    }
+
+    pub(crate) fn results(&self, tcx: TyCtxt<'_>, info: &AttrInfo) -> Results {
+        let mut facts = self
+            .facts
+            .iter()
+            .filter_map(|(&(function, span), (labels, entries))| {
+                let SourceRange { file, start, end } = compiler::source_range(tcx, span)?;
+                Some(Fact {
+                    function: tcx.def_path_str(function),
+                    file,
+                    start,
+                    end,
+                    labels: labels
+                        .iter()
+                        .map(|label| info.label_name(tcx, label))
+                        .collect(),
+                    entries: entries.iter().map(|&id| tcx.def_path_str(id)).collect(),
+                })
+            })
+            .collect::<Vec<_>>();
+        facts.sort_by(|a, b| {
+            (&a.file, a.start, a.end, &a.function).cmp(&(&b.file, b.start, b.end, &b.function))
+        });
+        Results { facts }
+    }
+}
@@ -0,0 +1,1297 @@
+//! Recording the findings reported by the analysis, for `--taint-findings`, `--taint-fuzz-targets`
+//! and `--taint-reproducers`.
+//!
+//! Findings are emitted as diagnostics once the analysis is done, one per location: a sink called
+//! with the same tainted data in two arguments, or reached with other labels in another context,
+//! is a single error listing every label it received. A sink in a helper analyzed in many contexts
+//! keeps each of them, and its notes on the calls it was reached through are only written then,
+//! once the contexts reached by reusing the summary of the helper are known too.
+
+use std::{fmt::Write, mem};
+
+use crate::{
+    analysis::{
+        facts::Facts,
+        labels::Labels,
+        reproducer::{self, Flow},
+        summary_usage::plural,
+    },
+    compiler::{self, DefId, Diagnostics, ErrorCode, SourceRange, Span, TyCtxt},
+    eval::attributes::AttrInfo,
+    fuzz::{Target, Targets},
+    options::FailLevel,
+    report::{self, ChokePoint, Confidence, Family, Report},
+    slice,
+    taint_analysis::InitSet,
+};
+
+/// An argument of a function, or of a sink, by the path of the function and the position
+/// of the argument. `None` stands for several arguments at once, or what a sink returns or formats.
+type Point = (String, Option<usize>);
+
+/// A function a finding was reached through, and the labels its arguments were called with.
+pub(crate) type Frame = (DefId, InitSet);
+
+/// A function a finding was reached through, the labels its arguments were called with, and where it was called,
+/// unless it was analyzed as an entry point or the initializer of a static.
+pub(crate) type Called = (DefId, InitSet, Option<Span>);
+
+/// How many paths are kept for a finding. Past it, only the function the finding is in is kept
+/// of each, which gives up the choke points of its callers but keeps those found sound.
+const MAX_PATHS: usize = 32;
+
+/// How many of the call chains a finding was reached through are noted on its diagnostic,
+/// and of the other contexts it was found in.
+const MAX_CHAINS: usize = 3;
+
+/// The sink a finding is about, and the arguments of the call to it which were tainted.
+#[derive(Debug, Clone)]
+pub(crate) struct Sink {
+    /// A function path, or a macro name followed by `!`.
+    pub(crate) name: String,
+    pub(crate) arguments: Vec<usize>,
+    /// The variables the tainted arguments were read from, by name and where they are declared.
+    /// Arguments which are constants or computed from several values have none.
+    pub(crate) variables: Vec<(String, Span)>,
+    /// The model of a function the finding was found by, like ``the model of `Display` for `Sensitive` ``,
+    /// which is noted when the model applied inside a dependency.
+    pub(crate) model: Option<String>,
+}
+
+impl Sink {
+    /// A sink which does not check the arguments of a call, like a function checked on return.
+    pub(crate) fn without_arguments(name: String) -> Sink {
+        Sink {
+            name,
+            arguments: vec![],
+            variables: vec![],
+            model: None,
+        }
+    }
+}
+
+/// A finding as reported, before it is resolved into paths and positions.
+#[derive(Debug)]
+pub(crate) struct Recorded {
+    pub(crate) function: DefId,
+    pub(crate) span: Span,
+    pub(crate) code: ErrorCode,
+    pub(crate) message: String,
+    pub(crate) sink: String,
+    /// The positions of the tainted arguments of the call to the sink.
+    pub(crate) arguments: Vec<usize>,
+    pub(crate) label: String,
+    /// The other labels the sink received at the same location, in the order they were found.
+    pub(crate) also: Vec<String>,
+    /// The variables the tainted arguments were read from, by name and where they are declared.
+    pub(crate) variables: Vec<(String, Span)>,
+    /// A note on the model the finding was found by inside a dependency, whose call site it is reported at.
+    pub(crate) model: Option<String>,
+    /// The calls through which `function` was reached, from the entry point in, in each context it was found in,
+    /// in the order they were found. A function whose summary is reused by another caller adds a context through it.
+    pub(crate) contexts: Vec<Vec<Called>>,
+    /// The callers of `function` when it was first found, and where they call it,
+    /// from the innermost one out, to look for the source of its tainted input.
+    pub(crate) callers: Vec<(DefId, Span)>,
+    /// The labels the sink received, including those in `also`.
+    pub(crate) labels: Labels,
+    /// The functions each flow to the finding was reached through, from the entry point in.
+    /// A function whose summary is reused by another caller adds a path through that caller.
+    pub(crate) paths: Vec<Vec<Frame>>,
+    /// The profiles of the entry points it was found from, in the order they were found with.
+    pub(crate) profiles: Vec<String>,
+}
+
+impl Recorded {
+    /// Whether both are about the same sink at the same location, whatever the labels
+    /// and the contexts they were found in.
+    fn same_location(&self, other: &Recorded) -> bool {
+        (self.function, self.span, self.code) == (other.function, other.span, other.code)
+            && self.sink == other.sink
+    }
+
+    /// Adds the labels, tainted arguments and contexts of `other`, found at the same location.
+    fn merge(&mut self, other: Recorded) {
+        self.labels = self.labels.union(other.labels);
+        for path in other.paths {
+            self.add_path(path);
+        }
+        for context in other.contexts {
+            self.add_context(context);
+        }
+        if other.label != self.label && !self.also.contains(&other.label) {
+            self.also.push(other.label);
+        }
+        for variable in other.variables {
+            if !self.variables.contains(&variable) {
+                self.variables.push(variable);
+            }
+        }
+        self.arguments.extend(other.arguments);
+        self.arguments.sort_unstable();
+        self.arguments.dedup();
+        for profile in other.profiles {
+            if !self.profiles.contains(&profile) {
+                self.profiles.push(profile);
+            }
+        }
+    }
+
+    fn add_path(&mut self, path: Vec<Frame>) {
+        if !self.paths.contains(&path) {
+            self.paths.push(path);
+        }
+        if self.paths.len() > MAX_PATHS {
+            let innermost = self.paths.iter().filter_map(|path| path.last().cloned());
+            let mut paths = innermost.map(|frame| vec![frame]).collect::<Vec<_>>();
+            paths.dedup();
+            self.paths = paths;
+        }
+    }
+
+    fn add_context(&mut self, context: Vec<Called>) {
+        if self.contexts.len() < MAX_PATHS && !self.contexts.contains(&context) {
+            self.contexts.push(context);
+        }
+    }
+
+    /// Notes on the calls through which the finding was first reached with tainted arguments, from the innermost
+    /// one out, followed by one on the innermost such call of each other context it was found in, up to a few,
+    /// like ``also found when `process(data)` is called with tainted `data` ``.
+    fn context_notes(&self, tcx: TyCtxt<'_>) -> Vec<(Option<Span>, String)> {
+        let mut contexts = self
+            .contexts
+            .iter()
+            .map(|context| context_notes(tcx, context));
+        let mut notes = contexts.next().unwrap_or_default();
+        let mut others = vec![];
+        for (call_site, note) in contexts.filter_map(|notes| notes.into_iter().next()) {
+            let also = (call_site, format!("also found {}", note));
+            if !notes.contains(&(call_site, note)) && !others.contains(&also) {
+                others.push(also);
+            }
+        }
+        let more = others.len().saturating_sub(MAX_CHAINS);
+        notes.extend(others.into_iter().take(MAX_CHAINS));
+        if more > 0 {
+            notes.push((
+                None,
+                format!("and in {}", plural(more, "other context", "other contexts")),
+            ));
+        }
+        notes
+    }
+
+    /// Notes on the chains of calls from an entry point to the function the finding is in, like
+    /// ``reached through `main` -> `handle` -> `render` ``, for each path which went through a call.
+    fn chains(&self, tcx: TyCtxt<'_>) -> Vec<String> {
+        let mut chains: Vec<Vec<DefId>> = vec![];
+        for path in self.paths.iter().filter(|path| path.len() > 1) {
+            let chain = path.iter().map(|(function, _)| *function).collect();
+            if !chains.contains(&chain) {
+                chains.push(chain);
+            }
+        }
+        let mut notes = chains
+            .iter()
+            .take(MAX_CHAINS)
+            .map(|chain| {
+                let functions = chain
+                    .iter()
+                    .map(|&function| format!("`{}`", tcx.def_path_str(function)))
+                    .collect::<Vec<_>>();
+                format!("reached through {}", functions.join(" -> "))
+            })
+            .collect::<Vec<_>>();
+        let others = chains.len().saturating_sub(MAX_CHAINS);
+        if others > 0 {
+            let plural = if others == 1 { "" } else { "s" };
+            notes.push(format!("and through {} other call chain{}", others, plural));
+        }
+        notes
+    }
+
+    /// The arguments every flow to the finding passes through: those of the functions it was
+    /// reached through which carry its labels, and those of the sink.
+    fn points(&self, tcx: TyCtxt<'_>) -> Vec<Point> {
+        // Flows through several arguments at once only all pass through the function.
+        let point = |function: String, arguments: &[usize]| match arguments {
+            &[argument] => (function, Some(argument)),
+            _ => (function, None),
+        };
+        let sink = point(self.sink.clone(), &self.arguments);
+        let path_points = |path: &Vec<Frame>| {
+            let reached = path.iter().filter_map(|(function, init)| {
+                let carrying = init
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, labels)| !labels.intersection(self.labels).is_empty())
+                    .map(|(ix, _)| ix)
+                    .collect::<Vec<_>>();
+                (!carrying.is_empty()).then(|| point(tcx.def_path_str(*function), &carrying))
+            });
+            reached.chain([sink.clone()]).collect::<Vec<_>>()
+        };
+        let mut paths = self.paths.iter().map(path_points);
+        let first = paths.next().unwrap_or_else(|| vec![sink.clone()]);
+        paths.fold(first, |mut points, others| {
+            points.retain(|point| others.contains(point));
+            points
+        })
+    }
+}
+
+/// Why the effect of a call was approximated rather than analyzed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Approximation {
+    /// The callee was still being analyzed when it was called back, so the call was cut off.
+    Recursion,
+    /// The callee has no MIR, so the call followed `--taint-unknown-calls`.
+    WithoutMir,
+    /// The callee is a naked function, whose body is assembly, so the call followed `--taint-unknown-calls`.
+    Naked,
+    /// The summary of the callee was approximated itself, because a recursive call was cut off in it
+    /// or in its callees. Calls without MIR in callees are not counted, as most of `std` makes some.
+    Callee,
+}
+
+impl Approximation {
+    fn reason(&self) -> &'static str {
+        match self {
+            Approximation::Recursion => "it was cut off where it calls itself back",
+            Approximation::WithoutMir => {
+                "it has no MIR, so the call follows `--taint-unknown-calls`"
+            }
+            Approximation::Naked => {
+                "it is a naked function, whose assembly has no MIR, so the call follows `--taint-unknown-calls`"
+            }
+            Approximation::Callee => "a recursive call in it was cut off",
+        }
+    }
+}
+
+/// A call in `function` whose effect was approximated.
+#[derive(Debug, PartialEq, Eq)]
+struct Approximated {
+    function: DefId,
+    span: Span,
+    callee: DefId,
+    approximation: Approximation,
+}
+
+/// A call to `sanitizer` at `span` in `function`, and the labels its input carried in every context
+/// it was analyzed in, for `--taint-lint-clean-sanitizers` and `--taint-coverage-report`.
+#[derive(Debug)]
+struct SanitizerCall {
+    function: DefId,
+    span: Span,
+    sanitizer: DefId,
+    received: Labels,
+}
+
+/// A call to `source` at `span` in `function`, and the labels it put on its data,
+/// for `--taint-coverage-report` and the families of findings.
+#[derive(Debug)]
+struct SourceCall {
+    function: DefId,
+    span: Span,
+    source: DefId,
+    labels: Labels,
+}
+
+/// A call to `callee`, which requires taint, at `span` in `function`, and the labels its input
+/// carried in every context it was analyzed in.
+#[derive(Debug)]
+struct RequiredTaintCall {
+    function: DefId,
+    span: Span,
+    callee: DefId,
+    received: Labels,
+}
+
+/// A write at `span` in `function` of data carrying `labels` into a field marked `#[taint::storage]`,
+/// in every context it was analyzed in.
+#[derive(Debug)]
+struct StoredWrite {
+    function: DefId,
+    span: Span,
+    field: DefId,
+    labels: Labels,
+}
+
+/// A call to `Clone::clone` at `span` in `function` on a value carrying `labels`, of those whose clones
+/// the config tracks, in every context it was analyzed in.
+#[derive(Debug)]
+struct TrackedClone {
+    function: DefId,
+    span: Span,
+    labels: Labels,
+}
+
+/// A value carrying `labels` which crossed the boundary `boundary` at `span`,
+/// and the storage fields within its type.
+#[derive(Debug)]
+struct Crossing {
+    span: Span,
+    boundary: String,
+    fields: Vec<DefId>,
+    labels: Labels,
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct Findings {
+    findings: Vec<Recorded>,
+    approximated: Vec<Approximated>,
+    sanitizer_calls: Vec<SanitizerCall>,
+    source_calls: Vec<SourceCall>,
+    required_taint_calls: Vec<RequiredTaintCall>,
+    stored: Vec<StoredWrite>,
+    crossings: Vec<Crossing>,
+    clones: Vec<TrackedClone>,
+    /// The constructs of MIR which were not modeled, and what they are, for `--taint-strict-mir`.
+    unmodeled: Vec<(Span, &'static str)>,
+    /// The findings of the checkpoint the run resumed from, reported along with those it finds
+    /// unless it finds them again.
+    resumed: Report,
+}
+
+impl Findings {
+    /// Records a finding, or merges it into the one already recorded at the same location.
+    /// A call may be visited several times while iterating to a fixpoint, and a function analyzed
+    /// in several contexts, so like the compiler does with its diagnostics, duplicates are dropped.
+    /// The message a finding was first found with is the one it is reported with.
+    pub(crate) fn record(&mut self, finding: Recorded) {
+        match self.findings.iter_mut().find(|f| f.same_location(&finding)) {
+            Some(recorded) => recorded.merge(finding),
+            None => self.findings.push(finding),
+        }
+    }
+
+    /// Records the findings of the checkpoint the run resumes from, with `--taint-resume`.
+    pub(crate) fn resume(&mut self, resumed: Report) {
+        self.resumed = resumed;
+    }
+
+    /// Records what `other` recorded, for the entry points of another profile.
+    /// Findings at the same location are merged, along with the profiles they were found with.
+    pub(crate) fn absorb(&mut self, other: Findings) {
+        for finding in other.findings {
+            self.record(finding);
+        }
+        self.resumed = mem::take(&mut self.resumed).with_resumed(other.resumed);
+        for approximated in other.approximated {
+            let Approximated {
+                function,
+                span,
+                callee,
+                approximation,
+            } = approximated;
+            self.approximated(function, span, callee, approximation);
+        }
+        for call in other.sanitizer_calls {
+            self.sanitizer_called(call.function, call.span, call.sanitizer, call.received);
+        }
+        for call in other.source_calls {
+            self.source_called(call.function, call.span, call.source, call.labels);
+        }
+        for call in other.required_taint_calls {
+            self.required_taint_called(call.function, call.span, call.callee, call.received);
+        }
+        for write in other.stored {
+            self.stored(write.function, write.span, write.field, write.labels);
+        }
+        for crossing in other.crossings {
+            self.crossed(
+                crossing.span,
+                crossing.boundary,
+                crossing.fields,
+                crossing.labels,
+            );
+        }
+        for clone in other.clones {
+            self.cloned(clone.function, clone.span, clone.labels);
+        }
+        for (span, construct) in other.unmodeled {
+            self.unmodeled(span, construct);
+        }
+    }
+
+    /// The call to `callee` at `span` in `function` was approximated, which the findings in `function`
+    /// whose sink received input computed from the call note, whether they were found before or after it.
+    pub(crate) fn approximated(
+        &mut self,
+        function: DefId,
+        span: Span,
+        callee: DefId,
+        approximation: Approximation,
+    ) {
+        let approximated = Approximated {
+            function,
+            span,
+            callee,
+            approximation,
+        };
+        if !self.approximated.contains(&approximated) {
+            self.approximated.push(approximated);
+        }
+    }
+
+    /// The call to `sanitizer` at `span` in `function` received `received`, in one of the contexts
+    /// `function` is analyzed in.
+    pub(crate) fn sanitizer_called(
+        &mut self,
+        function: DefId,
+        span: Span,
+        sanitizer: DefId,
+        received: Labels,
+    ) {
+        let call = self
+            .sanitizer_calls
+            .iter_mut()
+            .find(|call| (call.function, call.span, call.sanitizer) == (function, span, sanitizer));
+        match call {
+            Some(call) => call.received = call.received.union(received),
+            None => self.sanitizer_calls.push(SanitizerCall {
+                function,
+                span,
+                sanitizer,
+                received,
+            }),
+        }
+    }
+
+    /// The call to `source` at `span` in `function` put `labels` on its data.
+    pub(crate) fn source_called(
+        &mut self,
+        function: DefId,
+        span: Span,
+        source: DefId,
+        labels: Labels,
+    ) {
+        let call = self
+            .source_calls
+            .iter_mut()
+            .find(|call| (call.function, call.span, call.source) == (function, span, source));
+        match call {
+            Some(call) => call.labels = call.labels.union(labels),
+            None => self.source_calls.push(SourceCall {
+                function,
+                span,
+                source,
+                labels,
+            }),
+        }
+    }
+
+    /// For each label sources put on data or sinks received, the number of calls to sources which did, the number
+    /// of findings whose sink received it, and the number of calls to each sanitizer which received it
+    /// and removed it, or changed it into another label.
+    pub(crate) fn coverage(&self, tcx: TyCtxt<'_>, info: &AttrInfo) -> String {
+        // Sanitizers changing a label make labels no source puts on data, which findings may still have.
+        let sourced = self.source_calls.iter().map(|call| call.labels);
+        let found = self.findings.iter().map(|finding| finding.labels);
+        let mut labels = sourced
+            .chain(found)
+            .fold(Labels::EMPTY, Labels::union)
+            .intersection(info.reported_labels())
+            .iter()
+            .map(|label| (info.label_name(tcx, label), label))
+            .collect::<Vec<_>>();
+        labels.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut coverage = "coverage by label:\n".to_owned();
+        for (name, label) in labels {
+            let sources = self
+                .source_calls
+                .iter()
+                .filter(|call| call.labels.contains(label))
+                .count();
+            let findings = self
+                .findings
+                .iter()
+                .filter(|finding| finding.labels.contains(label))
+                .count();
+            let mut sanitizers = self
+                .sanitizer_calls
+                .iter()
+                .filter(|call| {
+                    let removed = info.sanitized_labels(call.sanitizer);
+                    let relabeled = info.relabels.get(&call.sanitizer).map(|&(from, _)| from);
+                    call.received.contains(label)
+                        && (removed.contains(label) || relabeled == Some(label))
+                })
+                .fold(Vec::<(String, usize)>::new(), |mut sanitizers, call| {
+                    let path = tcx.def_path_str(call.sanitizer);
+                    match sanitizers.iter_mut().find(|(named, _)| *named == path) {
+                        Some((_, calls)) => *calls += 1,
+                        None => sanitizers.push((path, 1)),
+                    }
+                    sanitizers
+                });
+            sanitizers.sort();
+
+            let sanitized = if sanitizers.is_empty() {
+                "never sanitized".to_owned()
+            } else {
+                let sanitizers = sanitizers.iter().map(|(path, calls)| {
+                    format!("`{}` at {}", path, plural(*calls, "call", "calls"))
+                });
+                format!("sanitized by {}", sanitizers.collect::<Vec<_>>().join(", "))
+            };
+            let _ = writeln!(
+                coverage,
+                "  `{}`: {}, {}, {}",
+                name,
+                plural(sources, "source call", "source calls"),
+                plural(findings, "finding", "findings"),
+                sanitized
+            );
+        }
+        coverage
+    }
+
+    /// Warns about the calls to sanitizers whose input was never tainted,
+    /// unless a `// taint-ignore:` comment suppresses them.
+    pub(crate) fn emit_clean_sanitizers(&self, tcx: TyCtxt<'_>, info: &AttrInfo) {
+        let clean = self.sanitizer_calls.iter().filter(|call| {
+            call.received.is_empty() && info.ignore(call.span, ErrorCode::T0008).is_none()
+        });
+        for call in clean {
+            Diagnostics::new(tcx).warning_with_notes(
+                call.span,
+                ErrorCode::T0008,
+                format!(
+                    "sanitizer `{}` is only called here with input which is never tainted",
+                    tcx.def_path_str(call.sanitizer)
+                ),
+                &[(
+                    None,
+                    format!(
+                        "in every context `{}` was analyzed in; the call may be dead defensive code, or sanitize other data than was tainted",
+                        tcx.def_path_str(call.function)
+                    ),
+                )],
+            );
+        }
+    }
+
+    /// The call to `Clone::clone` at `span` in `function` cloned a value carrying `labels`,
+    /// which the config tracks the clones of.
+    pub(crate) fn cloned(&mut self, function: DefId, span: Span, labels: Labels) {
+        let clone = self
+            .clones
+            .iter_mut()
+            .find(|clone| (clone.function, clone.span) == (function, span));
+        match clone {
+            Some(clone) => clone.labels = clone.labels.union(labels),
+            None => self.clones.push(TrackedClone {
+                function,
+                span,
+                labels,
+            }),
+        }
+    }
+
+    /// Warns about the clones of values carrying labels the config tracks the clones of,
+    /// unless a `// taint-ignore:` comment suppresses them.
+    pub(crate) fn emit_tracked_clones(&self, tcx: TyCtxt<'_>, info: &AttrInfo) {
+        let reported = self
+            .clones
+            .iter()
+            .filter(|clone| info.ignore(clone.span, ErrorCode::T0013).is_none());
+        for clone in reported {
+            let labels = clone
+                .labels
+                .iter()
+                .map(|label| format!("`{}`", info.label_name(tcx, label)))
+                .collect::<Vec<_>>();
+            Diagnostics::new(tcx).warning_with_notes(
+                clone.span,
+                ErrorCode::T0013,
+                format!("a value labeled {} is cloned here", labels.join(", ")),
+                &[(
+                    None,
+                    "the config tracks the clones of values with these labels, whether or not they reach a sink"
+                        .to_owned(),
+                )],
+            );
+        }
+    }
+
+    /// The call to `callee`, which requires taint, at `span` in `function` received `received`,
+    /// in one of the contexts `function` is analyzed in.
+    pub(crate) fn required_taint_called(
+        &mut self,
+        function: DefId,
+        span: Span,
+        callee: DefId,
+        received: Labels,
+    ) {
+        let call = self
+            .required_taint_calls
+            .iter_mut()
+            .find(|call| (call.function, call.span, call.callee) == (function, span, callee));
+        match call {
+            Some(call) => call.received = call.received.union(received),
+            None => self.required_taint_calls.push(RequiredTaintCall {
+                function,
+                span,
+                callee,
+                received,
+            }),
+        }
+    }
+
+    /// Reports the calls to functions requiring taint whose input never carried what they require,
+    /// unless a `// taint-ignore:` comment suppresses them. Returns the number of errors reported.
+    pub(crate) fn emit_missing_taint(&self, tcx: TyCtxt<'_>, info: &AttrInfo) -> usize {
+        let missing = self.required_taint_calls.iter().filter(|call| {
+            !info.satisfies_required_taint(call.callee, call.received)
+                && info.ignore(call.span, ErrorCode::T0010).is_none()
+        });
+        let mut count = 0;
+        for call in missing {
+            let required = match info.required_taint[&call.callee] {
+                Some(label) => format!("input labeled `{}`", info.label_name(tcx, label)),
+                None => "tainted input".to_owned(),
+            };
+            Diagnostics::new(tcx).error_with_notes(
+                call.span,
+                ErrorCode::T0010,
+                format!(
+                    "`{}` requires {}, but is called here with input which never carries it",
+                    tcx.def_path_str(call.callee),
+                    required
+                ),
+                &[(
+                    None,
+                    format!(
+                        "in every context `{}` was analyzed in; the input may be a scrubbed copy of what was required",
+                        tcx.def_path_str(call.function)
+                    ),
+                )],
+            );
+            count += 1;
+        }
+        count
+    }
+
+    /// Data carrying `labels` was written into the storage field `field` at `span` in `function`.
+    pub(crate) fn stored(&mut self, function: DefId, span: Span, field: DefId, labels: Labels) {
+        let write = self
+            .stored
+            .iter_mut()
+            .find(|write| (write.function, write.span, write.field) == (function, span, field));
+        match write {
+            Some(write) => write.labels = write.labels.union(labels),
+            None => self.stored.push(StoredWrite {
+                function,
+                span,
+                field,
+                labels,
+            }),
+        }
+    }
+
+    /// A value carrying `labels`, holding the storage fields `fields`, crossed `boundary` at `span`.
+    pub(crate) fn crossed(
+        &mut self,
+        span: Span,
+        boundary: String,
+        fields: Vec<DefId>,
+        labels: Labels,
+    ) {
+        let crossing = self
+            .crossings
+            .iter_mut()
+            .find(|crossing| crossing.span == span && crossing.fields == fields);
+        match crossing {
+            Some(crossing) => crossing.labels = crossing.labels.union(labels),
+            None => self.crossings.push(Crossing {
+                span,
+                boundary,
+                fields,
+                labels,
+            }),
+        }
+    }
+
+    /// Warns about the writes of tainted data into storage fields which a value holding the field,
+    /// and carrying some of the data's labels, crossed a boundary with anywhere in the analysis,
+    /// unless a `// taint-ignore:` comment suppresses them. Values are matched by their type,
+    /// so each crossing of such a value is noted.
+    pub(crate) fn emit_stored_crossings(&self, tcx: TyCtxt<'_>, info: &AttrInfo) {
+        for write in &self.stored {
+            let field = tcx.def_path_str(write.field);
+            let notes = self
+                .crossings
+                .iter()
+                .filter(|crossing| {
+                    crossing.fields.contains(&write.field)
+                        && !crossing.labels.intersection(write.labels).is_empty()
+                })
+                .map(|crossing| {
+                    (
+                        Some(crossing.span),
+                        format!(
+                            "a value holding `{}` crosses `{}` here",
+                            field, crossing.boundary
+                        ),
+                    )
+                })
+                .collect::<Vec<_>>();
+            if notes.is_empty() || info.ignore(write.span, ErrorCode::T0009).is_some() {
+                continue;
+            }
+            Diagnostics::new(tcx).warning_with_notes(
+                write.span,
+                ErrorCode::T0009,
+                format!(
+                    "tainted input is stored into `{}`, which crosses a boundary",
+                    field
+                ),
+                &notes,
+            );
+        }
+    }
+
+    /// The construct at `span`, described by `construct`, was not modeled, leaving the labels
+    /// of its destination as they were.
+    pub(crate) fn unmodeled(&mut self, span: Span, construct: &'static str) {
+        if !self.unmodeled.contains(&(span, construct)) {
+            self.unmodeled.push((span, construct));
+        }
+    }
+
+    /// Reports the constructs which were not modeled as errors, in the order of the source.
+    /// Returns how many were reported.
+    pub(crate) fn emit_unmodeled(&self, tcx: TyCtxt<'_>) -> usize {
+        let mut unmodeled = self.unmodeled.clone();
+        unmodeled.sort_by_key(|&(span, _)| span.lo());
+        for &(span, construct) in &unmodeled {
+            Diagnostics::new(tcx).error(
+                span,
+                ErrorCode::T0012,
+                format!(
+                    "{} is not modeled, so the labels it may carry are lost",
+                    construct
+                ),
+            );
+        }
+        unmodeled.len()
+    }
+
+    /// Whether a recursive call was cut off in `function`, or in a callee whose summary it used.
+    pub(crate) fn is_approximated(&self, function: DefId) -> bool {
+        self.approximated.iter().any(|approximated| {
+            approximated.function == function
+                && !matches!(
+                    approximated.approximation,
+                    Approximation::WithoutMir | Approximation::Naked
+                )
+        })
+    }
+
+    /// The summary of `function`, called with `init` at `call_site`, was reused by a caller whose stack is `stack`,
+    /// so the findings in it, or in its callees, are also reached through `stack`.
+    pub(crate) fn reused(
+        &mut self,
+        function: DefId,
+        init: &InitSet,
+        call_site: Option<Span>,
+        stack: &[Called],
+    ) {
+        let frames = stack
+            .iter()
+            .map(|(f, i, _)| (*f, i.clone()))
+            .collect::<Vec<_>>();
+        for finding in &mut self.findings {
+            let reached = finding
+                .paths
+                .iter()
+                .filter_map(|path| {
+                    let at = path.iter().position(|(f, i)| *f == function && i == init)?;
+                    Some(
+                        frames
+                            .iter()
+                            .chain(&path[at..])
+                            .cloned()
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            for path in reached {
+                finding.add_path(path);
+            }
+            let contexts = finding
+                .contexts
+                .iter()
+                .filter_map(|context| {
+                    let at = context
+                        .iter()
+                        .position(|(f, i, _)| *f == function && i == init)?;
+                    let called = (function, init.clone(), call_site);
+                    let inner = context[at + 1..].iter().cloned();
+                    Some(stack.iter().cloned().chain([called]).chain(inner).collect())
+                })
+                .collect::<Vec<_>>();
+            for context in contexts {
+                finding.add_context(context);
+            }
+        }
+    }
+
+    /// Emits every finding as an error, or a warning for codes which are and, with `fail_level`,
+    /// for findings whose confidence is not high, in the order they were found,
+    /// with a note pointing at each variable the tainted arguments were read from,
+    /// one on the model it was found by inside a dependency, if any,
+    /// one pointing at the call to the source its tainted input came from if other findings share it,
+    /// one for each call it was first reached through with tainted arguments, and one for each other context
+    /// it was found in, up to a few,
+    /// one for each chain of calls from an entry point it was reached through, up to a few,
+    /// one for each call in its function whose effect was approximated and which the sink's input was computed from,
+    /// one for each label received besides the one in the message,
+    /// one for each profile of the entry points it was found from,
+    /// and one on its confidence, unless nothing lowered it.
+    /// Returns how many were emitted as errors.
+    pub(crate) fn emit(&self, tcx: TyCtxt<'_>, fail_level: FailLevel) -> usize {
+        let mut errors = 0;
+        let sources = self
+            .findings
+            .iter()
+            .map(|finding| self.source_of(tcx, finding).map(|call| call.span))
+            .collect::<Vec<_>>();
+        for (finding, source) in self.findings.iter().zip(&sources) {
+            let mut notes = finding
+                .variables
+                .iter()
+                .map(|(name, span)| (Some(*span), format!("argument `{}` is tainted", name)))
+                .collect::<Vec<_>>();
+            notes.extend(finding.model.iter().map(|model| (None, model.clone())));
+            if let Some(source) = source {
+                let family = sources
+                    .iter()
+                    .filter(|&other| other == &Some(*source))
+                    .count();
+                if family > 1 {
+                    let others = plural(family - 1, "other finding", "other findings");
+                    let note = format!("its tainted input comes from this source, like {}", others);
+                    notes.push((Some(*source), note));
+                }
+            }
+            notes.extend(finding.context_notes(tcx));
+            notes.extend(finding.chains(tcx).into_iter().map(|chain| (None, chain)));
+            notes.extend(
+                self.approximations(tcx, finding)
+                    .into_iter()
+                    .map(|approximated| {
+                        let note = format!(
+                            "analysis of `{}` was approximated: {}",
+                            tcx.def_path_str(approximated.callee),
+                            approximated.approximation.reason()
+                        );
+                        (Some(approximated.span), note)
+                    }),
+            );
+            notes.extend(
+                finding
+                    .also
+                    .iter()
+                    .map(|label| (None, format!("also reported for data labeled `{}`", label))),
+            );
+            notes.extend(
+                finding
+                    .profiles
+                    .iter()
+                    .map(|profile| (None, format!("found with profile `{}`", profile))),
+            );
+            let confidence = self.confidence(tcx, finding);
+            let doubted = fail_level == FailLevel::HighConfidence && !confidence.is_high();
+            if !confidence.doubts.is_empty() {
+                let mut note = format!(
+                    "its confidence is {} of 100, as {}",
+                    confidence.score,
+                    confidence.doubts.join(", and ")
+                );
+                if doubted {
+                    note.push_str(", so `--taint-fail-level=high-confidence` makes it a warning");
+                }
+                notes.push((None, note));
+            }
+            let diagnostics = Diagnostics::new(tcx);
+            if finding.code.is_warning() || doubted {
+                diagnostics.warning_with_notes(
+                    finding.span,
+                    finding.code,
+                    finding.message.clone(),
+                    &notes,
+                );
+            } else {
+                diagnostics.error_with_notes(
+                    finding.span,
+                    finding.code,
+                    finding.message.clone(),
+                    &notes,
+                );
+                errors += 1;
+            }
+        }
+        errors + self.emit_resumed(tcx, fail_level)
+    }
+
+    /// Emits the findings of the checkpoint the run resumed from which it did not find again, like those of
+    /// the entry points it did not analyze again, returning the number of errors.
+    fn emit_resumed(&self, tcx: TyCtxt<'_>, fail_level: FailLevel) -> usize {
+        let mut errors = 0;
+        for finding in self.resumed_only(tcx) {
+            let (Some(code), Some(span)) = (
+                ErrorCode::from_code(&finding.code),
+                compiler::span_at(tcx, &finding.file, finding.start, finding.end),
+            ) else {
+                continue;
+            };
+            let mut notes = finding
+                .context
+                .iter()
+                .map(|context| (None, context.clone()))
+                .collect::<Vec<_>>();
+            notes.push((
+                None,
+                "found before the run resumed from its checkpoint, with --taint-resume".to_owned(),
+            ));
+            let doubted = fail_level == FailLevel::HighConfidence && !finding.confidence.is_high();
+            let diagnostics = Diagnostics::new(tcx);
+            if code.is_warning() || doubted {
+                diagnostics.warning_with_notes(span, code, finding.message, &notes);
+            } else {
+                diagnostics.error_with_notes(span, code, finding.message, &notes);
+                errors += 1;
+            }
+        }
+        errors
+    }
+
+    /// The call to a source the tainted input of `finding` came from: one its sink depends on
+    /// and which put one of its labels on its data, or else one the call to its function depends on
+    /// in one of its callers, from the innermost one out.
+    fn source_of(&self, tcx: TyCtxt<'_>, finding: &Recorded) -> Option<&SourceCall> {
+        self.source_site(tcx, finding).map(|(call, _)| call)
+    }
+
+    /// The call to a source the tainted input of `finding` came from, and the sink or call to its function
+    /// which depends on it.
+    fn source_site(&self, tcx: TyCtxt<'_>, finding: &Recorded) -> Option<(&SourceCall, Span)> {
+        let sites = [(finding.function, finding.span)];
+        sites
+            .iter()
+            .chain(&finding.callers)
+            .find_map(|&(function, span)| {
+                let call = self.source_calls.iter().find(|call| {
+                    call.function == function
+                        && !call.labels.intersection(finding.labels).is_empty()
+                        && slice::depends_on(tcx, function, span, call.span)
+                })?;
+                Some((call, span))
+            })
+    }
+
+    /// The calls in the function of `finding` whose effect was approximated, and which its sink depends on.
+    fn approximations(&self, tcx: TyCtxt<'_>, finding: &Recorded) -> Vec<&Approximated> {
+        self.approximated
+            .iter()
+            .filter(|approximated| approximated.function == finding.function)
+            .filter(|approximated| {
+                slice::depends_on(tcx, finding.function, finding.span, approximated.span)
+            })
+            .collect()
+    }
+
+    /// How confident the analysis is that the flow to `finding` is real. It is lowered when the call
+    /// to its source does not run on every path to the sink, for each call the sink depends on whose
+    /// effect was approximated, and when it was found by a model inside a dependency rather than by
+    /// analyzing its code. Implicit flows are not tracked, so every finding is of an explicit flow.
+    fn confidence(&self, tcx: TyCtxt<'_>, finding: &Recorded) -> Confidence {
+        let mut confidence = Confidence::certain();
+        if let Some((call, site)) = self.source_site(tcx, finding) {
+            if slice::runs_before(tcx, call.function, call.span, site) == Some(false) {
+                let doubt = format!(
+                    "the call to `{}` its input comes from does not run on every path to the sink",
+                    tcx.def_path_str(call.source)
+                );
+                confidence.doubt(30, doubt);
+            }
+        }
+        for approximated in self.approximations(tcx, finding) {
+            let penalty = match approximated.approximation {
+                Approximation::WithoutMir | Approximation::Naked => 20,
+                Approximation::Recursion | Approximation::Callee => 25,
+            };
+            let doubt = format!(
+                "the analysis of `{}` was approximated",
+                tcx.def_path_str(approximated.callee)
+            );
+            confidence.doubt(penalty, doubt);
+        }
+        if let Some(model) = &finding.model {
+            confidence.doubt(10, format!("it was found by {} inside a dependency", model));
+        }
+        confidence
+    }
+
+    /// The family of the findings whose tainted input came from `call`, without its findings yet.
+    fn family(&self, tcx: TyCtxt<'_>, call: &SourceCall) -> Option<Family> {
+        let SourceRange { file, start, end } = compiler::source_range(tcx, call.span)?;
+        let at = |other: &SourceCall| compiler::source_range(tcx, other.span).map(|r| r.start);
+        let occurrence = self
+            .source_calls
+            .iter()
+            .filter(|other| (other.function, other.source) == (call.function, call.source))
+            .filter(|other| at(other).map_or(false, |other| other < start))
+            .count();
+        let function = tcx.def_path_str(call.function);
+        let source = tcx.def_path_str(call.source);
+        Some(Family {
+            id: report::family_id(&function, &source, occurrence),
+            source,
+            function,
+            file,
+            start,
+            end,
+            findings: vec![],
+        })
+    }
+
+    /// How many findings were recorded so far.
+    pub(crate) fn count(&self, tcx: TyCtxt<'_>) -> usize {
+        self.findings.len() + self.resumed_only(tcx).len()
+    }
+
+    /// The findings of the checkpoint the run resumed from which it did not find again.
+    fn resumed_only(&self, tcx: TyCtxt<'_>) -> Vec<report::Finding> {
+        if self.resumed.findings.is_empty() {
+            return vec![];
+        }
+        let found = Report {
+            findings: self.reported(tcx).into_iter().map(|(_, f)| f).collect(),
+            ..Report::default()
+        };
+        let count = found.findings.len();
+        let mut findings = found.with_resumed(self.resumed.clone()).findings;
+        findings.split_off(count)
+    }
+
+    pub(crate) fn report(&self, tcx: TyCtxt<'_>) -> Report {
+        let reported = self.reported(tcx);
+        let mut families: Vec<Family> = vec![];
+        for (recorded, finding) in &reported {
+            let Some(id) = &finding.family else {
+                continue;
+            };
+            match families.iter_mut().find(|family| &family.id == id) {
+                Some(family) => family.findings.push(finding.fingerprint.clone()),
+                None => {
+                    let call = self.source_of(tcx, recorded);
+                    if let Some(mut family) = call.and_then(|call| self.family(tcx, call)) {
+                        family.findings.push(finding.fingerprint.clone());
+                        families.push(family);
+                    }
+                }
+            }
+        }
+        families.sort_by(|a, b| (&a.file, a.start).cmp(&(&b.file, b.start)));
+        let report = Report {
+            choke_points: choke_points(tcx, &reported),
+            findings: reported.into_iter().map(|(_, finding)| finding).collect(),
+            families,
+        };
+        report.with_resumed(self.resumed.clone())
+    }
+
+    /// The call sites of the sinks found, for fuzzers.
+    pub(crate) fn targets(&self, tcx: TyCtxt<'_>) -> Targets {
+        Targets {
+            targets: self
+                .reported(tcx)
+                .into_iter()
+                .map(|(recorded, finding)| Target {
+                    fingerprint: finding.fingerprint,
+                    function: finding.function,
+                    sink: finding.sink,
+                    label: finding.label,
+                    arguments: recorded.arguments.clone(),
+                    file: finding.file,
+                    line: finding.start.line,
+                    column: finding.start.column,
+                })
+                .collect(),
+        }
+    }
+
+    /// A program reproducing each finding, by its fingerprint, reduced to the slice of its function.
+    pub(crate) fn reproducers(&self, tcx: TyCtxt<'_>, facts: &Facts) -> Vec<(String, String)> {
+        self.reported(tcx)
+            .into_iter()
+            .map(|(recorded, finding)| {
+                let (function, sink) = (recorded.function, recorded.span);
+                let spans = slice::slice(tcx, facts, function, sink);
+                let flow = Flow {
+                    finding: &finding,
+                    source: self.source_of(tcx, recorded).map(|call| call.source),
+                    calls: slice::calls(tcx, facts, function, sink),
+                    slice: slice::render(tcx, &spans, sink),
+                };
+                let program = reproducer::program(tcx, &flow);
+                (finding.fingerprint, program)
+            })
+            .collect()
+    }
+
+    /// The function and the span of the finding reported with `fingerprint`, if there is one.
+    pub(crate) fn find(&self, tcx: TyCtxt<'_>, fingerprint: &str) -> Option<(DefId, Span)> {
+        self.reported(tcx)
+            .into_iter()
+            .find(|(_, finding)| finding.fingerprint == fingerprint)
+            .map(|(recorded, _)| (recorded.function, recorded.span))
+    }
+
+    /// Each finding with a position, as reported, along with how it was recorded.
+    fn reported(&self, tcx: TyCtxt<'_>) -> Vec<(&Recorded, report::Finding)> {
+        let mut located = self
+            .findings
+            .iter()
+            .filter_map(|finding| {
+                let range = compiler::source_range(tcx, finding.span)?;
+                Some((tcx.def_path_str(finding.function), range, finding))
+            })
+            .collect::<Vec<_>>();
+        located.sort_by(|(f, a, _), (g, b, _)| (&a.file, a.start, f).cmp(&(&b.file, b.start, g)));
+
+        let mut findings: Vec<(&Recorded, report::Finding)> = vec![];
+        for (function, SourceRange { file, start, end }, finding) in located {
+            let occurrence = findings
+                .iter()
+                .map(|(_, f)| f)
+                .filter(|f| f.function == function && f.sink == finding.sink)
+                .filter(|f| f.label == finding.label)
+                .count();
+            let reported = report::Finding {
+                fingerprint: report::fingerprint(
+                    &function,
+                    &finding.sink,
+                    &finding.label,
+                    occurrence,
+                ),
+                code: finding.code.as_str().to_owned(),
+                message: finding.message.clone(),
+                context: finding
+                    .contexts
+                    .first()
+                    .map_or_else(Vec::new, |context| context_notes(tcx, context))
+                    .into_iter()
+                    .map(|(call_site, note)| {
+                        match call_site.and_then(|span| compiler::source_range(tcx, span)) {
+                            Some(range) => format!(
+                                "{} (from caller at {}:{})",
+                                note, range.file, range.start.line
+                            ),
+                            None => note,
+                        }
+                    })
+                    .collect(),
+                function,
+                sink: finding.sink.clone(),
+                label: finding.label.clone(),
+                profiles: {
+                    let mut profiles = finding.profiles.clone();
+                    profiles.sort();
+                    profiles
+                },
+                family: self
+                    .source_of(tcx, finding)
+                    .and_then(|call| self.family(tcx, call))
+                    .map(|family| family.id),
+                // Given from the config once the findings of the run are gathered.
+                owner: None,
+                confidence: self.confidence(tcx, finding),
+                file,
+                start,
+                end,
+            };
+            findings.push((finding, reported));
+        }
+        findings
+    }
+}
+
+/// Greedily picks the point which the most findings not cleared yet pass through, until every
+/// finding is cleared, which finds a set of points close to the smallest one.
+/// Each lists every finding it clears, including those cleared by a point picked before it.
+fn choke_points(tcx: TyCtxt<'_>, reported: &[(&Recorded, report::Finding)]) -> Vec<ChokePoint> {
+    let findings = reported
+        .iter()
+        .map(|(recorded, finding)| (recorded.points(tcx), &finding.fingerprint))
+        .collect::<Vec<_>>();
+    let mut uncleared = findings.iter().collect::<Vec<_>>();
+    let mut points = vec![];
+    while !uncleared.is_empty() {
+        let mut best: Option<(&Point, usize)> = None;
+        for point in uncleared.iter().flat_map(|(points, _)| points) {
+            let clears = uncleared
+                .iter()
+                .filter(|(points, _)| points.contains(point))
+                .count();
+            if best.map_or(true, |(_, most)| clears > most) {
+                best = Some((point, clears));
+            }
+        }
+        // Every finding passes through its sink, so there always is a point left.
+        let Some((point, _)) = best else {
+            break;
+        };
+        points.push(ChokePoint {
+            function: point.0.clone(),
+            argument: point.1,
+            findings: findings
+                .iter()
+                .filter(|(points, _)| points.contains(point))
+                .map(|(_, fingerprint)| (*fingerprint).clone())
+                .collect(),
+        });
+        uncleared.retain(|(points, _)| !points.contains(point));
+    }
+    points
+}
+
+/// Notes on the calls of `context` through which its innermost function was reached with tainted arguments,
+/// from the innermost one out, like ``when `process(data)` is called with tainted `data` ``.
+fn context_notes(tcx: TyCtxt<'_>, context: &[Called]) -> Vec<(Option<Span>, String)> {
+    context
+        .iter()
+        .rev()
+        .filter_map(|(function, init, call_site)| {
+            let names = compiler::arg_names(tcx, *function);
+            let name = |ix: usize| names.get(ix).map_or("_", String::as_str);
+            let tainted = init
+                .iter()
+                .enumerate()
+                .filter(|(_, labels)| !labels.is_empty())
+                .map(|(ix, _)| format!("`{}`", name(ix)))
+                .collect::<Vec<_>>();
+            if tainted.is_empty() {
+                return None;
+            }
+            let function_name = match tcx.opt_item_name(*function) {
+                Some(name) if !tcx.is_closure(*function) => name.to_string(),
+                _ => tcx.def_path_str(*function),
+            };
+            let params = (0..init.len()).map(name).collect::<Vec<_>>();
+            let note = format!(
+                "when `{}({})` is called with tainted {}",
+                function_name,
+                params.join(", "),
+                tainted.join(", ")
+            );
+            Some((*call_site, note))
+        })
+        .collect()
+}
@@ -0,0 +1,199 @@
+//! Converting summaries to and from [`crate::summaries`],
+//! for `--taint-export-summaries` and `--taint-import-summaries`.
+
+use std::collections::HashMap;
+
+use crate::{
+    compiler::{DefId, Idx, Local, TyCtxt},
+    error::Error,
+    eval::attributes::AttrInfo,
+    summaries::{Flow, FunctionSummary, Summaries},
+};
+
+use super::{
+    ir::CallEffect,
+    labels::Labels,
+    taint_analysis::{Contexts, Summary},
+    taint_domain::TaintDomain,
+};
+
+/// Where the labels of a value come from, with the labels resolved.
+#[derive(Debug, Default, Clone)]
+struct ResolvedFlow {
+    from: Vec<usize>,
+    labels: Labels,
+}
+
+impl ResolvedFlow {
+    fn labels(&self, init: &[Labels]) -> Labels {
+        self.from
+            .iter()
+            .filter_map(|&ix| init.get(ix))
+            .fold(self.labels, |labels, &arg| labels.union(arg))
+    }
+}
+
+#[derive(Debug)]
+struct ImportedSummary {
+    returns: ResolvedFlow,
+    args: Vec<ResolvedFlow>,
+    points_into: Vec<usize>,
+}
+
+/// Summaries read with `--taint-import-summaries`, by the path of the function.
+#[derive(Default, Debug)]
+pub(crate) struct Imported {
+    summaries: HashMap<String, ImportedSummary>,
+}
+
+impl Imported {
+    /// Adds `summaries`, which replace those of the same functions added before.
+    pub(crate) fn add(
+        &mut self,
+        tcx: TyCtxt<'_>,
+        info: &AttrInfo,
+        summaries: &Summaries,
+    ) -> Result<(), Error> {
+        let flow = |flow: &Flow| {
+            let labels = flow.labels.iter().try_fold(Labels::EMPTY, |labels, name| {
+                let label = info.label_named(tcx, name).ok_or_else(|| {
+                    Error::format("summaries", format!("unknown label `{}`", name))
+                })?;
+                Ok(labels.union(label.into()))
+            })?;
+            Ok(ResolvedFlow {
+                from: flow.from.clone(),
+                labels,
+            })
+        };
+
+        for summary in &summaries.summaries {
+            let imported_summary = ImportedSummary {
+                returns: flow(&summary.returns)?,
+                args: summary
+                    .args
+                    .iter()
+                    .map(flow)
+                    .collect::<Result<_, Error>>()?,
+                points_into: summary.points_into.clone(),
+            };
+            let function = info.canonical_path(&summary.function).to_owned();
+            self.summaries.insert(function, imported_summary);
+        }
+        Ok(())
+    }
+
+    /// What calling the function at `path` with arguments carrying `init` does,
+    /// if a summary of it was imported.
+    pub(crate) fn effect(&self, path: &str, init: &[Labels]) -> Option<CallEffect> {
+        let summary = self.summaries.get(path)?;
+        Some(CallEffect::Summary {
+            returns: summary.returns.labels(init),
+            // Arguments the summary says nothing about keep their labels.
+            args: (0..init.len())
+                .map(|ix| {
+                    summary
+                        .args
+                        .get(ix)
+                        .map_or(init[ix], |flow| flow.labels(init))
+                })
+                .collect(),
+            points_into: summary.points_into.clone(),
+        })
+    }
+}
+
+/// Summaries of every function which was analyzed, independent of the context.
+///
+/// A function analyzed in several contexts has its summaries joined: a value is said to carry the labels
+/// of an argument if it carried any of them in some context, and the labels no argument carried otherwise.
+pub(crate) fn export(tcx: TyCtxt<'_>, info: &AttrInfo, contexts: &Contexts<'_>) -> Summaries {
+    export_named(tcx, info, contexts, |id| Some(tcx.def_path_str(id)))
+}
+
+/// Like [`export`], for the functions `name` gives a path, written with that path.
+pub(crate) fn export_named(
+    tcx: TyCtxt<'_>,
+    info: &AttrInfo,
+    contexts: &Contexts<'_>,
+    name: impl Fn(DefId) -> Option<String>,
+) -> Summaries {
+    let mut functions: HashMap<DefId, (ResolvedFlow, Vec<ResolvedFlow>, Vec<usize>)> =
+        HashMap::new();
+    for ((id, _, init), summary) in contexts {
+        let Some(Summary {
+            state,
+            returns,
+            points_into,
+            ..
+        }) = summary
+        else {
+            continue;
+        };
+        let (joined_returns, joined_args, joined_points_into) = functions
+            .entry(*id)
+            .or_insert_with(|| (ResolvedFlow::default(), vec![], vec![]));
+
+        join_flow(joined_returns, *returns, init);
+        joined_args.resize_with(init.len(), ResolvedFlow::default);
+        for (ix, joined) in joined_args.iter_mut().enumerate() {
+            join_flow(joined, state.get_taint(Local::new(ix + 1)), init);
+        }
+        for &ix in points_into {
+            if !joined_points_into.contains(&ix) {
+                joined_points_into.push(ix);
+            }
+        }
+    }
+
+    let flow = |flow: ResolvedFlow| Flow {
+        from: flow.from,
+        labels: flow
+            .labels
+            .iter()
+            .map(|label| info.label_name(tcx, label))
+            .collect(),
+    };
+    let mut summaries = functions
+        .into_iter()
+        .filter_map(|(id, flows)| Some((name(id)?, flows)))
+        .map(|(function, (returns, args, mut points_into))| {
+            points_into.sort_unstable();
+            FunctionSummary {
+                function,
+                returns: flow(returns),
+                args: args
+                    .into_iter()
+                    .enumerate()
+                    .map(|(ix, mut arg)| {
+                        // Contexts in which an argument was clean cannot tell whether it keeps its labels,
+                        // so it is assumed to.
+                        if !arg.from.contains(&ix) {
+                            arg.from.push(ix);
+                            arg.from.sort_unstable();
+                        }
+                        flow(arg)
+                    })
+                    .collect(),
+                points_into,
+            }
+        })
+        .collect::<Vec<_>>();
+    summaries.sort_by(|a, b| a.function.cmp(&b.function));
+    Summaries { summaries }
+}
+
+/// Adds to `flow` where the labels `labels`, computed in the context `init`, came from.
+fn join_flow(flow: &mut ResolvedFlow, labels: Labels, init: &[Labels]) {
+    let mut unexplained = labels;
+    for (ix, &arg) in init.iter().enumerate() {
+        if !labels.intersection(arg).is_empty() {
+            if !flow.from.contains(&ix) {
+                flow.from.push(ix);
+                flow.from.sort_unstable();
+            }
+            unexplained = unexplained.without(arg);
+        }
+    }
+    flow.labels = flow.labels.union(unexplained);
+}
@@ -0,0 +1,113 @@
+//! Checking that the analysis only ever moves up the lattice, for `--taint-debug-invariants`.
+//!
+//! The fixpoint iteration only terminates, and only finds every flow, if joins never lose labels
+//! and transfer functions are monotone: a block entered with more labels than before
+//! must not exit with fewer. Both are checked every time a block is analyzed again,
+//! which catches mistakes in the propagation rules at the block they are made in.
+
+use std::collections::HashMap;
+
+use crate::compiler::{stable, BasicBlock, Body, Local};
+
+use super::{
+    ir::{self, Statement},
+    taint_domain::TaintState,
+};
+
+#[derive(Default, Debug)]
+pub(crate) struct Invariants {
+    /// The block being analyzed, and the state it was entered in.
+    entered: Option<(BasicBlock, TaintState<Local>)>,
+    /// The states each block was last entered and exited in.
+    visits: HashMap<BasicBlock, (TaintState<Local>, TaintState<Local>)>,
+}
+
+impl Invariants {
+    pub(crate) fn enter(&mut self, block: BasicBlock, state: &TaintState<Local>) {
+        self.entered = Some((block, state.clone()));
+    }
+
+    /// Checks the states `block` of `function` was entered in and exited in `state`
+    /// against those of its last visit.
+    pub(crate) fn exit(&mut self, function: &str, block: BasicBlock, state: &TaintState<Local>) {
+        let Some((entered, entry)) = self.entered.take() else {
+            return;
+        };
+        if entered != block {
+            return;
+        }
+
+        if let Some((last_entry, last_exit)) = self.visits.get(&block) {
+            if let Some(local) = last_entry.not_below(&entry) {
+                panic!(
+                    "the join lost the labels of {:?} at the entry of {:?} in `{}`",
+                    local, block, function
+                );
+            }
+            if let Some(local) = last_exit.not_below(state) {
+                panic!(
+                    "the transfer function of {:?} in `{}` is not monotone: {:?} lost labels",
+                    block, function, local
+                );
+            }
+        }
+        self.visits.insert(block, (entry, state.clone()));
+    }
+}
+
+/// Checks, for `--taint-debug-stable-mir`, that the MIR of `function` lowers the same way from both representations.
+pub(crate) fn check_lowering(function: &str, internal: &Body<'_>, stable: &stable::Body) {
+    let (internal, stable): (Vec<Statement>, Vec<Statement>) =
+        (ir::assignments(internal), ir::stable_assignments(stable));
+    if let Some(ix) =
+        (0..internal.len().max(stable.len())).find(|&ix| internal.get(ix) != stable.get(ix))
+    {
+        panic!(
+            "the stable MIR of `{}` lowers differently at assignment {}: {:?} instead of {:?}",
+            function,
+            ix,
+            stable.get(ix),
+            internal.get(ix)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        analysis::{labels::TAINT, taint_domain::TaintDomain},
+        compiler::Idx,
+    };
+
+    fn state(tainted: &[usize]) -> TaintState<Local> {
+        let mut state = TaintState::new_empty(3);
+        for &ix in tainted {
+            state.set_taint(Local::new(ix), TAINT.into());
+        }
+        state
+    }
+
+    #[test]
+    fn growing_states_are_accepted() {
+        let mut invariants = Invariants::default();
+        let block = BasicBlock::new(0);
+
+        invariants.enter(block, &state(&[]));
+        invariants.exit("demo::main", block, &state(&[1]));
+        invariants.enter(block, &state(&[2]));
+        invariants.exit("demo::main", block, &state(&[1, 2]));
+    }
+
+    #[test]
+    #[should_panic(expected = "is not monotone")]
+    fn exits_losing_labels_are_rejected() {
+        let mut invariants = Invariants::default();
+        let block = BasicBlock::new(0);
+
+        invariants.enter(block, &state(&[]));
+        invariants.exit("demo::main", block, &state(&[1]));
+        invariants.enter(block, &state(&[2]));
+        invariants.exit("demo::main", block, &state(&[2]));
+    }
+}
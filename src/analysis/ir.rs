@@ -0,0 +1,587 @@
+//! A tiny MIR-like language describing what a statement does to taint.
+//!
+//! The transfer function lowers MIR into this language and then applies [`Statement::apply`],
+//! so the propagation rules themselves can be unit tested without compiling a whole program
+//! (see [`builder`]).
+//! Stable MIR lowers into it too (see [`stable_assignments`]), for `--taint-debug-stable-mir`.
+
+use crate::compiler::{self, stable, Idx, Local, StatementKind};
+
+use super::{
+    labels::{Label, Labels},
+    taint_domain::{PointsAwareTaintDomain, TaintDomain},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operand {
+    Local(Local),
+    Const,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Rvalue {
+    /// A value which cannot carry taint, such as a constant.
+    Clean,
+    /// A value computed from the operands, which is tainted if any of them are.
+    Derived(Vec<Operand>),
+    /// A reference to a local, or a pointer copied from one, which points to the same object.
+    Ref(Local),
+    /// A value carrying exactly the labels, like a reference to a static initialized with tainted data.
+    Labels(Labels),
+    /// A value we do not model. The destination is left as it was.
+    Unknown,
+}
+
+/// The effect of calling a function, as decided by its annotations, a model or its summary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CallEffect {
+    /// The result carries the labels.
+    Source(Labels),
+    /// The result carries the labels of the arguments, except for the ones removed,
+    /// and with the second label of `relabels` instead of the first.
+    ///
+    /// The result may borrow the original when it was already safe, like a `Cow`, so the destination
+    /// may point to it: the result is sanitized either way, but the original keeps its labels.
+    Sanitizer {
+        removes: Labels,
+        relabels: Option<(Label, Label)>,
+    },
+    /// None of the arguments may carry any of the labels checked.
+    Sink { checks: Labels },
+    /// The result carries the labels of the arguments.
+    Propagate,
+    /// The effect computed by analyzing the callee:
+    /// the labels of the data it returns, the labels of each argument when it returns,
+    /// and the arguments whose referents the returned value may point into, like `Vec::as_mut_ptr`.
+    Summary {
+        returns: Labels,
+        args: Vec<Labels>,
+        points_into: Vec<usize>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Statement {
+    Assign(Local, Rvalue),
+    Call {
+        effect: CallEffect,
+        args: Vec<Operand>,
+        destination: Local,
+    },
+}
+
+impl Operand {
+    fn taint(&self, state: &PointsAwareTaintDomain<'_, Local>) -> Labels {
+        match self {
+            Operand::Local(local) => state.get_taint(*local),
+            Operand::Const => Labels::EMPTY,
+        }
+    }
+}
+
+fn join_taint(operands: &[Operand], state: &PointsAwareTaintDomain<'_, Local>) -> Labels {
+    operands
+        .iter()
+        .fold(Labels::EMPTY, |labels, op| labels.union(op.taint(state)))
+}
+
+impl Rvalue {
+    /// The labels of the value in `state`, none if it is unknown.
+    pub(crate) fn taint(&self, state: &PointsAwareTaintDomain<'_, Local>) -> Labels {
+        match self {
+            Rvalue::Derived(operands) => join_taint(operands, state),
+            Rvalue::Ref(referent) => state.get_taint(*referent),
+            Rvalue::Labels(labels) => *labels,
+            Rvalue::Clean | Rvalue::Unknown => Labels::EMPTY,
+        }
+    }
+}
+
+impl Statement {
+    /// Applies the statement to `state`.
+    /// Returns the labels a sink received but must not, which are empty if there are none.
+    pub(crate) fn apply(&self, state: &mut PointsAwareTaintDomain<'_, Local>) -> Labels {
+        match self {
+            Statement::Assign(place, rvalue) => {
+                match rvalue {
+                    Rvalue::Clean => state.set_taint(*place, Labels::EMPTY),
+                    Rvalue::Derived(operands) => {
+                        let tainted = join_taint(operands, state);
+                        state.set_taint(*place, tainted);
+                    }
+                    Rvalue::Ref(referent) => state.add_ref(*place, *referent),
+                    Rvalue::Labels(labels) => state.set_taint(*place, *labels),
+                    Rvalue::Unknown => {}
+                }
+                Labels::EMPTY
+            }
+            Statement::Call {
+                effect,
+                args,
+                destination,
+            } => match effect {
+                CallEffect::Source(labels) => {
+                    state.set_taint(*destination, *labels);
+                    Labels::EMPTY
+                }
+                CallEffect::Sanitizer { removes, relabels } => {
+                    let mut tainted = join_taint(args, state);
+                    if let Some((from, to)) = relabels {
+                        tainted = tainted.relabeled(*from, *to);
+                    }
+                    state.set_taint_keeping_referents(*destination, tainted.without(*removes));
+                    Labels::EMPTY
+                }
+                CallEffect::Sink { checks } => join_taint(args, state).intersection(*checks),
+                CallEffect::Propagate => {
+                    let tainted = join_taint(args, state);
+                    state.set_taint(*destination, tainted);
+                    Labels::EMPTY
+                }
+                CallEffect::Summary {
+                    returns,
+                    args: arg_taints,
+                    points_into,
+                } => {
+                    if !returns.is_empty() {
+                        state.set_taint(*destination, *returns);
+                    }
+                    for &ix in points_into {
+                        if let Some(Operand::Local(local)) = args.get(ix) {
+                            state.add_ref(*destination, *local);
+                        }
+                    }
+
+                    // Check if any variables which were passed in are tainted at this point.
+                    for (arg, tainted) in args.iter().zip(arg_taints) {
+                        if let Operand::Local(local) = arg {
+                            state.set_taint(*local, *tainted);
+                        }
+                    }
+                    Labels::EMPTY
+                }
+            },
+        }
+    }
+}
+
+impl From<&compiler::Operand<'_>> for Operand {
+    fn from(operand: &compiler::Operand<'_>) -> Self {
+        match operand {
+            compiler::Operand::Copy(p) | compiler::Operand::Move(p) => Operand::Local(p.local),
+            compiler::Operand::Constant(_) => Operand::Const,
+        }
+    }
+}
+
+impl From<&compiler::Rvalue<'_>> for Rvalue {
+    fn from(rvalue: &compiler::Rvalue<'_>) -> Self {
+        use compiler::Rvalue as R;
+
+        match rvalue {
+            // If we assign a constant to a place, the place is clean.
+            R::Use(compiler::Operand::Constant(_))
+            | R::UnaryOp(_, compiler::Operand::Constant(_)) => Rvalue::Clean,
+
+            // Otherwise we propagate the taint
+            R::Use(op) | R::UnaryOp(_, op) => Rvalue::Derived(vec![op.into()]),
+            R::BinaryOp(_, box (a, b)) | R::CheckedBinaryOp(_, box (a, b)) => {
+                Rvalue::Derived(vec![a.into(), b.into()])
+            }
+            // An aggregate (including closures and generators, whose operands are their captures)
+            // is tainted if any of its parts are.
+            R::Aggregate(_, operands) => Rvalue::Derived(operands.iter().map(Into::into).collect()),
+            R::CopyForDeref(p) => Rvalue::Derived(vec![Operand::Local(p.local)]),
+            // The length of a slice built from tainted data is tainted,
+            // and so is an array repeating a tainted value.
+            R::Len(p) => Rvalue::Derived(vec![Operand::Local(p.local)]),
+            R::Repeat(op, _) => Rvalue::Derived(vec![op.into()]),
+            // A raw pointer to a place points to it just like a reference does.
+            R::Ref(_, _, p) | R::AddressOf(_, p) => Rvalue::Ref(p.local),
+            // The size, alignment or offset of a field of a type never depends on data.
+            R::NullaryOp(_, _) => Rvalue::Clean,
+            // Calls to `transmute` are lowered into a cast, which reinterprets the value like the call does.
+            R::Cast(compiler::CastKind::Transmute, op, _) => Rvalue::Derived(vec![op.into()]),
+            // A reference to a thread-local static carries the labels of its initializer,
+            // which the transfer function looks up like it does for other statics.
+            R::ThreadLocalRef(_) => Rvalue::Unknown,
+
+            R::Cast(_, _, _) | R::Discriminant(_) | R::ShallowInitBox(_, _) => Rvalue::Unknown,
+        }
+    }
+}
+
+/// The assignments of `body`, lowered, in the order of its blocks and statements.
+pub(crate) fn assignments(body: &compiler::Body<'_>) -> Vec<Statement> {
+    body.basic_blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .filter_map(|statement| match &statement.kind {
+            StatementKind::Assign(box (place, rvalue)) => {
+                Some(Statement::Assign(place.local, rvalue.into()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The assignments of the stable MIR `body`, lowered like those of the compiler's MIR by [`assignments`].
+/// The analysis itself runs on the compiler's MIR; this only lets `--taint-debug-stable-mir` compare them.
+pub(crate) fn stable_assignments(body: &stable::Body) -> Vec<Statement> {
+    body.blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .filter_map(|statement| match statement {
+            stable::Statement::Assign(place, rvalue) => {
+                Some(Statement::Assign(Local::new(place.local), rvalue.into()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+impl From<&stable::Operand> for Operand {
+    fn from(operand: &stable::Operand) -> Self {
+        match operand {
+            stable::Operand::Copy(p) | stable::Operand::Move(p) => {
+                Operand::Local(Local::new(p.local))
+            }
+            stable::Operand::Constant(_) => Operand::Const,
+        }
+    }
+}
+
+/// Lowers like the compiler's MIR does, rule for rule.
+impl From<&stable::Rvalue> for Rvalue {
+    fn from(rvalue: &stable::Rvalue) -> Self {
+        use stable::Rvalue as R;
+
+        let local = |p: &stable::Place| Local::new(p.local);
+        match rvalue {
+            R::Use(stable::Operand::Constant(_)) | R::UnaryOp(_, stable::Operand::Constant(_)) => {
+                Rvalue::Clean
+            }
+            R::Use(op) | R::UnaryOp(_, op) => Rvalue::Derived(vec![op.into()]),
+            R::BinaryOp(_, a, b) | R::CheckedBinaryOp(_, a, b) => {
+                Rvalue::Derived(vec![a.into(), b.into()])
+            }
+            R::Aggregate(_, operands) => Rvalue::Derived(operands.iter().map(Into::into).collect()),
+            R::CopyForDeref(p) | R::Len(p) => Rvalue::Derived(vec![Operand::Local(local(p))]),
+            R::Repeat(op, _) => Rvalue::Derived(vec![op.into()]),
+            R::Ref(_, _, p) | R::AddressOf(_, p) => Rvalue::Ref(local(p)),
+            R::NullaryOp(_, _) => Rvalue::Clean,
+            R::Cast(stable::CastKind::Transmute, op, _) => Rvalue::Derived(vec![op.into()]),
+            R::ThreadLocalRef(_) => Rvalue::Unknown,
+
+            R::Cast(_, _, _) | R::Discriminant(_) | R::ShallowInitBox(_, _) => Rvalue::Unknown,
+        }
+    }
+}
+
+/// Builders for writing small programs in the IR, and running them.
+#[cfg(test)]
+pub(crate) mod builder {
+    use super::*;
+    use crate::analysis::{
+        labels::TAINT,
+        taint_domain::{PointsMap, TaintState},
+    };
+
+    pub(crate) fn local(ix: u32) -> Local {
+        Local::from_u32(ix)
+    }
+
+    pub(crate) fn op(ix: u32) -> Operand {
+        Operand::Local(local(ix))
+    }
+
+    /// A straight-line program over `_0` to `_{locals - 1}`.
+    pub(crate) struct BodyBuilder {
+        locals: usize,
+        statements: Vec<Statement>,
+    }
+
+    /// The outcome of running a program.
+    pub(crate) struct Run {
+        pub(crate) state: TaintState<Local>,
+        /// The indices of the statements at which a sink received tainted input.
+        pub(crate) tainted_sinks: Vec<usize>,
+    }
+
+    impl BodyBuilder {
+        pub(crate) fn new(locals: usize) -> Self {
+            BodyBuilder {
+                locals,
+                statements: vec![],
+            }
+        }
+
+        pub(crate) fn assign(mut self, place: u32, rvalue: Rvalue) -> Self {
+            self.statements
+                .push(Statement::Assign(local(place), rvalue));
+            self
+        }
+
+        pub(crate) fn call(
+            mut self,
+            effect: CallEffect,
+            args: &[Operand],
+            destination: u32,
+        ) -> Self {
+            self.statements.push(Statement::Call {
+                effect,
+                args: args.to_vec(),
+                destination: local(destination),
+            });
+            self
+        }
+
+        pub(crate) fn source(self, destination: u32) -> Self {
+            self.call(CallEffect::Source(TAINT.into()), &[], destination)
+        }
+
+        pub(crate) fn sink(self, args: &[Operand]) -> Self {
+            self.call(
+                CallEffect::Sink {
+                    checks: TAINT.into(),
+                },
+                args,
+                0,
+            )
+        }
+
+        pub(crate) fn run(&self) -> Run {
+            let mut state = TaintState::new_empty(self.locals);
+            let mut map = PointsMap::default();
+            let mut domain = PointsAwareTaintDomain {
+                state: &mut state,
+                map: &mut map,
+            };
+
+            let tainted_sinks = self
+                .statements
+                .iter()
+                .enumerate()
+                .filter_map(|(ix, statement)| {
+                    (!statement.apply(&mut domain).is_empty()).then_some(ix)
+                })
+                .collect();
+
+            Run {
+                state,
+                tainted_sinks,
+            }
+        }
+    }
+
+    impl Run {
+        pub(crate) fn tainted(&self, ix: u32) -> bool {
+            !self.state.get_taint(local(ix)).is_empty()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::builder::*;
+    use super::*;
+    use crate::analysis::labels::{Label, TAINT};
+
+    #[test]
+    fn assigning_a_constant_cleans() {
+        let run = BodyBuilder::new(3).source(1).assign(1, Rvalue::Clean).run();
+
+        assert!(!run.tainted(1));
+    }
+
+    #[test]
+    fn nullary_op_cleans() {
+        let size_of = stable::Rvalue::NullaryOp(stable::NullOp::SizeOf, stable::Ty(0));
+        let run = BodyBuilder::new(2)
+            .source(1)
+            .assign(1, (&size_of).into())
+            .run();
+
+        assert!(!run.tainted(1));
+    }
+
+    #[test]
+    fn binary_op_propagates_from_either_operand() {
+        let run = BodyBuilder::new(4)
+            .source(1)
+            .assign(2, Rvalue::Derived(vec![Operand::Const, op(1)]))
+            .assign(3, Rvalue::Derived(vec![Operand::Const, Operand::Const]))
+            .run();
+
+        assert!(run.tainted(2));
+        assert!(!run.tainted(3));
+    }
+
+    #[test]
+    fn writes_through_a_reference_taint_the_referent() {
+        // _2 = &_1; (*_2) = source();
+        let run = BodyBuilder::new(3)
+            .assign(2, Rvalue::Ref(local(1)))
+            .source(2)
+            .run();
+
+        assert!(run.tainted(1));
+    }
+
+    #[test]
+    fn reads_through_a_reference_see_the_referent() {
+        // _1 = source(); _2 = &_1; sink(_2)
+        let run = BodyBuilder::new(3)
+            .source(1)
+            .assign(2, Rvalue::Ref(local(1)))
+            .sink(&[op(2)])
+            .run();
+
+        assert_eq!(run.tainted_sinks, vec![2]);
+    }
+
+    #[test]
+    fn sanitizer_cleans_the_result() {
+        let run = BodyBuilder::new(3)
+            .source(1)
+            .call(
+                CallEffect::Sanitizer {
+                    removes: TAINT.into(),
+                    relabels: None,
+                },
+                &[op(1)],
+                2,
+            )
+            .sink(&[op(2)])
+            .run();
+
+        assert!(run.tainted_sinks.is_empty());
+    }
+
+    #[test]
+    fn sanitizer_writing_a_borrow_of_the_original_leaves_it_tainted() {
+        // _1 = source(); _2 = &_1; _3 = Cow::from(_2), which points to _1; _3 = sanitize(_2); sink(_1)
+        let run = BodyBuilder::new(4)
+            .source(1)
+            .assign(2, Rvalue::Ref(local(1)))
+            .assign(3, Rvalue::Ref(local(1)))
+            .call(
+                CallEffect::Sanitizer {
+                    removes: TAINT.into(),
+                    relabels: None,
+                },
+                &[op(2)],
+                3,
+            )
+            .sink(&[op(1)])
+            .run();
+
+        assert_eq!(run.tainted_sinks, vec![4]);
+    }
+
+    #[test]
+    fn relabeling_sanitizer_changes_the_label_of_the_result() {
+        let (encoded, decoded) = (Label::new(1), Label::new(2));
+        let relabel = CallEffect::Sanitizer {
+            removes: Labels::EMPTY,
+            relabels: Some((encoded, decoded)),
+        };
+
+        let run = BodyBuilder::new(5)
+            .call(CallEffect::Source(encoded.into()), &[], 1)
+            .call(relabel.clone(), &[op(1)], 2)
+            .source(3)
+            .call(relabel, &[op(3)], 4)
+            .call(
+                CallEffect::Sink {
+                    checks: encoded.into(),
+                },
+                &[op(2)],
+                0,
+            )
+            .call(
+                CallEffect::Sink {
+                    checks: decoded.into(),
+                },
+                &[op(2), op(4)],
+                0,
+            )
+            .run();
+
+        assert_eq!(run.tainted_sinks, vec![5]);
+        assert!(run.tainted(4));
+    }
+
+    #[test]
+    fn summary_writes_back_arguments() {
+        // set_buffer(&mut _1), where `set_buffer` taints its argument.
+        let run = BodyBuilder::new(4)
+            .assign(2, Rvalue::Ref(local(1)))
+            .call(
+                CallEffect::Summary {
+                    returns: Labels::EMPTY,
+                    args: vec![TAINT.into()],
+                    points_into: vec![],
+                },
+                &[op(2)],
+                3,
+            )
+            .run();
+
+        assert!(run.tainted(1));
+        assert!(!run.tainted(3));
+    }
+
+    #[test]
+    fn writes_through_a_returned_pointer_taint_the_argument() {
+        // _2 = &mut _1; _3 = as_mut_ptr(_2); (*_3) = source();
+        let run = BodyBuilder::new(4)
+            .assign(2, Rvalue::Ref(local(1)))
+            .call(
+                CallEffect::Summary {
+                    returns: Labels::EMPTY,
+                    args: vec![Labels::EMPTY],
+                    points_into: vec![0],
+                },
+                &[op(2)],
+                3,
+            )
+            .source(3)
+            .run();
+
+        assert!(run.tainted(1));
+    }
+
+    #[test]
+    fn sink_requiring_a_sanitizer_sees_values_cleaned_by_another() {
+        // `pending` is only removed by the sanitizer the sink requires.
+        let pending = Label::new(1);
+        let labels = Labels::from(TAINT).union(pending.into());
+        let sink = CallEffect::Sink { checks: labels };
+
+        let run = BodyBuilder::new(4)
+            .call(CallEffect::Source(labels), &[], 1)
+            .call(
+                CallEffect::Sanitizer {
+                    removes: TAINT.into(),
+                    relabels: None,
+                },
+                &[op(1)],
+                2,
+            )
+            .call(
+                CallEffect::Sanitizer {
+                    removes: labels,
+                    relabels: None,
+                },
+                &[op(1)],
+                3,
+            )
+            .call(sink.clone(), &[op(2)], 0)
+            .call(sink, &[op(3)], 0)
+            .run();
+
+        assert!(!run.tainted(3));
+        assert_eq!(run.tainted_sinks, vec![3]);
+    }
+}
@@ -0,0 +1,201 @@
+//! Labels describe why a value is considered tainted.
+//!
+//! Every value from a source carries [`TAINT`]. Sinks which require a particular sanitizer
+//! additionally look for a label which only that sanitizer removes, so that values cleaned by
+//! some other sanitizer are still caught.
+//!
+//! Sources and sinks can also name a label, like `header`. Named labels form a [`Hierarchy`]
+//! declared in the config, in which a label like `user-input` subsumes `header`,
+//! so that a sink checking `user-input` catches values labeled `header`.
+
+use std::{collections::HashMap, fmt};
+
+use crate::config::MAX_LABELS;
+
+/// A label, identified by its index in the label table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Label(u8);
+
+/// The label every source puts on its result.
+pub(crate) const TAINT: Label = Label(0);
+
+impl Label {
+    /// The most labels a single analysis can distinguish.
+    pub(crate) const MAX: usize = MAX_LABELS;
+
+    pub(crate) fn new(ix: usize) -> Self {
+        assert!(ix < Self::MAX, "too many labels");
+        Label(ix as u8)
+    }
+
+    pub(crate) fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// The labels a value may carry. The empty set means the value is clean.
+///
+/// A set is a single word, so the state of a function holds one per local and copies them freely.
+/// Interning sets behind an ID would take as much room, and cost a lookup on every join.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub(crate) struct Labels(u64);
+
+impl Labels {
+    pub(crate) const EMPTY: Labels = Labels(0);
+
+    pub(crate) fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub(crate) fn contains(self, label: Label) -> bool {
+        self.0 & (1 << label.0) != 0
+    }
+
+    pub(crate) fn intersection(self, other: Labels) -> Labels {
+        Labels(self.0 & other.0)
+    }
+
+    pub(crate) fn insert(&mut self, label: Label) {
+        self.0 |= 1 << label.0;
+    }
+
+    pub(crate) fn union(self, other: Labels) -> Labels {
+        Labels(self.0 | other.0)
+    }
+
+    pub(crate) fn without(self, other: Labels) -> Labels {
+        Labels(self.0 & !other.0)
+    }
+
+    /// The labels, with `to` instead of `from` if they contain it.
+    pub(crate) fn relabeled(self, from: Label, to: Label) -> Labels {
+        if self.contains(from) {
+            self.without(from.into()).union(to.into())
+        } else {
+            self
+        }
+    }
+
+    pub(crate) fn is_subset(self, other: Labels) -> bool {
+        self.without(other).is_empty()
+    }
+
+    pub(crate) fn iter(self) -> impl Iterator<Item = Label> {
+        (0..Label::MAX)
+            .map(Label::new)
+            .filter(move |&label| self.contains(label))
+    }
+}
+
+impl From<Label> for Labels {
+    fn from(label: Label) -> Self {
+        let mut labels = Labels::EMPTY;
+        labels.insert(label);
+        labels
+    }
+}
+
+/// A value which only grows by taking in others, like the states of the dataflow analysis.
+///
+/// It is the compiler's `JoinSemiLattice`, kept apart so that the domain builds without the compiler;
+/// the analysis implements that trait for its state with this one.
+pub(crate) trait Join {
+    /// Takes in `other`, returning whether that changed `self`.
+    fn join(&mut self, other: &Self) -> bool;
+}
+
+impl Join for Labels {
+    fn join(&mut self, other: &Self) -> bool {
+        let joined = self.union(*other);
+        let changed = joined != *self;
+        *self = joined;
+        changed
+    }
+}
+
+impl fmt::Debug for Labels {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.iter().map(Label::index))
+            .finish()
+    }
+}
+
+/// Which labels subsume which.
+#[derive(Debug, Default)]
+pub(crate) struct Hierarchy {
+    /// The labels each label directly subsumes.
+    subsumed: HashMap<Label, Labels>,
+}
+
+impl Hierarchy {
+    /// Declares that `label` subsumes `sub`.
+    /// Returns false, and declares nothing, if `sub` already subsumes `label`.
+    pub(crate) fn add(&mut self, label: Label, sub: Label) -> bool {
+        if self.below(sub).contains(label) {
+            return false;
+        }
+        self.subsumed.entry(label).or_default().insert(sub);
+        true
+    }
+
+    /// `label` and every label it subsumes, directly or not.
+    pub(crate) fn below(&self, label: Label) -> Labels {
+        let mut below = Labels::from(label);
+        loop {
+            let next = below.iter().fold(below, |labels, label| {
+                labels.union(self.subsumed.get(&label).copied().unwrap_or_default())
+            });
+            if next == below {
+                return below;
+            }
+            below = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join_reports_changes() {
+        let mut labels = Labels::from(TAINT);
+
+        assert!(!labels.join(&Labels::from(TAINT)));
+        assert!(labels.join(&Labels::from(Label::new(3))));
+        assert_eq!(
+            labels.iter().collect::<Vec<_>>(),
+            vec![TAINT, Label::new(3)]
+        );
+    }
+
+    #[test]
+    fn hierarchy_is_transitive() {
+        let (input, header, cookie) = (Label::new(1), Label::new(2), Label::new(3));
+        let mut hierarchy = Hierarchy::default();
+
+        assert!(hierarchy.add(input, header));
+        assert!(hierarchy.add(header, cookie));
+        assert!(!hierarchy.add(cookie, input));
+        assert_eq!(
+            hierarchy.below(input),
+            Labels::from(input)
+                .union(header.into())
+                .union(cookie.into())
+        );
+        assert_eq!(hierarchy.below(cookie), Labels::from(cookie));
+    }
+
+    #[test]
+    fn without_removes_only_the_given_labels() {
+        let labels = Labels::from(TAINT).union(Label::new(1).into());
+
+        assert_eq!(labels.without(TAINT.into()), Labels::from(Label::new(1)));
+    }
+
+    #[test]
+    fn label_sets_are_a_single_word() {
+        assert_eq!(std::mem::size_of::<Labels>(), std::mem::size_of::<u64>());
+    }
+}
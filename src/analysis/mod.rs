@@ -1,3 +1,18 @@
 pub mod taint_analysis;
 
+mod callbacks;
+pub(crate) mod checkpoint;
+pub(crate) mod dependency_cache;
+mod facts;
+mod findings;
+pub(crate) mod interchange;
+mod invariants;
+mod ir;
+pub(crate) mod labels;
+pub(crate) mod models;
+pub(crate) mod reproducer;
+pub(crate) mod slice;
+pub(crate) mod summary_usage;
 mod taint_domain;
+mod transfer;
+pub(crate) mod warm;
@@ -0,0 +1,1007 @@
+//! Built-in models for library functions whose effect on taint cannot be read off their MIR.
+
+use crate::{
+    compiler::{self, hir, DefId, DefKind, GenericArgsRef, Symbol, Ty, TyCtxt, TyKind},
+    options::SinkPack,
+};
+
+/// Paths under which `tokio` exposes functions that detach a future (or closure) into a new task.
+const SPAWN_PATHS: &[&str] = &[
+    "tokio::spawn",
+    "tokio::task::spawn",
+    "tokio::task::spawn::spawn",
+    "tokio::task::spawn_local",
+    "tokio::task::spawn_blocking",
+    "tokio::runtime::Runtime::spawn",
+    "tokio::runtime::Handle::spawn",
+];
+
+/// Functions of `serde_json` which serialize a value, under the paths they are defined and re-exported at,
+/// with the position of the argument holding a reference to the value, and of the writer they write it to if any.
+const SERIALIZE_PATHS: &[(&str, usize, Option<usize>)] = &[
+    ("serde_json::to_string", 0, None),
+    ("serde_json::ser::to_string", 0, None),
+    ("serde_json::to_string_pretty", 0, None),
+    ("serde_json::ser::to_string_pretty", 0, None),
+    ("serde_json::to_vec", 0, None),
+    ("serde_json::ser::to_vec", 0, None),
+    ("serde_json::to_vec_pretty", 0, None),
+    ("serde_json::ser::to_vec_pretty", 0, None),
+    ("serde_json::to_value", 0, None),
+    ("serde_json::value::to_value", 0, None),
+    ("serde_json::to_writer", 1, Some(0)),
+    ("serde_json::ser::to_writer", 1, Some(0)),
+    ("serde_json::to_writer_pretty", 1, Some(0)),
+    ("serde_json::ser::to_writer_pretty", 1, Some(0)),
+];
+
+/// Cells which are set once, like a global initialized at startup, by the path of their definition.
+const ONCE_CELLS: &[&str] = &[
+    "std::cell::OnceCell",
+    "std::sync::OnceLock",
+    "once_cell::unsync::OnceCell",
+    "once_cell::sync::OnceCell",
+];
+
+/// Cells which compute their value the first time it is read, with the function they were created with.
+/// `lazy_static!` expands to a static of `lazy_static::lazy::Lazy` which its `Deref` impl reads
+/// with `LAZY.get(init)`.
+const LAZY_CELLS: &[&str] = &[
+    "std::cell::LazyCell",
+    "std::sync::LazyLock",
+    "once_cell::unsync::Lazy",
+    "once_cell::sync::Lazy",
+    "lazy_static::lazy::Lazy",
+];
+
+/// Iterators which yield items of the iterator they wrap, their first generic argument, by the path of their
+/// definition.
+const FORWARDING_ITERATORS: &[&str] = &[
+    "std::iter::Rev",
+    "std::iter::Skip",
+    "std::iter::Take",
+    "std::iter::StepBy",
+    "std::iter::Peekable",
+    "std::iter::Fuse",
+];
+
+/// Methods of iterators which yield items of the iterator they are called on, or return an iterator which does.
+const FORWARDING_METHODS: &[&str] = &[
+    "next",
+    "next_back",
+    "nth",
+    "last",
+    "into_iter",
+    "by_ref",
+    "rev",
+    "skip",
+    "take",
+    "step_by",
+    "peekable",
+    "fuse",
+];
+
+/// The wrapper for secrets of the `taint-runtime` crate, whose values carry the `secret` label
+/// until they are exposed.
+const SENSITIVE: &str = "taint_runtime::Sensitive";
+
+/// The function of the `taint-runtime` crate which stops taint without sanitizing it.
+const BARRIER: &str = "taint_runtime::barrier";
+
+/// The function which hides a value from the optimizer, which `--taint-black-box-barrier` makes a barrier,
+/// along with the intrinsic it calls.
+const BLACK_BOX: &str = "std::hint::black_box";
+
+/// The type `format_args!` wraps each formatted value in.
+const FORMAT_ARGUMENT: &str = "core::fmt::rt::Argument";
+
+/// The error type of `anyhow`, which erases the type of the error it holds like a `Box<dyn Error>`.
+const ANYHOW_ERROR: &str = "anyhow::Error";
+
+/// Functions of `anyhow` which wrap an error or a message into an `anyhow::Error`, or add context to one,
+/// including the one `anyhow!` expands to.
+const ANYHOW_WRAPS: &[&str] = &[
+    "anyhow::Error::new",
+    "anyhow::Error::msg",
+    "anyhow::Error::context",
+    "anyhow::__private::format_err",
+    "anyhow::Context::context",
+    "anyhow::Context::with_context",
+];
+
+/// Methods of `anyhow::Error` which return the error it holds, or one of the errors it was caused by.
+const ANYHOW_VIEWS: &[&str] = &[
+    "anyhow::Error::root_cause",
+    "anyhow::Error::chain",
+    "anyhow::Error::downcast",
+    "anyhow::Error::downcast_ref",
+    "anyhow::Error::downcast_mut",
+];
+
+/// Traits whose methods, by convention, return a view of the value they are called on,
+/// like `AsRef::as_ref`, `Borrow::borrow` or `Deref::deref`.
+const CONVERSION_TRAITS: &[&str] = &[
+    "std::convert::AsRef",
+    "std::convert::AsMut",
+    "std::borrow::Borrow",
+    "std::borrow::BorrowMut",
+    "std::ops::Deref",
+    "std::ops::DerefMut",
+];
+
+/// Wrappers which zeroize the value they hold when they are dropped.
+const ZEROIZING_WRAPPERS: &[&str] = &[
+    "zeroize::Zeroizing",
+    "secrecy::SecretBox",
+    "secrecy::Secret",
+];
+
+/// Traits of hashers and digests, and their methods which return the digest of what was fed to them.
+const DIGEST_TRAITS: &[(&str, &[&str])] = &[
+    ("std::hash::Hasher", &["finish"]),
+    ("std::hash::BuildHasher", &["hash_one"]),
+    ("digest::Digest", &["digest", "finalize", "finalize_reset"]),
+    ("digest::DynDigest", &["finalize", "finalize_reset"]),
+    ("digest::FixedOutput", &["finalize_fixed"]),
+    ("digest::FixedOutputReset", &["finalize_fixed_reset"]),
+    ("digest::Mac", &["finalize", "finalize_reset"]),
+];
+
+/// Functions and inherent methods of hashing crates which return a digest.
+const DIGEST_FUNCTIONS: &[&str] = &[
+    "ring::digest::digest",
+    "ring::digest::Context::finish",
+    "blake3::hash",
+    "blake3::keyed_hash",
+    "blake3::Hasher::finalize",
+    "md5::compute",
+    "crc32fast::hash",
+    "crc32fast::Hasher::finalize",
+    "openssl::hash::hash",
+    "openssl::sha::sha1",
+    "openssl::sha::sha256",
+    "openssl::sha::sha512",
+];
+
+/// The effects of the intrinsics which are modeled, by name.
+/// Other intrinsics have no MIR, so calls to them follow `--taint-unknown-calls`.
+const INTRINSICS: &[(&str, Intrinsic)] = &[
+    // Properties of types, rather than of values.
+    ("size_of", Intrinsic::Clean),
+    ("min_align_of", Intrinsic::Clean),
+    ("pref_align_of", Intrinsic::Clean),
+    ("needs_drop", Intrinsic::Clean),
+    ("type_id", Intrinsic::Clean),
+    ("type_name", Intrinsic::Clean),
+    ("variant_count", Intrinsic::Clean),
+    ("forget", Intrinsic::Clean),
+    // Reinterpreting or reading a value.
+    ("transmute", Intrinsic::Propagate),
+    ("transmute_unchecked", Intrinsic::Propagate),
+    ("read_via_copy", Intrinsic::Propagate),
+    ("volatile_load", Intrinsic::Propagate),
+    ("unaligned_volatile_load", Intrinsic::Propagate),
+    ("size_of_val", Intrinsic::Propagate),
+    ("min_align_of_val", Intrinsic::Propagate),
+    ("discriminant_value", Intrinsic::Propagate),
+    ("black_box", Intrinsic::Propagate),
+    // Arithmetic.
+    ("add_with_overflow", Intrinsic::Propagate),
+    ("sub_with_overflow", Intrinsic::Propagate),
+    ("mul_with_overflow", Intrinsic::Propagate),
+    ("wrapping_add", Intrinsic::Propagate),
+    ("wrapping_sub", Intrinsic::Propagate),
+    ("wrapping_mul", Intrinsic::Propagate),
+    ("unchecked_add", Intrinsic::Propagate),
+    ("unchecked_sub", Intrinsic::Propagate),
+    ("unchecked_mul", Intrinsic::Propagate),
+    ("unchecked_div", Intrinsic::Propagate),
+    ("unchecked_rem", Intrinsic::Propagate),
+    ("unchecked_shl", Intrinsic::Propagate),
+    ("unchecked_shr", Intrinsic::Propagate),
+    ("saturating_add", Intrinsic::Propagate),
+    ("saturating_sub", Intrinsic::Propagate),
+    ("exact_div", Intrinsic::Propagate),
+    ("rotate_left", Intrinsic::Propagate),
+    ("rotate_right", Intrinsic::Propagate),
+    ("ctpop", Intrinsic::Propagate),
+    ("ctlz", Intrinsic::Propagate),
+    ("cttz", Intrinsic::Propagate),
+    ("bswap", Intrinsic::Propagate),
+    ("bitreverse", Intrinsic::Propagate),
+    // Pointer arithmetic.
+    ("offset", Intrinsic::Propagate),
+    ("arith_offset", Intrinsic::Propagate),
+    ("ptr_offset_from", Intrinsic::Propagate),
+    ("ptr_offset_from_unsigned", Intrinsic::Propagate),
+    // Writing through a pointer: `copy(src, dst, count)`, `write_bytes(dst, val, count)`
+    // and `write_via_move(ptr, value)`.
+    ("copy", Intrinsic::Write { dst: 1, from: 0 }),
+    ("copy_nonoverlapping", Intrinsic::Write { dst: 1, from: 0 }),
+    ("write_bytes", Intrinsic::Write { dst: 0, from: 1 }),
+    ("write_via_move", Intrinsic::Write { dst: 0, from: 1 }),
+    ("volatile_store", Intrinsic::Write { dst: 0, from: 1 }),
+    (
+        "unaligned_volatile_store",
+        Intrinsic::Write { dst: 0, from: 1 },
+    ),
+    ("volatile_copy_memory", Intrinsic::Write { dst: 0, from: 1 }),
+    (
+        "volatile_copy_nonoverlapping_memory",
+        Intrinsic::Write { dst: 0, from: 1 },
+    ),
+    ("volatile_set_memory", Intrinsic::Write { dst: 0, from: 1 }),
+];
+
+/// Families of intrinsics which differ only by their memory ordering, like `atomic_load_seqcst`.
+const ATOMIC_INTRINSICS: &[(&str, Intrinsic)] = &[
+    ("atomic_load_", Intrinsic::Propagate),
+    ("atomic_store_", Intrinsic::Write { dst: 0, from: 1 }),
+];
+
+#[derive(Debug)]
+pub(crate) enum Model<'tcx> {
+    /// The last argument is run as a separate task with body `task`, instantiated with `args`.
+    /// The returned `JoinHandle` carries the taint of the task's output.
+    Spawn {
+        task: DefId,
+        args: GenericArgsRef<'tcx>,
+    },
+    /// Polling a `JoinHandle` yields the output of the task it was created for.
+    JoinHandlePoll,
+    /// `format_args!` wraps a value to be formatted. The wrapper carries the taint of the value, and when it is
+    /// formatted with `Debug`, of type `debugged`, those of the fields marked as sources its derived impls read.
+    FormatArgument { debugged: Option<Ty<'tcx>> },
+    /// A compiler intrinsic, which has no MIR to analyze.
+    Intrinsic(Intrinsic),
+    /// A method of `HashMap` or `BTreeMap` which puts entries in, or reads only keys or only values.
+    Map(MapMethod),
+    /// Serializing the value argument `value` points to, of type `ty`, which the result carries the labels of,
+    /// along with those of the fields marked as sources within it.
+    /// With a `writer`, the result is written into it instead, through its `std::io::Write` impl.
+    Serialize {
+        value: usize,
+        writer: Option<usize>,
+        ty: Ty<'tcx>,
+    },
+    /// Setting the value of a cell, the first argument, to argument `value`.
+    /// The result gives the value back if the cell was already set.
+    CellSet { value: usize },
+    /// Reading the value of a cell, the first argument, after initializing it with what `init` returns
+    /// if it was not, like `get_or_init(init)`. `init` is the type of the second argument.
+    CellInit { init: Ty<'tcx> },
+    /// Creating a lazy cell whose value is what `init`, the type of the only argument, returns,
+    /// like `Lazy::new(|| ...)`.
+    LazyNew { init: Ty<'tcx> },
+    /// Reading the value of a cell, the first argument, like `get` or `*LAZY`.
+    CellRead,
+    /// A method of `taint_runtime::Sensitive`, or formatting one.
+    Sensitive(SensitiveMethod),
+    /// A method of one of the conversion traits which is not resolved to an impl, like `t.as_ref()`
+    /// on a `T: AsRef<str>` or a `&dyn AsRef<str>`. The view it returns carries the labels of the value.
+    Conversion,
+    /// `to_string` on a newtype, a struct with a single field like `struct Email(String)`, whose `Display` impl
+    /// formats into a `Formatter` the labels do not get through. The result carries the labels of the value.
+    NewtypeToString,
+    /// Wrapping, formatting or reading an error whose type was erased, like the `Box<dyn Error>` which `?`
+    /// converts errors into, or an `anyhow::Error`. The call cannot be resolved to the impls of the error
+    /// it holds, or goes through the vtable `anyhow` builds, where the labels of the error would be lost.
+    ErasedError(ErrorMethod),
+    /// `Zeroize::zeroize`, which overwrites the value the first argument points to with zeros,
+    /// so that it carries no labels afterwards.
+    Zeroize,
+}
+
+/// What a map method does with the keys and values of the map it is called on, its first argument.
+/// Methods which read both, or return mutable references into the map, are analyzed like others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MapMethod {
+    /// `insert(key, value)` puts an entry in, and returns the value it replaced.
+    Insert,
+    /// Values are read, like with `get`, `remove`, `values` or `map[&key]`.
+    /// The key looked up only decides which value is returned, so its taint is not.
+    Values,
+    /// Keys are read, like with `keys` or `into_keys`.
+    Keys,
+}
+
+/// What a call does with a `taint_runtime::Sensitive`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SensitiveMethod {
+    /// `Sensitive::new` or `From::from` wraps a secret, which the result carries the `secret` label of.
+    Wrap,
+    /// `expose` is the only way to the secret, which it returns without the `secret` label.
+    Expose,
+    /// Formatting the wrapper with `Display` or `Debug`, named by `trait_name`, always leaks the secret,
+    /// like `format_args!` wrapping it or `to_string`.
+    Format { trait_name: &'static str },
+}
+
+/// What a call does with an error whose type was erased.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorMethod {
+    /// Wrapping an error or a message, like `From<E> for Box<dyn Error>`, `anyhow!` or `.context(..)`.
+    /// The result carries the labels of the error, and of the context.
+    Wrap,
+    /// Formatting the error with `Display` or `Debug`. The formatter, the second argument, gets its labels.
+    Format,
+    /// Reading the error it holds or was caused by, like `source()` or `root_cause()`,
+    /// which carries its labels.
+    View,
+}
+
+/// What an intrinsic does to taint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Intrinsic {
+    /// The result is clean, like the size of a type.
+    Clean,
+    /// The result carries the labels of the arguments, like the result of `transmute` or of arithmetic.
+    Propagate,
+    /// What argument `dst` points to gets the labels of argument `from`, besides those it had,
+    /// like the destination of `copy` gets those of the source.
+    Write { dst: usize, from: usize },
+}
+
+/// What a call does with the pairs an iterator yields, whose elements each carry their own labels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PairAdapter {
+    /// `iter.enumerate()` pairs a clean index with each item of `iter`, the first argument.
+    Enumerate,
+    /// `a.zip(b)` or `zip(a, b)` pairs each item of `a` with the item of `b` at the same position.
+    Zip,
+    /// `next`, `rev` or another method of an iterator over pairs, the first argument, which yields its pairs
+    /// or returns an iterator which does.
+    Forward,
+}
+
+pub(crate) fn find_model<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    id: DefId,
+    args: GenericArgsRef<'tcx>,
+) -> Option<Model<'tcx>> {
+    if tcx.is_intrinsic(id) {
+        return intrinsic(tcx.item_name(id).as_str()).map(Model::Intrinsic);
+    }
+
+    let path = tcx.def_path_str(id);
+
+    if SPAWN_PATHS.contains(&path.as_str()) {
+        return args
+            .types()
+            .find_map(|ty| task_body(tcx, ty))
+            .map(|(task, args)| Model::Spawn { task, args });
+    }
+
+    if let Some(&(_, value, writer)) = SERIALIZE_PATHS.iter().find(|(p, ..)| *p == path) {
+        // The serialized type is the last generic argument, after the writer's.
+        return args
+            .types()
+            .last()
+            .map(|ty| Model::Serialize { value, writer, ty });
+    }
+
+    if let Some(method) = sensitive_method(tcx, id, args) {
+        return Some(Model::Sensitive(method));
+    }
+
+    if let Some(adt) = tcx
+        .impl_of_method(id)
+        .and_then(|impl_id| tcx.type_of(impl_id).instantiate_identity().ty_adt_def())
+    {
+        if tcx.def_path_str(adt.did()) == FORMAT_ARGUMENT {
+            let debugged = (tcx.item_name(id).as_str() == "new_debug")
+                .then(|| args.types().last())
+                .flatten();
+            return Some(Model::FormatArgument { debugged });
+        }
+    }
+
+    if let Some(method) = map_method(tcx, id, args) {
+        return Some(Model::Map(method));
+    }
+
+    if let Some(method) = error_method(tcx, id, args) {
+        return Some(Model::ErasedError(method));
+    }
+
+    if let Some(model) = cell_method(tcx, id, args) {
+        return Some(model);
+    }
+
+    if is_newtype_to_string(tcx, id, args) {
+        return Some(Model::NewtypeToString);
+    }
+
+    let is_zeroize = tcx.trait_of_item(id).map_or(false, |trait_id| {
+        tcx.def_path_str(trait_id) == "zeroize::Zeroize"
+    }) && tcx.item_name(id).as_str() == "zeroize";
+    if is_zeroize {
+        return Some(Model::Zeroize);
+    }
+
+    // Calls which resolve to an impl are analyzed like others, as the impl may return any field.
+    if is_conversion(tcx, id) && compiler::resolve(tcx, id, args).0 == id {
+        return Some(Model::Conversion);
+    }
+
+    let is_poll = tcx.trait_of_item(id) == tcx.lang_items().future_trait()
+        && tcx.item_name(id).as_str() == "poll";
+    if is_poll {
+        if let Some(adt) = args.type_at(0).ty_adt_def() {
+            let path = tcx.def_path_str(adt.did());
+            if path.starts_with("tokio::") && path.ends_with("::JoinHandle") {
+                return Some(Model::JoinHandlePoll);
+            }
+        }
+    }
+
+    None
+}
+
+/// What `id`, called with `args`, does with a map, if it is a modeled method of `HashMap` or `BTreeMap`.
+fn map_method<'tcx>(tcx: TyCtxt<'tcx>, id: DefId, args: GenericArgsRef<'tcx>) -> Option<MapMethod> {
+    let adt = match tcx.impl_of_method(id) {
+        Some(impl_id) => tcx.type_of(impl_id).instantiate_identity().ty_adt_def(),
+        // `map[&key]` calls `Index::index` with the map as its `Self` type.
+        None if tcx.trait_of_item(id) == tcx.lang_items().index_trait() => {
+            args.type_at(0).ty_adt_def()
+        }
+        None => None,
+    }?;
+    let is_map = ["HashMap", "BTreeMap"]
+        .iter()
+        .any(|&map| tcx.is_diagnostic_item(Symbol::intern(map), adt.did()));
+    if !is_map {
+        return None;
+    }
+    match tcx.item_name(id).as_str() {
+        "insert" => Some(MapMethod::Insert),
+        "get" | "remove" | "index" | "values" | "into_values" => Some(MapMethod::Values),
+        "keys" | "into_keys" => Some(MapMethod::Keys),
+        _ => None,
+    }
+}
+
+/// What `id`, called with `args`, does with the pairs an iterator yields, if it makes an iterator over pairs
+/// with `enumerate` or `zip`, or is called on one.
+pub(crate) fn pair_adapter<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    id: DefId,
+    args: GenericArgsRef<'tcx>,
+) -> Option<PairAdapter> {
+    if tcx.def_path_str(id) == "std::iter::zip" {
+        return Some(PairAdapter::Zip);
+    }
+    let trait_path = tcx.def_path_str(tcx.trait_of_item(id)?);
+    let is_iterator = [
+        "std::iter::Iterator",
+        "std::iter::IntoIterator",
+        "std::iter::DoubleEndedIterator",
+    ]
+    .contains(&trait_path.as_str());
+    if !is_iterator {
+        return None;
+    }
+    match tcx.item_name(id).as_str() {
+        "enumerate" => Some(PairAdapter::Enumerate),
+        "zip" => Some(PairAdapter::Zip),
+        name if FORWARDING_METHODS.contains(&name) && yields_pairs(tcx, args.type_at(0)) => {
+            Some(PairAdapter::Forward)
+        }
+        _ => None,
+    }
+}
+
+/// Whether `ty` is an iterator made by `enumerate` or `zip`, or one which yields the items of one,
+/// or a reference to either.
+fn yields_pairs<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+    let TyKind::Adt(adt, args) = ty.peel_refs().kind() else {
+        return false;
+    };
+    match tcx.def_path_str(adt.did()).as_str() {
+        "std::iter::Enumerate" | "std::iter::Zip" => true,
+        path if FORWARDING_ITERATORS.contains(&path) => yields_pairs(tcx, args.type_at(0)),
+        _ => false,
+    }
+}
+
+/// What `id`, called with `args`, does with an error whose type was erased, if it wraps, formats or reads one.
+fn error_method<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    id: DefId,
+    args: GenericArgsRef<'tcx>,
+) -> Option<ErrorMethod> {
+    let path = tcx.def_path_str(id);
+    if ANYHOW_WRAPS.contains(&path.as_str()) {
+        return Some(ErrorMethod::Wrap);
+    }
+    if ANYHOW_VIEWS.contains(&path.as_str()) {
+        return Some(ErrorMethod::View);
+    }
+    let trait_id = tcx.trait_of_item(id)?;
+    let self_ty = args.types().next()?;
+    let erased = |ty: Ty<'tcx>| is_erased_error(tcx, ty.peel_refs());
+    match (
+        tcx.def_path_str(trait_id).as_str(),
+        tcx.item_name(id).as_str(),
+    ) {
+        // `?` converts the error of a `Result` with `From::from`, into the type the function returns.
+        ("std::convert::From", "from") if erased(self_ty) => Some(ErrorMethod::Wrap),
+        ("std::fmt::Display" | "std::fmt::Debug", "fmt") if erased(self_ty) => {
+            Some(ErrorMethod::Format)
+        }
+        ("std::error::Error", "source" | "cause" | "description") if erased(self_ty) => {
+            Some(ErrorMethod::View)
+        }
+        _ => None,
+    }
+}
+
+/// Whether `ty` erases the type of an error: a trait object of `Error`, a `Box` of one, or an `anyhow::Error`.
+fn is_erased_error<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+    match ty.kind() {
+        TyKind::Dynamic(predicates, ..) => predicates
+            .principal_def_id()
+            .map_or(false, |id| tcx.def_path_str(id) == "std::error::Error"),
+        TyKind::Adt(adt, args) if adt.is_box() => is_erased_error(tcx, args.type_at(0)),
+        TyKind::Adt(adt, _) => tcx.def_path_str(adt.did()) == ANYHOW_ERROR,
+        _ => false,
+    }
+}
+
+/// What `id`, called with `args`, does with a cell, if it is a modeled method of a once or lazy cell.
+/// Their values are set through the interior mutability of the cell, which summaries do not follow.
+fn cell_method<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    id: DefId,
+    args: GenericArgsRef<'tcx>,
+) -> Option<Model<'tcx>> {
+    let adt = match tcx.impl_of_method(id) {
+        Some(impl_id) => tcx.type_of(impl_id).instantiate_identity().ty_adt_def(),
+        // `*LAZY` calls `Deref::deref` with the cell as its `Self` type.
+        None if tcx.trait_of_item(id) == tcx.lang_items().deref_trait() => {
+            args.type_at(0).ty_adt_def()
+        }
+        None => None,
+    }?;
+    let path = tcx.def_path_str(adt.did());
+    let once = ONCE_CELLS.contains(&path.as_str());
+    if !once && !LAZY_CELLS.contains(&path.as_str()) {
+        return None;
+    }
+    let argument = |ix| {
+        let sig = tcx.fn_sig(id).instantiate(tcx, args).skip_binder();
+        sig.inputs().get(ix).copied()
+    };
+    match tcx.item_name(id).as_str() {
+        "get" if path == "lazy_static::lazy::Lazy" => {
+            argument(1).map(|init| Model::CellInit { init })
+        }
+        "set" | "try_insert" if once => Some(Model::CellSet { value: 1 }),
+        "get_or_init" | "get_or_try_init" if once => {
+            argument(1).map(|init| Model::CellInit { init })
+        }
+        "new" if !once => argument(0).map(|init| Model::LazyNew { init }),
+        "get" | "get_mut" | "into_inner" | "take" | "wait" | "force" | "deref" | "deref_mut"
+        | "into_value" => Some(Model::CellRead),
+        _ => None,
+    }
+}
+
+/// What `id`, called with `args`, does with a `taint_runtime::Sensitive`, if it wraps, exposes or formats one.
+fn sensitive_method<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    id: DefId,
+    args: GenericArgsRef<'tcx>,
+) -> Option<SensitiveMethod> {
+    let is_sensitive = |ty: Ty<'tcx>| is_sensitive(tcx, ty.peel_refs());
+    let name = tcx.item_name(id);
+    if let Some(trait_id) = tcx.trait_of_item(id) {
+        let self_ty = args.types().next()?;
+        return match (tcx.def_path_str(trait_id).as_str(), name.as_str()) {
+            ("std::convert::From", "from") if is_sensitive(self_ty) => Some(SensitiveMethod::Wrap),
+            ("std::fmt::Display", "fmt") | ("std::string::ToString", "to_string")
+                if is_sensitive(self_ty) =>
+            {
+                Some(SensitiveMethod::Format {
+                    trait_name: "Display",
+                })
+            }
+            ("std::fmt::Debug", "fmt") if is_sensitive(self_ty) => Some(SensitiveMethod::Format {
+                trait_name: "Debug",
+            }),
+            _ => None,
+        };
+    }
+    let adt = tcx
+        .impl_of_method(id)
+        .and_then(|impl_id| tcx.type_of(impl_id).instantiate_identity().ty_adt_def())?;
+    let path = tcx.def_path_str(adt.did());
+    if path == SENSITIVE {
+        return match name.as_str() {
+            "new" => Some(SensitiveMethod::Wrap),
+            "expose" => Some(SensitiveMethod::Expose),
+            _ => None,
+        };
+    }
+    // `format_args!("{}", secret)` wraps `&secret` with `Argument::new_display`.
+    let formatted = args.types().next().map_or(false, is_sensitive);
+    match name.as_str() {
+        "new_display" if path == FORMAT_ARGUMENT && formatted => Some(SensitiveMethod::Format {
+            trait_name: "Display",
+        }),
+        "new_debug" if path == FORMAT_ARGUMENT && formatted => Some(SensitiveMethod::Format {
+            trait_name: "Debug",
+        }),
+        _ => None,
+    }
+}
+
+/// Whether `id` is a barrier, whose result carries no labels, like `taint_runtime::barrier`,
+/// or `std::hint::black_box` if `black_box` is set.
+pub(crate) fn is_barrier(tcx: TyCtxt<'_>, id: DefId, black_box: bool) -> bool {
+    let path = tcx.def_path_str(id);
+    path == BARRIER
+        || black_box
+            && (path == BLACK_BOX
+                || tcx.is_intrinsic(id) && tcx.item_name(id).as_str() == "black_box")
+}
+
+/// Whether a call to `id` returns the digest of its arguments, either as one of the common hash APIs,
+/// like `Hasher::finish` or `Digest::digest`, or as one of the functions at `others`.
+/// Methods of traits are recognized as called, before they are resolved to the impl of a hasher.
+pub(crate) fn is_digest(tcx: TyCtxt<'_>, id: DefId, others: &[String]) -> bool {
+    if let Some(trait_id) = tcx.trait_of_item(id) {
+        let path = tcx.def_path_str(trait_id);
+        let name = tcx.item_name(id);
+        if DIGEST_TRAITS
+            .iter()
+            .any(|(digest, methods)| *digest == path && methods.contains(&name.as_str()))
+        {
+            return true;
+        }
+    }
+    let path = tcx.def_path_str(id);
+    DIGEST_FUNCTIONS.contains(&path.as_str()) || others.iter().any(|other| *other == path)
+}
+
+/// Whether `id` is a method of a trait whose methods return a view of the value they are called on,
+/// like `AsRef::as_ref` or `Deref::deref`.
+pub(crate) fn is_conversion(tcx: TyCtxt<'_>, id: DefId) -> bool {
+    tcx.trait_of_item(id).map_or(false, |trait_id| {
+        CONVERSION_TRAITS.contains(&tcx.def_path_str(trait_id).as_str())
+    })
+}
+
+/// Whether `id`, called with `args`, is `ToString::to_string` on a newtype with an impl of `Display`,
+/// rather than a generic type whose impl is not known yet.
+fn is_newtype_to_string<'tcx>(tcx: TyCtxt<'tcx>, id: DefId, args: GenericArgsRef<'tcx>) -> bool {
+    let is_to_string = tcx.trait_of_item(id).map_or(false, |trait_id| {
+        tcx.def_path_str(trait_id) == "std::string::ToString"
+    }) && tcx.item_name(id).as_str() == "to_string";
+    let Some(ty) = args.types().next().filter(|_| is_to_string) else {
+        return false;
+    };
+    let ty = ty.peel_refs();
+    let TyKind::Adt(adt, _) = ty.kind() else {
+        return false;
+    };
+    if !adt.is_struct() || adt.non_enum_variant().fields.len() != 1 {
+        return false;
+    }
+    let fmt = tcx
+        .get_diagnostic_item(Symbol::intern("Display"))
+        .and_then(|display| {
+            tcx.associated_items(display)
+                .in_definition_order()
+                .find(|item| item.name.as_str() == "fmt")
+        });
+    fmt.map_or(false, |fmt| {
+        compiler::resolve(tcx, fmt.def_id, tcx.mk_args(&[ty.into()])).0 != fmt.def_id
+    })
+}
+
+/// Whether `ty` is a `taint_runtime::Sensitive`.
+pub(crate) fn is_sensitive<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+    ty.ty_adt_def()
+        .map_or(false, |adt| tcx.def_path_str(adt.did()) == SENSITIVE)
+}
+
+/// Whether the crate can use `taint_runtime::Sensitive`, whose secrets get a label of their own.
+pub(crate) fn uses_sensitive(tcx: TyCtxt<'_>) -> bool {
+    let is_sensitive = |id: DefId| tcx.def_path_str(id) == SENSITIVE;
+    tcx.crates(())
+        .iter()
+        .any(|&krate| tcx.crate_name(krate).as_str() == "taint_runtime")
+        || tcx
+            .hir()
+            .items()
+            .any(|item| is_sensitive(item.owner_id.to_def_id()))
+}
+
+/// Whether dropping a value of `ty` zeroizes it: it is one of the wrappers of `zeroize` and `secrecy`,
+/// or implements `zeroize::ZeroizeOnDrop`, like the types deriving it.
+pub(crate) fn zeroizes_on_drop<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+    let Some(adt) = ty.ty_adt_def() else {
+        return false;
+    };
+    if ZEROIZING_WRAPPERS.contains(&tcx.def_path_str(adt.did()).as_str()) {
+        return true;
+    }
+    tcx.all_traits()
+        .filter(|&trait_id| tcx.def_path_str(trait_id) == "zeroize::ZeroizeOnDrop")
+        .flat_map(|trait_id| tcx.all_impls(trait_id))
+        .any(|impl_id| {
+            tcx.type_of(impl_id)
+                .instantiate_identity()
+                .ty_adt_def()
+                .map_or(false, |implementor| implementor.did() == adt.did())
+        })
+}
+
+/// Whether `ty` is a once or lazy cell, whose value code may set at any point, like that of a global.
+pub(crate) fn is_cell<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+    ty.ty_adt_def().map_or(false, |adt| {
+        let path = tcx.def_path_str(adt.did());
+        ONCE_CELLS.contains(&path.as_str()) || LAZY_CELLS.contains(&path.as_str())
+    })
+}
+
+/// The effect of the intrinsic called `name`, if it is modeled.
+pub(crate) fn intrinsic(name: &str) -> Option<Intrinsic> {
+    let exact = INTRINSICS.iter().find(|(intrinsic, _)| *intrinsic == name);
+    let family = || {
+        ATOMIC_INTRINSICS
+            .iter()
+            .find(|(prefix, _)| name.starts_with(prefix))
+    };
+    exact.or_else(family).map(|&(_, effect)| effect)
+}
+
+/// The indices of the arguments of `id` which `pack` checks, if `id` is one of its sinks.
+pub(crate) fn sink_pack_arguments(
+    tcx: TyCtxt<'_>,
+    pack: SinkPack,
+    id: DefId,
+) -> Option<Vec<usize>> {
+    match pack {
+        SinkPack::Allocation => allocation_size(tcx, id).map(|ix| vec![ix]),
+        SinkPack::Pointer => pointer_amount(tcx, id).map(|ix| vec![ix]),
+        SinkPack::Process => process_arguments(tcx, id),
+        SinkPack::Resource => allocation_size(tcx, id)
+            .or_else(|| resource_amount(tcx, id))
+            .map(|ix| vec![ix]),
+        SinkPack::Unsafe => unsafe_arguments(tcx, id),
+        SinkPack::Index => index_position(tcx, id).map(|ix| vec![ix]),
+        SinkPack::Sql => sql_query(tcx, id).map(|ix| vec![ix]),
+    }
+}
+
+/// The index of the argument of `id` which is the text of an SQL query, for functions in the SQL sink pack:
+/// the functions of `sqlx` and `diesel` building a query from its text, and the methods of the connections
+/// of `rusqlite`, `postgres` and `mysql` running one. The parameters bound to a query, with `Query::bind`
+/// or passed along its text like those of `Connection::execute`, are not checked, which is what sets
+/// a query built from tainted data apart from one tainted data is bound to.
+fn sql_query(tcx: TyCtxt<'_>, id: DefId) -> Option<usize> {
+    let name = tcx.item_name(id);
+    let name = name.as_str();
+    let krate = tcx.crate_name(id.krate);
+    let Some(impl_id) = tcx.impl_of_method(id) else {
+        return match (krate.as_str(), tcx.trait_of_item(id)) {
+            ("sqlx" | "sqlx_core", None) => matches!(
+                name,
+                "query"
+                    | "query_as"
+                    | "query_scalar"
+                    | "query_with"
+                    | "query_as_with"
+                    | "query_scalar_with"
+                    | "raw_sql"
+            )
+            .then_some(0),
+            ("diesel", None) => matches!(name, "sql_query" | "sql").then_some(0),
+            // The methods of `Queryable`, which connections implement, take the query or statement first.
+            ("mysql" | "mysql_async", Some(_)) => {
+                (name.starts_with("query") || name.starts_with("exec") || name == "prep")
+                    .then_some(1)
+            }
+            _ => None,
+        };
+    };
+    // Statements run with their parameters alone, like `Statement::query` of `rusqlite`, are left out.
+    let adt = tcx.type_of(impl_id).instantiate_identity().ty_adt_def()?;
+    let is_connection = matches!(
+        tcx.item_name(adt.did()).as_str(),
+        "Connection" | "Client" | "Transaction"
+    );
+    let runs_query = match krate.as_str() {
+        "rusqlite" => matches!(
+            name,
+            "execute"
+                | "execute_batch"
+                | "prepare"
+                | "prepare_cached"
+                | "query_row"
+                | "query_row_and_then"
+        ),
+        "postgres" | "tokio_postgres" => matches!(
+            name,
+            "query"
+                | "query_one"
+                | "query_opt"
+                | "query_raw"
+                | "execute"
+                | "execute_raw"
+                | "prepare"
+                | "prepare_typed"
+                | "batch_execute"
+                | "simple_query"
+        ),
+        _ => false,
+    };
+    (is_connection && runs_query).then_some(1)
+}
+
+/// The index of the argument of `id` which decides how much memory it allocates,
+/// for functions in the allocation sink pack.
+fn allocation_size(tcx: TyCtxt<'_>, id: DefId) -> Option<usize> {
+    let name = tcx.item_name(id);
+
+    // `vec![x; n]` expands to `alloc::vec::from_elem(x, n)`.
+    let is_free_fn = tcx.impl_of_method(id).is_none() && tcx.trait_of_item(id).is_none();
+    if is_free_fn && tcx.crate_name(id.krate).as_str() == "alloc" && name.as_str() == "from_elem" {
+        return Some(1);
+    }
+
+    let adt = tcx
+        .impl_of_method(id)
+        .and_then(|impl_id| tcx.type_of(impl_id).instantiate_identity().ty_adt_def())?;
+    let is_collection = tcx.is_diagnostic_item(Symbol::intern("Vec"), adt.did())
+        || tcx.lang_items().string() == Some(adt.did());
+    if !is_collection {
+        return None;
+    }
+    match name.as_str() {
+        "with_capacity" => Some(0),
+        "reserve" | "reserve_exact" => Some(1),
+        _ => None,
+    }
+}
+
+/// The index of the argument of `id` which decides how long it waits or how much it reads,
+/// for functions in the resource sink pack besides those of the allocation pack.
+/// The buffer `read_exact` fills carries the labels of the length it was sliced to.
+fn resource_amount(tcx: TyCtxt<'_>, id: DefId) -> Option<usize> {
+    let name = tcx.item_name(id);
+    match tcx.trait_of_item(id) {
+        Some(trait_id) => (tcx.def_path_str(trait_id) == "std::io::Read"
+            && name.as_str() == "read_exact")
+            .then_some(1),
+        None => (tcx.def_path_str(id) == "std::thread::sleep").then_some(0),
+    }
+}
+
+/// The index of the argument of `id` which is the position or key it reads or writes at,
+/// for functions in the index sink pack: `Index::index` and `IndexMut::index_mut`, which `data[i]`
+/// calls for collections like `Vec`, and the `get_unchecked` methods of slices and strings.
+/// Indexing slices and arrays directly is a projection of a place rather than a call.
+fn index_position(tcx: TyCtxt<'_>, id: DefId) -> Option<usize> {
+    let lang_items = tcx.lang_items();
+    let name = tcx.item_name(id);
+    if let Some(trait_id) = tcx.trait_of_item(id) {
+        let is_index = Some(trait_id) == lang_items.index_trait()
+            || Some(trait_id) == lang_items.index_mut_trait();
+        return is_index.then_some(1);
+    }
+
+    let self_ty = tcx.type_of(tcx.impl_of_method(id)?).instantiate_identity();
+    let is_sequence = matches!(self_ty.kind(), TyKind::Slice(_) | TyKind::Str);
+    (is_sequence && matches!(name.as_str(), "get_unchecked" | "get_unchecked_mut")).then_some(1)
+}
+
+/// The index of the argument of `id` which offsets a raw pointer, or decides how much memory
+/// an unsafe function reads or writes, for functions in the pointer sink pack.
+fn pointer_amount(tcx: TyCtxt<'_>, id: DefId) -> Option<usize> {
+    if tcx.crate_name(id.krate).as_str() != "core" {
+        return None;
+    }
+    let name = tcx.item_name(id);
+
+    // `ptr::copy_nonoverlapping`, `ptr::copy` and `ptr::write_bytes` are intrinsics,
+    // and `slice::from_raw_parts` takes the length after the pointer.
+    let is_free_fn = tcx.impl_of_method(id).is_none() && tcx.trait_of_item(id).is_none();
+    if is_free_fn {
+        return match name.as_str() {
+            "copy_nonoverlapping" | "copy" | "write_bytes" => Some(2),
+            "from_raw_parts" | "from_raw_parts_mut" => Some(1),
+            _ => None,
+        };
+    }
+
+    let is_raw_pointer = tcx.impl_of_method(id).map_or(false, |impl_id| {
+        tcx.type_of(impl_id).instantiate_identity().is_unsafe_ptr()
+    });
+    if !is_raw_pointer {
+        return None;
+    }
+    match name.as_str() {
+        "add" | "sub" | "offset" | "byte_add" | "byte_sub" | "byte_offset" => Some(1),
+        "copy_to"
+        | "copy_from"
+        | "copy_to_nonoverlapping"
+        | "copy_from_nonoverlapping"
+        | "write_bytes" => Some(2),
+        _ => None,
+    }
+}
+
+/// The indices of the arguments of `id` which end up in the environment of the process,
+/// or in the program, arguments or environment of a child process, for functions in the process sink pack.
+/// Spawning a command checks the command itself, which carries the labels of what it was built from.
+fn process_arguments(tcx: TyCtxt<'_>, id: DefId) -> Option<Vec<usize>> {
+    if tcx.crate_name(id.krate).as_str() != "std" {
+        return None;
+    }
+    let name = tcx.item_name(id);
+
+    let is_free_fn = tcx.impl_of_method(id).is_none() && tcx.trait_of_item(id).is_none();
+    if is_free_fn {
+        return match tcx.def_path_str(id).as_str() {
+            "std::env::set_var" => Some(vec![0, 1]),
+            _ => None,
+        };
+    }
+
+    let adt = tcx
+        .impl_of_method(id)
+        .and_then(|impl_id| tcx.type_of(impl_id).instantiate_identity().ty_adt_def())?;
+    if tcx.def_path_str(adt.did()) != "std::process::Command" {
+        return None;
+    }
+    match name.as_str() {
+        "new" => Some(vec![0]),
+        "arg" | "args" | "envs" => Some(vec![1]),
+        "env" => Some(vec![1, 2]),
+        "spawn" | "output" | "status" => Some(vec![0]),
+        _ => None,
+    }
+}
+
+/// The indices of every argument of `id` if it is an `unsafe fn`, for the unsafe sink pack.
+/// Foreign functions are unsafe to call too.
+fn unsafe_arguments(tcx: TyCtxt<'_>, id: DefId) -> Option<Vec<usize>> {
+    if !matches!(tcx.def_kind(id), DefKind::Fn | DefKind::AssocFn) {
+        return None;
+    }
+    let sig = tcx.fn_sig(id).skip_binder();
+    (sig.unsafety() == hir::Unsafety::Unsafe)
+        .then(|| (0..sig.inputs().skip_binder().len()).collect())
+}
+
+/// The body which is run when a task of type `ty` is spawned.
+fn task_body<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<(DefId, GenericArgsRef<'tcx>)> {
+    // `async fn` calls return an opaque future which is backed by a generator.
+    match compiler::reveal(tcx, ty).kind() {
+        TyKind::Generator(id, args, _) | TyKind::Closure(id, args) => Some((*id, args)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intrinsics_are_looked_up_by_name_and_family() {
+        assert_eq!(
+            intrinsic("copy_nonoverlapping"),
+            Some(Intrinsic::Write { dst: 1, from: 0 })
+        );
+        assert_eq!(
+            intrinsic("atomic_store_release"),
+            Some(Intrinsic::Write { dst: 0, from: 1 })
+        );
+        assert_eq!(intrinsic("size_of"), Some(Intrinsic::Clean));
+        assert_eq!(intrinsic("abort"), None);
+    }
+}
@@ -0,0 +1,169 @@
+//! Reduced programs reproducing findings, for `--taint-reproducers=<dir>`.
+//!
+//! Each finding is written to `<fingerprint>.rs` as a standalone program with the shape of its slice:
+//! a stub of its source, a stub passing its input through for each call or macro the slice goes through,
+//! and a stub of its sink, checking the label the finding was reported with. The program can be attached
+//! to a bug report, or added to the tests of a project, and the analysis reports it like it reported the finding,
+//! without the config of the project. The lines of the slice are kept in a comment, to tell what the stubs
+//! stand for. Every finding is reproduced as tainted input reaching a sink, even those of other kinds,
+//! like a sink missing the sanitizer it requires.
+
+use std::collections::HashMap;
+
+use crate::{
+    compiler::{self, DefId, Span, TyCtxt},
+    models,
+    report::Finding,
+};
+
+/// What a finding flowed through, from its source to its sink.
+pub(crate) struct Flow<'a> {
+    pub(crate) finding: &'a Finding,
+    /// The source its tainted input came from, if the analysis knows which.
+    pub(crate) source: Option<DefId>,
+    /// The calls its slice goes through besides the sink, in the order they appear in the source.
+    pub(crate) calls: Vec<(Span, DefId)>,
+    /// The lines of the slice, as `--taint-slice` prints them.
+    pub(crate) slice: String,
+}
+
+/// The program reproducing `flow`.
+pub(crate) fn program(tcx: TyCtxt<'_>, flow: &Flow<'_>) -> String {
+    let finding = flow.finding;
+    // Labels of required sanitizers, like `unsanitized:demo::escape`, cannot be given to a source.
+    let label = if finding.label == "taint" || finding.label.contains(':') {
+        String::new()
+    } else {
+        format!("(label = {:?})", finding.label)
+    };
+    let mut names = Names::default();
+
+    let source = match flow.source {
+        Some(id) => names.name(&item_name(tcx, id), &format!("`{}`", tcx.def_path_str(id))),
+        None => names.name("source", "the tainted input of the finding"),
+    };
+    let mut steps = vec![];
+    let mut expanded = None;
+    for &(span, callee) in &flow.calls {
+        // Views of a value, like those autoderef calls, do not change what it holds.
+        if Some(callee) == flow.source || models::is_conversion(tcx, callee) {
+            continue;
+        }
+        // The calls a macro expands to, like those of `format!`, are one step.
+        match compiler::outermost_macro(span) {
+            Some((_, call_site)) if expanded == Some(call_site) => {}
+            Some((name, call_site)) => {
+                expanded = Some(call_site);
+                steps.push(names.name(&name, &format!("`{}!`", name)));
+            }
+            None => {
+                let stands_for = format!("`{}`", tcx.def_path_str(callee));
+                steps.push(names.name(&item_name(tcx, callee), &stands_for));
+            }
+        }
+    }
+    let last_segment = finding.sink.rsplit("::").next().unwrap_or_default();
+    let sink = names.name(
+        last_segment.trim_end_matches('!'),
+        &format!("`{}`", finding.sink),
+    );
+
+    let mut program = format!(
+        "//! Reproduces finding {}: {}, in `{}` at {}:{}:{}.\n",
+        finding.fingerprint,
+        finding.message,
+        finding.function,
+        finding.file,
+        finding.start.line,
+        finding.start.column
+    );
+    program.push_str("//!\n");
+    program.push_str(&format!(
+        "//! The lines of `{}` the input flowed through:\n",
+        finding.function
+    ));
+    program.push_str("//!\n//! ```text\n");
+    for line in flow.slice.lines() {
+        program.push_str(&format!("//! {}\n", line));
+    }
+    program.push_str("//! ```\n\n");
+    program.push_str("#![feature(register_tool)]\n#![register_tool(taint)]\n\n");
+
+    program.push_str("fn main() {\n");
+    program.push_str(&format!("    let tainted = {}();\n", source));
+    for step in &steps {
+        program.push_str(&format!("    let tainted = {}(tainted);\n", step));
+    }
+    program.push_str(&format!("    {}(tainted);\n}}\n", sink));
+
+    program.push_str(&format!(
+        "\n/// Stands for {}.\n#[taint::source{}]\nfn {}() -> String {{\n    String::new()\n}}\n",
+        names.stands_for(&source),
+        label,
+        source
+    ));
+    let mut defined = vec![];
+    for step in steps {
+        if defined.contains(&step) {
+            continue;
+        }
+        program.push_str(&format!(
+            "\n/// Stands for {}.\nfn {}(input: String) -> String {{\n    input\n}}\n",
+            names.stands_for(&step),
+            step
+        ));
+        defined.push(step);
+    }
+    program.push_str(&format!(
+        "\n/// Stands for {}.\n#[taint::sink{}]\nfn {}(_: String) {{}}\n",
+        names.stands_for(&sink),
+        label,
+        sink
+    ));
+    program
+}
+
+/// The names of the stubs of a program, each given to one of what they stand for.
+#[derive(Default)]
+struct Names {
+    /// What each name stands for.
+    given: HashMap<String, String>,
+}
+
+impl Names {
+    /// The name of the stub standing for `stands_for`, named after `name` if it is free and can name a function.
+    fn name(&mut self, name: &str, stands_for: &str) -> String {
+        let is_ident = name
+            .chars()
+            .next()
+            .map_or(false, |c| c.is_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+            && name != "main";
+        let base = if is_ident { name } else { "step" };
+        let mut candidate = base.to_owned();
+        let mut suffix = 1;
+        loop {
+            match self.given.get(&candidate) {
+                Some(given) if given == stands_for => return candidate,
+                Some(_) => {
+                    suffix += 1;
+                    candidate = format!("{}_{}", base, suffix);
+                }
+                None => {
+                    self.given.insert(candidate.clone(), stands_for.to_owned());
+                    return candidate;
+                }
+            }
+        }
+    }
+
+    fn stands_for(&self, name: &str) -> &str {
+        &self.given[name]
+    }
+}
+
+/// The name of the function `id`, or `step` for closures and others without one.
+fn item_name(tcx: TyCtxt<'_>, id: DefId) -> String {
+    tcx.opt_item_name(id)
+        .map_or_else(|| "step".to_owned(), |name| name.to_string())
+}
@@ -0,0 +1,216 @@
+//! Slicing a function down to the statements a finding flows through, for `--taint-slice`.
+//!
+//! The slice starts from what the sink received, and walks back through the assignments and calls
+//! which computed it, and those which wrote to it through a mutable borrow.
+//! Apart from writes, only statements which assigned a tainted value are followed,
+//! so code which merely reads the same variables is left out.
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::compiler::{
+    self, Body, Call, DefId, Local, Operand, Span, StatementKind, TerminatorKind, TyCtxt,
+};
+
+use super::facts::Facts;
+
+/// The spans of the statements in `function` which the sink at `sink` received tainted input through,
+/// and the span of the sink itself.
+pub(crate) fn slice(tcx: TyCtxt<'_>, facts: &Facts, function: DefId, sink: Span) -> Vec<Span> {
+    let Some(body) = compiler::mir_body(tcx, function) else {
+        return vec![];
+    };
+    walk(body, sink, |span| facts.tainted(function, span)).0
+}
+
+/// The calls in `function` which the sink at `sink` received tainted input through, other than the sink,
+/// in the order they appear in the source, with the function each calls.
+pub(crate) fn calls(
+    tcx: TyCtxt<'_>,
+    facts: &Facts,
+    function: DefId,
+    sink: Span,
+) -> Vec<(Span, DefId)> {
+    let Some(body) = compiler::mir_body(tcx, function) else {
+        return vec![];
+    };
+    let spans = walk(body, sink, |span| facts.tainted(function, span)).0;
+    let mut calls = body
+        .basic_blocks
+        .iter()
+        .filter_map(|data| {
+            let span = data.terminator().source_info.span;
+            let call = Call::from_terminator(&data.terminator().kind)?;
+            let is_sink = span == sink || span.source_callsite() == sink;
+            (!is_sink && spans.contains(&span)).then_some((span, call.callee))
+        })
+        .collect::<Vec<_>>();
+    calls.sort_by_key(|&(span, _)| span.lo());
+    calls.dedup();
+    calls
+}
+
+/// Whether what the sink at `sink` in `function` received was computed from the result of the call at `call`,
+/// or from what the call wrote to through a mutable borrow, whether it was tainted or not.
+pub(crate) fn depends_on(tcx: TyCtxt<'_>, function: DefId, sink: Span, call: Span) -> bool {
+    let Some(body) = compiler::mir_body(tcx, function) else {
+        return false;
+    };
+    let relevant = walk(body, sink, |_| true).1;
+    body.basic_blocks.iter().any(|data| {
+        Call::from_terminator(&data.terminator().kind)
+            .filter(|found| found.span == call)
+            .map_or(false, |found| {
+                relevant.contains(&found.destination.local)
+                    || operand_locals(found.args)
+                        .into_iter()
+                        .any(|l| relevant.contains(&l) && compiler::is_mutable_pointer(body, l))
+            })
+    })
+}
+
+/// Whether the call at `call` in `function` runs on every path to the sink at `sink`,
+/// or `None` if either is not a call in its body.
+pub(crate) fn runs_before(
+    tcx: TyCtxt<'_>,
+    function: DefId,
+    call: Span,
+    sink: Span,
+) -> Option<bool> {
+    let body = compiler::mir_body(tcx, function)?;
+    let blocks = |span: Span| {
+        body.basic_blocks
+            .iter_enumerated()
+            .filter(|(_, data)| {
+                Call::from_terminator(&data.terminator().kind).map_or(false, |found| {
+                    found.span == span || found.span.source_callsite() == span
+                })
+            })
+            .map(|(block, _)| block)
+            .collect::<Vec<_>>()
+    };
+    let (calls, sinks) = (blocks(call), blocks(sink));
+    if calls.is_empty() || sinks.is_empty() {
+        return None;
+    }
+    let dominators = body.basic_blocks.dominators();
+    Some(
+        sinks
+            .iter()
+            .all(|&sink| calls.iter().any(|&call| dominators.dominates(call, sink))),
+    )
+}
+
+/// Walks back from what the sink at `sink` received through the statements of `body` which computed it,
+/// following those at a span `tainted` accepts and writes through mutable borrows.
+/// Returns the spans of the statements followed, starting with the sink, and the locals they read.
+fn walk(
+    body: &Body<'_>,
+    sink: Span,
+    tainted: impl Fn(Span) -> bool,
+) -> (Vec<Span>, HashSet<Local>) {
+    // Sink macros are reported at their invocation, which expands into several calls.
+    let mut relevant = HashSet::new();
+    for data in body.basic_blocks.iter() {
+        let terminator = data.terminator();
+        match &terminator.kind {
+            TerminatorKind::Drop { place, .. } if terminator.source_info.span == sink => {
+                relevant.insert(place.local);
+            }
+            kind => {
+                if let Some(call) = Call::from_terminator(kind)
+                    .filter(|call| call.span == sink || call.span.source_callsite() == sink)
+                {
+                    relevant.extend(operand_locals(call.args));
+                }
+            }
+        }
+    }
+
+    let mut spans = vec![sink];
+    let mut included = HashSet::new();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (block, data) in body.basic_blocks.iter_enumerated() {
+            for (ix, statement) in data.statements.iter().enumerate() {
+                let StatementKind::Assign(box (place, rvalue)) = &statement.kind else {
+                    continue;
+                };
+                // A mutable borrow is usually taken while what it borrows is still clean,
+                // to then be tainted by writing through it.
+                let writable =
+                    compiler::mutable_borrow(rvalue).map_or(false, |l| relevant.contains(&l));
+                let span = statement.source_info.span;
+                if (writable || relevant.contains(&place.local) && tainted(span))
+                    && included.insert((block, ix))
+                {
+                    relevant.insert(place.local);
+                    relevant.extend(compiler::used_locals(rvalue));
+                    spans.push(span);
+                    changed = true;
+                }
+            }
+
+            let terminator = data.terminator();
+            let Some(call) = Call::from_terminator(&terminator.kind) else {
+                continue;
+            };
+            let args = operand_locals(call.args);
+            let span = terminator.source_info.span;
+            let returns = relevant.contains(&call.destination.local) && tainted(span);
+            let writes = args
+                .iter()
+                .any(|&l| relevant.contains(&l) && compiler::is_mutable_pointer(body, l));
+            if (returns || writes) && included.insert((block, data.statements.len())) {
+                relevant.insert(call.destination.local);
+                relevant.extend(args);
+                spans.push(span);
+                changed = true;
+            }
+        }
+    }
+    (spans, relevant)
+}
+
+/// Renders the lines of source `spans` cover, in order, with the lines of `sink` marked by `>`.
+/// Lines in other files than the sink, like those of inlined functions, are left out.
+pub(crate) fn render(tcx: TyCtxt<'_>, spans: &[Span], sink: Span) -> String {
+    let file = |span| compiler::source_range(tcx, span).map(|range| range.file);
+    let sink_file = file(sink);
+    let sink_lines = compiler::source_lines(tcx, sink)
+        .into_iter()
+        .map(|(line, _)| line)
+        .collect::<HashSet<_>>();
+
+    let mut lines = BTreeMap::new();
+    for &span in spans {
+        // Code expanded from a macro is shown as the invocation of the macro.
+        let span = span.source_callsite();
+        if file(span) != sink_file {
+            continue;
+        }
+        lines.extend(compiler::source_lines(tcx, span));
+    }
+
+    let width = lines.keys().last().map_or(1, |line| line.to_string().len());
+    let mut rendered = String::new();
+    for (line, text) in lines {
+        let marker = if sink_lines.contains(&line) { '>' } else { ' ' };
+        rendered.push_str(&format!(
+            "{} {:>width$} | {}",
+            marker,
+            line,
+            text,
+            width = width
+        ));
+        rendered.push('\n');
+    }
+    rendered
+}
+
+fn operand_locals(operands: &[Operand<'_>]) -> Vec<Local> {
+    operands
+        .iter()
+        .filter_map(|operand| operand.place().map(|place| place.local))
+        .collect()
+}
@@ -0,0 +1,252 @@
+//! Bookkeeping of how function summaries are reused across entry points.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use crate::{
+    compiler::{self, DefId, TyCtxt},
+    taint_analysis::Contexts,
+};
+
+/// The number of functions listed with the largest points-to sets.
+const MAX_POINTS_TO_LISTED: usize = 5;
+
+#[derive(Default, Debug)]
+pub(crate) struct SummaryUsage {
+    entries: Vec<DefId>,
+    functions: HashMap<DefId, FunctionUsage>,
+    /// The number of times a call to each function without MIR, which follows `--taint-unknown-calls`,
+    /// was analyzed.
+    without_mir: HashMap<DefId, usize>,
+    /// Functions with more arguments than `--taint-max-arity` allows, by their number of arguments,
+    /// which were analyzed with the labels of all of their arguments on each.
+    abstracted: HashMap<DefId, usize>,
+    /// The size of the largest set of locals a local of each function points to, in any context,
+    /// and whether a local of it was taken to point anywhere past `--taint-max-points-to`.
+    points_to: HashMap<DefId, (usize, bool)>,
+}
+
+#[derive(Default, Debug)]
+struct FunctionUsage {
+    /// The number of contexts the function was analyzed in.
+    contexts: usize,
+    /// The number of times a cached summary was used instead of analyzing the function.
+    hits: usize,
+    /// The number of contexts whose summary was carried over from the previous run instead.
+    carried: usize,
+    /// The entry points which needed a summary of the function.
+    entries: Vec<DefId>,
+}
+
+impl SummaryUsage {
+    pub(crate) fn enter(&mut self, entry: DefId) {
+        self.entries.push(entry);
+    }
+
+    /// A summary of `id` was computed while analyzing `entry`.
+    pub(crate) fn computed(&mut self, entry: DefId, id: DefId) {
+        self.record(entry, id).contexts += 1;
+    }
+
+    /// A cached summary of `id` was used while analyzing `entry`.
+    pub(crate) fn reused(&mut self, entry: DefId, id: DefId) {
+        self.record(entry, id).hits += 1;
+    }
+
+    /// A summary of `id` carried over from the previous run was used while analyzing `entry`.
+    pub(crate) fn carried(&mut self, entry: DefId, id: DefId) {
+        self.record(entry, id).carried += 1;
+    }
+
+    /// A call to `id`, which has no MIR to analyze, like a foreign function or an intrinsic,
+    /// fell back to the policy for unknown calls.
+    pub(crate) fn without_mir(&mut self, id: DefId) {
+        *self.without_mir.entry(id).or_default() += 1;
+    }
+
+    /// `id`, which takes `arity` arguments, was analyzed with the labels of all of its arguments on each.
+    pub(crate) fn abstracted(&mut self, id: DefId, arity: usize) {
+        self.abstracted.insert(id, arity);
+    }
+
+    /// A local of `id` pointed to `largest` locals, and some may point `anywhere`.
+    pub(crate) fn points_to(&mut self, id: DefId, largest: usize, anywhere: bool) {
+        let recorded = self.points_to.entry(id).or_default();
+        recorded.0 = recorded.0.max(largest);
+        recorded.1 |= anywhere;
+    }
+
+    /// The number of functions whose body was analyzed, or whose summary was reused or carried over,
+    /// and the number of functions called without MIR to analyze.
+    pub(crate) fn coverage(&self) -> (usize, usize) {
+        (self.functions.len(), self.without_mir.len())
+    }
+
+    /// The dependencies whose MIR was not encoded, by name, each with the functions of it which were called,
+    /// whose calls followed the policy for unknown calls instead of being analyzed.
+    pub(crate) fn not_encoded(&self, tcx: TyCtxt<'_>) -> BTreeMap<String, Vec<String>> {
+        let mut crates = BTreeMap::<_, Vec<_>>::new();
+        for &id in self.without_mir.keys() {
+            if compiler::mir_not_encoded(tcx, id) {
+                crates
+                    .entry(tcx.crate_name(id.krate).to_string())
+                    .or_default()
+                    .push(tcx.def_path_str(id));
+            }
+        }
+        for functions in crates.values_mut() {
+            functions.sort();
+        }
+        crates
+    }
+
+    fn record(&mut self, entry: DefId, id: DefId) -> &mut FunctionUsage {
+        let usage = self.functions.entry(id).or_default();
+        if !usage.entries.contains(&entry) {
+            usage.entries.push(entry);
+        }
+        usage
+    }
+
+    /// Lists every summarized function, with the entry points that shared it,
+    /// and the number of times it had to be analyzed again in another context,
+    /// followed by how much memory the states of the summaries cached in `contexts` take.
+    pub(crate) fn report(&self, tcx: TyCtxt<'_>, contexts: &Contexts<'_>) -> String {
+        let mut functions = self
+            .functions
+            .iter()
+            .map(|(id, usage)| (tcx.def_path_str(*id), usage))
+            .collect::<Vec<_>>();
+        functions.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut report = format!(
+            "summary reuse across {}:\n",
+            plural(self.entries.len(), "entry point", "entry points")
+        );
+        for (path, usage) in functions {
+            let mut entries = usage
+                .entries
+                .iter()
+                .map(|id| format!("`{}`", tcx.def_path_str(*id)))
+                .collect::<Vec<_>>();
+            entries.sort();
+
+            let _ = write!(
+                report,
+                "  `{}`: {}",
+                path,
+                plural(usage.contexts, "context", "contexts")
+            );
+            if usage.contexts > 1 {
+                let _ = write!(report, " ({} reanalyzed)", usage.contexts - 1);
+            }
+            let _ = write!(
+                report,
+                ", {} by {} ({})",
+                if entries.len() > 1 {
+                    "shared"
+                } else {
+                    "needed"
+                },
+                plural(entries.len(), "entry point", "entry points"),
+                entries.join(", ")
+            );
+            if usage.carried > 0 {
+                let _ = write!(
+                    report,
+                    ", {} carried over from the previous run",
+                    plural(usage.carried, "context", "contexts")
+                );
+            }
+            if usage.hits > 0 {
+                let _ = write!(report, ", reused {}", plural(usage.hits, "time", "times"));
+            }
+            report.push('\n');
+        }
+
+        let (summaries, bytes) = contexts
+            .values()
+            .flatten()
+            .fold((0, 0), |(summaries, bytes), summary| {
+                (summaries + 1, bytes + summary.state.heap_size())
+            });
+        let _ = writeln!(
+            report,
+            "{} cached, whose states take {}",
+            plural(summaries, "summary", "summaries"),
+            plural(bytes, "byte", "bytes")
+        );
+
+        if !self.without_mir.is_empty() {
+            let mut functions = self
+                .without_mir
+                .iter()
+                .map(|(id, calls)| (tcx.def_path_str(*id), *calls))
+                .collect::<Vec<_>>();
+            functions.sort();
+
+            let _ = writeln!(
+                report,
+                "calls to {} without MIR, following --taint-unknown-calls:",
+                plural(functions.len(), "function", "functions")
+            );
+            for (path, calls) in functions {
+                let _ = writeln!(report, "  `{}`: {}", path, plural(calls, "call", "calls"));
+            }
+        }
+
+        if !self.abstracted.is_empty() {
+            let mut functions = self
+                .abstracted
+                .iter()
+                .map(|(id, arity)| (tcx.def_path_str(*id), *arity))
+                .collect::<Vec<_>>();
+            functions.sort();
+
+            let _ = writeln!(
+                report,
+                "{} with more arguments than --taint-max-arity, analyzed with the labels of all of them on each:",
+                plural(functions.len(), "function", "functions")
+            );
+            for (path, arity) in functions {
+                let _ = writeln!(
+                    report,
+                    "  `{}`: {}",
+                    path,
+                    plural(arity, "argument", "arguments")
+                );
+            }
+        }
+
+        // Only locals pointing to several others make reads and writes through them costly.
+        let mut functions = self
+            .points_to
+            .iter()
+            .filter(|(_, &(largest, _))| largest > 1)
+            .map(|(id, &(largest, anywhere))| (largest, tcx.def_path_str(*id), anywhere))
+            .collect::<Vec<_>>();
+        if !functions.is_empty() {
+            functions.sort_by(|(a, a_path, _), (b, b_path, _)| b.cmp(a).then(a_path.cmp(b_path)));
+            functions.truncate(MAX_POINTS_TO_LISTED);
+            let _ = writeln!(report, "largest points-to sets:");
+            for (largest, path, anywhere) in functions {
+                let _ = write!(
+                    report,
+                    "  `{}`: {}",
+                    path,
+                    plural(largest, "local", "locals")
+                );
+                if anywhere {
+                    let _ = write!(report, ", pointing anywhere past --taint-max-points-to");
+                }
+                report.push('\n');
+            }
+        }
+        report
+    }
+}
+
+/// `n` followed by `one` or `many`, whichever agrees with it.
+pub(crate) fn plural(n: usize, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
@@ -1,34 +1,120 @@
 use std::{
-    cell::RefCell,
+    cell::{Cell, OnceCell, RefCell},
     collections::{HashMap, HashSet},
-    rc::Rc,
+    fmt::Write,
 };
 
-use rustc_errors::struct_span_err;
-use rustc_hir::def_id::DefId;
-use rustc_index::bit_set::BitSet;
-use rustc_middle::{
-    mir::{
-        traversal::reverse_postorder, visit::Visitor, BasicBlock, Body, Constant, HasLocalDecls,
-        Local, Location, Operand, Place, Rvalue, Statement, StatementKind, Terminator,
-        TerminatorKind,
+use tracing::{info_span, instrument};
+
+use crate::{
+    compiler::{
+        self, Analysis, AnalysisDomain, BasicBlock, BinOp, Body, Call, CallReturnPlaces,
+        DebugWithContext, DefId, Diagnostics, ErrorCode, Forward, GenericArgsRef, HasLocalDecls,
+        Idx, IndexVec, InlineAsmOperand, JoinSemiLattice, Local, Location, MirVisitor,
+        NonDivergingIntrinsic, Operand, PairElement, Place, ResultsCursor, Rvalue, Serialized,
+        Shim, Span, Statement, StatementKind, SwitchIntEdgeEffects, Terminator, TerminatorKind, Ty,
+        TyCtxt, TyKind, LOCAL_CRATE, RETURN_PLACE,
+    },
+    eval::attributes::{Accessor, AccessorKind, AttrInfo, AttrInfoKind},
+    hook::{PolicyHook, Role},
+    options::{
+        Options, SinkPack, UnknownCalls, DEFAULT_BLOCK_SUMMARIES, DEFAULT_MAX_ARITY,
+        DEFAULT_MAX_POINTS_TO,
     },
-    ty::{TyCtxt, TyKind},
 };
 
-use rustc_mir_dataflow::{Analysis, AnalysisDomain, CallReturnPlaces, Forward};
-use rustc_span::Span;
+use super::{
+    callbacks::Callbacks,
+    checkpoint::Checkpoint,
+    dependency_cache::DependencyCache,
+    facts::Facts,
+    findings::{Approximation, Called, Findings, Frame as Reached, Recorded, Sink},
+    interchange::Imported,
+    invariants::{self, Invariants},
+    ir::{self, CallEffect},
+    labels::{self, Label, Labels, TAINT},
+    models::{self, ErrorMethod, Intrinsic, MapMethod, Model, PairAdapter, SensitiveMethod},
+    summary_usage::SummaryUsage,
+    taint_domain::{self, PointsAwareTaintDomain, PointsMap, TaintDomain, TaintState},
+    transfer::BlockTransfer,
+    warm::{Trace, Warm},
+};
+
+pub(crate) type Contexts<'tcx> = HashMap<ContextKey<'tcx>, Option<Summary>>;
 
-use tracing::instrument;
+/// A function, the generic arguments it is instantiated with, and the labels of its arguments.
+pub(crate) type ContextKey<'tcx> = (DefId, GenericArgsRef<'tcx>, InitSet);
 
-use crate::eval::attributes::{AttrInfo, AttrInfoKind};
+/// The labels of each argument a function is called with.
+pub(crate) type InitSet = Vec<Labels>;
 
-use super::taint_domain::{PointsAwareTaintDomain, TaintDomain};
+/// A function called without a role of its own, and the constant arguments of the call, if any.
+type HookedCall = (DefId, Vec<Option<String>>);
 
-pub(crate) type PointsMap = HashMap<Local, HashSet<Local>>;
-pub(crate) type Contexts = HashMap<(DefId, InitSet), Option<BitSet<Local>>>;
+/// What a function does to taint when called in a particular context.
+#[derive(Debug, Clone)]
+pub(crate) struct Summary {
+    /// The state the function may return in.
+    /// Callers only read the locals of the arguments from it, so a summary resumed from a checkpoint
+    /// holds those alone, and the rest of its locals read as untainted.
+    pub(crate) state: TaintState<Local>,
+    /// The labels of the returned value, including those of what it points to.
+    pub(crate) returns: Labels,
+    /// The arguments whose referents the returned value may point into.
+    /// Pointers are followed through the abstract objects they point to,
+    /// so that a callee writing into the contents of a `Vec` passed to it is seen by the caller.
+    pub(crate) points_into: Vec<usize>,
+    /// Whether a recursive call was cut off in the function, or in a callee whose summary was used.
+    pub(crate) approximated: bool,
+}
+
+/// State shared between the analyses of all entry points.
+#[derive(Default)]
+pub(crate) struct Shared<'tcx> {
+    /// Function summaries, so that callees are only analyzed once per context.
+    pub(crate) contexts: RefCell<Contexts<'tcx>>,
+    pub(crate) usage: RefCell<SummaryUsage>,
+    pub(crate) facts: RefCell<Facts>,
+    pub(crate) findings: RefCell<Findings>,
+    /// Summaries used instead of analyzing the functions they describe.
+    pub(crate) imported: Imported,
+    /// Summaries from the interfaces of the libraries the crate depends on, used for their functions without MIR.
+    pub(crate) interfaces: Imported,
+    /// The functions being analyzed, from the entry point to the innermost callee.
+    pub(crate) stack: RefCell<Vec<Frame>>,
+    /// Closures turned into trait objects, which calls through trait objects may run.
+    pub(crate) callbacks: RefCell<Callbacks<'tcx>>,
+    /// Summaries carried over from the previous run, and what the analysis of each context went through.
+    pub(crate) warm: RefCell<Warm<'tcx>>,
+    /// Where the summaries of the functions of dependencies are cached, with `--taint-dependency-cache`.
+    pub(crate) dependency_cache: Option<DependencyCache>,
+    /// Where the run is checkpointed, with `--taint-resume`.
+    pub(crate) checkpoint: Option<Checkpoint>,
+    /// The functions whose analysis did something reusing their summary would skip, like reporting a finding,
+    /// in some context, which the dependency cache leaves out.
+    pub(crate) side_effects: RefCell<HashSet<DefId>>,
+    /// The labels written so far into the cells statics hold, like `static CONFIG: OnceLock<Config>`,
+    /// which every read of the static carries from then on.
+    pub(crate) globals: RefCell<HashMap<DefId, Labels>>,
+    /// The script giving roles to the functions called without one, until it fails.
+    pub(crate) hook: RefCell<Option<PolicyHook>>,
+    /// The roles the hook gave, by callee and constant arguments, so that it is asked about each once.
+    pub(crate) hooked: RefCell<HashMap<HookedCall, Option<Role>>>,
+    /// With `--taint-fail-fast`, whether a finding reported as an error was found, which stops the analysis
+    /// like a cancellation does, but keeps what it found to be reported.
+    pub(crate) failed_fast: Cell<bool>,
+}
 
-type InitSet = Vec<Option<bool>>;
+/// A function being analyzed, and the context it was reached in.
+#[derive(Debug)]
+pub(crate) struct Frame {
+    function: DefId,
+    init: InitSet,
+    /// Where the function is called, unless it is analyzed as an entry point or the initializer of a static.
+    call_site: Option<Span>,
+    /// What the analysis of the function went through so far, for the next run.
+    trace: Trace,
+}
 
 /// A dataflow analysis that tracks whether a value may carry a taint.
 ///
@@ -39,119 +125,367 @@ pub struct TaintAnalysis<'tcx, 'inter> {
     tcx: TyCtxt<'tcx>,
     /// All the functions that have been marked
     info: &'inter AttrInfo,
-    contexts: Rc<RefCell<Contexts>>,
+    options: &'inter Options,
+    /// The entry point whose analysis this is a part of.
+    entry: DefId,
+    /// The function being analyzed.
+    body: DefId,
+    /// The generic arguments `body` is instantiated with, so that calls in it can be resolved.
+    args: GenericArgsRef<'tcx>,
+    /// Function summaries and bookkeeping, shared between all entry points.
+    shared: &'inter Shared<'tcx>,
     init: InitSet,
-    points: RefCell<PointsMap>,
+    points: RefCell<PointsMap<Local>>,
+    /// With `--taint-debug-invariants`, what is needed to check that the analysis only moves up the lattice.
+    invariants: Option<RefCell<Invariants>>,
+    /// Locals which never carry taint, because their type is zero-sized, or the config restricts it to kinds
+    /// of data their type does not hold.
+    untracked: Vec<Local>,
+    /// The summaries of the blocks which only move labels between locals, computed on first use,
+    /// if the function has more statements than `--taint-block-summaries`.
+    transfers: OnceCell<IndexVec<BasicBlock, Option<BlockTransfer>>>,
+    /// The block whose statements were all applied at its first one, if the last block started was.
+    summarized: Cell<Option<BasicBlock>>,
 }
 
 impl<'tcx, 'inter> TaintAnalysis<'tcx, 'inter> {
-    /// Call on `main` function
-    pub fn new(tcx: TyCtxt<'tcx>, info: &'inter AttrInfo) -> Self {
-        Self::new_with_init(
-            tcx,
-            info,
-            Rc::new(RefCell::new(Contexts::new())),
-            InitSet::new(),
-        )
-    }
-
-    /// Call on dependencies
-    #[inline]
-    fn new_with_init(
+    /// Call on an entry point, such as the `main` function.
+    /// Summaries computed for one entry point are reused by the others analyzed with the same `shared` state.
+    pub(crate) fn new(
         tcx: TyCtxt<'tcx>,
         info: &'inter AttrInfo,
-        contexts: Rc<RefCell<Contexts>>,
-        init: InitSet,
+        options: &'inter Options,
+        entry: DefId,
+        shared: &'inter Shared<'tcx>,
     ) -> Self {
+        shared.usage.borrow_mut().enter(entry);
         TaintAnalysis {
             tcx,
             info,
-            contexts,
+            options,
+            entry,
+            body: entry,
+            args: compiler::identity_args(tcx, entry),
+            shared,
+            init: InitSet::new(),
+            points: RefCell::new(points_map(options)),
+            invariants: options.debug_invariants.then(RefCell::default),
+            untracked: untracked_locals(tcx, info, entry, compiler::identity_args(tcx, entry)),
+            transfers: OnceCell::new(),
+            summarized: Cell::new(None),
+        }
+    }
+
+    /// Call on dependencies: the analysis of `body`, instantiated with `args`,
+    /// called in the context `init` as part of the same entry point.
+    #[inline]
+    fn for_callee(&self, body: DefId, args: GenericArgsRef<'tcx>, init: InitSet) -> Self {
+        TaintAnalysis {
+            body,
+            args,
             init,
-            points: RefCell::new(PointsMap::new()),
+            points: RefCell::new(points_map(self.options)),
+            invariants: self.options.debug_invariants.then(RefCell::default),
+            untracked: untracked_locals(self.tcx, self.info, body, args),
+            transfers: OnceCell::new(),
+            summarized: Cell::new(None),
+            ..*self
+        }
+    }
+
+    /// Clears the taint of the locals which cannot carry any, once an effect may have written to them.
+    fn forget_untracked(&self, state: &mut TaintState<Local>) {
+        for &local in &self.untracked {
+            state.set_taint(local, Labels::EMPTY);
+        }
+    }
+
+    /// Called before the effect of the statement or terminator at `location` is applied to `state`.
+    fn before_effect(&self, state: &TaintState<Local>, location: Location) {
+        if let Some(invariants) = &self.invariants {
+            if location.statement_index == 0 {
+                invariants.borrow_mut().enter(location.block, state);
+            }
+        }
+    }
+
+    /// With a summary of the block of `location`, applies all of its statements at the first one,
+    /// and skips the others. Returns whether the statement at `location` was applied that way.
+    fn apply_block_transfer(&self, state: &mut TaintState<Local>, location: Location) -> bool {
+        if location.statement_index > 0 {
+            return self.summarized.get() == Some(location.block);
+        }
+        let applied = self
+            .block_transfers()
+            .get(location.block)
+            .and_then(Option::as_ref)
+            .map_or(false, |transfer| {
+                transfer.apply(state, &self.points.borrow())
+            });
+        self.summarized.set(applied.then_some(location.block));
+        applied
+    }
+
+    /// The summaries of the blocks of the analyzed function, which has none unless it has more statements
+    /// than `--taint-block-summaries`.
+    fn block_transfers(&self) -> &IndexVec<BasicBlock, Option<BlockTransfer>> {
+        self.transfers.get_or_init(|| {
+            let Some(body) = compiler::mir_body(self.tcx, self.body) else {
+                return IndexVec::new();
+            };
+            let statements = body
+                .basic_blocks
+                .iter()
+                .map(|block| block.statements.len())
+                .sum::<usize>();
+            let max = self
+                .options
+                .block_summaries
+                .unwrap_or(DEFAULT_BLOCK_SUMMARIES);
+            // Facts are recorded, and states explained, after each statement, which a summary goes past.
+            let facts = self.options.records_facts() || self.options.explain.is_some();
+            if statements <= max || facts {
+                return IndexVec::new();
+            }
+            body.basic_blocks
+                .iter()
+                .map(|block| {
+                    let statements = block
+                        .statements
+                        .iter()
+                        .filter_map(|statement| match &statement.kind {
+                            StatementKind::Assign(box (place, rvalue)) => {
+                                Some(self.plain_assignment(body, place, rvalue))
+                            }
+                            StatementKind::Intrinsic(_) => Some(None),
+                            _ => None,
+                        })
+                        .collect::<Option<Vec<_>>>()?;
+                    BlockTransfer::new(&statements)
+                })
+                .collect()
+        })
+    }
+
+    /// `place = rvalue` lowered into an assignment which only moves labels between locals, as the
+    /// transfer function applies it, or `None` if the transfer function does more for it,
+    /// like following pointers or reading a field marked as a source.
+    fn plain_assignment(
+        &self,
+        body: &Body<'tcx>,
+        place: &Place<'tcx>,
+        rvalue: &Rvalue<'tcx>,
+    ) -> Option<ir::Statement> {
+        let tcx = self.tcx;
+        let static_ref = match rvalue {
+            Rvalue::Use(operand) => compiler::static_ref(tcx, operand).is_some(),
+            Rvalue::ThreadLocalRef(_) => true,
+            _ => false,
+        };
+        let special = place.is_indirect()
+            || static_ref
+            || compiler::closure_to_dyn(tcx, body, rvalue).is_some()
+            || compiler::read_result_variant(tcx, body, rvalue).is_some()
+            || compiler::built_result_variant(tcx, rvalue).is_some()
+            || compiler::read_pair_element(tcx, body, rvalue).is_some()
+            || compiler::built_pair(rvalue).is_some()
+            || compiler::pair_element(tcx, body, place).is_some()
+            || compiler::built_struct(tcx, rvalue).is_some()
+            || compiler::struct_field(body, place).is_some()
+            || compiler::read_struct_field(tcx, body, rvalue).is_some()
+            || !compiler::fn_pointer_selectors(tcx, body, rvalue).is_empty()
+            || compiler::read_fields(tcx, body, rvalue)
+                .iter()
+                .any(|field| self.info.source_fields.contains(field))
+            || compiler::written_fields(tcx, body, place, rvalue)
+                .iter()
+                .any(|(field, _)| self.info.storage_fields.contains(field));
+        if special {
+            return None;
+        }
+        let weak = self.options.weak_updates || compiler::writes_part(place);
+        let rvalue = match ir::Rvalue::from(rvalue) {
+            ir::Rvalue::Clean if weak => ir::Rvalue::Unknown,
+            ir::Rvalue::Derived(mut operands) if weak => {
+                operands.push(ir::Operand::Local(place.local));
+                ir::Rvalue::Derived(operands)
+            }
+            rvalue => rvalue,
+        };
+        let mut locals = match &rvalue {
+            ir::Rvalue::Derived(operands) => operands
+                .iter()
+                .filter_map(|operand| match operand {
+                    ir::Operand::Local(local) => Some(*local),
+                    ir::Operand::Const => None,
+                })
+                .collect(),
+            _ => vec![],
+        };
+        locals.push(place.local);
+        // Assigning pointers, or values holding them, changes what locals point to.
+        let plain = locals.iter().all(|&local| {
+            !compiler::is_pointer(tcx, body, local) && !self.untracked.contains(&local)
+        });
+        plain.then_some(ir::Statement::Assign(place.local, rvalue))
+    }
+
+    /// Called once the effect of the terminator of `block` was applied to `state`.
+    fn after_block(&self, state: &TaintState<Local>, block: BasicBlock) {
+        if let Some(invariants) = &self.invariants {
+            let function = self.tcx.def_path_str(self.body);
+            invariants.borrow_mut().exit(&function, block, state);
         }
     }
 }
 
 struct TransferFunction<'tcx, 'inter, 'intra> {
-    tcx: TyCtxt<'tcx>,
-    info: &'inter AttrInfo,
-    contexts: Rc<RefCell<Contexts>>,
+    analysis: &'intra TaintAnalysis<'tcx, 'inter>,
     state: &'intra mut PointsAwareTaintDomain<'intra, Local>,
 }
 
-impl<'inter> AnalysisDomain<'inter> for TaintAnalysis<'_, '_> {
-    type Domain = BitSet<Local>;
+impl taint_domain::LocalIndex for Local {
+    fn new(index: usize) -> Self {
+        <Local as Idx>::new(index)
+    }
+
+    fn index(self) -> usize {
+        Idx::index(self)
+    }
+}
+
+/// The state is joined by the dataflow engine of the compiler, through its own trait.
+impl JoinSemiLattice for TaintState<Local> {
+    fn join(&mut self, other: &Self) -> bool {
+        labels::Join::join(self, other)
+    }
+}
+
+impl<C> DebugWithContext<C> for TaintState<Local> {}
+
+impl<'tcx> AnalysisDomain<'tcx> for TaintAnalysis<'tcx, '_> {
+    type Domain = TaintState<Local>;
     const NAME: &'static str = "TaintAnalysis";
 
     type Direction = Forward;
 
-    fn bottom_value(&self, body: &Body<'inter>) -> Self::Domain {
+    fn bottom_value(&self, body: &Body<'tcx>) -> Self::Domain {
         // bottom = definitely untainted
-        BitSet::new_empty(body.local_decls().len())
+        TaintState::new_empty(body.local_decls().len())
     }
 
-    fn initialize_start_block(&self, body: &Body<'inter>, state: &mut Self::Domain) {
+    fn initialize_start_block(&self, body: &Body<'tcx>, state: &mut Self::Domain) {
         // For the main function, locals all start out untainted.
         // For other functions, however, we must check if they receive tainted parameters.
+        // A reference passed as an argument points to nothing of the body, so its local stands for
+        // its pointee: reads through it, like `(*r).field` or `&(*r)[..]`, carry the labels of the local,
+        // writes through it replace them, and callers read back what it points to from it.
+        // Its copies and reborrows point to it, and carry them too.
         if !self.init.is_empty() {
-            for (_, arg) in self
-                .init
-                .iter()
-                .zip(body.args_iter())
-                .filter(|(&t, _)| t.unwrap_or(false))
-            {
-                state.set_taint(arg, true);
+            for (&labels, arg) in self.init.iter().zip(body.args_iter()) {
+                state.set_taint(arg, labels);
             }
         }
+        self.forget_untracked(state);
     }
 }
 
-impl<'tcx, 'inter, 'intra> Analysis<'intra> for TaintAnalysis<'tcx, 'inter> {
+impl<'tcx> Analysis<'tcx> for TaintAnalysis<'tcx, '_> {
     fn apply_statement_effect(
         &mut self,
         state: &mut Self::Domain,
-        statement: &Statement<'intra>,
+        statement: &Statement<'tcx>,
         location: Location,
     ) {
+        self.before_effect(state, location);
+        if self.apply_block_transfer(state, location) {
+            return;
+        }
         TransferFunction {
-            tcx: self.tcx,
-            info: self.info,
-            contexts: self.contexts.clone(),
+            analysis: self,
             state: &mut PointsAwareTaintDomain {
                 state,
                 map: &mut self.points.borrow_mut(),
             },
         }
         .visit_statement(statement, location);
+        self.forget_untracked(state);
     }
 
     fn apply_terminator_effect(
         &mut self,
         state: &mut Self::Domain,
-        terminator: &Terminator<'intra>,
+        terminator: &Terminator<'tcx>,
         location: Location,
     ) {
+        // Checked once per block: once stopped, terminators do nothing, so no more callees are analyzed
+        // and the fixpoint is reached after as few passes as the statements take.
+        if self.is_stopped() {
+            return;
+        }
+        self.before_effect(state, location);
         TransferFunction {
-            tcx: self.tcx,
-            info: self.info,
-            contexts: self.contexts.clone(),
+            analysis: self,
             state: &mut PointsAwareTaintDomain {
                 state,
                 map: &mut self.points.borrow_mut(),
             },
         }
         .visit_terminator(terminator, location);
+        self.forget_untracked(state);
+        self.after_block(state, location.block);
     }
 
     fn apply_call_return_effect(
         &mut self,
         _state: &mut Self::Domain,
         _block: BasicBlock,
-        _return_place: CallReturnPlaces<'_, 'intra>,
+        _return_place: CallReturnPlaces<'_, 'tcx>,
     ) {
         // do nothing
     }
+
+    /// A sanitizer returning `bool` validates its arguments: on the branch where it returned `true`,
+    /// the variables they were read from no longer carry the labels it removes.
+    /// After a guard like `if !is_valid(&input) { return; }`, only that branch goes on,
+    /// so the rest of the function sees `input` as sanitized.
+    fn apply_switch_int_edge_effects(
+        &mut self,
+        _block: BasicBlock,
+        discr: &Operand<'tcx>,
+        apply_edge_effects: &mut impl SwitchIntEdgeEffects<Self::Domain>,
+    ) {
+        let Some(body) = compiler::mir_body(self.tcx, self.body) else {
+            return;
+        };
+        let Some(condition) = compiler::condition(self.tcx, body, discr) else {
+            return;
+        };
+        if self.info.call_role(self.tcx, condition.callee) != Some(AttrInfoKind::Sanitizer) {
+            return;
+        }
+        let removes = self.info.sanitized_labels(condition.callee);
+        let relabels = self.info.relabels.get(&condition.callee).copied();
+        // What the variables point to was validated along with them, like the contents of a `Vec`.
+        let validated = condition
+            .variables
+            .iter()
+            .flat_map(|&variable| taint_domain::aliases(&self.points.borrow(), variable))
+            .collect::<Vec<_>>();
+        apply_edge_effects.apply(|state, target| {
+            // `false` is 0, and `true` takes the `otherwise` edge.
+            let returned_true = target.value != Some(0);
+            if returned_true != condition.negated {
+                for &local in &validated {
+                    let mut labels = state.get_taint(local);
+                    if let Some((from, to)) = relabels {
+                        labels = labels.relabeled(from, to);
+                    }
+                    state.set_taint(local, labels.without(removes));
+                }
+            }
+        });
+    }
 }
 
 impl std::fmt::Debug for TransferFunction<'_, '_, '_> {
@@ -160,225 +494,2400 @@ impl std::fmt::Debug for TransferFunction<'_, '_, '_> {
     }
 }
 
-impl<'inter> Visitor<'inter> for TransferFunction<'_, '_, '_> {
-    fn visit_statement(&mut self, statement: &Statement<'inter>, _: Location) {
+impl<'tcx> MirVisitor<'tcx> for TransferFunction<'tcx, '_, '_> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, _: Location) {
         let Statement { source_info, kind } = statement;
 
         self.visit_source_info(source_info);
 
-        if let StatementKind::Assign(box (ref place, ref rvalue)) = kind {
-            self.t_visit_assign(place, rvalue);
+        match kind {
+            StatementKind::Assign(box (ref place, ref rvalue)) => {
+                self.t_check_indexed_places(place, rvalue, source_info.span);
+                self.t_visit_assign(place, rvalue, source_info.span)
+            }
+            // Optimized MIR lowers calls to the `copy_nonoverlapping` intrinsic into a statement,
+            // which writes like the call does.
+            StatementKind::Intrinsic(box NonDivergingIntrinsic::CopyNonOverlapping(copy)) => {
+                self.t_visit_copy(&copy.src, &copy.dst);
+            }
+            StatementKind::Intrinsic(box NonDivergingIntrinsic::Assume(_)) => {}
+            // The discriminant written is a constant, and the fields of the variant are assigned
+            // by statements of their own.
+            StatementKind::SetDiscriminant { .. } | StatementKind::Deinit(_) => {}
+            // Markers for the borrow checker, codegen and coverage, which move no data.
+            StatementKind::FakeRead(_)
+            | StatementKind::StorageLive(_)
+            | StatementKind::StorageDead(_)
+            | StatementKind::Retag(..)
+            | StatementKind::PlaceMention(_)
+            | StatementKind::AscribeUserType(..)
+            | StatementKind::Coverage(_)
+            | StatementKind::ConstEvalCounter
+            | StatementKind::Nop => {}
         }
     }
 
-    fn visit_terminator(&mut self, terminator: &Terminator<'inter>, _: Location) {
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
         let Terminator { source_info, kind } = terminator;
 
         self.visit_source_info(source_info);
 
+        // Every kind is listed, so that one added to MIR, like tail calls, must be given a meaning here
+        // before it is silently taken to leave the state as it is. The same goes for statements.
         match kind {
             TerminatorKind::Goto { .. } => {}
             TerminatorKind::SwitchInt { .. } => {}
+            // The returned value is read from the return place once the body is done.
             TerminatorKind::Return => {}
-            TerminatorKind::Call {
-                func: Operand::Constant(ref c),
-                args,
-                destination,
-                fn_span,
-                ..
-            } => {
-                self.t_visit_call(c, args, destination, fn_span);
+            // Unwinding ends in the caller's cleanup blocks, or aborts, and neither returns a value.
+            TerminatorKind::Resume | TerminatorKind::Terminate => {}
+            TerminatorKind::Unreachable => {}
+            TerminatorKind::Call { func, fn_span, .. } => {
+                if let Some(mut call) = Call::from_terminator(kind) {
+                    call.generic_args = compiler::instantiate(
+                        self.analysis.tcx,
+                        call.generic_args,
+                        self.analysis.args,
+                    );
+                    self.t_visit_call(&call);
+                } else {
+                    self.t_check_indirect_call(func, *fn_span);
+                }
+            }
+            // Drops run on the way out of a function, including in the cleanup blocks
+            // run while unwinding from a panic.
+            TerminatorKind::Drop { place, .. } => {
+                self.t_visit_drop(place, source_info.span, location.block)
             }
             TerminatorKind::Assert { .. } => {}
-            _ => {}
+            // The yielded value leaves the generator, and the resume argument comes from the code resuming it,
+            // like the context an async fn is polled with, which carries no labels we know of.
+            TerminatorKind::Yield { .. } | TerminatorKind::GeneratorDrop => {}
+            // Edges which only exist for the borrow checker.
+            TerminatorKind::FalseEdge { .. } | TerminatorKind::FalseUnwind { .. } => {}
+            TerminatorKind::InlineAsm { operands, .. } => self.t_visit_inline_asm(operands),
         }
     }
 }
 
-impl<'long> TransferFunction<'_, '_, '_>
+impl<'tcx> TransferFunction<'tcx, '_, '_>
 where
-    Self: Visitor<'long>,
+    Self: MirVisitor<'tcx>,
 {
     #[instrument]
-    fn t_visit_assign(&mut self, place: &Place, rvalue: &Rvalue) {
-        match rvalue {
-            // If we assign a constant to a place, the place is clean.
-            Rvalue::Use(Operand::Constant(_)) | Rvalue::UnaryOp(_, Operand::Constant(_)) => {
-                self.state.set_taint(place.local, false)
-            }
+    fn t_visit_assign(&mut self, place: &Place<'tcx>, rvalue: &Rvalue<'tcx>, span: Span) {
+        let body = compiler::mir_body(self.analysis.tcx, self.analysis.body)
+            .expect("the analyzed function has a body");
+        let written_fields = compiler::written_fields(self.analysis.tcx, body, place, rvalue);
 
-            // Otherwise we propagate the taint
-            Rvalue::Use(Operand::Copy(f) | Operand::Move(f)) => {
-                self.state.propagate(f.local, place.local);
+        // A closure turned into a trait object, usually to be stored as a callback,
+        // may be called later through it, and a future may be polled through it.
+        if let Some((closure, args)) = compiler::closure_to_dyn(self.analysis.tcx, body, rvalue) {
+            let args = compiler::instantiate(self.analysis.tcx, args, self.analysis.args);
+            self.analysis
+                .shared
+                .callbacks
+                .borrow_mut()
+                .register(self.analysis.tcx, closure, args);
+            self.analysis.side_effect();
+        }
+
+        // The elements of a pair, and of the pairs an iterator made by `enumerate` or `zip` yields,
+        // each only carry the labels put into them.
+        let read_element = compiler::read_pair_element(self.analysis.tcx, body, rvalue);
+        // A pointer copied from another one points to the same object,
+        // so that writes through either end up in it. Nothing is written through a shared reference
+        // read from a pair, which only carries the labels of its element rather than of the whole pair.
+        let shared_element = read_element.is_some() && {
+            let ty = body.local_decls[place.local].ty;
+            ty.is_ref() && !ty.is_mutable_ptr()
+        };
+        let copied_pointer = t_copied_pointer(rvalue).filter(|_| {
+            place.projection.is_empty()
+                && compiler::is_pointer(self.analysis.tcx, body, place.local)
+                && !shared_element
+        });
+        // A field of a struct a local holds only carries the labels put into it, and moving it out
+        // leaves the other fields with theirs, and without its own.
+        let read_field = compiler::read_struct_field(self.analysis.tcx, body, rvalue)
+            .filter(|_| copied_pointer.is_none());
+        let moved_field = match rvalue {
+            Rvalue::Use(Operand::Move(from)) if from.projection.len() == 1 => read_field,
+            _ => None,
+        };
+        // An aggregate built from pointers, like a slice pointer built from its address and length,
+        // points to what they point to.
+        let aggregated_pointers = t_aggregated_pointers(rvalue)
+            .into_iter()
+            .filter(|&local| {
+                place.projection.is_empty() && compiler::is_pointer(self.analysis.tcx, body, local)
+            })
+            .collect::<Vec<_>>();
+        // Reading a field marked as a source, or taking a reference to it, produces its labels.
+        let field_labels = compiler::read_fields(self.analysis.tcx, body, rvalue)
+            .into_iter()
+            .filter(|field| self.analysis.info.source_fields.contains(field))
+            .fold(Labels::EMPTY, |labels, field| {
+                labels.union(self.analysis.info.labels_of_source(field))
+            });
+        // A function pointer picked from a table carries the labels of the index it was picked by,
+        // since they decide which function it calls.
+        let selector_labels = compiler::fn_pointer_selectors(self.analysis.tcx, body, rvalue)
+            .into_iter()
+            .fold(Labels::EMPTY, |labels, local| {
+                labels.union(self.state.get_taint(local))
+            });
+        // A map moved or copied as a whole keeps track of what its keys and its values carry.
+        let moved = match rvalue {
+            Rvalue::Use(Operand::Copy(from) | Operand::Move(from))
+                if from.projection.is_empty() && place.projection.is_empty() =>
+            {
+                Some(from.local)
+            }
+            _ => None,
+        };
+        // A reference to a static, thread-local or not, carries the labels of the static's value.
+        let static_ref = match rvalue {
+            Rvalue::Use(operand) => compiler::static_ref(self.analysis.tcx, operand),
+            Rvalue::ThreadLocalRef(id) => Some(*id),
+            _ => None,
+        };
+        // The value of an `Ok` only carries the labels put into the `Ok` values of the `Result`,
+        // and the same goes for an `Err`.
+        let read_variant = compiler::read_result_variant(self.analysis.tcx, body, rvalue);
+        let built_variant = compiler::built_result_variant(self.analysis.tcx, rvalue);
+        let read_part = read_variant
+            .map(|(result, variant)| self.state.variant_taint(result, variant))
+            .or_else(|| read_element.map(|(pair, element)| self.state.element_taint(pair, element)))
+            .or_else(|| read_field.map(|(object, field, _)| self.state.field_taint(object, field)));
+        let built_pair = compiler::built_pair(rvalue)
+            .map(|(first, second)| (self.t_operand_taint(first), self.t_operand_taint(second)));
+        let built_struct = compiler::built_struct(self.analysis.tcx, rvalue).map(|operands| {
+            operands
+                .iter()
+                .map(|operand| self.t_operand_taint(operand))
+                .collect::<Box<[_]>>()
+        });
+        let rvalue = match (copied_pointer, static_ref, read_part) {
+            (Some(source), _, _) => ir::Rvalue::Ref(source),
+            (None, Some(id), _) => ir::Rvalue::Labels(self.analysis.static_taint(id)),
+            (None, None, Some(labels)) => ir::Rvalue::Labels(labels),
+            (None, None, None) => {
+                let lowered = ir::Rvalue::from(rvalue);
+                if lowered == ir::Rvalue::Unknown {
+                    self.t_unmodeled(rvalue, span);
+                }
+                lowered
             }
+        };
+        // Labels read from somewhere the rvalue itself does not carry them from.
+        let read_labels = field_labels.union(selector_labels);
+        let written = rvalue.taint(self.state).union(read_labels);
+        self.t_record_stored(&written_fields, written, span);
 
-            Rvalue::BinaryOp(_, box b) | Rvalue::CheckedBinaryOp(_, box b) => match b {
-                (Operand::Constant(_), Operand::Constant(_)) => {
-                    self.state.set_taint(place.local, false);
+        // Writing into an element of a pair a local holds puts the labels written into that element alone.
+        let written_element = compiler::pair_element(self.analysis.tcx, body, place)
+            .filter(|_| !place.is_indirect())
+            .map(|(pair, element)| {
+                let first = self.state.element_taint(pair, PairElement::First);
+                let second = self.state.element_taint(pair, PairElement::Second);
+                (pair, element, first, second)
+            });
+        // Writing into a field of a struct a local holds puts the labels written into that field alone,
+        // which they replace if the whole field is written.
+        let written_field = compiler::struct_field(body, place)
+            .map(|(object, field, count)| (object, field, self.state.fields(object, count)));
+
+        // Writing through a pointer writes into what it points to, rather than into the pointer.
+        let (written_locals, whole) =
+            t_written_locals(self.analysis.tcx, body, place, self.state.map);
+
+        // Writing a field or an element only changes part of an object. Raw pointers and boxes point
+        // to memory which may hold many values, like the buffer of a `Vec`, so writing through them
+        // does too, and a pointer which may point to several objects only changes one of them.
+        // Either way, each object keeps the taint it had.
+        let weak = self.analysis.options.weak_updates
+            || compiler::writes_part(place)
+            || (place.is_indirect() && compiler::is_heap_pointer(body, place.local))
+            || !whole;
+        // A local holding a borrow, like a `Cow`, is replaced as a whole rather than written through,
+        // so what it pointed to, which may be what a sanitizer made its new value from, keeps its labels.
+        let replaced = !place.is_indirect()
+            && place.projection.is_empty()
+            && !compiler::is_pointer(self.analysis.tcx, body, place.local);
+        for &written_local in &written_locals {
+            let rvalue = match rvalue.clone() {
+                // With weak updates, the place keeps the taint it had.
+                ir::Rvalue::Clean if weak => ir::Rvalue::Unknown,
+                ir::Rvalue::Derived(mut operands) if weak => {
+                    operands.push(ir::Operand::Local(written_local));
+                    ir::Rvalue::Derived(operands)
+                }
+                rvalue => rvalue,
+            };
+            match rvalue {
+                ir::Rvalue::Clean | ir::Rvalue::Derived(_) | ir::Rvalue::Labels(_) if replaced => {
+                    let labels = rvalue.taint(self.state);
+                    self.state
+                        .set_taint_keeping_referents(written_local, labels);
+                }
+                rvalue => {
+                    ir::Statement::Assign(written_local, rvalue).apply(self.state);
                 }
-                (Operand::Copy(a) | Operand::Move(a), Operand::Copy(b) | Operand::Move(b)) => {
-                    if self.state.get_taint(a.local) || self.state.get_taint(b.local) {
-                        self.state.set_taint(place.local, true);
-                    } else {
-                        self.state.set_taint(place.local, false);
+            }
+        }
+        match (moved, weak) {
+            (Some(from), false) => self.state.move_entries(from, place.local),
+            (None, false) if !place.is_indirect() => self.state.forget_entries(place.local),
+            _ if place.is_indirect() => self.state.mix_entries(place.local),
+            _ => {}
+        }
+        if let Some(variant) = built_variant.filter(|_| !weak) {
+            for &written_local in &written_locals {
+                self.state.set_variant(written_local, variant);
+            }
+        }
+        if let Some((pair, element, first, second)) = written_element {
+            let (first, second) = match element {
+                PairElement::First => (first.union(written), second),
+                PairElement::Second => (first, second.union(written)),
+            };
+            self.state.set_pair(pair, first, second);
+        }
+        if let Some((first, second)) = built_pair.filter(|_| !weak) {
+            for &written_local in &written_locals {
+                self.state.set_pair(written_local, first, second);
+            }
+        }
+        for pointer in aggregated_pointers {
+            ir::Statement::Assign(place.local, ir::Rvalue::Ref(pointer)).apply(self.state);
+        }
+        if !read_labels.is_empty() {
+            for &written_local in &written_locals {
+                let labels = self.state.get_taint(written_local).union(read_labels);
+                ir::Statement::Assign(written_local, ir::Rvalue::Labels(labels)).apply(self.state);
+            }
+        }
+        if let Some((object, field, mut fields)) = written_field {
+            fields[field] = if place.projection.len() == 1 && !self.analysis.options.weak_updates {
+                written
+            } else {
+                fields[field].union(written)
+            };
+            self.state.set_fields(object, fields);
+        }
+        if let Some(fields) = built_struct.filter(|_| !weak) {
+            for &written_local in &written_locals {
+                self.state.set_fields(written_local, fields.clone());
+            }
+        }
+        if let Some((object, field, count)) = moved_field {
+            let mut fields = self.state.fields(object, count);
+            fields[field] = Labels::EMPTY;
+            self.state.set_fields(object, fields);
+        }
+    }
+
+    /// Notes that the assignment of `rvalue` at `span` was not modeled, for `--taint-strict-mir`,
+    /// if it is in the crate analyzed.
+    fn t_unmodeled(&self, rvalue: &Rvalue<'tcx>, span: Span) {
+        if !self.analysis.options.strict_mir || !compiler::is_local(self.analysis.tcx, span) {
+            return;
+        }
+        let construct = match rvalue {
+            Rvalue::Cast(..) => "this cast",
+            Rvalue::Discriminant(_) => "reading the discriminant of an enum",
+            Rvalue::ShallowInitBox(..) => "this allocation of a box",
+            _ => "this expression",
+        };
+        self.analysis
+            .shared
+            .findings
+            .borrow_mut()
+            .unmodeled(span, construct);
+        self.analysis.side_effect();
+    }
+
+    /// Records the tainted data written into fields marked `#[taint::storage]` among `fields`,
+    /// which carries `written` unless an operand is given for it.
+    fn t_record_stored(
+        &self,
+        fields: &[(DefId, Option<&Operand<'tcx>>)],
+        written: Labels,
+        span: Span,
+    ) {
+        let info = self.analysis.info;
+        if info.storage_fields.is_empty() {
+            return;
+        }
+        for &(field, operand) in fields {
+            let labels = operand.map_or(written, |operand| self.t_operand_taint(operand));
+            if !info.storage_fields.contains(&field) || labels.is_empty() {
+                continue;
+            }
+            self.analysis.shared.findings.borrow_mut().stored(
+                self.analysis.body,
+                span,
+                field,
+                labels,
+            );
+            // A summary carried over to the next run would skip the write.
+            self.analysis.side_effect();
+        }
+    }
+
+    /// Records the values a call to a function marked `#[taint::boundary]` takes across it.
+    fn t_check_boundary(&self, call: &Call<'_, 'tcx>) {
+        let tcx = self.analysis.tcx;
+        let info = self.analysis.info;
+        if info.boundaries.is_empty() {
+            return;
+        }
+        let (id, _) = compiler::resolve(tcx, call.callee, call.generic_args);
+        if !info.boundaries.contains(&id) {
+            return;
+        }
+        let Some(body) = compiler::mir_body(tcx, self.analysis.body) else {
+            return;
+        };
+        for arg in call.args {
+            let labels = self.t_operand_taint(arg);
+            self.t_record_crossing(call.span, tcx.def_path_str(id), arg.ty(body, tcx), labels);
+        }
+    }
+
+    /// Reports a call in the `Display` or `Debug` impl of a type marked as a source which hands one of its
+    /// fields to the formatter, or to a builder made from it, like `DebugStruct::field`.
+    fn t_check_formatted_fields(&self, call: &Call<'_, 'tcx>) {
+        let tcx = self.analysis.tcx;
+        let info = self.analysis.info;
+        let Some(&Accessor {
+            ty,
+            kind: AccessorKind::Format(name),
+            ..
+        }) = info.accessor(self.analysis.body)
+        else {
+            return;
+        };
+        let Some(body) = compiler::mir_body(tcx, self.analysis.body) else {
+            return;
+        };
+        // The formatter is the argument after `self`.
+        let formatter = compiler::derived_locals(body, Local::from_u32(2));
+        let (formatting, fields): (Vec<_>, Vec<_>) = call.args.iter().partition(|arg| {
+            arg.place()
+                .map_or(false, |place| formatter.contains(&place.local))
+        });
+        if formatting.is_empty() {
+            return;
+        }
+        let labels = fields
+            .iter()
+            .fold(Labels::EMPTY, |labels, arg| {
+                labels.union(self.t_operand_taint(arg))
+            })
+            .intersection(info.labels_of_source(ty));
+        let Some(label) = labels.iter().next() else {
+            return;
+        };
+        let (callee, _) = compiler::resolve(tcx, call.callee, call.generic_args);
+        self.t_report(
+            call.span,
+            ErrorCode::T0001,
+            format!(
+                "a field of `{}`, which is marked as a source, is formatted with `{}`, which exposes it",
+                tcx.def_path_str(ty),
+                name
+            ),
+            Sink::without_arguments(tcx.def_path_str(callee)),
+            label,
+        );
+    }
+
+    /// A value of type `ty` carrying `labels` crossed `boundary` at `span`, which the writes into
+    /// the storage fields within `ty` are reported for.
+    fn t_record_crossing(&self, span: Span, boundary: String, ty: Ty<'tcx>, labels: Labels) {
+        let info = self.analysis.info;
+        let fields = compiler::fields_within(self.analysis.tcx, ty)
+            .into_iter()
+            .filter(|field| info.storage_fields.contains(field))
+            .collect::<Vec<_>>();
+        if fields.is_empty() || labels.is_empty() {
+            return;
+        }
+        self.analysis
+            .shared
+            .findings
+            .borrow_mut()
+            .crossed(span, boundary, fields, labels);
+        self.analysis.side_effect();
+    }
+
+    /// The assembly may compute any of its outputs from any of its inputs, so each output carries
+    /// the labels of all of them, besides those it had if only part of it is written.
+    fn t_visit_inline_asm(&mut self, operands: &[InlineAsmOperand<'tcx>]) {
+        let inputs = operands.iter().filter_map(|operand| match operand {
+            InlineAsmOperand::In { value, .. } => Some(value),
+            InlineAsmOperand::InOut { in_value, .. } => Some(in_value),
+            _ => None,
+        });
+        let labels = inputs.fold(Labels::EMPTY, |labels, input| {
+            labels.union(self.t_operand_taint(input))
+        });
+        let outputs = operands.iter().filter_map(|operand| match operand {
+            InlineAsmOperand::Out { place, .. } => *place,
+            InlineAsmOperand::InOut { out_place, .. } => *out_place,
+            _ => None,
+        });
+        for place in outputs {
+            let labels = if compiler::writes_part(&place) {
+                labels.union(self.state.get_taint(place.local))
+            } else {
+                labels
+            };
+            self.state.set_taint(place.local, labels);
+        }
+    }
+
+    /// A call through a function pointer whose value is tainted lets whoever controls the data choose
+    /// the code which runs, like an index into a table of handlers or a pointer read from a message.
+    /// Methods of trait objects are left out, as the object carries the labels of its data too.
+    fn t_check_indirect_call(&self, func: &Operand<'tcx>, span: Span) {
+        let received = self.t_operand_taint(func);
+        let tcx = self.analysis.tcx;
+        let sink = || Sink::without_arguments("indirect call".to_owned());
+        if received.contains(TAINT) {
+            self.t_report(
+                span,
+                ErrorCode::T0011,
+                "call through a tainted function pointer".to_owned(),
+                sink(),
+                TAINT,
+            );
+        }
+        for label in received.without(TAINT.into()).iter() {
+            self.t_report(
+                span,
+                ErrorCode::T0011,
+                format!(
+                    "call through a function pointer tainted with label `{}`",
+                    self.analysis.info.label_name(tcx, label)
+                ),
+                sink(),
+                label,
+            );
+        }
+    }
+
+    /// What `dst` points to gets the labels of what `src` points to, besides those it had.
+    fn t_visit_copy(&mut self, src: &Operand, dst: &Operand) {
+        if let Operand::Copy(p) | Operand::Move(p) = dst {
+            let labels = self
+                .state
+                .get_taint(p.local)
+                .union(self.t_operand_taint(src));
+            self.state.set_taint(p.local, labels);
+            self.state.mix_entries(p.local);
+        }
+    }
+
+    #[instrument]
+    fn t_visit_call(&mut self, call: &Call<'_, 'tcx>) {
+        self.t_check_sink_packs(call);
+        self.t_check_boundary(call);
+        self.t_check_formatted_fields(call);
+        self.t_record_required_taint_call(call);
+        self.t_record_tracked_clone(call);
+
+        let Some(effect) = self.t_call_effect(call) else {
+            return;
+        };
+
+        // The arguments of a sink which carry labels it checks, for fuzz targets.
+        let tainted_args = match &effect {
+            CallEffect::Sink { checks } => call
+                .args
+                .iter()
+                .enumerate()
+                .filter(|(_, arg)| !self.t_operand_taint(arg).intersection(*checks).is_empty())
+                .map(|(ix, _)| ix)
+                .collect(),
+            _ => vec![],
+        };
+        if let CallEffect::Sanitizer { .. } = effect {
+            self.t_check_sanitizer_order(call);
+            self.t_record_sanitizer_call(call);
+        }
+        if let CallEffect::Source(labels) = effect {
+            self.t_record_source_call(call, labels);
+        }
+        // A summarized callee may change what its mutable arguments point to, like a map,
+        // but advancing an iterator over pairs leaves the pairs it has left as they were.
+        let summarized = matches!(effect, CallEffect::Summary { .. });
+        let pair = models::pair_adapter(self.analysis.tcx, call.callee, call.generic_args)
+            .and_then(|adapter| self.t_pair_elements(adapter, call));
+        let statement = ir::Statement::Call {
+            effect,
+            args: call.args.iter().map(Into::into).collect(),
+            destination: call.destination.local,
+        };
+
+        let received = statement.apply(self.state);
+        self.state.forget_entries(call.destination.local);
+        if let Some((first, second)) = pair.filter(|_| call.destination.projection.is_empty()) {
+            // Labels the call added, like those a closure of the iterator returned, may be in either element.
+            let added = self
+                .state
+                .get_taint(call.destination.local)
+                .without(first.union(second));
+            self.state.set_pair(
+                call.destination.local,
+                first.union(added),
+                second.union(added),
+            );
+        }
+        if summarized && pair.is_none() {
+            let body = compiler::mir_body(self.analysis.tcx, self.analysis.body)
+                .expect("the analyzed function has a body");
+            for arg in call.args {
+                if let Operand::Copy(p) | Operand::Move(p) = arg {
+                    if compiler::is_mutable_pointer(body, p.local) {
+                        self.state.mix_entries(p.local);
                     }
                 }
-                (Operand::Copy(p) | Operand::Move(p), Operand::Constant(_))
-                | (Operand::Constant(_), Operand::Copy(p) | Operand::Move(p)) => {
-                    self.state.propagate(p.local, place.local);
+            }
+        }
+        let tcx = self.analysis.tcx;
+        if let Some((name, span)) = self.t_sink_macro(call) {
+            let debugged = match models::find_model(tcx, call.callee, call.generic_args) {
+                Some(Model::FormatArgument { debugged: Some(ty) }) => self.t_debugged_fields(ty),
+                _ => Labels::EMPTY,
+            };
+            let received = received.union(debugged.intersection(TAINT.into()));
+            if !received.is_empty() {
+                self.t_report(
+                    span,
+                    ErrorCode::T0001,
+                    format!("macro `{}!` received tainted input", name),
+                    Sink::without_arguments(format!("{}!", name)),
+                    TAINT,
+                );
+            }
+        } else if !received.is_empty() {
+            let (id, _) = compiler::resolve(tcx, call.callee, call.generic_args);
+            let callee = t_callee_name(tcx, id, call);
+            let sink = Sink {
+                name: tcx.def_path_str(id),
+                variables: self.t_variables(call.args, &tainted_args),
+                arguments: tainted_args,
+                model: None,
+            };
+            self.t_report_sink(call.span, id, callee, received, sink);
+        }
+    }
+
+    /// Reports the sink `id`, described as `callee`, receiving the labels `received`
+    /// through the tainted arguments of `sink`.
+    /// Every label is reported, and they are merged into a single error for the call.
+    /// A gated sink only reports calls which are not acknowledged, whatever labels they received.
+    fn t_report_sink(&self, span: Span, id: DefId, callee: String, received: Labels, sink: Sink) {
+        let tcx = self.analysis.tcx;
+        let info = self.analysis.info;
+        let sink = || sink.clone();
+        if info.gated.contains(&id) {
+            if info.acknowledgment(span).is_some() {
+                return;
+            }
+            for label in received.iter() {
+                self.t_report(
+                    span,
+                    ErrorCode::T0007,
+                    format!(
+                        "{} is gated, and received tainted input at a call which does not acknowledge it",
+                        callee
+                    ),
+                    sink(),
+                    label,
+                );
+            }
+            return;
+        }
+        let unsanitized = received.intersection(info.pending_labels());
+        let labeled = received.without(unsanitized).without(TAINT.into());
+        if received.contains(TAINT) {
+            self.t_report(
+                span,
+                ErrorCode::T0001,
+                format!("{} received tainted input", callee),
+                sink(),
+                TAINT,
+            );
+        }
+        for label in labeled.iter() {
+            self.t_report(
+                span,
+                ErrorCode::T0001,
+                format!(
+                    "{} received tainted input labeled `{}`",
+                    callee,
+                    info.label_name(tcx, label)
+                ),
+                sink(),
+                label,
+            );
+        }
+        if received.contains(TAINT) || !labeled.is_empty() {
+            return;
+        }
+        if let Some(label) = unsanitized.iter().next() {
+            let sanitizer = self
+                .analysis
+                .info
+                .skipped_sanitizer(id, received)
+                .map_or_else(String::new, |id| tcx.def_path_str(id));
+            self.t_report(
+                span,
+                ErrorCode::T0003,
+                format!(
+                    "{} received input which was not sanitized by `{}`",
+                    callee, sanitizer
+                ),
+                sink(),
+                label,
+            );
+        }
+    }
+
+    /// Records the labels a call to a sanitizer in the crate received, for `--taint-lint-clean-sanitizers`
+    /// and `--taint-coverage-report`.
+    fn t_record_sanitizer_call(&self, call: &Call<'_, 'tcx>) {
+        let tcx = self.analysis.tcx;
+        let options = self.analysis.options;
+        if !(options.lint_clean_sanitizers || options.coverage_report)
+            || !compiler::is_local(tcx, call.span)
+        {
+            return;
+        }
+        let (id, _) = compiler::resolve(tcx, call.callee, call.generic_args);
+        let received = call.args.iter().fold(Labels::EMPTY, |labels, arg| {
+            labels.union(self.t_operand_taint(arg))
+        });
+        self.analysis.shared.findings.borrow_mut().sanitizer_called(
+            self.analysis.body,
+            call.span,
+            id,
+            received,
+        );
+        // A summary carried over to the next run would skip the call.
+        self.analysis.side_effect();
+    }
+
+    /// Records the clones in the crate of values carrying labels the config tracks the clones of.
+    /// Those made by the code of a macro, like the clones of the fields in a derived `Clone` impl,
+    /// are left out, since the clone of the value holding them is recorded.
+    fn t_record_tracked_clone(&self, call: &Call<'_, 'tcx>) {
+        let tcx = self.analysis.tcx;
+        let tracked = self.analysis.info.tracked_clones;
+        if tracked.is_empty()
+            || call.span.from_expansion()
+            || !compiler::is_local(tcx, call.span)
+            || tcx.trait_of_item(call.callee) != tcx.lang_items().clone_trait()
+            || tcx.item_name(call.callee).as_str() != "clone"
+        {
+            return;
+        }
+        let Some(cloned) = call.args.first() else {
+            return;
+        };
+        let labels = self.t_operand_taint(cloned).intersection(tracked);
+        if labels.is_empty() {
+            return;
+        }
+        self.analysis
+            .shared
+            .findings
+            .borrow_mut()
+            .cloned(self.analysis.body, call.span, labels);
+        self.analysis.side_effect();
+    }
+
+    /// Records the labels a call to a source in the crate put on its data, for `--taint-coverage-report`
+    /// and the families of findings. Models producing data, like reading a map, are not sources.
+    fn t_record_source_call(&self, call: &Call<'_, 'tcx>, labels: Labels) {
+        let tcx = self.analysis.tcx;
+        if !compiler::is_local(tcx, call.span) {
+            return;
+        }
+        let (id, _) = compiler::resolve(tcx, call.callee, call.generic_args);
+        if self.analysis.info.call_role(tcx, id) != Some(AttrInfoKind::Source) {
+            return;
+        }
+        self.analysis.shared.findings.borrow_mut().source_called(
+            self.analysis.body,
+            call.span,
+            id,
+            labels,
+        );
+        self.analysis.side_effect();
+    }
+
+    /// Records the labels a call to a function requiring taint received, which are only known to
+    /// never carry what it requires once every context of the caller has been analyzed.
+    fn t_record_required_taint_call(&self, call: &Call<'_, 'tcx>) {
+        let tcx = self.analysis.tcx;
+        if self.analysis.info.required_taint.is_empty() || !compiler::is_local(tcx, call.span) {
+            return;
+        }
+        let (id, _) = compiler::resolve(tcx, call.callee, call.generic_args);
+        if !self.analysis.info.required_taint.contains_key(&id) {
+            return;
+        }
+        let received = call.args.iter().fold(Labels::EMPTY, |labels, arg| {
+            labels.union(self.t_operand_taint(arg))
+        });
+        self.analysis
+            .shared
+            .findings
+            .borrow_mut()
+            .required_taint_called(self.analysis.body, call.span, id, received);
+        // A summary carried over to the next run would skip the call.
+        self.analysis.side_effect();
+    }
+
+    /// Reports a call to a sanitizer with input which did not pass through the sanitizers
+    /// it must come after, like `#[taint::sanitizer(after = "decode")]`.
+    fn t_check_sanitizer_order(&self, call: &Call<'_, 'tcx>) {
+        let tcx = self.analysis.tcx;
+        let info = self.analysis.info;
+        let (id, _) = compiler::resolve(tcx, call.callee, call.generic_args);
+        let before = info.labels_before(id);
+        if before.is_empty() {
+            return;
+        }
+        let tainted = call
+            .args
+            .iter()
+            .map(|arg| self.t_operand_taint(arg).intersection(before))
+            .collect::<Vec<_>>();
+        let received = tainted
+            .iter()
+            .fold(Labels::EMPTY, |received, &labels| received.union(labels));
+        let arguments = tainted
+            .iter()
+            .enumerate()
+            .filter(|(_, labels)| !labels.is_empty())
+            .map(|(ix, _)| ix)
+            .collect::<Vec<_>>();
+        let (Some(label), Some(earlier)) =
+            (received.iter().next(), info.skipped_sanitizer(id, received))
+        else {
+            return;
+        };
+        self.t_report(
+            call.span,
+            ErrorCode::T0005,
+            format!(
+                "{} received input which was not sanitized by `{}` first",
+                t_callee_name(tcx, id, call),
+                tcx.def_path_str(earlier)
+            ),
+            Sink {
+                name: tcx.def_path_str(id),
+                variables: self.t_variables(call.args, &arguments),
+                arguments,
+                model: None,
+            },
+            label,
+        );
+    }
+
+    /// The variables the arguments at the positions `arguments` of a call were read from.
+    fn t_variables(&self, args: &[Operand<'tcx>], arguments: &[usize]) -> Vec<(String, Span)> {
+        let Some(body) = compiler::mir_body(self.analysis.tcx, self.analysis.body) else {
+            return vec![];
+        };
+        arguments
+            .iter()
+            .filter_map(|&ix| match args.get(ix)? {
+                Operand::Copy(place) | Operand::Move(place) => compiler::variable_of(body, place),
+                Operand::Constant(_) => None,
+            })
+            .collect()
+    }
+
+    /// Reports a sink receiving `label` in the analyzed function.
+    fn t_report(&self, span: Span, code: ErrorCode, message: String, sink: Sink, label: Label) {
+        self.analysis
+            .report(self.analysis.body, span, code, message, sink, label);
+    }
+
+    /// Calls passing taint to an argument a sink pack checks, like the size of an allocation,
+    /// are reported when the pack is enabled. The call itself still has its usual effect on taint.
+    fn t_check_sink_packs(&self, call: &Call<'_, 'tcx>) {
+        let tcx = self.analysis.tcx;
+        // Allocations made by `vec!` are reported at the invocation of the macro,
+        // and the calls made inside dependencies are not ours to report.
+        let span = call.span.source_callsite();
+        if !compiler::is_local(tcx, span) {
+            return;
+        }
+
+        for &pack in &self.analysis.options.sink_packs {
+            let Some(checked) = models::sink_pack_arguments(tcx, pack, call.callee) else {
+                continue;
+            };
+            if pack == SinkPack::Unsafe {
+                self.analysis.called(call.callee);
+                if self.analysis.info.trusts_unsafe(tcx, call.callee) {
+                    continue;
+                }
+            }
+            let tainted = checked
+                .into_iter()
+                .filter(|&ix| {
+                    call.args
+                        .get(ix)
+                        .map_or(false, |arg| self.t_operand_taint(arg).contains(TAINT))
+                })
+                .collect::<Vec<_>>();
+            if !tainted.is_empty() {
+                let what = match pack {
+                    SinkPack::Allocation => "allocates memory of tainted size",
+                    SinkPack::Pointer => "received a tainted pointer offset or length",
+                    SinkPack::Process => {
+                        "passes tainted data to the environment or a child process"
+                    }
+                    SinkPack::Resource => {
+                        "consumes an amount of resources decided by tainted input"
+                    }
+                    SinkPack::Unsafe => "is unsafe and received tainted input",
+                    SinkPack::Index => "received a tainted index",
+                    SinkPack::Sql => "received an SQL query built from tainted input",
+                };
+                let code = match pack {
+                    SinkPack::Unsafe => ErrorCode::T0006,
+                    _ => ErrorCode::T0001,
+                };
+                self.t_report(
+                    span,
+                    code,
+                    format!("function `{}` {}", call.name, what),
+                    Sink {
+                        name: tcx.def_path_str(call.callee),
+                        variables: self.t_variables(call.args, &tainted),
+                        arguments: tainted,
+                        model: None,
+                    },
+                    TAINT,
+                );
+            }
+        }
+    }
+
+    /// Slices and arrays indexed at a tainted position, like `data[i]`, are reported when the index
+    /// sink pack is enabled. The index is a projection of the place rather than a call to `Index::index`,
+    /// which [`Self::t_check_sink_packs`] checks for other collections.
+    fn t_check_indexed_places(&self, place: &Place<'tcx>, rvalue: &Rvalue<'tcx>, span: Span) {
+        let tcx = self.analysis.tcx;
+        if !self.analysis.options.sink_packs.contains(&SinkPack::Index)
+            || !compiler::is_local(tcx, span)
+        {
+            return;
+        }
+        let Some(body) = compiler::mir_body(tcx, self.analysis.body) else {
+            return;
+        };
+        for (indexed, index) in compiler::indexed_places(place, rvalue) {
+            if !self.state.get_taint(index).contains(TAINT) {
+                continue;
+            }
+            let message = match compiler::local_name(body, indexed) {
+                Some(name) => format!("`{}` is indexed at a tainted position", name),
+                None => "indexing at a tainted position".to_owned(),
+            };
+            self.t_report(
+                span,
+                ErrorCode::T0001,
+                message,
+                Sink::without_arguments("index".to_owned()),
+                TAINT,
+            );
+        }
+    }
+
+    /// Decide what a call does, from its annotations, a model, or by analyzing the callee.
+    fn t_call_effect(&mut self, call: &Call<'_, 'tcx>) -> Option<CallEffect> {
+        // A barrier is not a sanitizer, so sinks cannot require it, and audits list its calls apart.
+        let black_box = self.analysis.options.black_box_barrier;
+        if models::is_barrier(self.analysis.tcx, call.callee, black_box) {
+            return Some(CallEffect::Source(Labels::EMPTY));
+        }
+        if let Some(model) = models::find_model(self.analysis.tcx, call.callee, call.generic_args) {
+            return Some(self.t_model_effect(model, call));
+        }
+
+        match compiler::shim(self.analysis.tcx, call.callee, call.generic_args) {
+            // The clone carries the labels of what was cloned.
+            Some(Shim::Clone) => return Some(CallEffect::Propagate),
+            Some(Shim::DropGlue(ty)) => {
+                let labels = call
+                    .args
+                    .first()
+                    .map_or(Labels::EMPTY, |arg| self.t_operand_taint(arg));
+                let destructors =
+                    ty.map_or(vec![], |ty| compiler::destructors_in(self.analysis.tcx, ty));
+                for (id, args) in destructors {
+                    self.t_run_destructor(id, args, labels, call.span, vec![]);
                 }
+                return None;
+            }
+            Some(Shim::FnPtr) => return self.t_unknown_call(call.callee, call.span),
+            None => {}
+        }
+
+        let (id, args) = compiler::resolve(self.analysis.tcx, call.callee, call.generic_args);
+        // Trusted functions are not analyzed as callees, like barriers, and may be called through their trait.
+        if self.analysis.info.trusted.contains(&id) {
+            return Some(CallEffect::Source(Labels::EMPTY));
+        }
+        self.analysis.called(id);
+
+        if let Some(tupled) = compiler::dyn_closure_call(self.analysis.tcx, id, args) {
+            if let Some(effect) = self.t_dyn_call_effect(call, tupled) {
+                return Some(effect);
+            }
+        }
+        if let Some(output) = compiler::dyn_future_poll(self.analysis.tcx, id, args) {
+            if let Some(effect) = self.t_dyn_poll_effect(call, output) {
+                return Some(effect);
+            }
+        }
+
+        if let Some(removes) = self.t_sanitizer_mode(call, id) {
+            return Some(CallEffect::Sanitizer {
+                removes,
+                relabels: None,
+            });
+        }
+
+        let info = self.analysis.info;
+        match info.call_role(self.analysis.tcx, id) {
+            Some(AttrInfoKind::Source) => Some(CallEffect::Source(info.labels_of_source(id))),
+            Some(AttrInfoKind::Sanitizer) => Some(CallEffect::Sanitizer {
+                removes: info.sanitized_labels(id),
+                relabels: info.relabels.get(&id).copied(),
+            }),
+            Some(AttrInfoKind::Sink) => Some(CallEffect::Sink {
+                checks: info.sink_labels(id),
+            }),
+            None => match self.t_hook_role(call, id) {
+                Some(Role::Source) => Some(CallEffect::Source(info.labels_of_source(id))),
+                Some(Role::Sanitizer) => Some(CallEffect::Sanitizer {
+                    removes: info.sanitized_labels(id),
+                    relabels: None,
+                }),
+                Some(Role::Sink) => Some(CallEffect::Sink {
+                    checks: info.sink_labels(id),
+                }),
+                Some(Role::Propagate) => Some(CallEffect::Propagate),
+                None => match self.t_hashing_effect(call) {
+                    Some(effect) => Some(effect),
+                    None => self.t_fn_call_analysis(call, id, args),
+                },
             },
-            Rvalue::UnaryOp(_, Operand::Move(p) | Operand::Copy(p)) => {
-                self.state.propagate(p.local, place.local);
+        }
+    }
+
+    /// A call returning the digest of its arguments, like `Hasher::finish` or `Digest::digest`, returns
+    /// their labels but those the config clears through hashing, if it clears any.
+    fn t_hashing_effect(&self, call: &Call<'_, 'tcx>) -> Option<CallEffect> {
+        let info = self.analysis.info;
+        let removes = info.cleared_by_hashing;
+        let is_digest = !removes.is_empty()
+            && models::is_digest(self.analysis.tcx, call.callee, &info.hash_functions);
+        is_digest.then_some(CallEffect::Sanitizer {
+            removes,
+            relabels: None,
+        })
+    }
+
+    /// The role `--taint-policy-hook` gives to a call to `id`, which has none of its own.
+    /// A hook which fails is reported, and not asked again.
+    fn t_hook_role(&self, call: &Call<'_, 'tcx>, id: DefId) -> Option<Role> {
+        let shared = &self.analysis.shared;
+        let mut hook = shared.hook.borrow_mut();
+        let script = hook.as_ref()?;
+        let tcx = self.analysis.tcx;
+        let args = match compiler::mir_body(tcx, self.analysis.body) {
+            Some(body) => call
+                .args
+                .iter()
+                .map(|arg| compiler::constant_value(tcx, body, arg))
+                .collect(),
+            None => vec![None; call.args.len()],
+        };
+        let key = (id, args);
+        if let Some(&role) = shared.hooked.borrow().get(&key) {
+            return role;
+        }
+        match script.classify(&tcx.def_path_str(id), &key.1) {
+            Ok(role) => {
+                shared.hooked.borrow_mut().insert(key, role);
+                role
             }
-            Rvalue::Ref(_region_kind, _borrow_kind, p) => {
-                self.state.add_ref(place, p);
+            Err(error) => {
+                Diagnostics::new(tcx).failure(&error);
+                *hook = None;
+                None
             }
+        }
+    }
+
+    /// The labels a call to `id` removes, if the config lists the modes of `id` as a sanitizer
+    /// and the call passes one of them as a constant.
+    fn t_sanitizer_mode(&self, call: &Call<'_, 'tcx>, id: DefId) -> Option<Labels> {
+        let tcx = self.analysis.tcx;
+        let info = self.analysis.info;
+        let sanitizer = info.sanitizer_modes(tcx, id)?;
+        let body = compiler::mir_body(tcx, self.analysis.body)?;
+        let value = compiler::constant_value(tcx, body, call.args.get(sanitizer.arg)?)?;
+        info.removed_in_mode(sanitizer, &value)
+    }
+
+    /// A call through a trait object, like `handler(request)` on a `Box<dyn Fn(Request)>`,
+    /// runs one of the callbacks registered so far which take arguments of the tuple type `tupled`,
+    /// so it has the effects of all of them. Without any, it is a call to a function without a body.
+    fn t_dyn_call_effect(&mut self, call: &Call<'_, 'tcx>, tupled: Ty<'tcx>) -> Option<CallEffect> {
+        self.analysis.side_effect();
+        let candidates = self
+            .analysis
+            .shared
+            .callbacks
+            .borrow()
+            .candidates(self.analysis.tcx, tupled);
+        candidates
+            .into_iter()
+            .filter_map(|(closure, args)| self.t_fn_call_analysis(call, closure, args))
+            .reduce(t_join_effects)
+    }
 
-            Rvalue::Repeat(_, _) => {}
-            Rvalue::ThreadLocalRef(_) => {}
-            Rvalue::AddressOf(_, _) => {}
-            Rvalue::Len(_) => {}
-            Rvalue::Cast(_, _, _) => {}
-            Rvalue::NullaryOp(_, _) => {}
-            Rvalue::Discriminant(_) => {}
-            Rvalue::Aggregate(_, _) => {}
-            Rvalue::ShallowInitBox(_, _) | Rvalue::CopyForDeref(_) => {}
+    /// Polling a trait object, like a `Pin<Box<dyn Future<Output = String>>>`, polls one of the futures
+    /// turned into trait objects so far which have the output type `output`, so it has the effects of all of them.
+    fn t_dyn_poll_effect(&mut self, call: &Call<'_, 'tcx>, output: Ty<'tcx>) -> Option<CallEffect> {
+        self.analysis.side_effect();
+        let candidates = self
+            .analysis
+            .shared
+            .callbacks
+            .borrow()
+            .futures(self.analysis.tcx, output);
+        candidates
+            .into_iter()
+            .filter_map(|(generator, args)| self.t_fn_call_analysis(call, generator, args))
+            .reduce(t_join_effects)
+    }
+
+    /// Dropping a value runs the `drop` methods of its type and of the values it owns which implement `Drop`.
+    fn t_visit_drop(&mut self, place: &Place<'tcx>, span: Span, block: BasicBlock) {
+        let tcx = self.analysis.tcx;
+        let Some(body) = compiler::mir_body(tcx, self.analysis.body) else {
+            return;
+        };
+        let labels = self.state.get_taint(place.local);
+        // A secret only dropped while unwinding from a panic would have been moved out otherwise.
+        if !body.basic_blocks[block].is_cleanup {
+            self.t_check_dropped_secret(body, place, labels, span);
+        }
+        for (id, args) in compiler::local_destructors(tcx, body, place, self.analysis.args) {
+            let variables = compiler::variable_of(body, place).into_iter().collect();
+            self.t_run_destructor(id, args, labels, span, variables);
         }
     }
 
-    #[instrument]
-    fn t_visit_call(
+    /// With `--taint-lint-unzeroized-secrets`, reports a local dropped while it holds a secret created in the function,
+    /// which was neither zeroized nor moved out. The secrets the arguments brought in are left to the caller,
+    /// and so are values which zeroize themselves when dropped, like a `Zeroizing`.
+    fn t_check_dropped_secret(
+        &self,
+        body: &Body<'tcx>,
+        place: &Place<'tcx>,
+        labels: Labels,
+        span: Span,
+    ) {
+        let tcx = self.analysis.tcx;
+        let info = self.analysis.info;
+        if !self.analysis.options.lint_unzeroized_secrets
+            || !place.projection.is_empty()
+            || !compiler::is_local(tcx, span)
+        {
+            return;
+        }
+        let brought = self
+            .analysis
+            .init
+            .iter()
+            .fold(Labels::EMPTY, |labels, &arg| labels.union(arg));
+        let created = labels
+            .intersection(info.secret_labels(tcx))
+            .without(brought);
+        if created.is_empty() {
+            return;
+        }
+        let ty = compiler::place_ty(tcx, body, place, self.analysis.args);
+        if models::zeroizes_on_drop(tcx, ty) {
+            return;
+        }
+        let variable = compiler::variable_of(body, place);
+        let dropped = match &variable {
+            Some((name, _)) => format!("`{}`", name),
+            None => "a value".to_owned(),
+        };
+        for label in created.iter() {
+            self.t_report(
+                span,
+                ErrorCode::T0014,
+                format!(
+                    "{} is dropped here holding a secret labeled `{}`, which was neither zeroized nor moved out",
+                    dropped,
+                    info.label_name(tcx, label)
+                ),
+                Sink {
+                    name: "drop".to_owned(),
+                    arguments: vec![],
+                    variables: variable.clone().into_iter().collect(),
+                    model: None,
+                },
+                label,
+            );
+        }
+    }
+
+    /// Runs the `drop` method `id` on a value carrying `labels`, read from `variables`.
+    /// It is checked like a sink if it is one, and analyzed like any other callee otherwise.
+    fn t_run_destructor(
         &mut self,
-        func: &Constant,
-        args: &[Operand],
-        destination: &Place,
-        span: &Span,
+        id: DefId,
+        args: GenericArgsRef<'tcx>,
+        labels: Labels,
+        span: Span,
+        variables: Vec<(String, Span)>,
     ) {
-        let name = func.to_string();
-        let id = match func.literal.ty().kind() {
-            TyKind::FnDef(id, _args) => Some(id),
-            _ => None,
+        let tcx = self.analysis.tcx;
+        let info = self.analysis.info;
+        match info.call_role(tcx, id) {
+            Some(AttrInfoKind::Sink) => {
+                let received = labels.intersection(info.sink_labels(id));
+                let callee = format!("function `{}`", tcx.def_path_str(id));
+                // The value dropped is the only argument of `drop`.
+                let sink = Sink {
+                    name: tcx.def_path_str(id),
+                    arguments: vec![0],
+                    variables,
+                    model: None,
+                };
+                self.t_report_sink(span, id, callee, received, sink);
+            }
+            _ => {
+                // Sinks in the destructor are reported while analyzing it.
+                let _ = self.analysis.summarize(id, args, vec![labels], Some(span));
+            }
         }
-        .unwrap();
+    }
+
+    fn t_model_effect(&mut self, model: Model<'tcx>, call: &Call<'_, 'tcx>) -> CallEffect {
+        match model {
+            Model::Spawn { task, args } => {
+                // The task is analyzed as if it was called right here, with its captures as the argument.
+                let captured = call
+                    .args
+                    .last()
+                    .map_or(Labels::EMPTY, |arg| self.t_operand_taint(arg));
+                let summary = self
+                    .analysis
+                    .summarize(task, args, vec![captured], Some(call.span));
 
-        match self.info.get_kind(id) {
-            Some(AttrInfoKind::Source) => self.t_visit_source_destination(destination),
-            Some(AttrInfoKind::Sanitizer) => self.t_visit_sanitizer_destination(destination),
-            Some(AttrInfoKind::Sink) => self.t_visit_sink(name, args, span),
-            None => self.t_fn_call_analysis(args, id, destination),
+                CallEffect::Summary {
+                    returns: summary.map_or(Labels::EMPTY, |s| s.returns),
+                    args: vec![],
+                    points_into: vec![],
+                }
+            }
+            Model::JoinHandlePoll => CallEffect::Propagate,
+            Model::FormatArgument { .. } if self.t_sink_macro(call).is_some() => CallEffect::Sink {
+                checks: TAINT.into(),
+            },
+            Model::FormatArgument { debugged: None } => CallEffect::Propagate,
+            Model::FormatArgument {
+                debugged: Some(ty), ..
+            } => {
+                let value = call
+                    .args
+                    .first()
+                    .map_or(Labels::EMPTY, |arg| self.t_operand_taint(arg));
+                CallEffect::Source(value.union(self.t_debugged_fields(ty)))
+            }
+            Model::Map(method) => self.t_map_effect(method, call),
+            Model::Serialize { value, writer, ty } => {
+                self.t_serialize_effect(call, value, writer, ty)
+            }
+            Model::CellSet { value } => self.t_cell_set_effect(call, value),
+            Model::CellInit { init } => self.t_cell_init_effect(call, init),
+            Model::LazyNew { init } => CallEffect::Source(self.t_init_returns(call, 0, init)),
+            Model::CellRead => CallEffect::Propagate,
+            Model::Conversion => CallEffect::Propagate,
+            Model::NewtypeToString => CallEffect::Propagate,
+            Model::Zeroize => CallEffect::Summary {
+                returns: Labels::EMPTY,
+                args: vec![Labels::EMPTY],
+                points_into: vec![],
+            },
+            Model::ErasedError(ErrorMethod::Wrap | ErrorMethod::View) => CallEffect::Propagate,
+            Model::ErasedError(ErrorMethod::Format) => {
+                let args = call
+                    .args
+                    .iter()
+                    .map(|arg| self.t_operand_taint(arg))
+                    .collect::<Vec<_>>();
+                let error = args.first().copied().unwrap_or(Labels::EMPTY);
+                let args = args
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, &labels)| if ix == 1 { labels.union(error) } else { labels })
+                    .collect();
+                CallEffect::Summary {
+                    returns: Labels::EMPTY,
+                    args,
+                    points_into: vec![],
+                }
+            }
+            Model::Sensitive(method) => self.t_sensitive_effect(method, call),
+            // A source of no labels, which cleans the result.
+            Model::Intrinsic(Intrinsic::Clean) => CallEffect::Source(Labels::EMPTY),
+            Model::Intrinsic(Intrinsic::Propagate) => CallEffect::Propagate,
+            Model::Intrinsic(Intrinsic::Write { dst, from }) => {
+                let mut args = call
+                    .args
+                    .iter()
+                    .map(|arg| self.t_operand_taint(arg))
+                    .collect::<Vec<_>>();
+                if let (Some(&written), Some(_)) = (args.get(from), args.get(dst)) {
+                    args[dst] = args[dst].union(written);
+                }
+                CallEffect::Summary {
+                    returns: Labels::EMPTY,
+                    args,
+                    points_into: vec![],
+                }
+            }
         }
     }
 
-    fn t_fn_call_analysis(
+    /// A call to a map method returns the labels of the keys or of the values of the map,
+    /// rather than those of both. Results are not references into the map,
+    /// since the methods modeled only return shared references or owned values.
+    fn t_map_effect(&mut self, method: MapMethod, call: &Call<'_, 'tcx>) -> CallEffect {
+        let Some(Operand::Copy(map) | Operand::Move(map)) = call.args.first() else {
+            return CallEffect::Propagate;
+        };
+        let arg = |ix: usize| {
+            call.args
+                .get(ix)
+                .map_or(Labels::EMPTY, |arg| self.t_operand_taint(arg))
+        };
+        match method {
+            MapMethod::Insert => {
+                let (key, value) = (arg(1), arg(2));
+                let replaced = self.state.value_taint(map.local);
+                self.state.insert_entry(map.local, key, value);
+                CallEffect::Source(replaced)
+            }
+            MapMethod::Values => CallEffect::Source(self.state.value_taint(map.local)),
+            MapMethod::Keys => CallEffect::Source(self.state.key_taint(map.local)),
+        }
+    }
+
+    /// The labels the first and the second elements of the pairs the result of a call to a pair `adapter`
+    /// may carry: none and those of the items for an index and an item, those of each iterator zipped,
+    /// or those of the elements of the pairs of the iterator a method is called on.
+    fn t_pair_elements(
+        &self,
+        adapter: PairAdapter,
+        call: &Call<'_, 'tcx>,
+    ) -> Option<(Labels, Labels)> {
+        let arg = |ix: usize| {
+            call.args
+                .get(ix)
+                .map_or(Labels::EMPTY, |arg| self.t_operand_taint(arg))
+        };
+        match adapter {
+            PairAdapter::Enumerate => Some((Labels::EMPTY, arg(0))),
+            PairAdapter::Zip => Some((arg(0), arg(1))),
+            PairAdapter::Forward => {
+                let Some(Operand::Copy(iter) | Operand::Move(iter)) = call.args.first() else {
+                    return None;
+                };
+                Some((
+                    self.state
+                        .iterated_element_taint(iter.local, PairElement::First),
+                    self.state
+                        .iterated_element_taint(iter.local, PairElement::Second),
+                ))
+            }
+        }
+    }
+
+    /// The labels of the fields marked as sources which formatting a value of type `ty` with `Debug` reads,
+    /// through the impls derived for it and for the types within it.
+    fn t_debugged_fields(&self, ty: Ty<'tcx>) -> Labels {
+        let info = self.analysis.info;
+        if info.source_fields.is_empty() {
+            return Labels::EMPTY;
+        }
+        compiler::debugged_fields(self.analysis.tcx, ty)
+            .into_iter()
+            .filter(|field| info.source_fields.contains(field))
+            .fold(Labels::EMPTY, |labels, field| {
+                labels.union(info.labels_of_source(field))
+            })
+    }
+
+    /// Serializing a value produces its labels, and those of the fields marked as sources within its type `ty`,
+    /// which its `Serialize` impl reads. Serializing it into a writer writes them into the writer,
+    /// and reaches the writer's `write` or `write_all` if it is a sink, like the body of a response.
+    /// Fields marked `#[serde(skip)]` or redacted are not read, so neither are the sources among them,
+    /// nor the secret of a `Sensitive` among them if no other field holds one.
+    fn t_serialize_effect(
         &mut self,
-        args: &[Operand],
-        id: &rustc_hir::def_id::DefId,
-        destination: &Place,
-    ) {
-        let init = args
+        call: &Call<'_, 'tcx>,
+        value: usize,
+        writer: Option<usize>,
+        ty: Ty<'tcx>,
+    ) -> CallEffect {
+        let tcx = self.analysis.tcx;
+        let info = self.analysis.info;
+        let arg = |ix: usize| {
+            call.args
+                .get(ix)
+                .map_or(Labels::EMPTY, |arg| self.t_operand_taint(arg))
+        };
+        let Serialized {
+            fields,
+            written,
+            redacted,
+        } = compiler::serialized(tcx, ty);
+        let mut labels = arg(value);
+        if let Some(secret) = info.secret {
+            let holds_secret = |ty: &Ty<'tcx>| {
+                ty.walk()
+                    .filter_map(|arg| arg.as_type())
+                    .any(|ty| models::is_sensitive(tcx, ty))
+            };
+            if redacted.iter().any(holds_secret) && !written.iter().any(holds_secret) {
+                labels = labels.without(secret.into());
+            }
+        }
+        let serialized = fields
+            .into_iter()
+            .filter(|field| info.source_fields.contains(field))
+            .fold(labels, |labels, field| {
+                labels.union(info.labels_of_source(field))
+            });
+        // Serializing a value takes it across a boundary, like into a response or a file.
+        self.t_record_crossing(call.span, tcx.def_path_str(call.callee), ty, serialized);
+        let Some(writer) = writer else {
+            return CallEffect::Source(serialized);
+        };
+
+        let sink = compiler::write_methods(tcx, call.generic_args.type_at(0))
+            .into_iter()
+            .find(|&id| info.call_role(tcx, id) == Some(AttrInfoKind::Sink));
+        if let Some(sink) = sink {
+            let received = serialized.intersection(info.sink_labels(sink));
+            if !received.is_empty() {
+                let callee = format!("function `{}`", tcx.def_path_str(sink));
+                let reported = Sink {
+                    name: tcx.def_path_str(sink),
+                    arguments: vec![value],
+                    variables: self.t_variables(call.args, &[value]),
+                    model: Some(format!("the model of `{}`", tcx.def_path_str(call.callee))),
+                };
+                self.t_report_sink(call.span, sink, callee, received, reported);
+            }
+        }
+        let mut args = (0..call.args.len()).map(arg).collect::<Vec<_>>();
+        if let Some(written) = args.get_mut(writer) {
+            *written = written.union(serialized);
+        }
+        CallEffect::Summary {
+            returns: Labels::EMPTY,
+            args,
+            points_into: vec![],
+        }
+    }
+
+    /// Setting a cell writes the value into it, and returns it back in case the cell was already set.
+    fn t_cell_set_effect(&mut self, call: &Call<'_, 'tcx>, value: usize) -> CallEffect {
+        let mut args = call
+            .args
             .iter()
-            .map(|arg| match arg {
-                Operand::Copy(p) | Operand::Move(p) => Some(self.state.get_taint(p.local)),
-                Operand::Constant(_) => None,
-            })
+            .map(|arg| self.t_operand_taint(arg))
             .collect::<Vec<_>>();
+        let value = args.get(value).copied().unwrap_or(Labels::EMPTY);
+        if let Some(cell) = args.first_mut() {
+            *cell = cell.union(value);
+        }
+        self.t_write_global(call, value);
+        CallEffect::Summary {
+            returns: value,
+            args,
+            points_into: vec![],
+        }
+    }
 
-        let end_state = self.t_function_summary(id, init);
+    /// Initializing a cell which was not set writes what the initializer `init` returns into it,
+    /// and the value read is either that or what the cell held.
+    fn t_cell_init_effect(&mut self, call: &Call<'_, 'tcx>, init: Ty<'tcx>) -> CallEffect {
+        let mut args = call
+            .args
+            .iter()
+            .map(|arg| self.t_operand_taint(arg))
+            .collect::<Vec<_>>();
+        let initialized = self.t_init_returns(call, 1, init);
+        let Some(cell) = args.first_mut() else {
+            return CallEffect::Source(initialized);
+        };
+        *cell = cell.union(initialized);
+        let returns = *cell;
+        self.t_write_global(call, initialized);
+        CallEffect::Summary {
+            returns,
+            args,
+            points_into: vec![],
+        }
+    }
 
-        if let Some(end_state) = end_state {
-            let return_place = Local::from_usize(0);
+    /// The labels returned by the initializer of a cell, argument `ix` of type `init`, which is run
+    /// as if it was called right here with its captures. A function pointer is followed if it was made
+    /// from a function item, like `input` in `LazyLock::new(input)`, and its labels are taken otherwise.
+    fn t_init_returns(&mut self, call: &Call<'_, 'tcx>, ix: usize, init: Ty<'tcx>) -> Labels {
+        let tcx = self.analysis.tcx;
+        let captured = call
+            .args
+            .get(ix)
+            .map_or(Labels::EMPTY, |arg| self.t_operand_taint(arg));
+        let reified = compiler::mir_body(tcx, self.analysis.body)
+            .zip(call.args.get(ix))
+            .and_then(|(body, arg)| compiler::reified_fn(tcx, body, arg));
+        let summary = match reified.unwrap_or(init).kind() {
+            TyKind::Closure(id, args) => {
+                self.analysis
+                    .summarize(*id, args, vec![captured], Some(call.span))
+            }
+            TyKind::FnDef(id, args) => {
+                let (id, args) = compiler::resolve(tcx, *id, args);
+                self.analysis.called(id);
+                let info = self.analysis.info;
+                if info.call_role(tcx, id) == Some(AttrInfoKind::Source) {
+                    return info.labels_of_source(id);
+                }
+                self.analysis.summarize(id, args, vec![], Some(call.span))
+            }
+            _ => return captured,
+        };
+        summary.map_or(Labels::EMPTY, |summary| summary.returns)
+    }
 
-            if end_state.get_taint(return_place) {
-                self.t_visit_source_destination(destination);
+    /// A cell held by a static, like the receiver of `CONFIG.set(config)`, keeps `labels`
+    /// for every function which reads the static afterwards.
+    fn t_write_global(&self, call: &Call<'_, 'tcx>, labels: Labels) {
+        let tcx = self.analysis.tcx;
+        let Some(body) = compiler::mir_body(tcx, self.analysis.body) else {
+            return;
+        };
+        let id = match call.args.first() {
+            Some(operand @ Operand::Constant(_)) => compiler::static_ref(tcx, operand),
+            Some(Operand::Copy(p) | Operand::Move(p)) => {
+                compiler::static_behind(tcx, body, p.local)
             }
+            None => None,
+        };
+        if let Some(id) = id {
+            let mut globals = self.analysis.shared.globals.borrow_mut();
+            let global = globals.entry(id).or_insert(Labels::EMPTY);
+            *global = global.union(labels);
+            // Reusing the summary would skip the write.
+            self.analysis.side_effect();
+        }
+    }
 
-            let target_body = self.tcx.optimized_mir(*id);
-            let arg_map = args
+    /// Wrapping a value in a `taint_runtime::Sensitive` adds the `secret` label, which only `expose` removes.
+    /// Formatting the wrapper is reported whatever it carries, as it would print the secret.
+    fn t_sensitive_effect(&mut self, method: SensitiveMethod, call: &Call<'_, 'tcx>) -> CallEffect {
+        let secret = self.analysis.info.secret.unwrap_or(TAINT);
+        match method {
+            SensitiveMethod::Wrap => {
+                let args = call
+                    .args
+                    .iter()
+                    .map(|arg| self.t_operand_taint(arg))
+                    .collect::<Vec<_>>();
+                let returns = args
+                    .iter()
+                    .fold(secret.into(), |labels: Labels, &arg| labels.union(arg));
+                CallEffect::Summary {
+                    returns,
+                    args,
+                    points_into: vec![],
+                }
+            }
+            SensitiveMethod::Expose => CallEffect::Sanitizer {
+                removes: secret.into(),
+                relabels: None,
+            },
+            SensitiveMethod::Format { trait_name } => {
+                // Formatting in `println!` happens in its expansion.
+                self.t_report(
+                    call.span.source_callsite(),
+                    ErrorCode::T0001,
+                    format!(
+                        "a secret wrapped in `Sensitive` is formatted with `{}`, which exposes it",
+                        trait_name
+                    ),
+                    Sink {
+                        name: format!("std::fmt::{}", trait_name),
+                        variables: self.t_variables(call.args, &[0]),
+                        arguments: vec![0],
+                        model: Some(format!("the model of `{}` for `Sensitive`", trait_name)),
+                    },
+                    secret,
+                );
+                CallEffect::Propagate
+            }
+        }
+    }
+
+    /// Values formatted by one of the sink macros are reported at the invocation of the macro.
+    fn t_sink_macro(&self, call: &Call<'_, 'tcx>) -> Option<(&str, Span)> {
+        let tcx = self.analysis.tcx;
+        match models::find_model(tcx, call.callee, call.generic_args) {
+            Some(Model::FormatArgument { .. }) => {
+                compiler::macro_call_site(tcx, call.span, &self.analysis.options.sink_macros)
+            }
+            _ => None,
+        }
+    }
+
+    fn t_operand_taint(&self, operand: &Operand) -> Labels {
+        match operand {
+            Operand::Copy(p) | Operand::Move(p) => self.state.get_taint(p.local),
+            Operand::Constant(_) => Labels::EMPTY,
+        }
+    }
+
+    fn t_fn_call_analysis(
+        &mut self,
+        call: &Call<'_, 'tcx>,
+        id: DefId,
+        generic_args: GenericArgsRef<'tcx>,
+    ) -> Option<CallEffect> {
+        let tcx = self.analysis.tcx;
+        // A function item called through `Fn` gets the tuple of its arguments, whose labels
+        // are those of each of them.
+        let untupled = compiler::fn_item_call(tcx, call.callee, call.generic_args).is_some();
+        // Constants cannot carry taint, so calls with constant arguments share summaries with calls
+        // with untainted ones, including the analysis of the callee as an entry point.
+        let init = if untupled {
+            let tupled = call
+                .args
+                .get(1)
+                .map_or(Labels::EMPTY, |arg| self.t_operand_taint(arg));
+            let inputs = tcx.fn_sig(id).skip_binder().inputs().skip_binder().len();
+            vec![tupled; inputs]
+        } else {
+            call.args
                 .iter()
-                .map(|arg| arg.place().or(None))
-                .zip(target_body.args_iter())
-                .collect::<Vec<_>>();
+                .map(|arg| self.t_operand_taint(arg))
+                .collect::<Vec<_>>()
+        };
 
-            // Check if any variables which were passed in are tainted at this point.
-            for (caller_arg, callee_arg) in arg_map {
-                if let Some(place) = caller_arg {
-                    self.state
-                        .set_taint(place.local, end_state.get_taint(callee_arg));
+        if let Some(effect) = self
+            .analysis
+            .shared
+            .imported
+            .effect(&tcx.def_path_str(id), &init)
+        {
+            return Some(effect);
+        }
+
+        // Without a body, e.g. for foreign functions, intrinsics, naked functions or functions of other crates
+        // whose MIR was not encoded, there is nothing for us to analyze.
+        let Some(target_body) = compiler::mir_body(tcx, id) else {
+            let path = tcx.def_path_str(id);
+            if let Some(effect) = self.analysis.shared.interfaces.effect(&path, &init) {
+                return Some(effect);
+            }
+            return self.t_unknown_call(id, call.span);
+        };
+
+        let summary = self
+            .analysis
+            .summarize(id, generic_args, init, Some(call.span))?;
+        if summary.approximated {
+            self.t_approximated(id, call.span, Approximation::Callee);
+        }
+
+        let args = target_body
+            .args_iter()
+            .map(|arg| summary.state.get_taint(arg));
+        if untupled {
+            let tupled = args.fold(Labels::EMPTY, Labels::union);
+            let points_into = if summary.points_into.is_empty() {
+                vec![]
+            } else {
+                vec![1]
+            };
+            return Some(CallEffect::Summary {
+                returns: summary.returns,
+                args: vec![Labels::EMPTY, tupled],
+                points_into,
+            });
+        }
+        Some(CallEffect::Summary {
+            returns: summary.returns,
+            args: args.collect(),
+            points_into: summary.points_into,
+        })
+    }
+
+    /// The effect of a call to `id` at `span` whose body we cannot analyze, as `--taint-unknown-calls` decides.
+    fn t_unknown_call(&self, id: DefId, span: Span) -> Option<CallEffect> {
+        self.analysis.shared.usage.borrow_mut().without_mir(id);
+        let approximation = if compiler::is_naked(self.analysis.tcx, id) {
+            Approximation::Naked
+        } else {
+            Approximation::WithoutMir
+        };
+        self.t_approximated(id, span, approximation);
+        match self.analysis.options.unknown_calls {
+            UnknownCalls::Clean => None,
+            UnknownCalls::Propagate => Some(CallEffect::Propagate),
+        }
+    }
+
+    /// The call to `callee` at `span` was approximated, which findings in the function note.
+    fn t_approximated(&self, callee: DefId, span: Span, approximation: Approximation) {
+        let function = self.analysis.body;
+        self.analysis.shared.findings.borrow_mut().approximated(
+            function,
+            span,
+            callee,
+            approximation,
+        );
+    }
+}
+
+/// An empty points-to map, whose locals point anywhere past `--taint-max-points-to` locals.
+fn points_map(options: &Options) -> PointsMap<Local> {
+    PointsMap::new(options.max_points_to.unwrap_or(DEFAULT_MAX_POINTS_TO))
+}
+
+/// The functions on the stack `frames`, for the paths of findings.
+/// The locals of `body`, instantiated with `args`, which `tracked_types` in the config keeps untainted,
+/// along with those of zero-sized types, which hold no data: taint on markers like `PhantomData` would
+/// only flow on to whatever they are passed to, like the value a typestate builder builds.
+fn untracked_locals<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    info: &AttrInfo,
+    body: DefId,
+    args: GenericArgsRef<'tcx>,
+) -> Vec<Local> {
+    let Some(mir) = compiler::mir_body(tcx, body) else {
+        return vec![];
+    };
+    let mut untracked = compiler::zero_sized_locals(tcx, mir, args);
+    if let Some(classes) = &info.tracked_types {
+        untracked.extend(compiler::untracked_locals(tcx, mir, args, classes));
+        untracked.sort();
+        untracked.dedup();
+    }
+    untracked
+}
+
+fn reached(frames: &[Frame]) -> Vec<Reached> {
+    frames
+        .iter()
+        .map(|frame| (frame.function, frame.init.clone()))
+        .collect()
+}
+
+fn called(frames: &[Frame]) -> Vec<Called> {
+    frames
+        .iter()
+        .map(|frame| (frame.function, frame.init.clone(), frame.call_site))
+        .collect()
+}
+
+/// How a diagnostic names the callee `id` of `call`.
+/// A closure is called through `Fn::call`, which is named after the closure's span, so name the closure itself,
+/// and a function item called through it, like by a combinator, is named after the function.
+fn t_callee_name<'tcx>(tcx: TyCtxt<'tcx>, id: DefId, call: &Call<'_, 'tcx>) -> String {
+    if tcx.is_closure(id) {
+        format!("closure `{}`", tcx.def_path_str(id))
+    } else if compiler::fn_item_call(tcx, call.callee, call.generic_args).is_some() {
+        format!("function `{}`", tcx.def_path_str(id))
+    } else {
+        format!("function `{}`", call.name)
+    }
+}
+
+/// The effect of calling either of two functions: the result and the arguments carry the labels
+/// they would after calling either. Only summaries can be joined, and other effects win over them.
+fn t_join_effects(a: CallEffect, b: CallEffect) -> CallEffect {
+    match (a, b) {
+        (
+            CallEffect::Summary {
+                returns,
+                args,
+                mut points_into,
+            },
+            CallEffect::Summary {
+                returns: other_returns,
+                args: other_args,
+                points_into: other_points_into,
+            },
+        ) => {
+            for ix in other_points_into {
+                if !points_into.contains(&ix) {
+                    points_into.push(ix);
                 }
             }
+            CallEffect::Summary {
+                returns: returns.union(other_returns),
+                args: args
+                    .iter()
+                    .zip(&other_args)
+                    .map(|(&arg, &other)| arg.union(other))
+                    .collect(),
+                points_into,
+            }
         }
+        (CallEffect::Summary { .. }, other) | (other, _) => other,
     }
+}
 
-    fn t_function_summary(&mut self, id: &DefId, init: Vec<Option<bool>>) -> Option<BitSet<Local>> {
-        let key = (*id, init.clone());
+/// The locals `rvalue` builds an aggregate from, if it builds one from whole locals.
+fn t_aggregated_pointers(rvalue: &Rvalue<'_>) -> Vec<Local> {
+    let Rvalue::Aggregate(_, operands) = rvalue else {
+        return vec![];
+    };
+    operands
+        .iter()
+        .filter_map(|operand| match operand {
+            Operand::Copy(p) | Operand::Move(p) if p.projection.is_empty() => Some(p.local),
+            _ => None,
+        })
+        .collect()
+}
 
-        if let Some(summary) = self.t_get_cached_summary(&key) {
+/// The locals an assignment to `place` writes into, and whether it writes into all of each of them.
+/// Writing through a pointer writes into the objects of the pointee's type it may point to,
+/// possibly through the pointers it was copied from. When it points to none of them, the pointer stands
+/// for what it points into: all of it, like a reference passed as an argument, if it points to nothing we
+/// know of, or a part of it otherwise, like a field it was borrowed from.
+fn t_written_locals<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    place: &Place<'tcx>,
+    points: &PointsMap<Local>,
+) -> (Vec<Local>, bool) {
+    let Some(pointee) = compiler::pointee_ty(tcx, body, place) else {
+        return (vec![place.local], true);
+    };
+    let mut referents = taint_domain::aliases(points, place.local);
+    referents.remove(&place.local);
+    let mut objects = referents
+        .iter()
+        .copied()
+        .filter(|&local| tcx.erase_regions(body.local_decls[local].ty) == pointee)
+        .collect::<Vec<_>>();
+    objects.sort();
+    match objects.len() {
+        0 => (vec![place.local], referents.is_empty()),
+        1 => (objects, true),
+        _ => (objects, false),
+    }
+}
+
+/// The local a pointer is copied from, if `rvalue` copies one, possibly with an offset or a cast.
+fn t_copied_pointer(rvalue: &Rvalue<'_>) -> Option<Local> {
+    match rvalue {
+        Rvalue::Use(Operand::Copy(p) | Operand::Move(p))
+        | Rvalue::CopyForDeref(p)
+        | Rvalue::Cast(_, Operand::Copy(p) | Operand::Move(p), _)
+        | Rvalue::BinaryOp(BinOp::Offset, box (Operand::Copy(p) | Operand::Move(p), _)) => {
+            Some(p.local)
+        }
+        _ => None,
+    }
+}
+
+impl<'tcx> TaintAnalysis<'tcx, '_> {
+    /// The context to analyze `id` in when called with arguments carrying `init`.
+    /// A function with more arguments than `--taint-max-arity` is analyzed with the labels of all of them
+    /// on each, so that it is analyzed at most once per set of labels rather than once per combination
+    /// of tainted arguments.
+    fn bounded_init(&self, id: DefId, init: InitSet) -> InitSet {
+        let max_arity = self.options.max_arity.unwrap_or(DEFAULT_MAX_ARITY);
+        if init.len() <= max_arity {
+            return init;
+        }
+        self.shared.usage.borrow_mut().abstracted(id, init.len());
+        let all = init
+            .iter()
+            .fold(Labels::EMPTY, |all, &labels| all.union(labels));
+        vec![all; init.len()]
+    }
+
+    /// Whether the run was cancelled, or failed fast, after which no more callees are analyzed.
+    fn is_stopped(&self) -> bool {
+        self.options.cancellation.is_cancelled() || self.shared.failed_fast.get()
+    }
+
+    /// What `id`, instantiated with `args`, does when called at `call_site` in the context `init`.
+    /// Summaries are cached, so each function is only analyzed once per context.
+    pub(crate) fn summarize(
+        &self,
+        id: DefId,
+        args: GenericArgsRef<'tcx>,
+        init: InitSet,
+        call_site: Option<Span>,
+    ) -> Option<Summary> {
+        let init = self.bounded_init(id, init);
+        let key = (id, args, init.clone());
+
+        // A cancelled analysis is thrown away, and one which failed fast only reports what it found,
+        // so what callees do no longer matters.
+        if self.is_stopped() {
+            return None;
+        }
+        if let Some(summary) = self.cached_summary(&key) {
+            // A missing summary is a placeholder for one which is still being computed,
+            // so the call is cut off and the caller's summary approximated.
+            if let (None, Some(call_site)) = (&summary, call_site) {
+                self.shared.findings.borrow_mut().approximated(
+                    self.body,
+                    call_site,
+                    id,
+                    Approximation::Recursion,
+                );
+            }
+            if summary.is_some() {
+                self.shared.usage.borrow_mut().reused(self.entry, id);
+                let stack = called(&self.shared.stack.borrow());
+                self.shared
+                    .findings
+                    .borrow_mut()
+                    .reused(id, &init, call_site, &stack);
+                let trace = self.shared.warm.borrow().traced(&key).cloned();
+                if let Some(trace) = trace {
+                    self.merge_trace(&trace);
+                }
+                if self.shared.side_effects.borrow().contains(&id) {
+                    self.side_effect();
+                }
+            } else {
+                // What the caller does then depends on where the recursion was cut off.
+                self.side_effect();
+            }
             summary
         } else {
+            let carried = self.shared.warm.borrow_mut().carried(self.tcx, &key);
+            if let Some((summary, trace)) = carried {
+                self.insert_summary(&key, Some(summary.clone()));
+                self.shared.usage.borrow_mut().carried(self.entry, id);
+                self.merge_trace(&trace);
+                return Some(summary);
+            }
+            // What the analysis of a context of the checkpoint went through is not known, so runs carrying
+            // summaries over to the next one analyze it again.
+            let resumed = self
+                .shared
+                .checkpoint
+                .as_ref()
+                .filter(|_| !self.shared.warm.borrow().is_carrying())
+                .and_then(|checkpoint| checkpoint.resumed(self.tcx, &key));
+            if let Some(summary) = resumed {
+                self.insert_summary(&key, Some(summary.clone()));
+                self.shared.usage.borrow_mut().carried(self.entry, id);
+                return Some(summary);
+            }
+
             // In the case that we have recursive or mutually recursive function calls,
             // we make sure that we only compute a summary once per key by inserting None while we compute it.
-            // For subsequent calls, calling `t_function_summary` will simply return None and the visitor will analyze other branches.
-            self.t_insert_summary(&key, None);
-
-            let target_body = self.tcx.optimized_mir(*id);
-            let mut results =
-                TaintAnalysis::new_with_init(self.tcx, self.info, self.contexts.clone(), init)
-                    .into_engine(self.tcx, target_body)
-                    .pass_name("taint_analysis")
-                    .iterate_to_fixpoint()
-                    .into_results_cursor(target_body);
-
-            let state = if let Some((last, _)) = reverse_postorder(target_body).last() {
-                results.seek_to_block_end(last);
-                Some(results.get().clone())
+            // For subsequent calls, calling `summarize` will simply return None and the visitor will analyze other branches.
+            self.insert_summary(&key, None);
+
+            let target_body = compiler::mir_body(self.tcx, id)?;
+            self.shared.usage.borrow_mut().computed(self.entry, id);
+            if self.options.debug_stable_mir {
+                if let Some((internal, stable)) = compiler::stable::bodies(self.tcx, id) {
+                    invariants::check_lowering(&self.tcx.def_path_str(id), internal, &stable);
+                }
+            }
+            // Lets `TAINT_LOG` select the functions to trace, like `taint[{function=my_crate::handler}]=trace`.
+            // Paths of the crate analyzed are printed without its name, which the field is given.
+            let function = match id.as_local() {
+                Some(_) => format!(
+                    "{}::{}",
+                    self.tcx.crate_name(LOCAL_CRATE),
+                    self.tcx.def_path_str(id)
+                ),
+                None => self.tcx.def_path_str(id),
+            };
+            let _span = info_span!("summarize", function = %function).entered();
+            self.shared.stack.borrow_mut().push(Frame {
+                function: id,
+                init: init.clone(),
+                call_site,
+                trace: self.shared.warm.borrow().trace(self.tcx, id),
+            });
+            let mut results = self
+                .for_callee(id, args, init)
+                .into_engine(self.tcx, target_body)
+                .pass_name("taint_analysis")
+                .iterate_to_fixpoint()
+                .into_results_cursor(target_body);
+
+            // The summary is the state the function may return in, i.e. the join of the states at every `return`.
+            // Generators in particular end in blocks which panic, so the last block is not necessarily a return.
+            // A function which never returns, because it loops forever or always panics, may still
+            // have written to its arguments before diverging, which callers see when unwinding.
+            // Its summary is the join of the states at the end of every block it reaches, and it returns nothing.
+            let returns = compiler::return_blocks(target_body).collect::<Vec<_>>();
+            let diverges = returns.is_empty();
+            let exits = if diverges {
+                compiler::reachable_blocks(target_body).collect()
             } else {
-                None
+                returns
             };
+            let mut state: Option<TaintState<Local>> = None;
+            for block in exits {
+                results.seek_to_block_end(block);
+                match state {
+                    Some(ref mut state) => {
+                        state.join(results.get());
+                    }
+                    None => state = Some(results.get().clone()),
+                }
+            }
+
+            let points = results.analysis().points.borrow().clone();
+            let (largest, anywhere) = points.largest();
+            self.shared
+                .usage
+                .borrow_mut()
+                .points_to(id, largest, anywhere);
+            let aliases = taint_domain::aliases(&points, RETURN_PLACE);
+            let points_into = target_body
+                .args_iter()
+                .enumerate()
+                .filter(|(_, arg)| aliases.contains(arg))
+                .map(|(ix, _)| ix)
+                .filter(|_| !diverges)
+                .collect();
+
+            if self.options.records_facts() {
+                self.record_facts(id, &mut results);
+            }
+            if self.options.explain.as_deref() == Some(self.tcx.def_path_str(id).as_str()) {
+                self.explain(id, &key.2, &mut results);
+            }
+
+            let summary = state.map(|state| Summary {
+                returns: if diverges {
+                    Labels::EMPTY
+                } else {
+                    taint_domain::aliased_taint(&state, &points, RETURN_PLACE)
+                },
+                state,
+                points_into,
+                approximated: self.shared.findings.borrow().is_approximated(id),
+            });
+            if let Some(summary) = &summary {
+                if self.info.return_sinks.contains(&id) {
+                    self.check_return_sink(id, summary.returns);
+                }
+                if let Some(accessor) = self.info.accessor(id) {
+                    self.check_getter(accessor, summary.returns);
+                }
+            }
+            // The function is still on the stack, so that findings in it note how it was called.
+            let frame = self.shared.stack.borrow_mut().pop();
+            if let Some(mut frame) = frame {
+                // Callees analyzed once the run was stopped were left out of the summary.
+                if self.is_stopped() {
+                    frame.trace.side_effect();
+                }
+                if frame.trace.has_side_effects() {
+                    self.shared.side_effects.borrow_mut().insert(id);
+                }
+                self.shared.warm.borrow_mut().finished(
+                    self.tcx,
+                    &key,
+                    &frame.trace,
+                    summary.as_ref(),
+                );
+                self.merge_trace(&frame.trace);
+            }
 
             // Once the function summary has been computed, we insert it into the cache.
-            self.t_insert_summary(&key, state.clone());
+            self.insert_summary(&key, summary.clone());
+            // Long analyses of a single entry point are checkpointed too.
+            if let Some(checkpoint) = &self.shared.checkpoint {
+                checkpoint.save(self.tcx, self.shared, false);
+            }
 
-            state
+            summary
         }
     }
 
-    fn t_insert_summary(&mut self, key: &(DefId, Vec<Option<bool>>), val: Option<BitSet<Local>>) {
-        self.contexts.borrow_mut().insert(key.clone(), val);
+    /// The labels of the value of the static `id`: those its initializer computes, those returned
+    /// by the closures defined in the initializer, which usually initialize it lazily,
+    /// like `LazyLock::new(|| ...)`, and those of the source it is if the config marks it as one.
+    /// Statics of `extern` blocks have no initializer, so foreign code only taints them through the config.
+    /// Initializers are summarized like functions, so each is only analyzed once.
+    /// A static holding a cell also carries what was written into the cell so far, like with `CONFIG.set(config)`.
+    fn static_taint(&self, id: DefId) -> Labels {
+        let ty = self.tcx.type_of(id).instantiate_identity();
+        if models::is_cell(self.tcx, ty) {
+            // What was written so far may change from one run to the next.
+            self.side_effect();
+        }
+        let written = self
+            .shared
+            .globals
+            .borrow()
+            .get(&id)
+            .copied()
+            .unwrap_or(Labels::EMPTY);
+        let returns = |id, init| {
+            self.summarize(id, compiler::identity_args(self.tcx, id), init, None)
+                .map_or(Labels::EMPTY, |summary| summary.returns)
+        };
+        compiler::initializer_closures(self.tcx, id)
+            .into_iter()
+            .fold(returns(id, vec![]), |labels, closure| {
+                // The only argument of a closure which takes none is the closure itself.
+                let init = compiler::mir_body(self.tcx, closure)
+                    .map_or(vec![], |body| vec![Labels::EMPTY; body.arg_count]);
+                labels.union(returns(closure, init))
+            })
+            .union(self.info.labels_of_static(self.tcx, id))
+            .union(written)
     }
 
-    fn t_get_cached_summary(
-        &mut self,
-        key: &(DefId, Vec<Option<bool>>),
-    ) -> Option<Option<BitSet<Local>>> {
-        let contexts = self.contexts.borrow();
-        contexts.get(key).cloned()
+    /// Records a sink receiving `label` in `function`, for the errors emitted once the analysis is done,
+    /// `--taint-findings` and the progress events.
+    /// Findings at the same location are merged, keeping the context the first one was found in.
+    /// A sink called by code of another crate, like a function item a combinator of `std` calls,
+    /// is reported where the analyzed crate called into that code, unless `--taint-report-in-deps` is given.
+    /// Findings on a line below a `// taint-ignore:` comment for their code are dropped.
+    fn report(
+        &self,
+        mut function: DefId,
+        mut span: Span,
+        code: ErrorCode,
+        message: String,
+        sink: Sink,
+        label: Label,
+    ) {
+        let stack = self.shared.stack.borrow();
+        let found_in = function;
+        let mut frames = stack.len();
+        while !self.options.report_in_deps && !compiler::is_local(self.tcx, span) && frames > 1 {
+            let Some(call_site) = stack[frames - 1].call_site else {
+                break;
+            };
+            frames -= 1;
+            function = stack[frames - 1].function;
+            span = call_site;
+        }
+        if self.info.ignore(span, code).is_some() {
+            return;
+        }
+        // Variables of a dependency it was lifted out of would point into its internals.
+        let mut variables = sink.variables;
+        variables.retain(|(_, declared)| compiler::is_local(self.tcx, *declared));
+        // A finding a model made inside a dependency notes where, as its call site alone would not tell.
+        let model = sink.model.filter(|_| frames < stack.len()).map(|model| {
+            format!(
+                "found inside `{}`, by {}",
+                self.tcx.def_path_str(found_in),
+                model
+            )
+        });
+        let frames = &stack[..frames];
+        let callers = (1..frames.len())
+            .rev()
+            .map_while(|ix| Some((frames[ix - 1].function, frames[ix].call_site?)))
+            .collect();
+        let finding = Recorded {
+            function,
+            span,
+            code,
+            message,
+            sink: sink.name,
+            arguments: sink.arguments,
+            label: self.info.label_name(self.tcx, label),
+            also: vec![],
+            variables,
+            model,
+            contexts: vec![called(frames)],
+            callers,
+            labels: label.into(),
+            paths: vec![reached(frames)],
+            profiles: self.options.profile.iter().cloned().collect(),
+        };
+        drop(stack);
+        self.shared.findings.borrow_mut().record(finding);
+        self.side_effect();
+        if self.options.fail_fast && !code.is_warning() {
+            self.shared.failed_fast.set(true);
+        }
     }
 
-    fn t_visit_source_destination(&mut self, destination: &Place) {
-        self.state.set_taint(destination.local, true);
+    /// Reports a getter of a type marked as a source which returns one of its fields.
+    fn check_getter(&self, accessor: &Accessor, returns: Labels) {
+        if accessor.kind != AccessorKind::Getter {
+            return;
+        }
+        let tcx = self.tcx;
+        let Some(label) = returns
+            .intersection(self.info.labels_of_source(accessor.ty))
+            .iter()
+            .next()
+        else {
+            return;
+        };
+        let function = tcx.def_path_str(accessor.function);
+        self.report(
+            accessor.function,
+            tcx.def_span(accessor.function),
+            ErrorCode::T0001,
+            format!(
+                "method `{}` returns a field of `{}`, which is marked as a source",
+                function,
+                tcx.def_path_str(accessor.ty)
+            ),
+            Sink::without_arguments(function.clone()),
+            label,
+        );
     }
 
-    fn t_visit_sanitizer_destination(&mut self, destination: &Place) {
-        self.state.set_taint(destination.local, false);
+    /// Reports `id`, a function marked `#[taint::sink(return)]`, returning the labels `returns`.
+    /// It is checked once per context it is summarized in, rather than at its call sites.
+    fn check_return_sink(&self, id: DefId, returns: Labels) {
+        let tcx = self.tcx;
+        let returned = returns.intersection(self.info.sink_labels(id));
+        let unsanitized = returned.intersection(self.info.pending_labels());
+        let labeled = returned.without(unsanitized).without(TAINT.into());
+        let function = tcx.def_path_str(id);
+        let report = |code, message, label| {
+            let sink = Sink::without_arguments(function.clone());
+            self.report(id, tcx.def_span(id), code, message, sink, label);
+        };
+        if returned.contains(TAINT) {
+            report(
+                ErrorCode::T0001,
+                format!("function `{}` returns tainted data", function),
+                TAINT,
+            );
+        }
+        for label in labeled.iter() {
+            report(
+                ErrorCode::T0001,
+                format!(
+                    "function `{}` returns tainted data labeled `{}`",
+                    function,
+                    self.info.label_name(tcx, label)
+                ),
+                label,
+            );
+        }
+        if returned.contains(TAINT) || !labeled.is_empty() {
+            return;
+        }
+        if let Some(label) = unsanitized.iter().next() {
+            let sanitizer = self
+                .info
+                .skipped_sanitizer(id, returned)
+                .map_or_else(String::new, |id| tcx.def_path_str(id));
+            report(
+                ErrorCode::T0003,
+                format!(
+                    "function `{}` returns data which was not sanitized by `{}`",
+                    function, sanitizer
+                ),
+                label,
+            );
+        }
     }
 
-    fn t_visit_sink(&mut self, name: String, args: &[Operand], span: &Span) {
-        if args.iter().map(|op| op.place()).any(|el| {
-            if let Some(place) = el {
-                self.state.get_taint(place.local)
-            } else {
-                false
-            }
-        }) {
-            struct_span_err!(
-                self.tcx.sess,
-                *span,
-                T0001,
-                "function `{}` received tainted input",
-                name
-            )
-            .emit();
+    /// Notes that the analysis of the function on top of the stack did something which reusing
+    /// its summary in the next run would skip, like reporting a finding.
+    fn side_effect(&self) {
+        if let Some(frame) = self.shared.stack.borrow_mut().last_mut() {
+            frame.trace.side_effect();
+        }
+    }
+
+    /// Notes that the function on top of the stack calls `id`, which the config may give a rule to,
+    /// so that its summary is not carried over once the rule changes.
+    fn called(&self, id: DefId) {
+        if !self.shared.warm.borrow().is_carrying() {
+            return;
+        }
+        if let Some(frame) = self.shared.stack.borrow_mut().last_mut() {
+            frame.trace.called(self.tcx.def_path_str(id));
+        }
+    }
+
+    /// Adds what the analysis of a callee went through to that of the function on top of the stack.
+    fn merge_trace(&self, trace: &Trace) {
+        if let Some(frame) = self.shared.stack.borrow_mut().last_mut() {
+            frame.trace.merge(trace);
+        }
+    }
+
+    fn insert_summary(&self, key: &ContextKey<'tcx>, val: Option<Summary>) {
+        self.shared.contexts.borrow_mut().insert(key.clone(), val);
+    }
+
+    fn cached_summary(&self, key: &ContextKey<'tcx>) -> Option<Option<Summary>> {
+        let contexts = self.shared.contexts.borrow();
+        contexts.get(key).cloned()
+    }
+
+    /// Prints every statement of `id`, analyzed with the arguments `init`, which changes the labels
+    /// of a local, next to the labels it changes, for `--taint-explain`.
+    fn explain<'mir>(
+        &self,
+        id: DefId,
+        init: &InitSet,
+        results: &mut ResultsCursor<'mir, 'tcx, TaintAnalysis<'tcx, '_>>,
+    ) {
+        let labels = |labels: Labels| {
+            let names = labels
+                .iter()
+                .map(|label| self.info.label_name(self.tcx, label))
+                .collect::<Vec<_>>();
+            format!("{{{}}}", names.join(", "))
+        };
+        let args = init.iter().map(|&arg| labels(arg)).collect::<Vec<_>>();
+        let mut explained = format!(
+            "`{}` called with ({}):\n",
+            self.tcx.def_path_str(id),
+            args.join(", ")
+        );
+
+        let body = results.body();
+        for (block, data) in body.basic_blocks.iter_enumerated() {
+            let statements = data
+                .statements
+                .iter()
+                .map(|statement| format!("{:?}", statement))
+                // Without the blocks a terminator goes to, which only make it longer.
+                .chain(
+                    format!("{:?}", data.terminator().kind)
+                        .split(" -> ")
+                        .next()
+                        .map(str::to_owned),
+                );
+            for (statement_index, statement) in statements.enumerate() {
+                let location = Location {
+                    block,
+                    statement_index,
+                };
+                results.seek_before_primary_effect(location);
+                let before = results.get().clone();
+                results.seek_after_primary_effect(location);
+                let after = results.get();
+                let changed = body
+                    .local_decls
+                    .indices()
+                    .filter(|&local| before.get_taint(local) != after.get_taint(local))
+                    .map(|local| {
+                        let name = compiler::local_name(body, local)
+                            .map_or_else(String::new, |name| format!(" ({})", name));
+                        format!(
+                            "{:?}{}: {} -> {}",
+                            local,
+                            name,
+                            labels(before.get_taint(local)),
+                            labels(after.get_taint(local))
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                if !changed.is_empty() {
+                    let _ = writeln!(
+                        explained,
+                        "  {:<8} {:<48} {}",
+                        format!("{:?}", location),
+                        statement,
+                        changed.join(", ")
+                    );
+                }
+            }
+        }
+        print!("{}", explained);
+    }
+
+    /// Records the labels of the value assigned by every statement and call of `id`.
+    fn record_facts<'mir>(
+        &self,
+        id: DefId,
+        results: &mut ResultsCursor<'mir, 'tcx, TaintAnalysis<'tcx, '_>>,
+    ) {
+        if !id.is_local() {
+            return;
+        }
+
+        let body = results.body();
+        for (block, data) in body.basic_blocks.iter_enumerated() {
+            let assignments = data
+                .statements
+                .iter()
+                .enumerate()
+                .filter_map(|(ix, statement)| match &statement.kind {
+                    StatementKind::Assign(box (place, _)) => {
+                        Some((ix, place.local, statement.source_info.span))
+                    }
+                    _ => None,
+                });
+            let call = Call::from_terminator(&data.terminator().kind).map(|call| {
+                let ix = data.statements.len();
+                (
+                    ix,
+                    call.destination.local,
+                    data.terminator().source_info.span,
+                )
+            });
+
+            for (statement_index, local, span) in assignments.chain(call) {
+                results.seek_after_primary_effect(Location {
+                    block,
+                    statement_index,
+                });
+                let labels = taint_domain::aliased_taint(
+                    results.get(),
+                    &results.analysis().points.borrow(),
+                    local,
+                );
+                self.shared
+                    .facts
+                    .borrow_mut()
+                    .record(id, span, labels, self.entry);
+            }
         }
     }
 }
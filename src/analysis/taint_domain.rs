@@ -1,112 +1,686 @@
 //! A trait to constrain the domain operations to taint analysis.
+//!
+//! The domain does not name the compiler's types: it is generic over the [index](LocalIndex) of the locals,
+//! which the analysis instantiates with the compiler's `Local`, so that it builds and is tested on its own.
 
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    hash::Hash,
+};
 
-use rustc_index::{bit_set::BitSet, Idx};
-use rustc_middle::mir::{Local, Place};
 use tracing::instrument;
 
-use crate::taint_analysis::PointsMap;
+use crate::{
+    compiler::{PairElement, ResultVariant},
+    options::DEFAULT_MAX_POINTS_TO,
+};
+
+use super::labels::{Join, Labels};
+
+/// The index of a local of a body, like the compiler's `Local`.
+pub trait LocalIndex: Copy + Eq + Hash + fmt::Debug + 'static {
+    fn new(index: usize) -> Self;
+    fn index(self) -> usize;
+}
+
+/// The labels of every local.
+///
+/// Locals holding a map also tell which of their labels its keys may carry, and which its values may,
+/// so that looking up a value does not return the taint of the keys, and the other way around.
+/// Only the map calls the analysis models put labels into one of them alone: any other write counts
+/// the labels it adds as both, so every label of a local is carried by its keys, its values, or both.
+/// Writes which may put labels the map already has into the other one, like calls taking the map
+/// by mutable reference, must [mix](PointsAwareTaintDomain::mix_entries) them.
+///
+/// Locals holding a `Result` split their labels the same way, between the value it holds if it is `Ok`
+/// and the error it holds if it is `Err`, which only building one of the variants puts labels into alone.
+/// So do locals holding a pair, between its two elements, and the iterators over pairs made by `enumerate`
+/// and `zip`, between the first and the second element of the pairs they yield.
+///
+/// Locals holding a struct may give each of its fields labels of their own, once it was built from its fields
+/// or one of them was written or moved out, so that moving a field out leaves the others with theirs.
+/// The labels of such a local are those of its fields together; any write to it as a whole forgets its fields,
+/// each of which may then carry all of its labels again.
+///
+/// Few of the locals of a body carry labels, and a state is cloned into every cached summary,
+/// so the state only lists the locals which carry something until that takes more room than
+/// an entry for every local would, like rustc's `HybridBitSet`.
+#[derive(Clone)]
+pub struct TaintState<T: LocalIndex> {
+    len: usize,
+    locals: Locals<T>,
+    /// The labels of each field of the struct a local holds, in order of the locals, for those whose fields
+    /// do not all carry all of their labels.
+    fields: Vec<(T, Box<[Labels]>)>,
+}
+
+#[derive(Clone)]
+enum Locals<T: LocalIndex> {
+    /// The locals which carry something, in order.
+    Sparse(Vec<(T, Taint)>),
+    /// What each local carries, by its index.
+    Dense(Vec<Taint>),
+}
+
+/// What a single local carries: its labels, and those of them the first and the second of its parts
+/// may carry, which are the keys and the values of a map, the `Ok` and `Err` values of a `Result`,
+/// or the elements of a pair.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Taint {
+    labels: Labels,
+    keys: Labels,
+    values: Labels,
+}
+
+impl Taint {
+    fn is_clean(&self) -> bool {
+        self.labels.is_empty() && self.keys.is_empty() && self.values.is_empty()
+    }
+
+    fn is_subset(&self, other: &Taint) -> bool {
+        self.labels.is_subset(other.labels)
+            && self.keys.is_subset(other.keys)
+            && self.values.is_subset(other.values)
+    }
+
+    /// Any label may be carried by the keys of the map the local holds as well as by its values.
+    fn mix_entries(&mut self) {
+        self.keys = self.keys.union(self.labels);
+        self.values = self.values.union(self.labels);
+    }
+}
+
+impl Join for Taint {
+    fn join(&mut self, other: &Self) -> bool {
+        let labels = self.labels.join(&other.labels);
+        let keys = self.keys.join(&other.keys);
+        let values = self.values.join(&other.values);
+        labels || keys || values
+    }
+}
+
+/// What each local may point to.
+///
+/// Pathological code, like a loop pushing references to many locals into one vector, can make a local
+/// point to so many others that every read and write through it visits all of them. A local which may
+/// point to more than `max` locals is taken to point anywhere instead: to every local of the body,
+/// whose labels writes through it add to rather than replace.
+#[derive(Debug, Clone)]
+pub(crate) struct PointsMap<T: LocalIndex> {
+    referents: HashMap<T, HashSet<T>>,
+    anywhere: HashSet<T>,
+    max: usize,
+}
+
+impl<T: LocalIndex> Default for PointsMap<T> {
+    fn default() -> Self {
+        PointsMap::new(DEFAULT_MAX_POINTS_TO)
+    }
+}
+
+impl<T: LocalIndex> PointsMap<T> {
+    pub(crate) fn new(max: usize) -> Self {
+        PointsMap {
+            referents: HashMap::new(),
+            anywhere: HashSet::new(),
+            max,
+        }
+    }
+
+    /// The locals `local` directly points to.
+    pub(crate) fn referents(&self, local: T) -> Option<&HashSet<T>> {
+        self.referents.get(&local)
+    }
+
+    /// The size of the largest set of locals one local points to, and whether one of them points anywhere.
+    pub(crate) fn largest(&self) -> (usize, bool) {
+        let largest = self.referents.values().map(HashSet::len).max();
+        (largest.unwrap_or(0), !self.anywhere.is_empty())
+    }
+
+    /// `from` may point to `to`, or anywhere among the `len` locals of the body if that makes too many.
+    fn insert(&mut self, from: T, to: T, len: usize) {
+        let set = self.referents.entry(from).or_default();
+        if self.anywhere.contains(&from) || !set.insert(to) || set.len() <= self.max {
+            return;
+        }
+        set.extend((0..len).map(T::new));
+        self.anywhere.insert(from);
+    }
+}
 
 #[derive(Debug)]
-pub(crate) struct PointsAwareTaintDomain<'a, T: Idx> {
-    pub(crate) state: &'a mut BitSet<T>,
-    pub(crate) map: &'a mut PointsMap,
+pub(crate) struct PointsAwareTaintDomain<'a, T: LocalIndex> {
+    pub(crate) state: &'a mut TaintState<T>,
+    pub(crate) map: &'a mut PointsMap<T>,
 }
 
-pub(crate) trait TaintDomain<T: Idx> {
+pub(crate) trait TaintDomain<T: LocalIndex> {
     fn propagate(&mut self, old: T, new: T);
-    fn get_taint(&self, elem: T) -> bool;
-    fn set_taint(&mut self, ix: T, value: bool);
+    fn get_taint(&self, elem: T) -> Labels;
+    fn set_taint(&mut self, ix: T, value: Labels);
 }
 
-impl<T: Idx> TaintDomain<T> for BitSet<T> {
+impl<T: LocalIndex> TaintState<T> {
+    /// A state in which all of the `len` locals are clean.
+    pub(crate) fn new_empty(len: usize) -> Self {
+        TaintState {
+            len,
+            locals: Locals::Sparse(vec![]),
+            fields: vec![],
+        }
+    }
+
+    fn get(&self, ix: T) -> Taint {
+        match &self.locals {
+            Locals::Sparse(locals) => locals
+                .binary_search_by_key(&ix.index(), |(local, _)| local.index())
+                .map_or_else(|_| Taint::default(), |found| locals[found].1),
+            Locals::Dense(locals) => locals[ix.index()],
+        }
+    }
+
+    /// Changes what `ix` carries with `change`, switching to an entry for every local
+    /// once listing the locals which carry something would take more room.
+    fn update(&mut self, ix: T, change: impl FnOnce(&mut Taint)) {
+        let locals = match &mut self.locals {
+            Locals::Dense(locals) => return change(&mut locals[ix.index()]),
+            Locals::Sparse(locals) => locals,
+        };
+        match locals.binary_search_by_key(&ix.index(), |(local, _)| local.index()) {
+            Ok(found) => {
+                change(&mut locals[found].1);
+                if locals[found].1.is_clean() {
+                    locals.remove(found);
+                }
+            }
+            Err(at) => {
+                let mut taint = Taint::default();
+                change(&mut taint);
+                if taint.is_clean() {
+                    return;
+                }
+                let sparse = (locals.len() + 1) * std::mem::size_of::<(T, Taint)>();
+                if sparse <= self.len * std::mem::size_of::<Taint>() {
+                    locals.insert(at, (ix, taint));
+                    return;
+                }
+                let mut dense = vec![Taint::default(); self.len];
+                for &(local, taint) in locals.iter() {
+                    dense[local.index()] = taint;
+                }
+                dense[ix.index()] = taint;
+                self.locals = Locals::Dense(dense);
+            }
+        }
+    }
+
+    /// The locals which carry something, in order.
+    fn tainted(&self) -> impl Iterator<Item = (T, Taint)> + '_ {
+        let (sparse, dense) = match &self.locals {
+            Locals::Sparse(locals) => (Some(locals.iter().copied()), None),
+            Locals::Dense(locals) => (None, Some(locals.iter().enumerate())),
+        };
+        let dense = dense
+            .into_iter()
+            .flatten()
+            .filter(|(_, taint)| !taint.is_clean())
+            .map(|(local, &taint)| (T::new(local), taint));
+        sparse.into_iter().flatten().chain(dense)
+    }
+
+    /// The labels of `ix`, restricted to those the keys of the map it holds may carry.
+    fn keys(&self, ix: T) -> Labels {
+        let taint = self.get(ix);
+        taint.labels.intersection(taint.keys)
+    }
+
+    /// The labels of `ix`, restricted to those the values of the map it holds may carry.
+    fn values(&self, ix: T) -> Labels {
+        let taint = self.get(ix);
+        taint.labels.intersection(taint.values)
+    }
+
+    /// Puts an entry whose key carries `key` and whose value carries `value` into the map `ix` holds.
+    fn insert_entry(&mut self, ix: T, key: Labels, value: Labels) {
+        self.forget_fields(ix);
+        self.update(ix, |taint| {
+            taint.labels = taint.labels.union(key).union(value);
+            taint.keys = taint.keys.union(key);
+            taint.values = taint.values.union(value);
+        });
+    }
+
+    /// Gives `ix` a `Result` of `variant`, whose value carries all of the labels of `ix`.
+    fn set_variant(&mut self, ix: T, variant: ResultVariant) {
+        self.update(ix, |taint| match variant {
+            ResultVariant::Ok => (taint.keys, taint.values) = (taint.labels, Labels::EMPTY),
+            ResultVariant::Err => (taint.keys, taint.values) = (Labels::EMPTY, taint.labels),
+        });
+    }
+
+    /// Gives `ix` a pair whose first element carries `first` and whose second carries `second`,
+    /// which are all of the labels of `ix` between them.
+    fn set_pair(&mut self, ix: T, first: Labels, second: Labels) {
+        self.update(ix, |taint| (taint.keys, taint.values) = (first, second));
+    }
+
+    /// Whether the keys and the values of the map `ix` holds, or the variants of its `Result`,
+    /// may each carry any of its labels, like those of a local which holds neither.
+    pub(crate) fn is_unsplit(&self, ix: T) -> bool {
+        let taint = self.get(ix);
+        taint.keys == taint.labels && taint.values == taint.labels
+    }
+
+    /// Gives `ix` the labels `labels`, which its keys and values, or variants, may each carry.
+    pub(crate) fn set_unsplit(&mut self, ix: T, labels: Labels) {
+        self.forget_fields(ix);
+        self.update(ix, |taint| {
+            *taint = Taint {
+                labels,
+                keys: labels,
+                values: labels,
+            }
+        });
+    }
+
+    /// The labels each of the `count` fields of the struct `ix` holds may carry.
+    fn fields(&self, ix: T, count: usize) -> Box<[Labels]> {
+        match self
+            .fields
+            .binary_search_by_key(&ix.index(), |(local, _)| local.index())
+        {
+            Ok(found) => self.fields[found].1.clone(),
+            Err(_) => vec![self.get_taint(ix); count].into_boxed_slice(),
+        }
+    }
+
+    /// The labels the field `field` of the struct `ix` holds may carry.
+    fn field(&self, ix: T, field: usize) -> Labels {
+        match self
+            .fields
+            .binary_search_by_key(&ix.index(), |(local, _)| local.index())
+        {
+            Ok(found) => self.fields[found].1[field],
+            Err(_) => self.get_taint(ix),
+        }
+    }
+
+    /// Gives each field of the struct `ix` holds the labels of `fields`, and `ix` all of them.
+    fn set_fields(&mut self, ix: T, fields: Box<[Labels]>) {
+        let labels = fields
+            .iter()
+            .fold(Labels::EMPTY, |labels, &field| labels.union(field));
+        self.set_taint(ix, labels);
+        if fields.iter().any(|&field| field != labels) {
+            let at = self
+                .fields
+                .binary_search_by_key(&ix.index(), |(local, _)| local.index())
+                .unwrap_err();
+            self.fields.insert(at, (ix, fields));
+        }
+    }
+
+    /// Any field of the struct `ix` holds may carry any of its labels.
+    fn forget_fields(&mut self, ix: T) {
+        if let Ok(found) = self
+            .fields
+            .binary_search_by_key(&ix.index(), |(local, _)| local.index())
+        {
+            self.fields.remove(found);
+        }
+    }
+
+    /// The fields of the structs `self` or `other` give labels of their own, with the labels each of them
+    /// may carry in either.
+    fn joined_fields(&self, other: &Self) -> Vec<(T, Box<[Labels]>)> {
+        let mut locals = self
+            .fields
+            .iter()
+            .chain(&other.fields)
+            .map(|(local, fields)| (*local, fields.len()))
+            .collect::<Vec<_>>();
+        locals.sort_by_key(|(local, _)| local.index());
+        locals.dedup_by_key(|(local, _)| local.index());
+        locals
+            .into_iter()
+            .filter_map(|(local, count)| {
+                let theirs = other.fields(local, count);
+                let mut fields = self.fields(local, count);
+                for (ours, theirs) in fields.iter_mut().zip(theirs.iter()) {
+                    *ours = ours.union(*theirs);
+                }
+                let labels = self.get_taint(local).union(other.get_taint(local));
+                fields
+                    .iter()
+                    .any(|&field| field != labels)
+                    .then_some((local, fields))
+            })
+            .collect()
+    }
+
+    /// The bytes the state takes up on the heap.
+    pub(crate) fn heap_size(&self) -> usize {
+        let locals = match &self.locals {
+            Locals::Sparse(locals) => locals.capacity() * std::mem::size_of::<(T, Taint)>(),
+            Locals::Dense(locals) => locals.len() * std::mem::size_of::<Taint>(),
+        };
+        let fields = self.fields.iter().fold(
+            self.fields.capacity() * std::mem::size_of::<(T, Box<[Labels]>)>(),
+            |size, (_, fields)| size + fields.len() * std::mem::size_of::<Labels>(),
+        );
+        locals + fields
+    }
+
+    /// The first local which carries a label in `self` which it does not in `other`,
+    /// or `None` if `self` is below `other`.
+    pub(crate) fn not_below(&self, other: &Self) -> Option<T> {
+        let fields_below = |&(ix, ref fields): &(T, Box<[Labels]>)| {
+            let (ours, theirs) = (
+                self.fields(ix, fields.len()),
+                other.fields(ix, fields.len()),
+            );
+            ours.iter()
+                .zip(theirs.iter())
+                .all(|(ours, theirs)| ours.is_subset(*theirs))
+        };
+        self.tainted()
+            .find(|(ix, taint)| !taint.is_subset(&other.get(*ix)))
+            .map(|(ix, _)| ix)
+            .or_else(|| {
+                self.fields
+                    .iter()
+                    .chain(&other.fields)
+                    .find(|entry| !fields_below(entry))
+                    .map(|(ix, _)| *ix)
+            })
+    }
+}
+
+impl<T: LocalIndex> TaintDomain<T> for TaintState<T> {
     #[instrument]
     fn propagate(&mut self, old: T, new: T) {
         self.set_taint(new, self.get_taint(old));
     }
 
     #[instrument]
-    fn get_taint(&self, elem: T) -> bool {
-        self.contains(elem)
+    fn get_taint(&self, elem: T) -> Labels {
+        self.get(elem).labels
     }
 
+    /// The labels `taint` adds to `ix` may be carried by the keys of a map as well as by its values,
+    /// and any of them by any field of a struct.
     #[instrument]
-    fn set_taint(&mut self, ix: T, taint: bool) {
-        if taint {
-            self.insert(ix);
-        } else {
-            self.remove(ix);
+    fn set_taint(&mut self, ix: T, taint: Labels) {
+        if !self.fields.is_empty() {
+            self.forget_fields(ix);
         }
+        self.update(ix, |current| {
+            let added = taint.without(current.labels);
+            current.keys = current.keys.union(added);
+            current.values = current.values.union(added);
+            current.labels = taint;
+        });
     }
 }
 
-impl TaintDomain<Local> for PointsAwareTaintDomain<'_, Local> {
-    fn propagate(&mut self, old: Local, new: Local) {
+impl<T: LocalIndex> Join for TaintState<T> {
+    fn join(&mut self, other: &Self) -> bool {
+        let fields = self.joined_fields(other);
+        let mut changed = fields != self.fields;
+        for (ix, theirs) in other.tainted() {
+            self.update(ix, |ours| changed |= ours.join(&theirs));
+        }
+        self.fields = fields;
+        changed
+    }
+}
+
+/// States are equal when the same locals, and the same fields of them, carry the same labels,
+/// however they are represented.
+impl<T: LocalIndex> PartialEq for TaintState<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.tainted().eq(other.tainted()) && self.fields == other.fields
+    }
+}
+
+impl<T: LocalIndex> Eq for TaintState<T> {}
+
+impl<T: LocalIndex> fmt::Debug for TaintState<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.tainted()).finish()
+    }
+}
+
+impl<T: LocalIndex> TaintDomain<T> for PointsAwareTaintDomain<'_, T> {
+    fn propagate(&mut self, old: T, new: T) {
         self.set_taint(new, self.get_taint(old));
     }
 
-    fn get_taint(&self, ix: Local) -> bool {
-        self.state.get_taint(ix)
+    /// A reference carries the labels of everything it (transitively) points to.
+    fn get_taint(&self, ix: T) -> Labels {
+        aliased_taint(self.state, self.map, ix)
     }
 
-    fn set_taint(&mut self, ix: Local, value: bool) {
+    fn set_taint(&mut self, ix: T, value: Labels) {
         let children = self.get_aliases(ix);
 
+        // Through a local which points anywhere, any of them may be the one written to.
+        let weak = children
+            .iter()
+            .any(|child| self.map.anywhere.contains(child));
         for child in children {
-            self.state.set_taint(child, value);
+            if weak {
+                let labels = self.state.get_taint(child).union(value);
+                self.state.set_taint(child, labels);
+            } else {
+                self.state.set_taint(child, value);
+            }
         }
     }
 }
 
-impl PointsAwareTaintDomain<'_, Local> {
-    pub(crate) fn add_ref(&mut self, from: &Place, to: &Place) {
-        let set = self.map.entry(from.local).or_default();
-        set.insert(to.local);
+impl<T: LocalIndex> PointsAwareTaintDomain<'_, T> {
+    pub(crate) fn add_ref(&mut self, from: T, to: T) {
+        self.map.insert(from, to, self.state.len);
     }
 
-    fn get_aliases(&mut self, ix: Local) -> HashSet<Local> {
-        let mut result = HashSet::new();
-        result.insert(ix);
-        let mut previous_size = result.len();
-
-        loop {
-            for (key, set) in self.map.iter() {
-                if result.contains(key) {
-                    for l in set.iter() {
-                        result.insert(*l);
-                    }
-                }
-            }
-
-            let current_size = result.len();
-            if previous_size != current_size {
-                previous_size = current_size;
+    /// Sets the labels of `ix`, and adds them to those of what it points to, which keep their own.
+    /// What `ix` pointed to before may be a value it was made from, which the write must not clean.
+    pub(crate) fn set_taint_keeping_referents(&mut self, ix: T, value: Labels) {
+        for child in self.get_aliases(ix) {
+            if child == ix {
+                self.state.set_taint(child, value);
             } else {
-                break;
+                let labels = self.state.get_taint(child).union(value);
+                self.state.set_taint(child, labels);
             }
         }
+    }
+
+    fn get_aliases(&self, ix: T) -> HashSet<T> {
+        aliases(self.map, ix)
+    }
+
+    /// The labels the keys of the map `ix` holds or points to may carry.
+    pub(crate) fn key_taint(&self, ix: T) -> Labels {
+        self.get_aliases(ix)
+            .into_iter()
+            .fold(Labels::EMPTY, |labels, alias| {
+                labels.union(self.state.keys(alias))
+            })
+    }
+
+    /// The labels the values of the map `ix` holds or points to may carry.
+    pub(crate) fn value_taint(&self, ix: T) -> Labels {
+        self.get_aliases(ix)
+            .into_iter()
+            .fold(Labels::EMPTY, |labels, alias| {
+                labels.union(self.state.values(alias))
+            })
+    }
+
+    /// Puts an entry whose key carries `key` and whose value carries `value` into the map `ix` holds
+    /// or points to.
+    pub(crate) fn insert_entry(&mut self, ix: T, key: Labels, value: Labels) {
+        for alias in self.get_aliases(ix) {
+            self.state.insert_entry(alias, key, value);
+        }
+    }
+
+    /// The map `ix` holds or points to may have been changed in ways its labels do not tell,
+    /// so any of them may be carried by its keys as well as by its values.
+    pub(crate) fn mix_entries(&mut self, ix: T) {
+        for alias in self.get_aliases(ix) {
+            self.state.update(alias, Taint::mix_entries);
+        }
+    }
+
+    /// The labels the value of `variant` of the `Result` `ix` holds or points to may carry,
+    /// like those of `value` in `Ok(value)`.
+    pub(crate) fn variant_taint(&self, ix: T, variant: ResultVariant) -> Labels {
+        match variant {
+            ResultVariant::Ok => self.key_taint(ix),
+            ResultVariant::Err => self.value_taint(ix),
+        }
+    }
+
+    /// `ix` was given a new `Result` of `variant`, whose value carries all of its labels.
+    pub(crate) fn set_variant(&mut self, ix: T, variant: ResultVariant) {
+        self.state.set_variant(ix, variant);
+    }
+
+    /// The labels `element` of the pair `ix` holds or points to may carry, like those of `a` in `(a, b)`.
+    pub(crate) fn element_taint(&self, ix: T, element: PairElement) -> Labels {
+        match element {
+            PairElement::First => self.key_taint(ix),
+            PairElement::Second => self.value_taint(ix),
+        }
+    }
+
+    /// The labels `element` of the pairs the iterator `ix` holds or points to yields may carry.
+    /// Only the locals holding the iterator keep its pairs apart: references to it, and the data it reads,
+    /// get the labels of its pairs as a whole, so their labels only count for either element when none
+    /// of the others has them.
+    pub(crate) fn iterated_element_taint(&self, ix: T, element: PairElement) -> Labels {
+        let (split, whole): (Vec<_>, Vec<_>) = self
+            .get_aliases(ix)
+            .into_iter()
+            .partition(|&alias| !self.state.is_unsplit(alias));
+        let kept = split.iter().fold(Labels::EMPTY, |labels, &alias| {
+            labels.union(self.state.get_taint(alias))
+        });
+        let parts = split.iter().fold(Labels::EMPTY, |labels, &alias| {
+            labels.union(match element {
+                PairElement::First => self.state.keys(alias),
+                PairElement::Second => self.state.values(alias),
+            })
+        });
+        whole.iter().fold(parts, |labels, &alias| {
+            labels.union(self.state.get_taint(alias).without(kept))
+        })
+    }
+
+    /// `ix` was given a new pair, whose first element carries `first` and whose second carries `second`.
+    pub(crate) fn set_pair(&mut self, ix: T, first: Labels, second: Labels) {
+        self.state.set_pair(ix, first, second);
+    }
+
+    /// `ix` was given a new value, whose labels may be carried by keys as well as by values.
+    pub(crate) fn forget_entries(&mut self, ix: T) {
+        self.state.update(ix, |taint| {
+            taint.keys = taint.labels;
+            taint.values = taint.labels;
+        });
+    }
+
+    /// The labels the field `field` of the struct `ix` holds may carry, along with those of what it points to.
+    pub(crate) fn field_taint(&self, ix: T, field: usize) -> Labels {
+        self.get_aliases(ix)
+            .into_iter()
+            .filter(|&alias| alias != ix)
+            .fold(self.state.field(ix, field), |labels, alias| {
+                labels.union(self.state.get_taint(alias))
+            })
+    }
+
+    /// The labels each of the `count` fields of the struct `ix` holds may carry, without what it points to.
+    pub(crate) fn fields(&self, ix: T, count: usize) -> Box<[Labels]> {
+        self.state.fields(ix, count)
+    }
+
+    /// `ix` was given a struct whose fields carry `fields`.
+    pub(crate) fn set_fields(&mut self, ix: T, fields: Box<[Labels]>) {
+        self.state.set_fields(ix, fields);
+    }
+
+    /// `to` holds the map `from` held, along with what its keys and values carry.
+    pub(crate) fn move_entries(&mut self, from: T, to: T) {
+        let (keys, values) = (self.key_taint(from), self.value_taint(from));
+        self.state.update(to, |taint| {
+            taint.keys = keys;
+            taint.values = values;
+        });
+    }
+}
+
+/// The labels of `ix` in `state`, including the labels of everything it (transitively) points to.
+pub(crate) fn aliased_taint<T: LocalIndex>(
+    state: &TaintState<T>,
+    map: &PointsMap<T>,
+    ix: T,
+) -> Labels {
+    aliases(map, ix)
+        .into_iter()
+        .fold(Labels::EMPTY, |labels, alias| {
+            labels.union(state.get_taint(alias))
+        })
+}
 
-        result
+/// `ix` and everything it (transitively) points to.
+/// References may form cycles, like those of self-referential structs behind an `Rc`,
+/// so each local is only followed once.
+pub(crate) fn aliases<T: LocalIndex>(map: &PointsMap<T>, ix: T) -> HashSet<T> {
+    let mut result = HashSet::new();
+    result.insert(ix);
+    let mut pending = vec![ix];
+    while let Some(local) = pending.pop() {
+        for &pointee in map.referents(local).into_iter().flatten() {
+            if result.insert(pointee) {
+                pending.push(pointee);
+            }
+        }
     }
+    result
 }
 
 #[cfg(test)]
 mod tests {
-    use rustc_middle::mir::Local;
-
     use super::*;
+    use crate::analysis::labels::{Label, TAINT};
+
+    impl LocalIndex for usize {
+        fn new(index: usize) -> Self {
+            index
+        }
 
-    const ONE: Local = Local::from_u32(1);
-    const TWO: Local = Local::from_u32(2);
-    const THREE: Local = Local::from_u32(3);
+        fn index(self) -> usize {
+            self
+        }
+    }
+
+    const ZERO: usize = 0;
+    const ONE: usize = 1;
+    const TWO: usize = 2;
+    const THREE: usize = 3;
 
     #[test]
     fn propagate() {
-        let mut set: BitSet<Local> = BitSet::new_empty(4);
+        let mut set: TaintState<usize> = TaintState::new_empty(4);
 
         // Taint the first element
-        set.set_taint(ONE, true);
+        set.set_taint(ONE, TAINT.into());
 
         // Propagate the taint through the domain
 
@@ -119,9 +693,192 @@ mod tests {
         set.propagate(THREE, ONE);
 
         // TWO should be tainted.
-        assert!(set.get_taint(TWO));
+        assert!(set.get_taint(TWO).contains(TAINT));
 
         // One should not be tainted.
-        assert!(!set.get_taint(ONE));
+        assert!(set.get_taint(ONE).is_empty());
+    }
+
+    #[test]
+    fn references_which_point_to_each_other_share_their_labels() {
+        let mut state: TaintState<usize> = TaintState::new_empty(5);
+        let mut map = PointsMap::default();
+        let mut domain = PointsAwareTaintDomain {
+            state: &mut state,
+            map: &mut map,
+        };
+        let four = 4;
+
+        // `_1 -> _2 -> _3 -> _1`, and `_4` points to itself.
+        domain.add_ref(ONE, TWO);
+        domain.add_ref(TWO, THREE);
+        domain.add_ref(THREE, ONE);
+        domain.add_ref(four, four);
+        assert_eq!(aliases(domain.map, TWO), HashSet::from([ONE, TWO, THREE]));
+        assert_eq!(aliases(domain.map, four), HashSet::from([four]));
+
+        domain.set_taint(THREE, TAINT.into());
+        for local in [ONE, TWO, THREE] {
+            assert!(domain.state.get_taint(local).contains(TAINT));
+        }
+        assert!(domain.get_taint(ZERO).is_empty());
+        assert!(domain.get_taint(four).is_empty());
+
+        domain.propagate(ONE, four);
+        assert!(domain.get_taint(four).contains(TAINT));
+
+        // Adding a reference already in the cycle changes nothing.
+        domain.add_ref(TWO, THREE);
+        assert_eq!(domain.map.referents(TWO), Some(&HashSet::from([THREE])));
+    }
+
+    #[test]
+    fn locals_pointing_to_too_many_others_point_anywhere() {
+        let mut state: TaintState<usize> = TaintState::new_empty(5);
+        let mut map = PointsMap::new(2);
+        let mut domain = PointsAwareTaintDomain {
+            state: &mut state,
+            map: &mut map,
+        };
+        let four = 4;
+        domain.set_taint(four, TAINT.into());
+
+        domain.add_ref(ZERO, ONE);
+        domain.add_ref(ZERO, TWO);
+        assert_eq!(domain.map.largest(), (2, false));
+        assert!(domain.get_taint(ZERO).is_empty());
+
+        // A third referent is one too many, so `_0` may now point to `_4` as well.
+        domain.add_ref(ZERO, THREE);
+        assert_eq!(domain.map.largest(), (5, true));
+        assert!(domain.get_taint(ZERO).contains(TAINT));
+
+        // Writing through it adds labels to every local, and clears none.
+        let other = Label::new(TAINT.index() + 1);
+        domain.set_taint(ZERO, other.into());
+        assert!(domain.get_taint(ONE).contains(other));
+        assert!(domain.get_taint(four).contains(TAINT));
+    }
+
+    #[test]
+    fn keys_and_values_carry_their_own_labels() {
+        let mut state: TaintState<usize> = TaintState::new_empty(4);
+        let mut map = PointsMap::default();
+        let mut domain = PointsAwareTaintDomain {
+            state: &mut state,
+            map: &mut map,
+        };
+        let (key, value) = (Label::new(1), Label::new(2));
+
+        // `_2 = &mut _1`, then `_2.insert(key, value)`.
+        domain.add_ref(TWO, ONE);
+        domain.insert_entry(TWO, key.into(), value.into());
+        assert_eq!(domain.key_taint(TWO), key.into());
+        assert_eq!(domain.value_taint(ONE), value.into());
+
+        // Labels a map gets otherwise may be in either.
+        domain.set_taint(ONE, domain.get_taint(ONE).union(TAINT.into()));
+        assert!(domain.key_taint(ONE).contains(TAINT));
+        assert!(domain.value_taint(ONE).contains(TAINT));
+        assert!(!domain.value_taint(ONE).contains(key));
+    }
+
+    #[test]
+    fn ok_and_err_values_carry_their_own_labels() {
+        let mut state: TaintState<usize> = TaintState::new_empty(4);
+        let mut map = PointsMap::default();
+        let mut domain = PointsAwareTaintDomain {
+            state: &mut state,
+            map: &mut map,
+        };
+
+        // `_1 = Err(source())`, then `_2 = &_1`.
+        domain.set_taint(ONE, TAINT.into());
+        domain.set_variant(ONE, ResultVariant::Err);
+        domain.add_ref(TWO, ONE);
+        assert!(domain.variant_taint(TWO, ResultVariant::Ok).is_empty());
+        assert!(domain
+            .variant_taint(TWO, ResultVariant::Err)
+            .contains(TAINT));
+
+        // Joining with an `Ok(source())` taints both.
+        let mut ok = TaintState::new_empty(4);
+        ok.set_taint(ONE, TAINT.into());
+        ok.set_variant(ONE, ResultVariant::Ok);
+        assert!(state.join(&ok));
+        assert!(state.keys(ONE).contains(TAINT));
+        assert!(state.values(ONE).contains(TAINT));
+    }
+
+    #[test]
+    fn fields_carry_their_own_labels_until_the_struct_is_written() {
+        let mut state: TaintState<usize> = TaintState::new_empty(4);
+        let mut map = PointsMap::default();
+        let mut domain = PointsAwareTaintDomain {
+            state: &mut state,
+            map: &mut map,
+        };
+
+        // `_1 = Request { body: source(), path: String::new() }`, then `_2 = move (_1.0)`.
+        domain.set_fields(ONE, vec![TAINT.into(), Labels::EMPTY].into_boxed_slice());
+        assert!(domain.get_taint(ONE).contains(TAINT));
+        assert!(domain.field_taint(ONE, 1).is_empty());
+        domain.set_fields(ONE, vec![Labels::EMPTY; 2].into_boxed_slice());
+        assert!(domain.get_taint(ONE).is_empty());
+
+        // Joining with a state where `_1` is tainted as a whole taints both fields, and is not equal to it.
+        domain.set_fields(ONE, vec![TAINT.into(), Labels::EMPTY].into_boxed_slice());
+        let mut whole = TaintState::new_empty(4);
+        whole.set_taint(ONE, TAINT.into());
+        assert!(state != whole);
+        assert!(state.not_below(&whole).is_none());
+        assert_eq!(whole.not_below(&state), Some(ONE));
+        assert!(state.join(&whole));
+        assert!(state.field(ONE, 1).contains(TAINT));
+        assert!(state == whole);
+
+        // Writing the struct as a whole forgets its fields.
+        state.set_fields(ONE, vec![TAINT.into(), Labels::EMPTY].into_boxed_slice());
+        state.set_taint(ONE, TAINT.into());
+        assert!(state.field(ONE, 1).contains(TAINT));
+    }
+
+    #[test]
+    fn states_switch_to_an_entry_for_every_local_and_stay_equal() {
+        let mut sparse: TaintState<usize> = TaintState::new_empty(4);
+        sparse.set_taint(ONE, TAINT.into());
+        assert!(matches!(sparse.locals, Locals::Sparse(_)));
+
+        let mut dense = sparse.clone();
+        dense.set_taint(TWO, TAINT.into());
+        dense.set_taint(THREE, TAINT.into());
+        assert!(matches!(dense.locals, Locals::Sparse(_)));
+        // Listing every local takes more room than an entry for each.
+        dense.set_taint(ZERO, TAINT.into());
+        assert!(matches!(dense.locals, Locals::Dense(_)));
+        assert!(dense.get_taint(THREE).contains(TAINT));
+        assert_eq!(sparse.not_below(&dense), None);
+        assert_eq!(dense.not_below(&sparse), Some(ZERO));
+
+        // Joining into the sparse state switches it too.
+        assert!(sparse.join(&dense));
+        assert!(!sparse.join(&dense));
+        assert!(matches!(sparse.locals, Locals::Dense(_)));
+        assert_eq!(sparse, dense);
+    }
+
+    #[test]
+    fn only_locals_carrying_something_are_listed() {
+        let mut state: TaintState<usize> = TaintState::new_empty(4);
+        let mut map = PointsMap::default();
+        let mut domain = PointsAwareTaintDomain {
+            state: &mut state,
+            map: &mut map,
+        };
+        domain.set_taint(TWO, TAINT.into());
+        domain.set_taint(TWO, Labels::EMPTY);
+        domain.forget_entries(TWO);
+        assert_eq!(state, TaintState::new_empty(4));
+        assert_eq!(format!("{:?}", state), "{}");
     }
 }
@@ -0,0 +1,171 @@
+//! Transfer summaries of whole blocks, for functions with more statements than `--taint-block-summaries`.
+//!
+//! Machine-generated functions can have tens of thousands of statements, which the transfer function
+//! would lower from MIR again on every pass of the fixpoint. Their blocks which only move labels between
+//! locals, like `_5 = Add(_3, _4)` or `_6 = move _5`, are instead summarized once, as the locals each local
+//! they write takes its labels from at the start of the block, and the summary is applied in one step.
+//!
+//! A summary is only exact for locals which point to nothing and whose labels are not split between
+//! the keys and values of a map, or the variants of a `Result`. On a pass where the block involves
+//! other locals, it is visited statement by statement instead.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::compiler::Local;
+
+use super::{
+    ir::{Operand, Rvalue, Statement},
+    labels::Labels,
+    taint_domain::{PointsMap, TaintDomain, TaintState},
+};
+
+/// What a block does to the labels of the locals it writes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BlockTransfer {
+    /// The locals the block writes, and the locals whose labels at the start of the block they carry.
+    writes: Vec<(Local, BTreeSet<Local>)>,
+    /// Every local the block reads or writes.
+    locals: BTreeSet<Local>,
+}
+
+impl BlockTransfer {
+    /// The summary of `statements`, applied in order, or `None` if one of them does more than assign
+    /// the labels of other locals, like a call or taking a reference.
+    pub(crate) fn new(statements: &[Statement]) -> Option<BlockTransfer> {
+        let mut writes: HashMap<Local, BTreeSet<Local>> = HashMap::new();
+        let mut locals = BTreeSet::new();
+        // The locals whose labels at the start of the block `local` carries so far.
+        let sources = |writes: &HashMap<Local, BTreeSet<Local>>, local: Local| {
+            writes
+                .get(&local)
+                .cloned()
+                .unwrap_or_else(|| BTreeSet::from([local]))
+        };
+        for statement in statements {
+            let Statement::Assign(place, rvalue) = statement else {
+                return None;
+            };
+            locals.insert(*place);
+            let written = match rvalue {
+                Rvalue::Clean => BTreeSet::new(),
+                Rvalue::Derived(operands) => operands
+                    .iter()
+                    .filter_map(|operand| match operand {
+                        Operand::Local(local) => Some(*local),
+                        Operand::Const => None,
+                    })
+                    .flat_map(|local| {
+                        locals.insert(local);
+                        sources(&writes, local)
+                    })
+                    .collect(),
+                Rvalue::Unknown => continue,
+                Rvalue::Ref(_) | Rvalue::Labels(_) => return None,
+            };
+            writes.insert(*place, written);
+        }
+        let mut writes = writes.into_iter().collect::<Vec<_>>();
+        writes.sort_by_key(|(local, _)| *local);
+        Some(BlockTransfer { writes, locals })
+    }
+
+    /// Applies the block to `state`, unless one of its locals points to something in `points`,
+    /// or has labels split between parts. Returns whether it was applied.
+    pub(crate) fn apply(&self, state: &mut TaintState<Local>, points: &PointsMap<Local>) -> bool {
+        let exact = self
+            .locals
+            .iter()
+            .all(|&local| points.referents(local).is_none() && state.is_unsplit(local));
+        if !exact {
+            return false;
+        }
+        let written = self
+            .writes
+            .iter()
+            .map(|(local, sources)| {
+                let labels = sources.iter().fold(Labels::EMPTY, |labels, &source| {
+                    labels.union(state.get_taint(source))
+                });
+                (*local, labels)
+            })
+            .collect::<Vec<_>>();
+        for (local, labels) in written {
+            state.set_unsplit(local, labels);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{
+        ir::builder::{local, op},
+        labels::{Label, TAINT},
+        taint_domain::PointsAwareTaintDomain,
+    };
+
+    /// `state` after applying `statements` one by one, the way the transfer function does,
+    /// which gives the labels of a local written as a whole to its keys and values alike.
+    fn run(
+        state: &TaintState<Local>,
+        points: &mut PointsMap<Local>,
+        statements: &[Statement],
+    ) -> TaintState<Local> {
+        let mut state = state.clone();
+        let mut domain = PointsAwareTaintDomain {
+            state: &mut state,
+            map: points,
+        };
+        for statement in statements {
+            statement.apply(&mut domain);
+            if let Statement::Assign(place, _) = statement {
+                domain.forget_entries(*place);
+            }
+        }
+        state
+    }
+
+    #[test]
+    fn a_summary_applies_like_its_statements() {
+        let mut state = TaintState::new_empty(7);
+        state.set_taint(local(1), TAINT.into());
+        state.set_taint(local(2), Label::new(1).into());
+        let statements = [
+            Statement::Assign(local(3), Rvalue::Derived(vec![op(1), Operand::Const])),
+            Statement::Assign(local(1), Rvalue::Clean),
+            Statement::Assign(local(4), Rvalue::Derived(vec![op(3), op(2)])),
+            Statement::Assign(local(2), Rvalue::Derived(vec![op(1)])),
+            // A write to part of a local keeps what it had.
+            Statement::Assign(local(5), Rvalue::Derived(vec![op(2), op(5)])),
+            Statement::Assign(local(6), Rvalue::Unknown),
+            Statement::Assign(local(3), Rvalue::Derived(vec![op(3), op(4)])),
+        ];
+        let transfer = BlockTransfer::new(&statements).unwrap();
+        let mut points = PointsMap::default();
+        let mut summarized = state.clone();
+
+        assert!(transfer.apply(&mut summarized, &points));
+        assert_eq!(summarized, run(&state, &mut points, &statements));
+        assert!(summarized.get_taint(local(4)).contains(TAINT));
+        assert!(summarized.get_taint(local(2)).is_empty());
+    }
+
+    #[test]
+    fn blocks_with_locals_pointing_to_others_are_not_summarized() {
+        let reference = [Statement::Assign(local(1), Rvalue::Ref(local(2)))];
+        assert_eq!(BlockTransfer::new(&reference), None);
+
+        let statements = [Statement::Assign(local(2), Rvalue::Derived(vec![op(1)]))];
+        let transfer = BlockTransfer::new(&statements).unwrap();
+        let mut state = TaintState::new_empty(4);
+        let mut points = PointsMap::default();
+        PointsAwareTaintDomain {
+            state: &mut state,
+            map: &mut points,
+        }
+        .add_ref(local(1), local(3));
+
+        assert!(!transfer.apply(&mut state, &points));
+    }
+}
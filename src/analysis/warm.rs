@@ -0,0 +1,300 @@
+//! Carrying summaries over from one run to the next, for tools which run the analysis in process
+//! with a [`WarmStart`].
+//!
+//! Summaries belong to the compilation session they were computed in, so a context is carried over
+//! by the stable hash of the path of its function and the labels of its arguments, and only for
+//! functions without generic arguments, whose instantiation has no such hash.
+//! Along with its summary goes the [`Stamp`] of every function its analysis went through,
+//! and the function is analyzed again once any of them changed, like the file it is defined in.
+//!
+//! Reusing a summary skips the analysis of the function and of its callees altogether,
+//! so only contexts whose analysis did nothing else than computing summaries are carried over:
+//! no finding was reported, no callback registered or looked up and no recursive call cut off,
+//! and the run was not cancelled before it was done, which would have cut off the analysis of callees.
+//! Everything is dropped once the options, the config, the imported summaries or the annotations
+//! change, since they decide what any function does, and number the labels. A change to the config which
+//! only touches the rules of some functions, like their propagation rules, only drops the contexts whose
+//! analysis called one of them, so that a tool reloading the config as it is edited keeps the others.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    compiler::{self, DefPathHash, Stamp, TyCtxt},
+    config::Config,
+    error::Error,
+    eval::attributes::AttrInfo,
+    options::Options,
+    summaries::Summaries,
+    warm_start::WarmStart,
+};
+
+use super::taint_analysis::{ContextKey, InitSet, Summary};
+
+/// What a run leaves for the next one.
+struct Left {
+    inputs: Inputs,
+    contexts: HashMap<(DefPathHash, InitSet), Carried>,
+}
+
+/// Everything besides the code which decides what the summaries are.
+#[derive(PartialEq)]
+struct Inputs {
+    /// The options, but for the handles tools keep to control the run.
+    options: String,
+    config: Config,
+    /// The summaries imported besides the propagation rules of the config.
+    summaries: Vec<Result<Summaries, Error>>,
+    /// Every annotation, by the path of what it is on.
+    annotations: Vec<String>,
+}
+
+/// A summary computed by a previous run.
+struct Carried {
+    summary: Summary,
+    stamps: HashSet<Stamp>,
+    callees: HashSet<String>,
+}
+
+/// What the analysis of a context went through, in itself and in its callees.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Trace {
+    stamps: HashSet<Stamp>,
+    /// The paths of the functions called, which the config may give rules to.
+    callees: HashSet<String>,
+    /// Whether the analysis did something which reusing its summary would skip, like reporting a finding.
+    side_effects: bool,
+}
+
+impl Trace {
+    pub(crate) fn merge(&mut self, other: &Trace) {
+        self.stamps.extend(other.stamps.iter().cloned());
+        self.callees.extend(other.callees.iter().cloned());
+        self.side_effects |= other.side_effects;
+    }
+
+    /// Notes that the analysis called the function at `path`.
+    pub(crate) fn called(&mut self, path: String) {
+        self.callees.insert(path);
+    }
+
+    /// Notes that the analysis did something which reusing its summary would skip.
+    pub(crate) fn side_effect(&mut self) {
+        self.side_effects = true;
+    }
+
+    pub(crate) fn has_side_effects(&self) -> bool {
+        self.side_effects
+    }
+}
+
+/// The summaries carried over from the previous run, and what this run will leave.
+#[derive(Default)]
+pub(crate) struct Warm<'tcx> {
+    /// Where to leave what this run computed, unless it starts from scratch.
+    warm_start: Option<WarmStart>,
+    left: Option<Left>,
+    traces: HashMap<ContextKey<'tcx>, Trace>,
+}
+
+impl<'tcx> Warm<'tcx> {
+    /// Takes what the previous run left in the warm start of `options`, if it ran with the same inputs.
+    /// Runs which record facts for `--taint-results`, `--taint-slice` or `--taint-reproducers` start from scratch,
+    /// as do those without a warm start.
+    pub(crate) fn start(
+        tcx: TyCtxt<'tcx>,
+        info: &AttrInfo,
+        options: &Options,
+        config: &Config,
+        summaries: &[Result<Summaries, Error>],
+    ) -> Warm<'tcx> {
+        let Some(warm_start) = &options.warm_start else {
+            return Warm::default();
+        };
+        if options.records_facts() {
+            return Warm::default();
+        }
+        let inputs = Inputs {
+            options: format!(
+                "{:?}",
+                Options {
+                    cancellation: Default::default(),
+                    warm_start: None,
+                    ..options.clone()
+                }
+            ),
+            config: config.clone(),
+            summaries: summaries.to_vec(),
+            annotations: annotations(tcx, info),
+        };
+        let contexts = match warm_start.take::<Left>() {
+            Some(left) if left.inputs == inputs => left.contexts,
+            Some(left) => carried_over(info, left, &inputs),
+            None => HashMap::new(),
+        };
+        Warm {
+            warm_start: Some(warm_start.clone()),
+            left: Some(Left { inputs, contexts }),
+            traces: HashMap::new(),
+        }
+    }
+
+    /// Whether this run carries summaries over to the next, so that traces are recorded.
+    pub(crate) fn is_carrying(&self) -> bool {
+        self.left.is_some()
+    }
+
+    /// The trace of the analysis of `id` before it looked at anything else.
+    pub(crate) fn trace(&self, tcx: TyCtxt<'tcx>, id: compiler::DefId) -> Trace {
+        let mut trace = Trace::default();
+        if self.warm_start.is_some() {
+            trace.stamps.insert(compiler::stamp(tcx, id));
+        }
+        trace
+    }
+
+    /// The summary of the context `key` the previous run left, with what its analysis went through,
+    /// if none of that changed since. Its trace is that of a context analyzed in this run from then on.
+    pub(crate) fn carried(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        key: &ContextKey<'tcx>,
+    ) -> Option<(Summary, Trace)> {
+        let left = self.left.as_mut()?;
+        let (id, args, init) = key;
+        if !args.is_empty() {
+            return None;
+        }
+        let stable = (tcx.def_path_hash(*id), init.clone());
+        let carried = left.contexts.get(&stable)?;
+        if !carried
+            .stamps
+            .iter()
+            .all(|stamp| compiler::is_current(tcx, stamp))
+        {
+            left.contexts.remove(&stable);
+            return None;
+        }
+        let trace = Trace {
+            stamps: carried.stamps.clone(),
+            callees: carried.callees.clone(),
+            side_effects: false,
+        };
+        self.traces.insert(key.clone(), trace.clone());
+        Some((carried.summary.clone(), trace))
+    }
+
+    /// Records what the analysis of `key` went through, once it is done with `summary`,
+    /// and carries the summary over if its analysis can be skipped in the next run.
+    pub(crate) fn finished(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        key: &ContextKey<'tcx>,
+        trace: &Trace,
+        summary: Option<&Summary>,
+    ) {
+        let Some(left) = &mut self.left else {
+            return;
+        };
+        self.traces.insert(key.clone(), trace.clone());
+        let (id, args, init) = key;
+        if let (Some(summary), true) = (summary, args.is_empty() && !trace.side_effects) {
+            let carried = Carried {
+                summary: summary.clone(),
+                stamps: trace.stamps.clone(),
+                callees: trace.callees.clone(),
+            };
+            left.contexts
+                .insert((tcx.def_path_hash(*id), init.clone()), carried);
+        }
+    }
+
+    /// What the analysis of `key` went through, if it was analyzed in this run.
+    pub(crate) fn traced(&self, key: &ContextKey<'tcx>) -> Option<&Trace> {
+        self.traces.get(key)
+    }
+
+    /// Leaves the summaries this run carried over for the next one, along with those left by
+    /// the previous run which this one did not need, since they may still be up to date.
+    pub(crate) fn leave(&mut self) {
+        if let (Some(warm_start), Some(left)) = (self.warm_start.take(), self.left.take()) {
+            warm_start.leave(left);
+        }
+    }
+}
+
+/// The contexts `left` carried over which are still up to date with `inputs`: those whose analysis called none
+/// of the functions whose rules changed, if only the config changed and only in these rules.
+fn carried_over(
+    info: &AttrInfo,
+    left: Left,
+    inputs: &Inputs,
+) -> HashMap<(DefPathHash, InitSet), Carried> {
+    let same_code = left.inputs.options == inputs.options
+        && left.inputs.summaries == inputs.summaries
+        && left.inputs.annotations == inputs.annotations;
+    let Some(changed) = left
+        .inputs
+        .config
+        .changed_functions(&inputs.config)
+        .filter(|_| same_code)
+    else {
+        return HashMap::new();
+    };
+    let changed = changed
+        .iter()
+        .map(|path| info.canonical_path(path))
+        .collect::<HashSet<_>>();
+    left.contexts
+        .into_iter()
+        .filter(|(_, carried)| {
+            carried
+                .callees
+                .iter()
+                .all(|callee| !changed.contains(callee.as_str()))
+        })
+        .collect()
+}
+
+/// The annotations of `info`, one per line, in an order which does not depend on the session.
+fn annotations(tcx: TyCtxt<'_>, info: &AttrInfo) -> Vec<String> {
+    let path = |id| tcx.def_path_str(id);
+    let roles = [
+        ("source", &info.sources),
+        ("source field", &info.source_fields),
+        ("sink", &info.sinks),
+        ("gated sink", &info.gated),
+        ("return sink", &info.return_sinks),
+        ("sanitizer", &info.sanitizers),
+        ("storage field", &info.storage_fields),
+        ("boundary", &info.boundaries),
+        ("trusted", &info.trusted),
+    ];
+    let mut lines = vec![];
+    for (role, ids) in roles {
+        lines.extend(
+            ids.iter()
+                .map(|&id| format!("{} `{}` {:?}", role, path(id), info.labeled.get(&id))),
+        );
+    }
+    lines.extend(info.requirements.iter().map(|req| {
+        format!(
+            "`{}` requires `{}` {:?}",
+            path(req.sink),
+            path(req.sanitizer),
+            req.label
+        )
+    }));
+    lines.extend(
+        info.required_taint
+            .iter()
+            .map(|(&id, label)| format!("`{}` requires taint {:?}", path(id), label)),
+    );
+    lines.extend(
+        info.relabels
+            .iter()
+            .map(|(&id, relabel)| format!("`{}` relabels {:?}", path(id), relabel)),
+    );
+    lines.extend(info.names.iter().map(|name| format!("label {:?}", name)));
+    lines.sort();
+    lines
+}
@@ -0,0 +1,201 @@
+//! `taint benchmark [<dir>] [<args>...]` measures the precision and recall of the analysis on programs
+//! whose flows are known, so that a change to how taint propagates can be judged by numbers.
+//!
+//! Programs are laid out like the cases of the Juliet test suite: a function whose name starts with `bad`
+//! passes data from a source to a sink, and one whose name starts with `good` does not.
+//! A `bad` function with a finding is a true positive, and one without a false negative.
+//! A `good` function with a finding is a false positive. Findings in other functions are not counted.
+//! `<dir>` is `tests/benchmark` by default. Flags a program asks for in a `// compile-flags:` line
+//! are passed to the driver along with the remaining arguments, as `taint selftest` does.
+
+use std::{
+    collections::HashSet,
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use taint::{options::Options, report::Report};
+
+use crate::{
+    check::find_programs, compat::EarlyErrorHandler, selftest::compile_flags, text::plural,
+};
+
+/// How the findings of a program compare to its known flows.
+#[derive(Default)]
+struct Score {
+    true_positives: usize,
+    false_positives: usize,
+    false_negatives: usize,
+}
+
+impl Score {
+    fn add(&mut self, other: &Score) {
+        self.true_positives += other.true_positives;
+        self.false_positives += other.false_positives;
+        self.false_negatives += other.false_negatives;
+    }
+
+    /// The share of the flagged functions which have a flow, in percent.
+    fn precision(&self) -> Option<f64> {
+        percent(
+            self.true_positives,
+            self.true_positives + self.false_positives,
+        )
+    }
+
+    /// The share of the functions with a flow which are flagged, in percent.
+    fn recall(&self) -> Option<f64> {
+        percent(
+            self.true_positives,
+            self.true_positives + self.false_negatives,
+        )
+    }
+}
+
+/// Runs the programs of the corpus in `args`, where `driver_args` are the arguments the driver
+/// was started with before `benchmark`.
+pub(crate) fn run(
+    driver_args: Vec<String>,
+    mut args: Vec<String>,
+    handler: &EarlyErrorHandler,
+) -> ! {
+    let dir = match args.first() {
+        Some(first) if !first.starts_with('-') => PathBuf::from(args.remove(0)),
+        _ => PathBuf::from("tests/benchmark"),
+    };
+
+    let mut programs = vec![];
+    if let Err(e) = find_programs(&dir, &mut programs) {
+        eprintln!("error: could not read `{}`: {}", dir.display(), e);
+        process::exit(2);
+    }
+    programs.sort();
+
+    let rows = programs
+        .into_iter()
+        .map(|program| {
+            let score = score(&program, &driver_args, &args, handler);
+            (program, score)
+        })
+        .collect::<Vec<_>>();
+
+    print!("{}", table(&rows));
+    process::exit(if rows.iter().all(|(_, score)| score.is_some()) {
+        0
+    } else {
+        1
+    })
+}
+
+/// Analyzes `program` with the flags it asks for, and scores its findings against its `bad`
+/// and `good` functions. `None` if it did not compile.
+fn score(
+    program: &Path,
+    driver_args: &[String],
+    args: &[String],
+    handler: &EarlyErrorHandler,
+) -> Option<Score> {
+    let source = fs::read_to_string(program).ok()?;
+    let all_args = driver_args
+        .iter()
+        .cloned()
+        .chain(["--edition=2018".to_owned(), program.display().to_string()])
+        .chain(compile_flags(&source))
+        .chain(args.iter().cloned());
+    let (mut options, rustc_args) = match Options::from_args(all_args) {
+        Ok(split) => split,
+        Err(error) => handler.early_error(error.to_string()),
+    };
+    let path = env::temp_dir().join(format!("taint-benchmark-{}.json", process::id()));
+    let _ = fs::remove_file(&path);
+    options.findings = Some(path.clone());
+
+    crate::run_compiler(rustc_args, handler, options, false);
+    let report = Report::read(&path).ok()?;
+    let _ = fs::remove_file(&path);
+
+    // Findings are in functions named by their path, like `cases::bad`.
+    let flagged = report
+        .findings
+        .iter()
+        .map(|finding| finding.function.rsplit("::").next().unwrap_or_default())
+        .collect::<HashSet<_>>();
+    let mut score = Score::default();
+    for function in labeled_functions(&source) {
+        match (function.starts_with("bad"), flagged.contains(function)) {
+            (true, true) => score.true_positives += 1,
+            (true, false) => score.false_negatives += 1,
+            (false, true) => score.false_positives += 1,
+            (false, false) => {}
+        }
+    }
+    Some(score)
+}
+
+/// The names of the functions of `source` which start with `bad` or `good`.
+fn labeled_functions(source: &str) -> Vec<&str> {
+    source
+        .split("fn ")
+        .skip(1)
+        .filter_map(|rest| {
+            let end = rest.find(|c: char| !c.is_alphanumeric() && c != '_')?;
+            Some(&rest[..end])
+        })
+        .filter(|name| name.starts_with("bad") || name.starts_with("good"))
+        .collect()
+}
+
+fn percent(part: usize, whole: usize) -> Option<f64> {
+    (whole > 0).then_some(100.0 * part as f64 / whole as f64)
+}
+
+/// A row per program, followed by the precision and recall over the whole corpus.
+fn table(rows: &[(PathBuf, Option<Score>)]) -> String {
+    let width = rows
+        .iter()
+        .map(|(program, _)| program.display().to_string().len())
+        .max()
+        .unwrap_or(0)
+        .max("program".len());
+
+    let mut table = format!(
+        "{:<width$}  {:<3}  {:<3}  {}\n",
+        "program",
+        "tp",
+        "fp",
+        "fn",
+        width = width
+    );
+    let mut total = Score::default();
+    for (program, score) in rows {
+        let Some(score) = score else {
+            table.push_str(&format!(
+                "{:<width$}  did not compile\n",
+                program.display(),
+                width = width
+            ));
+            continue;
+        };
+        table.push_str(&format!(
+            "{:<width$}  {:<3}  {:<3}  {}\n",
+            program.display(),
+            score.true_positives,
+            score.false_positives,
+            score.false_negatives,
+            width = width
+        ));
+        total.add(score);
+    }
+
+    let shown = |value: Option<f64>| value.map_or_else(|| "-".to_owned(), |v| format!("{:.1}%", v));
+    table.push_str(&format!(
+        "benchmark: precision {}, recall {} ({}, {}, {})\n",
+        shown(total.precision()),
+        shown(total.recall()),
+        plural(total.true_positives, "true positive"),
+        plural(total.false_positives, "false positive"),
+        plural(total.false_negatives, "false negative")
+    ));
+    table
+}
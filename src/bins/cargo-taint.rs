@@ -0,0 +1,239 @@
+//! `cargo taint` runs the taint analysis on every crate of a cargo workspace.
+//!
+//! Usage: `cargo taint [--feature-matrix=<features>;<features>;...] [--taint-include-tests]
+//! [--taint-no-dependency-mir] [--taint-dependency-mir-opt-level=<n>] [<cargo check args>...]`
+//!
+//! Tests are left out unless `--taint-include-tests` is given, in which case the test targets are
+//! checked too, and `#[test]` functions are analyzed as entry points.
+//!
+//! Code behind `#[cfg(feature = ...)]` is only analyzed when the feature is enabled.
+//! With a feature matrix the workspace is checked once per feature set,
+//! and each finding is labeled with the feature sets it appears under.
+//! An empty feature set stands for the default features.
+//!
+//! Each library of the workspace leaves its interface to the crates of the workspace depending on it, like the
+//! binaries of its package: the roles it declares and the summaries of its functions, which their analysis loads.
+//! cargo checks a library before the crates depending on it, so its interface is written by then.
+//!
+//! Dependencies are built with `-Zalways-encode-mir`, so that the analysis can descend into their functions,
+//! and with `-Zmir-opt-level=<n>` too when `--taint-dependency-mir-opt-level=<n>` is given.
+//! `--taint-no-dependency-mir` leaves their flags alone, so that they are not built again for the analysis,
+//! and calls into them follow `--taint-unknown-calls`. The flags are added to `RUSTFLAGS`,
+//! or to `CARGO_ENCODED_RUSTFLAGS` if it is set.
+
+use std::{
+    env,
+    io::{BufRead, BufReader},
+    path::PathBuf,
+    process::{self, Command, Stdio},
+};
+
+use serde_json::Value;
+
+const MATRIX_FLAG: &str = "--feature-matrix=";
+const INCLUDE_TESTS_FLAG: &str = "--taint-include-tests";
+const NO_DEPENDENCY_MIR_FLAG: &str = "--taint-no-dependency-mir";
+const MIR_OPT_LEVEL_FLAG: &str = "--taint-dependency-mir-opt-level=";
+
+/// A diagnostic emitted by the analysis, and the configurations it was emitted under.
+struct Finding {
+    rendered: String,
+    configurations: Vec<usize>,
+}
+
+fn main() {
+    let mut args = env::args().skip(1).peekable();
+    // Cargo passes the name of the subcommand on to us.
+    if args.peek().map(String::as_str) == Some("taint") {
+        args.next();
+    }
+
+    let mut matrix = vec![];
+    let mut cargo_args = vec![];
+    let mut include_tests = false;
+    let mut dependency_mir = true;
+    let mut opt_level = None;
+    for arg in args {
+        if arg == INCLUDE_TESTS_FLAG {
+            include_tests = true;
+            continue;
+        }
+        if arg == NO_DEPENDENCY_MIR_FLAG {
+            dependency_mir = false;
+            continue;
+        }
+        if let Some(level) = arg.strip_prefix(MIR_OPT_LEVEL_FLAG) {
+            opt_level = Some(level.to_owned());
+            continue;
+        }
+        match arg.strip_prefix(MATRIX_FLAG) {
+            Some(sets) => matrix.extend(sets.split(';').map(|set| set.trim().to_owned())),
+            None => cargo_args.push(arg),
+        }
+    }
+    let mut rustflags = vec![];
+    if dependency_mir {
+        rustflags.push("-Zalways-encode-mir".to_owned());
+        rustflags.extend(opt_level.map(|level| format!("-Zmir-opt-level={}", level)));
+    }
+    if include_tests {
+        cargo_args.push("--tests".to_owned());
+    }
+    if matrix.is_empty() {
+        matrix.push(String::new());
+    }
+
+    let mut findings: Vec<Finding> = vec![];
+    let mut failed = false;
+    for (configuration, features) in matrix.iter().enumerate() {
+        let rendered = match check(features, &cargo_args, include_tests, &rustflags) {
+            Ok(rendered) => rendered,
+            Err(message) => {
+                eprintln!("error: {} ({})", message, label(features));
+                failed = true;
+                continue;
+            }
+        };
+
+        for rendered in rendered {
+            match findings.iter_mut().find(|f| f.rendered == rendered) {
+                Some(finding) => finding.configurations.push(configuration),
+                None => findings.push(Finding {
+                    rendered,
+                    configurations: vec![configuration],
+                }),
+            }
+        }
+    }
+
+    for finding in &findings {
+        let labels = finding
+            .configurations
+            .iter()
+            .map(|&c| label(&matrix[c]))
+            .collect::<Vec<_>>();
+        print!("{}", finding.rendered);
+        println!("note: found with {}\n", labels.join(", "));
+    }
+
+    if !findings.is_empty() || failed {
+        process::exit(1);
+    }
+}
+
+/// Checks the workspace with `features` enabled and `rustflags` added to those of every crate,
+/// returning the rendered findings of the analysis.
+fn check(
+    features: &str,
+    cargo_args: &[String],
+    include_tests: bool,
+    rustflags: &[String],
+) -> Result<Vec<String>, String> {
+    let mut cargo = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned()));
+    cargo
+        .arg("check")
+        .arg("--message-format=json")
+        .args(cargo_args)
+        .env("RUSTC_WORKSPACE_WRAPPER", driver_path())
+        .env("TAINT_CRATE_INTERFACES", "yes")
+        .stdout(Stdio::piped());
+    if !features.is_empty() {
+        cargo.arg("--features").arg(features);
+    }
+    if include_tests {
+        cargo.env("TAINT_INCLUDE_TESTS", "yes");
+    }
+    if !rustflags.is_empty() {
+        cargo.env("CARGO_ENCODED_RUSTFLAGS", encoded_rustflags(rustflags));
+    }
+    // The driver can only read metadata of dependencies built by the toolchain it was built with.
+    if let Some(toolchain) = option_env!("RUSTUP_TOOLCHAIN") {
+        cargo.env("RUSTUP_TOOLCHAIN", toolchain);
+    }
+
+    let mut child = cargo
+        .spawn()
+        .map_err(|e| format!("could not run cargo: {}", e))?;
+
+    let mut findings = vec![];
+    let mut other_errors = false;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    for line in BufReader::new(stdout).lines() {
+        let line = line.map_err(|e| format!("could not read cargo output: {}", e))?;
+        let Ok(message) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if message["reason"] != "compiler-message" {
+            continue;
+        }
+
+        let diagnostic = &message["message"];
+        let rendered = diagnostic["rendered"].as_str().unwrap_or_default();
+        let code = diagnostic["code"]["code"].as_str().unwrap_or_default();
+        if is_taint_code(code) {
+            findings.push(rendered.to_owned());
+        } else if diagnostic["message"]
+            .as_str()
+            .map_or(false, |m| m.starts_with("aborting due to"))
+        {
+            // This only counts the errors already reported.
+        } else {
+            // Warnings and errors of the compiler itself are not part of the merged findings.
+            eprint!("{}", rendered);
+            other_errors |= diagnostic["level"] == "error";
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("could not run cargo: {}", e))?;
+    if !status.success() && findings.is_empty() && other_errors {
+        return Err("could not compile the workspace".to_owned());
+    }
+    Ok(findings)
+}
+
+/// The flags of the environment with `extra` after them, as cargo reads them from `CARGO_ENCODED_RUSTFLAGS`,
+/// which takes precedence over `RUSTFLAGS`.
+fn encoded_rustflags(extra: &[String]) -> String {
+    let mut flags = match env::var("CARGO_ENCODED_RUSTFLAGS") {
+        Ok(encoded) => encoded
+            .split('\x1f')
+            .filter(|flag| !flag.is_empty())
+            .map(str::to_owned)
+            .collect(),
+        Err(_) => env::var("RUSTFLAGS")
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_owned)
+            .collect::<Vec<_>>(),
+    };
+    flags.extend(extra.iter().cloned());
+    flags.join("\x1f")
+}
+
+/// Our error codes are `T` followed by digits, like `T0001`.
+fn is_taint_code(code: &str) -> bool {
+    code.strip_prefix('T').map_or(false, |n| {
+        !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+fn label(features: &str) -> String {
+    if features.is_empty() {
+        "default features".to_owned()
+    } else {
+        format!("features `{}`", features)
+    }
+}
+
+/// The driver is installed next to us.
+/// You can set a custom location by setting the `TAINT` environment variable.
+fn driver_path() -> PathBuf {
+    if let Some(path) = env::var_os("TAINT") {
+        return path.into();
+    }
+    let mut path = env::current_exe().expect("current executable path is known");
+    path.set_file_name(format!("taint{}", env::consts::EXE_SUFFIX));
+    path
+}
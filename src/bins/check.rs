@@ -0,0 +1,119 @@
+//! `taint check <dir> [<args>...]` analyzes a directory without having to know how to build it.
+//!
+//! A directory with a `Cargo.toml` is a cargo project, which is analyzed with `cargo taint`.
+//! Otherwise every `.rs` file in the directory and its subdirectories is analyzed as a standalone program.
+//! The programs are analyzed one after the other in this process, each in a compiler session of its own,
+//! which saves starting a process and loading the summary packs and the config for each of them.
+//! The remaining arguments are passed on to `cargo taint`, or to the driver for each program.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+use taint::options::Options;
+
+use crate::compat::EarlyErrorHandler;
+
+/// Checks the directory in `args`, where `driver_args` are the arguments the driver was started with
+/// before `check`.
+pub(crate) fn run(
+    driver_args: Vec<String>,
+    mut args: Vec<String>,
+    handler: &EarlyErrorHandler,
+) -> ! {
+    if args.is_empty() {
+        eprintln!("error: usage: taint check <dir> [<args>...]");
+        process::exit(2);
+    }
+    let dir = PathBuf::from(args.remove(0));
+
+    let success = if dir.join("Cargo.toml").is_file() {
+        check_project(&dir, &args)
+    } else {
+        check_programs(&dir, driver_args, args, handler)
+    };
+    process::exit(if success { 0 } else { 1 })
+}
+
+fn check_project(dir: &Path, args: &[String]) -> bool {
+    println!("checking cargo project `{}`", dir.display());
+    let status = Command::new(cargo_taint_path())
+        .arg("taint")
+        .args(args)
+        .current_dir(dir)
+        .env(
+            "TAINT",
+            env::current_exe().expect("current executable path is known"),
+        )
+        .status();
+    match status {
+        Ok(status) => status.success(),
+        Err(e) => {
+            eprintln!("error: could not run cargo-taint: {}", e);
+            false
+        }
+    }
+}
+
+/// Analyzes every program, printing the findings of each, followed by a summary.
+fn check_programs(
+    dir: &Path,
+    driver_args: Vec<String>,
+    args: Vec<String>,
+    handler: &EarlyErrorHandler,
+) -> bool {
+    let mut programs = vec![];
+    if let Err(e) = find_programs(dir, &mut programs) {
+        eprintln!("error: could not read `{}`: {}", dir.display(), e);
+        return false;
+    }
+    programs.sort();
+
+    let (options, rustc_args) = match Options::from_args(driver_args.into_iter().chain(args)) {
+        Ok(split) => split,
+        Err(error) => handler.early_error(error.to_string()),
+    };
+    let mut failed = vec![];
+    for program in &programs {
+        let mut program_args = rustc_args.clone();
+        program_args.insert(1, program.display().to_string());
+        let outcome = crate::run_compiler(program_args, handler, options.clone(), false);
+        if outcome.exit_code != 0 {
+            failed.push((program, outcome.findings));
+        }
+    }
+
+    println!("checked {} programs in `{}`", programs.len(), dir.display());
+    for (program, findings) in &failed {
+        match findings {
+            0 => println!("    {}: did not compile", program.display()),
+            n => println!("    {}: {} findings", program.display(), n),
+        }
+    }
+    failed.is_empty()
+}
+
+pub(crate) fn find_programs(dir: &Path, programs: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_programs(&path, programs)?;
+        } else if path.extension().map_or(false, |ext| ext == "rs") {
+            programs.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// `cargo-taint` is installed next to us.
+/// You can set a custom location by setting the `CARGO_TAINT` environment variable.
+fn cargo_taint_path() -> PathBuf {
+    if let Some(path) = env::var_os("CARGO_TAINT") {
+        return path.into();
+    }
+    let mut path = env::current_exe().expect("current executable path is known");
+    path.set_file_name(format!("cargo-taint{}", env::consts::EXE_SUFFIX));
+    path
+}
@@ -0,0 +1,96 @@
+//! The entry points of `rustc_driver` and `rustc_interface` the driver uses, for the nightlies it supports.
+//!
+//! The analysis itself only names compiler internals through the `compiler` module, which is fixed up on a
+//! toolchain bump. The driver's entry points change more often than the internals the analysis uses, so the
+//! nightlies just before the one in `rust-toolchain` can usually build the analysis but not the driver.
+//! The build script probes the compiler it builds with and sets a cfg for each entry point it lacks,
+//! which picks the older spelling here. Without any, the driver is built for the pinned nightly.
+//!
+//! - `taint_no_early_error_handler`: nightlies from before `EarlyErrorHandler`, which report early errors
+//!   with `rustc_session::early_error`, start the logger without a handler, and do not pass one to
+//!   `Callbacks::after_analysis`.
+
+use rustc_driver::Compilation;
+use rustc_interface::{
+    interface::{Compiler, Config},
+    Queries,
+};
+use rustc_session::config::ErrorOutputType;
+
+#[cfg(not(taint_no_early_error_handler))]
+pub(crate) use rustc_session::EarlyErrorHandler;
+
+/// Reports errors which happen before there is a session to report them with.
+#[cfg(taint_no_early_error_handler)]
+pub(crate) struct EarlyErrorHandler {
+    output: ErrorOutputType,
+}
+
+#[cfg(taint_no_early_error_handler)]
+impl EarlyErrorHandler {
+    pub(crate) fn new(output: ErrorOutputType) -> EarlyErrorHandler {
+        EarlyErrorHandler { output }
+    }
+
+    pub(crate) fn early_error(&self, msg: impl Into<rustc_errors::DiagnosticMessage>) -> ! {
+        rustc_session::early_error(self.output, msg)
+    }
+}
+
+/// Installs the hook reporting internal compiler errors, and the compiler's logger, set with `RUSTC_LOG`.
+/// Returns the handler for errors in the arguments.
+pub(crate) fn init() -> EarlyErrorHandler {
+    rustc_driver::install_ice_hook("https://github.com/LiHRaM/taint/issues", |_| ());
+    let handler = EarlyErrorHandler::new(ErrorOutputType::default());
+    #[cfg(not(taint_no_early_error_handler))]
+    rustc_driver::init_rustc_env_logger(&handler);
+    #[cfg(taint_no_early_error_handler)]
+    rustc_driver::init_rustc_env_logger();
+    handler
+}
+
+/// The callbacks the driver needs, the same on every supported nightly.
+pub(crate) trait Callbacks: Send {
+    fn config(&mut self, config: &mut Config);
+
+    fn after_analysis<'tcx>(
+        &mut self,
+        compiler: &Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation;
+}
+
+/// Runs the compiler with `args` and `callbacks`, returning its exit code.
+pub(crate) fn run_compiler(args: &[String], callbacks: &mut impl Callbacks) -> i32 {
+    let mut callbacks = Shim(callbacks);
+    rustc_driver::catch_with_exit_code(|| {
+        rustc_driver::RunCompiler::new(args, &mut callbacks).run()
+    })
+}
+
+struct Shim<'a, C>(&'a mut C);
+
+impl<C: Callbacks> rustc_driver::Callbacks for Shim<'_, C> {
+    fn config(&mut self, config: &mut Config) {
+        self.0.config(config)
+    }
+
+    #[cfg(not(taint_no_early_error_handler))]
+    fn after_analysis<'tcx>(
+        &mut self,
+        _handler: &EarlyErrorHandler,
+        compiler: &Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        self.0.after_analysis(compiler, queries)
+    }
+
+    #[cfg(taint_no_early_error_handler)]
+    fn after_analysis<'tcx>(
+        &mut self,
+        compiler: &Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        self.0.after_analysis(compiler, queries)
+    }
+}
@@ -9,25 +9,101 @@ extern crate rustc_middle;
 extern crate rustc_session;
 extern crate rustc_span;
 
-use eval::main;
+mod benchmark;
+mod check;
+mod compat;
+mod findings;
+mod policy;
+mod selftest;
+mod text;
+
+use std::path::Path;
+
+use compat::EarlyErrorHandler;
+use eval::{integration, main};
 use rustc_driver::Compilation;
+use rustc_errors::ErrorGuaranteed;
 use rustc_middle::ty::TyCtxt;
-use rustc_session::{config::ErrorOutputType, EarlyErrorHandler};
-use taint::eval;
+use taint::{
+    capabilities, eval, explain,
+    options::{self, Integration, Options},
+};
 use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
 
 fn main() {
-    rustc_driver::install_ice_hook("https://github.com/LiHRaM/taint/issues", |_| ());
-    rustc_driver::init_rustc_env_logger(&EarlyErrorHandler::new(ErrorOutputType::default()));
+    let handler = compat::init();
     init_tracing();
 
-    let mut rustc_args: Vec<String> = vec![];
+    let mut args = std::env::args().collect::<Vec<_>>();
+
+    if args.get(1).map(String::as_str) == Some("--capabilities") {
+        println!("{}", capabilities::to_json());
+        std::process::exit(0);
+    }
+
+    if args.get(1).map(String::as_str) == Some("--explain") {
+        let code = args.get(2).map_or("", String::as_str);
+        match explain::explanation(code) {
+            Some(explanation) => print!("{explanation}"),
+            None => handler.early_error(format!(
+                "`{code}` is not an error code of the analysis, which are {}",
+                explain::codes().join(", ")
+            )),
+        }
+        std::process::exit(0);
+    }
+
+    if options::asks_for_help(&args) {
+        print!("{}", options::help());
+        std::process::exit(0);
+    }
+
+    if args.get(1).map(String::as_str) == Some("check") {
+        let rest = args.split_off(2);
+        args.truncate(1);
+        check::run(args, rest, &handler);
+    }
+
+    if args.get(1).map(String::as_str) == Some("benchmark") {
+        let rest = args.split_off(2);
+        args.truncate(1);
+        benchmark::run(args, rest, &handler);
+    }
+
+    if args.get(1).map(String::as_str) == Some("findings") {
+        findings::run(args.split_off(2));
+    }
+
+    if args.get(1).map(String::as_str) == Some("policy") {
+        let rest = args.split_off(2);
+        args.truncate(1);
+        policy::run(args, rest, &handler);
+    }
+
+    if args.get(1).map(String::as_str) == Some("selftest") {
+        let rest = args.split_off(2);
+        args.truncate(1);
+        selftest::run(args, rest, &handler);
+    }
 
-    for arg in std::env::args() {
-        rustc_args.push(arg);
+    // As a `RUSTC_WORKSPACE_WRAPPER`, cargo passes us the path to rustc first.
+    // Dependent crates then need our metadata, so compilation has to go on after the analysis.
+    let wrapper = args.get(1).map_or(false, |arg| {
+        Path::new(arg)
+            .file_stem()
+            .map_or(false, |stem| stem == "rustc")
+    });
+    if wrapper {
+        args.remove(1);
     }
 
-    run_compiler(rustc_args, &mut TaintCompilerCallbacks)
+    let (options, rustc_args) = match Options::from_args(args) {
+        Ok(split) => split,
+        Err(error) => handler.early_error(error.to_string()),
+    };
+
+    let outcome = run_compiler(rustc_args, &handler, options, wrapper);
+    std::process::exit(outcome.exit_code)
 }
 
 /// We want our own tracing to debug the taint analysis.
@@ -35,6 +111,9 @@ fn main() {
 ///
 /// Example: `TAINT_LOG=INFO cargo run -- tests/fails/simple.rs`
 ///
+/// Only the analysis of some functions, and of the functions it analyzes in turn, is traced with
+/// a `function` field, like `TAINT_LOG='taint[{function=my_crate::handler}]=trace'`.
+///
 /// It is configured for minimal hassle.
 /// It logs when functions marked with `#[instrument]` are entered,
 /// and does not require any further code (such as the `event!` macro
@@ -49,8 +128,22 @@ fn init_tracing() {
     }
 }
 
-fn run_compiler(mut args: Vec<String>, callbacks: &mut (dyn rustc_driver::Callbacks + Send)) -> ! {
-    if let Some(sysroot) = compile_time_sysroot() {
+/// How compiling and analyzing a crate went.
+pub(crate) struct Outcome {
+    pub(crate) exit_code: i32,
+    /// The number of findings and invalid annotations reported by the analysis.
+    pub(crate) findings: usize,
+}
+
+/// Compiles and analyzes the crate `args` are for, in a session of its own,
+/// so that it can be called again for other crates in the same process.
+pub(crate) fn run_compiler(
+    mut args: Vec<String>,
+    handler: &EarlyErrorHandler,
+    options: Options,
+    emit_artifacts: bool,
+) -> Outcome {
+    if let Some(sysroot) = compile_time_sysroot(handler) {
         let sysroot_flag = "--sysroot";
         if !args.iter().any(|e| e == sysroot_flag) {
             args.push(sysroot_flag.to_owned());
@@ -58,13 +151,19 @@ fn run_compiler(mut args: Vec<String>, callbacks: &mut (dyn rustc_driver::Callba
         }
     }
 
-    let exit_code = rustc_driver::catch_with_exit_code(move || {
-        rustc_driver::RunCompiler::new(&args, callbacks).run()
-    });
-    std::process::exit(exit_code)
+    let mut callbacks = TaintCompilerCallbacks {
+        options,
+        emit_artifacts,
+        findings: 0,
+    };
+    let exit_code = compat::run_compiler(&args, &mut callbacks);
+    Outcome {
+        exit_code,
+        findings: callbacks.findings,
+    }
 }
 
-fn compile_time_sysroot() -> Option<String> {
+fn compile_time_sysroot(handler: &EarlyErrorHandler) -> Option<String> {
     if option_env!("RUSTC_STAGE").is_some() {
         None
     } else {
@@ -72,45 +171,79 @@ fn compile_time_sysroot() -> Option<String> {
         let toolchain = option_env!("RUSTUP_TOOLCHAIN").or(option_env!("MULTIRUST_TOOLCHAIN"));
         Some(match (home, toolchain) {
             (Some(home), Some(toolchain)) => format!("{}/toolchains/{}", home, toolchain),
-            _ => option_env!("RUST_SYSROOT")
-                .expect("To build this without rustup, set the RUST_SYSROOT env var at build time")
-                .to_owned(),
+            _ => match option_env!("RUST_SYSROOT") {
+                Some(sysroot) => sysroot.to_owned(),
+                None => handler.early_error(
+                    "could not find the sysroot: to build this without rustup, \
+                     set the RUST_SYSROOT environment variable at build time",
+                ),
+            },
         })
     }
 }
 
 /// Runs taint analysis once built-in analyses are complete.
-/// No artifacts are emitted, since this is meant to be an analysis tool only.
-struct TaintCompilerCallbacks;
+/// No artifacts are emitted, since this is meant to be an analysis tool only,
+/// unless we are run by cargo, which needs them to build the rest of the workspace.
+struct TaintCompilerCallbacks {
+    options: Options,
+    emit_artifacts: bool,
+    /// The number of findings and invalid annotations reported by the analysis.
+    findings: usize,
+}
+
+impl compat::Callbacks for TaintCompilerCallbacks {
+    fn config(&mut self, config: &mut rustc_interface::interface::Config) {
+        match self.options.integration {
+            Integration::Callbacks => {
+                config.override_queries = main::override_queries(&self.options)
+            }
+            Integration::Query => integration::register(config, self.options.clone()),
+        }
+
+        // A source or sink inlined into its caller is no longer called, so it would lose its role.
+        // Inlining can still be turned back on with `-Zinline-mir=yes`.
+        let inline_mir = &mut config.opts.unstable_opts.inline_mir;
+        if inline_mir.is_none() {
+            *inline_mir = Some(false);
+        }
+    }
 
-impl rustc_driver::Callbacks for TaintCompilerCallbacks {
     /// All the work we do happens after analysis, so that we can make assumptions about the validity of the MIR.
     fn after_analysis<'tcx>(
         &mut self,
-        _handler: &EarlyErrorHandler,
         compiler: &rustc_interface::interface::Compiler,
         queries: &'tcx rustc_interface::Queries<'tcx>,
     ) -> Compilation {
         compiler.session().abort_if_errors();
-        enter_with_fn(queries, mir_analysis);
+        // With `--taint-integration=query`, the crate was analyzed along with the compiler's own analysis.
+        let findings = match self.options.integration {
+            Integration::Callbacks => {
+                enter_with_fn(queries, |tcx| main::eval_crate(tcx, &self.options))
+            }
+            Integration::Query => Ok(integration::findings()),
+        };
+        match findings {
+            Ok(findings) => self.findings = findings,
+            // The compiler has already reported why it could not go on.
+            Err(_) => return Compilation::Stop,
+        }
         compiler.session().abort_if_errors();
-        Compilation::Stop
+        if self.emit_artifacts {
+            Compilation::Continue
+        } else {
+            Compilation::Stop
+        }
     }
 }
 
 /// Call a function which takes the `TyCtxt`.
-fn enter_with_fn<'tcx, TyCtxtFn>(queries: &'tcx rustc_interface::Queries<'tcx>, enter_fn: TyCtxtFn)
+fn enter_with_fn<'tcx, T, TyCtxtFn>(
+    queries: &'tcx rustc_interface::Queries<'tcx>,
+    enter_fn: TyCtxtFn,
+) -> Result<T, ErrorGuaranteed>
 where
-    TyCtxtFn: Fn(TyCtxt),
+    TyCtxtFn: Fn(TyCtxt) -> T,
 {
-    queries.global_ctxt().unwrap().enter(enter_fn);
-}
-
-/// Perform the taint analysis.
-fn mir_analysis(tcx: TyCtxt) {
-    if let Some((entry_def_id, _)) = tcx.entry_fn(()) {
-        main::eval_main(tcx, entry_def_id);
-    } else {
-        main::eval_all_pub_fn(tcx);
-    }
+    Ok(queries.global_ctxt()?.enter(enter_fn))
 }
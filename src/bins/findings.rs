@@ -0,0 +1,134 @@
+//! `taint findings [--db=<file>] list [<run>] | show <id> | diff <run> <run>` reads the findings of past runs,
+//! kept with `--taint-findings-db`, so that they can be triaged without analyzing again.
+//!
+//! `list` prints the findings of a run, the latest one by default. `show` prints everything known about
+//! the finding whose fingerprint starts with `id`, as of the latest run which has it.
+//! `diff` prints the findings the second run has which the first does not, marked `+`, and the other way around,
+//! marked `-`. The runs are kept in `taint-findings.jsonl` unless `--db` names another file.
+
+use std::{path::PathBuf, process};
+
+use taint::{
+    history::{self, History, Run},
+    report::Finding,
+};
+
+use crate::text::plural;
+
+const USAGE: &str =
+    "usage: taint findings [--db=<file>] list [<run>] | show <id> | diff <run> <run>";
+
+/// Answers the query in `args`, the arguments after `findings`.
+pub(crate) fn run(mut args: Vec<String>) -> ! {
+    let mut path = PathBuf::from(history::FILE_NAME);
+    if let Some(db) = args.first().and_then(|arg| arg.strip_prefix("--db=")) {
+        path = PathBuf::from(db);
+        args.remove(0);
+    }
+    let history = match History::read(&path) {
+        Ok(history) => history,
+        Err(error) => fail(&error.to_string()),
+    };
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let output = match args[..] {
+        ["list"] => match history.latest() {
+            Some(run) => list(run),
+            None => fail(&format!("no runs in `{}`", path.display())),
+        },
+        ["list", id] => list(find_run(&history, id)),
+        ["show", id] => match history.find(id) {
+            Some((run, finding)) => show(run, finding),
+            None => fail(&format!(
+                "no finding has a fingerprint starting with `{}`",
+                id
+            )),
+        },
+        ["diff", earlier, later] => diff(find_run(&history, earlier), find_run(&history, later)),
+        _ => fail(USAGE),
+    };
+    print!("{}", output);
+    process::exit(0)
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(2)
+}
+
+fn find_run<'a>(history: &'a History, id: &str) -> &'a Run {
+    let run = id.parse().ok().and_then(|id| history.run(id));
+    run.unwrap_or_else(|| fail(&format!("no run numbered `{}`", id)))
+}
+
+fn list(run: &Run) -> String {
+    let mut output = format!(
+        "run {} of `{}`: {}\n",
+        run.id,
+        run.krate,
+        plural(run.findings.len(), "finding")
+    );
+    for finding in &run.findings {
+        output.push_str(&line(finding));
+    }
+    output
+}
+
+fn show(run: &Run, finding: &Finding) -> String {
+    let mut output = format!(
+        "finding {} in run {} of `{}`\n{}: {}\n  at {}:{}:{}\n  in `{}`, reaching `{}` with label `{}`\n",
+        finding.fingerprint,
+        run.id,
+        run.krate,
+        finding.code,
+        finding.message,
+        finding.file,
+        finding.start.line,
+        finding.start.column,
+        finding.function,
+        finding.sink,
+        finding.label,
+    );
+    output.push_str(&format!(
+        "  with confidence {} of 100\n",
+        finding.confidence.score
+    ));
+    for doubt in &finding.confidence.doubts {
+        output.push_str(&format!("  lowered as {}\n", doubt));
+    }
+    for note in &finding.context {
+        output.push_str(&format!("  {}\n", note));
+    }
+    output
+}
+
+fn diff(earlier: &Run, later: &Run) -> String {
+    let diff = later.diff(earlier);
+    let mut output = format!(
+        "run {} to run {}: {} new, {} fixed, {} unchanged\n",
+        earlier.id,
+        later.id,
+        diff.added.len(),
+        diff.removed.len(),
+        diff.unchanged
+    );
+    for finding in &diff.added {
+        output.push_str(&format!("+ {}", line(finding)));
+    }
+    for finding in &diff.removed {
+        output.push_str(&format!("- {}", line(finding)));
+    }
+    output
+}
+
+/// A finding on one line: its fingerprint, code, position and message.
+fn line(finding: &Finding) -> String {
+    format!(
+        "{} {} {}:{}:{} {}\n",
+        finding.fingerprint,
+        finding.code,
+        finding.file,
+        finding.start.line,
+        finding.start.column,
+        finding.message
+    )
+}
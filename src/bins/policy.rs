@@ -0,0 +1,37 @@
+//! `taint policy check <args>...` checks the paths and patterns of the policy against the crate `args` are for,
+//! without analyzing it, so that a config can be edited and checked again in seconds.
+//!
+//! The arguments are those the driver would be run with, like the root of the crate and `--taint-config=<file>`.
+//! The crate is compiled up to its MIR, then each path of the config and of the command line is printed
+//! with what it matches, as `--taint-emit=policy-check` does.
+
+use std::process;
+
+use taint::options::{Emit, Options};
+
+use crate::compat::EarlyErrorHandler;
+
+const USAGE: &str = "usage: taint policy check <args>...";
+
+/// Runs the query in `args`, the arguments after `policy`, where `driver_args` are the arguments the driver
+/// was started with before `policy`.
+pub(crate) fn run(
+    driver_args: Vec<String>,
+    mut args: Vec<String>,
+    handler: &EarlyErrorHandler,
+) -> ! {
+    if args.first().map(String::as_str) != Some("check") || args.len() < 2 {
+        eprintln!("error: {}", USAGE);
+        process::exit(2);
+    }
+    args.remove(0);
+
+    let (mut options, rustc_args) = match Options::from_args(driver_args.into_iter().chain(args)) {
+        Ok(split) => split,
+        Err(error) => handler.early_error(error.to_string()),
+    };
+    options.emit = vec![Emit::PolicyCheck];
+    options.list_annotations = false;
+    let outcome = crate::run_compiler(rustc_args, handler, options, false);
+    process::exit(outcome.exit_code)
+}
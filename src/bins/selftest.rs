@@ -0,0 +1,168 @@
+//! `taint selftest [<dir>] [<args>...]` runs the analysis over its own test programs,
+//! so that a change can be checked with one command, without the compiletest harness.
+//!
+//! Every program under `<dir>/passes` must have no findings, and every program under `<dir>/fails`
+//! at least one. `<dir>` is `tests` by default. Flags a program asks for in a `// compile-flags:` line
+//! are passed to the driver along with the remaining arguments, as compiletest does.
+//! Unlike compiletest, where the findings are reported is not checked.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use taint::options::Options;
+
+use crate::{check::find_programs, compat::EarlyErrorHandler};
+
+/// Whether the programs of a directory are expected to have findings.
+#[derive(Clone, Copy)]
+enum Expected {
+    None,
+    Some,
+}
+
+/// How analyzing a program went.
+struct Row {
+    program: PathBuf,
+    expected: Expected,
+    /// The number of findings, or `None` if the program did not compile.
+    findings: Option<usize>,
+}
+
+impl Row {
+    fn passed(&self) -> bool {
+        match (self.expected, self.findings) {
+            (Expected::None, Some(0)) => true,
+            (Expected::Some, Some(n)) => n > 0,
+            _ => false,
+        }
+    }
+}
+
+/// Runs the test programs in `args`, where `driver_args` are the arguments the driver was started with
+/// before `selftest`.
+pub(crate) fn run(
+    driver_args: Vec<String>,
+    mut args: Vec<String>,
+    handler: &EarlyErrorHandler,
+) -> ! {
+    let dir = match args.first() {
+        Some(first) if !first.starts_with('-') => PathBuf::from(args.remove(0)),
+        _ => PathBuf::from("tests"),
+    };
+
+    let mut rows = vec![];
+    for (subdir, expected) in [("passes", Expected::None), ("fails", Expected::Some)] {
+        let mut programs = vec![];
+        if let Err(e) = find_programs(&dir.join(subdir), &mut programs) {
+            eprintln!(
+                "error: could not read `{}`: {}",
+                dir.join(subdir).display(),
+                e
+            );
+            process::exit(2);
+        }
+        programs.sort();
+        for program in programs {
+            let findings = analyze(&program, &driver_args, &args, handler);
+            rows.push(Row {
+                program,
+                expected,
+                findings,
+            });
+        }
+    }
+
+    print!("{}", table(&rows));
+    process::exit(if rows.iter().all(Row::passed) { 0 } else { 1 })
+}
+
+/// Analyzes `program` with the flags it asks for, returning its number of findings,
+/// or `None` if it did not compile.
+fn analyze(
+    program: &Path,
+    driver_args: &[String],
+    args: &[String],
+    handler: &EarlyErrorHandler,
+) -> Option<usize> {
+    let flags = fs::read_to_string(program)
+        .map(|source| compile_flags(&source))
+        .unwrap_or_default();
+    let all_args = driver_args
+        .iter()
+        .cloned()
+        .chain(["--edition=2018".to_owned(), program.display().to_string()])
+        .chain(flags)
+        .chain(args.iter().cloned());
+    let (options, rustc_args) = match Options::from_args(all_args) {
+        Ok(split) => split,
+        Err(error) => handler.early_error(error.to_string()),
+    };
+
+    let outcome = crate::run_compiler(rustc_args, handler, options, false);
+    match (outcome.exit_code, outcome.findings) {
+        (0, findings) => Some(findings),
+        (_, 0) => None,
+        (_, findings) => Some(findings),
+    }
+}
+
+/// The flags in the `// compile-flags:` lines of `source`.
+pub(crate) fn compile_flags(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .filter_map(|line| line.strip_prefix("// compile-flags:"))
+        .flat_map(str::split_whitespace)
+        .map(str::to_owned)
+        .collect()
+}
+
+/// A row per program, followed by the number of programs which passed and failed.
+fn table(rows: &[Row]) -> String {
+    let width = rows
+        .iter()
+        .map(|row| row.program.display().to_string().len())
+        .max()
+        .unwrap_or(0)
+        .max("program".len());
+
+    let mut table = format!(
+        "{:<width$}  {:<8}  {:<8}  result\n",
+        "program",
+        "expected",
+        "findings",
+        width = width
+    );
+    for row in rows {
+        let expected = match row.expected {
+            Expected::None => "none",
+            Expected::Some => "some",
+        };
+        let findings = row
+            .findings
+            .map_or_else(|| "-".to_owned(), |n| n.to_string());
+        let result = match (row.passed(), row.findings) {
+            (true, _) => "ok",
+            (false, None) => "did not compile",
+            (false, Some(_)) => "FAILED",
+        };
+        table.push_str(&format!(
+            "{:<width$}  {:<8}  {:<8}  {}\n",
+            row.program.display(),
+            expected,
+            findings,
+            result,
+            width = width
+        ));
+    }
+
+    let passed = rows.iter().filter(|row| row.passed()).count();
+    table.push_str(&format!(
+        "selftest: {} passed, {} failed\n",
+        passed,
+        rows.len() - passed
+    ));
+    table
+}
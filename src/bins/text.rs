@@ -0,0 +1,6 @@
+//! Wording shared by the reports of the subcommands.
+
+/// `n` followed by `what`, made plural unless `n` is one.
+pub(crate) fn plural(n: usize, what: &str) -> String {
+    format!("{} {}{}", n, what, if n == 1 { "" } else { "s" })
+}
@@ -0,0 +1,57 @@
+//! What this build of the analysis supports, printed with `taint --capabilities`,
+//! so that tools running it can check they are compatible before they do.
+//!
+//! ```json
+//! {
+//!     "version": 1,
+//!     "analysis": "0.1.0",
+//!     "rules": [{ "id": "T0001", "description": "a sink received tainted input" }],
+//!     "summary_packs": ["std"],
+//!     "sink_packs": ["allocation", "pointer", "process", "resource", "unsafe"],
+//!     "source_packs": ["wasm-bindgen", "pyo3"],
+//!     "formats": { "config": 1, "findings": 1, "findings_db": 1, "fuzz_targets": 1, "results": 1, "summaries": 1 },
+//!     "emit": ["policy-doc", "policy-check"],
+//!     "progress": ["json"]
+//! }
+//! ```
+//!
+//! `formats` has the version of each file format the analysis reads or writes.
+
+use serde_json::json;
+
+use crate::{
+    compiler::ErrorCode,
+    config, fuzz, history,
+    options::{Emit, SinkPack, SourcePack},
+    query, report, summaries,
+};
+
+/// The version of the capabilities format, bumped on incompatible changes.
+pub const VERSION: u64 = 1;
+
+/// The capabilities of the analysis, as JSON.
+pub fn to_json() -> String {
+    let rules = ErrorCode::ALL
+        .iter()
+        .map(|code| json!({ "id": code.as_str(), "description": code.description() }))
+        .collect::<Vec<_>>();
+    json!({
+        "version": VERSION,
+        "analysis": env!("CARGO_PKG_VERSION"),
+        "rules": rules,
+        "summary_packs": summaries::PACKS.iter().map(|(krate, _)| krate).collect::<Vec<_>>(),
+        "sink_packs": SinkPack::ALL.iter().map(|pack| pack.name()).collect::<Vec<_>>(),
+        "source_packs": SourcePack::ALL.iter().map(|pack| pack.name()).collect::<Vec<_>>(),
+        "formats": {
+            "config": config::VERSION,
+            "findings": report::VERSION,
+            "findings_db": history::VERSION,
+            "fuzz_targets": fuzz::VERSION,
+            "results": query::VERSION,
+            "summaries": summaries::VERSION,
+        },
+        "emit": Emit::ALL.iter().map(|emit| emit.name()).collect::<Vec<_>>(),
+        "progress": ["json"],
+    })
+    .to_string()
+}
@@ -0,0 +1,1468 @@
+//! Narrow views of function bodies.
+
+use std::collections::HashSet;
+
+use rustc_hir::{def::DefKind, def_id::DefId};
+use rustc_middle::{
+    middle::codegen_fn_attrs::CodegenFnAttrFlags,
+    mir::{
+        interpret::ConstValue,
+        traversal,
+        visit::{MutatingUseContext, PlaceContext, Visitor},
+        AggregateKind, BasicBlock, Body, BorrowKind, CastKind, Constant, Local, LocalKind,
+        Location, Mutability, Operand, Place, PlaceElem, PlaceRef, ProjectionElem, Rvalue,
+        StatementKind, Terminator, TerminatorKind, UnOp, VarDebugInfo, VarDebugInfoContents,
+    },
+    ty::{
+        adjustment::PointerCoercion, AliasKind, EarlyBinder, GenericArgs, GenericArgsRef, Instance,
+        InstanceDef, IntTy, ParamEnv, Ty, TyCtxt, TyKind, TypeAndMut, UintTy,
+    },
+};
+use rustc_span::{sym, Span};
+
+use super::stages;
+use crate::config::TypeClass;
+
+/// The MIR we analyze for `id`, if it has any: the body of a function, or the initializer of a static.
+/// Foreign functions, intrinsics and functions of other crates whose MIR was not encoded
+/// have no body for us to analyze, and asking for their MIR would panic.
+/// Naked functions have MIR, but none we can analyze.
+pub(crate) fn mir_body(tcx: TyCtxt<'_>, id: DefId) -> Option<&Body<'_>> {
+    match tcx.def_kind(id) {
+        kind if kind.is_fn_like() && tcx.is_mir_available(id) && !is_naked(tcx, id) => {
+            Some(stages::kept_body(tcx, id).unwrap_or_else(|| tcx.optimized_mir(id)))
+        }
+        // Initializers are evaluated at compile time, and `optimized_mir` is only for functions.
+        DefKind::Static(_) if tcx.is_ctfe_mir_available(id) => Some(tcx.mir_for_ctfe(id)),
+        _ => None,
+    }
+}
+
+/// Whether `id` is a function of a dependency with a body whose MIR was not encoded, because the
+/// dependency was built without `-Zalways-encode-mir`. The crates of the sysroot, like `std`, never are,
+/// so their functions do not count.
+pub(crate) fn mir_not_encoded(tcx: TyCtxt<'_>, id: DefId) -> bool {
+    if id.is_local()
+        || !tcx.def_kind(id).is_fn_like()
+        || tcx.is_mir_available(id)
+        || tcx.is_foreign_item(id)
+        || tcx.is_intrinsic(id)
+    {
+        return false;
+    }
+    // A required method of a trait has no body to encode.
+    if tcx.trait_of_item(id).is_some() && !tcx.defaultness(id).has_value() {
+        return false;
+    }
+    !tcx.used_crate_source(id.krate)
+        .paths()
+        .any(|path| path.starts_with(&tcx.sess.sysroot))
+}
+
+/// Whether `id` is a `#[naked]` function, whose body is a single `asm!` which takes its arguments and returns
+/// without MIR seeing it: analyzing that MIR would find a function which never reads its arguments nor returns.
+pub(crate) fn is_naked(tcx: TyCtxt<'_>, id: DefId) -> bool {
+    tcx.def_kind(id).is_fn_like()
+        && tcx
+            .codegen_fn_attrs(id)
+            .flags
+            .contains(CodegenFnAttrFlags::NAKED)
+}
+
+pub(crate) fn is_fn(tcx: TyCtxt<'_>, id: DefId) -> bool {
+    tcx.def_kind(id).is_fn_like()
+}
+
+/// The static `operand` refers to, if it is a reference to one.
+pub(crate) fn static_ref(tcx: TyCtxt<'_>, operand: &Operand<'_>) -> Option<DefId> {
+    match operand {
+        Operand::Constant(constant) => constant.check_static_ptr(tcx),
+        Operand::Copy(_) | Operand::Move(_) => None,
+    }
+}
+
+/// The static `local` refers to, if it was assigned a reference to one, or a reborrow or copy of such a local,
+/// like the receiver of `CELL.set(value)`.
+pub(crate) fn static_behind(tcx: TyCtxt<'_>, body: &Body<'_>, local: Local) -> Option<DefId> {
+    let mut local = local;
+    for _ in 0..body.local_decls.len() {
+        match assignment(body, local)? {
+            Rvalue::Use(operand @ Operand::Constant(_)) => return static_ref(tcx, operand),
+            Rvalue::Use(Operand::Copy(from) | Operand::Move(from))
+            | Rvalue::CopyForDeref(from)
+            | Rvalue::Ref(_, _, from) => local = from.local,
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// The type of the function item `operand` was made from, if it is a function pointer
+/// made from one, like the initializer of a `static SECRET: LazyLock<String> = LazyLock::new(input)`.
+pub(crate) fn reified_fn<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    operand: &Operand<'tcx>,
+) -> Option<Ty<'tcx>> {
+    let (Operand::Copy(place) | Operand::Move(place)) = operand else {
+        return None;
+    };
+    match assignment(body, place.as_local()?)? {
+        Rvalue::Cast(CastKind::PointerCoercion(PointerCoercion::ReifyFnPointer), item, _) => {
+            Some(item.ty(body, tcx))
+        }
+        _ => None,
+    }
+}
+
+/// The constant `operand` holds in `body`, written the way the config names it: the path of a variant
+/// without fields, `true` or `false`, an integer, or a string in quotes.
+/// A local counts if it is only ever assigned a constant, like the `Mode::Html` built before a call.
+pub(crate) fn constant_value<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    operand: &Operand<'tcx>,
+) -> Option<String> {
+    let mut operand = operand;
+    for _ in 0..body.local_decls.len() {
+        let local = match operand {
+            Operand::Constant(constant) => return render_constant(tcx, constant),
+            Operand::Copy(place) | Operand::Move(place) => place.as_local()?,
+        };
+        let mut assignments = body.basic_blocks.iter().flat_map(|block| {
+            block
+                .statements
+                .iter()
+                .filter_map(move |statement| match &statement.kind {
+                    StatementKind::Assign(box (place, rvalue)) if place.local == local => {
+                        Some((place, rvalue))
+                    }
+                    _ => None,
+                })
+        });
+        let (place, rvalue) = assignments.next()?;
+        if assignments.next().is_some() || place.as_local().is_none() {
+            return None;
+        }
+        match rvalue {
+            Rvalue::Use(from) => operand = from,
+            Rvalue::Aggregate(box AggregateKind::Adt(id, variant, ..), fields)
+                if fields.is_empty() =>
+            {
+                return Some(tcx.def_path_str(tcx.adt_def(*id).variant(*variant).def_id));
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn render_constant<'tcx>(tcx: TyCtxt<'tcx>, constant: &Constant<'tcx>) -> Option<String> {
+    let param_env = ParamEnv::reveal_all();
+    let ty = constant.ty();
+    match ty.kind() {
+        TyKind::Bool => constant
+            .literal
+            .try_eval_bool(tcx, param_env)
+            .map(|value| value.to_string()),
+        TyKind::Uint(_) => constant
+            .literal
+            .try_eval_bits(tcx, param_env, ty)
+            .map(|bits| bits.to_string()),
+        TyKind::Int(_) => {
+            let bits = constant.literal.try_eval_bits(tcx, param_env, ty)?;
+            let size = tcx.layout_of(param_env.and(ty)).ok()?.size;
+            Some((size.sign_extend(bits) as i128).to_string())
+        }
+        TyKind::Ref(_, inner, _) if inner.is_str() => {
+            let value = constant.literal.eval(tcx, param_env).try_to_value(tcx)?;
+            let ConstValue::Slice { data, start, end } = value else {
+                return None;
+            };
+            let bytes = data
+                .inner()
+                .inspect_with_uninit_and_ptr_outside_interpreter(start..end);
+            Some(format!("{:?}", std::str::from_utf8(bytes).ok()?))
+        }
+        TyKind::Adt(adt, _) if adt.is_enum() => {
+            let value = constant.literal.eval(tcx, param_env).try_to_value(tcx)?;
+            let destructured = tcx.try_destructure_mir_constant_for_diagnostics((value, ty))?;
+            let variant = adt.variant(destructured.variant?);
+            variant
+                .fields
+                .is_empty()
+                .then(|| tcx.def_path_str(variant.def_id))
+        }
+        _ => None,
+    }
+}
+
+/// What `local` is assigned, if it is a temporary, which is only assigned once.
+fn assignment<'a, 'tcx>(body: &'a Body<'tcx>, local: Local) -> Option<&'a Rvalue<'tcx>> {
+    body.basic_blocks.iter().find_map(|block| {
+        block
+            .statements
+            .iter()
+            .find_map(|statement| match &statement.kind {
+                StatementKind::Assign(box (place, rvalue)) if place.as_local() == Some(local) => {
+                    Some(rvalue)
+                }
+                _ => None,
+            })
+    })
+}
+
+/// The closures defined in the initializer of the static `id`, like the one in `LazyLock::new(|| ...)`.
+pub(crate) fn initializer_closures(tcx: TyCtxt<'_>, id: DefId) -> Vec<DefId> {
+    tcx.hir()
+        .body_owners()
+        .map(|owner| owner.to_def_id())
+        .filter(|&owner| tcx.is_closure(owner) && tcx.typeck_root_def_id(owner) == id)
+        .collect()
+}
+
+/// Whether `local` holds a pointer to memory elsewhere: a reference, a raw pointer, a box,
+/// or a struct wrapping one, like `NonNull` or `Vec`.
+pub(crate) fn is_pointer<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, local: Local) -> bool {
+    is_pointer_ty(tcx, body.local_decls[local].ty, 4)
+}
+
+/// Looks for a pointer at most `depth` structs deep, since types like linked lists are recursive.
+fn is_pointer_ty<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>, depth: usize) -> bool {
+    match ty.kind() {
+        TyKind::Ref(..) | TyKind::RawPtr(_) => true,
+        _ if ty.is_box() => true,
+        TyKind::Adt(adt, args) if (adt.is_struct() || adt.is_union()) && depth > 0 => adt
+            .all_fields()
+            .any(|field| is_pointer_ty(tcx, field.ty(tcx, args), depth - 1)),
+        _ => false,
+    }
+}
+
+/// The locals of `body`, instantiated with `body_args`, whose type holds none of the kinds of data in `classes`,
+/// like the length of a string when only strings are tracked.
+pub(crate) fn untracked_locals<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    body_args: GenericArgsRef<'tcx>,
+    classes: &[TypeClass],
+) -> Vec<Local> {
+    body.local_decls
+        .iter_enumerated()
+        .filter(|(_, decl)| {
+            let ty = tcx
+                .try_subst_and_normalize_erasing_regions(
+                    body_args,
+                    ParamEnv::reveal_all(),
+                    EarlyBinder::bind(decl.ty),
+                )
+                .unwrap_or(decl.ty);
+            !holds_any(tcx, ty, classes)
+        })
+        .map(|(local, _)| local)
+        .collect()
+}
+
+/// The locals of `body`, instantiated with `body_args`, whose type is zero-sized, like `PhantomData<T>`,
+/// a unit struct or a closure which captures nothing, and so cannot hold any data.
+/// Locals whose size depends on a generic parameter are not among them.
+pub(crate) fn zero_sized_locals<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    body_args: GenericArgsRef<'tcx>,
+) -> Vec<Local> {
+    let param_env = ParamEnv::reveal_all();
+    body.local_decls
+        .iter_enumerated()
+        .filter(|(_, decl)| {
+            let ty = tcx
+                .try_subst_and_normalize_erasing_regions(
+                    body_args,
+                    param_env,
+                    EarlyBinder::bind(decl.ty),
+                )
+                .unwrap_or(decl.ty);
+            tcx.layout_of(param_env.and(ty))
+                .map_or(false, |layout| layout.is_zst())
+        })
+        .map(|(local, _)| local)
+        .collect()
+}
+
+/// Whether values of type `ty` may hold data of one of the kinds in `classes`, through references,
+/// tuples, and the fields and generic arguments of ADTs. Generic parameters, trait objects and generators
+/// may hold anything.
+fn holds_any<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>, classes: &[TypeClass]) -> bool {
+    let is_byte =
+        |ty: Ty<'_>| matches!(ty.kind(), TyKind::Uint(UintTy::U8) | TyKind::Int(IntTy::I8));
+    let mut seen = HashSet::new();
+    let mut pending = vec![ty];
+    while let Some(ty) = pending.pop() {
+        let ty = reveal(tcx, ty);
+        if !seen.insert(ty) {
+            continue;
+        }
+        let class = match ty.kind() {
+            TyKind::Str | TyKind::Char => Some(TypeClass::String),
+            TyKind::Bool => Some(TypeClass::Bool),
+            TyKind::Int(_) | TyKind::Uint(_) | TyKind::Float(_) => Some(TypeClass::Number),
+            TyKind::Slice(element) | TyKind::Array(element, _) if is_byte(*element) => {
+                Some(TypeClass::Bytes)
+            }
+            TyKind::Slice(element) | TyKind::Array(element, _) => {
+                pending.push(*element);
+                None
+            }
+            TyKind::Ref(_, pointee, _) | TyKind::RawPtr(TypeAndMut { ty: pointee, .. }) => {
+                pending.push(*pointee);
+                None
+            }
+            TyKind::Tuple(elements) => {
+                pending.extend(elements.iter());
+                None
+            }
+            TyKind::Closure(_, args) => {
+                pending.push(args.as_closure().tupled_upvars_ty());
+                None
+            }
+            TyKind::Adt(adt, args) => {
+                let name = tcx.item_name(adt.did());
+                let is_std = matches!(
+                    tcx.crate_name(adt.did().krate).as_str(),
+                    "std" | "core" | "alloc"
+                );
+                let is_string = matches!(
+                    name.as_str(),
+                    "String" | "OsString" | "OsStr" | "PathBuf" | "Path" | "CString" | "CStr"
+                );
+                if is_std && is_string {
+                    Some(TypeClass::String)
+                } else if is_std && name.as_str() == "Vec" && is_byte(args.type_at(0)) {
+                    Some(TypeClass::Bytes)
+                } else {
+                    pending.extend(args.types());
+                    pending.extend(adt.all_fields().map(|field| field.ty(tcx, args)));
+                    (!is_std).then_some(TypeClass::User)
+                }
+            }
+            TyKind::FnDef(..) | TyKind::FnPtr(_) | TyKind::Never => None,
+            _ => return true,
+        };
+        if class.is_some_and(|class| classes.contains(&class)) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether `local` holds a raw pointer or a box, which point to memory which may hold many values,
+/// like the buffer of a `Vec`.
+pub(crate) fn is_heap_pointer(body: &Body<'_>, local: Local) -> bool {
+    let ty = body.local_decls[local].ty;
+    ty.is_unsafe_ptr() || ty.is_box()
+}
+
+/// Whether assigning to `place` writes only a part of the object, like one of its fields,
+/// rather than the whole object its local holds or refers to.
+pub(crate) fn writes_part(place: &Place<'_>) -> bool {
+    place
+        .projection
+        .iter()
+        .any(|elem| !matches!(elem, ProjectionElem::Deref))
+}
+
+/// The type of the object assigning to `place` writes into through its last pointer,
+/// like `User` in `(*user).bio = input`, if it goes through one.
+pub(crate) fn pointee_ty<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    place: &Place<'tcx>,
+) -> Option<Ty<'tcx>> {
+    let (base, _) = place
+        .iter_projections()
+        .filter(|(_, elem)| matches!(elem, ProjectionElem::Deref))
+        .last()?;
+    let pointee = base.ty(body, tcx).ty.builtin_deref(true)?.ty;
+    Some(tcx.erase_regions(pointee))
+}
+
+/// The struct fields `rvalue` reads, like `body` in `request.body` or `&request.body`.
+pub(crate) fn read_fields<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    rvalue: &Rvalue<'tcx>,
+) -> Vec<DefId> {
+    let place = match rvalue {
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+        | Rvalue::Ref(_, _, place)
+        | Rvalue::AddressOf(_, place)
+        | Rvalue::CopyForDeref(place) => place,
+        _ => return vec![],
+    };
+    place
+        .iter_projections()
+        .filter_map(|(base, elem)| projected_field(tcx, body, base, elem))
+        .collect()
+}
+
+/// One of the two variants of `Result`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ResultVariant {
+    Ok,
+    Err,
+}
+
+/// The variant of `Result` `rvalue` builds, like `Ok` in `Ok(value)`.
+pub(crate) fn built_result_variant(tcx: TyCtxt<'_>, rvalue: &Rvalue<'_>) -> Option<ResultVariant> {
+    let Rvalue::Aggregate(box AggregateKind::Adt(id, variant, _, _, None), _) = rvalue else {
+        return None;
+    };
+    if !tcx.is_diagnostic_item(sym::Result, *id) {
+        return None;
+    }
+    Some(if variant.as_u32() == 0 {
+        ResultVariant::Ok
+    } else {
+        ResultVariant::Err
+    })
+}
+
+/// The locals indexing the table `rvalue` reads a function pointer from, like `i` in `handlers[i]`,
+/// which select the function the pointer calls.
+pub(crate) fn fn_pointer_selectors<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    rvalue: &Rvalue<'tcx>,
+) -> Vec<Local> {
+    let (Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) | Rvalue::CopyForDeref(place)) =
+        rvalue
+    else {
+        return vec![];
+    };
+    if !matches!(place.ty(body, tcx).ty.kind(), TyKind::FnPtr(_)) {
+        return vec![];
+    }
+    place
+        .projection
+        .iter()
+        .filter_map(|elem| match elem {
+            ProjectionElem::Index(local) => Some(local),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The local holding the `Result` whose value `rvalue` reads or borrows, and the variant it holds it in,
+/// like `result` and `Err` in `(result as Err).0` or `&((*result) as Err).0`.
+/// Borrows of the value which may write to it are not reads.
+pub(crate) fn read_result_variant<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    rvalue: &Rvalue<'tcx>,
+) -> Option<(Local, ResultVariant)> {
+    let place = match rvalue {
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+        | Rvalue::Ref(_, BorrowKind::Shared, place)
+        | Rvalue::CopyForDeref(place) => place,
+        _ => return None,
+    };
+    // Only the `Result` a local holds or points to splits its labels, not one held in a field of it.
+    let (base, elem) = place
+        .iter_projections()
+        .find(|(_, elem)| !matches!(elem, ProjectionElem::Deref))?;
+    let ProjectionElem::Downcast(_, variant) = elem else {
+        return None;
+    };
+    let TyKind::Adt(adt, _) = base.ty(body, tcx).ty.kind() else {
+        return None;
+    };
+    if !tcx.is_diagnostic_item(sym::Result, adt.did()) {
+        return None;
+    }
+    let variant = if variant.as_u32() == 0 {
+        ResultVariant::Ok
+    } else {
+        ResultVariant::Err
+    };
+    Some((place.local, variant))
+}
+
+/// One of the two elements of a pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PairElement {
+    First,
+    Second,
+}
+
+/// The two operands of the pair `rvalue` builds, like `a` and `b` in `(a, b)`.
+pub(crate) fn built_pair<'a, 'tcx>(
+    rvalue: &'a Rvalue<'tcx>,
+) -> Option<(&'a Operand<'tcx>, &'a Operand<'tcx>)> {
+    let Rvalue::Aggregate(box AggregateKind::Tuple, operands) = rvalue else {
+        return None;
+    };
+    match &operands.raw[..] {
+        [first, second] => Some((first, second)),
+        _ => None,
+    }
+}
+
+/// The local holding the pair whose element `rvalue` reads or borrows, and which of its elements that is,
+/// like `pair` and the second in `pair.1`. Borrows of the element which may write to it are not reads.
+pub(crate) fn read_pair_element<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    rvalue: &Rvalue<'tcx>,
+) -> Option<(Local, PairElement)> {
+    match rvalue {
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+        | Rvalue::Ref(_, BorrowKind::Shared, place)
+        | Rvalue::CopyForDeref(place) => pair_element(tcx, body, place),
+        _ => None,
+    }
+}
+
+/// The local holding the pair `place` is within an element of, and which of its elements that is,
+/// like `pair` and the first in `pair.0.name`, or `next` and the second in `((next as Some).0).1`,
+/// where `next` is the item an iterator over pairs yielded.
+pub(crate) fn pair_element<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    place: &Place<'tcx>,
+) -> Option<(Local, PairElement)> {
+    // Only the pair a local holds or points to splits its labels, or the one in the `Some` it holds.
+    let mut projections = place
+        .iter_projections()
+        .skip_while(|(_, elem)| matches!(elem, ProjectionElem::Deref));
+    let (mut base, mut elem) = projections.next()?;
+    if let ProjectionElem::Downcast(_, variant) = elem {
+        let TyKind::Adt(adt, _) = base.ty(body, tcx).ty.kind() else {
+            return None;
+        };
+        if !tcx.is_diagnostic_item(sym::Option, adt.did()) || variant.as_u32() != 1 {
+            return None;
+        }
+        let Some((_, ProjectionElem::Field(field, _))) = projections.next() else {
+            return None;
+        };
+        if field.as_u32() != 0 {
+            return None;
+        }
+        (base, elem) = projections.next()?;
+    }
+    let ProjectionElem::Field(field, _) = elem else {
+        return None;
+    };
+    let TyKind::Tuple(elements) = base.ty(body, tcx).ty.kind() else {
+        return None;
+    };
+    match (elements.len(), field.as_u32()) {
+        (2, 0) => Some((place.local, PairElement::First)),
+        (2, 1) => Some((place.local, PairElement::Second)),
+        _ => None,
+    }
+}
+
+/// The local holding the struct `place` is a field of or is within, the index of that field, and how many
+/// fields the struct has, like `request`, 1 and 2 in `request.path.len`. Only a struct the local holds
+/// itself counts, not one it points to.
+pub(crate) fn struct_field<'tcx>(
+    body: &Body<'tcx>,
+    place: &Place<'tcx>,
+) -> Option<(Local, usize, usize)> {
+    let ProjectionElem::Field(field, _) = *place.projection.first()? else {
+        return None;
+    };
+    if place.is_indirect() {
+        return None;
+    }
+    let TyKind::Adt(adt, _) = body.local_decls[place.local].ty.kind() else {
+        return None;
+    };
+    adt.is_struct().then(|| {
+        (
+            place.local,
+            field.index(),
+            adt.non_enum_variant().fields.len(),
+        )
+    })
+}
+
+/// The local holding the struct whose field `rvalue` reads or borrows, the index of that field, and how many
+/// fields the struct has, like `request`, 1 and 2 in `&request.path`. Shared borrows of a field with interior
+/// mutability, like a `Cell`, may write to it, so they are not reads.
+pub(crate) fn read_struct_field<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    rvalue: &Rvalue<'tcx>,
+) -> Option<(Local, usize, usize)> {
+    match rvalue {
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) => struct_field(body, place),
+        Rvalue::Ref(_, BorrowKind::Shared, place)
+            if place
+                .ty(body, tcx)
+                .ty
+                .is_freeze(tcx, ParamEnv::reveal_all()) =>
+        {
+            struct_field(body, place)
+        }
+        _ => None,
+    }
+}
+
+/// The operands `rvalue` gives each field of the struct it builds, in order, like `input` and `path`
+/// in `Request { body: input, path }`.
+pub(crate) fn built_struct<'a, 'tcx>(
+    tcx: TyCtxt<'tcx>,
+    rvalue: &'a Rvalue<'tcx>,
+) -> Option<&'a [Operand<'tcx>]> {
+    let Rvalue::Aggregate(box AggregateKind::Adt(id, _, _, _, None), operands) = rvalue else {
+        return None;
+    };
+    tcx.adt_def(*id).is_struct().then_some(&operands.raw[..])
+}
+
+/// The struct fields an assignment of `rvalue` to `place` writes, each with the operand written into it
+/// if it is not the whole of `rvalue`: `bio` in `user.bio = input`, or every field of `User { bio: input, .. }`
+/// with the operand given for it.
+pub(crate) fn written_fields<'a, 'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    place: &Place<'tcx>,
+    rvalue: &'a Rvalue<'tcx>,
+) -> Vec<(DefId, Option<&'a Operand<'tcx>>)> {
+    let mut fields = place
+        .iter_projections()
+        .filter_map(|(base, elem)| projected_field(tcx, body, base, elem))
+        .last()
+        .map(|field| (field, None))
+        .into_iter()
+        .collect::<Vec<_>>();
+    if let Rvalue::Aggregate(box AggregateKind::Adt(id, variant, _, _, None), operands) = rvalue {
+        let variant = tcx.adt_def(*id).variant(*variant);
+        fields.extend(
+            variant
+                .fields
+                .iter()
+                .zip(operands.iter())
+                .map(|(field, operand)| (field.did, Some(operand))),
+        );
+    }
+    fields
+}
+
+/// The struct field `elem` projects `base` to, if it is a field of a struct or of a known variant.
+fn projected_field<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    base: PlaceRef<'tcx>,
+    elem: PlaceElem<'tcx>,
+) -> Option<DefId> {
+    let ProjectionElem::Field(field, _) = elem else {
+        return None;
+    };
+    let base = base.ty(body, tcx);
+    let TyKind::Adt(adt, _) = base.ty.kind() else {
+        return None;
+    };
+    let variant = match base.variant_index {
+        Some(index) => adt.variant(index),
+        None if adt.is_enum() => return None,
+        None => adt.non_enum_variant(),
+    };
+    Some(variant.fields[field].did)
+}
+
+/// The blocks through which `body` returns to its caller.
+/// Unoptimized MIR keeps blocks which cannot be reached, like the `return` of a function which
+/// always panics, so those are left out.
+pub(crate) fn return_blocks<'a>(body: &'a Body<'a>) -> impl Iterator<Item = BasicBlock> + 'a {
+    reachable_blocks(body).filter(move |&block| {
+        matches!(
+            body.basic_blocks[block].terminator().kind,
+            TerminatorKind::Return
+        )
+    })
+}
+
+/// The names of the arguments of the function `id`, in the order of its MIR, with `_` for those bound by a pattern.
+/// The first argument of a closure is the closure itself, named `self`.
+pub(crate) fn arg_names(tcx: TyCtxt<'_>, id: DefId) -> Vec<String> {
+    let closure = tcx.is_closure(id).then(|| "self".to_owned());
+    let names = tcx
+        .fn_arg_names(id)
+        .iter()
+        .map(|ident| match ident.as_str() {
+            "" => "_".to_owned(),
+            name => name.to_owned(),
+        });
+    closure.into_iter().chain(names).collect()
+}
+
+/// The variable of the source code `place` reads, by name and where it is declared, following
+/// the temporaries the MIR copies, borrows or casts it into, like those made for the arguments of a call.
+/// `None` for temporaries assigned more than once or computed from several values, like `a + b`.
+pub(crate) fn variable_of(body: &Body<'_>, place: &Place<'_>) -> Option<(String, Span)> {
+    let (_, info) = variable(body, place.local, |local| copied_from(body, local))?;
+    Some((info.name.to_string(), info.source_info.span))
+}
+
+/// The name of the variable `local` holds, if it is one.
+pub(crate) fn local_name(body: &Body<'_>, local: Local) -> Option<String> {
+    variable(body, local, |_| None).map(|(_, info)| info.name.to_string())
+}
+
+/// The local of the variable `place` reads, like [`variable_of`], also following the calls
+/// to `Deref::deref` which auto-deref inserts, like the one turning `&input` into a `&str`.
+fn variable_local(tcx: TyCtxt<'_>, body: &Body<'_>, place: &Place<'_>) -> Option<Local> {
+    let step = |local| copied_from(body, local).or_else(|| dereferenced(tcx, body, local));
+    variable(body, place.local, step).map(|(local, _)| local)
+}
+
+/// The local `local` dereferences, if the only assignment to it is a call to `Deref::deref`
+/// or `DerefMut::deref_mut`.
+fn dereferenced(tcx: TyCtxt<'_>, body: &Body<'_>, local: Local) -> Option<Local> {
+    let call = only_call_to(body, local)?;
+    let deref_traits = [
+        tcx.lang_items().deref_trait(),
+        tcx.lang_items().deref_mut_trait(),
+    ];
+    if !deref_traits.contains(&tcx.trait_of_item(call.callee)) {
+        return None;
+    }
+    call.args.first()?.place()?.as_local()
+}
+
+/// The call returning into `local`, if it is the only call which does.
+fn only_call_to<'a, 'tcx>(body: &'a Body<'tcx>, local: Local) -> Option<Call<'a, 'tcx>> {
+    let mut calls = body.basic_blocks.iter().filter_map(|block| {
+        Call::from_terminator(&block.terminator().kind)
+            .filter(|call| call.destination.as_local() == Some(local))
+    });
+    let call = calls.next()?;
+    calls.next().is_none().then_some(call)
+}
+
+fn variable<'a, 'tcx>(
+    body: &'a Body<'tcx>,
+    mut local: Local,
+    step: impl Fn(Local) -> Option<Local>,
+) -> Option<(Local, &'a VarDebugInfo<'tcx>)> {
+    // Each step follows an assignment, so there cannot be more steps than locals.
+    for _ in 0..body.local_decls.len() {
+        let variable = body.var_debug_info.iter().find(|info| {
+            matches!(info.value, VarDebugInfoContents::Place(place)
+                if place.local == local && place.projection.is_empty())
+        });
+        if let Some(info) = variable {
+            return Some((local, info));
+        }
+        local = step(local)?;
+    }
+    None
+}
+
+/// A branch on what a call returned, like `if !is_valid(&input)`.
+#[derive(Debug)]
+pub(crate) struct Condition {
+    pub(crate) callee: DefId,
+    /// The variables the arguments of the call were read from, where they were.
+    pub(crate) variables: Vec<Local>,
+    /// Whether the branch is on the negation of what the call returned.
+    pub(crate) negated: bool,
+}
+
+/// The call whose result `discr`, the operand of a `SwitchInt`, holds or negates, if it is a `bool`
+/// which the MIR only copied, moved or negated since the call returned it.
+pub(crate) fn condition(
+    tcx: TyCtxt<'_>,
+    body: &Body<'_>,
+    discr: &Operand<'_>,
+) -> Option<Condition> {
+    let mut local = discr.place()?.as_local()?;
+    let mut negated = false;
+    // Each step follows an assignment, so there cannot be more steps than locals.
+    for _ in 0..body.local_decls.len() {
+        if !body.local_decls[local].ty.is_bool() {
+            return None;
+        }
+        let mut assignments = body
+            .basic_blocks
+            .iter()
+            .flat_map(|block| &block.statements)
+            .filter_map(|statement| match &statement.kind {
+                StatementKind::Assign(assign) if assign.0.as_local() == Some(local) => {
+                    Some(&assign.1)
+                }
+                _ => None,
+            });
+        let Some(rvalue) = assignments.next() else {
+            let call = only_call_to(body, local)?;
+            return Some(Condition {
+                callee: call.callee,
+                variables: call
+                    .args
+                    .iter()
+                    .filter_map(|arg| variable_local(tcx, body, arg.place().as_ref()?))
+                    .collect(),
+                negated,
+            });
+        };
+        if assignments.next().is_some() {
+            return None;
+        }
+        local = match rvalue {
+            Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) => place.as_local()?,
+            Rvalue::UnaryOp(UnOp::Not, Operand::Copy(place) | Operand::Move(place)) => {
+                negated = !negated;
+                place.as_local()?
+            }
+            _ => return None,
+        };
+    }
+    None
+}
+
+/// The local the only assignment to `local` copies, moves, borrows or casts, if it has one.
+fn copied_from(body: &Body<'_>, local: Local) -> Option<Local> {
+    let mut assignments = body
+        .basic_blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .filter_map(|statement| match &statement.kind {
+            StatementKind::Assign(assign) if assign.0.local == local => Some(&**assign),
+            _ => None,
+        });
+    let (assigned, rvalue) = assignments.next()?;
+    if assignments.next().is_some()
+        || assigned.as_local().is_none()
+        || body.local_kind(local) != LocalKind::Temp
+    {
+        return None;
+    }
+    match rvalue {
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+        | Rvalue::Cast(_, Operand::Copy(place) | Operand::Move(place), _)
+        | Rvalue::Ref(_, _, place)
+        | Rvalue::AddressOf(_, place) => Some(place.local),
+        _ => None,
+    }
+}
+
+/// The local `rvalue` borrows mutably or takes a mutable raw pointer to, which may be written through.
+pub(crate) fn mutable_borrow(rvalue: &Rvalue<'_>) -> Option<Local> {
+    match rvalue {
+        Rvalue::Ref(_, BorrowKind::Mut { .. }, place)
+        | Rvalue::AddressOf(Mutability::Mut, place) => Some(place.local),
+        _ => None,
+    }
+}
+
+/// Whether `local` is a mutable reference or raw pointer, which may be written through.
+pub(crate) fn is_mutable_pointer(body: &Body<'_>, local: Local) -> bool {
+    body.local_decls[local].ty.is_mutable_ptr()
+}
+
+/// The locals `rvalue` reads, including those it indexes with or takes the address of.
+pub(crate) fn used_locals(rvalue: &Rvalue<'_>) -> Vec<Local> {
+    struct Used(Vec<Local>);
+
+    impl Visitor<'_> for Used {
+        fn visit_local(&mut self, local: Local, _: PlaceContext, _: Location) {
+            self.0.push(local);
+        }
+    }
+
+    let mut used = Used(vec![]);
+    used.visit_rvalue(rvalue, Location::START);
+    used.0
+}
+
+/// The places assigning `rvalue` to `place` indexes with a local, like `data[i]`, each with the local
+/// holding the position: the local of the place indexed, and that of the index.
+pub(crate) fn indexed_places(place: &Place<'_>, rvalue: &Rvalue<'_>) -> Vec<(Local, Local)> {
+    struct Indexed(Vec<(Local, Local)>);
+
+    impl<'tcx> Visitor<'tcx> for Indexed {
+        fn visit_place(&mut self, place: &Place<'tcx>, _: PlaceContext, _: Location) {
+            for elem in place.projection {
+                if let ProjectionElem::Index(index) = elem {
+                    self.0.push((place.local, index));
+                }
+            }
+        }
+    }
+
+    let mut indexed = Indexed(vec![]);
+    indexed.visit_place(
+        place,
+        PlaceContext::MutatingUse(MutatingUseContext::Store),
+        Location::START,
+    );
+    indexed.visit_rvalue(rvalue, Location::START);
+    indexed.0
+}
+
+/// `local` and the locals computed from it in `body`, through assignments and the results of calls
+/// taking one of them, like a builder made from a `Formatter`.
+pub(crate) fn derived_locals(body: &Body<'_>, local: Local) -> HashSet<Local> {
+    let mut derived = HashSet::from([local]);
+    loop {
+        let before = derived.len();
+        for data in body.basic_blocks.iter() {
+            for statement in &data.statements {
+                if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+                    if used_locals(rvalue)
+                        .iter()
+                        .any(|used| derived.contains(used))
+                    {
+                        derived.insert(place.local);
+                    }
+                }
+            }
+            if let TerminatorKind::Call {
+                args, destination, ..
+            } = &data.terminator().kind
+            {
+                let takes_derived = args
+                    .iter()
+                    .filter_map(|arg| arg.place())
+                    .any(|place| derived.contains(&place.local));
+                if takes_derived {
+                    derived.insert(destination.local);
+                }
+            }
+        }
+        if derived.len() == before {
+            return derived;
+        }
+    }
+}
+
+/// The blocks which can be reached from the start of `body`.
+pub(crate) fn reachable_blocks<'a>(body: &'a Body<'a>) -> impl Iterator<Item = BasicBlock> + 'a {
+    traversal::reachable(body).map(|(block, _)| block)
+}
+
+/// Calls to trait methods name the method of the trait, not the implementation which is run.
+/// Resolve those to the implementation when it is known, so that its body can be analyzed.
+/// When the implementation does not override a method with a default, that is the default body of the trait.
+/// A function item called through `Fn`, like `lookup` in `ids.map(lookup)`, resolves to the function.
+pub(crate) fn resolve<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    id: DefId,
+    generic_args: GenericArgsRef<'tcx>,
+) -> (DefId, GenericArgsRef<'tcx>) {
+    if let Some((item, args)) = fn_item_call(tcx, id, generic_args) {
+        return resolve(tcx, item, args);
+    }
+    match Instance::resolve(tcx, ParamEnv::reveal_all(), id, generic_args) {
+        Ok(Some(Instance {
+            def: InstanceDef::Item(def_id),
+            args,
+        })) => (def_id, args),
+        _ => (id, generic_args),
+    }
+}
+
+/// The fields of the values of type `ty`, and of the values they own, as a derived `Serialize` impl
+/// reads them: the fields of every variant of the local ADTs in `ty` or in its generic arguments,
+/// and those within the types of these fields in turn. ADTs of other crates only lead to their generic arguments.
+/// The fields it leaves out or redacts, and those within them, are not read.
+pub(crate) fn fields_within<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Vec<DefId> {
+    serialized(tcx, ty).fields
+}
+
+/// What a derived `Serialize` impl writes of a value, as [`serialized`] walks its type.
+pub(crate) struct Serialized<'tcx> {
+    /// The fields read, as [`fields_within`] lists them.
+    pub(crate) fields: Vec<DefId>,
+    /// The types of the values written, `ty` itself and those within the fields read.
+    pub(crate) written: Vec<Ty<'tcx>>,
+    /// The types of the fields left out or redacted.
+    pub(crate) redacted: Vec<Ty<'tcx>>,
+}
+
+/// What serializing a value of type `ty` writes of it, and what it leaves out.
+pub(crate) fn serialized<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Serialized<'tcx> {
+    let mut serialized = Serialized {
+        fields: vec![],
+        written: vec![],
+        redacted: vec![],
+    };
+    let mut seen = HashSet::new();
+    let mut pending = vec![ty];
+    while let Some(ty) = pending.pop() {
+        for ty in ty.walk().filter_map(|arg| arg.as_type()) {
+            serialized.written.push(ty);
+            let TyKind::Adt(adt, args) = ty.kind() else {
+                continue;
+            };
+            if !adt.did().is_local() || !seen.insert(ty) {
+                continue;
+            }
+            for field in adt.all_fields() {
+                if is_redacted(tcx, field.did) {
+                    serialized.redacted.push(field.ty(tcx, args));
+                    continue;
+                }
+                serialized.fields.push(field.did);
+                pending.push(field.ty(tcx, args));
+            }
+        }
+    }
+    serialized
+}
+
+/// Whether serializing the field `id` leaves it out or redacts it: it is marked `#[serde(skip)]`
+/// or `#[serde(skip_serializing)]`, serialized with a function named like `redact`, as with
+/// `#[serde(serialize_with = "redact")]`, or marked with an attribute named `redact` or `redacted`,
+/// like the helper attributes of redaction derives.
+fn is_redacted(tcx: TyCtxt<'_>, id: DefId) -> bool {
+    let is_redaction = |name: &str| name.starts_with("redact");
+    tcx.get_attrs_unchecked(id).iter().any(|attr| {
+        let Some(name) = attr.ident().map(|ident| ident.name) else {
+            return false;
+        };
+        if matches!(name.as_str(), "redact" | "redacted") {
+            return true;
+        }
+        if name.as_str() != "serde" {
+            return false;
+        }
+        attr.meta_item_list()
+            .unwrap_or_default()
+            .iter()
+            .any(
+                |item| match (item.name_or_empty().as_str(), item.value_str()) {
+                    ("skip" | "skip_serializing", None) => item.is_word(),
+                    ("serialize_with" | "with", Some(function)) => {
+                        is_redaction(function.as_str().rsplit("::").next().unwrap_or_default())
+                    }
+                    _ => false,
+                },
+            )
+    })
+}
+
+/// The fields of the types within `ty` which formatting it with `Debug` reads: those of the types of the crate
+/// whose `Debug` impl is derived, and of the types within their fields, through references and collections.
+/// A type of the crate whose `Debug` impl is written by hand may leave its fields out, like a redacting one,
+/// so neither its fields nor the types within them are.
+pub(crate) fn debugged_fields<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Vec<DefId> {
+    let Some(debug) = tcx.get_diagnostic_item(sym::Debug) else {
+        return vec![];
+    };
+    let Some(fmt) = tcx
+        .associated_items(debug)
+        .in_definition_order()
+        .find(|item| item.name == sym::fmt)
+    else {
+        return vec![];
+    };
+    let is_derived = |ty: Ty<'tcx>| {
+        let (method, _) = resolve(tcx, fmt.def_id, tcx.mk_args(&[ty.into()]));
+        method != fmt.def_id && tcx.has_attr(tcx.parent(method), sym::automatically_derived)
+    };
+    let mut fields = vec![];
+    let mut seen = HashSet::new();
+    let mut pending = vec![ty];
+    while let Some(ty) = pending.pop() {
+        for ty in ty.walk().filter_map(|arg| arg.as_type()) {
+            let TyKind::Adt(adt, args) = ty.kind() else {
+                continue;
+            };
+            if !adt.did().is_local() || !seen.insert(ty) || !is_derived(ty) {
+                continue;
+            }
+            for field in adt.all_fields() {
+                fields.push(field.did);
+                pending.push(field.ty(tcx, args));
+            }
+        }
+    }
+    fields
+}
+
+/// The methods of `std::io::Write` a writer of type `ty`, or the writer it points to, writes bytes out with,
+/// `write` and `write_all`, resolved to their impls.
+pub(crate) fn write_methods<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Vec<DefId> {
+    let Some(write) = tcx.get_diagnostic_item(sym::IoWrite) else {
+        return vec![];
+    };
+    let args = tcx.mk_args(&[ty.peel_refs().into()]);
+    tcx.associated_items(write)
+        .in_definition_order()
+        .filter(|item| matches!(item.name.as_str(), "write" | "write_all"))
+        .map(|item| resolve(tcx, item.def_id, args).0)
+        .collect()
+}
+
+/// The closure or generator `rvalue` turns into a trait object, like `Box::new(|request| ...) as Box<dyn Fn(Request)>`
+/// or `Box::pin(fetch(url)) as Pin<Box<dyn Future<Output = String>>>`, with its generic arguments.
+/// A value of an opaque type, like one returned as `impl Fn(Request)`, is the closure or generator behind it.
+pub(crate) fn closure_to_dyn<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    rvalue: &Rvalue<'tcx>,
+) -> Option<(DefId, GenericArgsRef<'tcx>)> {
+    let Rvalue::Cast(CastKind::PointerCoercion(PointerCoercion::Unsize), operand, target) = rvalue
+    else {
+        return None;
+    };
+    let (source, target) = unsized_pointees(operand.ty(body, tcx), *target)?;
+    match (reveal(tcx, source).kind(), target.kind()) {
+        (TyKind::Closure(id, args) | TyKind::Generator(id, args, _), TyKind::Dynamic(..)) => {
+            Some((*id, args))
+        }
+        _ => None,
+    }
+}
+
+/// The types an unsizing cast from `source` to `target` turns one into the other, behind the pointers
+/// they are, or the pointers wrapped in them, like the `Box` of a `Pin<Box<_>>`.
+fn unsized_pointees<'tcx>(source: Ty<'tcx>, target: Ty<'tcx>) -> Option<(Ty<'tcx>, Ty<'tcx>)> {
+    match (source.kind(), target.kind()) {
+        (TyKind::Adt(wrapper, source_args), TyKind::Adt(other, target_args))
+            if wrapper == other && !source.is_box() =>
+        {
+            let (source, target) = source_args
+                .types()
+                .zip(target_args.types())
+                .find(|(source, target)| source != target)?;
+            unsized_pointees(source, target)
+        }
+        _ => Some((
+            source.builtin_deref(true)?.ty,
+            target.builtin_deref(true)?.ty,
+        )),
+    }
+}
+
+/// The type behind `ty` if it is opaque, like the closure a function returning `impl Fn(Request)` returns,
+/// or the generator of an `async fn`. Opaque types may hide others, which are revealed in turn.
+pub(crate) fn reveal<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Ty<'tcx> {
+    let mut ty = ty;
+    while let TyKind::Alias(AliasKind::Opaque, alias) = ty.kind() {
+        ty = tcx.type_of(alias.def_id).instantiate(tcx, alias.args);
+    }
+    ty
+}
+
+/// The type of the output of a future polled through a trait object, like `dyn Future<Output = String>`,
+/// if `id` is `Future::poll` called on one.
+pub(crate) fn dyn_future_poll<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    id: DefId,
+    generic_args: GenericArgsRef<'tcx>,
+) -> Option<Ty<'tcx>> {
+    if tcx.trait_of_item(id) != tcx.lang_items().future_trait() {
+        return None;
+    }
+    match generic_args.type_at(0).kind() {
+        TyKind::Dynamic(predicates, ..) => predicates
+            .projection_bounds()
+            .find_map(|projection| projection.skip_binder().term.ty()),
+        _ => None,
+    }
+}
+
+/// Whether the generator with generic arguments `generator_args` returns values of type `output`,
+/// like an `async fn` whose future is polled through a `dyn Future<Output = ...>`.
+pub(crate) fn generator_returns<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    generator_args: GenericArgsRef<'tcx>,
+    output: Ty<'tcx>,
+) -> bool {
+    tcx.erase_regions(generator_args.as_generator().return_ty()) == tcx.erase_regions(output)
+}
+
+/// The type of the tupled arguments of a call to a trait object like `dyn Fn(Request)`,
+/// if `id` is `Fn::call`, `FnMut::call_mut` or `FnOnce::call_once` called on one.
+pub(crate) fn dyn_closure_call<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    id: DefId,
+    generic_args: GenericArgsRef<'tcx>,
+) -> Option<Ty<'tcx>> {
+    tcx.trait_of_item(id)
+        .and_then(|trait_id| tcx.fn_trait_kind_from_def_id(trait_id))?;
+    match generic_args.type_at(0).kind() {
+        TyKind::Dynamic(..) => Some(generic_args.type_at(1)),
+        _ => None,
+    }
+}
+
+/// The function item and its generic arguments, if `id` is `Fn::call`, `FnMut::call_mut`
+/// or `FnOnce::call_once` called on one, like when a combinator calls the `lookup` of `ids.map(lookup)`.
+/// Unlike closures, function items take the arguments in the tuple one by one.
+pub(crate) fn fn_item_call<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    id: DefId,
+    generic_args: GenericArgsRef<'tcx>,
+) -> Option<(DefId, GenericArgsRef<'tcx>)> {
+    tcx.trait_of_item(id)
+        .and_then(|trait_id| tcx.fn_trait_kind_from_def_id(trait_id))?;
+    match generic_args.type_at(0).kind() {
+        TyKind::FnDef(item, args) => Some((*item, args)),
+        _ => None,
+    }
+}
+
+/// Whether the closure with generic arguments `closure_args` takes arguments of the tuple type `args`.
+pub(crate) fn closure_accepts<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    closure_args: GenericArgsRef<'tcx>,
+    args: Ty<'tcx>,
+) -> bool {
+    let sig = tcx.erase_late_bound_regions(closure_args.as_closure().sig());
+    sig.inputs().first().map_or(false, |&inputs| {
+        tcx.erase_regions(inputs) == tcx.erase_regions(args)
+    })
+}
+
+/// The generic arguments of `id` itself, for analyzing it without knowing what it is instantiated with.
+pub(crate) fn identity_args(tcx: TyCtxt<'_>, id: DefId) -> GenericArgsRef<'_> {
+    GenericArgs::identity_for_item(tcx, id)
+}
+
+/// The generic arguments of a call in a body, once the body's own parameters are replaced by `body_args`.
+/// Inside the default body of a trait method, for example, this tells us what `Self` is.
+pub(crate) fn instantiate<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    generic_args: GenericArgsRef<'tcx>,
+    body_args: GenericArgsRef<'tcx>,
+) -> GenericArgsRef<'tcx> {
+    tcx.try_subst_and_normalize_erasing_regions(
+        body_args,
+        ParamEnv::reveal_all(),
+        EarlyBinder::bind(generic_args),
+    )
+    .unwrap_or(generic_args)
+}
+
+/// The functions of the crate being analyzed which `body` may run: those it calls or names, the closures
+/// it creates, and the destructors of what it drops. A method of a trait stands for all of its implementations
+/// in the crate, since which one runs may only be known to the callers of `body`, or at runtime.
+pub(crate) fn mentioned_fns<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> Vec<DefId> {
+    struct Mentioned<'a, 'tcx> {
+        tcx: TyCtxt<'tcx>,
+        body: &'a Body<'tcx>,
+        fns: Vec<DefId>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for Mentioned<'_, 'tcx> {
+        fn visit_constant(&mut self, constant: &Constant<'tcx>, location: Location) {
+            if let TyKind::FnDef(id, _) = constant.literal.ty().kind() {
+                self.fns.push(*id);
+            }
+            self.super_constant(constant, location);
+        }
+
+        fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+            if let Rvalue::Aggregate(
+                box AggregateKind::Closure(id, _) | box AggregateKind::Generator(id, ..),
+                _,
+            ) = rvalue
+            {
+                self.fns.push(*id);
+            }
+            self.super_rvalue(rvalue, location);
+        }
+
+        fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+            if let TerminatorKind::Drop { place, .. } = &terminator.kind {
+                let ty = place.ty(self.body, self.tcx).ty;
+                self.fns
+                    .extend(destructors_in(self.tcx, ty).into_iter().map(|(id, _)| id));
+            }
+            self.super_terminator(terminator, location);
+        }
+    }
+
+    let mut mentioned = Mentioned {
+        tcx,
+        body,
+        fns: vec![],
+    };
+    mentioned.visit_body(body);
+    let mut fns = vec![];
+    for id in mentioned.fns {
+        let mut implementations = vec![];
+        if let Some(trait_id) = tcx.trait_of_item(id) {
+            implementations
+                .extend(tcx.all_impls(trait_id).filter_map(|impl_id| {
+                    tcx.impl_item_implementor_ids(impl_id).get(&id).copied()
+                }));
+        }
+        for id in implementations.into_iter().chain([id]) {
+            if id.is_local() && !fns.contains(&id) {
+                fns.push(id);
+            }
+        }
+    }
+    fns
+}
+
+/// The functions and statics `body` names, in any crate, like the callees of its calls and the statics it reads.
+pub(crate) fn named_items<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> Vec<DefId> {
+    struct Named<'tcx> {
+        tcx: TyCtxt<'tcx>,
+        items: Vec<DefId>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for Named<'tcx> {
+        fn visit_constant(&mut self, constant: &Constant<'tcx>, location: Location) {
+            if let TyKind::FnDef(id, _) = constant.literal.ty().kind() {
+                self.items.push(*id);
+            }
+            self.items.extend(constant.check_static_ptr(self.tcx));
+            self.super_constant(constant, location);
+        }
+    }
+
+    let mut named = Named { tcx, items: vec![] };
+    named.visit_body(body);
+    named.items
+}
+
+/// The `Drop::drop` methods run when `place` in `body` is dropped, with the generic arguments they are
+/// instantiated with, for the types which implement `Drop` in the crate being analyzed.
+pub(crate) fn local_destructors<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    place: &Place<'tcx>,
+    body_args: GenericArgsRef<'tcx>,
+) -> Vec<(DefId, GenericArgsRef<'tcx>)> {
+    destructors_in(tcx, place_ty(tcx, body, place, body_args))
+}
+
+/// The type of `place` in `body`, instantiated with the generic arguments `body_args` of the function.
+pub(crate) fn place_ty<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    place: &Place<'tcx>,
+    body_args: GenericArgsRef<'tcx>,
+) -> Ty<'tcx> {
+    let ty = place.ty(body, tcx).ty;
+    tcx.try_subst_and_normalize_erasing_regions(
+        body_args,
+        ParamEnv::reveal_all(),
+        EarlyBinder::bind(ty),
+    )
+    .unwrap_or(ty)
+}
+
+/// The `Drop::drop` methods the drop glue of `ty` runs which are defined in the crate being analyzed:
+/// the one of `ty` itself, and those of the values it owns, like its fields or the elements of a collection.
+/// Values behind references and in `ManuallyDrop` are not dropped with it.
+///
+/// Destructors from other crates, like the one of `Vec`, are left out:
+/// drops are everywhere, and analyzing those for every one of them would be too slow.
+/// The values a type of another crate owns are taken to be those of its type parameters.
+pub(crate) fn destructors_in<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    ty: Ty<'tcx>,
+) -> Vec<(DefId, GenericArgsRef<'tcx>)> {
+    let mut destructors = vec![];
+    let mut seen = vec![];
+    let mut pending = vec![ty];
+    while let Some(ty) = pending.pop() {
+        if seen.contains(&ty) {
+            continue;
+        }
+        seen.push(ty);
+        match ty.kind() {
+            TyKind::Adt(adt, _) if adt.is_manually_drop() => {}
+            TyKind::Adt(adt, args) => {
+                // An implementation of `Drop` has exactly the generic parameters of the type.
+                if let Some(destructor) = tcx.adt_destructor(adt.did()) {
+                    if destructor.did.is_local() {
+                        destructors.push((destructor.did, *args));
+                    }
+                }
+                if adt.did().is_local() {
+                    pending.extend(adt.all_fields().map(|field| field.ty(tcx, args)));
+                } else {
+                    pending.extend(args.types());
+                }
+            }
+            TyKind::Tuple(tys) => pending.extend(tys.iter()),
+            TyKind::Array(element, _) | TyKind::Slice(element) => pending.push(*element),
+            TyKind::Closure(_, args) => pending.extend(args.as_closure().upvar_tys()),
+            _ => {}
+        }
+    }
+    destructors
+}
+
+/// A function whose body the compiler generates instead of taking it from the source,
+/// which `resolve` leaves as the function named in the source.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Shim<'tcx> {
+    /// `Clone::clone` of a type whose implementation is built in, like tuples, arrays and closures,
+    /// which clones each field of what its argument points to.
+    Clone,
+    /// `ptr::drop_in_place`, which drops what its argument points to, of type `Ty`,
+    /// or nothing which needs dropping if `None`.
+    DropGlue(Option<Ty<'tcx>>),
+    /// `Fn::call`, `FnMut::call_mut` or `FnOnce::call_once` of a function pointer,
+    /// whose callee is not known. Function items are called through them too, but resolve to themselves.
+    FnPtr,
+}
+
+/// The shim a call to `id` with `generic_args` runs, if it runs one.
+pub(crate) fn shim<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    id: DefId,
+    generic_args: GenericArgsRef<'tcx>,
+) -> Option<Shim<'tcx>> {
+    match Instance::resolve(tcx, ParamEnv::reveal_all(), id, generic_args) {
+        Ok(Some(instance)) => match instance.def {
+            InstanceDef::CloneShim(..) => Some(Shim::Clone),
+            InstanceDef::DropGlue(_, ty) => Some(Shim::DropGlue(ty)),
+            InstanceDef::FnPtrShim(..) if fn_item_call(tcx, id, generic_args).is_none() => {
+                Some(Shim::FnPtr)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A call to a statically known function.
+#[derive(Debug)]
+pub(crate) struct Call<'a, 'tcx> {
+    pub(crate) callee: DefId,
+    pub(crate) generic_args: GenericArgsRef<'tcx>,
+    /// The callee as written in the MIR, for use in diagnostics.
+    pub(crate) name: String,
+    pub(crate) args: &'a [Operand<'tcx>],
+    pub(crate) destination: &'a Place<'tcx>,
+    pub(crate) span: Span,
+}
+
+impl<'a, 'tcx> Call<'a, 'tcx> {
+    /// Calls through function pointers and other indirect calls have no statically known callee.
+    pub(crate) fn from_terminator(kind: &'a TerminatorKind<'tcx>) -> Option<Self> {
+        match kind {
+            TerminatorKind::Call {
+                func: Operand::Constant(func),
+                args,
+                destination,
+                fn_span,
+                ..
+            } => match func.literal.ty().kind() {
+                TyKind::FnDef(callee, generic_args) => Some(Call {
+                    callee: *callee,
+                    generic_args,
+                    name: func.to_string(),
+                    args,
+                    destination,
+                    span: *fn_span,
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! The crates the analyzed crate depends on, and where their files are.
+
+use std::path::PathBuf;
+
+use rustc_hir::def_id::{CrateNum, DefIndex};
+use rustc_metadata::creader::CStore;
+use rustc_session::{config::CrateType, config::OutFileName, output};
+
+use super::{DefId, TyCtxt, LOCAL_CRATE};
+
+/// The metadata of `krate`: the file the compiler writes it to for the crate being compiled, if it is a library,
+/// or the file it was loaded from for another crate. Files derived from a crate's metadata are named after it.
+pub(crate) fn metadata_path(tcx: TyCtxt<'_>, krate: CrateNum) -> Option<PathBuf> {
+    if krate != LOCAL_CRATE {
+        let source = tcx.used_crate_source(krate);
+        return source.paths().next().cloned();
+    }
+    let is_library = tcx
+        .crate_types()
+        .iter()
+        .any(|ty| matches!(ty, CrateType::Rlib | CrateType::Dylib));
+    if !is_library {
+        return None;
+    }
+    let outputs = tcx.output_filenames(());
+    match output::filename_for_metadata(tcx.sess, tcx.crate_name(LOCAL_CRATE), outputs) {
+        OutFileName::Real(path) => Some(path),
+        OutFileName::Stdout => None,
+    }
+}
+
+/// Every definition of the crate `krate` other than the one being compiled, including private ones,
+/// which its metadata has no other way to list.
+pub(crate) fn crate_items(tcx: TyCtxt<'_>, krate: CrateNum) -> Vec<DefId> {
+    let count = CStore::from_tcx(tcx).num_def_ids_untracked(krate);
+    (0..count)
+        .map(|index| DefId {
+            krate,
+            index: DefIndex::from_usize(index),
+        })
+        .collect()
+}
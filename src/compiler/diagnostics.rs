@@ -0,0 +1,256 @@
+//! Reporting errors through the compiler's diagnostics machinery.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rustc_errors::{Diagnostic, DiagnosticId};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+
+use crate::error::Error;
+
+/// The error codes the analysis reports under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorCode {
+    /// A sink received tainted input.
+    T0001,
+    /// A `taint` attribute we do not recognize.
+    T0002,
+    /// A sink received input which did not pass through the sanitizer it requires.
+    T0003,
+    /// A function was given more than one role.
+    T0004,
+    /// A sanitizer received input which did not pass through the sanitizer it must come after.
+    T0005,
+    /// An unsafe function received tainted input, with the `unsafe` sink pack.
+    /// It is reported as a warning.
+    T0006,
+    /// A gated sink received tainted input at a call which does not acknowledge it.
+    T0007,
+    /// A sanitizer is only called with input which is never tainted, with `--taint-lint-clean-sanitizers`.
+    /// It is reported as a warning.
+    T0008,
+    /// Tainted input stored into a field marked `#[taint::storage]` crosses a boundary with a value holding the field.
+    /// It is reported as a warning.
+    T0009,
+    /// A function marked `#[taint::requires_taint]` is called with input which is never tainted.
+    T0010,
+    /// A function pointer carrying tainted data is called.
+    T0011,
+    /// A construct of MIR the analysis does not model, with `--taint-strict-mir`.
+    T0012,
+    /// A value carrying a label whose clones the config tracks, with its `clones`, is cloned.
+    /// It is reported as a warning.
+    T0013,
+    /// A secret created in a function is dropped without being zeroized, with `--taint-lint-unzeroized-secrets`.
+    /// It is reported as a warning.
+    T0014,
+}
+
+impl ErrorCode {
+    pub(crate) const ALL: [ErrorCode; 14] = [
+        ErrorCode::T0001,
+        ErrorCode::T0002,
+        ErrorCode::T0003,
+        ErrorCode::T0004,
+        ErrorCode::T0005,
+        ErrorCode::T0006,
+        ErrorCode::T0007,
+        ErrorCode::T0008,
+        ErrorCode::T0009,
+        ErrorCode::T0010,
+        ErrorCode::T0011,
+        ErrorCode::T0012,
+        ErrorCode::T0013,
+        ErrorCode::T0014,
+    ];
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::T0001 => "T0001",
+            ErrorCode::T0002 => "T0002",
+            ErrorCode::T0003 => "T0003",
+            ErrorCode::T0004 => "T0004",
+            ErrorCode::T0005 => "T0005",
+            ErrorCode::T0006 => "T0006",
+            ErrorCode::T0007 => "T0007",
+            ErrorCode::T0008 => "T0008",
+            ErrorCode::T0009 => "T0009",
+            ErrorCode::T0010 => "T0010",
+            ErrorCode::T0011 => "T0011",
+            ErrorCode::T0012 => "T0012",
+            ErrorCode::T0013 => "T0013",
+            ErrorCode::T0014 => "T0014",
+        }
+    }
+
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            ErrorCode::T0001 => "a sink received tainted input",
+            ErrorCode::T0002 => "a `taint` attribute is invalid",
+            ErrorCode::T0003 => {
+                "a sink received input which did not pass through the sanitizer it requires"
+            }
+            ErrorCode::T0004 => "a function was given more than one role",
+            ErrorCode::T0005 => {
+                "a sanitizer received input which did not pass through the sanitizer it must come after"
+            }
+            ErrorCode::T0006 => "an unsafe function received tainted input",
+            ErrorCode::T0007 => {
+                "a gated sink received tainted input at a call which does not acknowledge it"
+            }
+            ErrorCode::T0008 => "a sanitizer is only called with input which is never tainted",
+            ErrorCode::T0009 => "tainted input stored into a field crosses a boundary",
+            ErrorCode::T0010 => {
+                "a function requiring tainted input is called with input which is never tainted"
+            }
+            ErrorCode::T0011 => "a function pointer carrying tainted data is called",
+            ErrorCode::T0012 => "a construct of MIR is not modeled by the analysis",
+            ErrorCode::T0013 => "a value carrying a label whose clones are tracked is cloned",
+            ErrorCode::T0014 => {
+                "a secret created in a function is dropped without being zeroized"
+            }
+        }
+    }
+
+    /// The code written like `T0001`, in any case.
+    pub(crate) fn from_code(code: &str) -> Option<ErrorCode> {
+        ErrorCode::ALL
+            .iter()
+            .copied()
+            .find(|known| known.as_str().eq_ignore_ascii_case(code))
+    }
+
+    /// What the code means, with examples and how to fix it, in Markdown, printed by `taint --explain`.
+    /// Each explanation is kept in a file of `error_codes/`, like rustc's index of error codes.
+    pub(crate) fn explanation(self) -> &'static str {
+        match self {
+            ErrorCode::T0001 => include_str!("error_codes/T0001.md"),
+            ErrorCode::T0002 => include_str!("error_codes/T0002.md"),
+            ErrorCode::T0003 => include_str!("error_codes/T0003.md"),
+            ErrorCode::T0004 => include_str!("error_codes/T0004.md"),
+            ErrorCode::T0005 => include_str!("error_codes/T0005.md"),
+            ErrorCode::T0006 => include_str!("error_codes/T0006.md"),
+            ErrorCode::T0007 => include_str!("error_codes/T0007.md"),
+            ErrorCode::T0008 => include_str!("error_codes/T0008.md"),
+            ErrorCode::T0009 => include_str!("error_codes/T0009.md"),
+            ErrorCode::T0010 => include_str!("error_codes/T0010.md"),
+            ErrorCode::T0011 => include_str!("error_codes/T0011.md"),
+            ErrorCode::T0012 => include_str!("error_codes/T0012.md"),
+            ErrorCode::T0013 => include_str!("error_codes/T0013.md"),
+            ErrorCode::T0014 => include_str!("error_codes/T0014.md"),
+        }
+    }
+
+    /// Whether findings under this code are reported as warnings, which do not fail the build.
+    pub(crate) fn is_warning(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::T0006
+                | ErrorCode::T0008
+                | ErrorCode::T0009
+                | ErrorCode::T0013
+                | ErrorCode::T0014
+        )
+    }
+}
+
+/// The warnings emitted so far by the analysis in this process, for the status line of each run.
+static WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of warnings emitted so far, which the compiler does not tell.
+pub(crate) fn warnings_emitted() -> usize {
+    WARNINGS.load(Ordering::Relaxed)
+}
+
+/// Emits diagnostics for the crate being analyzed.
+#[derive(Clone, Copy)]
+pub(crate) struct Diagnostics<'tcx> {
+    tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> Diagnostics<'tcx> {
+    pub(crate) fn new(tcx: TyCtxt<'tcx>) -> Self {
+        Diagnostics { tcx }
+    }
+
+    pub(crate) fn error(&self, span: Span, code: ErrorCode, message: String) {
+        self.tcx
+            .sess
+            .struct_span_err_with_code(span, message, DiagnosticId::Error(code.as_str().to_owned()))
+            .emit();
+    }
+
+    /// An error with a note pointing at another span, like where something was declared before.
+    pub(crate) fn error_with_note(
+        &self,
+        span: Span,
+        code: ErrorCode,
+        message: String,
+        note_span: Span,
+        note: &str,
+    ) {
+        self.tcx
+            .sess
+            .struct_span_err_with_code(span, message, DiagnosticId::Error(code.as_str().to_owned()))
+            .span_note(note_span, note.to_owned())
+            .emit();
+    }
+
+    /// An error with notes, each pointing at a span if it has one.
+    pub(crate) fn error_with_notes(
+        &self,
+        span: Span,
+        code: ErrorCode,
+        message: String,
+        notes: &[(Option<Span>, String)],
+    ) {
+        let mut diagnostic = self.tcx.sess.struct_span_err_with_code(
+            span,
+            message,
+            DiagnosticId::Error(code.as_str().to_owned()),
+        );
+        add_notes(&mut diagnostic, notes);
+        diagnostic.emit();
+    }
+
+    /// A warning with notes, like `error_with_notes`.
+    pub(crate) fn warning_with_notes(
+        &self,
+        span: Span,
+        code: ErrorCode,
+        message: String,
+        notes: &[(Option<Span>, String)],
+    ) {
+        let mut diagnostic = self.tcx.sess.struct_span_warn_with_code(
+            span,
+            message,
+            DiagnosticId::Error(code.as_str().to_owned()),
+        );
+        add_notes(&mut diagnostic, notes);
+        diagnostic.emit();
+        WARNINGS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A warning which is not about a span of the analyzed code, with notes.
+    pub(crate) fn warning(&self, message: String, notes: &[(Option<Span>, String)]) {
+        let mut diagnostic = self.tcx.sess.struct_warn(message);
+        add_notes(&mut diagnostic, notes);
+        diagnostic.emit();
+        WARNINGS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// An error which is not about the analyzed code, like failing to write a report.
+    pub(crate) fn failure(&self, error: &Error) {
+        self.tcx.sess.err(error.to_string());
+    }
+}
+
+/// Adds notes to a diagnostic, each pointing at a span if it has one.
+fn add_notes(diagnostic: &mut Diagnostic, notes: &[(Option<Span>, String)]) {
+    for (note_span, note) in notes {
+        match note_span {
+            Some(note_span) => diagnostic.span_note(*note_span, note.clone()),
+            None => diagnostic.note(note.clone()),
+        };
+    }
+}
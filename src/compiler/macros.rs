@@ -0,0 +1,60 @@
+//! Looking through macro expansions.
+
+use rustc_span::{hygiene::MacroKind, ExpnId, ExpnKind, Span};
+
+use super::{DefId, TyCtxt};
+
+/// The outermost invocation of one of the bang macros `names` that `span` was expanded from,
+/// with the name it was matched by.
+///
+/// Macros are matched by their name, like `println`, or by their path, like `tracing::info`.
+pub(crate) fn macro_call_site<'a>(
+    tcx: TyCtxt<'_>,
+    span: Span,
+    names: &'a [String],
+) -> Option<(&'a str, Span)> {
+    let mut found = None;
+    for expansion in span.macro_backtrace() {
+        let ExpnKind::Macro(MacroKind::Bang, name) = expansion.kind else {
+            continue;
+        };
+        let path = expansion.macro_def_id.map(|id| tcx.def_path_str(id));
+        let matched = names
+            .iter()
+            .find(|&n| n.as_str() == name.as_str() || Some(n) == path.as_ref());
+        if let Some(matched) = matched {
+            found = Some((matched.as_str(), expansion.call_site));
+        }
+    }
+    found
+}
+
+/// The name of the outermost bang macro `span` was expanded from, like `format`, and the span of its invocation.
+pub(crate) fn outermost_macro(span: Span) -> Option<(String, Span)> {
+    span.macro_backtrace()
+        .filter_map(|expansion| match expansion.kind {
+            ExpnKind::Macro(MacroKind::Bang, name) => Some((name.to_string(), expansion.call_site)),
+            _ => None,
+        })
+        .last()
+}
+
+/// The attribute macros whose expansions defined `id`, innermost first, like `wasm_bindgen`
+/// for a function it was put on, or for the methods of an impl it was put on.
+/// Items the macros generated themselves, whose spans point into the expansion, have none,
+/// so that only the items written in the source are found.
+pub(crate) fn defining_attribute_macros(tcx: TyCtxt<'_>, id: DefId) -> Vec<String> {
+    if tcx.def_span(id).from_expansion() {
+        return vec![];
+    }
+    let mut names = vec![];
+    let mut expansion = tcx.expn_that_defined(id);
+    while expansion != ExpnId::root() {
+        let data = expansion.expn_data();
+        if let ExpnKind::Macro(MacroKind::Attr, name) = data.kind {
+            names.push(name.to_string());
+        }
+        expansion = data.parent;
+    }
+    names
+}
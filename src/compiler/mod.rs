@@ -0,0 +1,68 @@
+//! Everything the analysis needs from the compiler, in one place.
+//!
+//! The rest of the crate only names compiler internals through this module,
+//! so that a toolchain bump means fixing up this module rather than the analysis itself.
+//! What the analysis does with MIR goes through the functions of the submodules; the types it works on,
+//! like `Body` and `Local`, are re-exported as they are. The taint domain names none of them.
+
+mod body;
+mod crates;
+mod diagnostics;
+mod macros;
+mod spans;
+mod stages;
+mod tests;
+
+pub(crate) mod stable;
+
+pub(crate) use body::{
+    arg_names, built_pair, built_result_variant, built_struct, closure_accepts, closure_to_dyn,
+    condition, constant_value, debugged_fields, derived_locals, destructors_in, dyn_closure_call,
+    dyn_future_poll, fields_within, fn_item_call, fn_pointer_selectors, generator_returns,
+    identity_args, indexed_places, initializer_closures, instantiate, is_fn, is_heap_pointer,
+    is_mutable_pointer, is_naked, is_pointer, local_destructors, local_name, mentioned_fns,
+    mir_body, mir_not_encoded, mutable_borrow, named_items, pair_element, place_ty, pointee_ty,
+    reachable_blocks, read_fields, read_pair_element, read_result_variant, read_struct_field,
+    reified_fn, resolve, return_blocks, reveal, serialized, shim, static_behind, static_ref,
+    struct_field, untracked_locals, used_locals, variable_of, write_methods, writes_part,
+    written_fields, zero_sized_locals, Call, PairElement, ResultVariant, Serialized, Shim,
+};
+pub(crate) use crates::{crate_items, metadata_path};
+pub(crate) use diagnostics::{warnings_emitted, Diagnostics, ErrorCode};
+pub(crate) use macros::{defining_attribute_macros, macro_call_site, outermost_macro};
+pub(crate) use spans::{
+    is_current, is_local, item_lines, line_comments, source_lines, source_range, span_at, stamp,
+    SourceRange, Stamp,
+};
+pub(crate) use stages::override_queries;
+pub(crate) use tests::{is_test_code, test_functions};
+
+pub(crate) use rustc_ast::{
+    token::{Delimiter, LitKind, Token, TokenKind},
+    tokenstream::{TokenStream, TokenTree},
+    AttrArgs, AttrItem, AttrKind, Attribute,
+};
+pub(crate) use rustc_errors::ErrorGuaranteed;
+pub(crate) use rustc_hir::{
+    self as hir,
+    def::DefKind,
+    def_id::{CrateNum, DefId, DefPathHash, LOCAL_CRATE},
+    intravisit::Visitor as HirVisitor,
+};
+pub(crate) use rustc_index::{Idx, IndexVec};
+pub(crate) use rustc_interface::interface::Config;
+pub(crate) use rustc_middle::query::{ExternProviders, Providers};
+pub(crate) use rustc_middle::{
+    mir::{
+        visit::Visitor as MirVisitor, BasicBlock, BinOp, Body, CastKind, HasLocalDecls,
+        InlineAsmOperand, Local, Location, NonDivergingIntrinsic, Operand, Place, Rvalue,
+        Statement, StatementKind, Terminator, TerminatorKind, RETURN_PLACE,
+    },
+    ty::{GenericArgsRef, Ty, TyCtxt, TyKind},
+};
+pub(crate) use rustc_mir_dataflow::{
+    fmt::DebugWithContext, lattice::JoinSemiLattice, Analysis, AnalysisDomain, CallReturnPlaces,
+    Forward, ResultsCursor, SwitchIntEdgeEffects,
+};
+pub(crate) use rustc_session::Session;
+pub(crate) use rustc_span::{Span, Symbol};
@@ -0,0 +1,185 @@
+//! Source locations of spans.
+
+use std::{ops::RangeInclusive, path::PathBuf};
+
+use rustc_data_structures::svh::Svh;
+use rustc_span::{BytePos, FileName, SourceFileHash, Span};
+
+use super::{DefId, TyCtxt};
+use crate::query::Position;
+
+/// The file a span is in, and where in it the span starts and ends.
+#[derive(Debug)]
+pub(crate) struct SourceRange {
+    pub(crate) file: String,
+    pub(crate) start: Position,
+    pub(crate) end: Position,
+}
+
+/// What a change to the definition of a function shows in: the source file it is defined in,
+/// by the hash of its contents, or for a function of another crate, the hash of that crate.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Stamp {
+    File(FileName, SourceFileHash),
+    Crate(Svh),
+}
+
+/// A comment on a line of its own in the crate being compiled, which starts with a marker.
+#[derive(Debug)]
+pub(crate) struct LineComment {
+    /// The comment, from `//` to the end of the line.
+    pub(crate) span: Span,
+    /// What follows the marker, trimmed.
+    pub(crate) text: String,
+    /// The line right below the comment, if there is one.
+    pub(crate) below: Option<Span>,
+}
+
+/// The comments like `// taint-ignore: ...` for `marker` `taint-ignore:`, in every file of the crate.
+pub(crate) fn line_comments(tcx: TyCtxt<'_>, marker: &str) -> Vec<LineComment> {
+    let mut comments = vec![];
+    for file in tcx.sess.source_map().files().iter() {
+        if file.is_imported() || file.src.is_none() {
+            continue;
+        }
+        for index in 0..file.count_lines() {
+            let Some(line) = file.get_line(index) else {
+                continue;
+            };
+            let comment = line.trim_start();
+            let Some(text) = comment
+                .strip_prefix("//")
+                .map(str::trim_start)
+                .and_then(|rest| rest.strip_prefix(marker))
+            else {
+                continue;
+            };
+            let bounds = file.line_bounds(index);
+            let start = bounds.start + BytePos((line.len() - comment.len()) as u32);
+            let end = bounds.start + BytePos(line.trim_end().len() as u32);
+            let below = (index + 1 < file.count_lines()).then(|| {
+                let below = file.line_bounds(index + 1);
+                Span::with_root_ctxt(below.start, below.end)
+            });
+            comments.push(LineComment {
+                span: Span::with_root_ctxt(start, end),
+                text: text.trim().to_owned(),
+                below,
+            });
+        }
+    }
+    comments
+}
+
+/// The stamp of the definition of `id`.
+pub(crate) fn stamp(tcx: TyCtxt<'_>, id: DefId) -> Stamp {
+    if !id.is_local() {
+        return Stamp::Crate(tcx.crate_hash(id.krate));
+    }
+    let file = tcx
+        .sess
+        .source_map()
+        .lookup_source_file(tcx.def_span(id).lo());
+    Stamp::File(file.name.clone(), file.src_hash)
+}
+
+/// Whether what `stamp` was taken from is unchanged in the session of `tcx`.
+pub(crate) fn is_current(tcx: TyCtxt<'_>, stamp: &Stamp) -> bool {
+    match stamp {
+        Stamp::File(name, hash) => tcx
+            .sess
+            .source_map()
+            .get_source_file(name)
+            .map_or(false, |file| file.src_hash == *hash),
+        Stamp::Crate(hash) => tcx
+            .crates(())
+            .iter()
+            .any(|&krate| tcx.crate_hash(krate) == *hash),
+    }
+}
+
+/// The file the local function or closure `id` is defined in, and the lines of its definition,
+/// from its attributes to the end of its body.
+pub(crate) fn item_lines(tcx: TyCtxt<'_>, id: DefId) -> Option<(PathBuf, RangeInclusive<usize>)> {
+    let hir_id = tcx.hir().local_def_id_to_hir_id(id.as_local()?);
+    let span = tcx
+        .hir()
+        .attrs(hir_id)
+        .iter()
+        .fold(tcx.hir().span_with_body(hir_id), |span, attr| {
+            span.to(attr.span)
+        });
+    let source_map = tcx.sess.source_map();
+    let start = source_map.lookup_char_pos(span.lo());
+    let end = source_map.lookup_char_pos(span.hi());
+    let FileName::Real(name) = &start.file.name else {
+        return None;
+    };
+    Some((
+        name.local_path_if_available().to_owned(),
+        start.line..=end.line,
+    ))
+}
+
+/// Whether `span` points into the crate being compiled, rather than into a dependency.
+pub(crate) fn is_local(tcx: TyCtxt<'_>, span: Span) -> bool {
+    !tcx.sess.source_map().is_imported(span)
+}
+
+/// The lines `span` covers, by their number, with their text.
+pub(crate) fn source_lines(tcx: TyCtxt<'_>, span: Span) -> Vec<(usize, String)> {
+    let Ok(lines) = tcx.sess.source_map().span_to_lines(span) else {
+        return vec![];
+    };
+    lines
+        .lines
+        .iter()
+        .filter_map(|line| {
+            let text = lines.file.get_line(line.line_index)?;
+            Some((line.line_index + 1, text.into_owned()))
+        })
+        .collect()
+}
+
+/// The span from `start` to `end` of `file`, like [`source_range`] gives them, if the file is part of the session.
+pub(crate) fn span_at(tcx: TyCtxt<'_>, file: &str, start: Position, end: Position) -> Option<Span> {
+    let source_map = tcx.sess.source_map();
+    let file = source_map.files().iter().find(|source| {
+        matches!(&source.name, FileName::Real(name) if name.local_path_if_available().display().to_string() == file)
+    })?.clone();
+    let position = |position: Position| {
+        let index = position.line.checked_sub(1)?;
+        let line = file.get_line(index)?;
+        // Columns count characters rather than bytes.
+        let column = line
+            .char_indices()
+            .nth(position.column.saturating_sub(1))
+            .map_or(line.len(), |(ix, _)| ix);
+        Some(file.line_bounds(index).start + BytePos(column as u32))
+    };
+    Some(Span::with_root_ctxt(position(start)?, position(end)?))
+}
+
+pub(crate) fn source_range(tcx: TyCtxt<'_>, span: Span) -> Option<SourceRange> {
+    if span.is_dummy() {
+        return None;
+    }
+    let source_map = tcx.sess.source_map();
+    let start = source_map.lookup_char_pos(span.lo());
+    let end = source_map.lookup_char_pos(span.hi());
+    let file = match &start.file.name {
+        FileName::Real(name) => name.local_path_if_available().display().to_string(),
+        _ => return None,
+    };
+    Some(SourceRange {
+        file,
+        start: Position {
+            line: start.line,
+            column: start.col.0 + 1,
+        },
+        end: Position {
+            line: end.line,
+            column: end.col.0 + 1,
+        },
+    })
+}
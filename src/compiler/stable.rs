@@ -0,0 +1,24 @@
+//! Stable MIR, the representation of MIR which is meant to stay the same across toolchains.
+//!
+//! The analysis does not run on it yet: a call only names its callee as a string, and nothing carries a span.
+//! Until it can, functions are lowered from both representations and compared,
+//! with `--taint-debug-stable-mir`, so that the migration does not change what the analysis sees.
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+
+pub(crate) use rustc_smir::stable_mir::mir::{Body, CastKind, Operand, Place, Rvalue, Statement};
+#[cfg(test)]
+pub(crate) use rustc_smir::stable_mir::{mir::NullOp, ty::Ty};
+
+/// The optimized MIR of the function `id` in both representations, if it has any.
+/// Stable MIR is only ever built from optimized MIR.
+pub(crate) fn bodies(tcx: TyCtxt<'_>, id: DefId) -> Option<(&super::Body<'_>, Body)> {
+    if !tcx.def_kind(id).is_fn_like() || !tcx.is_mir_available(id) || super::is_naked(tcx, id) {
+        return None;
+    }
+    let mut stable = None;
+    rustc_internal::run(tcx, || stable = Some(rustc_internal::crate_item(id).body()));
+    Some((tcx.optimized_mir(id), stable?))
+}
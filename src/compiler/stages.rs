@@ -0,0 +1,81 @@
+//! Analyzing MIR from before it was optimized, for `--taint-mir`.
+//!
+//! The compiler steals the MIR of each stage to build the next one, so by the time we analyze,
+//! only optimized MIR is left. To analyze an earlier stage, we replace the query which builds it
+//! with one that also keeps a copy of what it built.
+
+use std::{cell::RefCell, collections::HashMap};
+
+use rustc_data_structures::steal::Steal;
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_index::IndexVec;
+use rustc_interface::DEFAULT_QUERY_PROVIDERS;
+use rustc_middle::{
+    mir::{Body, Promoted},
+    query::{ExternProviders, Providers},
+    ty::TyCtxt,
+};
+use rustc_session::Session;
+
+use crate::options::Mir;
+
+thread_local! {
+    /// The bodies kept from before they were stolen, allocated in the arena of the type context.
+    /// Their lifetime is erased, since a thread local cannot name the lifetime of the context.
+    static KEPT: RefCell<HashMap<LocalDefId, &'static Body<'static>>> = RefCell::new(HashMap::new());
+}
+
+/// The queries to replace so that the bodies of `stage` are kept, if they would be stolen.
+pub(crate) fn override_queries(
+    stage: Mir,
+) -> Option<fn(&Session, &mut Providers, &mut ExternProviders)> {
+    // The queries are replaced once per session, so that is when the bodies of the last one are forgotten,
+    // for when several crates are analyzed in the same process.
+    match stage {
+        Mir::Built => Some(|_, providers, _| {
+            forget_kept();
+            providers.mir_built = keep_built
+        }),
+        Mir::Promoted => Some(|_, providers, _| {
+            forget_kept();
+            providers.mir_promoted = keep_promoted
+        }),
+        Mir::Optimized => None,
+    }
+}
+
+fn forget_kept() {
+    KEPT.with(|kept| kept.borrow_mut().clear());
+}
+
+/// The body kept for `id`, if the bodies of an earlier stage are kept and `id` is in the crate being analyzed.
+/// The type context is only taken for the lifetime of the body.
+pub(crate) fn kept_body<'tcx>(_tcx: TyCtxt<'tcx>, id: DefId) -> Option<&'tcx Body<'tcx>> {
+    let id = id.as_local()?;
+    let body = KEPT.with(|kept| kept.borrow().get(&id).copied())?;
+    // SAFETY: the body was allocated in the arena of the type context of the current session,
+    // since the bodies of earlier ones were forgotten, and its lifetime was erased in `keep`.
+    Some(unsafe { std::mem::transmute::<&'static Body<'static>, &'tcx Body<'tcx>>(body) })
+}
+
+fn keep_built(tcx: TyCtxt<'_>, id: LocalDefId) -> &Steal<Body<'_>> {
+    let built = (DEFAULT_QUERY_PROVIDERS.mir_built)(tcx, id);
+    keep(tcx, id, &built.borrow());
+    built
+}
+
+fn keep_promoted(
+    tcx: TyCtxt<'_>,
+    id: LocalDefId,
+) -> (&Steal<Body<'_>>, &Steal<IndexVec<Promoted, Body<'_>>>) {
+    let promoted = (DEFAULT_QUERY_PROVIDERS.mir_promoted)(tcx, id);
+    keep(tcx, id, &promoted.0.borrow());
+    promoted
+}
+
+fn keep<'tcx>(tcx: TyCtxt<'tcx>, id: LocalDefId, body: &Body<'tcx>) {
+    let body: &'tcx Body<'tcx> = tcx.arena.alloc(body.clone());
+    // SAFETY: only read back by `kept_body`, with the lifetime of the same `tcx`.
+    let body = unsafe { std::mem::transmute::<&'tcx Body<'tcx>, &'static Body<'static>>(body) };
+    KEPT.with(|kept| kept.borrow_mut().insert(id, body));
+}
@@ -0,0 +1,72 @@
+//! Code which only exists to test the crate, when it is compiled with `--test`.
+
+use rustc_hir::def::DefKind;
+use rustc_span::{def_id::DefId, Symbol};
+
+use super::TyCtxt;
+
+/// The attribute `#[test]` puts on the descriptor of a test, a constant named after the test function.
+const TEST_MARKER: &str = "rustc_test_marker";
+
+/// Whether `id` is part of the test harness, a test function, or inside an item under `#[cfg(test)]`,
+/// like the usual `mod tests`.
+pub(crate) fn is_test_code(tcx: TyCtxt<'_>, id: DefId) -> bool {
+    if !tcx.sess.opts.test {
+        return false;
+    }
+    // The harness generates the `main` which runs the tests.
+    if tcx.entry_fn(()).map(|(main, _)| main) == Some(id) {
+        return true;
+    }
+    let mut current = Some(id);
+    while let Some(id) = current {
+        if is_cfg_test(tcx, id) || is_test_function(tcx, id) || is_test_descriptor(tcx, id) {
+            return true;
+        }
+        current = tcx.opt_parent(id);
+    }
+    false
+}
+
+/// The functions marked `#[test]`, if the crate is compiled with `--test`.
+pub(crate) fn test_functions(tcx: TyCtxt<'_>) -> Vec<DefId> {
+    if !tcx.sess.opts.test {
+        return vec![];
+    }
+    tcx.hir()
+        .items()
+        .map(|item| item.owner_id.to_def_id())
+        .filter(|&id| is_test_function(tcx, id))
+        .collect()
+}
+
+/// Whether `id` is a function with a test descriptor next to it, which `#[test]` generates.
+fn is_test_function(tcx: TyCtxt<'_>, id: DefId) -> bool {
+    if tcx.def_kind(id) != DefKind::Fn {
+        return false;
+    }
+    let (Some(local), Some(name)) = (id.as_local(), tcx.opt_item_name(id)) else {
+        return false;
+    };
+    let module = tcx.parent_module_from_def_id(local);
+    tcx.hir().module_items(module).any(|item| {
+        let item = item.owner_id.to_def_id();
+        is_test_descriptor(tcx, item) && tcx.opt_item_name(item) == Some(name)
+    })
+}
+
+fn is_test_descriptor(tcx: TyCtxt<'_>, id: DefId) -> bool {
+    tcx.def_kind(id) == DefKind::Const && tcx.has_attr(id, Symbol::intern(TEST_MARKER))
+}
+
+/// Whether `id` has a `#[cfg(test)]` attribute. Items whose `cfg` is false were removed before,
+/// so it being there means the crate is compiled with `--test`.
+fn is_cfg_test(tcx: TyCtxt<'_>, id: DefId) -> bool {
+    let test = Symbol::intern("test");
+    tcx.get_attrs_unchecked(id).iter().any(|attr| {
+        attr.has_name(Symbol::intern("cfg"))
+            && attr
+                .meta_item_list()
+                .map_or(false, |items| items.iter().any(|item| item.has_name(test)))
+    })
+}
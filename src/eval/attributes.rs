@@ -1,31 +1,222 @@
-use hir::intravisit::Visitor;
-use rustc_ast::AttrKind;
-use rustc_errors::struct_span_err;
-use rustc_hir as hir;
-use rustc_hir::def_id::DefId;
-use rustc_middle::ty::TyCtxt;
-use rustc_span::Symbol;
+use std::{collections::HashMap, iter};
+
+use crate::{
+    analysis::{
+        labels::{Hierarchy, Label, Labels, TAINT},
+        models,
+    },
+    annotations::Role,
+    compiler::{
+        self, hir, AttrArgs, AttrItem, AttrKind, Attribute, CrateNum, DefId, DefKind, Delimiter,
+        Diagnostics, ErrorCode, HirVisitor, LitKind, Span, Symbol, Token, TokenKind, TokenStream,
+        TokenTree, TyCtxt, LOCAL_CRATE,
+    },
+    config::{Config, HashPolicy, ModuleKind, TypeClass},
+    error::Error,
+    eval::interfaces,
+    options::{Options, SourcePack},
+    summaries::FunctionSummary,
+};
 
 /// Find all attributes in a crate which originate from the `taint` tool.
 pub struct TaintAttributeFinder<'tcx> {
     tcx: TyCtxt<'tcx>,
     pub(crate) info: AttrInfo,
+    /// Sanitizers required by sinks, by the name they were given in `requires = "..."`,
+    /// and by later sanitizers, by the name they were given in `after = "..."`.
+    required: Vec<(DefId, Symbol, Span)>,
+    /// Modules marked `#![taint::source_boundary]` or `#![taint::trusted]`, or given a role in the config,
+    /// with the label the functions of a source boundary put on their data.
+    modules: Vec<(DefId, ModuleKind, Option<Label>)>,
+    /// Whether more labels were declared than can be told apart, which is only reported once.
+    out_of_labels: bool,
 }
 
 #[derive(Default, Debug)]
 pub struct AttrInfo {
     pub sources: Vec<DefId>,
     pub sinks: Vec<DefId>,
+    /// Sinks marked with `#[taint::sink(gated)]`, which may receive tainted data at calls
+    /// which acknowledge it.
+    pub(crate) gated: Vec<DefId>,
+    /// Statements and expressions marked `#[taint::acknowledged(reason = "...")]`.
+    pub(crate) acknowledgments: Vec<Acknowledgment>,
+    /// Calls to barriers, like `taint_runtime::barrier`, which stop taint without sanitizing it,
+    /// by the barrier called. They are listed apart from sanitizers in audits.
+    pub(crate) barriers: Vec<(DefId, Span)>,
+    /// Functions exported to another language by a framework of an enabled source pack, like `#[wasm_bindgen]`,
+    /// by the pack. They are analyzed as entry points whose arguments are tainted.
+    pub(crate) exports: Vec<(DefId, SourcePack)>,
+    /// Comments like `// taint-ignore: T0001 reason="..."` above the findings they suppress.
+    pub(crate) ignores: Vec<Ignore>,
+    /// Functions marked with `#[taint::sink(return)]`, which must not return tainted data.
+    /// Calls to them are analyzed like calls to any other function.
+    pub return_sinks: Vec<DefId>,
     pub sanitizers: Vec<DefId>,
+    /// Struct fields marked with `#[taint::source]`, whose every read produces tainted data.
+    /// The fields of a type marked as a source are too.
+    pub source_fields: Vec<DefId>,
+    /// Structs and enums marked with `#[taint::source]`, like a type holding credentials.
+    pub(crate) source_types: Vec<DefId>,
+    /// The methods of the source types which could leak their fields: their getters and their `Display`
+    /// and `Debug` impls. They are analyzed as entry points, since formatting calls them through pointers.
+    pub(crate) accessors: Vec<Accessor>,
+    /// Struct fields marked with `#[taint::storage]`, into which writing tainted data is reported
+    /// once a value holding the field crosses a boundary.
+    pub(crate) storage_fields: Vec<DefId>,
+    /// Functions marked with `#[taint::boundary]`, like saving a record or sending a message,
+    /// which the values passed to them cross. Calls to them are analyzed like calls to any other function.
+    pub(crate) boundaries: Vec<DefId>,
+    /// Functions defined in modules marked `#![taint::trusted]`, or which the config trusts, whose calls
+    /// return clean data like those to barriers. They are still analyzed when they are entry points.
+    pub(crate) trusted: Vec<DefId>,
+    /// Functions marked with `#[taint::requires_taint]`, which must be called with tainted data, and the
+    /// label it must carry if they were given one with `label = "..."`.
+    pub(crate) required_taint: HashMap<DefId, Option<Label>>,
+    pub(crate) requirements: Vec<Requirement>,
+    /// Where the role of each function or field was declared.
+    pub(crate) origins: HashMap<DefId, Origin>,
+    /// Labels named in the config or in `label = "..."`.
+    pub(crate) names: Vec<(Label, String)>,
+    pub(crate) hierarchy: Hierarchy,
+    /// The label sources, source fields and sinks were given with `label = "..."`.
+    pub(crate) labeled: HashMap<DefId, Label>,
+    /// The label each sanitizer marked `#[taint::sanitizer(from = "...", to = "...")]` changes,
+    /// and the label it changes it to.
+    pub(crate) relabels: HashMap<DefId, (Label, Label)>,
+    /// Functions given a role by their path with `--taint-add-source`, `--taint-add-sink`
+    /// or `--taint-add-sanitizer`, which can be defined in any crate.
+    pub(crate) added: Vec<(String, AttrInfoKind)>,
+    /// Statics marked as sources by their path in the config, like those of `extern` blocks,
+    /// and the label they were given if they were.
+    pub(crate) source_statics: Vec<(String, Option<Label>)>,
+    /// Paths of the unsafe functions the config trusts with tainted data.
+    pub(crate) trusted_unsafe: Vec<String>,
+    /// The paths items are defined at, by the other paths the config says they are re-exported under.
+    pub(crate) aliases: HashMap<String, String>,
+    /// The kinds of data the config restricts taint to, if it does.
+    pub(crate) tracked_types: Option<Vec<TypeClass>>,
+    /// Sanitizers whose effect the config makes depend on a constant argument.
+    pub(crate) sanitizer_modes: Vec<ModalSanitizer>,
+    /// The labels the config clears from digests, with those they subsume but the ones it propagates.
+    pub(crate) cleared_by_hashing: Labels,
+    /// Paths of the hash functions the config lists besides the common hash APIs.
+    pub(crate) hash_functions: Vec<String>,
+    /// The labels the config tracks the clones of, with those they subsume.
+    pub(crate) tracked_clones: Labels,
+    /// Propagation rules of foreign functions declared with `#[taint::model(...)]`, like those of the config,
+    /// and the attribute declaring each.
+    pub(crate) models: Vec<(FunctionSummary, Span)>,
+    /// The label of the secrets wrapped in `taint_runtime::Sensitive`, if the crate can use it.
+    pub(crate) secret: Option<Label>,
+    /// The number of invalid annotations reported.
+    pub errors: usize,
+}
+
+/// A method of a type marked `#[taint::source]` which could leak its fields.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Accessor {
+    pub(crate) function: DefId,
+    /// The type marked as a source.
+    pub(crate) ty: DefId,
+    pub(crate) kind: AccessorKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AccessorKind {
+    /// A method of an inherent impl taking `self`, which must not return the fields.
+    Getter,
+    /// The `fmt` method of the impl of `Display` or `Debug`, named here, which must not format the fields.
+    Format(&'static str),
+}
+
+/// Where a role was declared.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Origin {
+    Attribute(Span),
+    /// The interface of a library the crate depends on, for `--taint-crate-interfaces`.
+    Interface(CrateNum),
+    /// The role of the module the function is defined in, given by an attribute or the config.
+    Module(DefId),
 }
 
+/// A sanitizer whose effect depends on the constant passed as its argument `arg`, like the mode of
+/// `escape(s, Mode::Html)`.
 #[derive(Debug)]
+pub(crate) struct ModalSanitizer {
+    pub(crate) path: String,
+    pub(crate) arg: usize,
+    /// The constants the argument can be, as `compiler::constant_value` writes them,
+    /// and the labels the sanitizer removes when it is.
+    pub(crate) modes: Vec<(String, Vec<Label>)>,
+}
+
+/// A sink which only accepts data that passed through a particular sanitizer, or a sanitizer
+/// which must only be applied after it, like escaping after decoding.
+///
+/// Sources put `label` on their data, and only `sanitizer` removes it,
+/// so data which reaches `sink` with the label did not pass through `sanitizer` before.
+#[derive(Debug)]
+pub(crate) struct Requirement {
+    pub(crate) sink: DefId,
+    pub(crate) sanitizer: DefId,
+    pub(crate) label: Label,
+}
+
+/// A statement or expression in which the calls to gated sinks may receive tainted data,
+/// for the reason given.
+#[derive(Debug)]
+pub(crate) struct Acknowledgment {
+    /// The statement or expression.
+    pub(crate) span: Span,
+    /// The attribute, which is where the acknowledgment is listed.
+    pub(crate) attribute: Span,
+    pub(crate) reason: String,
+}
+
+/// A comment which suppresses the findings under some codes on the line below it, for the reason given.
+#[derive(Debug)]
+pub(crate) struct Ignore {
+    /// The comment, which is where the suppression is listed.
+    pub(crate) comment: Span,
+    /// The line below the comment.
+    pub(crate) line: Span,
+    pub(crate) codes: Vec<ErrorCode>,
+    pub(crate) reason: String,
+}
+
+/// The codes findings are reported under, which comments can suppress.
+const IGNORABLE: [ErrorCode; 11] = [
+    ErrorCode::T0001,
+    ErrorCode::T0003,
+    ErrorCode::T0005,
+    ErrorCode::T0006,
+    ErrorCode::T0007,
+    ErrorCode::T0008,
+    ErrorCode::T0009,
+    ErrorCode::T0010,
+    ErrorCode::T0011,
+    ErrorCode::T0013,
+    ErrorCode::T0014,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AttrInfoKind {
     Source,
     Sink,
     Sanitizer,
 }
 
+impl AttrInfoKind {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            AttrInfoKind::Source => "source",
+            AttrInfoKind::Sink => "sink",
+            AttrInfoKind::Sanitizer => "sanitizer",
+        }
+    }
+}
+
 impl AttrInfo {
     pub fn get_kind(&self, id: &DefId) -> Option<AttrInfoKind> {
         if self.sources.contains(id) {
@@ -38,6 +229,227 @@ impl AttrInfo {
             None
         }
     }
+
+    /// The role `id` is called with, given by an annotation or by its path on the command line.
+    pub(crate) fn call_role(&self, tcx: TyCtxt<'_>, id: DefId) -> Option<AttrInfoKind> {
+        self.get_kind(&id).or_else(|| {
+            if self.added.is_empty() {
+                return None;
+            }
+            let path = tcx.def_path_str(id);
+            self.added
+                .iter()
+                .find(|(added, _)| *added == path)
+                .map(|&(_, kind)| kind)
+        })
+    }
+
+    /// The role of `id`, including that of a sink on return, which calls do not see,
+    /// and that of a source field, which is never called.
+    fn role(&self, id: &DefId) -> Option<AttrInfoKind> {
+        self.get_kind(id)
+            .or_else(|| self.return_sinks.contains(id).then_some(AttrInfoKind::Sink))
+            .or_else(|| {
+                self.source_fields
+                    .contains(id)
+                    .then_some(AttrInfoKind::Source)
+            })
+    }
+
+    /// The accessor `id` is, if it is a method of a type marked as a source which could leak its fields.
+    pub(crate) fn accessor(&self, id: DefId) -> Option<&Accessor> {
+        self.accessors
+            .iter()
+            .find(|accessor| accessor.function == id)
+    }
+
+    /// The labels sources without a label of their own put on their data.
+    pub(crate) fn source_labels(&self) -> Labels {
+        self.pending_labels().union(TAINT.into())
+    }
+
+    /// The labels `source`, a function or a field, puts on its data: its own label if it was given one, and the labels
+    /// only the sanitizers required by sinks remove.
+    pub(crate) fn labels_of_source(&self, source: DefId) -> Labels {
+        match self.labeled.get(&source) {
+            Some(&label) => self.pending_labels().union(label.into()),
+            None => self.source_labels(),
+        }
+    }
+
+    /// The labels every read of the static `id` produces, if the config marks it as a source.
+    pub(crate) fn labels_of_static(&self, tcx: TyCtxt<'_>, id: DefId) -> Labels {
+        if self.source_statics.is_empty() {
+            return Labels::EMPTY;
+        }
+        let path = tcx.def_path_str(id);
+        match self
+            .source_statics
+            .iter()
+            .find(|(source, _)| *source == path)
+        {
+            Some((_, Some(label))) => self.pending_labels().union((*label).into()),
+            Some((_, None)) => self.source_labels(),
+            None => Labels::EMPTY,
+        }
+    }
+
+    /// The path the item named `path` is defined at, which is how the analysis names it,
+    /// if `path` is one of its aliases in the config.
+    pub(crate) fn canonical_path<'a>(&'a self, path: &'a str) -> &'a str {
+        self.aliases.get(path).map_or(path, String::as_str)
+    }
+
+    /// The modes the config lists for the sanitizer `id`, if it does.
+    pub(crate) fn sanitizer_modes(&self, tcx: TyCtxt<'_>, id: DefId) -> Option<&ModalSanitizer> {
+        if self.sanitizer_modes.is_empty() {
+            return None;
+        }
+        let path = tcx.def_path_str(id);
+        self.sanitizer_modes
+            .iter()
+            .find(|sanitizer| sanitizer.path == path)
+    }
+
+    /// The labels `sanitizer` removes when its mode is `value`, with the labels they subsume,
+    /// if `value` is one of its modes.
+    pub(crate) fn removed_in_mode(
+        &self,
+        sanitizer: &ModalSanitizer,
+        value: &str,
+    ) -> Option<Labels> {
+        let (_, labels) = sanitizer.modes.iter().find(|(mode, _)| mode == value)?;
+        Some(labels.iter().fold(Labels::EMPTY, |removed, &label| {
+            removed.union(self.hierarchy.below(label))
+        }))
+    }
+
+    /// Whether the config trusts the unsafe function `id` with tainted data.
+    pub(crate) fn trusts_unsafe(&self, tcx: TyCtxt<'_>, id: DefId) -> bool {
+        !self.trusted_unsafe.is_empty() && self.trusted_unsafe.contains(&tcx.def_path_str(id))
+    }
+
+    /// The acknowledgment a call to a gated sink at `span` is made under, if any.
+    pub(crate) fn acknowledgment(&self, span: Span) -> Option<&Acknowledgment> {
+        let span = span.source_callsite();
+        self.acknowledgments
+            .iter()
+            .find(|acknowledgment| acknowledgment.span.contains(span))
+    }
+
+    /// The comment suppressing the finding under `code` at `span`, if the line it starts on has one above it.
+    pub(crate) fn ignore(&self, span: Span, code: ErrorCode) -> Option<&Ignore> {
+        let start = span.source_callsite().shrink_to_lo();
+        self.ignores
+            .iter()
+            .find(|ignore| ignore.codes.contains(&code) && ignore.line.contains(start))
+    }
+
+    /// The labels `sanitizer` removes from data. A sanitizer which changes a label
+    /// only removes the labels of the sinks requiring it.
+    pub(crate) fn sanitized_labels(&self, sanitizer: DefId) -> Labels {
+        let removed = if self.relabels.contains_key(&sanitizer) {
+            Labels::EMPTY
+        } else {
+            self.named_labels().union(TAINT.into())
+        };
+        self.requirements
+            .iter()
+            .filter(|req| req.sanitizer == sanitizer)
+            .fold(removed, |labels, req| labels.union(req.label.into()))
+    }
+
+    /// The labels `sink` must not receive, or return if it is a sink on return.
+    /// A sink with a label only checks that label and the labels it subsumes,
+    /// as well as data from sources without a label.
+    pub(crate) fn sink_labels(&self, sink: DefId) -> Labels {
+        let checked = match self.labeled.get(&sink) {
+            Some(&label) => self.hierarchy.below(label),
+            None => self.named_labels(),
+        };
+        self.requirements
+            .iter()
+            .filter(|req| req.sink == sink)
+            .fold(checked.union(TAINT.into()), |labels, req| {
+                labels.union(req.label.into())
+            })
+    }
+
+    /// Whether input carrying `received` satisfies `function`, if it requires taint: it must carry
+    /// the label the function requires or one the label subsumes, or any label without one.
+    pub(crate) fn satisfies_required_taint(&self, function: DefId, received: Labels) -> bool {
+        match self.required_taint.get(&function) {
+            Some(Some(label)) => !received
+                .intersection(self.hierarchy.below(*label))
+                .is_empty(),
+            Some(None) => !received.is_empty(),
+            None => true,
+        }
+    }
+
+    /// The labels which are only removed by the sanitizers required by sinks.
+    pub(crate) fn pending_labels(&self) -> Labels {
+        self.requirements
+            .iter()
+            .fold(Labels::EMPTY, |labels, req| labels.union(req.label.into()))
+    }
+
+    /// `taint` and the labels given a name, leaving out those standing for a sanitizer which was not called.
+    pub(crate) fn reported_labels(&self) -> Labels {
+        self.named_labels().union(TAINT.into())
+    }
+
+    fn named_labels(&self) -> Labels {
+        self.names
+            .iter()
+            .fold(Labels::EMPTY, |labels, &(label, _)| {
+                labels.union(label.into())
+            })
+    }
+
+    /// A name for `label` fit for reports.
+    pub(crate) fn label_name(&self, tcx: TyCtxt<'_>, label: Label) -> String {
+        if let Some((_, name)) = self.names.iter().find(|&&(named, _)| named == label) {
+            return name.clone();
+        }
+        match self.requirements.iter().find(|req| req.label == label) {
+            Some(req) => format!("unsanitized:{}", tcx.def_path_str(req.sanitizer)),
+            None => "taint".to_owned(),
+        }
+    }
+
+    /// The label called `name` in reports, if there is one.
+    pub(crate) fn label_named(&self, tcx: TyCtxt<'_>, name: &str) -> Option<Label> {
+        let named = self.names.iter().map(|&(label, _)| label);
+        let required = self.requirements.iter().map(|req| req.label);
+        std::iter::once(TAINT)
+            .chain(named)
+            .chain(required)
+            .find(|&label| self.label_name(tcx, label) == name)
+    }
+
+    /// The labels of secrets: `secret`, if it is declared, and the labels it subsumes.
+    pub(crate) fn secret_labels(&self, tcx: TyCtxt<'_>) -> Labels {
+        self.label_named(tcx, "secret")
+            .map_or(Labels::EMPTY, |secret| self.hierarchy.below(secret))
+    }
+
+    /// The labels the sanitizer `id` must not receive, since only the sanitizers it comes after remove them.
+    pub(crate) fn labels_before(&self, id: DefId) -> Labels {
+        self.requirements
+            .iter()
+            .filter(|req| req.sink == id)
+            .fold(Labels::EMPTY, |labels, req| labels.union(req.label.into()))
+    }
+
+    /// The sanitizer `sink` requires, which was skipped by data carrying `labels`.
+    /// `sink` can also be a sanitizer, and the one returned one it must come after.
+    pub(crate) fn skipped_sanitizer(&self, sink: DefId, labels: Labels) -> Option<DefId> {
+        self.requirements
+            .iter()
+            .find(|req| req.sink == sink && labels.contains(req.label))
+            .map(|req| req.sanitizer)
+    }
 }
 
 impl<'tcx> TaintAttributeFinder<'tcx> {
@@ -45,57 +457,857 @@ impl<'tcx> TaintAttributeFinder<'tcx> {
         TaintAttributeFinder {
             tcx,
             info: AttrInfo::default(),
+            required: vec![],
+            modules: vec![],
+            out_of_labels: false,
+        }
+    }
+
+    /// Find the annotations of every item in the crate, with the labels and source statics declared
+    /// in `config` and the roles given on the command line.
+    pub fn collect(tcx: TyCtxt<'tcx>, config: &Config, options: &Options) -> AttrInfo {
+        let mut finder = TaintAttributeFinder::new(tcx);
+        finder.declare_labels(config);
+        if models::uses_sensitive(tcx) {
+            finder.info.secret = Some(finder.label("secret"));
+        }
+        for (path, others) in &config.aliases {
+            for other in others {
+                finder.info.aliases.insert(other.clone(), path.clone());
+            }
+        }
+        for (path, label) in &config.statics {
+            let label = label.as_deref().map(|name| finder.label(name));
+            let path = finder.info.canonical_path(path).to_owned();
+            finder.info.source_statics.push((path, label));
+        }
+        let trusted = config
+            .trusted_unsafe
+            .iter()
+            .map(|path| finder.info.canonical_path(path).to_owned())
+            .collect();
+        finder.info.trusted_unsafe = trusted;
+        finder.info.tracked_types = config.tracked_types.clone();
+        for sanitizer in &config.sanitizer_modes {
+            let modes = sanitizer
+                .modes
+                .iter()
+                .map(|(mode, names)| {
+                    let labels = names.iter().map(|name| finder.label(name)).collect();
+                    (mode.clone(), labels)
+                })
+                .collect();
+            let path = finder.info.canonical_path(&sanitizer.function).to_owned();
+            finder.info.sanitizer_modes.push(ModalSanitizer {
+                path,
+                arg: sanitizer.arg,
+                modes,
+            });
+        }
+        finder.declare_hashing(config);
+        finder.declare_clones(config);
+        let added = [
+            (&options.add_sources, AttrInfoKind::Source),
+            (&options.add_sinks, AttrInfoKind::Sink),
+            (&options.add_sanitizers, AttrInfoKind::Sanitizer),
+        ];
+        for (paths, kind) in added {
+            for path in paths {
+                let path = finder.info.canonical_path(path).to_owned();
+                finder.info.added.push((path, kind));
+            }
         }
+        if options.crate_interfaces {
+            finder.visit_interfaces();
+        }
+        tcx.hir().visit_all_item_likes_in_crate(&mut finder);
+        finder.visit_closures();
+        finder.visit_modules(config);
+        finder.visit_acknowledgments();
+        finder.visit_barriers(options);
+        finder.visit_exports(options);
+        finder.visit_accessors();
+        finder.visit_ignores();
+        finder.resolve_requirements();
+        finder.info
     }
 }
 
 impl TaintAttributeFinder<'_> {
     fn visit_hir_id(&mut self, item_id: hir::HirId) {
+        self.visit_attrs(item_id, item_id.owner.to_def_id());
+    }
+
+    /// Closures are functions of their own, and do not take the role of the function they are
+    /// defined in: a sink which formats its input with a closure would otherwise report itself.
+    /// A closure gets a role by annotating the closure expression,
+    /// like `#[taint::sink] |query| run(query)`, which needs `#![feature(stmt_expr_attributes)]`.
+    fn visit_closures(&mut self) {
+        for def_id in self.tcx.hir().body_owners() {
+            if self.tcx.is_closure(def_id.to_def_id()) {
+                let hir_id = self.tcx.hir().local_def_id_to_hir_id(def_id);
+                self.visit_attrs(hir_id, def_id.to_def_id());
+            }
+        }
+    }
+
+    /// Gives each function defined in a module with a role, or in a module inside it, the role of the innermost
+    /// of them. Functions with a role of their own keep it, and closures are left out like they are of the
+    /// role of the function they are defined in.
+    fn visit_modules(&mut self, config: &Config) {
+        let tcx = self.tcx;
+        let local = format!("{}::", tcx.crate_name(LOCAL_CRATE));
+        for role in &config.modules {
+            let path = self.info.canonical_path(&role.module);
+            let path = path
+                .strip_prefix("crate::")
+                .or_else(|| path.strip_prefix(&local))
+                .unwrap_or(path)
+                .to_owned();
+            let module = tcx
+                .hir()
+                .items()
+                .map(|item| item.owner_id.to_def_id())
+                .find(|&id| tcx.def_kind(id) == DefKind::Mod && tcx.def_path_str(id) == path);
+            if let Some(module) = module {
+                let label = role.label.as_deref().map(|name| self.label(name));
+                self.modules.push((module, role.kind, label));
+            }
+        }
+        if self.modules.is_empty() {
+            return;
+        }
+
+        for def_id in tcx.hir().body_owners() {
+            let id = def_id.to_def_id();
+            if !matches!(tcx.def_kind(id), DefKind::Fn | DefKind::AssocFn)
+                || self.info.role(&id).is_some()
+            {
+                continue;
+            }
+            // Attributes come before the config, so they win over it for the same module.
+            let marked = iter::successors(tcx.opt_parent(id), |&parent| tcx.opt_parent(parent))
+                .find_map(|parent| self.modules.iter().find(|(module, ..)| *module == parent));
+            let Some(&(module, kind, label)) = marked else {
+                continue;
+            };
+            self.info.origins.insert(id, Origin::Module(module));
+            match kind {
+                ModuleKind::SourceBoundary => {
+                    self.info.sources.push(id);
+                    if let Some(label) = label {
+                        self.info.labeled.insert(id, label);
+                    }
+                }
+                ModuleKind::Trusted => self.info.trusted.push(id),
+            }
+        }
+    }
+
+    /// The calls to barriers in the bodies of the crate, for audits.
+    fn visit_barriers(&mut self, options: &Options) {
+        let tcx = self.tcx;
+        for &def_id in tcx.mir_keys(()) {
+            let Some(body) = compiler::mir_body(tcx, def_id.to_def_id()) else {
+                continue;
+            };
+            for block in body.basic_blocks.iter() {
+                let Some(call) = compiler::Call::from_terminator(&block.terminator().kind) else {
+                    continue;
+                };
+                if models::is_barrier(tcx, call.callee, options.black_box_barrier) {
+                    self.info.barriers.push((call.callee, call.span));
+                }
+            }
+        }
+    }
+
+    /// A type marked `#[taint::source]` makes each of its fields a source, with the label of the type.
+    fn visit_source_type(&mut self, ty: DefId, span: Span) {
+        self.info.source_types.push(ty);
+        let label = self.info.labeled.get(&ty).copied();
+        for field in self.tcx.adt_def(ty).all_fields() {
+            if !self.info.source_fields.contains(&field.did) {
+                self.info.source_fields.push(field.did);
+            }
+            self.info
+                .origins
+                .entry(field.did)
+                .or_insert(Origin::Attribute(span));
+            if let Some(label) = label {
+                self.info.labeled.entry(field.did).or_insert(label);
+            }
+        }
+    }
+
+    /// The methods of the source types which could leak their fields: those of their inherent impls
+    /// taking `self` and returning something else than the type, and `fmt` of their `Display` and `Debug` impls,
+    /// derived or not.
+    fn visit_accessors(&mut self) {
+        if self.info.source_types.is_empty() {
+            return;
+        }
+        let tcx = self.tcx;
+        for item in tcx.hir().items() {
+            let impl_id = item.owner_id.to_def_id();
+            if !matches!(tcx.def_kind(impl_id), DefKind::Impl { .. }) {
+                continue;
+            }
+            let self_ty = tcx.type_of(impl_id).instantiate_identity();
+            let Some(ty) = self_ty.ty_adt_def().map(|adt| adt.did()) else {
+                continue;
+            };
+            if !self.info.source_types.contains(&ty) {
+                continue;
+            }
+            let format = match tcx.impl_trait_ref(impl_id) {
+                None => None,
+                Some(trait_ref) => {
+                    match tcx.def_path_str(trait_ref.skip_binder().def_id).as_str() {
+                        "std::fmt::Display" => Some("Display"),
+                        "std::fmt::Debug" => Some("Debug"),
+                        _ => continue,
+                    }
+                }
+            };
+            for &function in tcx.associated_item_def_ids(impl_id) {
+                let item = tcx.associated_item(function);
+                if !item.fn_has_self_parameter {
+                    continue;
+                }
+                let kind = match format {
+                    Some(name) => AccessorKind::Format(name),
+                    None => {
+                        let output = tcx.fn_sig(function).skip_binder().output().skip_binder();
+                        let returns_self = output.walk().any(|arg| {
+                            arg.as_type()
+                                .and_then(|ty| ty.ty_adt_def())
+                                .map_or(false, |adt| adt.did() == ty)
+                        });
+                        if returns_self {
+                            continue;
+                        }
+                        AccessorKind::Getter
+                    }
+                };
+                self.info.accessors.push(Accessor { function, ty, kind });
+            }
+        }
+    }
+
+    /// The roles declared by the libraries the crate depends on, which their interfaces list by path.
+    fn visit_interfaces(&mut self) {
+        let tcx = self.tcx;
+        for (krate, annotations) in interfaces::annotations(tcx) {
+            let annotations = match annotations {
+                Ok(annotations) => annotations,
+                Err(error) => {
+                    Diagnostics::new(tcx).failure(&error);
+                    continue;
+                }
+            };
+            let items = compiler::crate_items(tcx, krate)
+                .into_iter()
+                .filter(|&id| {
+                    matches!(
+                        tcx.opt_def_kind(id),
+                        Some(DefKind::Fn | DefKind::AssocFn | DefKind::Field)
+                    )
+                })
+                .map(|id| (tcx.def_path_str(id), id))
+                .collect::<HashMap<_, _>>();
+            for annotation in &annotations.annotations {
+                let Some(&id) = items.get(&annotation.path) else {
+                    continue;
+                };
+                let ids = match annotation.role {
+                    Role::Source => &mut self.info.sources,
+                    Role::SourceField => &mut self.info.source_fields,
+                    Role::Sink => &mut self.info.sinks,
+                    Role::ReturnSink => &mut self.info.return_sinks,
+                    Role::Sanitizer => &mut self.info.sanitizers,
+                };
+                ids.push(id);
+                if let Some(name) = &annotation.label {
+                    let label = self.label(name);
+                    self.info.labeled.insert(id, label);
+                }
+                self.info.origins.insert(id, Origin::Interface(krate));
+            }
+        }
+    }
+
+    /// The functions the frameworks of the source packs export, found by the attribute macro which defined them,
+    /// since the macro is expanded away before its attribute could be read. It matches by its name, however its
+    /// path was written, like `wasm_bindgen` in `#[wasm_bindgen::prelude::wasm_bindgen]`.
+    fn visit_exports(&mut self, options: &Options) {
+        if options.source_packs.is_empty() {
+            return;
+        }
+        let tcx = self.tcx;
+        for &def_id in tcx.mir_keys(()) {
+            let def_id = def_id.to_def_id();
+            if !matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn) {
+                continue;
+            }
+            let macros = compiler::defining_attribute_macros(tcx, def_id);
+            let names = macros
+                .iter()
+                .map(|name| name.rsplit("::").next().unwrap_or(name));
+            for name in names {
+                let pack = options
+                    .source_packs
+                    .iter()
+                    .find(|pack| pack.macros().contains(&name));
+                if let Some(&pack) = pack {
+                    self.info.exports.push((def_id, pack));
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Calls are acknowledged by the statement or the expression they are made in,
+    /// like `#[taint::acknowledged(reason = "...")] run(&query);`, wherever it is in a body.
+    fn visit_acknowledgments(&mut self) {
+        let tcx = self.tcx;
+        let sym_acknowledged = Symbol::intern("acknowledged");
+        let mut visited = vec![];
+        for owner in tcx.hir_crate_items(()).owners() {
+            for &(local_id, attrs) in tcx.hir_attrs(owner).map.iter() {
+                let hir_id = hir::HirId { owner, local_id };
+                if !matches!(
+                    tcx.hir().get(hir_id),
+                    hir::Node::Stmt(_) | hir::Node::Local(_) | hir::Node::Expr(_)
+                ) {
+                    continue;
+                }
+                for attr in attrs.iter() {
+                    let AttrKind::Normal(ref kind) = attr.kind else {
+                        continue;
+                    };
+                    // The attributes of an expression statement are those of its expression too.
+                    if get_taint_attr(&kind.item) != Some(&sym_acknowledged)
+                        || visited.contains(&attr.span)
+                    {
+                        continue;
+                    }
+                    visited.push(attr.span);
+                    let reason = attr
+                        .meta_item_list()
+                        .and_then(|args| match &args[..] {
+                            [arg] if arg.has_name(Symbol::intern("reason")) => arg.value_str(),
+                            _ => None,
+                        })
+                        .filter(|reason| !reason.as_str().trim().is_empty());
+                    match reason {
+                        Some(reason) => self.info.acknowledgments.push(Acknowledgment {
+                            span: tcx.hir().span(hir_id),
+                            attribute: attr.span,
+                            reason: reason.to_string(),
+                        }),
+                        None => self.invalid(
+                            attr.span,
+                            "Taint attribute is invalid. Acknowledgments must give a `reason = \"<reason>\"`".to_owned(),
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Findings can be suppressed where attributes are not allowed, like on a call in the middle of
+    /// an expression, by a comment on the line above them, like
+    /// `// taint-ignore: T0001 reason="validated upstream"`, with one or more codes separated by commas.
+    fn visit_ignores(&mut self) {
+        for comment in compiler::line_comments(self.tcx, "taint-ignore:") {
+            let (codes, reason) = comment
+                .text
+                .split_once("reason=")
+                .unwrap_or((&comment.text, ""));
+            let reason = reason.trim();
+            let reason = reason
+                .strip_prefix('"')
+                .and_then(|reason| reason.strip_suffix('"'))
+                .filter(|reason| !reason.trim().is_empty());
+            let codes = codes
+                .split(',')
+                .map(|code| {
+                    IGNORABLE
+                        .iter()
+                        .copied()
+                        .find(|c| c.as_str() == code.trim())
+                })
+                .collect::<Option<Vec<_>>>();
+            match (codes, reason, comment.below) {
+                (Some(codes), Some(reason), Some(line)) => self.info.ignores.push(Ignore {
+                    comment: comment.span,
+                    line,
+                    codes,
+                    reason: reason.to_owned(),
+                }),
+                (None, ..) => self.invalid(
+                    comment.span,
+                    "Taint comment is invalid. Only the codes of findings can be ignored, like `taint-ignore: T0001, T0003`".to_owned(),
+                ),
+                (_, None, _) => self.invalid(
+                    comment.span,
+                    "Taint comment is invalid. Ignored findings must give a `reason=\"<reason>\"`".to_owned(),
+                ),
+                (_, _, None) => self.invalid(
+                    comment.span,
+                    "Taint comment is invalid. It must be on the line above the findings it ignores".to_owned(),
+                ),
+            }
+        }
+    }
+
+    /// Record the role `def_id` is given by the attributes on `hir_id`.
+    fn visit_attrs(&mut self, hir_id: hir::HirId, def_id: DefId) {
         let sym_source = Symbol::intern("source");
         let sym_sink = Symbol::intern("sink");
         let sym_sanitizer = Symbol::intern("sanitizer");
+        let sym_acknowledged = Symbol::intern("acknowledged");
+        let sym_storage = Symbol::intern("storage");
+        let sym_boundary = Symbol::intern("boundary");
+        let sym_requires_taint = Symbol::intern("requires_taint");
+        let sym_model = Symbol::intern("model");
+        let sym_source_boundary = Symbol::intern("source_boundary");
+        let sym_trusted = Symbol::intern("trusted");
 
-        let def_id = item_id.owner.to_def_id();
-        let attrs = self.tcx.hir().attrs(item_id);
+        let attrs = self.tcx.hir().attrs(hir_id);
         for attr in attrs {
             if let AttrKind::Normal(ref kind) = attr.kind {
                 let item = &kind.item;
                 if let Some(symbol) = get_taint_attr(item) {
+                    let is_role = [sym_source, sym_sink, sym_sanitizer].contains(symbol);
+                    let is_field = self.tcx.def_kind(def_id) == DefKind::Field;
+                    if is_role && is_field && symbol != &sym_source {
+                        self.invalid(
+                            item.span(),
+                            "Taint attribute is invalid. Fields can only be marked as a `source`"
+                                .to_owned(),
+                        );
+                        continue;
+                    }
+                    if is_role && self.conflicts(def_id, attr.span) {
+                        continue;
+                    }
+                    if is_role {
+                        self.info
+                            .origins
+                            .insert(def_id, Origin::Attribute(attr.span));
+                    }
                     if symbol == &sym_source {
-                        self.info.sources.push(def_id)
+                        self.visit_source_args(def_id, attr);
+                        let is_type = matches!(
+                            self.tcx.def_kind(def_id),
+                            DefKind::Struct | DefKind::Enum | DefKind::Union
+                        );
+                        if is_field {
+                            self.info.source_fields.push(def_id);
+                        } else if is_type {
+                            self.visit_source_type(def_id, attr.span);
+                        } else {
+                            self.info.sources.push(def_id);
+                        }
                     } else if symbol == &sym_sink {
-                        self.info.sinks.push(def_id)
+                        self.visit_sink_args(def_id, attr);
                     } else if symbol == &sym_sanitizer {
-                        self.info.sanitizers.push(def_id)
+                        self.info.sanitizers.push(def_id);
+                        self.visit_sanitizer_args(def_id, attr);
+                    } else if symbol == &sym_storage {
+                        if is_field {
+                            self.info.storage_fields.push(def_id);
+                        } else {
+                            self.invalid(item.span(), "Taint attribute is invalid. Only fields can be marked as `storage`".to_owned());
+                        }
+                    } else if symbol == &sym_boundary {
+                        if self.tcx.def_kind(def_id).is_fn_like() {
+                            self.info.boundaries.push(def_id);
+                        } else {
+                            self.invalid(item.span(), "Taint attribute is invalid. Only functions can be marked as a `boundary`".to_owned());
+                        }
+                    } else if symbol == &sym_requires_taint {
+                        if self.tcx.def_kind(def_id).is_fn_like() {
+                            self.visit_requires_taint_args(def_id, attr);
+                        } else {
+                            self.invalid(
+                                item.span(),
+                                "Taint attribute is invalid. Only functions can require taint"
+                                    .to_owned(),
+                            );
+                        }
+                    } else if symbol == &sym_model {
+                        self.visit_model_args(def_id, attr);
+                    } else if symbol == &sym_source_boundary || symbol == &sym_trusted {
+                        let kind = if symbol == &sym_trusted {
+                            ModuleKind::Trusted
+                        } else {
+                            ModuleKind::SourceBoundary
+                        };
+                        if self.tcx.def_kind(def_id) == DefKind::Mod {
+                            self.visit_module_args(def_id, kind, attr);
+                        } else {
+                            self.invalid(
+                                item.span(),
+                                format!(
+                                    "Taint attribute is invalid. Only modules can be marked as `{}`",
+                                    kind.name()
+                                ),
+                            );
+                        }
+                    } else if symbol == &sym_acknowledged {
+                        // A closure is an expression, whose calls the acknowledgment covers.
+                        if !self.tcx.is_closure(def_id) {
+                            self.invalid(item.span(), "Taint attribute is invalid. Only statements and expressions can be `acknowledged`".to_owned());
+                        }
                     } else {
-                        struct_span_err!(self.tcx.sess, item.span(), T0002, "Taint attribute `{}` is invalid. We currently only support `source`, `sink`, and `sanitizer`", symbol.to_ident_string()).emit();
+                        self.invalid(item.span(), format!("Taint attribute `{}` is invalid. We currently only support `source`, `sink`, and `sanitizer`", symbol.to_ident_string()));
                     };
-                    break;
                 }
             }
         }
     }
+
+    /// A function has a single role, so a second one declared at `span` is reported
+    /// instead of letting one of them win.
+    fn conflicts(&mut self, def_id: DefId, span: Span) -> bool {
+        let (Some(kind), Some(&Origin::Attribute(declared))) =
+            (self.info.role(&def_id), self.info.origins.get(&def_id))
+        else {
+            return false;
+        };
+        Diagnostics::new(self.tcx).error_with_note(
+            span,
+            ErrorCode::T0004,
+            format!(
+                "`{}` is already marked as a {}",
+                self.tcx.def_path_str(def_id),
+                kind.name()
+            ),
+            declared,
+            "the role was declared here",
+        );
+        self.info.errors += 1;
+        true
+    }
+
+    /// Reports an invalid annotation at `span`.
+    fn invalid(&mut self, span: Span, message: String) {
+        Diagnostics::new(self.tcx).error(span, ErrorCode::T0002, message);
+        self.info.errors += 1;
+    }
+
+    /// `#[taint::source(label = "header")]` labels the data of the source with `header`.
+    fn visit_source_args(&mut self, source: DefId, attr: &Attribute) {
+        for arg in attr.meta_item_list().unwrap_or_default() {
+            match (arg.name_or_empty().as_str(), arg.value_str()) {
+                ("label", Some(label)) => {
+                    let label = self.label(label.as_str());
+                    self.info.labeled.insert(source, label);
+                }
+                _ => self.invalid(
+                    arg.span(),
+                    "Taint attribute argument is invalid. Sources only support `label = \"<label>\"`".to_owned(),
+                ),
+            }
+        }
+    }
+
+    /// `#![taint::source_boundary]` makes every function of the module a source, whose data is labeled
+    /// with `label = "..."` if it is given, and `#![taint::trusted]` makes calls to them return clean data.
+    fn visit_module_args(&mut self, module: DefId, kind: ModuleKind, attr: &Attribute) {
+        let mut label = None;
+        for arg in attr.meta_item_list().unwrap_or_default() {
+            match (arg.name_or_empty().as_str(), arg.value_str()) {
+                ("label", Some(name)) if kind == ModuleKind::SourceBoundary => {
+                    label = Some(self.label(name.as_str()))
+                }
+                _ => self.invalid(
+                    arg.span(),
+                    "Taint attribute argument is invalid. Source boundaries only support `label = \"<label>\"`, and trusted modules no argument".to_owned(),
+                ),
+            }
+        }
+        self.modules.push((module, kind, label));
+    }
+
+    /// `#[taint::sink(requires = "escape")]` makes the sink report data which did not pass through
+    /// the sanitizer `escape`, even if another sanitizer cleaned it.
+    /// `#[taint::sink(label = "user-input")]` makes the sink only check data labeled `user-input`,
+    /// or with a label it subsumes, besides data from sources without a label.
+    /// `#[taint::sink(return)]` checks the data the function returns instead of the data it is called with.
+    /// `#[taint::sink(gated)]` lets calls marked `#[taint::acknowledged(reason = "...")]` pass tainted data.
+    fn visit_sink_args(&mut self, sink: DefId, attr: &Attribute) {
+        let mut on_return = false;
+        let mut gated = None;
+        for arg in attr.meta_item_list().unwrap_or_default() {
+            match (arg.name_or_empty().as_str(), arg.value_str()) {
+                ("requires", Some(sanitizer)) => self.required.push((sink, sanitizer, arg.span())),
+                ("label", Some(label)) => {
+                    let label = self.label(label.as_str());
+                    self.info.labeled.insert(sink, label);
+                }
+                ("return", None) if arg.is_word() => on_return = true,
+                ("gated", None) if arg.is_word() => gated = Some(arg.span()),
+                _ => self.invalid(
+                    arg.span(),
+                    "Taint attribute argument is invalid. Sinks only support `requires = \"<sanitizer>\"`, `label = \"<label>\"`, `return` and `gated`".to_owned(),
+                ),
+            }
+        }
+        match gated {
+            Some(span) if on_return => self.invalid(
+                span,
+                "Taint attribute argument is invalid. Sinks on `return` are not checked at their calls, so they cannot be `gated`".to_owned(),
+            ),
+            Some(_) => self.info.gated.push(sink),
+            None => {}
+        }
+        if on_return {
+            self.info.return_sinks.push(sink);
+        } else {
+            self.info.sinks.push(sink);
+        }
+    }
+
+    /// `#[taint::requires_taint(label = "request-id")]` makes calls to the function report input which
+    /// never carries `request-id`, or a label it subsumes, like an audit log given a scrubbed copy of
+    /// the request id. Without a label, any taint will do.
+    fn visit_requires_taint_args(&mut self, function: DefId, attr: &Attribute) {
+        let mut required = None;
+        for arg in attr.meta_item_list().unwrap_or_default() {
+            match (arg.name_or_empty().as_str(), arg.value_str()) {
+                ("label", Some(label)) => required = Some(self.label(label.as_str())),
+                _ => self.invalid(
+                    arg.span(),
+                    "Taint attribute argument is invalid. Requiring taint only supports `label = \"<label>\"`".to_owned(),
+                ),
+            }
+        }
+        self.info.required_taint.insert(function, required);
+    }
+
+    /// `#[taint::model(result_from = [0], writes = { 0: [1] })]` on a foreign function, or on the `extern` block
+    /// declaring it, makes calls to it follow the propagation rule the config would give it with
+    /// `{ "result_from": [0], "writes": { "0": [1] } }`.
+    fn visit_model_args(&mut self, def_id: DefId, attr: &Attribute) {
+        let functions = match self.tcx.def_kind(def_id) {
+            DefKind::ForeignMod => {
+                let item = self.tcx.hir().expect_item(def_id.expect_local());
+                let hir::ItemKind::ForeignMod { items, .. } = item.kind else {
+                    return;
+                };
+                let declared = items.iter().map(|item| item.id.owner_id.to_def_id());
+                declared
+                    .filter(|&id| self.tcx.def_kind(id) == DefKind::Fn)
+                    .collect()
+            }
+            DefKind::Fn if self.tcx.is_foreign_item(def_id) => vec![def_id],
+            _ => {
+                return self.invalid(
+                    attr.span,
+                    "Taint attribute is invalid. Only `extern` blocks and the functions they declare can be given a `model`".to_owned(),
+                )
+            }
+        };
+        let Some((result_from, writes)) = model_args(&attr.get_normal_item().args) else {
+            return self.invalid(
+                attr.span,
+                "Taint attribute is invalid. Models only support `result_from = [<arg>, ...]` and `writes = { <arg>: [<arg>, ...], ... }`".to_owned(),
+            );
+        };
+        for function in functions {
+            let path = self.tcx.def_path_str(function);
+            let rule = FunctionSummary::rule(&path, result_from.clone(), &writes);
+            self.info.models.push((rule, attr.span));
+        }
+    }
+
+    /// `#[taint::sanitizer(after = "decode")]` makes the sanitizer report data which did not pass
+    /// through the sanitizer `decode` before, like data escaped before it is decoded.
+    /// `#[taint::sanitizer(from = "encoded", to = "decoded")]` makes the sanitizer change the label
+    /// `encoded` of its data into `decoded`, instead of removing every label.
+    fn visit_sanitizer_args(&mut self, sanitizer: DefId, attr: &Attribute) {
+        let (mut from, mut to) = (None, None);
+        for arg in attr.meta_item_list().unwrap_or_default() {
+            match (arg.name_or_empty().as_str(), arg.value_str()) {
+                ("after", Some(earlier)) => self.required.push((sanitizer, earlier, arg.span())),
+                ("from", Some(label)) => from = Some(label),
+                ("to", Some(label)) => to = Some(label),
+                _ => self.invalid(
+                    arg.span(),
+                    "Taint attribute argument is invalid. Sanitizers only support `after = \"<sanitizer>\"`, `from = \"<label>\"` and `to = \"<label>\"`".to_owned(),
+                ),
+            }
+        }
+        match (from, to) {
+            (Some(from), Some(to)) if from == to => self.invalid(
+                attr.span,
+                format!("Taint attribute is invalid. `{}` cannot be changed into itself", from),
+            ),
+            (Some(from), Some(to)) => {
+                let relabel = (self.label(from.as_str()), self.label(to.as_str()));
+                self.info.relabels.insert(sanitizer, relabel);
+            }
+            (None, None) => {}
+            _ => self.invalid(
+                attr.span,
+                "Taint attribute is invalid. Sanitizers change a label `from = \"<label>\"` into another one `to = \"<label>\"`, and need both".to_owned(),
+            ),
+        }
+    }
+
+    /// Declare the labels of the config, and which labels they subsume.
+    fn declare_labels(&mut self, config: &Config) {
+        for (name, subsumed) in &config.labels {
+            let label = self.label(name);
+            for sub_name in subsumed {
+                let sub = self.label(sub_name);
+                if !self.info.hierarchy.add(label, sub) {
+                    Diagnostics::new(self.tcx).failure(&Error::format(
+                        "config",
+                        format!("`{}` and `{}` subsume each other", name, sub_name),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// The labels hashing clears, and the functions the config says hash, from its `hashing`.
+    /// A label the config propagates is kept even if it is below one it clears.
+    fn declare_hashing(&mut self, config: &Config) {
+        let mut cleared = Labels::EMPTY;
+        let mut propagated = Labels::EMPTY;
+        for (name, policy) in &config.hashing.labels {
+            let label = if name == "taint" {
+                TAINT
+            } else {
+                self.label(name)
+            };
+            match policy {
+                HashPolicy::Clear => cleared = cleared.union(self.info.hierarchy.below(label)),
+                HashPolicy::Propagate => propagated = propagated.union(label.into()),
+            }
+        }
+        self.info.cleared_by_hashing = cleared.without(propagated);
+        self.info.hash_functions = config
+            .hashing
+            .functions
+            .iter()
+            .map(|path| self.info.canonical_path(path).to_owned())
+            .collect();
+    }
+
+    /// The labels whose clones are reported, from the `clones` of the config, and those they subsume.
+    fn declare_clones(&mut self, config: &Config) {
+        for name in &config.clones {
+            let label = if name == "taint" {
+                TAINT
+            } else {
+                self.label(name)
+            };
+            self.info.tracked_clones = self
+                .info
+                .tracked_clones
+                .union(self.info.hierarchy.below(label));
+        }
+    }
+
+    /// The label called `name`, which is declared if it was not yet.
+    fn label(&mut self, name: &str) -> Label {
+        if let Some(&(label, _)) = self.info.names.iter().find(|(_, named)| named == name) {
+            return label;
+        }
+        let label = self.next_label();
+        self.info.names.push((label, name.to_owned()));
+        label
+    }
+
+    /// Match the sanitizers required by sinks, or which sanitizers must come after,
+    /// with the functions marked as sanitizers, by their path or their name.
+    fn resolve_requirements(&mut self) {
+        for (sink, name, span) in std::mem::take(&mut self.required) {
+            let sanitizer = self.info.sanitizers.iter().copied().find(|&id| {
+                self.tcx.def_path_str(id) == name.as_str() || self.tcx.item_name(id) == name
+            });
+            let Some(sanitizer) = sanitizer else {
+                self.invalid(span, format!("`{}` is not marked as a sanitizer", name));
+                continue;
+            };
+            if sanitizer == sink {
+                self.invalid(span, format!("`{}` cannot come after itself", name));
+                continue;
+            }
+
+            // Sinks and sanitizers requiring the same sanitizer share a label.
+            let shared = self
+                .info
+                .requirements
+                .iter()
+                .find(|req| req.sanitizer == sanitizer)
+                .map(|req| req.label);
+            let label = shared.unwrap_or_else(|| self.next_label());
+            self.info.requirements.push(Requirement {
+                sink,
+                sanitizer,
+                label,
+            });
+        }
+    }
+
+    /// A label no other label is yet. Once they run out, which the config alone cannot get to,
+    /// the annotations of the crate are invalid, and the last label is handed out again.
+    fn next_label(&mut self) -> Label {
+        let requirements = self.info.requirements.iter().map(|req| req.label);
+        let named = self.info.names.iter().map(|&(label, _)| label);
+        let last = requirements.chain(named).map(Label::index).max();
+        let next = last.unwrap_or(TAINT.index()) + 1;
+        if next < Label::MAX {
+            return Label::new(next);
+        }
+        if !self.out_of_labels {
+            self.out_of_labels = true;
+            self.info.errors += 1;
+            Diagnostics::new(self.tcx).failure(&Error::format(
+                "labels",
+                format!(
+                    "the config and the annotations declare more labels, and sanitizers required by sinks, than the {} which can be told apart",
+                    Label::MAX
+                ),
+            ));
+        }
+        Label::new(Label::MAX - 1)
+    }
 }
 
-impl<'v> Visitor<'v> for TaintAttributeFinder<'_> {
-    fn visit_item(&mut self, item: &'v rustc_hir::Item<'_>) {
+impl<'v> HirVisitor<'v> for TaintAttributeFinder<'_> {
+    fn visit_item(&mut self, item: &'v hir::Item<'_>) {
         self.visit_hir_id(item.hir_id());
+        // Fields of the framework types we do not construct ourselves, like the body of a request,
+        // are sources of their own: every read of them produces tainted data.
+        if let hir::ItemKind::Struct(data, _) | hir::ItemKind::Union(data, _) = &item.kind {
+            for field in data.fields() {
+                self.visit_attrs(field.hir_id, field.def_id.to_def_id());
+            }
+        }
     }
 
-    fn visit_trait_item(&mut self, trait_item: &rustc_hir::TraitItem<'_>) {
+    fn visit_trait_item(&mut self, trait_item: &hir::TraitItem<'_>) {
         self.visit_hir_id(trait_item.hir_id());
     }
 
-    fn visit_impl_item(&mut self, impl_item: &rustc_hir::ImplItem<'_>) {
+    fn visit_impl_item(&mut self, impl_item: &hir::ImplItem<'_>) {
         self.visit_hir_id(impl_item.hir_id());
     }
 
-    fn visit_foreign_item(&mut self, foreign_item: &rustc_hir::ForeignItem<'_>) {
+    fn visit_foreign_item(&mut self, foreign_item: &hir::ForeignItem<'_>) {
         self.visit_hir_id(foreign_item.hir_id());
     }
 }
 
-fn get_taint_attr(item: &rustc_ast::AttrItem) -> Option<&Symbol> {
+fn get_taint_attr(item: &AttrItem) -> Option<&Symbol> {
     if item.path.segments.len() == 2 && item.path.segments[0].ident.name == Symbol::intern("taint")
     {
         Some(&item.path.segments[1].ident.name)
@@ -103,3 +1315,79 @@ fn get_taint_attr(item: &rustc_ast::AttrItem) -> Option<&Symbol> {
         None
     }
 }
+
+/// The arguments a model writes to, each with the arguments whose labels it writes.
+type Writes = Vec<(usize, Vec<usize>)>;
+
+/// The arguments the result of a model comes from and those it writes, from `args` like
+/// `(result_from = [0], writes = { 0: [1] })`, or `None` if they are malformed.
+fn model_args(args: &AttrArgs) -> Option<(Vec<usize>, Writes)> {
+    let AttrArgs::Delimited(args) = args else {
+        return None;
+    };
+    let (mut result_from, mut writes) = (vec![], vec![]);
+    for arg in comma_separated(&args.tokens) {
+        match arg.as_slice() {
+            [TokenTree::Token(name, _), TokenTree::Token(eq, _), TokenTree::Delimited(_, delimiter, tokens)]
+                if eq.kind == TokenKind::Eq =>
+            {
+                match (ident(name)?.as_str(), delimiter) {
+                    ("result_from", Delimiter::Bracket) => result_from.extend(indices(tokens)?),
+                    ("writes", Delimiter::Brace) => {
+                        for write in comma_separated(tokens) {
+                            let [TokenTree::Token(arg, _), TokenTree::Token(colon, _), TokenTree::Delimited(_, Delimiter::Bracket, from)] =
+                                write.as_slice()
+                            else {
+                                return None;
+                            };
+                            if colon.kind != TokenKind::Colon {
+                                return None;
+                            }
+                            writes.push((index(arg)?, indices(from)?));
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some((result_from, writes))
+}
+
+/// The token trees of `tokens` between commas, without a trailing comma.
+fn comma_separated(tokens: &TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut separated = vec![vec![]];
+    for tree in tokens.trees() {
+        match tree {
+            TokenTree::Token(token, _) if token.kind == TokenKind::Comma => separated.push(vec![]),
+            tree => separated.last_mut().unwrap().push(tree.clone()),
+        }
+    }
+    separated.retain(|trees| !trees.is_empty());
+    separated
+}
+
+/// The indices of arguments listed in `tokens`, like `0, 1`.
+fn indices(tokens: &TokenStream) -> Option<Vec<usize>> {
+    comma_separated(tokens)
+        .iter()
+        .map(|trees| match trees.as_slice() {
+            [TokenTree::Token(token, _)] => index(token),
+            _ => None,
+        })
+        .collect()
+}
+
+fn index(token: &Token) -> Option<usize> {
+    match token.kind {
+        TokenKind::Literal(lit) if lit.kind == LitKind::Integer && lit.suffix.is_none() => {
+            lit.symbol.as_str().parse().ok()
+        }
+        _ => None,
+    }
+}
+
+fn ident(token: &Token) -> Option<Symbol> {
+    token.ident().map(|(ident, _)| ident.name)
+}
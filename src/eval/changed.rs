@@ -0,0 +1,57 @@
+//! Picking the entry points to analyze with `--taint-changed-only`.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::changes::Changes;
+use crate::compiler::{self, DefId, TyCtxt};
+use crate::error::Error;
+use crate::options::ChangedOnly;
+
+/// The changes `changed_only` reads.
+pub(crate) fn changes(changed_only: &ChangedOnly) -> Result<Changes, Error> {
+    match changed_only {
+        ChangedOnly::Git => Changes::from_git(Path::new(".")),
+        ChangedOnly::Listed(path) => Changes::read(path),
+    }
+}
+
+/// The entry points among `entries` which may run a function of the crate whose definition changed,
+/// themselves or through the functions they call, in the order they were given.
+///
+/// Calls are found by what the bodies name rather than by analyzing them, so an entry point which only
+/// reaches a changed function through a function pointer made outside of the functions it reaches is left out.
+pub(crate) fn changed_entries(tcx: TyCtxt<'_>, changes: &Changes, entries: &[DefId]) -> Vec<DefId> {
+    let mut callees = HashMap::new();
+    let mut changed = HashMap::new();
+    let mut is_changed = |id: DefId| {
+        *changed.entry(id).or_insert_with(|| {
+            compiler::item_lines(tcx, id)
+                .map_or(false, |(file, lines)| changes.touches(&file, lines))
+        })
+    };
+    entries
+        .iter()
+        .copied()
+        .filter(|&entry| {
+            let mut seen = HashSet::from([entry]);
+            let mut pending = vec![entry];
+            while let Some(id) = pending.pop() {
+                if is_changed(id) {
+                    return true;
+                }
+                let mentioned = callees.entry(id).or_insert_with(|| {
+                    compiler::mir_body(tcx, id)
+                        .map(|body| compiler::mentioned_fns(tcx, body))
+                        .unwrap_or_default()
+                });
+                for &callee in mentioned.iter() {
+                    if seen.insert(callee) {
+                        pending.push(callee);
+                    }
+                }
+            }
+            false
+        })
+        .collect()
+}
@@ -0,0 +1,96 @@
+//! Running the analysis as part of the compiler's `analysis` query, for `--taint-integration=query`.
+//!
+//! By default the driver analyzes the crate from its `after_analysis` callback, which the driver of
+//! another tool cannot share. Like clippy registers its late lint passes, the analysis can instead be
+//! [registered](register) from `Callbacks::config` as an override of the `analysis` query, which runs it
+//! once the compiler's own analysis of the crate succeeded. Any driver can then run it alongside callbacks
+//! of its own, in a single compiler invocation.
+
+use std::sync::Mutex;
+
+use crate::{
+    compiler::{self, Config, ErrorGuaranteed, ExternProviders, Providers, Session, TyCtxt},
+    eval::main,
+    options::Options,
+};
+
+type OverrideQueries = fn(&Session, &mut Providers, &mut ExternProviders);
+
+type AnalysisQuery = for<'tcx> fn(TyCtxt<'tcx>, ()) -> Result<(), ErrorGuaranteed>;
+
+/// The analysis registered for the compiler invocations of this process.
+static REGISTERED: Mutex<Option<Registered>> = Mutex::new(None);
+
+struct Registered {
+    options: Options,
+    /// The queries the driver overrode before registering the analysis, which stay overridden.
+    previous: Option<OverrideQueries>,
+    /// The `analysis` query the analysis runs after, which another override may have replaced.
+    analysis: Option<AnalysisQuery>,
+    /// The number of findings and invalid annotations reported by the last analysis.
+    findings: usize,
+}
+
+/// Registers the analysis with `options` as an override of the `analysis` query of the compiler `config`
+/// is for, keeping the queries it already overrides.
+pub fn register(config: &mut Config, options: Options) {
+    *REGISTERED.lock().unwrap() = Some(Registered {
+        options,
+        previous: config.override_queries,
+        analysis: None,
+        findings: 0,
+    });
+    config.override_queries = Some(override_queries);
+}
+
+/// The number of findings and invalid annotations reported by the last analysis registered,
+/// or 0 if none ran.
+pub fn findings() -> usize {
+    let registered = REGISTERED.lock().unwrap();
+    registered
+        .as_ref()
+        .map_or(0, |registered| registered.findings)
+}
+
+fn override_queries(
+    session: &Session,
+    providers: &mut Providers,
+    extern_providers: &mut ExternProviders,
+) {
+    let (previous, mir) = {
+        let registered = REGISTERED.lock().unwrap();
+        let registered = registered.as_ref().expect("the analysis is registered");
+        (registered.previous, registered.options.mir)
+    };
+    if let Some(previous) = previous {
+        previous(session, providers, extern_providers);
+    }
+    if let Some(keep) = compiler::override_queries(mir) {
+        keep(session, providers, extern_providers);
+    }
+
+    let mut registered = REGISTERED.lock().unwrap();
+    let registered = registered.as_mut().expect("the analysis is registered");
+    registered.analysis = Some(providers.analysis);
+    providers.analysis = analysis;
+}
+
+fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<(), ErrorGuaranteed> {
+    // The lock is not held while analyzing, since the analysis may run queries of its own.
+    let (analysis, options) = {
+        let registered = REGISTERED.lock().unwrap();
+        let registered = registered.as_ref().expect("the analysis is registered");
+        (registered.analysis, registered.options.clone())
+    };
+    if let Some(analysis) = analysis {
+        analysis(tcx, ())?;
+    }
+    // Like after `after_analysis`, the MIR is only valid if the compiler reported no error.
+    tcx.sess.abort_if_errors();
+
+    let findings = main::eval_crate(tcx, &options);
+    if let Some(registered) = REGISTERED.lock().unwrap().as_mut() {
+        registered.findings = findings;
+    }
+    Ok(())
+}
@@ -0,0 +1,97 @@
+//! Sharing what the analysis of a library learned with the crates depending on it, for `--taint-crate-interfaces`.
+//!
+//! cargo compiles each crate of a package, like its library and its binaries, in a compiler invocation
+//! of its own, whose analysis does not read the attributes of the other crates, and only descends into their
+//! functions if their MIR was encoded. With the option, the analysis of a library writes its interface next to
+//! its metadata: the roles it declared, as [`crate::annotations`], and the summaries of the functions it analyzed.
+//! The analysis of a crate depending on it, like a binary of the same package, loads them, so that the functions
+//! and fields of the library keep their roles, and the summaries stand in for the functions without MIR.
+
+use std::path::PathBuf;
+
+use crate::{
+    annotations::{Annotation, Annotations, Role},
+    compiler::{self, CrateNum, DefId, TyCtxt, LOCAL_CRATE},
+    error::Error,
+    eval::attributes::AttrInfo,
+    interchange,
+    summaries::Summaries,
+    taint_analysis::Contexts,
+};
+
+/// The extensions of the files of an interface, which replace that of the metadata.
+const ANNOTATIONS: &str = "taint-annotations.json";
+const SUMMARIES: &str = "taint-summaries.json";
+
+/// The files of the interface of `krate`, next to its metadata, whether they were written or not.
+fn files(tcx: TyCtxt<'_>, krate: CrateNum) -> Option<(PathBuf, PathBuf)> {
+    let metadata = compiler::metadata_path(tcx, krate)?;
+    Some((
+        metadata.with_extension(ANNOTATIONS),
+        metadata.with_extension(SUMMARIES),
+    ))
+}
+
+/// The crates the crate depends on which have an interface, and the files of each.
+fn dependencies(tcx: TyCtxt<'_>) -> impl Iterator<Item = (CrateNum, (PathBuf, PathBuf))> + '_ {
+    tcx.crates(())
+        .iter()
+        .filter_map(move |&krate| Some((krate, files(tcx, krate)?)))
+        .filter(|(_, (annotations, _))| annotations.is_file())
+}
+
+/// The annotations of the crates the crate depends on which have an interface.
+pub(crate) fn annotations(tcx: TyCtxt<'_>) -> Vec<(CrateNum, Result<Annotations, Error>)> {
+    dependencies(tcx)
+        .map(|(krate, (annotations, _))| (krate, Annotations::read(annotations)))
+        .collect()
+}
+
+/// The summaries of the functions of the crates the crate depends on which have an interface.
+pub(crate) fn summaries(tcx: TyCtxt<'_>) -> Vec<Result<Summaries, Error>> {
+    dependencies(tcx)
+        .map(|(_, (_, summaries))| Summaries::read(summaries))
+        .collect()
+}
+
+/// Writes the interface of the crate, if it is a library: the roles it declared, and the summaries of its
+/// functions analyzed in `contexts`. Both are written with the paths the crates depending on it see.
+pub(crate) fn export(
+    tcx: TyCtxt<'_>,
+    info: &AttrInfo,
+    contexts: &Contexts<'_>,
+) -> Result<(), Error> {
+    let Some((annotations, summaries)) = files(tcx, LOCAL_CRATE) else {
+        return Ok(());
+    };
+    let path = |id: DefId| format!("{}::{}", tcx.crate_name(LOCAL_CRATE), tcx.def_path_str(id));
+
+    let roles = [
+        (Role::Source, &info.sources),
+        (Role::SourceField, &info.source_fields),
+        (Role::Sink, &info.sinks),
+        (Role::ReturnSink, &info.return_sinks),
+        (Role::Sanitizer, &info.sanitizers),
+    ];
+    let mut declared = vec![];
+    for (role, ids) in roles {
+        // The roles the crate loaded from its own dependencies are in their interfaces.
+        for &id in ids.iter().filter(|id| id.is_local()) {
+            declared.push(Annotation {
+                path: path(id),
+                role,
+                label: info
+                    .labeled
+                    .get(&id)
+                    .map(|&label| info.label_name(tcx, label)),
+            });
+        }
+    }
+    Annotations {
+        annotations: declared,
+    }
+    .write(annotations)?;
+
+    interchange::export_named(tcx, info, contexts, |id| id.is_local().then(|| path(id)))
+        .write(summaries)
+}
@@ -0,0 +1,175 @@
+//! The inventory of annotated functions, printed with `--taint-list-annotations`.
+
+use std::fmt::Write;
+
+use crate::{
+    compiler::{self, DefId, Span, TyCtxt},
+    config::FILE_NAME,
+    eval::attributes::{AttrInfo, Origin},
+    options::Options,
+};
+
+/// Where the role of `id` was declared, like `attribute at src/lib.rs:12:1`.
+pub(crate) fn origin(tcx: TyCtxt<'_>, info: &AttrInfo, id: DefId) -> String {
+    match info.origins.get(&id) {
+        Some(Origin::Attribute(span)) => attribute(tcx, *span),
+        Some(Origin::Interface(krate)) => format!("interface of `{}`", tcx.crate_name(*krate)),
+        Some(Origin::Module(module)) => format!("module `{}`", tcx.def_path_str(*module)),
+        None => "unknown origin".to_owned(),
+    }
+}
+
+/// The attribute at `span`, like `attribute at src/lib.rs:12:1`.
+pub(crate) fn attribute(tcx: TyCtxt<'_>, span: Span) -> String {
+    match compiler::source_range(tcx, span) {
+        Some(range) => format!(
+            "attribute at {}:{}:{}",
+            range.file, range.start.line, range.start.column
+        ),
+        None => "attribute".to_owned(),
+    }
+}
+
+/// The comment at `span`, like `comment at src/lib.rs:12:5`.
+pub(crate) fn comment(tcx: TyCtxt<'_>, span: Span) -> String {
+    match compiler::source_range(tcx, span) {
+        Some(range) => format!(
+            "comment at {}:{}:{}",
+            range.file, range.start.line, range.start.column
+        ),
+        None => "comment".to_owned(),
+    }
+}
+
+/// The call at `span`, like `call at src/lib.rs:12:5`.
+pub(crate) fn call(tcx: TyCtxt<'_>, span: Span) -> String {
+    match compiler::source_range(tcx, span) {
+        Some(range) => format!(
+            "call at {}:{}:{}",
+            range.file, range.start.line, range.start.column
+        ),
+        None => "call".to_owned(),
+    }
+}
+
+/// Lists every function or field with a role, and where the role was declared, one per line,
+/// along with the acknowledgments of calls to gated sinks, the calls to barriers and the comments ignoring findings.
+pub(crate) fn list(tcx: TyCtxt<'_>, info: &AttrInfo, options: &Options) -> String {
+    let origin = |id| origin(tcx, info, id);
+
+    let mut lines = vec![];
+    for &id in &info.sources {
+        lines.push(format!(
+            "source `{}` ({})",
+            tcx.def_path_str(id),
+            origin(id)
+        ));
+    }
+    for &id in &info.source_fields {
+        lines.push(format!(
+            "source field `{}` ({})",
+            tcx.def_path_str(id),
+            origin(id)
+        ));
+    }
+    for (path, _) in &info.source_statics {
+        lines.push(format!("source static `{}` ({})", path, FILE_NAME));
+    }
+    for &id in info.sinks.iter().chain(&info.return_sinks) {
+        let mut line = format!("sink `{}`", tcx.def_path_str(id));
+        if info.return_sinks.contains(&id) {
+            line.push_str(" on return");
+        }
+        if info.gated.contains(&id) {
+            line.push_str(" gated");
+        }
+        for req in info.requirements.iter().filter(|req| req.sink == id) {
+            let _ = write!(line, " requires `{}`", tcx.def_path_str(req.sanitizer));
+        }
+        let _ = write!(line, " ({})", origin(id));
+        lines.push(line);
+    }
+    for &id in &info.sanitizers {
+        let mut line = format!("sanitizer `{}`", tcx.def_path_str(id));
+        for req in info.requirements.iter().filter(|req| req.sink == id) {
+            let _ = write!(line, " after `{}`", tcx.def_path_str(req.sanitizer));
+        }
+        if let Some(&(from, to)) = info.relabels.get(&id) {
+            let (from, to) = (info.label_name(tcx, from), info.label_name(tcx, to));
+            let _ = write!(line, " from `{}` to `{}`", from, to);
+        }
+        let _ = write!(line, " ({})", origin(id));
+        lines.push(line);
+    }
+    for (path, kind) in &info.added {
+        lines.push(format!(
+            "{} `{}` (--taint-add-{})",
+            kind.name(),
+            path,
+            kind.name()
+        ));
+    }
+    for name in &options.sink_macros {
+        lines.push(format!("sink macro `{}!` (--taint-sink-macros)", name));
+    }
+    for pack in &options.sink_packs {
+        lines.push(format!("sink pack `{}` (--taint-sink-packs)", pack.name()));
+    }
+    for pack in &options.source_packs {
+        lines.push(format!(
+            "source pack `{}` (--taint-source-packs)",
+            pack.name()
+        ));
+    }
+    for &(id, pack) in &info.exports {
+        lines.push(format!(
+            "source arguments `{}` (exported, source pack `{}`)",
+            tcx.def_path_str(id),
+            pack.name()
+        ));
+    }
+    for &id in &info.trusted {
+        lines.push(format!(
+            "trusted `{}` ({})",
+            tcx.def_path_str(id),
+            origin(id)
+        ));
+    }
+    for path in &info.trusted_unsafe {
+        lines.push(format!(
+            "trusted unsafe function `{}` ({})",
+            path, FILE_NAME
+        ));
+    }
+    for acknowledgment in &info.acknowledgments {
+        lines.push(format!(
+            "acknowledged calls: {} ({})",
+            acknowledgment.reason,
+            attribute(tcx, acknowledgment.attribute)
+        ));
+    }
+    for &(barrier, span) in &info.barriers {
+        lines.push(format!(
+            "barrier `{}` ({})",
+            tcx.def_path_str(barrier),
+            call(tcx, span)
+        ));
+    }
+    for ignore in &info.ignores {
+        let codes = ignore.codes.iter().map(|code| code.as_str());
+        lines.push(format!(
+            "ignored {}: {} ({})",
+            codes.collect::<Vec<_>>().join(", "),
+            ignore.reason,
+            comment(tcx, ignore.comment)
+        ));
+    }
+    lines.sort();
+
+    let mut inventory = String::new();
+    for line in lines {
+        inventory.push_str(&line);
+        inventory.push('\n');
+    }
+    inventory
+}
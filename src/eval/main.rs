@@ -1,35 +1,817 @@
-use rustc_hir::def_id::DefId;
-use rustc_middle::ty::TyCtxt;
-use rustc_mir_dataflow::Analysis;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
 
-use crate::eval::attributes::TaintAttributeFinder;
-use crate::taint_analysis::TaintAnalysis;
+use crate::analysis::labels::Labels;
+use crate::checkpoint::Checkpoint;
+use crate::compiler::{self, DefId, Diagnostics, ErrorCode, TyCtxt, LOCAL_CRATE};
+use crate::config::{Config, FILE_NAME};
+use crate::dependency_cache::DependencyCache;
+use crate::entries;
+use crate::error::Error;
+use crate::eval::attributes::{AttrInfo, TaintAttributeFinder};
+use crate::eval::progress::Events;
+use crate::eval::{changed, interfaces, inventory, policy, policy_check};
+use crate::history::History;
+use crate::hook::PolicyHook;
+use crate::interchange::{self, Imported};
+use crate::metrics::Metrics;
+use crate::options::{Emit, FailLevel, Options};
+use crate::report::{self, Report};
+use crate::slice;
+use crate::status::{Reported, Status};
+use crate::summaries::Summaries;
+use crate::taint_analysis::{Shared, TaintAnalysis};
+use crate::warm::Warm;
 
-pub fn eval_main(tcx: TyCtxt<'_>, main_id: DefId) {
+/// The queries the driver must replace for the analysis to see the MIR asked for in `options`.
+pub fn override_queries(
+    options: &Options,
+) -> Option<fn(&compiler::Session, &mut compiler::Providers, &mut compiler::ExternProviders)> {
+    compiler::override_queries(options.mir)
+}
+
+/// Analyzes the crate from its `main` function, or from its public functions if it is a library,
+/// is analyzed as one with `--taint-library`, or is compiled with `--test`.
+/// Returns the number of findings and invalid annotations.
+pub fn eval_crate(tcx: TyCtxt<'_>, options: &Options) -> usize {
+    match tcx.entry_fn(()) {
+        // With `--test`, the entry point is the harness which runs the tests, which have entry points of their own.
+        Some((entry_def_id, _)) if !options.library && !tcx.sess.opts.test => {
+            eval_main(tcx, entry_def_id, options)
+        }
+        _ => eval_all_pub_fn(tcx, options),
+    }
+}
+
+/// Analyzes the program starting at `main_id`, returning the number of findings and invalid annotations.
+pub fn eval_main(tcx: TyCtxt<'_>, main_id: DefId, options: &Options) -> usize {
+    let run = Run::start();
+    let config = config(tcx, options);
     // Find all functions in the current crate that have been tagged
-    let mut finder = TaintAttributeFinder::new(tcx);
-    tcx.hir().visit_all_item_likes_in_crate(&mut finder);
+    let info = TaintAttributeFinder::collect(tcx, &config, options);
+    if describe(tcx, &info, options, &config) {
+        return info.errors;
+    }
+
+    let shared = shared(tcx, &info, options, &config);
 
-    let entry = tcx.optimized_mir(main_id);
+    let entries = with_accessors(&info, vec![main_id]);
+    let entry_points = match analyze(
+        tcx,
+        &info,
+        options,
+        &config,
+        entries.clone(),
+        Labels::EMPTY,
+        &shared,
+    ) {
+        Ok(entry_points) => entry_points,
+        Err(error) => {
+            Diagnostics::new(tcx).failure(&error);
+            return info.errors;
+        }
+    };
+    check_hash_order(
+        tcx,
+        &info,
+        options,
+        &config,
+        &entries,
+        Labels::EMPTY,
+        &shared,
+    );
 
-    let _ = TaintAnalysis::new(tcx, &finder.info)
-        .into_engine(tcx, entry)
-        .pass_name("taint_analysis")
-        .iterate_to_fixpoint();
+    let errors = info.errors + report(tcx, &info, options, &config, &shared);
+    run.finish(tcx, options, &shared, entry_points, errors);
+    errors
 }
 
-pub fn eval_all_pub_fn(tcx: TyCtxt<'_>) {
-    let mut finder = TaintAttributeFinder::new(tcx);
-    tcx.hir().visit_all_item_likes_in_crate(&mut finder);
-    for def_id in tcx
+/// Analyzes every public function of the crate, returning the number of findings and invalid annotations.
+pub fn eval_all_pub_fn(tcx: TyCtxt<'_>, options: &Options) -> usize {
+    let run = Run::start();
+    let config = config(tcx, options);
+    let info = TaintAttributeFinder::collect(tcx, &config, options);
+    if describe(tcx, &info, options, &config) {
+        return info.errors;
+    }
+
+    // Every entry point uses the same summaries, so that callees are only analyzed once per context.
+    let shared = shared(tcx, &info, options, &config);
+
+    // In library mode, the callers of public functions are not to be trusted.
+    let args = if options.library {
+        info.source_labels()
+    } else {
+        Labels::EMPTY
+    };
+
+    // Code only compiled for `--test` is left out, unless the tests are to be analyzed as well.
+    let mut entries = tcx
         .mir_keys(())
         .iter()
         .filter(|&&def_id| tcx.visibility(def_id).is_public())
+        .map(|def_id| def_id.to_def_id())
+        .filter(|&def_id| compiler::is_fn(tcx, def_id))
+        .filter(|&def_id| options.include_tests || !compiler::is_test_code(tcx, def_id))
+        .collect::<Vec<_>>();
+    if options.include_tests {
+        for test in compiler::test_functions(tcx) {
+            if !entries.contains(&test) {
+                entries.push(test);
+            }
+        }
+    }
+    // Functions exported to another language are called from code we cannot see, whatever their visibility.
+    for &(export, _) in &info.exports {
+        if !entries.contains(&export) {
+            entries.push(export);
+        }
+    }
+    let entries = with_accessors(&info, entries);
+    let entry_points = match analyze(tcx, &info, options, &config, entries.clone(), args, &shared) {
+        Ok(entry_points) => entry_points,
+        Err(error) => {
+            Diagnostics::new(tcx).failure(&error);
+            return info.errors;
+        }
+    };
+    check_hash_order(tcx, &info, options, &config, &entries, args, &shared);
+
+    let errors = info.errors + report(tcx, &info, options, &config, &shared);
+    run.finish(tcx, options, &shared, entry_points, errors);
+    errors
+}
+
+/// `entries` and the accessors of the types marked as sources, which formatting may call through pointers
+/// the analysis does not follow.
+fn with_accessors(info: &AttrInfo, mut entries: Vec<DefId>) -> Vec<DefId> {
+    for accessor in &info.accessors {
+        if !entries.contains(&accessor.function) {
+            entries.push(accessor.function);
+        }
+    }
+    entries
+}
+
+/// Analyzes `entries` with every argument carrying `args`, or with `--taint-entries`, the functions of the crate
+/// the file lists, each with the options of its profile.
+///
+/// Entry points of the same profile share their summaries, but not with those of other profiles, which were
+/// computed with other options. Once analyzed, their findings and facts are added to those in `shared`.
+/// Returns the number of entry points analyzed.
+fn analyze<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    info: &AttrInfo,
+    options: &Options,
+    config: &Config,
+    entries: Vec<DefId>,
+    args: Labels,
+    shared: &Shared<'tcx>,
+) -> Result<usize, Error> {
+    let Some(path) = &options.entries else {
+        let entries = changed_only(tcx, options, entries);
+        return analyze_entries(tcx, info, options, &entries, args, shared);
+    };
+
+    // The entry points of each profile, in the order the file first lists them.
+    let mut profiles: Vec<(Option<String>, Vec<DefId>)> = vec![];
+    for listed in entries::read(path)? {
+        let Some(entry) = listed_function(tcx, info, &listed.path) else {
+            continue;
+        };
+        match profiles
+            .iter_mut()
+            .find(|(profile, _)| *profile == listed.profile)
+        {
+            Some((_, entries)) => entries.push(entry),
+            None => profiles.push((listed.profile, vec![entry])),
+        }
+    }
+
+    let mut entry_points = 0;
+    for (profile, entries) in profiles {
+        if shared.failed_fast.get() {
+            break;
+        }
+        let entries = changed_only(tcx, options, entries);
+        let Some(name) = profile else {
+            entry_points += analyze_entries(tcx, info, options, &entries, args, shared)?;
+            continue;
+        };
+        let (_, profile_args) = config
+            .profiles
+            .iter()
+            .find(|(profile, _)| *profile == name)
+            .ok_or_else(|| {
+                Error::option(
+                    &format!("--taint-entries={}", path.display()),
+                    format!("profile `{}` is not in the config", name),
+                )
+            })?;
+        let options = options.with_profile(&name, profile_args)?;
+        // In library mode, the callers of entry points are not to be trusted.
+        let args = if options.library {
+            info.source_labels()
+        } else {
+            Labels::EMPTY
+        };
+        let profile_shared = self::shared(tcx, info, &options, config);
+        entry_points += analyze_entries(tcx, info, &options, &entries, args, &profile_shared)?;
+        shared.failed_fast.set(profile_shared.failed_fast.get());
+        shared
+            .findings
+            .borrow_mut()
+            .absorb(profile_shared.findings.into_inner());
+        shared
+            .facts
+            .borrow_mut()
+            .absorb(profile_shared.facts.into_inner());
+    }
+    Ok(entry_points)
+}
+
+/// The function of the crate at `path`, with or without the name of the crate, or one of its aliases.
+fn listed_function(tcx: TyCtxt<'_>, info: &AttrInfo, path: &str) -> Option<DefId> {
+    let path = info.canonical_path(path);
+    let krate = tcx.crate_name(LOCAL_CRATE);
+    let path = path
+        .strip_prefix(krate.as_str())
+        .and_then(|path| path.strip_prefix("::"))
+        .unwrap_or(path);
+    tcx.mir_keys(())
+        .iter()
+        .map(|def_id| def_id.to_def_id())
+        .filter(|&def_id| compiler::is_fn(tcx, def_id))
+        .find(|&def_id| tcx.def_path_str(def_id) == path)
+}
+
+/// The entry points among `entries` to analyze: with `--taint-changed-only`, those which may run a function
+/// which changed, or all of them if the changes cannot be read.
+fn changed_only(tcx: TyCtxt<'_>, options: &Options, entries: Vec<DefId>) -> Vec<DefId> {
+    let Some(changed_only) = &options.changed_only else {
+        return entries;
+    };
+    match changed::changes(changed_only) {
+        Ok(changes) => changed::changed_entries(tcx, &changes, &entries),
+        Err(error) => {
+            Diagnostics::new(tcx).failure(&error);
+            entries
+        }
+    }
+}
+
+/// Prints the descriptions of the policy asked for instead of analyzing, like `--taint-list-annotations`.
+/// Returns whether any was asked for.
+fn describe(tcx: TyCtxt<'_>, info: &AttrInfo, options: &Options, config: &Config) -> bool {
+    if options.list_annotations {
+        print!("{}", inventory::list(tcx, info, options));
+    }
+    for emit in &options.emit {
+        match emit {
+            Emit::PolicyDoc => print!("{}", policy::document(tcx, info, options, config)),
+            Emit::PolicyCheck => print!("{}", policy_check::check(tcx, info, options, config)),
+        }
+    }
+    options.list_annotations || !options.emit.is_empty()
+}
+
+/// The configs read so far, by the path they were given with,
+/// along with a hash of the contents of their file as they were read.
+type Configs = HashMap<Option<PathBuf>, (Option<u64>, Result<Config, Error>)>;
+
+/// The config of the project, or an empty one if it cannot be read.
+/// Each config is only read once per process, however many crates it analyzes,
+/// and read again once the contents of its file changed, for tools which keep running the analysis in process
+/// as the config is edited. Contents are compared rather than times, which may not tell apart edits made
+/// within the same second.
+fn config(tcx: TyCtxt<'_>, options: &Options) -> Config {
+    static DISCOVERED: OnceLock<Mutex<Configs>> = OnceLock::new();
+    let file = options.config.as_deref().unwrap_or(Path::new(FILE_NAME));
+    let contents = fs::read(file).ok().map(|contents| {
+        let mut hasher = DefaultHasher::new();
+        contents.hash(&mut hasher);
+        hasher.finish()
+    });
+    let mut configs = DISCOVERED.get_or_init(Mutex::default).lock().unwrap();
+    let (read, config) = configs
+        .entry(options.config.clone())
+        .or_insert_with(|| (contents, Config::discover(options.config.as_deref())));
+    if *read != contents {
+        *read = contents;
+        *config = Config::discover(options.config.as_deref());
+    }
+    config.clone().unwrap_or_else(|error| {
+        Diagnostics::new(tcx).failure(&error);
+        Config::default()
+    })
+}
+
+/// The state shared between entry points, starting with the summaries bundled for the dependencies
+/// of the crate, those imported with `--taint-import-summaries`, the models declared with `#[taint::model]`,
+/// and the propagation rules of the config, each taking precedence over the ones before and over those
+/// cached for dependencies with `--taint-dependency-cache`, along with the summaries carried over from the previous
+/// run and the summaries and findings of the checkpoint `--taint-resume` resumes from.
+fn shared<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    info: &AttrInfo,
+    options: &Options,
+    config: &Config,
+) -> Shared<'tcx> {
+    let mut summaries = vec![];
+    if !options.skip_summary_packs {
+        let crates = tcx.crates(()).iter().map(|&krate| tcx.crate_name(krate));
+        summaries.extend(crates.filter_map(|name| Summaries::pack(name.as_str())));
+    }
+    if let Some(path) = &options.import_summaries {
+        summaries.push(Summaries::read(path));
+    }
+    let models = info.models.iter().map(|(model, _)| model.clone());
+    summaries.push(Ok(Summaries {
+        summaries: models.collect(),
+    }));
+    // The rules of the config are carried over with the config, which tells which of them changed.
+    let warm = Warm::start(tcx, info, options, config, &summaries);
+    summaries.push(Ok(config.propagation.clone()));
+    let dependency_cache = DependencyCache::open(options, config, &summaries);
+    let checkpoint = Checkpoint::open(tcx, options, config, &summaries);
+    let cached = dependency_cache
+        .as_ref()
+        .map_or_else(Vec::new, |cache| cache.summaries(tcx));
+
+    let mut imported = Imported::default();
+    for summaries in cached.iter().chain(&summaries) {
+        let added = summaries
+            .clone()
+            .and_then(|summaries| imported.add(tcx, info, &summaries));
+        if let Err(error) = added {
+            Diagnostics::new(tcx).failure(&error);
+        }
+    }
+    let hook = options.policy_hook.as_ref().and_then(|script| {
+        PolicyHook::load(script)
+            .map_err(|error| Diagnostics::new(tcx).failure(&error))
+            .ok()
+    });
+    let mut interfaces = Imported::default();
+    if options.crate_interfaces {
+        for summaries in interfaces::summaries(tcx) {
+            let added = summaries.and_then(|summaries| interfaces.add(tcx, info, &summaries));
+            if let Err(error) = added {
+                Diagnostics::new(tcx).failure(&error);
+            }
+        }
+    }
+    let shared = Shared {
+        imported,
+        interfaces,
+        warm: RefCell::new(warm),
+        dependency_cache,
+        checkpoint,
+        hook: RefCell::new(hook),
+        ..Shared::default()
+    };
+    if let Some(checkpoint) = &shared.checkpoint {
+        shared.findings.borrow_mut().resume(checkpoint.findings());
+    }
+    shared
+}
+
+/// With `--taint-debug-hash-order`, analyzes `entries` again as many times as asked, each time with state
+/// whose maps are created anew, and so seeded anew, and panics if a run finds other than the first did,
+/// in `shared`: a finding then depends on the order a map iterates in.
+fn check_hash_order<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    info: &AttrInfo,
+    options: &Options,
+    config: &Config,
+    entries: &[DefId],
+    args: Labels,
+    shared: &Shared<'tcx>,
+) {
+    let Some(runs) = options.debug_hash_order else {
+        return;
+    };
+    let first = shared.findings.borrow().report(tcx);
+    for run in 2..=runs {
+        let again = self::shared(tcx, info, options, config);
+        if analyze(tcx, info, options, config, entries.to_vec(), args, &again).is_err() {
+            return;
+        }
+        let report = again.findings.borrow().report(tcx);
+        if report == first {
+            continue;
+        }
+        let fingerprints = |report: &Report| {
+            report
+                .findings
+                .iter()
+                .map(|finding| finding.fingerprint.clone())
+                .collect::<Vec<_>>()
+        };
+        let (expected, found) = (fingerprints(&first), fingerprints(&report));
+        let missing = expected
+            .iter()
+            .filter(|fingerprint| !found.contains(fingerprint));
+        let added = found
+            .iter()
+            .filter(|fingerprint| !expected.contains(fingerprint));
+        let (missing, added) = (missing.collect::<Vec<_>>(), added.collect::<Vec<_>>());
+        if missing.is_empty() && added.is_empty() {
+            panic!(
+                "run {} of {} found the findings of the first, but in another order or with other details",
+                run, runs
+            );
+        }
+        panic!(
+            "run {} of {} found other findings than the first: missing {:?}, added {:?}",
+            run, runs, missing, added
+        );
+    }
+}
+
+/// Analyze each of `entries` with every argument carrying `args`, reporting progress as we go,
+/// and checkpointing it with `--taint-resume`.
+/// Fails if the analysis is cancelled before it is done, in which case nothing should be reported,
+/// and stops after the entry point it failed fast in with `--taint-fail-fast`.
+/// Returns the number of entry points analyzed.
+fn analyze_entries<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    info: &AttrInfo,
+    options: &Options,
+    entries: &[DefId],
+    args: Labels,
+    shared: &Shared<'tcx>,
+) -> Result<usize, Error> {
+    let events = Events::new(tcx, options.progress);
+    events.started(entries.len());
+    let checkpoint = |stopping| {
+        if let Some(checkpoint) = &shared.checkpoint {
+            checkpoint.save(tcx, shared, stopping);
+        }
+    };
+    for (index, &entry) in entries.iter().enumerate() {
+        events.entry_started(entry, index);
+        // The findings of an entry point the checkpoint resumed from was done with are those it holds.
+        let done = shared
+            .checkpoint
+            .as_ref()
+            .map_or(false, |checkpoint| checkpoint.is_done(tcx, entry));
+        if !done {
+            analyze_entry(tcx, info, options, entry, args, shared);
+        }
+        if options.cancellation.is_cancelled() {
+            shared.warm.borrow_mut().leave();
+            checkpoint(true);
+            events.cancelled(shared);
+            return Err(Error::Cancelled);
+        }
+        events.entry_finished(entry, index, shared);
+        if shared.failed_fast.get() {
+            shared.warm.borrow_mut().leave();
+            checkpoint(true);
+            events.finished(shared);
+            return Ok(index + 1);
+        }
+        if let Some(checkpoint) = &shared.checkpoint {
+            checkpoint.done(tcx, entry);
+        }
+        checkpoint(false);
+    }
+    shared.warm.borrow_mut().leave();
+    checkpoint(true);
+    events.finished(shared);
+    Ok(entries.len())
+}
+
+/// Analyze `entry` with every argument carrying `args`, or the labels of sources without one
+/// if it is exported to another language.
+///
+/// Entry points are summarized like any other function, so an entry point which was already
+/// analyzed as the callee of another one (or the other way around) is not analyzed again.
+fn analyze_entry<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    info: &AttrInfo,
+    options: &Options,
+    entry: DefId,
+    args: Labels,
+    shared: &Shared<'tcx>,
+) {
+    let Some(body) = compiler::mir_body(tcx, entry) else {
+        return;
+    };
+    let args = if info.exports.iter().any(|&(export, _)| export == entry) {
+        info.source_labels()
+    } else {
+        args
+    };
+    let init = vec![args; body.arg_count];
+    let _ = TaintAnalysis::new(tcx, info, options, entry, shared).summarize(
+        entry,
+        compiler::identity_args(tcx, entry),
+        init,
+        None,
+    );
+}
+
+/// Print and write out what was asked for besides the diagnostics,
+/// returning the number of findings reported as errors.
+fn report(
+    tcx: TyCtxt<'_>,
+    info: &AttrInfo,
+    options: &Options,
+    config: &Config,
+    shared: &Shared<'_>,
+) -> usize {
+    if options.summary_report {
+        print!(
+            "{}",
+            shared.usage.borrow().report(tcx, &shared.contexts.borrow())
+        );
+    }
+
+    for (krate, functions) in shared.usage.borrow().not_encoded(tcx) {
+        Diagnostics::new(tcx).warning(
+            format!(
+                "the MIR of `{}` was not encoded, so calls to {} of its functions, like `{}`, follow --taint-unknown-calls",
+                krate,
+                functions.len(),
+                functions[0]
+            ),
+            &[(
+                None,
+                "build it with `-Zalways-encode-mir`, which `cargo taint` passes to dependencies".to_owned(),
+            )],
+        );
+    }
+
+    if let Some(path) = &options.results {
+        let results = shared.facts.borrow().results(tcx, info);
+        if let Err(error) = results.write(path) {
+            Diagnostics::new(tcx).failure(&error);
+        }
+    }
+
+    if shared.failed_fast.get() {
+        let mut notes = vec![(None, "run without it to find them all".to_owned())];
+        // The summaries of the functions the analysis was in when it stopped leave out the rest of their callees.
+        if options.export_summaries.is_some() || options.crate_interfaces {
+            notes.push((
+                None,
+                "summaries are not exported, as some were computed before their function was done"
+                    .to_owned(),
+            ));
+        }
+        Diagnostics::new(tcx).warning(
+            "the analysis stopped at its first error, as --taint-fail-fast asks, so other findings may be missing"
+                .to_owned(),
+            &notes,
+        );
+    }
+
+    if let Some(path) = options
+        .export_summaries
+        .as_ref()
+        .filter(|_| !shared.failed_fast.get())
     {
-        let mir = tcx.optimized_mir(*def_id);
-        let _ = TaintAnalysis::new(tcx, &finder.info)
-            .into_engine(tcx, mir)
-            .pass_name("taint_analysis")
-            .iterate_to_fixpoint();
+        let summaries = interchange::export(tcx, info, &shared.contexts.borrow());
+        if let Err(error) = summaries.write(path) {
+            Diagnostics::new(tcx).failure(&error);
+        }
+    }
+
+    if options.crate_interfaces && !shared.failed_fast.get() {
+        if let Err(error) = interfaces::export(tcx, info, &shared.contexts.borrow()) {
+            Diagnostics::new(tcx).failure(&error);
+        }
+    }
+
+    if let Some(cache) = &shared.dependency_cache {
+        let contexts = shared.contexts.borrow();
+        if let Err(error) = cache.write(tcx, info, &contexts, &shared.side_effects.borrow()) {
+            Diagnostics::new(tcx).failure(&error);
+        }
+    }
+
+    if let Some(fingerprint) = &options.slice {
+        match shared.findings.borrow().find(tcx, fingerprint) {
+            Some((function, sink)) => {
+                let spans = slice::slice(tcx, &shared.facts.borrow(), function, sink);
+                print!(
+                    "slice of finding {} in `{}`:\n{}",
+                    fingerprint,
+                    tcx.def_path_str(function),
+                    slice::render(tcx, &spans, sink)
+                );
+            }
+            None => Diagnostics::new(tcx).failure(&Error::option(
+                &format!("--taint-slice={}", fingerprint),
+                "no finding has this fingerprint",
+            )),
+        }
+    }
+
+    if let Some(dir) = &options.reproducers {
+        if let Err(error) = write_reproducers(tcx, shared, dir) {
+            Diagnostics::new(tcx).failure(&error);
+        }
+    }
+
+    if let Some(path) = &options.findings {
+        if let Err(error) = findings_report(tcx, options, config, shared).write(path) {
+            Diagnostics::new(tcx).failure(&error);
+        }
+    }
+
+    if let Some(path) = &options.findings_db {
+        let findings = findings_report(tcx, options, config, shared).findings;
+        let krate = tcx.crate_name(LOCAL_CRATE);
+        if let Err(error) = History::append(path, krate.as_str(), findings) {
+            Diagnostics::new(tcx).failure(&error);
+        }
+    }
+
+    if !options.reporters.0.is_empty() {
+        let report = findings_report(tcx, options, config, shared);
+        let krate = tcx.crate_name(LOCAL_CRATE);
+        for reporter in &options.reporters.0 {
+            if let Err(error) = reporter.send(krate.as_str(), &report) {
+                Diagnostics::new(tcx).failure(&error);
+            }
+        }
     }
+
+    shared.findings.borrow().emit_stored_crossings(tcx, info);
+    shared.findings.borrow().emit_tracked_clones(tcx, info);
+    let others = shared.findings.borrow().emit_missing_taint(tcx, info)
+        + shared.findings.borrow().emit_unmodeled(tcx);
+    if options.lint_clean_sanitizers {
+        shared.findings.borrow().emit_clean_sanitizers(tcx, info);
+    }
+    if options.coverage_report {
+        print!("{}", shared.findings.borrow().coverage(tcx, info));
+    }
+
+    // Findings written as fuzz targets are not reported as errors,
+    // unlike missing taint and unmodeled constructs.
+    if let Some(path) = &options.fuzz_targets {
+        if let Err(error) = shared.findings.borrow().targets(tcx).write(path) {
+            Diagnostics::new(tcx).failure(&error);
+        }
+        return others;
+    }
+    others + shared.findings.borrow().emit(tcx, options.fail_level)
+}
+
+/// Writes a program reproducing each finding to `<fingerprint>.rs` in `dir`, which is created if needed.
+fn write_reproducers(tcx: TyCtxt<'_>, shared: &Shared<'_>, dir: &Path) -> Result<(), Error> {
+    fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+    let reproducers = shared
+        .findings
+        .borrow()
+        .reproducers(tcx, &shared.facts.borrow());
+    for (fingerprint, program) in reproducers {
+        let path = dir.join(format!("{}.rs", fingerprint));
+        fs::write(&path, program).map_err(|e| Error::io(&path, e))?;
+    }
+    Ok(())
+}
+
+/// The findings of the run, along with those of `--taint-baseline`, each given the owner the config
+/// gives its function.
+fn findings_report(
+    tcx: TyCtxt<'_>,
+    options: &Options,
+    config: &Config,
+    shared: &Shared<'_>,
+) -> Report {
+    let mut report = with_baseline(tcx, options, shared, shared.findings.borrow().report(tcx));
+    let krate = tcx.crate_name(LOCAL_CRATE);
+    for finding in &mut report.findings {
+        finding.owner = config
+            .owner(krate.as_str(), &finding.function)
+            .map(str::to_owned);
+    }
+    report
+}
+
+/// `report` along with the findings of `--taint-baseline` in the functions which `--taint-changed-only`
+/// did not analyze again.
+fn with_baseline(
+    tcx: TyCtxt<'_>,
+    options: &Options,
+    shared: &Shared<'_>,
+    report: Report,
+) -> Report {
+    let (Some(_), Some(path)) = (&options.changed_only, &options.baseline) else {
+        return report;
+    };
+    match Report::read(path) {
+        Ok(baseline) => {
+            let mut analyzed = shared
+                .contexts
+                .borrow()
+                .keys()
+                .map(|&(id, ..)| tcx.def_path_str(id))
+                .collect::<Vec<_>>();
+            analyzed.sort();
+            analyzed.dedup();
+            report.with_baseline(baseline, &analyzed)
+        }
+        Err(error) => {
+            Diagnostics::new(tcx).failure(&error);
+            report
+        }
+    }
+}
+
+/// A run of the analysis on the crate, from when it started, for its status line, `--taint-junit`
+/// and `--taint-metrics`.
+struct Run {
+    started: Instant,
+    /// The warnings emitted before the run, which were about other crates.
+    warnings: usize,
+}
+
+impl Run {
+    fn start() -> Run {
+        Run {
+            started: Instant::now(),
+            warnings: compiler::warnings_emitted(),
+        }
+    }
+
+    /// Prints the status line of the run, which analyzed `entry_points` and reported `errors`, to stderr
+    /// with `--taint-status-line`, and writes it with the findings to `--taint-junit` and `--taint-metrics`.
+    fn finish(
+        self,
+        tcx: TyCtxt<'_>,
+        options: &Options,
+        shared: &Shared<'_>,
+        entry_points: usize,
+        errors: usize,
+    ) {
+        let status = Status {
+            errors,
+            warnings: compiler::warnings_emitted() - self.warnings,
+            entry_points,
+            seconds: self.started.elapsed().as_secs_f64(),
+        };
+        if options.status_line {
+            eprintln!("{}", status);
+        }
+
+        if options.junit.is_none() && options.metrics.is_none() {
+            return;
+        }
+        // Findings written as fuzz targets are not reported, and those kept from `--taint-baseline` were not
+        // reported again.
+        let findings = if options.fuzz_targets.is_some() {
+            vec![]
+        } else {
+            shared
+                .findings
+                .borrow()
+                .report(tcx)
+                .findings
+                .into_iter()
+                .map(|finding| Reported {
+                    error: !is_warning(&finding, options.fail_level),
+                    finding,
+                })
+                .collect()
+        };
+        let krate = tcx.crate_name(LOCAL_CRATE);
+        if let Some(path) = &options.junit {
+            if let Err(error) = status.write_junit(path, krate.as_str(), &findings) {
+                Diagnostics::new(tcx).failure(&error);
+            }
+        }
+        if let Some(path) = &options.metrics {
+            let (analyzed, without_mir) = shared.usage.borrow().coverage();
+            let metrics = Metrics {
+                krate: krate.to_string(),
+                status,
+                findings,
+                analyzed,
+                without_mir,
+            };
+            if let Err(error) = metrics.write(path) {
+                Diagnostics::new(tcx).failure(&error);
+            }
+        }
+    }
+}
+
+/// Whether `finding` was reported as a warning, like `Findings::emit` decides.
+fn is_warning(finding: &report::Finding, fail_level: FailLevel) -> bool {
+    let warning_code = ErrorCode::ALL
+        .iter()
+        .any(|code| code.as_str() == finding.code && code.is_warning());
+    warning_code || (fail_level == FailLevel::HighConfidence && !finding.confidence.is_high())
 }
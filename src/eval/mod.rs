@@ -1,4 +1,11 @@
 //! Logic for running the taint analysis
 
 pub mod attributes;
+mod changed;
+pub mod integration;
+mod interfaces;
+mod inventory;
 pub mod main;
+mod policy;
+mod policy_check;
+mod progress;
@@ -0,0 +1,310 @@
+//! The policy the analysis enforces, as a Markdown page printed with `--taint-emit=policy-doc`.
+//!
+//! It describes what the analysis would check with the same annotations, config and options,
+//! and where each rule comes from, so that it can be reviewed without reading the code.
+
+use std::fmt::Write;
+
+use crate::{
+    compiler::{TyCtxt, LOCAL_CRATE},
+    config::{Config, HashPolicy, FILE_NAME},
+    eval::{
+        attributes::{AttrInfo, AttrInfoKind},
+        inventory,
+    },
+    options::Options,
+    summaries::{Summaries, PACKS},
+};
+
+/// A Markdown table with `header` and `rows`, or a line saying there is nothing to list.
+fn table(header: &[&str], mut rows: Vec<Vec<String>>) -> String {
+    if rows.is_empty() {
+        return "None.\n".to_owned();
+    }
+    rows.sort();
+
+    let mut table = format!("| {} |\n|", header.join(" | "));
+    for _ in header {
+        table.push_str(" --- |");
+    }
+    table.push('\n');
+    for row in rows {
+        let _ = writeln!(table, "| {} |", row.join(" | "));
+    }
+    table
+}
+
+/// Documents the sources, sinks, sanitizers, acknowledgments, barriers, ignored findings and labels of the crate, and the built-in rules enabled.
+pub(crate) fn document(
+    tcx: TyCtxt<'_>,
+    info: &AttrInfo,
+    options: &Options,
+    config: &Config,
+) -> String {
+    let path = |id| format!("`{}`", tcx.def_path_str(id));
+    let origin = |id| inventory::origin(tcx, info, id);
+    let label = |id| {
+        info.labeled.get(&id).map_or_else(
+            || "any".to_owned(),
+            |&label| format!("`{}`", info.label_name(tcx, label)),
+        )
+    };
+
+    // Functions given a role on the command line, which have no label or requirement.
+    let added = |role| {
+        info.added
+            .iter()
+            .filter(move |&&(_, kind)| kind == role)
+            .map(|(path, kind)| {
+                (
+                    format!("`{}`", path),
+                    format!("`--taint-add-{}`", kind.name()),
+                )
+            })
+    };
+
+    let mut doc = format!("# Taint policy of `{}`\n", tcx.crate_name(LOCAL_CRATE));
+
+    doc.push_str("\n## Sources\n\n");
+    let sources = info.sources.iter().chain(&info.source_fields).map(|&id| {
+        let label = info.labeled.get(&id).map_or_else(
+            || "`taint`".to_owned(),
+            |&label| format!("`{}`", info.label_name(tcx, label)),
+        );
+        vec![path(id), label, origin(id)]
+    });
+    let sources = sources
+        .chain(
+            added(AttrInfoKind::Source)
+                .map(|(path, origin)| vec![path, "`taint`".to_owned(), origin]),
+        )
+        .chain(info.source_statics.iter().map(|(path, label)| {
+            let label = label.map_or("taint".to_owned(), |label| info.label_name(tcx, label));
+            vec![
+                format!("`{}`", path),
+                format!("`{}`", label),
+                FILE_NAME.to_owned(),
+            ]
+        }))
+        .chain(info.exports.iter().map(|&(id, pack)| {
+            vec![
+                format!("{} arguments", path(id)),
+                "`taint`".to_owned(),
+                format!("source pack `{}`", pack.name()),
+            ]
+        }));
+    doc.push_str(&table(&["Source", "Label", "Origin"], sources.collect()));
+
+    doc.push_str("\n## Sinks\n\n");
+    let sinks = info
+        .sinks
+        .iter()
+        .map(|&id| {
+            if info.gated.contains(&id) {
+                (id, "arguments, unless acknowledged")
+            } else {
+                (id, "arguments")
+            }
+        })
+        .chain(info.return_sinks.iter().map(|&id| (id, "returned value")))
+        .map(|(id, checks)| {
+            let requires = info
+                .requirements
+                .iter()
+                .filter(|req| req.sink == id)
+                .map(|req| path(req.sanitizer))
+                .collect::<Vec<_>>();
+            let requires = if requires.is_empty() {
+                "any sanitizer".to_owned()
+            } else {
+                requires.join(", ")
+            };
+            vec![path(id), checks.to_owned(), label(id), requires, origin(id)]
+        })
+        .chain(added(AttrInfoKind::Sink).map(|(path, origin)| {
+            let checks = "arguments".to_owned();
+            vec![
+                path,
+                checks,
+                "any".to_owned(),
+                "any sanitizer".to_owned(),
+                origin,
+            ]
+        }));
+    doc.push_str(&table(
+        &["Function", "Checks", "Labels", "Requires", "Origin"],
+        sinks.collect(),
+    ));
+
+    doc.push_str("\n## Sanitizers\n\n");
+    let sanitizers = info
+        .sanitizers
+        .iter()
+        .map(|&id| {
+            let after = info
+                .requirements
+                .iter()
+                .filter(|req| req.sink == id)
+                .map(|req| path(req.sanitizer))
+                .collect::<Vec<_>>();
+            let after = if after.is_empty() {
+                "any order".to_owned()
+            } else {
+                after.join(", ")
+            };
+            let labels = info.relabels.get(&id).map_or_else(
+                || "removes all".to_owned(),
+                |&(from, to)| {
+                    format!(
+                        "`{}` to `{}`",
+                        info.label_name(tcx, from),
+                        info.label_name(tcx, to)
+                    )
+                },
+            );
+            vec![path(id), after, labels, origin(id)]
+        })
+        .chain(added(AttrInfoKind::Sanitizer).map(|(path, origin)| {
+            vec![
+                path,
+                "any order".to_owned(),
+                "removes all".to_owned(),
+                origin,
+            ]
+        }));
+    doc.push_str(&table(
+        &["Function", "After", "Labels", "Origin"],
+        sanitizers.collect(),
+    ));
+
+    doc.push_str("\n## Acknowledgments\n\n");
+    let acknowledgments = info.acknowledgments.iter().map(|acknowledgment| {
+        vec![
+            acknowledgment.reason.clone(),
+            inventory::attribute(tcx, acknowledgment.attribute),
+        ]
+    });
+    doc.push_str(&table(&["Reason", "Origin"], acknowledgments.collect()));
+
+    doc.push_str("\n## Barriers\n\n");
+    // Calls to the functions of trusted modules return clean data like those to barriers.
+    let barriers = info
+        .barriers
+        .iter()
+        .map(|&(barrier, span)| vec![path(barrier), inventory::call(tcx, span)])
+        .chain(info.trusted.iter().map(|&id| vec![path(id), origin(id)]));
+    doc.push_str(&table(&["Function", "Origin"], barriers.collect()));
+
+    doc.push_str("\n## Ignored findings\n\n");
+    let ignores = info.ignores.iter().map(|ignore| {
+        let codes = ignore
+            .codes
+            .iter()
+            .map(|code| format!("`{}`", code.as_str()));
+        vec![
+            codes.collect::<Vec<_>>().join(", "),
+            ignore.reason.clone(),
+            inventory::comment(tcx, ignore.comment),
+        ]
+    });
+    doc.push_str(&table(&["Codes", "Reason", "Origin"], ignores.collect()));
+
+    doc.push_str("\n## Labels\n\n");
+    let labels = info.names.iter().map(|(_, name)| {
+        let declared = config.labels.iter().find(|(label, _)| label == name);
+        let subsumes = declared.map_or_else(String::new, |(_, subsumed)| {
+            subsumed
+                .iter()
+                .map(|label| format!("`{}`", label))
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+        let in_config = declared.is_some()
+            || config
+                .labels
+                .iter()
+                .any(|(_, subsumed)| subsumed.contains(name));
+        let origin = if in_config { FILE_NAME } else { "attribute" };
+        vec![format!("`{}`", name), subsumes, origin.to_owned()]
+    });
+    doc.push_str(&table(&["Label", "Subsumes", "Origin"], labels.collect()));
+
+    doc.push_str("\n## Built-in rules\n\n");
+    let mut rules = vec![];
+    for name in &options.sink_macros {
+        rules.push(vec![
+            format!("sink macro `{}!`", name),
+            "`--taint-sink-macros`".to_owned(),
+        ]);
+    }
+    for pack in &options.sink_packs {
+        rules.push(vec![
+            format!("sink pack `{}`", pack.name()),
+            "`--taint-sink-packs`".to_owned(),
+        ]);
+    }
+    for pack in &options.source_packs {
+        rules.push(vec![
+            format!("source pack `{}`", pack.name()),
+            "`--taint-source-packs`".to_owned(),
+        ]);
+    }
+    for path in &info.trusted_unsafe {
+        rules.push(vec![
+            format!("trusted unsafe function `{}`", path),
+            FILE_NAME.to_owned(),
+        ]);
+    }
+    for (label, policy) in &config.hashing.labels {
+        let verb = match policy {
+            HashPolicy::Clear => "clears",
+            HashPolicy::Propagate => "propagates",
+        };
+        rules.push(vec![
+            format!("hashing {} `{}`", verb, label),
+            FILE_NAME.to_owned(),
+        ]);
+    }
+    for path in &info.hash_functions {
+        rules.push(vec![
+            format!("hash function `{}`", path),
+            FILE_NAME.to_owned(),
+        ]);
+    }
+    for label in &config.clones {
+        rules.push(vec![
+            format!("clones of `{}` are reported", label),
+            FILE_NAME.to_owned(),
+        ]);
+    }
+    if !options.skip_summary_packs {
+        let crates = tcx.crates(()).iter().map(|&krate| tcx.crate_name(krate));
+        for name in crates.filter(|name| PACKS.iter().any(|(pack, _)| *pack == name.as_str())) {
+            rules.push(vec![
+                format!("summary pack `{}`", name),
+                "bundled".to_owned(),
+            ]);
+        }
+    }
+    if let Some(path) = &options.import_summaries {
+        let count = Summaries::read(path).map_or(0, |summaries| summaries.summaries.len());
+        rules.push(vec![
+            format!("{} imported summaries", count),
+            format!("`--taint-import-summaries={}`", path.display()),
+        ]);
+    }
+    for (model, span) in &info.models {
+        rules.push(vec![
+            format!("propagation rule for `{}`", model.function),
+            inventory::attribute(tcx, *span),
+        ]);
+    }
+    for summary in &config.propagation.summaries {
+        rules.push(vec![
+            format!("propagation rule for `{}`", summary.function),
+            FILE_NAME.to_owned(),
+        ]);
+    }
+    doc.push_str(&table(&["Rule", "Origin"], rules));
+    doc
+}
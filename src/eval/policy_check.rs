@@ -0,0 +1,246 @@
+//! The check of the paths of the policy against the crate graph, printed with `--taint-emit=policy-check`
+//! or `taint policy check`.
+//!
+//! Every path of the config and of the command line, and every pattern of the owners, is looked up among the items
+//! the crate defines or names, without analyzing anything. A path which matches nothing gives no role to anything,
+//! and goes unnoticed until a finding is missing, so each is listed with what it matches, or with the items named
+//! like it when it matches nothing.
+
+use std::fmt::Write;
+
+use crate::{
+    compiler::{self, DefId, DefKind, TyCtxt, LOCAL_CRATE},
+    config::{self, Config, FILE_NAME},
+    eval::attributes::AttrInfo,
+    options::Options,
+};
+
+/// The most items listed for a pattern, or suggested for a path matching nothing.
+const LISTED: usize = 3;
+
+/// What a path of the policy names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Target {
+    Function,
+    Static,
+    Module,
+    /// Any item, like those the config gives aliases to.
+    Item,
+}
+
+impl Target {
+    fn name(self) -> &'static str {
+        match self {
+            Target::Function => "function",
+            Target::Static => "static",
+            Target::Module => "module",
+            Target::Item => "item",
+        }
+    }
+
+    fn admits(self, kind: Target) -> bool {
+        self == Target::Item || self == kind
+    }
+}
+
+/// An entry of the policy: where it comes from, like `` `trusted_unsafe` entry ``, the path or pattern it is,
+/// and what it names.
+struct Entry<'a> {
+    origin: String,
+    path: &'a str,
+    target: Target,
+    pattern: bool,
+}
+
+/// The functions, statics and modules of the crate, and the functions and statics its bodies name in other crates,
+/// by the path the analysis matches them with.
+fn items(tcx: TyCtxt<'_>) -> Vec<(String, Target)> {
+    let target = |id: DefId| match tcx.def_kind(id) {
+        DefKind::Fn | DefKind::AssocFn => Some(Target::Function),
+        DefKind::Static(_) => Some(Target::Static),
+        DefKind::Mod if !id.is_crate_root() => Some(Target::Module),
+        _ => None,
+    };
+    let mut ids = tcx
+        .hir_crate_items(())
+        .definitions()
+        .map(|def_id| def_id.to_def_id())
+        .collect::<Vec<_>>();
+    for &def_id in tcx.mir_keys(()) {
+        if let Some(body) = compiler::mir_body(tcx, def_id.to_def_id()) {
+            ids.extend(compiler::named_items(tcx, body));
+        }
+    }
+    let mut items = ids
+        .into_iter()
+        .filter_map(|id| Some((tcx.def_path_str(id), target(id)?)))
+        .collect::<Vec<_>>();
+    items.sort();
+    items.dedup();
+    items
+}
+
+/// The last segment of `path`, like `escape` for `html::escape`.
+fn name(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+/// `paths` as a list for a sentence, like `` `a`, `b` and 2 more ``.
+fn listed(paths: &[&str], conjunction: &str) -> String {
+    let shown = paths
+        .iter()
+        .take(LISTED)
+        .map(|path| format!("`{}`", path))
+        .collect::<Vec<_>>();
+    match (paths.len() - shown.len(), &shown[..]) {
+        (0, [only]) => only.clone(),
+        (0, [init @ .., last]) => format!("{} {} {}", init.join(", "), conjunction, last),
+        (more, _) => format!("{} and {} more", shown.join(", "), more),
+    }
+}
+
+/// Lists what each path and pattern of the policy matches, starting with how many match nothing.
+pub(crate) fn check(
+    tcx: TyCtxt<'_>,
+    info: &AttrInfo,
+    options: &Options,
+    config: &Config,
+) -> String {
+    let krate = tcx.crate_name(LOCAL_CRATE);
+    let local = format!("{}::", krate);
+    let entry = |section: &str, path, target| Entry {
+        origin: format!("`{}` entry", section),
+        path,
+        target,
+        pattern: false,
+    };
+
+    let mut entries = vec![];
+    for (path, _) in &config.statics {
+        entries.push(entry("statics", path, Target::Static));
+    }
+    for path in &config.trusted_unsafe {
+        entries.push(entry("trusted_unsafe", path, Target::Function));
+    }
+    for (path, _) in &config.aliases {
+        entries.push(entry("aliases", path, Target::Item));
+    }
+    for sanitizer in &config.sanitizer_modes {
+        entries.push(entry(
+            "sanitizer_modes",
+            &sanitizer.function,
+            Target::Function,
+        ));
+    }
+    for path in &config.hashing.functions {
+        entries.push(entry("hashing", path, Target::Function));
+    }
+    for summary in &config.propagation.summaries {
+        entries.push(entry("propagation", &summary.function, Target::Function));
+    }
+    for role in &config.modules {
+        entries.push(entry("modules", &role.module, Target::Module));
+    }
+    for (pattern, _) in &config.owners {
+        entries.push(Entry {
+            origin: "`owners` pattern".to_owned(),
+            path: pattern,
+            target: Target::Function,
+            pattern: true,
+        });
+    }
+    let added = [
+        ("source", &options.add_sources),
+        ("sink", &options.add_sinks),
+        ("sanitizer", &options.add_sanitizers),
+    ];
+    for (role, paths) in added {
+        for path in paths {
+            entries.push(Entry {
+                origin: format!("`--taint-add-{}` path", role),
+                path,
+                target: Target::Function,
+                pattern: false,
+            });
+        }
+    }
+
+    let items = items(tcx);
+    let mut lines = vec![];
+    let mut unmatched = 0;
+    for entry in &entries {
+        let candidates = items.iter().filter(|&&(_, kind)| entry.target.admits(kind));
+        let matched = if entry.pattern {
+            candidates
+                .filter(|(function, _)| {
+                    config::owner_match(entry.path, krate.as_str(), function).is_some()
+                })
+                .collect::<Vec<_>>()
+        } else {
+            let mut path = info.canonical_path(entry.path);
+            // Modules are looked up in the crate, which they can name.
+            if entry.target == Target::Module {
+                path = path
+                    .strip_prefix("crate::")
+                    .or_else(|| path.strip_prefix(&local))
+                    .unwrap_or(path);
+            }
+            candidates.filter(|(item, _)| item == path).collect()
+        };
+
+        let mut line = format!("{} `{}` ", entry.origin, entry.path);
+        if matched.is_empty() {
+            unmatched += 1;
+            let _ = write!(
+                line,
+                "matches no {} the crate defines or names",
+                entry.target.name()
+            );
+            let similar = items
+                .iter()
+                .filter(|&&(ref path, kind)| {
+                    entry.target.admits(kind) && !entry.pattern && name(path) == name(entry.path)
+                })
+                .map(|(path, _)| path.as_str())
+                .collect::<Vec<_>>();
+            if !similar.is_empty() {
+                let _ = write!(line, ", did you mean {}?", listed(&similar, "or"));
+            }
+        } else if entry.pattern {
+            let functions = matched
+                .iter()
+                .map(|(path, _)| path.as_str())
+                .collect::<Vec<_>>();
+            let _ = write!(
+                line,
+                "matches {} function{}: {}",
+                functions.len(),
+                if functions.len() == 1 { "" } else { "s" },
+                listed(&functions, "and")
+            );
+        } else {
+            let matched = matched
+                .iter()
+                .map(|(path, kind)| format!("the {} `{}`", kind.name(), path))
+                .collect::<Vec<_>>();
+            let _ = write!(line, "matches {}", matched.join(" and "));
+        }
+        lines.push(line);
+    }
+
+    let source = options
+        .config
+        .as_deref()
+        .map_or_else(|| FILE_NAME.to_owned(), |path| path.display().to_string());
+    let mut check = format!(
+        "{} of the {} paths and patterns of `{}` and the command line match nothing in crate `{}`\n",
+        unmatched,
+        entries.len(),
+        source,
+        krate
+    );
+    for line in lines {
+        let _ = writeln!(check, "{}", line);
+    }
+    check
+}
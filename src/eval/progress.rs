@@ -0,0 +1,80 @@
+//! Progress events for `--taint-progress=json`, so that wrappers and editors can show how far
+//! along a long analysis is.
+//!
+//! Every event is a JSON object on its own line of stderr, since stdout is taken by the reports:
+//!
+//! ```text
+//! {"entries":2,"event":"started"}
+//! {"entry":"demo::run","event":"entry-started","index":0}
+//! {"entry":"demo::run","event":"entry-finished","findings":1,"index":0,"summaries":14}
+//! ...
+//! {"event":"finished","findings":1,"summaries":20}
+//! ```
+//!
+//! A run which is [cancelled](crate::cancellation) ends with a `cancelled` event instead of `finished`.
+
+use serde_json::{json, Value};
+
+use crate::{
+    compiler::{DefId, TyCtxt},
+    options::Progress,
+    taint_analysis::Shared,
+};
+
+pub(crate) struct Events<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    progress: Progress,
+}
+
+impl<'tcx> Events<'tcx> {
+    pub(crate) fn new(tcx: TyCtxt<'tcx>, progress: Progress) -> Self {
+        Events { tcx, progress }
+    }
+
+    /// The analysis of `entries` entry points starts.
+    pub(crate) fn started(&self, entries: usize) {
+        self.emit(json!({ "event": "started", "entries": entries }));
+    }
+
+    pub(crate) fn entry_started(&self, entry: DefId, index: usize) {
+        self.emit(json!({
+            "event": "entry-started",
+            "entry": self.tcx.def_path_str(entry),
+            "index": index,
+        }));
+    }
+
+    /// Also tells how many summaries were computed and how many findings were reported so far.
+    pub(crate) fn entry_finished(&self, entry: DefId, index: usize, shared: &Shared<'_>) {
+        self.emit(json!({
+            "event": "entry-finished",
+            "entry": self.tcx.def_path_str(entry),
+            "index": index,
+            "summaries": shared.contexts.borrow().len(),
+            "findings": shared.findings.borrow().count(self.tcx),
+        }));
+    }
+
+    pub(crate) fn finished(&self, shared: &Shared<'_>) {
+        self.emit(json!({
+            "event": "finished",
+            "summaries": shared.contexts.borrow().len(),
+            "findings": shared.findings.borrow().count(self.tcx),
+        }));
+    }
+
+    /// The analysis was cancelled before it was done, so its findings are not reported.
+    pub(crate) fn cancelled(&self, shared: &Shared<'_>) {
+        self.emit(json!({
+            "event": "cancelled",
+            "summaries": shared.contexts.borrow().len(),
+        }));
+    }
+
+    fn emit(&self, event: Value) {
+        match self.progress {
+            Progress::Off => {}
+            Progress::Json => eprintln!("{}", event),
+        }
+    }
+}
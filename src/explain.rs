@@ -0,0 +1,13 @@
+//! The explanations of the error codes, printed with `taint --explain T0001`.
+
+use crate::compiler::ErrorCode;
+
+/// The explanation of `code`, like `T0001`, in Markdown, or `None` if the analysis has no such code.
+pub fn explanation(code: &str) -> Option<&'static str> {
+    ErrorCode::from_code(code).map(ErrorCode::explanation)
+}
+
+/// The codes the analysis reports under, in order, for pointing at the known ones.
+pub fn codes() -> Vec<&'static str> {
+    ErrorCode::ALL.iter().map(|code| code.as_str()).collect()
+}
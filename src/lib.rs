@@ -11,14 +11,23 @@ extern crate rustc_hir;
 extern crate rustc_index;
 extern crate rustc_interface;
 extern crate rustc_macros;
+extern crate rustc_metadata;
 extern crate rustc_middle;
 extern crate rustc_mir_dataflow;
 extern crate rustc_session;
+extern crate rustc_smir;
 extern crate rustc_span;
 extern crate rustc_target;
 
 mod analysis;
+mod compiler;
 
+pub mod capabilities;
 pub mod eval;
+pub mod explain;
 
 pub use analysis::*;
+pub use taint_core::{
+    annotations, cancellation, changes, config, entries, error, fuzz, history, hook, metrics,
+    options, query, report, reporters, status, summaries, warm_start,
+};
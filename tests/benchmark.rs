@@ -0,0 +1,26 @@
+//! Tests for `taint benchmark`, run on the corpus in `tests/benchmark`.
+//!
+//! A change to how taint propagates which moves these numbers should update them here,
+//! so that the review shows what it gained and what it cost.
+
+use std::process::Command;
+
+#[test]
+fn the_corpus_is_scored_by_its_known_flows() {
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg("benchmark")
+        .output()
+        .expect("taint benchmark runs");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout,
+        "program                                            tp   fp   fn\n\
+         tests/benchmark/cwe22_path_traversal.rs            1    1    0\n\
+         tests/benchmark/cwe789_uncontrolled_allocation.rs  1    0    1\n\
+         tests/benchmark/cwe78_os_command_injection.rs      2    0    0\n\
+         tests/benchmark/cwe89_sql_injection.rs             2    0    0\n\
+         benchmark: precision 85.7%, recall 85.7% (6 true positives, 1 false positive, 1 false negative)\n"
+    );
+}
@@ -0,0 +1,40 @@
+// Paths read from input, which could name files outside of the directory served.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    bad();
+    good_normalized();
+    good_array();
+}
+
+fn bad() {
+    let name = read_request();
+    open(&format!("/srv/files/{}", name));
+}
+
+fn good_normalized() {
+    let name = file_name(read_request());
+    open(&format!("/srv/files/{}", name));
+}
+
+// Elements of an array share their taint, so the clean path is reported.
+fn good_array() {
+    let names = [String::from("index.html"), read_request()];
+    open(&format!("/srv/files/{}", names[0]));
+}
+
+#[taint::source]
+fn read_request() -> String {
+    String::from("index.html")
+}
+
+/// Keeps the last component of `name`, dropping any `..` or directory before it.
+#[taint::sanitizer]
+fn file_name(name: String) -> String {
+    name.rsplit('/').next().unwrap_or_default().to_owned()
+}
+
+#[taint::sink]
+fn open(_path: &str) {}
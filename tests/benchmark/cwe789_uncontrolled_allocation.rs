@@ -0,0 +1,48 @@
+// Sizes of allocations read from input, which could exhaust memory.
+// compile-flags: --taint-sink-packs=allocation
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    bad();
+    bad_widened();
+    good_clamped();
+    good_constant();
+}
+
+fn bad() {
+    let size = read_size();
+    let _ = Vec::<u8>::with_capacity(size);
+}
+
+// Numeric casts do not carry taint, so this flow is missed.
+fn bad_widened() {
+    let size = read_header_length();
+    let _ = Vec::<u8>::with_capacity(size as usize);
+}
+
+fn good_clamped() {
+    let size = clamp(read_size());
+    let _ = Vec::<u8>::with_capacity(size);
+}
+
+fn good_constant() {
+    let _ = read_size();
+    let _ = Vec::<u8>::with_capacity(4096);
+}
+
+#[taint::source]
+fn read_size() -> usize {
+    4096
+}
+
+#[taint::source]
+fn read_header_length() -> u16 {
+    512
+}
+
+#[taint::sanitizer]
+fn clamp(size: usize) -> usize {
+    size.min(1 << 20)
+}
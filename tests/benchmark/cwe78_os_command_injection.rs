@@ -0,0 +1,47 @@
+// Arguments of child processes built from input, which could inject options into the program run.
+// compile-flags: --taint-sink-packs=process
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::process::Command;
+
+fn main() {
+    bad();
+    bad_env();
+    good_constant();
+    good_allow_listed();
+}
+
+fn bad() {
+    let directory = read_request();
+    let _ = Command::new("ls").arg(directory);
+}
+
+fn bad_env() {
+    let locale = read_request();
+    let _ = Command::new("date").env("LC_ALL", locale);
+}
+
+fn good_constant() {
+    let _ = read_request();
+    let _ = Command::new("ls").arg("-l");
+}
+
+fn good_allow_listed() {
+    let directory = allow_listed(read_request());
+    let _ = Command::new("ls").arg(directory);
+}
+
+#[taint::source]
+fn read_request() -> String {
+    String::from("/tmp")
+}
+
+#[taint::sanitizer]
+fn allow_listed(directory: String) -> String {
+    match directory.as_str() {
+        "/tmp" | "/var/tmp" => directory,
+        _ => String::from("/tmp"),
+    }
+}
@@ -0,0 +1,56 @@
+// Queries built from input, which could change what the query does.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    bad();
+    bad_through_helper();
+    good_parameter();
+    good_fields();
+}
+
+struct Statement {
+    text: String,
+    parameter: String,
+}
+
+fn bad() {
+    let name = read_form();
+    execute(&format!("SELECT * FROM users WHERE name = '{}'", name));
+}
+
+fn bad_through_helper() {
+    let name = read_form();
+    execute(&where_name(&name));
+}
+
+fn where_name(name: &str) -> String {
+    format!("SELECT * FROM users WHERE name = '{}'", name)
+}
+
+fn good_parameter() {
+    let name = read_form();
+    execute_with("SELECT * FROM users WHERE name = ?", &name);
+}
+
+// Each field of a struct keeps its own taint, so the clean query text is not reported.
+fn good_fields() {
+    let statement = Statement {
+        text: String::from("SELECT * FROM users WHERE name = ?"),
+        parameter: read_form(),
+    };
+    execute(&statement.text);
+    execute_with(&statement.text, &statement.parameter);
+}
+
+#[taint::source]
+fn read_form() -> String {
+    String::from("alice")
+}
+
+#[taint::sink]
+fn execute(_query: &str) {}
+
+/// Binds `parameter` to the placeholder of `query`, which the database never parses.
+fn execute_with(_query: &str, _parameter: &str) {}
@@ -0,0 +1,54 @@
+//! Tests for `taint --capabilities`.
+
+use std::process::Command;
+
+use serde_json::Value;
+
+#[test]
+fn capabilities_list_rules_packs_and_formats() {
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg("--capabilities")
+        .output()
+        .expect("taint runs");
+    assert!(output.status.success());
+    let capabilities: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(capabilities["version"], 1);
+    assert_eq!(capabilities["analysis"], env!("CARGO_PKG_VERSION"));
+    let rules = capabilities["rules"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|rule| rule["id"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rules,
+        vec![
+            "T0001", "T0002", "T0003", "T0004", "T0005", "T0006", "T0007", "T0008", "T0009",
+            "T0010", "T0011", "T0012", "T0013", "T0014"
+        ]
+    );
+    assert_eq!(capabilities["summary_packs"], serde_json::json!(["std"]));
+    assert_eq!(
+        capabilities["sink_packs"],
+        serde_json::json!([
+            "allocation",
+            "pointer",
+            "process",
+            "resource",
+            "unsafe",
+            "index",
+            "sql"
+        ])
+    );
+    assert_eq!(
+        capabilities["source_packs"],
+        serde_json::json!(["wasm-bindgen", "pyo3"])
+    );
+    assert_eq!(capabilities["formats"]["findings"], 1);
+    assert_eq!(capabilities["formats"]["findings_db"], 1);
+    assert_eq!(
+        capabilities["emit"],
+        serde_json::json!(["policy-doc", "policy-check"])
+    );
+}
@@ -0,0 +1,3 @@
+pub fn relay(value: i32) -> i32 {
+    value
+}
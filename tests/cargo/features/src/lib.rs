@@ -0,0 +1,19 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+pub fn always() {
+    output(input());
+}
+
+#[cfg(feature = "leaky")]
+pub fn leaky() {
+    output(input() + 1);
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,100 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+#![allow(dead_code)]
+
+use derive::Serialize;
+use taint_runtime::Sensitive;
+
+#[derive(Serialize)]
+pub struct Login {
+    user: String,
+    #[serde(skip)]
+    password: Sensitive<String>,
+}
+
+#[derive(Serialize)]
+pub struct Session {
+    user: String,
+    #[redact]
+    token: Sensitive<String>,
+}
+
+#[derive(Serialize)]
+pub struct Audit {
+    action: String,
+    #[serde(skip_serializing)]
+    #[taint::source]
+    body: String,
+}
+
+#[derive(Serialize)]
+pub struct Leaky {
+    user: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    token: Sensitive<String>,
+}
+
+pub fn log_login() {
+    let login = Login {
+        user: String::new(),
+        password: Sensitive::new(String::new()),
+    };
+    log(serde_json::to_string(&login).unwrap());
+}
+
+pub fn log_session() {
+    let session = Session {
+        user: String::new(),
+        token: Sensitive::new(String::new()),
+    };
+    log(serde_json::to_string(&session).unwrap());
+}
+
+pub fn log_audit() {
+    let audit = Audit {
+        action: String::new(),
+        body: String::new(),
+    };
+    log(serde_json::to_string(&audit).unwrap());
+}
+
+pub fn log_leaky() {
+    let leaky = Leaky {
+        user: String::new(),
+        token: Sensitive::new(String::new()),
+    };
+    log(serde_json::to_string(&leaky).unwrap());
+}
+
+#[taint::sink]
+fn log(_: String) {}
+
+/// A stand-in for the parts of `serde_json` that the analysis models.
+mod serde_json {
+    pub trait Serialize {}
+
+    impl Serialize for super::Login {}
+    impl Serialize for super::Session {}
+    impl Serialize for super::Audit {}
+    impl Serialize for super::Leaky {}
+
+    #[derive(Debug)]
+    pub struct Error;
+
+    pub fn to_string<T: ?Sized + Serialize>(_: &T) -> Result<String, Error> {
+        Ok(String::new())
+    }
+}
+
+/// A stand-in for the wrapper of secrets of `taint-runtime`.
+mod taint_runtime {
+    pub struct Sensitive<T> {
+        secret: T,
+    }
+
+    impl<T> Sensitive<T> {
+        pub fn new(secret: T) -> Self {
+            Sensitive { secret }
+        }
+    }
+}
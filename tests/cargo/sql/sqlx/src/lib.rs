@@ -0,0 +1,24 @@
+//! Stands for the query builders of `sqlx`, which the SQL sink pack knows by the name of their crate.
+
+pub struct Query {
+    sql: String,
+    arguments: Vec<String>,
+}
+
+pub fn query(sql: &str) -> Query {
+    Query {
+        sql: sql.to_owned(),
+        arguments: vec![],
+    }
+}
+
+impl Query {
+    pub fn bind(mut self, value: &str) -> Query {
+        self.arguments.push(value.to_owned());
+        self
+    }
+
+    pub fn execute(self) -> usize {
+        self.sql.len() + self.arguments.len()
+    }
+}
@@ -0,0 +1,19 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+pub fn find_user() -> usize {
+    let name = input();
+    sqlx::query(&format!("SELECT * FROM users WHERE name = '{}'", name)).execute()
+}
+
+pub fn find_user_bound() -> usize {
+    let name = input();
+    sqlx::query("SELECT * FROM users WHERE name = ?")
+        .bind(&name)
+        .execute()
+}
+
+#[taint::source]
+fn input() -> String {
+    "input".to_owned()
+}
@@ -0,0 +1,26 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+pub struct Request {
+    #[taint::source(label = "body")]
+    pub body: String,
+    pub id: u32,
+}
+
+#[taint::source]
+pub fn receive() -> Request {
+    Request {
+        body: String::new(),
+        id: 0,
+    }
+}
+
+#[taint::sink]
+pub fn execute(_: &str) {}
+
+#[taint::sink]
+pub fn count(_: u32) {}
+
+pub fn trim(input: &str) -> &str {
+    input.trim()
+}
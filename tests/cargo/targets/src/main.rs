@@ -0,0 +1,7 @@
+use targets::{count, execute, receive, trim};
+
+fn main() {
+    let request = receive();
+    execute(trim(&request.body));
+    count(request.id);
+}
@@ -0,0 +1,29 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+pub fn always() {
+    output(input());
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub fn fixture() {
+        output(input() + 1);
+    }
+
+    #[test]
+    fn flows() {
+        fixture();
+        output(input() + 2);
+    }
+}
@@ -0,0 +1,202 @@
+//! Tests for `cargo taint`, run on the cargo projects in `tests/cargo`.
+
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Output},
+};
+
+fn cargo_taint(project: &str, args: &[&str]) -> Output {
+    command(project, args).output().expect("cargo-taint runs")
+}
+
+/// `cargo taint` run on `project`, to which the options of the analysis are given in the environment.
+fn command(project: &str, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_cargo-taint"));
+    command
+        .arg("taint")
+        .args(args)
+        .current_dir(Path::new("tests/cargo").join(project))
+        .env("CARGO", env!("CARGO"))
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join(project),
+        )
+        .env("TAINT", env!("CARGO_BIN_EXE_taint"));
+    command
+}
+
+#[test]
+fn feature_matrix_labels_findings() {
+    let output = cargo_taint("features", &["--feature-matrix=;leaky"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(stdout.matches("error[T0001]").count(), 2, "{}", stdout);
+    assert!(stdout.contains(
+        "5 |     output(input());\n  |     ^^^^^^^^^^^^^^^\n\n\
+         note: found with default features, features `leaky`\n"
+    ));
+    assert!(stdout.contains(
+        "10 |     output(input() + 1);\n   |     ^^^^^^^^^^^^^^^^^^^\n\n\
+         note: found with features `leaky`\n"
+    ));
+}
+
+#[test]
+fn default_configuration_skips_disabled_features() {
+    let output = cargo_taint("features", &[]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.matches("error[T0001]").count(), 1, "{}", stdout);
+    assert!(stdout.contains("note: found with default features\n"));
+}
+
+#[test]
+fn tests_are_left_out_by_default() {
+    for args in [&[][..], &["--tests"][..]] {
+        let output = cargo_taint("testing", args);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+
+        assert_eq!(stdout.matches("error[T0001]").count(), 1, "{}", stdout);
+        assert!(stdout.contains("5 |     output(input());\n"), "{}", stdout);
+    }
+}
+
+#[test]
+fn included_tests_are_analyzed() {
+    let output = cargo_taint("testing", &["--taint-include-tests"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(stdout.matches("error[T0001]").count(), 3, "{}", stdout);
+    assert!(stdout.contains("output(input() + 1);\n"), "{}", stdout);
+    assert!(stdout.contains("output(input() + 2);\n"), "{}", stdout);
+}
+
+#[test]
+fn dependencies_are_descended_into() {
+    let output = cargo_taint("dependency", &[]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(stdout.matches("error[T0001]").count(), 1, "{}", stdout);
+    assert!(
+        stdout.contains("output(relay::relay(input()));\n"),
+        "{}",
+        stdout
+    );
+    assert!(!stderr.contains("was not encoded"), "{}", stderr);
+}
+
+#[test]
+fn dependencies_without_mir_are_reported() {
+    let output = cargo_taint("dependency", &["--taint-no-dependency-mir"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(
+        stderr.contains(
+            "warning: the MIR of `relay` was not encoded, so calls to 1 of its functions, \
+             like `relay::relay`, follow --taint-unknown-calls\n"
+        ),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn summaries_of_dependencies_are_reused_across_builds() {
+    let tmp = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let cache = tmp.join("dependency-cache");
+    // Each build has a target directory of its own, so that the second is not an incremental one.
+    let build = |target: &str| {
+        let target = tmp.join(target);
+        let _ = fs::remove_dir_all(&target);
+        command("dependency", &[])
+            .env("TAINT_DEPENDENCY_CACHE", &cache)
+            .env("CARGO_TARGET_DIR", target)
+            .output()
+            .expect("cargo-taint runs")
+    };
+    let _ = fs::remove_dir_all(&cache);
+
+    let output = build("cached-first");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.matches("error[T0001]").count(), 1, "{}", stdout);
+    let files = fs::read_dir(&cache)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(files.len(), 1, "{:?}", files);
+    let name = files[0].file_name().unwrap().to_str().unwrap();
+    assert!(name.starts_with("relay-"), "{}", name);
+    let cached = fs::read_to_string(&files[0]).unwrap();
+    assert!(
+        cached.contains("\"function\":\"relay::relay\""),
+        "{}",
+        cached
+    );
+
+    // The summary of the same version of `relay` stands in for its analysis, so making it drop
+    // the labels of its argument drops the finding.
+    let clean = cached.replace("\"returns\":{\"from\":[0]", "\"returns\":{\"from\":[]");
+    fs::write(&files[0], clean).unwrap();
+    let output = build("cached-second");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}", stdout);
+    assert!(!stdout.contains("error[T0001]"), "{}", stdout);
+}
+
+#[test]
+fn binaries_load_the_interface_of_their_library() {
+    let output = cargo_taint("targets", &[]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(stdout.matches("error[T0001]").count(), 2, "{}", stdout);
+    assert!(
+        stdout.contains("function `targets::execute` received tainted input\n"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("count(request.id);\n"), "{}", stdout);
+    assert!(stdout.contains("note: also reported for data labeled `body`\n"));
+}
+
+#[test]
+fn sql_sink_pack_reports_queries_built_from_tainted_data() {
+    let output = command("sql", &[])
+        .env("TAINT_SINK_PACKS", "sql")
+        .output()
+        .expect("cargo-taint runs");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(stdout.matches("error[T0001]").count(), 1, "{}", stdout);
+    assert!(
+        stdout.contains("function `sqlx::query` received an SQL query built from tainted input\n"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("sqlx::query(&format!(\"SELECT * FROM users WHERE name = '{}'\", name))"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn skipped_and_redacted_fields_are_not_serialized() {
+    let output = cargo_taint("redaction", &[]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(stdout.matches("error[T0001]").count(), 1, "{}", stdout);
+    assert!(
+        stdout.contains("log(serde_json::to_string(&leaky).unwrap());\n"),
+        "{}",
+        stdout
+    );
+}
@@ -0,0 +1,42 @@
+//! Tests for `taint check`, run on the directories in `tests/check` and `tests/cargo`.
+
+use std::{
+    path::Path,
+    process::{Command, Output},
+};
+
+fn check(dir: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg("check")
+        .arg(dir)
+        .env("CARGO", env!("CARGO"))
+        .env("CARGO_TAINT", env!("CARGO_BIN_EXE_cargo-taint"))
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join("check"),
+        )
+        .output()
+        .expect("taint check runs")
+}
+
+#[test]
+fn standalone_programs_are_aggregated() {
+    let output = check("tests/check/programs");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout,
+        "checked 2 programs in `tests/check/programs`\n\
+         \x20   tests/check/programs/nested/leaky.rs: 2 findings\n"
+    );
+}
+
+#[test]
+fn cargo_projects_are_checked_with_cargo_taint() {
+    let output = check("tests/cargo/features");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.starts_with("checking cargo project `tests/cargo/features`\n"));
+    assert_eq!(stdout.matches("error[T0001]").count(), 1, "{}", stdout);
+}
@@ -0,0 +1,20 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    output(sanitize(input()));
+    output(4);
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sanitizer]
+fn sanitize(i: i32) -> i32 {
+    i
+}
+
+#[taint::sink]
+fn output(_: i32) {}
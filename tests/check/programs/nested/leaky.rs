@@ -0,0 +1,15 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    output(input());
+    output(input() + 1);
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
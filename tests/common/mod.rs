@@ -0,0 +1,21 @@
+//! Helpers shared by the integration tests.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+/// A directory of the test `name` of the `suite` alone, emptied, for the files its runs write and the crates
+/// they compile. It is named after the process too, so that concurrent runs of the tests do not share it.
+pub fn scratch_dir(suite: &str, name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!(
+        "{}-{}-{}",
+        suite,
+        name,
+        process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -11,7 +11,7 @@ fn test_runner() {
     env::set_var("TAINT_ENV_VAR_TEST", "0");
     env::set_var("TAINT_TEMP", env::temp_dir());
     env::set_var("RUST_BACKTRACE", "1");
-    
+
     let target = get_target();
 
     run_compile_pass("tests/passes", &target);
@@ -0,0 +1,319 @@
+//! Tests for choosing the config and options from the environment and the command line.
+
+use std::process::Command;
+
+mod common;
+
+/// The errors reported on `tests/config/program.rs` with `env` and `args`.
+fn errors(env: &[(&str, &str)], args: &[&str]) -> Vec<String> {
+    errors_in("program.rs", env, args)
+}
+
+/// The errors reported on `program`, in `tests/config`, with `env` and `args`.
+fn errors_in(program: &str, env: &[(&str, &str)], args: &[&str]) -> Vec<String> {
+    diagnostics_in(program, env, args, "error")
+}
+
+/// The diagnostics of `level`, like `warning`, reported on `program` with `env` and `args`.
+fn diagnostics_in(program: &str, env: &[(&str, &str)], args: &[&str], level: &str) -> Vec<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg(program)
+        .args(args)
+        .envs(env.iter().copied())
+        .current_dir("tests/config")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    stderr
+        .lines()
+        .filter(|line| line.starts_with(level))
+        .map(str::to_owned)
+        .collect()
+}
+
+#[test]
+fn taint_json_is_read_by_default() {
+    assert_eq!(
+        errors(&[], &[]),
+        vec![
+            "error[T0001]: function `log` received tainted input labeled `header`",
+            "error: aborting due to previous error",
+        ]
+    );
+}
+
+#[test]
+fn the_environment_replaces_taint_json() {
+    assert_eq!(
+        errors(
+            &[("TAINT_CONFIG", "ci.json"), ("TAINT_SINK_PACKS", "allocation")],
+            &[]
+        ),
+        vec![
+            "error[T0001]: function `std::vec::Vec::<u8>::with_capacity` allocates memory of tainted size",
+            "error: aborting due to previous error",
+        ]
+    );
+}
+
+#[test]
+fn flags_take_precedence_over_the_environment() {
+    assert_eq!(
+        errors(
+            &[("TAINT_CONFIG", "ci.json"), ("TAINT_SINK_PACKS", "allocation")],
+            &["--taint-config=taint.json", "--taint-sink-packs=pointer"]
+        ),
+        vec![
+            "error[T0001]: function `log` received tainted input labeled `header`",
+            "error[T0001]: function `std::vec::Vec::<u8>::with_capacity` allocates memory of tainted size",
+            "error: aborting due to 2 previous errors",
+        ]
+    );
+}
+
+#[test]
+fn a_missing_config_is_an_error() {
+    assert_eq!(
+        errors(&[], &["--taint-config=missing.json"]),
+        vec![
+            "error: could not access `missing.json`: No such file or directory (os error 2)",
+            "error: aborting due to previous error",
+        ]
+    );
+}
+
+#[test]
+fn a_config_with_more_labels_than_can_be_told_apart_is_an_error() {
+    assert_eq!(
+        errors(&[], &["--taint-config=many_labels.json"]),
+        vec![
+            "error: invalid config: 64 labels are declared, but at most 63 can be told apart besides `taint`",
+            "error: aborting due to previous error",
+        ]
+    );
+}
+
+#[test]
+fn statics_marked_in_the_config_are_sources() {
+    assert_eq!(
+        errors_in("statics.rs", &[], &["--taint-config=statics.json"]),
+        vec![
+            "error[T0001]: function `log` received tainted input",
+            "error[T0001]: function `log` received tainted input labeled `header`",
+            "error: aborting due to 2 previous errors",
+        ]
+    );
+}
+
+#[test]
+fn modules_given_a_role_in_the_config_give_it_to_their_functions() {
+    assert_eq!(
+        errors_in("modules.rs", &[], &["--taint-config=modules.json"]),
+        vec![
+            "error[T0001]: function `log` received tainted input labeled `grpc`",
+            "error: aborting due to previous error",
+        ]
+    );
+}
+
+#[test]
+fn unsafe_functions_trusted_in_the_config_are_not_checked() {
+    assert_eq!(
+        diagnostics_in(
+            "trusted_unsafe.rs",
+            &[],
+            &[
+                "--taint-config=trusted_unsafe.json",
+                "--taint-sink-packs=unsafe"
+            ],
+            "warning"
+        ),
+        vec![
+            "warning[T0006]: function `checked` is unsafe and received tainted input",
+            "warning: 1 warning emitted",
+        ]
+    );
+}
+
+#[test]
+fn paths_are_matched_through_the_aliases_in_the_config() {
+    let args = ["--taint-add-source=fetch", "--taint-add-sink=run"];
+    assert_eq!(errors_in("aliases.rs", &[], &args), Vec::<String>::new());
+    assert_eq!(
+        errors_in(
+            "aliases.rs",
+            &[],
+            &[&args[..], &["--taint-config=aliases.json"]].concat()
+        ),
+        vec![
+            "error[T0001]: function `engine::execute` received tainted input",
+            "error[T0001]: function `engine::execute` received tainted input",
+            "error: aborting due to 2 previous errors",
+        ]
+    );
+}
+
+#[test]
+fn only_the_kinds_of_data_tracked_in_the_config_carry_taint() {
+    assert_eq!(
+        errors_in(
+            "tracked_types.rs",
+            &[],
+            &["--taint-config=tracked_types.json"]
+        ),
+        vec![
+            "error[T0001]: function `run` received tainted input",
+            "error[T0001]: function `execute` received tainted input",
+            "error: aborting due to 2 previous errors",
+        ]
+    );
+}
+
+#[test]
+fn sanitizer_modes_depend_on_constant_arguments() {
+    assert_eq!(
+        errors_in(
+            "sanitizer_modes.rs",
+            &[],
+            &["--taint-config=sanitizer_modes.json"]
+        ),
+        vec![
+            "error[T0001]: function `render` received tainted input labeled `xss`",
+            "error[T0001]: function `respond` received tainted input labeled `xss`",
+            "error[T0001]: function `query` received tainted input labeled `sqli`",
+            "error: aborting due to 3 previous errors",
+        ]
+    );
+}
+
+#[test]
+fn hashing_clears_the_labels_the_config_says_it_does() {
+    assert_eq!(
+        errors_in("hashing.rs", &[], &["--taint-config=hashing.json"]),
+        vec![
+            "error[T0001]: function `query` received tainted input labeled `sqli`",
+            "error[T0001]: function `log` received tainted input labeled `secret`",
+            "error: aborting due to 2 previous errors",
+        ]
+    );
+}
+
+#[test]
+fn clones_of_the_labels_the_config_tracks_are_reported() {
+    assert_eq!(
+        diagnostics_in("clones.rs", &[], &["--taint-config=clones.json"], "warning"),
+        vec![
+            "warning[T0013]: a value labeled `api-key` is cloned here",
+            "warning[T0013]: a value labeled `api-key` is cloned here",
+            "warning: 2 warnings emitted",
+        ]
+    );
+}
+
+#[test]
+fn listed_entry_points_are_analyzed_with_the_options_of_their_profile() {
+    let scratch = common::scratch_dir(
+        "config",
+        "listed_entry_points_are_analyzed_with_the_options_of_their_profile",
+    );
+    let args = [
+        "--taint-config=profiles.json",
+        "--taint-entries=entries.txt",
+    ];
+    assert_eq!(
+        errors_in("profiles.rs", &[], &args),
+        vec![
+            "error[T0001]: function `execute` received tainted input",
+            "error[T0001]: function `std::vec::Vec::<u8>::with_capacity` allocates memory of tainted size",
+            "error[T0001]: function `execute` received tainted input",
+            "error: aborting due to 3 previous errors",
+        ]
+    );
+
+    // Findings are tagged with the profile they were found with.
+    let findings = scratch.join("profiles-findings.json");
+    let findings_arg = format!("--taint-findings={}", findings.display());
+    errors_in("profiles.rs", &[], &[&args[..], &[&findings_arg]].concat());
+    let report = taint::report::Report::read(&findings).unwrap();
+    let profiles = report
+        .findings
+        .iter()
+        .map(|finding| (finding.start.line, finding.profiles.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        profiles,
+        vec![
+            (7, vec!["web".to_owned()]),
+            (8, vec!["web".to_owned()]),
+            (17, vec![]),
+        ]
+    );
+
+    assert_eq!(
+        errors_in(
+            "profiles.rs",
+            &[],
+            &["--taint-config=ci.json", "--taint-entries=entries.txt"]
+        ),
+        vec![
+            "error: invalid option `--taint-entries=entries.txt`: profile `web` is not in the config",
+            "error: aborting due to previous error",
+        ]
+    );
+}
+
+#[test]
+fn findings_are_given_the_owners_of_their_functions() {
+    let scratch = common::scratch_dir("config", "findings_are_given_the_owners_of_their_functions");
+    let findings = scratch.join("owners-findings.json");
+    let findings_arg = format!("--taint-findings={}", findings.display());
+    errors_in(
+        "owners.rs",
+        &[],
+        &["--taint-config=owners.json", &findings_arg],
+    );
+
+    let report = taint::report::Report::read(&findings).unwrap();
+    let owners = report
+        .findings
+        .iter()
+        .map(|finding| (finding.function.as_str(), finding.owner.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        owners,
+        vec![
+            ("main", None),
+            ("payments::charge", Some("team-payments")),
+            ("payments::refunds::issue", Some("team-refunds")),
+        ]
+    );
+}
+
+#[test]
+fn policy_check_lists_what_each_path_of_the_config_matches() {
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .args(["policy", "check", "policy_check.rs"])
+        .args(["--taint-config=policy_check.json", "--taint-add-sink=log"])
+        .current_dir("tests/config")
+        .output()
+        .expect("taint runs");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        vec![
+            "6 of the 11 paths and patterns of `policy_check.json` and the command line match nothing in crate `policy_check`",
+            "`statics` entry `INPUT` matches the static `INPUT`",
+            "`statics` entry `crate::HEADER` matches no static the crate defines or names, did you mean `HEADER`?",
+            "`trusted_unsafe` entry `ffi::copy_raw` matches the function `ffi::copy_raw`",
+            "`trusted_unsafe` entry `copy_unchecked` matches no function the crate defines or names",
+            "`aliases` entry `ffi::copy_raw` matches the function `ffi::copy_raw`",
+            "`hashing` entry `digest::sha256` matches no function the crate defines or names",
+            "`modules` entry `crate::ffi` matches the module `ffi`",
+            "`modules` entry `grpc` matches no module the crate defines or names",
+            "`owners` pattern `billing::*` matches no function the crate defines or names",
+            "`owners` pattern `crate::payments::*` matches 2 functions: `payments::charge` and `payments::refund`",
+            "`--taint-add-sink` path `log` matches no function the crate defines or names",
+        ]
+    );
+}
@@ -0,0 +1,19 @@
+// A facade re-exporting the functions of an engine under other names.
+pub use engine::{execute as run, load as fetch};
+
+mod engine {
+    pub fn load() -> i32 {
+        1
+    }
+
+    pub fn execute(_: i32) {}
+
+    pub fn combine(a: i32, _: i32) -> i32 {
+        a
+    }
+}
+
+fn main() {
+    run(fetch());
+    run(engine::combine(1, fetch()));
+}
@@ -0,0 +1,34 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+#[derive(Clone)]
+struct Credentials {
+    key: String,
+    user: String,
+}
+
+fn main() {
+    let key = api_key();
+    let _copy = key.clone();
+    let credentials = Credentials {
+        key: api_key(),
+        user: String::from("admin"),
+    };
+    let _backup = credentials.clone();
+    let _name = name().clone();
+    // taint-ignore: T0013 reason="handed to the worker, which wipes it"
+    let _moved = key.clone();
+    store(key, credentials.key + &credentials.user);
+}
+
+#[taint::source(label = "api-key")]
+fn api_key() -> String {
+    String::new()
+}
+
+#[taint::source]
+fn name() -> String {
+    String::new()
+}
+
+fn store(_: String, _: String) {}
@@ -0,0 +1,38 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+fn main() {
+    query(digest(&name()).to_string());
+    query(fingerprint(name()));
+    query(name());
+    log(digest(&password()).to_string());
+}
+
+fn digest(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn fingerprint(text: String) -> String {
+    text
+}
+
+#[taint::source(label = "sqli")]
+fn name() -> String {
+    String::new()
+}
+
+#[taint::source(label = "secret")]
+fn password() -> String {
+    String::new()
+}
+
+#[taint::sink(label = "user-input")]
+fn query(_: String) {}
+
+#[taint::sink(label = "secret")]
+fn log(_: String) {}
@@ -0,0 +1,22 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+mod grpc {
+    pub fn decode(bytes: &[u8]) -> String {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+mod vendored {
+    pub fn normalize(text: String) -> String {
+        text.trim().to_owned()
+    }
+}
+
+fn main() {
+    log(&grpc::decode(b"name"));
+    log(&vendored::normalize(grpc::decode(b"name")));
+}
+
+#[taint::sink]
+fn log(_: &str) {}
@@ -0,0 +1,28 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    payments::charge(input());
+    payments::refunds::issue(input());
+    output(input());
+}
+
+mod payments {
+    pub fn charge(amount: String) {
+        super::output(amount);
+    }
+
+    pub mod refunds {
+        pub fn issue(amount: String) {
+            super::super::output(amount);
+        }
+    }
+}
+
+#[taint::source]
+fn input() -> String {
+    String::new()
+}
+
+#[taint::sink]
+fn output(_: String) {}
@@ -0,0 +1,22 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+static INPUT: &str = "";
+static HEADER: &str = "";
+
+fn main() {
+    let data = INPUT.to_owned() + HEADER;
+    payments::charge(data.clone());
+    payments::refund(data.len());
+    unsafe { ffi::copy_raw(data.as_ptr()) };
+}
+
+mod ffi {
+    pub unsafe fn copy_raw(_: *const u8) {}
+}
+
+mod payments {
+    pub fn charge(_: String) {}
+
+    pub fn refund(_: usize) {}
+}
@@ -0,0 +1,32 @@
+#![crate_type = "lib"]
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+// Reached by anyone over HTTP, analyzed with `--taint-library` by its profile.
+pub fn handle(query: String, size: usize) {
+    execute(&query);
+    let _ = Vec::<u8>::with_capacity(size);
+}
+
+// Only run by administrators, whose arguments are trusted.
+pub fn run(query: String) {
+    execute(&query);
+}
+
+pub fn cleanup() {
+    execute(&input());
+    let _ = Vec::<u8>::with_capacity(input().len());
+}
+
+// Not listed, so not analyzed.
+pub fn internal() {
+    execute(&input());
+}
+
+#[taint::source]
+fn input() -> String {
+    String::new()
+}
+
+#[taint::sink]
+fn execute(_: &str) {}
@@ -0,0 +1,20 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    log(header());
+    let _ = Vec::<u8>::with_capacity(input());
+}
+
+#[taint::source(label = "header")]
+fn header() -> usize {
+    4
+}
+
+#[taint::source]
+fn input() -> usize {
+    4
+}
+
+#[taint::sink(label = "user-input")]
+fn log(_: usize) {}
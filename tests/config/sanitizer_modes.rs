@@ -0,0 +1,47 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+enum Mode {
+    Html,
+    Url,
+}
+
+fn main() {
+    render(escape(comment(), Mode::Html));
+    render(escape(comment(), Mode::Url));
+    let mode = if comment().is_empty() {
+        Mode::Html
+    } else {
+        Mode::Url
+    };
+    respond(escape(comment(), mode));
+    query(quote(name(), "sql"));
+    query(quote(name(), "shell"));
+}
+
+#[taint::source(label = "xss")]
+fn comment() -> String {
+    String::new()
+}
+
+#[taint::source(label = "sqli")]
+fn name() -> String {
+    String::new()
+}
+
+fn escape(text: String, _: Mode) -> String {
+    text
+}
+
+fn quote(text: String, _: &str) -> String {
+    text
+}
+
+#[taint::sink(label = "xss")]
+fn render(_: String) {}
+
+#[taint::sink(label = "xss")]
+fn respond(_: String) {}
+
+#[taint::sink(label = "sqli")]
+fn query(_: String) {}
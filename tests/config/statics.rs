@@ -0,0 +1,20 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+// Written by the C side before `main` runs.
+extern "C" {
+    static INPUT_BUF: [u8; 16];
+    static HEADER: u8;
+    static LIMIT: u8;
+}
+
+fn main() {
+    unsafe {
+        log(INPUT_BUF[0]);
+        log(HEADER);
+        log(LIMIT);
+    }
+}
+
+#[taint::sink]
+fn log(_: u8) {}
@@ -0,0 +1,42 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+struct Query {
+    sql: String,
+    limit: usize,
+}
+
+fn main() {
+    let name = input();
+    let length = name.len();
+    let empty = name.is_empty();
+    let query = Query {
+        sql: format!("SELECT * FROM users WHERE name = '{}'", name),
+        limit: length,
+    };
+    allocate(length);
+    branch(empty);
+    run(&query.sql);
+    limit(query.limit);
+    execute(query);
+}
+
+#[taint::source]
+fn input() -> String {
+    String::new()
+}
+
+#[taint::sink]
+fn allocate(_: usize) {}
+
+#[taint::sink]
+fn branch(_: bool) {}
+
+#[taint::sink]
+fn run(_: &str) {}
+
+#[taint::sink]
+fn limit(_: usize) {}
+
+#[taint::sink]
+fn execute(_: Query) {}
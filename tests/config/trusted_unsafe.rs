@@ -0,0 +1,19 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let n = input();
+    unsafe {
+        checked(n);
+        trusted(n);
+    }
+}
+
+unsafe fn checked(_: usize) {}
+
+unsafe fn trusted(_: usize) {}
+
+#[taint::source]
+fn input() -> usize {
+    2
+}
@@ -0,0 +1,54 @@
+//! Tests running `cargo taint` on the example projects in `examples`, each of which lists the findings
+//! it is expected to have in its `expected.txt`: the headline and the position of each.
+
+use std::{fs, path::Path, process::Command};
+
+/// The headlines and positions of the findings of `cargo taint` on `example`, run with `env`.
+fn findings(example: &str, env: &[(&str, &str)]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-taint"))
+        .arg("taint")
+        .current_dir(Path::new("examples").join(example))
+        .env("CARGO", env!("CARGO"))
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join(example),
+        )
+        .env("TAINT", env!("CARGO_BIN_EXE_taint"))
+        .envs(env.iter().copied())
+        .output()
+        .expect("cargo-taint runs");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+    let mut findings = String::new();
+    while let Some(line) = lines.next() {
+        if line.starts_with("error[") {
+            findings.push_str(line);
+            findings.push('\n');
+            findings.push_str(lines.next().unwrap_or_default());
+            findings.push('\n');
+        }
+    }
+    findings
+}
+
+fn expected(example: &str) -> String {
+    fs::read_to_string(Path::new("examples").join(example).join("expected.txt")).unwrap()
+}
+
+#[test]
+fn web_handler_sql_injection() {
+    assert_eq!(findings("web_sqli", &[]), expected("web_sqli"));
+}
+
+#[test]
+fn environment_command_injection() {
+    assert_eq!(
+        findings("env_command_injection", &[("TAINT_SINK_PACKS", "process")]),
+        expected("env_command_injection")
+    );
+}
+
+#[test]
+fn secret_logging() {
+    assert_eq!(findings("secret_logging", &[]), expected("secret_logging"));
+}
@@ -0,0 +1,48 @@
+//! Tests for `taint --explain`.
+
+use std::process::Command;
+
+use serde_json::Value;
+
+fn taint(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_taint"))
+        .args(args)
+        .output()
+        .expect("taint runs")
+}
+
+#[test]
+fn every_rule_is_explained_from_its_description() {
+    let capabilities: Value = serde_json::from_slice(&taint(&["--capabilities"]).stdout).unwrap();
+    for rule in capabilities["rules"].as_array().unwrap() {
+        let output = taint(&["--explain", rule["id"].as_str().unwrap()]);
+        assert!(output.status.success());
+        let explanation = String::from_utf8(output.stdout).unwrap();
+        let description = rule["description"].as_str().unwrap();
+        let first_line = explanation.lines().next().unwrap();
+        assert!(
+            first_line.eq_ignore_ascii_case(&format!("{description}.")),
+            "{}",
+            first_line
+        );
+        assert!(explanation.contains("```rust"));
+    }
+}
+
+#[test]
+fn codes_are_matched_in_any_case() {
+    let output = taint(&["--explain", "t0001"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with("A sink received tainted input."));
+}
+
+#[test]
+fn unknown_codes_are_an_error() {
+    let output = taint(&["--explain", "E0308"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("`E0308` is not an error code of the analysis"));
+    assert!(stderr.contains("T0001"));
+}
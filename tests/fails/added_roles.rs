@@ -0,0 +1,19 @@
+// Test that functions can be given a role by their path on the command line,
+// including functions of other crates.
+// compile-flags: --taint-add-source=input --taint-add-sink=output --taint-add-sink=std::process::exit --taint-add-sanitizer=clamp
+
+fn main() {
+    output(input()); //~ ERROR function `output` received tainted input [T0001]
+    output(clamp(input()));
+    std::process::exit(input()); //~ ERROR function `std::process::exit` received tainted input [T0001]
+}
+
+fn input() -> i32 {
+    4
+}
+
+fn clamp(n: i32) -> i32 {
+    n.min(8)
+}
+
+fn output(_: i32) {}
@@ -0,0 +1,19 @@
+// Test that a function cannot be given more than one role.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+#[taint::source]
+#[taint::sanitizer] //~ ERROR `input` is already marked as a source [T0004]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+#[taint::sink] //~ ERROR `output` is already marked as a sink [T0004]
+fn output(_: i32) {}
+
+fn main() {
+    let _ = input;
+    let _ = output;
+}
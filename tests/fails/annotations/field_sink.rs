@@ -0,0 +1,16 @@
+// Test that fields can only be marked as sources.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+struct Query {
+    #[taint::sink] //~ ERROR Taint attribute is invalid. Fields can only be marked as a `source` [T0002]
+    text: String,
+}
+
+fn main() {
+    let _ = Query {
+        text: String::new(),
+    }
+    .text;
+}
@@ -0,0 +1,27 @@
+// Test that acknowledgments must give a reason, and only apply to statements and expressions,
+// and that sinks on return cannot be gated.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+#[taint::acknowledged(reason = "a whole function")] //~ ERROR Taint attribute is invalid. Only statements and expressions can be `acknowledged` [T0002]
+fn main() {
+    let query = input();
+    #[taint::acknowledged] //~ ERROR Taint attribute is invalid. Acknowledgments must give a `reason = "<reason>"` [T0002]
+    execute(&query); //~ ERROR function `execute` is gated, and received tainted input at a call which does not acknowledge it [T0007]
+    #[taint::acknowledged(reason = "")] //~ ERROR Taint attribute is invalid. Acknowledgments must give a `reason = "<reason>"` [T0002]
+    execute(&query); //~ ERROR function `execute` is gated, and received tainted input at a call which does not acknowledge it [T0007]
+}
+
+#[taint::source]
+fn input() -> String {
+    "SELECT 1".to_owned()
+}
+
+#[taint::sink(gated)]
+fn execute(_query: &str) {}
+
+#[taint::sink(return, gated)] //~ ERROR Taint attribute argument is invalid. Sinks on `return` are not checked at their calls, so they cannot be `gated` [T0002]
+pub fn session() -> String {
+    String::new()
+}
@@ -0,0 +1,15 @@
+// Test that a sanitizer changing a label needs both the label it changes and the one it changes it to.
+
+#![crate_type = "lib"]
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+#[taint::sanitizer(from = "encoded")] //~ ERROR Taint attribute is invalid. Sanitizers change a label `from = "<label>"` into another one `to = "<label>"`, and need both [T0002]
+pub fn decode(url: String) -> String {
+    url
+}
+
+#[taint::sanitizer(from = "encoded", to = "encoded")] //~ ERROR Taint attribute is invalid. `encoded` cannot be changed into itself [T0002]
+pub fn unchanged(url: String) -> String {
+    url
+}
@@ -0,0 +1,27 @@
+// Test that sanitizers can only come after other functions marked as sanitizers.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    escape(decode(trim(strip(1))));
+}
+
+#[taint::sanitizer(after = "decode")] //~ ERROR `decode` is not marked as a sanitizer [T0002]
+fn escape(i: i32) -> i32 {
+    i
+}
+
+fn decode(i: i32) -> i32 {
+    i
+}
+
+#[taint::sanitizer(after = "trim")] //~ ERROR `trim` cannot come after itself [T0002]
+fn trim(i: i32) -> i32 {
+    i
+}
+
+#[taint::sanitizer(decode)] //~ ERROR Taint attribute argument is invalid. Sanitizers only support `after = "<sanitizer>"`, `from = "<label>"` and `to = "<label>"` [T0002]
+fn strip(i: i32) -> i32 {
+    i
+}
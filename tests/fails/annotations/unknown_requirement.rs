@@ -0,0 +1,15 @@
+// Test that sinks can only require functions marked as sanitizers.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    render(1);
+    output(2);
+}
+
+#[taint::sink(requires = "escape_html")] //~ ERROR `escape_html` is not marked as a sanitizer [T0002]
+fn render(_: i32) {}
+
+#[taint::sink(escape_html)] //~ ERROR Taint attribute argument is invalid. Sinks only support `requires = "<sanitizer>"`, `label = "<label>"`, `return` and `gated` [T0002]
+fn output(_: i32) {}
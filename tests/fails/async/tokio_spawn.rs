@@ -0,0 +1,48 @@
+// Test that taint flows into tasks spawned with `tokio::spawn`, and back out through their `JoinHandle`.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let data = input();
+    tokio::spawn(async move { output(data) }); //~ ERROR function `output` received tainted input [T0001]
+
+    let handle = tokio::spawn(async { input() });
+    tokio::spawn(async move { output(handle.await) }); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> i32 {
+    15
+}
+
+#[taint::sink]
+fn output(_: i32) {}
+
+/// A stand-in for the parts of `tokio` that the analysis models.
+mod tokio {
+    use std::future::Future;
+    use std::marker::PhantomData;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    pub struct JoinHandle<T> {
+        _id: usize,
+        _output: PhantomData<T>,
+    }
+
+    impl<T> Future for JoinHandle<T> {
+        type Output = T;
+
+        fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<T> {
+            Poll::Pending
+        }
+    }
+
+    pub fn spawn<F: Future>(_: F) -> JoinHandle<F::Output> {
+        JoinHandle {
+            _id: 0,
+            _output: PhantomData,
+        }
+    }
+}
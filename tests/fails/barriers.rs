@@ -0,0 +1,38 @@
+// Test that `taint_runtime::barrier` stops taint without counting as a sanitizer,
+// and that `std::hint::black_box` is a barrier under `--taint-black-box-barrier`.
+// compile-flags: --taint-black-box-barrier
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::hint::black_box;
+
+fn main() {
+    let id = input();
+    store(taint_runtime::barrier(id));
+    store(black_box(id));
+    store(taint_runtime::barrier(id) + id); //~ ERROR function `store` received tainted input [T0001]
+
+    let hashed = hash(id);
+    store(hashed);
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn store(_: i32) {}
+
+fn hash(i: i32) -> i32 {
+    taint_runtime::barrier(i.wrapping_mul(31))
+}
+
+/// Stands in for the `taint-runtime` crate.
+mod taint_runtime {
+    #[inline(never)]
+    pub fn barrier<T>(value: T) -> T {
+        value
+    }
+}
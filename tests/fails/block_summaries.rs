@@ -0,0 +1,30 @@
+// Test that blocks summarized before they are analyzed move labels like their statements do.
+// compile-flags: --taint-block-summaries=0
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let mut checksum = 0u32;
+    let mut clean = 7u32;
+    let mut tainted = input();
+    for round in 0..4u32 {
+        checksum = checksum.wrapping_mul(31).wrapping_add(tainted ^ round);
+        tainted = clean.rotate_left(round);
+        clean = round * 3 + 1;
+    }
+    output(clean);
+    output(checksum); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut fields = (0u32, input());
+    fields.0 = fields.1 + 1;
+    fields.1 = 0;
+    output(fields.0); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> u32 {
+    15
+}
+
+#[taint::sink]
+fn output(_: u32) {}
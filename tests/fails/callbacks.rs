@@ -0,0 +1,50 @@
+// Test that calls through trait objects run the closures which were registered as callbacks earlier,
+// as long as they take the same arguments.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+struct Server {
+    handlers: Vec<Box<dyn Fn(i32)>>,
+    loggers: Vec<Box<dyn Fn(&str)>>,
+}
+
+impl Server {
+    fn on_request(&mut self, handler: Box<dyn Fn(i32)>) {
+        self.handlers.push(handler);
+    }
+
+    fn on_log(&mut self, logger: Box<dyn Fn(&str)>) {
+        self.loggers.push(logger);
+    }
+
+    fn serve(&self, request: i32) {
+        for handler in &self.handlers {
+            handler(request);
+        }
+        for logger in &self.loggers {
+            logger("served");
+        }
+    }
+}
+
+fn main() {
+    let mut server = Server {
+        handlers: vec![],
+        loggers: vec![],
+    };
+    server.on_request(Box::new(|request| output(request))); //~ ERROR function `output` received tainted input [T0001]
+    server.on_log(Box::new(|message| log(message.len())));
+    server.serve(input());
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
+
+#[taint::sink]
+fn log(_: usize) {}
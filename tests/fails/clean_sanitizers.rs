@@ -0,0 +1,46 @@
+// Test that with `--taint-lint-clean-sanitizers`, calls to sanitizers whose input is never tainted,
+// in any context they are analyzed in, are reported as warnings.
+// compile-flags: --taint-lint-clean-sanitizers
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let name = input();
+    render(escape(name));
+    render(escape(7)); //~ WARN sanitizer `escape` is only called here with input which is never tainted [T0008]
+
+    // Tainted in one context, clean in the other.
+    page(name);
+    page(3);
+    banner(5);
+
+    // taint-ignore: T0008 reason="kept in case the constant becomes configurable"
+    render(escape(8));
+
+    output(name); //~ ERROR function `output` received tainted input [T0001]
+}
+
+fn page(body: i32) {
+    render(escape(body));
+}
+
+fn banner(text: i32) {
+    render(escape(text)); //~ WARN sanitizer `escape` is only called here with input which is never tainted [T0008]
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sanitizer]
+fn escape(i: i32) -> i32 {
+    i
+}
+
+#[taint::sink]
+fn render(_: i32) {}
+
+#[taint::sink]
+fn output(_: i32) {}
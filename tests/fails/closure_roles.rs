@@ -0,0 +1,42 @@
+// Test that closures do not take the role of the function they are defined in,
+// and that annotating a closure expression gives the closure a role.
+
+#![feature(register_tool, stmt_expr_attributes)]
+#![register_tool(taint)]
+
+fn main() {
+    let query = #[taint::source]
+    || 1;
+    let run = #[taint::sink]
+    |_: i32| {};
+    let escape = #[taint::sanitizer]
+    |i: i32| i;
+
+    run(query()); //~ ERROR closure `main::{closure#1}` received tainted input [T0001]
+    run(escape(query()));
+    run(2);
+
+    // The closure returned by `sink` is not a sink, and the one inside `input` not a source.
+    let log = sink();
+    log(input());
+    output(clean());
+}
+
+#[taint::source]
+fn input() -> i32 {
+    let read = || 1;
+    read()
+}
+
+#[taint::sink]
+fn sink() -> impl Fn(i32) {
+    |_| {}
+}
+
+fn clean() -> i32 {
+    let read = || 1;
+    read()
+}
+
+#[taint::sink]
+fn output(_: i32) {}
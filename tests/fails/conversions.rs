@@ -0,0 +1,35 @@
+// Test that views returned by `AsRef`, `Borrow` and `Deref` carry the labels of the value they were
+// taken of, both when the call resolves to an impl, which is analyzed, and when it does not,
+// like on a generic parameter or a trait object, where the call is modeled as passing the value on.
+// compile-flags: --taint-library
+
+#![crate_type = "lib"]
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::{borrow::Borrow, borrow::Cow, ops::Deref, path::Path};
+
+pub fn concrete(request: String) {
+    let borrowed: Cow<str> = Cow::Borrowed(&request);
+    execute(&borrowed); //~ ERROR function `execute` received tainted input [T0001]
+    execute(Borrow::<str>::borrow(&request)); //~ ERROR function `execute` received tainted input [T0001]
+    open(request.as_ref()); //~ ERROR function `open` received tainted input [T0001]
+    let owned: Cow<str> = Cow::Owned(request);
+    execute(&owned.into_owned()); //~ ERROR function `execute` received tainted input [T0001]
+}
+
+pub fn generic<T: AsRef<str>, B: Borrow<str>, D: Deref<Target = str>>(t: T, b: B, d: D) {
+    execute(t.as_ref()); //~ ERROR function `execute` received tainted input [T0001]
+    execute(b.borrow()); //~ ERROR function `execute` received tainted input [T0001]
+    execute(&d); //~ ERROR function `execute` received tainted input [T0001]
+}
+
+pub fn dynamic(request: &dyn AsRef<str>) {
+    execute(request.as_ref()); //~ ERROR function `execute` received tainted input [T0001]
+}
+
+#[taint::sink]
+fn execute(_: &str) {}
+
+#[taint::sink]
+fn open(_: &Path) {}
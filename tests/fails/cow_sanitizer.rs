@@ -0,0 +1,41 @@
+// Test that a sanitizer returning a `Cow`, which borrows its input when it was already safe and owns a cleaned
+// copy otherwise, returns sanitized data either way, while the input it borrows from stays tainted, even once
+// the result is assigned to a variable which borrowed the input before.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::borrow::Cow;
+
+#[taint::source]
+fn input() -> String {
+    "<script>".to_owned()
+}
+
+#[taint::sink]
+fn render(_: &str) {}
+
+#[taint::sanitizer]
+fn escape(s: &str) -> Cow<'_, str> {
+    if s.contains('<') {
+        Cow::Owned(s.replace('<', "&lt;"))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+fn main() {
+    let raw = input();
+    let escaped = escape(&raw);
+    render(&escaped);
+    render(escaped.as_ref());
+    render(&escaped.into_owned());
+    render(&raw); //~ ERROR function `render` received tainted input [T0001]
+
+    let raw = input();
+    let mut title = Cow::from(raw.as_str());
+    render(&title); //~ ERROR function `render` received tainted input [T0001]
+    title = escape(&raw);
+    drop(title);
+    render(&raw); //~ ERROR function `render` received tainted input [T0001]
+}
@@ -0,0 +1,66 @@
+// Test that formatting a value with a derived `Debug` impl carries the labels of the fields marked as sources
+// within its type, however deeply they are nested, since the impl reads every field.
+// A `Debug` impl written by hand may leave fields out, so the fields of its type are not followed.
+// compile-flags: --taint-sink-macros=println
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+#![allow(dead_code)]
+
+use std::fmt;
+
+#[derive(Debug)]
+struct Credentials {
+    #[taint::source(label = "secret")]
+    api_key: String,
+    #[taint::source]
+    password: String,
+}
+
+#[derive(Debug)]
+struct Config {
+    name: String,
+    credentials: Option<Credentials>,
+    fallbacks: Vec<Credentials>,
+}
+
+struct Redacted(Credentials);
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+#[derive(Debug)]
+struct Public {
+    name: String,
+    credentials: Redacted,
+}
+
+fn main() {
+    let config = load();
+    log(&format!("{:?}", config)); //~ ERROR function `log` received tainted input [T0001]
+    println!("{:#?}", config); //~ ERROR macro `println!` received tainted input [T0001]
+    log(&format!("{}", config.name));
+
+    let public = Public {
+        name: String::new(),
+        credentials: Redacted(Credentials {
+            api_key: String::new(),
+            password: String::new(),
+        }),
+    };
+    log(&format!("{:?}", public));
+}
+
+fn load() -> Config {
+    Config {
+        name: String::new(),
+        credentials: None,
+        fallbacks: vec![],
+    }
+}
+
+#[taint::sink]
+fn log(_: &str) {}
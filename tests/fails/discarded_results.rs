@@ -0,0 +1,62 @@
+// Test that chains of method calls through temporaries keep their taint
+// when the result of the last call is discarded, into `_` or as a statement.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::sync::OnceLock;
+
+static CACHE: OnceLock<String> = OnceLock::new();
+static LAST: OnceLock<String> = OnceLock::new();
+
+struct Query {
+    text: String,
+}
+
+impl Query {
+    fn new(text: String) -> Query {
+        Query { text }
+    }
+
+    fn trimmed(self) -> Query {
+        self
+    }
+
+    fn cache(mut self) -> Query {
+        let _ = CACHE.set(std::mem::take(&mut self.text));
+        self
+    }
+
+    fn remember(self) -> bool {
+        LAST.set(self.text).is_ok()
+    }
+
+    fn append_to(self, log: &mut Vec<String>) -> usize {
+        log.push(self.text);
+        log.len()
+    }
+}
+
+fn main() {
+    let _ = Query::new(input()).trimmed().cache();
+    send(CACHE.get().unwrap()); //~ ERROR function `send` received tainted input [T0001]
+
+    Query::new(input()).trimmed().remember();
+    send(LAST.get().unwrap()); //~ ERROR function `send` received tainted input [T0001]
+
+    let mut log = Vec::new();
+    let _ = Query::new(input()).trimmed().append_to(&mut log);
+    send(&log[0]); //~ ERROR function `send` received tainted input [T0001]
+
+    let mut other = Vec::new();
+    Query::new(input()).append_to(&mut other);
+    send(&other[0]); //~ ERROR function `send` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> String {
+    String::new()
+}
+
+#[taint::sink]
+fn send(_: &str) {}
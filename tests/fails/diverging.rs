@@ -0,0 +1,43 @@
+// Test that functions which never return are analyzed, and that calls to them which may diverge
+// do not hide the taint of the paths which do return.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let x = input();
+    output(checked(x)); //~ ERROR function `output` received tainted input [T0001]
+    output(checked(1));
+
+    if x > 4 {
+        serve(x);
+    }
+    abort(x);
+}
+
+fn checked(x: i32) -> i32 {
+    if x < 0 {
+        abort(x)
+    } else {
+        x
+    }
+}
+
+fn serve(x: i32) -> ! {
+    loop {
+        output(x); //~ ERROR function `output` received tainted input [T0001]
+    }
+}
+
+fn abort(x: i32) -> ! {
+    output(x); //~ ERROR function `output` received tainted input [T0001]
+    panic!("aborting");
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,69 @@
+// Test that dropping a value runs its destructor, including while unwinding from a panic,
+// and that a destructor marked as a sink reports the tainted values dropped.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+struct Logger {
+    value: i32,
+}
+
+impl Drop for Logger {
+    fn drop(&mut self) {
+        output(self.value); //~ ERROR function `output` received tainted input [T0001]
+    }
+}
+
+struct Audit {
+    value: i32,
+}
+
+impl Drop for Audit {
+    fn drop(&mut self) {
+        output(self.value); //~ ERROR function `output` received tainted input [T0001]
+    }
+}
+
+struct Sealed {
+    _value: i32,
+}
+
+impl Drop for Sealed {
+    #[taint::sink]
+    fn drop(&mut self) {}
+}
+
+fn main() {
+    let _clean = Logger { value: 1 };
+
+    // Only dropped when `work` panics.
+    let guard = Logger { value: input() };
+    work();
+    std::mem::forget(guard);
+
+    // Only tainted when `fill` panics.
+    let mut audit = Audit { value: 1 };
+    fill(&mut audit.value);
+}
+
+fn seal() {
+    let _clean = Sealed { _value: 1 };
+    let _sealed = Sealed { _value: input() };
+} //~ ERROR function `<Sealed as std::ops::Drop>::drop` received tainted input [T0001]
+
+fn work() {
+    seal();
+}
+
+fn fill(value: &mut i32) -> ! {
+    *value = input();
+    panic!("filled");
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
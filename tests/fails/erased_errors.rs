@@ -0,0 +1,116 @@
+// Test that errors keep their labels when their type is erased, into the `Box<dyn Error>` which `?`
+// converts them to or into an `anyhow::Error`, up to where they are formatted for a log.
+// The stand-in for `anyhow` holds nothing of the error, like the vtable of the real one hides what it holds.
+// compile-flags: --taint-library
+
+#![crate_type = "lib"]
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::{error::Error, fmt};
+
+#[derive(Debug)]
+pub struct ParseError {
+    input: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot parse `{}`", self.input)
+    }
+}
+
+impl Error for ParseError {}
+
+fn parse(input: String) -> Result<u32, ParseError> {
+    Err(ParseError { input })
+}
+
+fn parse_boxed(input: String) -> Result<u32, Box<dyn Error>> {
+    let n = parse(input)?;
+    Ok(n)
+}
+
+pub fn boxed(input: String, other: String) {
+    if let Err(e) = parse_boxed(other) {
+        log(&e.to_string()); //~ ERROR function `log` received tainted input [T0001]
+    }
+    let e: Box<dyn Error + Send + Sync> = input.into();
+    log(&format!("{:?}", e)); //~ ERROR function `log` received tainted input [T0001]
+    if let Some(source) = e.source() {
+        log(&source.to_string()); //~ ERROR function `log` received tainted input [T0001]
+    }
+}
+
+pub fn with_anyhow(input: String, message: String, context: String) {
+    let e = anyhow::Error::msg(message);
+    log(&e.to_string()); //~ ERROR function `log` received tainted input [T0001]
+    let e = anyhow::Error::new(parse(input).unwrap_err()).context("while parsing");
+    log(&format!("{:#}", e)); //~ ERROR function `log` received tainted input [T0001]
+    let e = anyhow::Error::msg("cannot parse").context(context);
+    log(&e.root_cause().to_string()); //~ ERROR function `log` received tainted input [T0001]
+}
+
+pub fn with_context(input: String) -> Result<u32, anyhow::Error> {
+    use anyhow::Context;
+    let parsed = parse(input).context("while parsing");
+    if let Err(e) = &parsed {
+        log(&e.to_string()); //~ ERROR function `log` received tainted input [T0001]
+    }
+    parsed
+}
+
+pub fn clean() {
+    let e: Box<dyn Error> = "cannot parse".into();
+    log(&e.to_string());
+    log(&anyhow::Error::msg("cannot parse").to_string());
+}
+
+#[taint::sink]
+fn log(_: &str) {}
+
+mod anyhow {
+    use std::fmt;
+
+    pub struct Error(u8);
+
+    impl Error {
+        pub fn new<E>(_: E) -> Error {
+            Error(0)
+        }
+
+        pub fn msg<M>(_: M) -> Error {
+            Error(0)
+        }
+
+        pub fn context<C>(self, _: C) -> Error {
+            self
+        }
+
+        pub fn root_cause(&self) -> &(dyn std::error::Error + 'static) {
+            unimplemented!()
+        }
+    }
+
+    pub trait Context<T, E> {
+        fn context<C>(self, context: C) -> Result<T, Error>;
+    }
+
+    impl<T, E> Context<T, E> for Result<T, E> {
+        fn context<C>(self, _: C) -> Result<T, Error> {
+            self.map_err(|_| Error(0))
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("error")
+        }
+    }
+
+    impl fmt::Debug for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("error")
+        }
+    }
+}
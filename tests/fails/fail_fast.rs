@@ -0,0 +1,24 @@
+// Test that `--taint-fail-fast` stops the analysis at the first finding which is an error,
+// so that the sinks after it are not reported.
+// compile-flags: --taint-fail-fast
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let input = input();
+    output(&input); //~ ERROR function `output` received tainted input [T0001]
+    output(&input);
+    forward(input);
+}
+
+fn forward(value: String) {
+    output(&value);
+}
+
+#[taint::source]
+fn input() -> String {
+    String::from("input")
+}
+
+#[taint::sink]
+fn output(_: &str) {}
@@ -0,0 +1,36 @@
+// Test that reading a field marked as a source produces tainted data,
+// whether the field is copied, borrowed or moved out of the struct.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+struct Request {
+    #[taint::source]
+    body: String,
+    #[taint::source(label = "header")]
+    length: usize,
+    method: u8,
+}
+
+fn main() {
+    let request = receive();
+    output(request.method as usize);
+    output(request.length); //~ ERROR function `output` received tainted input labeled `header` [T0001]
+    output_str(&request.body); //~ ERROR function `output_str` received tainted input [T0001]
+    let body = request.body;
+    output_str(&body); //~ ERROR function `output_str` received tainted input [T0001]
+}
+
+fn receive() -> Request {
+    Request {
+        body: String::new(),
+        length: 0,
+        method: 0,
+    }
+}
+
+#[taint::sink]
+fn output(_: usize) {}
+
+#[taint::sink]
+fn output_str(_: &str) {}
@@ -0,0 +1,54 @@
+// Test that functions passed by name to combinators, like `map(lookup)`, have their role
+// or their effect on the elements, and that sinks they call are reported at the combinator.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let name = Some(1).map(lookup);
+    output(&name.unwrap()); //~ ERROR function `output` received tainted input [T0001]
+
+    let inputs = vec![input(), input()];
+    inputs.iter().for_each(output); //~ ERROR function `output` received tainted input [T0001]
+
+    let query = Some(input());
+    query.as_ref().map(output); //~ ERROR function `output` received tainted input [T0001]
+
+    let cleaned = Some(input()).map(clean);
+    output(&cleaned.unwrap());
+
+    // Functions without a role are analyzed with the elements as their arguments.
+    let copied = Some(input()).map(copy);
+    output(&copied.unwrap()); //~ ERROR function `output` received tainted input [T0001]
+    let counted = Some(input()).map(count);
+    count_sink(counted.unwrap());
+}
+
+fn copy(s: String) -> String {
+    s
+}
+
+fn count(_: String) -> usize {
+    1
+}
+
+#[taint::source]
+fn lookup(_id: i32) -> String {
+    String::new()
+}
+
+#[taint::source]
+fn input() -> String {
+    String::new()
+}
+
+#[taint::sanitizer]
+fn clean(s: String) -> String {
+    s
+}
+
+#[taint::sink]
+fn output(_: &String) {}
+
+#[taint::sink]
+fn count_sink(_: usize) {}
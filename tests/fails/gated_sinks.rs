@@ -0,0 +1,50 @@
+// Test that a gated sink only accepts tainted input at calls which acknowledge it,
+// by the statement or the expression they are made in, and that other sinks do not.
+
+#![feature(register_tool, stmt_expr_attributes)]
+#![register_tool(taint)]
+
+fn main() {
+    let query = input();
+
+    execute(&query); //~ ERROR function `execute` is gated, and received tainted input at a call which does not acknowledge it [T0007]
+
+    #[taint::acknowledged(reason = "queries from the admin console are trusted")]
+    execute(&query);
+
+    #[taint::acknowledged(reason = "the migration is run by hand")]
+    let _rows = execute(&query);
+
+    let _rows = #[taint::acknowledged(reason = "reviewed in the security audit")]
+    execute(&query);
+
+    // Each call is acknowledged on its own.
+    #[taint::acknowledged(reason = "the first one is reviewed")]
+    execute(&query);
+    execute(&query); //~ ERROR function `execute` is gated, and received tainted input at a call which does not acknowledge it [T0007]
+
+    // A call made by a callee is not the acknowledged call.
+    #[taint::acknowledged(reason = "the helper is reviewed")]
+    run(&query);
+
+    // Acknowledgments do not apply to sinks which are not gated.
+    #[taint::acknowledged(reason = "logs are private")]
+    log(&query); //~ ERROR function `log` received tainted input [T0001]
+}
+
+fn run(query: &str) {
+    execute(query); //~ ERROR function `execute` is gated, and received tainted input at a call which does not acknowledge it [T0007]
+}
+
+#[taint::source]
+fn input() -> String {
+    "SELECT 1".to_owned()
+}
+
+#[taint::sink(gated)]
+fn execute(_query: &str) -> usize {
+    0
+}
+
+#[taint::sink]
+fn log(_message: &str) {}
@@ -0,0 +1,111 @@
+// Test that data stashed in a global cell, like a `OnceLock`, a `once_cell` cell or a `lazy_static!`,
+// is tainted where it is read, in whichever function reads it after it was set.
+
+#![feature(register_tool)]
+#![feature(lazy_cell)]
+#![register_tool(taint)]
+
+use std::cell::OnceCell;
+use std::sync::{LazyLock, OnceLock};
+
+static CONFIG: OnceLock<String> = OnceLock::new();
+static GREETING: OnceLock<String> = OnceLock::new();
+static BANNER: OnceLock<String> = OnceLock::new();
+static TOKEN: once_cell::sync::OnceCell<String> = once_cell::sync::OnceCell::new();
+static SECRET: LazyLock<String> = LazyLock::new(input);
+
+lazy_static::lazy_static! {
+    static ref SESSION: String = input();
+}
+
+fn main() {
+    let _ = CONFIG.set(input());
+    let _ = GREETING.set(String::new());
+    let _ = BANNER.get_or_init(|| input());
+    let _ = TOKEN.set(input());
+    handle();
+
+    let local = OnceCell::new();
+    let _ = local.set(input());
+    output(local.get().unwrap()); //~ ERROR function `output` received tainted input [T0001]
+}
+
+fn handle() {
+    output(CONFIG.get().unwrap()); //~ ERROR function `output` received tainted input [T0001]
+    output(GREETING.get().unwrap());
+    output(BANNER.get().unwrap()); //~ ERROR function `output` received tainted input [T0001]
+    output(TOKEN.get().unwrap()); //~ ERROR function `output` received tainted input [T0001]
+    output(&SECRET); //~ ERROR function `output` received tainted input [T0001]
+    output(&SESSION); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> String {
+    String::from("<script>")
+}
+
+#[taint::sink]
+fn output(_: &str) {}
+
+/// A stand-in for the cells of `once_cell`, whose value the analysis does not follow through their interior mutability.
+mod once_cell {
+    pub mod sync {
+        pub struct OnceCell<T>(std::sync::OnceLock<T>);
+
+        impl<T> OnceCell<T> {
+            pub const fn new() -> OnceCell<T> {
+                OnceCell(std::sync::OnceLock::new())
+            }
+
+            pub fn set(&self, value: T) -> Result<(), T> {
+                self.0.set(value)
+            }
+
+            pub fn get(&self) -> Option<&T> {
+                self.0.get()
+            }
+        }
+    }
+}
+
+/// A stand-in for `lazy_static`, which expands like the real macro does.
+mod lazy_static {
+    pub mod lazy {
+        pub struct Lazy<T: Sync>(std::sync::OnceLock<T>);
+
+        impl<T: Sync> Lazy<T> {
+            #[allow(clippy::declare_interior_mutable_const)]
+            pub const INIT: Self = Lazy(std::sync::OnceLock::new());
+
+            pub fn get<F: FnOnce() -> T>(&'static self, f: F) -> &T {
+                self.0.get_or_init(f)
+            }
+        }
+    }
+
+    #[macro_export]
+    macro_rules! lazy_static {
+        (static ref $name:ident : $t:ty = $init:expr;) => {
+            #[allow(non_camel_case_types)]
+            struct $name {
+                __private_field: (),
+            }
+            static $name: $name = $name { __private_field: () };
+            impl std::ops::Deref for $name {
+                type Target = $t;
+                fn deref(&self) -> &$t {
+                    fn __static_ref_initialize() -> $t {
+                        $init
+                    }
+                    fn __stability() -> &'static $t {
+                        static LAZY: $crate::lazy_static::lazy::Lazy<$t> =
+                            $crate::lazy_static::lazy::Lazy::INIT;
+                        LAZY.get(__static_ref_initialize)
+                    }
+                    __stability()
+                }
+            }
+        };
+    }
+    pub use lazy_static;
+}
@@ -0,0 +1,62 @@
+// Test that a sanitizer returning `bool` validates its arguments: where it returned `true`,
+// like after a guard clause returning or panicking when it did not, the variable it checked is clean.
+// Where the paths which did not check it join back, the variable is tainted again.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    guard();
+    guard_with_variable();
+    branch();
+    copy_before_check();
+}
+
+fn guard() {
+    let query = input();
+    if !is_valid(&query) {
+        return;
+    }
+    output(&query);
+}
+
+fn guard_with_variable() {
+    let query = input();
+    let valid = is_valid(&query);
+    if !valid {
+        panic!("invalid query");
+    }
+    output(&query);
+}
+
+fn branch() {
+    let query = input();
+    if is_valid(&query) {
+        output(&query);
+    } else {
+        output(&query); //~ ERROR function `output` received tainted input [T0001]
+    }
+    output(&query); //~ ERROR function `output` received tainted input [T0001]
+}
+
+fn copy_before_check() {
+    let query = input();
+    let copy = format!("{}", query);
+    if !is_valid(&query) {
+        return;
+    }
+    output(&copy); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> String {
+    String::from("SELECT 1")
+}
+
+#[taint::sanitizer]
+fn is_valid(query: &str) -> bool {
+    !query.contains(';')
+}
+
+#[taint::sink]
+fn output(_: &str) {}
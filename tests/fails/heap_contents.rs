@@ -0,0 +1,47 @@
+// Test that taint written into the contents of boxes and collections is seen by the caller,
+// also when the writing happens in a callee which only gets a reference to the container.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn fill(v: &mut Vec<i32>) {
+    v.push(input());
+}
+
+fn fill_box(b: &mut Box<i32>) {
+    **b = input();
+}
+
+fn wrap(i: i32) -> Vec<i32> {
+    vec![i]
+}
+
+fn main() {
+    let mut v = Vec::new();
+    fill(&mut v);
+    output(v[0]); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut b = Box::new(1);
+    fill_box(&mut b);
+    output(*b); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut w = Vec::new();
+    w.push(input());
+    w.push(1);
+    output(w[1]); //~ ERROR function `output` received tainted input [T0001]
+
+    let x = wrap(input());
+    output(x[0]); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut clean = Vec::new();
+    clean.push(1);
+    output(clean[0]);
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
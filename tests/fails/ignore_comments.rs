@@ -0,0 +1,45 @@
+// Test that a `// taint-ignore:` comment suppresses the findings under its codes on the line below it,
+// and that it must name the codes of findings and give a reason.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let query = input();
+
+    // taint-ignore: T0001 reason="validated upstream"
+    output(query);
+
+    let _ = 1 + {
+        // taint-ignore: T0001, T0003 reason="only counted"
+        count(query)
+    };
+
+    // taint-ignore: T0003 reason="escaped by the template"
+    output(query); //~ ERROR function `output` received tainted input [T0001]
+
+    // taint-ignore: T0001 reason="too far from the call"
+
+    output(query); //~ ERROR function `output` received tainted input [T0001]
+
+    // taint-ignore: T0001
+    //~^ ERROR Taint comment is invalid. Ignored findings must give a `reason="<reason>"` [T0002]
+    output(query); //~ ERROR function `output` received tainted input [T0001]
+
+    // taint-ignore: T0002 reason="not a finding"
+    //~^ ERROR Taint comment is invalid. Only the codes of findings can be ignored, like `taint-ignore: T0001, T0003` [T0002]
+    output(query); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
+
+#[taint::sink]
+fn count(i: i32) -> i32 {
+    i
+}
@@ -0,0 +1,38 @@
+// Test that with `--taint-include-tests`, a crate compiled with `--test` has its `#[test]` functions
+// and the public functions under `#[cfg(test)]` analyzed as entry points, besides its other public functions.
+// compile-flags: --test --taint-include-tests
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+pub fn handle() {
+    output(input()); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[test]
+fn flows_to_output() {
+    output(input() + 1); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub fn fixture() {
+        output(input() + 2); //~ ERROR function `output` received tainted input [T0001]
+    }
+
+    #[test]
+    fn clean() {
+        fixture();
+        output(1);
+    }
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
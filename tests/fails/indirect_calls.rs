@@ -0,0 +1,43 @@
+// Test that calls through function pointers picked or forged by tainted data are reported.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+struct Handler {
+    run: fn(i32),
+}
+
+fn main() {
+    let handlers: [fn(i32); 2] = [greet, shutdown];
+    let picked = handlers[opcode()];
+    picked(1); //~ ERROR call through a function pointer tainted with label `opcode` [T0011]
+
+    let forged: fn(i32) = unsafe { std::mem::transmute(input()) };
+    forged(2); //~ ERROR call through a tainted function pointer [T0011]
+
+    let handler = Handler {
+        run: handlers[opcode()],
+    };
+    (handler.run)(3); //~ ERROR call through a function pointer tainted with label `opcode` [T0011]
+
+    // Only the arguments are tainted, not which function runs.
+    let fixed = handlers[0];
+    fixed(input() as i32);
+
+    // taint-ignore: T0011 reason="the opcode was checked against the table"
+    handlers[opcode()](4);
+}
+
+fn greet(_: i32) {}
+
+fn shutdown(_: i32) {}
+
+#[taint::source(label = "opcode")]
+fn opcode() -> usize {
+    1
+}
+
+#[taint::source]
+fn input() -> usize {
+    1
+}
@@ -0,0 +1,29 @@
+// Test that the outputs of inline assembly carry the labels of its inputs.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::arch::asm;
+
+fn main() {
+    let tainted = input();
+    let copied: u64;
+    unsafe { asm!("mov {0}, {1}", out(reg) copied, in(reg) tainted) };
+    output(copied); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut doubled = tainted;
+    unsafe { asm!("add {0}, {0}", inout(reg) doubled) };
+    output(doubled); //~ ERROR function `output` received tainted input [T0001]
+
+    let clean: u64;
+    unsafe { asm!("mov {0}, 4", out(reg) clean) };
+    output(clean);
+}
+
+#[taint::source]
+fn input() -> u64 {
+    4
+}
+
+#[taint::sink]
+fn output(_: u64) {}
@@ -0,0 +1,52 @@
+// Test that `#[taint::model(...)]` gives foreign functions the propagation rule the config would.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+#[taint::model(result_from = [0])]
+extern "C" {
+    fn decode(p: *const u8, n: usize) -> *mut u8;
+    fn checksum(p: *const u8, n: usize) -> *mut u8;
+}
+
+extern "C" {
+    #[taint::model(writes = { 0: [1] })]
+    fn copy(to: *mut u8, from: *const u8);
+    fn length(p: *const u8) -> usize;
+}
+
+fn main() {
+    let input = input();
+    let decoded = unsafe { decode(input.as_ptr(), input.len()) };
+    output(decoded); //~ ERROR function `output` received tainted input [T0001]
+    let summed = unsafe { checksum(input.as_ptr(), input.len()) };
+    output(summed); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut buffer = [0u8; 16];
+    unsafe { copy(buffer.as_mut_ptr(), input.as_ptr()) };
+    output(buffer.as_mut_ptr()); //~ ERROR function `output` received tainted input [T0001]
+
+    // Without a model, the call is not analyzed and its result is clean.
+    let n = unsafe { length(input.as_ptr()) };
+    output(n as *mut u8);
+}
+
+#[allow(dead_code)]
+#[taint::model(result_from = [0])] //~ ERROR Taint attribute is invalid. Only `extern` blocks and the functions they declare can be given a `model` [T0002]
+fn local(p: *const u8) -> *const u8 {
+    p
+}
+
+extern "C" {
+    #[allow(dead_code)]
+    #[taint::model(result_from = 0)] //~ ERROR Taint attribute is invalid. Models only support `result_from = [<arg>, ...]` and `writes = { <arg>: [<arg>, ...], ... }` [T0002]
+    fn malformed(p: *const u8) -> *const u8;
+}
+
+#[taint::source]
+fn input() -> Vec<u8> {
+    vec![1, 2, 3]
+}
+
+#[taint::sink]
+fn output(_: *mut u8) {}
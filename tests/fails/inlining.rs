@@ -0,0 +1,27 @@
+// Test that sources and sinks keep their roles when the optimizer would inline them.
+// compile-flags: -O
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    output(input()); //~ ERROR function `output` received tainted input [T0001]
+    output(forward(input())); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[inline(always)]
+fn forward(x: i32) -> i32 {
+    x
+}
+
+#[taint::source]
+#[inline(always)]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+#[inline(always)]
+fn output(x: i32) {
+    std::hint::black_box(x);
+}
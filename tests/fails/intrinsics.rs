@@ -0,0 +1,49 @@
+// Test that calls to intrinsics follow their models: reinterpreting and reading values carries their taint,
+// and copying or writing through pointers taints what they point to.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::{mem, ptr};
+
+fn main() {
+    let bytes: [u8; 4] = unsafe { mem::transmute(input()) };
+    output_u8(bytes[0]); //~ ERROR function `output_u8` received tainted input [T0001]
+
+    let secret = input();
+    let read = unsafe { ptr::read(&secret) };
+    output(read); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut copied = 0;
+    unsafe { ptr::copy_nonoverlapping(&secret, &mut copied, 1) };
+    output(copied); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut byte = 0u8;
+    unsafe { ptr::write_bytes(&mut byte, input_byte(), 1) };
+    output_u8(byte); //~ ERROR function `output_u8` received tainted input [T0001]
+
+    let mut clean = 0;
+    unsafe { ptr::copy_nonoverlapping(&4, &mut clean, 1) };
+    output(clean);
+
+    output_usize(mem::size_of::<i32>());
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::source]
+fn input_byte() -> u8 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
+
+#[taint::sink]
+fn output_u8(_: u8) {}
+
+#[taint::sink]
+fn output_usize(_: usize) {}
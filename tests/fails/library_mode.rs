@@ -0,0 +1,31 @@
+// Test that in library mode, the arguments of public functions are tainted,
+// while functions which are not part of the public interface only see what they are called with.
+// compile-flags: --taint-library
+
+#![crate_type = "lib"]
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+pub fn handle(request: i32) {
+    execute(request); //~ ERROR function `execute` received tainted input [T0001]
+}
+
+pub fn handle_checked(request: i32) {
+    execute(validate(request));
+}
+
+pub fn handle_constant(_request: i32) {
+    internal(5);
+}
+
+fn internal(query: i32) {
+    execute(query);
+}
+
+#[taint::sanitizer]
+fn validate(i: i32) -> i32 {
+    i
+}
+
+#[taint::sink]
+fn execute(_: i32) {}
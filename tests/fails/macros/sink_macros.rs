@@ -0,0 +1,31 @@
+// Test that macros can be sinks, matched by their name or by their path,
+// and that findings point at the invocation of the macro.
+// compile-flags: --taint-sink-macros=println,log::info
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let secret = input();
+    println!("{}", secret); //~ ERROR macro `println!` received tainted input [T0001]
+    log::info!("{:?}", secret + 1); //~ ERROR macro `log::info!` received tainted input [T0001]
+    println!("{}", 4);
+    eprintln!("{}", secret);
+}
+
+#[taint::source]
+fn input() -> i32 {
+    15
+}
+
+/// A stand-in for the `log` crate.
+mod log {
+    macro_rules! info {
+        ($($arg:tt)+) => {
+            crate::log::write(format_args!($($arg)+))
+        };
+    }
+    pub(crate) use info;
+
+    pub fn write(_: std::fmt::Arguments<'_>) {}
+}
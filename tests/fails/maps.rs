@@ -0,0 +1,65 @@
+// Test that maps keep track of which labels their keys carry and which their values do:
+// looking up a value does not return the taint of the keys, and iterating over keys does not
+// return the taint of the values, while reading both does.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::collections::{BTreeMap, HashMap};
+
+fn main() {
+    let mut values = HashMap::new();
+    values.insert(1, input());
+    output(*values.get(&1).unwrap()); //~ ERROR function `output` received tainted input [T0001]
+    output(values[&1]); //~ ERROR function `output` received tainted input [T0001]
+    for key in values.keys() {
+        output(*key);
+    }
+
+    let mut keys = HashMap::new();
+    keys.insert(input(), 1);
+    output(*keys.get(&input()).unwrap());
+    output(keys[&2]);
+    output(keys.remove(&2).unwrap());
+    for key in keys.keys() {
+        output(*key); //~ ERROR function `output` received tainted input [T0001]
+    }
+
+    let moved = keys;
+    output(moved[&2]);
+    // Iterators borrowing a map alias it, so the labels of the map are mixed after they advance.
+    for (key, value) in &moved {
+        output(*key + *value); //~ ERROR function `output` received tainted input [T0001]
+    }
+
+    let mut tree = BTreeMap::new();
+    tree.insert(input(), 1);
+    output(*tree.get(&1).unwrap());
+    for key in tree.keys() {
+        output(*key); //~ ERROR function `output` received tainted input [T0001]
+    }
+    for value in tree.into_values() {
+        output(value);
+    }
+
+    // Entries put in by methods which are not modeled may carry their labels in keys or values.
+    let mut entries = HashMap::new();
+    entries.insert(input(), 1);
+    *entries.entry(2).or_insert(0) += input();
+    output(entries[&2]); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut extended = HashMap::new();
+    extended.insert(1, input());
+    extended.extend([(input(), 2)]);
+    for key in extended.keys() {
+        output(*key); //~ ERROR function `output` received tainted input [T0001]
+    }
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
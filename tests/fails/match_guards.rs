@@ -0,0 +1,41 @@
+// Test that the bindings of a match arm carry the taint of the scrutinee,
+// whether the arm has a guard or not, and that writes through them reach the scrutinee.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    match Some(input()) {
+        Some(v) if v > 0 => output(v), //~ ERROR function `output` received tainted input [T0001]
+        Some(w) => output(w), //~ ERROR function `output` received tainted input [T0001]
+        None => {}
+    }
+
+    match input() {
+        n if n > 1 => output(n + 1), //~ ERROR function `output` received tainted input [T0001]
+        m @ 0..=1 => output(m), //~ ERROR function `output` received tainted input [T0001]
+        _ => {}
+    }
+
+    let mut slot = Some(0);
+    match slot {
+        Some(ref mut v) if *v == 0 => *v = input(),
+        _ => {}
+    }
+    output(slot.unwrap()); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut other = Some(0);
+    match &mut other {
+        Some(v) if *v == 0 => *v = input(),
+        _ => {}
+    }
+    output(other.unwrap()); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
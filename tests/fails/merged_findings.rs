@@ -0,0 +1,39 @@
+// Test that a sink is reported once per call, however many of its arguments are tainted
+// and whatever labels they carry, and once per function for a sink on return.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let a = input();
+    output(a, a); //~ ERROR function `output` received tainted input [T0001]
+
+    output(header(), cookie()); //~ ERROR function `output` received tainted input labeled `header` [T0001]
+    output(input(), header()); //~ ERROR function `output` received tainted input [T0001]
+
+    let _ = session(header());
+    let _ = session(cookie());
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::source(label = "header")]
+fn header() -> i32 {
+    5
+}
+
+#[taint::source(label = "cookie")]
+fn cookie() -> i32 {
+    6
+}
+
+#[taint::sink(return)]
+fn session(token: i32) -> i32 { //~ ERROR function `session` returns tainted data labeled `header` [T0001]
+    token
+}
+
+#[taint::sink]
+fn output(_: i32, _: i32) {}
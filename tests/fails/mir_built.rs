@@ -0,0 +1,26 @@
+// Test that analyzing built MIR finds flows which optimizations propagate away,
+// like the read of a static whose value is known at compile time.
+// compile-flags: -O --taint-mir=built
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+static SECRET: i32 = input();
+
+fn main() {
+    output(SECRET); //~ ERROR function `output` received tainted input [T0001]
+    output(forward(SECRET)); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[inline]
+fn forward(x: i32) -> i32 {
+    x
+}
+
+#[taint::source]
+const fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,45 @@
+// Test that calls to functions without MIR we can analyze, like naked functions, whose body is assembly,
+// follow `--taint-unknown-calls` like foreign functions do, while `#[no_mangle]` and `#[export_name]`
+// functions are analyzed like any other.
+// compile-flags: --taint-unknown-calls=propagate
+
+#![feature(register_tool, naked_functions)]
+#![register_tool(taint)]
+
+use std::arch::asm;
+
+fn main() {
+    let tainted = input();
+    output(unsafe { identity(tainted) }); //~ ERROR function `output` received tainted input [T0001]
+    output(unsafe { abs(tainted) }); //~ ERROR function `output` received tainted input [T0001]
+    exported(tainted);
+    renamed(tainted);
+    output(unsafe { identity(4) });
+}
+
+#[naked]
+unsafe extern "C" fn identity(_: i32) -> i32 {
+    asm!("mov eax, edi", "ret", options(noreturn));
+}
+
+extern "C" {
+    fn abs(n: i32) -> i32;
+}
+
+#[no_mangle]
+extern "C" fn exported(n: i32) {
+    output(n); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[export_name = "taint_renamed"]
+extern "C" fn renamed(n: i32) {
+    output(n); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,57 @@
+// Test that every function defined in a module marked `#![taint::source_boundary]` is a source,
+// down to the methods of its impls and the functions of the modules inside it,
+// and that calls to the functions of a module marked `#[taint::trusted]` return clean data.
+// Attributes inside a module need `custom_inner_attributes`.
+
+#![feature(register_tool, custom_inner_attributes)]
+#![register_tool(taint)]
+
+mod grpc {
+    #![taint::source_boundary(label = "grpc")]
+
+    pub struct Request {
+        pub name: String,
+    }
+
+    impl Request {
+        pub fn decode(bytes: &[u8]) -> Request {
+            Request {
+                name: String::from_utf8_lossy(bytes).into_owned(),
+            }
+        }
+    }
+
+    pub mod metadata {
+        pub fn header(name: &str) -> String {
+            name.to_owned()
+        }
+    }
+
+    // A role of its own is kept.
+    #[taint::sanitizer]
+    pub fn escape(text: String) -> String {
+        text
+    }
+}
+
+#[taint::trusted]
+mod vendored {
+    pub fn normalize(text: String) -> String {
+        text.trim().to_owned()
+    }
+}
+
+#[taint::trusted] //~ ERROR Taint attribute is invalid. Only modules can be marked as `trusted` [T0002]
+fn helper() {}
+
+fn main() {
+    let request = grpc::Request::decode(b"name");
+    execute(&request.name); //~ ERROR function `execute` received tainted input labeled `grpc` [T0001]
+    execute(&grpc::metadata::header("user")); //~ ERROR function `execute` received tainted input labeled `grpc` [T0001]
+    execute(&grpc::escape(request.name));
+    execute(&vendored::normalize(grpc::metadata::header("user")));
+    helper();
+}
+
+#[taint::sink]
+fn execute(_: &str) {}
@@ -0,0 +1,46 @@
+// Test that `to_string` on a newtype, a struct with a single field, carries the labels of the value
+// it wraps, through its `Display` impl, like formatting it with `format!` does.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::fmt;
+
+struct Email(String);
+
+impl fmt::Display for Email {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}>", self.0)
+    }
+}
+
+struct UserId {
+    id: String,
+}
+
+impl fmt::Display for UserId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.id.fmt(f)
+    }
+}
+
+fn main() {
+    let email = Email(input());
+    output(&email.to_string()); //~ ERROR function `output` received tainted input [T0001]
+    output(&format!("{}", email)); //~ ERROR function `output` received tainted input [T0001]
+
+    let user = UserId { id: input() };
+    let by_ref = &user;
+    output(&by_ref.to_string()); //~ ERROR function `output` received tainted input [T0001]
+
+    let clean = Email(String::from("admin@example.com"));
+    output(&clean.to_string());
+}
+
+#[taint::source]
+fn input() -> String {
+    String::from("input")
+}
+
+#[taint::sink]
+fn output(_: &str) {}
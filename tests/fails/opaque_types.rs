@@ -0,0 +1,73 @@
+// Test that values of opaque types, returned as `impl Trait` or by `async fn`s, are followed into the
+// closures, iterators and futures behind them, including once they are turned into trait objects.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+fn main() {
+    for item in items(input()) {
+        output(&item); //~ ERROR function `output` received tainted input [T0001]
+    }
+    let greet = greeter(input());
+    output(&greet()); //~ ERROR function `output` received tainted input [T0001]
+
+    let handler: Box<dyn Fn(String)> = Box::new(handler());
+    handler(input());
+
+    output(&block_on(fetch(input()))); //~ ERROR function `output` received tainted input [T0001]
+    output(&block_on(boxed(input()))); //~ ERROR function `output` received tainted input [T0001]
+    output(&block_on(boxed(String::new())));
+}
+
+fn items(s: String) -> impl Iterator<Item = String> {
+    vec![s].into_iter()
+}
+
+fn greeter(s: String) -> impl Fn() -> String {
+    move || format!("hello {}", s)
+}
+
+fn handler() -> impl Fn(String) {
+    |s| output(&s) //~ ERROR function `output` received tainted input [T0001]
+}
+
+async fn fetch(s: String) -> String {
+    later(s).await
+}
+
+fn later(s: String) -> impl Future<Output = String> {
+    async move { s }
+}
+
+fn boxed(s: String) -> Pin<Box<dyn Future<Output = String>>> {
+    Box::pin(fetch(s))
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    fn clone(data: *const ()) -> RawWaker {
+        RawWaker::new(data, &VTABLE)
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    let mut future = Box::pin(future);
+    let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) };
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[taint::source]
+fn input() -> String {
+    String::from("<script>")
+}
+
+#[taint::sink]
+fn output(_: &str) {}
@@ -0,0 +1,55 @@
+// Test that taint flows through the closures passed to the combinators of `Option` and `Result`,
+// which are analyzed as callees of the code of `core` calling them, and through `?`.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let id = Some(input());
+    count(id.map(|v| transform(v)).unwrap()); //~ ERROR function `count` received tainted input [T0001]
+    count(id.and_then(|v| Some(transform(v))).unwrap_or(0)); //~ ERROR function `count` received tainted input [T0001]
+    count(None.unwrap_or_else(|| input())); //~ ERROR function `count` received tainted input [T0001]
+    count(id.filter(|v| *v > 0).map(|v| v + 1).unwrap_or(1)); //~ ERROR function `count` received tainted input [T0001]
+    count(Some(1).map(|v| v + input()).unwrap()); //~ ERROR function `count` received tainted input [T0001]
+    count(id.map(transform).unwrap()); //~ ERROR function `count` received tainted input [T0001]
+    count(Some(1).map(|v| v + 1).unwrap());
+
+    let parsed: Result<i32, String> = Ok(input());
+    count(parsed.clone().map(|v| v + 1).unwrap_or_default()); //~ ERROR function `count` received tainted input [T0001]
+    count(parsed.clone().map_or(0, |v| v)); //~ ERROR function `count` received tainted input [T0001]
+
+    let name = Some(read());
+    let quoted = name.as_ref().map(|s| format!("<{}>", s));
+    output(&quoted.unwrap_or_default()); //~ ERROR function `output` received tainted input [T0001]
+    let first = name.as_ref().and_then(|s| s.split(',').next()).map(str::trim);
+    output(first.unwrap_or("")); //~ ERROR function `output` received tainted input [T0001]
+    let failed: Result<(), String> = Err(read());
+    output(&failed.map_err(|e| format!("bad {}", e)).unwrap_err()); //~ ERROR function `output` received tainted input [T0001]
+    let _ = greet(Some(read()));
+}
+
+fn greet(name: Option<String>) -> Option<()> {
+    let name = name?;
+    output(&name); //~ ERROR function `output` received tainted input [T0001]
+    Some(())
+}
+
+fn transform(v: i32) -> i32 {
+    v * 3
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::source]
+fn read() -> String {
+    String::new()
+}
+
+#[taint::sink]
+fn count(_: i32) {}
+
+#[taint::sink]
+fn output(_: &str) {}
@@ -0,0 +1,48 @@
+// Test that a pair keeps track of which labels each of its elements carries, and so do the pairs
+// iterators made by `enumerate` and `zip` yield: the index of a tainted item is not tainted,
+// and neither is the item zipped with one.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let names = vec![input()];
+    for (ix, name) in names.iter().enumerate() {
+        output(&ix.to_string());
+        output(name); //~ ERROR function `output` received tainted input [T0001]
+    }
+    for (ix, name) in names.iter().enumerate().rev().skip(1) {
+        output(&ix.to_string());
+        output(name); //~ ERROR function `output` received tainted input [T0001]
+    }
+
+    let ids = vec![String::from("id")];
+    for (id, name) in ids.iter().zip(names.iter()) {
+        output(id);
+        output(name); //~ ERROR function `output` received tainted input [T0001]
+    }
+
+    // Writing an element puts its labels into that element alone.
+    let mut entry = (String::new(), String::new());
+    entry.1 = input();
+    let moved = entry;
+    output(&moved.0);
+    output(&moved.1); //~ ERROR function `output` received tainted input [T0001]
+
+    // A pair returned by a function carries its labels in both.
+    let (clean, tainted) = split(input());
+    output(&clean); //~ ERROR function `output` received tainted input [T0001]
+    output(&tainted); //~ ERROR function `output` received tainted input [T0001]
+}
+
+fn split(value: String) -> (String, String) {
+    (String::new(), value)
+}
+
+#[taint::source]
+fn input() -> String {
+    String::from("input")
+}
+
+#[taint::sink]
+fn output(_: &str) {}
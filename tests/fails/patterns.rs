@@ -0,0 +1,50 @@
+// Test that taint flows through subslice patterns and bindings by reference,
+// both when reading the bindings and when writing through them.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let [head, rest @ ..] = numbers();
+    output(head); //~ ERROR function `output` received tainted input [T0001]
+    output_slice(&rest); //~ ERROR function `output_slice` received tainted input [T0001]
+
+    let pair = (input(), 0);
+    let (ref first, _) = pair;
+    output(*first); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut written = (0, 0);
+    let (_, ref mut second) = written;
+    *second = input();
+    output_pair(written); //~ ERROR function `output_pair` received tainted input [T0001]
+
+    let mut buffer = vec![0; 4];
+    if let [first, ..] = buffer.as_mut_slice() {
+        *first = input();
+    }
+    output_slice(&buffer); //~ ERROR function `output_slice` received tainted input [T0001]
+
+    let mut clean = [0; 3];
+    let [.., last] = &mut clean;
+    *last = 1;
+    output_slice(&clean);
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::source]
+fn numbers() -> [i32; 4] {
+    [1, 2, 3, 4]
+}
+
+#[taint::sink]
+fn output(_: i32) {}
+
+#[taint::sink]
+fn output_slice(_: &[i32]) {}
+
+#[taint::sink]
+fn output_pair(_: (i32, i32)) {}
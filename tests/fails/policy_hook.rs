@@ -0,0 +1,28 @@
+// Test that a policy hook gives roles to functions without annotations, by their path
+// and the constant arguments of each call.
+// compile-flags: --taint-policy-hook=tests/hooks/policy.rhai
+
+fn main() {
+    let request = read_request();
+    run_query(&request); //~ ERROR function `run_query` received tainted input [T0001]
+
+    run_query(&escape(&request, "sql"));
+    run_query(&escape(&request, "html")); //~ ERROR function `run_query` received tainted input [T0001]
+
+    // `wrap` drops its input, but the hook says its result carries it.
+    run_query(&wrap(&request)); //~ ERROR function `run_query` received tainted input [T0001]
+}
+
+fn read_request() -> String {
+    String::from("1 OR 1=1")
+}
+
+fn run_query(_: &str) {}
+
+fn escape(input: &str, _mode: &str) -> String {
+    input.replace('\'', "''")
+}
+
+fn wrap(_: &str) -> String {
+    String::new()
+}
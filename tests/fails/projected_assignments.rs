@@ -0,0 +1,60 @@
+// Test that assignments through pointers, fields and indices write into the objects they reach,
+// and only replace their taint when they are known to overwrite all of one.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+struct Request {
+    body: i32,
+    id: i32,
+}
+
+fn main() {
+    // A reference which may point to either local leaves both with the taint they had.
+    let mut clean = 0;
+    let mut tainted = input();
+    let either = if clean == 0 { &mut clean } else { &mut tainted };
+    *either = 0;
+    sink(tainted); //~ ERROR function `sink` received tainted input [T0001]
+
+    // A reference to a single local overwrites it.
+    let mut overwritten = input();
+    let only = &mut overwritten;
+    *only = 0;
+    sink(overwritten);
+
+    // Writing a field through a reference taints the object it points to, but does not clean it.
+    let mut request = Request { body: 0, id: 0 };
+    let by_ref = &mut request;
+    (*by_ref).body = input();
+    by_ref.id = 0;
+    sink(request.id); //~ ERROR function `sink` received tainted input [T0001]
+
+    // Writing through a reference to a field does not clean the rest of the object.
+    let mut partial = Request { body: input(), id: 0 };
+    let id = &mut partial.id;
+    *id = 0;
+    sink(partial.body); //~ ERROR function `sink` received tainted input [T0001]
+
+    // Writing an element taints the array, through a reference to it too.
+    let mut ids = [0; 4];
+    let slice = &mut ids[..];
+    slice[1] = input();
+    sink(ids[0]); //~ ERROR function `sink` received tainted input [T0001]
+
+    // Writing a reference through a reference makes the pointer it writes point there.
+    let secret = input();
+    let public = 0;
+    let mut shown = &public;
+    let at = &mut shown;
+    *at = &secret;
+    sink(*shown); //~ ERROR function `sink` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn sink(_: i32) {}
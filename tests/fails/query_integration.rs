@@ -0,0 +1,24 @@
+// Test that the analysis finds the same flows when run from an override of the `analysis` query.
+// compile-flags: --taint-integration=query
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let password = read_password();
+    let hidden = hash(password);
+    log(hidden);
+    log(password); //~ ERROR function `log` received tainted input [T0001]
+}
+
+#[taint::source]
+fn read_password() -> i32 {
+    42
+}
+
+#[taint::sanitizer]
+fn hash(password: i32) -> i32 {
+    password.wrapping_mul(31)
+}
+
+#[taint::sink]
+fn log(_: i32) {}
@@ -0,0 +1,73 @@
+// Test that the labels of an argument passed by reference are carried by reads through it,
+// whatever their projections, and by the references copied or reborrowed from it.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+struct Request {
+    body: String,
+    id: u32,
+}
+
+fn main() {
+    let mut request = Request {
+        body: input(),
+        id: 0,
+    };
+    field(&request);
+    reborrowed(&mut request);
+    double(&&request.body);
+    copied(&request.body);
+    let clean = Request {
+        body: String::new(),
+        id: 1,
+    };
+    chosen(&request, &clean);
+
+    let mut body = input();
+    overwritten(&mut body);
+}
+
+fn field(request: &Request) {
+    let body = &request.body;
+    output(body); //~ ERROR function `output` received tainted input [T0001]
+}
+
+fn reborrowed(request: &mut Request) {
+    let inner = &mut *request;
+    let body = &mut inner.body;
+    body.push('!');
+    output(body); //~ ERROR function `output` received tainted input [T0001]
+}
+
+fn double(body: &&String) {
+    let inner: &String = *body;
+    output(inner); //~ ERROR function `output` received tainted input [T0001]
+}
+
+fn copied(body: &String) {
+    let pair = (body, 1);
+    let (copy, _) = pair;
+    output(copy); //~ ERROR function `output` received tainted input [T0001]
+}
+
+fn chosen<'a>(request: &'a Request, other: &'a Request) {
+    let mut picked = other;
+    if request.id == 0 {
+        picked = request;
+    }
+    output(&picked.body); //~ ERROR function `output` received tainted input [T0001]
+}
+
+fn overwritten(body: &mut String) {
+    *body = String::new();
+    output(body);
+}
+
+#[taint::source]
+fn input() -> String {
+    String::new()
+}
+
+#[taint::sink]
+fn output(_: &String) {}
@@ -0,0 +1,47 @@
+// Test that a sanitizer with `from` and `to` changes the label of its data instead of removing it,
+// so that data can be checked differently at each stage, and that other labels pass through it.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let url = request_path();
+    store_path(&url);
+    render_path(&url); //~ ERROR function `render_path` received tainted input labeled `encoded` [T0001]
+
+    let decoded = url_decode(url);
+    store_path(&decoded); //~ ERROR function `store_path` received tainted input labeled `decoded` [T0001]
+    render_path(&decoded);
+    store_path(&escape(decoded));
+
+    // Data without the label keeps its labels.
+    let text = input();
+    store_path(&url_decode(text)); //~ ERROR function `store_path` received tainted input [T0001]
+}
+
+#[taint::source(label = "encoded")]
+fn request_path() -> String {
+    "%2Fhome".to_owned()
+}
+
+#[taint::source]
+fn input() -> String {
+    "text".to_owned()
+}
+
+#[taint::sanitizer(from = "encoded", to = "decoded")]
+fn url_decode(url: String) -> String {
+    url.replace("%2F", "/")
+}
+
+#[taint::sanitizer]
+fn escape(path: String) -> String {
+    path.replace("..", "")
+}
+
+// Encoded data cannot traverse directories yet, but it must not be shown before it is decoded.
+#[taint::sink(label = "decoded")]
+fn store_path(_: &str) {}
+
+#[taint::sink(label = "encoded")]
+fn render_path(_: &str) {}
@@ -0,0 +1,59 @@
+// Test that a function marked `#[taint::requires_taint]` reports the calls whose input never carries
+// the label it requires, in any context they are analyzed in, like an audit log given a scrubbed id.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+#![allow(dead_code)]
+
+fn main() {
+    let id = request_id();
+    audit(&id, "login");
+    audit(&scrub(id.clone()), "login"); //~ ERROR `audit` requires input labeled `request-id`, but is called here with input which never carries it [T0010]
+    audit("anonymous", "login"); //~ ERROR `audit` requires input labeled `request-id`, but is called here with input which never carries it [T0010]
+
+    // Other taint does not carry the label.
+    audit(&input(), "login"); //~ ERROR `audit` requires input labeled `request-id`, but is called here with input which never carries it [T0010]
+
+    // Tainted in one context, clean in the other.
+    forward(&id);
+    forward("system");
+
+    trace(input());
+    trace(String::new()); //~ ERROR `trace` requires tainted input, but is called here with input which never carries it [T0010]
+
+    // taint-ignore: T0010 reason="startup happens before any request"
+    audit("startup", "boot");
+}
+
+fn forward(id: &str) {
+    audit(id, "forwarded");
+}
+
+#[taint::source(label = "request-id")]
+fn request_id() -> String {
+    "4f1c".to_owned()
+}
+
+#[taint::source]
+fn input() -> String {
+    "text".to_owned()
+}
+
+#[taint::sanitizer]
+fn scrub(_: String) -> String {
+    "****".to_owned()
+}
+
+#[taint::requires_taint(label = "request-id")]
+fn audit(_id: &str, _event: &str) {}
+
+#[taint::requires_taint]
+fn trace(_: String) {}
+
+#[taint::requires_taint(level = "high")] //~ ERROR Taint attribute argument is invalid. Requiring taint only supports `label = "<label>"` [T0002]
+fn check(_: String) {}
+
+struct Record {
+    #[taint::requires_taint] //~ ERROR Taint attribute is invalid. Only functions can require taint [T0002]
+    id: String,
+}
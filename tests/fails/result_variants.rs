@@ -0,0 +1,55 @@
+// Test that a `Result` keeps track of which labels its `Ok` value carries and which its `Err` value does:
+// matching on a tainted error does not taint the value, and the other way around.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let failed: Result<u32, String> = Err(input());
+    match failed {
+        Ok(value) => output(&value.to_string()),
+        Err(error) => output(&error), //~ ERROR function `output` received tainted input [T0001]
+    }
+
+    let parsed: Result<String, u32> = Ok(input());
+    if let Err(code) = &parsed {
+        output(&code.to_string());
+    }
+    if let Ok(value) = &parsed {
+        output(value); //~ ERROR function `output` received tainted input [T0001]
+    }
+
+    // Each branch builds its own variant, and the `Result` keeps what either put in each.
+    let either = if input().is_empty() {
+        Ok(input())
+    } else {
+        Err(String::new())
+    };
+    let moved = either;
+    match moved {
+        Ok(value) => output(&value), //~ ERROR function `output` received tainted input [T0001]
+        Err(error) => output(&error),
+    }
+
+    // A `Result` returned by a function carries its labels in both.
+    match check(input()) {
+        Ok(value) => output(&value.to_string()), //~ ERROR function `output` received tainted input [T0001]
+        Err(error) => output(&error), //~ ERROR function `output` received tainted input [T0001]
+    }
+}
+
+fn check(name: String) -> Result<usize, String> {
+    if name.is_empty() {
+        Err(name)
+    } else {
+        Ok(0)
+    }
+}
+
+#[taint::source]
+fn input() -> String {
+    String::new()
+}
+
+#[taint::sink]
+fn output(_: &str) {}
@@ -0,0 +1,45 @@
+// Test that functions marked `#[taint::sink(return)]` are reported when they return tainted data,
+// and that calls to them are still analyzed.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let token = session_token();
+    log(token); //~ ERROR function `log` received tainted input [T0001]
+
+    public_len(5);
+    let s = secret();
+    public_len(s);
+
+    public_summary(secret());
+}
+
+#[taint::source]
+fn secret() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn log(_: i32) {}
+
+#[taint::sanitizer]
+fn redact(_: i32) -> i32 {
+    0
+}
+
+#[taint::sink(return)]
+fn session_token() -> i32 { //~ ERROR function `session_token` returns tainted data [T0001]
+    secret()
+}
+
+// Only reported in the context in which it is called with a secret.
+#[taint::sink(return)]
+fn public_len(len: i32) -> i32 { //~ ERROR function `public_len` returns tainted data [T0001]
+    len + 1
+}
+
+#[taint::sink(return, requires = "redact")]
+fn public_summary(data: i32) -> i32 {
+    redact(data)
+}
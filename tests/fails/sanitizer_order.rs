@@ -0,0 +1,41 @@
+// Test that a sanitizer which must come after another reports data which did not pass through
+// the other one first, like data escaped before it is decoded.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let a = input();
+    render(escape(decode(a)));
+
+    let b = input();
+    render(decode(escape(b))); //~ ERROR function `escape` received input which was not sanitized by `decode` first [T0005]
+
+    let c = input();
+    render(escape(c)); //~ ERROR function `escape` received input which was not sanitized by `decode` first [T0005]
+
+    let d = input();
+    let e = if d < 5 { decode(d) } else { d };
+    render(escape(e)); //~ ERROR function `escape` received input which was not sanitized by `decode` first [T0005]
+
+    // Data which was never tainted can be escaped in any order.
+    render(escape(4));
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sanitizer]
+fn decode(i: i32) -> i32 {
+    i
+}
+
+#[taint::sanitizer(after = "decode")]
+fn escape(i: i32) -> i32 {
+    i
+}
+
+#[taint::sink]
+fn render(_: i32) {}
@@ -0,0 +1,44 @@
+// Test that a sink which requires a particular sanitizer rejects data cleaned by another one,
+// and data which was only sanitized on some paths.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let a = input();
+    render(escape_html(a));
+
+    let b = input();
+    render(trim(b)); //~ ERROR function `render` received input which was not sanitized by `escape_html` [T0003]
+
+    let c = input();
+    let d = if c < 5 { escape_html(c) } else { trim(c) };
+    render(d); //~ ERROR function `render` received input which was not sanitized by `escape_html` [T0003]
+
+    let e = input();
+    render(e); //~ ERROR function `render` received tainted input [T0001]
+
+    // Sinks without a requirement accept any sanitizer.
+    output(trim(input()));
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sanitizer]
+fn escape_html(i: i32) -> i32 {
+    i
+}
+
+#[taint::sanitizer]
+fn trim(i: i32) -> i32 {
+    i
+}
+
+#[taint::sink(requires = "escape_html")]
+fn render(_: i32) {}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,72 @@
+// Test that secrets wrapped in `taint_runtime::Sensitive` are labeled `secret` until they are exposed,
+// and that formatting the wrapper is always reported.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use taint_runtime::Sensitive;
+
+fn main() {
+    let token = Sensitive::new(read_token());
+    let password: Sensitive<String> = Sensitive::from(String::from("hunter2"));
+    let key: Sensitive<String> = String::from("key").into();
+
+    send(token.expose());
+    log(password.expose());
+    store(&token); //~ ERROR function `store` received tainted input labeled `secret` [T0001]
+    store(&key); //~ ERROR function `store` received tainted input labeled `secret` [T0001]
+
+    println!("token: {}", token); //~ ERROR a secret wrapped in `Sensitive` is formatted with `Display`, which exposes it [T0001]
+    let _ = format!("{:?}", password); //~ ERROR a secret wrapped in `Sensitive` is formatted with `Debug`, which exposes it [T0001]
+    let _ = key.to_string(); //~ ERROR a secret wrapped in `Sensitive` is formatted with `Display`, which exposes it [T0001]
+}
+
+fn read_token() -> String {
+    String::from("token")
+}
+
+#[taint::sink]
+fn send(_: &str) {}
+
+#[taint::sink]
+fn log(_: &String) {}
+
+#[taint::sink]
+fn store(_: &Sensitive<String>) {}
+
+/// Stands in for the `taint-runtime` crate.
+mod taint_runtime {
+    use std::fmt;
+
+    pub struct Sensitive<T> {
+        secret: T,
+    }
+
+    impl<T> Sensitive<T> {
+        pub fn new(secret: T) -> Self {
+            Sensitive { secret }
+        }
+
+        pub fn expose(&self) -> &T {
+            &self.secret
+        }
+    }
+
+    impl<T> From<T> for Sensitive<T> {
+        fn from(secret: T) -> Self {
+            Sensitive::new(secret)
+        }
+    }
+
+    impl<T> fmt::Display for Sensitive<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("[redacted]")
+        }
+    }
+
+    impl<T> fmt::Debug for Sensitive<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("Sensitive([redacted])")
+        }
+    }
+}
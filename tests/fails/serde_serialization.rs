@@ -0,0 +1,98 @@
+// Test that serializing a value with `serde_json` produces the labels of its fields,
+// including those of fields marked as sources, and reaches the writer it is serialized into.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+#![allow(dead_code)]
+
+use std::io::{self, Write};
+
+struct User {
+    name: String,
+    id: u32,
+}
+
+struct Request {
+    #[taint::source]
+    body: String,
+    id: u32,
+}
+
+struct Envelope {
+    requests: Vec<Request>,
+}
+
+struct Response;
+
+impl Write for Response {
+    #[taint::sink]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn main() {
+    let user = User {
+        name: input(),
+        id: 1,
+    };
+    output(serde_json::to_string(&user).unwrap()); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut buf = vec![];
+    serde_json::to_writer(&mut buf, &user).unwrap();
+    output_bytes(buf); //~ ERROR function `output_bytes` received tainted input [T0001]
+
+    let mut response = Response;
+    serde_json::to_writer(&mut response, &user).unwrap(); //~ ERROR function `<Response as std::io::Write>::write` received tainted input [T0001]
+
+    let envelope = Envelope {
+        requests: vec![Request {
+            body: String::new(),
+            id: 2,
+        }],
+    };
+    output(serde_json::to_string(&envelope).unwrap()); //~ ERROR function `output` received tainted input [T0001]
+
+    let clean = User {
+        name: String::new(),
+        id: 3,
+    };
+    output(serde_json::to_string(&clean).unwrap());
+    serde_json::to_writer(&mut response, &clean).unwrap();
+}
+
+#[taint::source]
+fn input() -> String {
+    String::from("<script>")
+}
+
+#[taint::sink]
+fn output(_: String) {}
+
+#[taint::sink]
+fn output_bytes(_: Vec<u8>) {}
+
+/// A stand-in for the parts of `serde_json` that the analysis models, which write nothing of the value.
+mod serde_json {
+    use std::io::Write;
+
+    pub trait Serialize {}
+
+    impl Serialize for super::User {}
+    impl Serialize for super::Envelope {}
+
+    #[derive(Debug)]
+    pub struct Error;
+
+    pub fn to_string<T: ?Sized + Serialize>(_: &T) -> Result<String, Error> {
+        Ok(String::new())
+    }
+
+    pub fn to_writer<W: Write, T: ?Sized + Serialize>(mut writer: W, _: &T) -> Result<(), Error> {
+        writer.write_all(b"{}").map_err(|_| Error)
+    }
+}
@@ -0,0 +1,49 @@
+// Test that calls to functions whose body the compiler generates have their built-in effect:
+// clones of tuples and closures carry the labels of what was cloned, and dropping a value,
+// explicitly with `drop_in_place` or at the end of its scope, runs the destructors of what it owns.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+struct Guard(i32);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        output(self.0); //~ ERROR function `output` received tainted input [T0001]
+    }
+}
+
+struct Audit(i32);
+
+impl Drop for Audit {
+    fn drop(&mut self) {
+        output(self.0); //~ ERROR function `output` received tainted input [T0001]
+    }
+}
+
+fn main() {
+    let pair = (input(), 1);
+    output(pair.clone().0); //~ ERROR function `output` received tainted input [T0001]
+    output((1, 2).clone().0);
+
+    let value = input();
+    let closure = move || value;
+    let cloned = closure.clone();
+    output(cloned()); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut guard = Some(Guard(input()));
+    unsafe {
+        std::ptr::drop_in_place(&mut guard as *mut Option<Guard>);
+    }
+    std::mem::forget(guard);
+
+    let _audits = vec![Audit(input())];
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,31 @@
+// Test that with the allocation sink pack, tainted allocation sizes are reported,
+// while tainted elements are not.
+// compile-flags: --taint-sink-packs=allocation
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let n = input();
+    let _ = Vec::<u8>::with_capacity(n); //~ ERROR allocates memory of tainted size [T0001]
+    let _ = vec![0u8; n]; //~ ERROR allocates memory of tainted size [T0001]
+
+    let mut s = String::new();
+    s.reserve(n); //~ ERROR allocates memory of tainted size [T0001]
+
+    let lengths = [n; 4];
+    let _ = Vec::<u8>::with_capacity(lengths[0]); //~ ERROR allocates memory of tainted size [T0001]
+
+    let _ = vec![n; 4];
+    let _ = Vec::<u8>::with_capacity(clamp(n));
+}
+
+#[taint::source]
+fn input() -> usize {
+    4
+}
+
+#[taint::sanitizer]
+fn clamp(n: usize) -> usize {
+    n.min(1024)
+}
@@ -0,0 +1,43 @@
+// Test that with the index sink pack, slices, arrays and collections indexed at a tainted position
+// are reported, while the elements read carry their own labels rather than those of the index.
+// compile-flags: --taint-sink-packs=index
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::collections::HashMap;
+
+fn main() {
+    let i = input();
+    let table = [1u8, 2, 3, 4];
+    let element = table[i]; //~ ERROR `table` is indexed at a tainted position [T0001]
+    output(element);
+
+    let mut counts = vec![0u32; 4];
+    counts[i] += 1; //~ ERROR received a tainted index [T0001]
+    let _ = &counts[..i]; //~ ERROR received a tainted index [T0001]
+    let values = &counts[..];
+    let _ = unsafe { values.get_unchecked(i) }; //~ ERROR received a tainted index [T0001]
+
+    let mut names = HashMap::new();
+    names.insert(0usize, "root");
+    let _ = names[&i]; //~ ERROR received a tainted index [T0001]
+
+    let checked = clamp(i);
+    let _ = table[checked];
+    let _ = counts[checked];
+    let _ = values.get(i);
+}
+
+#[taint::source]
+fn input() -> usize {
+    4
+}
+
+#[taint::sanitizer]
+fn clamp(i: usize) -> usize {
+    i.min(3)
+}
+
+#[taint::sink]
+fn output(_: u8) {}
@@ -0,0 +1,38 @@
+// Test that with the pointer sink pack, tainted pointer offsets and lengths of unsafe reads and writes
+// are reported, while tainted pointers and values are not.
+// compile-flags: --taint-sink-packs=pointer
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::{ptr, slice};
+
+fn main() {
+    let n = input();
+    let mut buffer = [0u8; 16];
+    let source = [1u8; 16];
+    unsafe {
+        ptr::copy_nonoverlapping(source.as_ptr(), buffer.as_mut_ptr(), n); //~ ERROR received a tainted pointer offset or length [T0001]
+        ptr::copy(source.as_ptr(), buffer.as_mut_ptr(), n); //~ ERROR received a tainted pointer offset or length [T0001]
+        ptr::write_bytes(buffer.as_mut_ptr(), 0, n); //~ ERROR received a tainted pointer offset or length [T0001]
+        let _ = slice::from_raw_parts(source.as_ptr(), n); //~ ERROR received a tainted pointer offset or length [T0001]
+        let _ = *source.as_ptr().add(n); //~ ERROR received a tainted pointer offset or length [T0001]
+        let _ = *source.as_ptr().offset(1);
+
+        let mut lengths = [0usize; 4];
+        lengths.as_mut_ptr().write(n);
+
+        let length = clamp(n);
+        let _ = slice::from_raw_parts(source.as_ptr(), length);
+    }
+}
+
+#[taint::source]
+fn input() -> usize {
+    4
+}
+
+#[taint::sanitizer]
+fn clamp(n: usize) -> usize {
+    n.min(16)
+}
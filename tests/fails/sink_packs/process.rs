@@ -0,0 +1,31 @@
+// Test that with the process sink pack, tainted data reaching the environment of the process,
+// or the program, arguments and environment of child processes, is reported,
+// as well as spawning a command built from it.
+// compile-flags: --taint-sink-packs=process
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::{env, process::Command};
+
+fn main() {
+    let value = input();
+    env::set_var("MODE", &value); //~ ERROR passes tainted data to the environment or a child process [T0001]
+    env::set_var("MODE", "safe");
+
+    let _ = Command::new(&value); //~ ERROR passes tainted data to the environment or a child process [T0001]
+    let mut command = Command::new("ls");
+    command.arg("-l");
+    command.arg(&value); //~ ERROR passes tainted data to the environment or a child process [T0001]
+    command.env("MODE", &value); //~ ERROR passes tainted data to the environment or a child process [T0001]
+    let _ = command.spawn(); //~ ERROR passes tainted data to the environment or a child process [T0001]
+
+    let mut clean = Command::new("ls");
+    clean.arg("-l").env("MODE", "safe");
+    let _ = clean.status();
+}
+
+#[taint::source]
+fn input() -> String {
+    "input".to_owned()
+}
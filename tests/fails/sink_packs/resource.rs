@@ -0,0 +1,39 @@
+// Test that with the resource sink pack, tainted allocation sizes, lengths of the buffers `read_exact` fills
+// and durations of `thread::sleep` are reported, while clean ones are not.
+// compile-flags: --taint-sink-packs=resource
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::{io::Read, thread, time::Duration};
+
+fn main() {
+    let n = input();
+    let _ = Vec::<u8>::with_capacity(n); //~ ERROR consumes an amount of resources decided by tainted input [T0001]
+    let _ = vec![0u8; n]; //~ ERROR consumes an amount of resources decided by tainted input [T0001]
+
+    let mut reader: &[u8] = &[1, 2, 3, 4];
+    let mut buffer = [0u8; 4];
+    let _ = reader.read_exact(&mut buffer[..n]); //~ ERROR consumes an amount of resources decided by tainted input [T0001]
+    let mut header = [0u8; 4];
+    let _ = reader.read_exact(&mut header[..2]);
+
+    let seconds = seconds();
+    thread::sleep(Duration::from_secs(seconds)); //~ ERROR consumes an amount of resources decided by tainted input [T0001]
+    thread::sleep(Duration::from_secs(clamp(seconds)));
+}
+
+#[taint::source]
+fn input() -> usize {
+    4
+}
+
+#[taint::source]
+fn seconds() -> u64 {
+    1
+}
+
+#[taint::sanitizer]
+fn clamp(n: u64) -> u64 {
+    n.min(10)
+}
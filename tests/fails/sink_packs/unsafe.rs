@@ -0,0 +1,36 @@
+// Test that with the unsafe sink pack, tainted arguments of calls to unsafe functions are reported
+// as warnings, while clean arguments and calls to safe functions are not.
+// compile-flags: --taint-sink-packs=unsafe
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let n = input();
+    let values = [1u8, 2, 3, 4];
+    unsafe {
+        let _ = values.get_unchecked(n); //~ WARN function `core::slice::<impl [u8]>::get_unchecked::<usize>` is unsafe and received tainted input [T0006]
+        let _ = values.get_unchecked(0);
+        let _ = std::str::from_utf8_unchecked(&values[..n]); //~ WARN function `std::str::from_utf8_unchecked` is unsafe and received tainted input [T0006]
+        unchecked(n); //~ WARN function `unchecked` is unsafe and received tainted input [T0006]
+        consume(n); //~ WARN function `consume` is unsafe and received tainted input [T0006]
+    }
+    let _ = values.get(n);
+    sink(n); //~ ERROR function `sink` received tainted input [T0001]
+}
+
+extern "C" {
+    fn consume(n: usize);
+}
+
+unsafe fn unchecked(n: usize) -> usize {
+    n
+}
+
+#[taint::source]
+fn input() -> usize {
+    2
+}
+
+#[taint::sink]
+fn sink(_: usize) {}
@@ -0,0 +1,69 @@
+// Test that the fields of a type marked as a source are sources, and that the methods of its impls which
+// could leak them are analyzed even when nothing calls them: getters returning a field, and `Display`
+// or `Debug` impls, derived or not, handing a field to the formatter.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::fmt;
+
+#[taint::source(label = "credential")]
+#[derive(Debug)] //~ ERROR a field of `Credentials`, which is marked as a source, is formatted with `Debug`, which exposes it [T0001]
+struct Credentials {
+    user: String,
+    password: String,
+}
+
+impl Credentials {
+    // Nothing calls it, which does not keep it from leaking the password.
+    #[allow(dead_code)]
+    fn password(&self) -> &str { //~ ERROR method `Credentials::password` returns a field of `Credentials`, which is marked as a source [T0001]
+        &self.password
+    }
+
+    fn is_empty(&self) -> bool {
+        false
+    }
+
+    fn empty() -> Credentials {
+        Credentials {
+            user: String::new(),
+            password: String::new(),
+        }
+    }
+}
+
+impl fmt::Display for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.user, self.password) //~ ERROR a field of `Credentials`, which is marked as a source, is formatted with `Display`, which exposes it [T0001]
+    }
+}
+
+#[taint::source]
+struct Token(u8);
+
+impl Token {
+    fn is_set(&self) -> bool {
+        true
+    }
+}
+
+// Redacting the fields keeps them out of the output.
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Token").field(&"<redacted>").finish()
+    }
+}
+
+fn main() {
+    let credentials = Credentials::empty();
+    output_str(&credentials.user); //~ ERROR function `output_str` received tainted input labeled `credential` [T0001]
+    output(credentials.is_empty());
+    output(Token(0).is_set());
+}
+
+#[taint::sink]
+fn output_str(_: &str) {}
+
+#[taint::sink]
+fn output(_: bool) {}
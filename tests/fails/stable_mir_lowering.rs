@@ -0,0 +1,25 @@
+// compile-flags: --taint-debug-stable-mir
+// Test that stable MIR lowers like the compiler's MIR does, over a variety of rvalues,
+// while the analysis finds the same flows as without the check.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let value = input();
+    let sum = value + 1;
+    let pair = (sum, -value);
+    let array = [pair.0; 4];
+    let slice: &[i32] = &array;
+    let raw = &array as *const [i32; 4];
+    let double = |x: i32| x * 2;
+    output(double(slice[1] + unsafe { (*raw)[0] })); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,31 @@
+// Test that reads of statics carry the taint of their initializers,
+// including of the closures which initialize them lazily.
+
+#![feature(register_tool, lazy_cell)]
+#![register_tool(taint)]
+
+use std::sync::LazyLock;
+
+static SECRET: i32 = input();
+static PUBLIC: i32 = 4;
+static CONFIG: LazyLock<i32> = LazyLock::new(|| input() + 1);
+static DEFAULTS: LazyLock<i32> = LazyLock::new(|| 2);
+
+fn main() {
+    output(SECRET); //~ ERROR function `output` received tainted input [T0001]
+    output(PUBLIC);
+
+    let secret = &SECRET;
+    output(*secret); //~ ERROR function `output` received tainted input [T0001]
+
+    output(*CONFIG); //~ ERROR function `output` received tainted input [T0001]
+    output(*DEFAULTS);
+}
+
+#[taint::source]
+const fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,74 @@
+// Test that writing tainted input into a field marked `#[taint::storage]` is only reported
+// once a value holding the field crosses a boundary: a function marked `#[taint::boundary]`,
+// or serialization.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+#![allow(dead_code)]
+
+struct Profile {
+    #[taint::storage]
+    bio: String,
+    name: String,
+}
+
+struct Draft {
+    #[taint::storage]
+    text: String,
+}
+
+struct Comment {
+    #[taint::storage]
+    text: String,
+}
+
+fn main() {
+    let profile = Profile { bio: input(), name: String::new() }; //~ WARN tainted input is stored into `Profile::bio`, which crosses a boundary [T0009]
+    // Serialized data reaching a sink is reported as usual.
+    render(serde_json::to_string(&profile).unwrap()); //~ ERROR function `render` received tainted input [T0001]
+
+    let mut renamed = Profile { bio: String::new(), name: String::new() };
+    renamed.name = input();
+    save(&renamed);
+
+    let mut edited = Profile { bio: String::new(), name: String::new() };
+    edited.bio = input(); //~ WARN tainted input is stored into `Profile::bio`, which crosses a boundary [T0009]
+    save(&edited);
+
+    // Never crosses a boundary.
+    let mut draft = Draft { text: String::new() };
+    draft.text = input();
+    keep(&draft);
+
+    // taint-ignore: T0009 reason="comments are escaped when they are rendered"
+    let comment = Comment { text: input() };
+    let _ = serde_json::to_string(&comment);
+}
+
+#[taint::source]
+fn input() -> String {
+    String::from("<script>")
+}
+
+#[taint::sink]
+fn render(_: String) {}
+
+#[taint::boundary]
+fn save(_: &Profile) {}
+
+fn keep(_: &Draft) {}
+
+/// A stand-in for the parts of `serde_json` that the analysis models, which write nothing of the value.
+mod serde_json {
+    pub trait Serialize {}
+
+    impl Serialize for super::Profile {}
+    impl Serialize for super::Comment {}
+
+    #[derive(Debug)]
+    pub struct Error;
+
+    pub fn to_string<T: ?Sized + Serialize>(_: &T) -> Result<String, Error> {
+        Ok(String::new())
+    }
+}
@@ -0,0 +1,30 @@
+// Test that with `--taint-strict-mir`, the constructs of MIR which the analysis does not model
+// are reported, since tainted data may go through them unseen.
+// compile-flags: --taint-strict-mir=yes
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+enum Command {
+    Read,
+    Write,
+}
+
+fn main() {
+    let length = input();
+    let widened = length as u64; //~ ERROR this cast is not modeled, so the labels it may carry are lost [T0012]
+    output(widened);
+
+    let command = if length > 0 { Command::Read } else { Command::Write };
+    if let Command::Write = command { //~ ERROR reading the discriminant of an enum is not modeled, so the labels it may carry are lost [T0012]
+        output(0);
+    }
+}
+
+#[taint::source]
+fn input() -> u32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: u64) {}
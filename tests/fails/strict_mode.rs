@@ -0,0 +1,29 @@
+// Test that in strict mode, calls to functions without a body propagate taint,
+// and places stay tainted after being overwritten.
+// compile-flags: --taint-mode=strict
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+extern "C" {
+    fn abs(i: i32) -> i32;
+}
+
+fn main() {
+    let a = unsafe { abs(input()) };
+    output(a); //~ ERROR function `output` received tainted input [T0001]
+
+    let mut b = input();
+    if b > 4 {
+        b = 5;
+    }
+    output(b); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
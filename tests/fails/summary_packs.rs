@@ -0,0 +1,51 @@
+// Test that the summaries bundled for `std` keep the flows through functions whose MIR is not available,
+// like cloning a `String`, which would otherwise lose its labels in helpers reading fields and elements too.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::path::PathBuf;
+
+struct Request {
+    body: String,
+}
+
+fn main() {
+    output(input().to_uppercase()); //~ ERROR function `output::<std::string::String>` received tainted input [T0001]
+    output(input().repeat(2)); //~ ERROR function `output::<std::string::String>` received tainted input [T0001]
+    output(format!("{}!", input())); //~ ERROR function `output::<std::string::String>` received tainted input [T0001]
+    output(input().parse::<u64>()); //~ ERROR function `output::<std::result::Result<u64, std::num::ParseIntError>>` received tainted input [T0001]
+    output(input().len().to_string()); //~ ERROR function `output::<std::string::String>` received tainted input [T0001]
+    output(PathBuf::from(input())); //~ ERROR function `output::<std::path::PathBuf>` received tainted input [T0001]
+
+    let tainted = input();
+    output(tainted.clone()); //~ ERROR function `output::<std::string::String>` received tainted input [T0001]
+    let mut replaced = String::new();
+    replaced.clone_from(&tainted);
+    output(replaced); //~ ERROR function `output::<std::string::String>` received tainted input [T0001]
+    output(Box::<str>::from(input()).clone()); //~ ERROR function `output::<std::boxed::Box<str>>` received tainted input [T0001]
+    output(vec![input()].clone()); //~ ERROR function `output::<std::vec::Vec<std::string::String>>` received tainted input [T0001]
+    output(input().as_str().to_owned()); //~ ERROR function `output::<std::string::String>` received tainted input [T0001]
+    output(input().as_bytes().to_owned()); //~ ERROR function `output::<std::vec::Vec<u8>>` received tainted input [T0001]
+    output(input().as_str().len()); //~ ERROR function `output::<usize>` received tainted input [T0001]
+    output(String::from_utf8_lossy(input().as_bytes()).into_owned()); //~ ERROR function `output::<std::string::String>` received tainted input [T0001]
+    field(&Request { body: input() });
+    element(&[input()]);
+    output("constant".to_owned());
+}
+
+fn field(request: &Request) {
+    output(request.body.clone()); //~ ERROR function `output::<std::string::String>` received tainted input [T0001]
+}
+
+fn element(values: &[String]) {
+    output(values[0].clone()); //~ ERROR function `output::<std::string::String>` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> String {
+    "tainted".to_owned()
+}
+
+#[taint::sink]
+fn output<T>(_: T) {}
@@ -0,0 +1,39 @@
+// Test that reads of thread-local statics carry the taint of their initializers, like other statics,
+// and that sizes and offsets computed from types are clean.
+
+#![feature(register_tool, thread_local, offset_of)]
+#![register_tool(taint)]
+
+use std::mem;
+
+#[thread_local]
+static SECRET: i32 = input();
+#[thread_local]
+static PUBLIC: i32 = 4;
+
+struct Header {
+    _len: i32,
+    body: i32,
+}
+
+fn main() {
+    output(SECRET); //~ ERROR function `output` received tainted input [T0001]
+    output(PUBLIC);
+
+    let secret = &SECRET;
+    output(*secret); //~ ERROR function `output` received tainted input [T0001]
+
+    let offset = mem::offset_of!(Header, body);
+    output_usize(offset);
+}
+
+#[taint::source]
+const fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
+
+#[taint::sink]
+fn output_usize(_: usize) {}
@@ -0,0 +1,53 @@
+// Test that calls to default methods of traits analyze the default body,
+// resolving the calls in it for the type the method is called on.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+trait Render {
+    fn value(&self) -> i32;
+
+    fn render(&self) {
+        output(self.value()); //~ ERROR function `output` received tainted input [T0001]
+    }
+
+    fn pass(&self, i: i32) -> i32 {
+        i
+    }
+}
+
+struct Tainted;
+
+impl Render for Tainted {
+    fn value(&self) -> i32 {
+        input()
+    }
+}
+
+struct Clean;
+
+impl Render for Clean {
+    fn value(&self) -> i32 {
+        1
+    }
+}
+
+fn generic<T: Render>(t: &T) {
+    t.render();
+}
+
+fn main() {
+    Clean.render();
+    generic(&Clean);
+    generic(&Tainted);
+
+    output(Clean.pass(input())); //~ ERROR function `output` received tainted input [T0001]
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,79 @@
+// Test that with `--taint-lint-unzeroized-secrets`, a secret created in a function and dropped there
+// is reported, unless it was zeroized, moved out, or held in a type which zeroizes itself when dropped.
+// compile-flags: --taint-lint-unzeroized-secrets
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+mod zeroize {
+    pub trait Zeroize {
+        fn zeroize(&mut self);
+    }
+
+    impl Zeroize for String {
+        fn zeroize(&mut self) {
+            self.clear();
+        }
+    }
+
+    pub struct Zeroizing<T: Zeroize>(pub T);
+
+    impl<T: Zeroize> Drop for Zeroizing<T> {
+        fn drop(&mut self) {
+            self.0.zeroize();
+        }
+    }
+}
+
+use zeroize::{Zeroize, Zeroizing};
+
+fn main() {
+    dropped();
+    zeroized();
+    wrapped();
+    let _ = returned(); //~ WARN a value is dropped here holding a secret labeled `secret`, which was neither zeroized nor moved out [T0014]
+    given(api_key());
+    leaked();
+}
+
+fn dropped() {
+    let key = api_key();
+    sign(&key);
+} //~ WARN `key` is dropped here holding a secret labeled `secret`, which was neither zeroized nor moved out [T0014]
+
+fn zeroized() {
+    let mut key = api_key();
+    sign(&key);
+    key.zeroize();
+}
+
+fn wrapped() {
+    let key = Zeroizing(api_key());
+    sign(&key.0);
+}
+
+fn returned() -> String {
+    let key = api_key();
+    sign(&key);
+    key
+}
+
+fn given(key: String) {
+    sign(&key);
+}
+
+// Moved into the sink, which reports the leak rather than the drop.
+fn leaked() {
+    let key = api_key();
+    log(key); //~ ERROR function `log` received tainted input labeled `secret` [T0001]
+}
+
+fn sign(_: &str) {}
+
+#[taint::sink]
+fn log(_: String) {}
+
+#[taint::source(label = "secret")]
+fn api_key() -> String {
+    String::from("key")
+}
@@ -0,0 +1,657 @@
+//! Tests for what findings are reported with: the context they were found in, and their slice.
+
+use std::{
+    env, fs,
+    io::{Read, Write},
+    net::TcpListener,
+    path::Path,
+    process::Command,
+};
+
+use taint::{fuzz::Targets, report::Report};
+
+mod common;
+
+#[test]
+fn findings_tell_the_calls_they_were_reached_through() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "findings_tell_the_calls_they_were_reached_through",
+    );
+    let path = scratch.join("context.json");
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg(format!("--taint-findings={}", path.display()))
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/findings/program.rs")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    assert!(stderr.contains("note: when `forward(_, value)` is called with tainted `value`"));
+    assert!(stderr.contains("note: when `process(data, len)` is called with tainted `data`"));
+
+    let report = Report::read(&path).unwrap();
+    assert_eq!(report.findings.len(), 1);
+    assert_eq!(
+        report.findings[0].context,
+        [
+            "when `forward(_, value)` is called with tainted `value` (from caller at tests/findings/program.rs:10)",
+            "when `process(data, len)` is called with tainted `data` (from caller at tests/findings/program.rs:6)",
+        ]
+    );
+}
+
+#[test]
+fn findings_in_a_helper_note_every_context_it_was_found_in() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "findings_in_a_helper_note_every_context_it_was_found_in",
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/findings/contexts.rs")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches("error[T0001]").count(), 1);
+    assert!(stderr.contains(
+        "note: when `store(key, value)` is called with tainted `key`\n  --> tests/findings/contexts.rs:5:5"
+    ));
+    assert!(stderr.contains(
+        "note: also found when `store(key, value)` is called with tainted `value`\n  --> tests/findings/contexts.rs:6:5"
+    ));
+    // `forward` calls `store` in the context `main` did, whose summary is reused rather than analyzed again.
+    assert!(stderr.contains(
+        "note: also found when `store(key, value)` is called with tainted `key`\n  --> tests/findings/contexts.rs:11:5"
+    ));
+}
+
+#[test]
+fn findings_name_the_variables_their_tainted_arguments_were_read_from() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "findings_name_the_variables_their_tainted_arguments_were_read_from",
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/findings/variables.rs")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr
+        .contains("note: argument `user_name` is tainted\n --> tests/findings/variables.rs:5:9"));
+    assert!(
+        stderr.contains("note: argument `length` is tainted\n --> tests/findings/variables.rs:6:9")
+    );
+    // `length + padding` is computed from several variables, so no single one is named.
+    assert_eq!(stderr.matches("note: argument").count(), 2);
+    assert!(!stderr.contains("`padding` is tainted"));
+}
+
+#[test]
+fn findings_note_the_calls_their_input_was_approximated_through() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "findings_note_the_calls_their_input_was_approximated_through",
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/findings/approximated.rs")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains(
+        "note: analysis of `steps` was approximated: a recursive call in it was cut off\n \
+         --> tests/findings/approximated.rs:7:16"
+    ));
+    assert!(stderr.contains(
+        "note: analysis of `abs` was approximated: it has no MIR, \
+         so the call follows `--taint-unknown-calls`\n --> tests/findings/approximated.rs:8:25"
+    ));
+    // `output(steps(n))` is not reported, and the other findings do not depend on the other call.
+    assert_eq!(stderr.matches("error[T0001]").count(), 2);
+    assert_eq!(stderr.matches("note: analysis of").count(), 2);
+}
+
+#[test]
+fn findings_note_the_call_chains_they_were_reached_through() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "findings_note_the_call_chains_they_were_reached_through",
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/findings/chains.rs")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let notes = stderr
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("= note: "))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        notes,
+        vec![
+            "reached through `login` -> `render`",
+            "reached through `logout` -> `render`",
+            "reached through `admin` -> `audit` -> `render`",
+            "and through 1 other call chain",
+        ]
+    );
+}
+
+#[test]
+fn choke_points_cover_every_finding() {
+    let scratch = common::scratch_dir("findings", "choke_points_cover_every_finding");
+    let path = scratch.join("choke.json");
+    Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg(format!("--taint-findings={}", path.display()))
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/findings/choke.rs")
+        .output()
+        .expect("taint runs");
+
+    let report = Report::read(&path).unwrap();
+    let fingerprints = |function: &str, sink: &str| {
+        report
+            .findings
+            .iter()
+            .filter(|finding| finding.function == function && finding.sink == sink)
+            .map(|finding| finding.fingerprint.clone())
+            .collect::<Vec<_>>()
+    };
+    let points = report
+        .choke_points
+        .iter()
+        .map(|point| {
+            (
+                point.function.as_str(),
+                point.argument,
+                point.findings.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    // `audit` is also called from `main` directly, so sanitizing in `handle` does not clear its finding.
+    assert_eq!(
+        points,
+        [
+            (
+                "handle",
+                Some(0),
+                [
+                    fingerprints("handle", "store"),
+                    fingerprints("handle", "log")
+                ]
+                .concat()
+            ),
+            ("audit", Some(1), fingerprints("audit", "log")),
+        ]
+    );
+}
+
+#[test]
+fn slices_show_the_statements_a_finding_flows_through() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "slices_show_the_statements_a_finding_flows_through",
+    );
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_taint"))
+            .args(args)
+            .args(["--out-dir", &scratch.display().to_string()])
+            .arg("tests/findings/slice.rs")
+            .output()
+            .expect("taint runs")
+    };
+    let path = scratch.join("slice.json");
+    run(&[&format!("--taint-findings={}", path.display())]);
+    let fingerprint = Report::read(Path::new(&path)).unwrap().findings[0]
+        .fingerprint
+        .clone();
+
+    let output = run(&[&format!("--taint-slice={}", fingerprint)]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "slice of finding {} in `main`:\n\
+             \x20  5 |     let data = input();\n\
+             \x20 12 |     buffer.push(data);\n\
+             \x20 14 |     let doubled = buffer[0] * 2;\n\
+             > 15 |     output(doubled);\n",
+            fingerprint
+        )
+    );
+}
+
+#[test]
+fn reproducers_are_reported_like_the_findings_they_reduce() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "reproducers_are_reported_like_the_findings_they_reduce",
+    );
+    let run = |args: &[&str], file: &Path| {
+        let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+            .args(args)
+            .args(["--out-dir", &scratch.display().to_string()])
+            .arg(file)
+            .output()
+            .expect("taint runs");
+        String::from_utf8(output.stderr).unwrap()
+    };
+    let dir = scratch.join("reproducers");
+    let stderr = run(
+        &[&format!("--taint-reproducers={}", dir.display())],
+        Path::new("tests/findings/reproducer.rs"),
+    );
+    let error = "error[T0001]: function `execute` received tainted input labeled `sqli`";
+    assert!(stderr.contains(error), "{}", stderr);
+
+    let reproducers = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(reproducers.len(), 1);
+    let program = fs::read_to_string(&reproducers[0]).unwrap();
+    assert!(program.contains(
+        "fn main() {\n\
+         \x20   let tainted = read_param();\n\
+         \x20   let tainted = trim(tainted);\n\
+         \x20   let tainted = to_owned(tainted);\n\
+         \x20   let tainted = format(tainted);\n\
+         \x20   execute(tainted);\n\
+         }\n"
+    ));
+    assert!(program.contains("//! > 9 |     execute(&query);"));
+    assert!(run(&[], &reproducers[0]).contains(error));
+}
+
+#[test]
+fn fuzz_targets_are_written_instead_of_errors() {
+    let scratch = common::scratch_dir("findings", "fuzz_targets_are_written_instead_of_errors");
+    let path = scratch.join("fuzz.json");
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg(format!("--taint-fuzz-targets={}", path.display()))
+        .arg("--taint-sink-packs=allocation")
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/findings/fuzz.rs")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "{}", stderr);
+    assert!(!stderr.contains("error"));
+
+    let targets = Targets::read(&path).unwrap().targets;
+    let sites = targets
+        .iter()
+        .map(|target| {
+            (
+                target.sink.as_str(),
+                target.arguments.clone(),
+                target.line,
+                target.column,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sites,
+        [
+            ("execute", vec![1], 6, 5),
+            ("std::vec::Vec::<T>::with_capacity", vec![0], 7, 13),
+            ("leak", vec![], 20, 1),
+        ]
+    );
+}
+
+#[test]
+fn findings_in_dependencies_are_reported_where_the_crate_called_into_them() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "findings_in_dependencies_are_reported_where_the_crate_called_into_them",
+    );
+    let run = |flags: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+            .args(flags)
+            .args(["--out-dir", &scratch.display().to_string()])
+            .arg("tests/findings/dependencies.rs")
+            .output()
+            .expect("taint runs");
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    let stderr = run(&[]);
+    assert!(stderr.contains(
+        "error[T0001]: function `output` received tainted input\n \
+         --> tests/findings/dependencies.rs:6:20"
+    ));
+
+    // The sink is called by `Option::map`, in the source of `core`.
+    let stderr = run(&["--taint-report-in-deps"]);
+    let location = stderr
+        .lines()
+        .find(|line| line.trim_start().starts_with("-->"))
+        .unwrap();
+    assert!(location.contains("core/src/option.rs"), "{}", location);
+    assert!(stderr.contains("note: when `map(self, f)` is called with tainted `self`"));
+    assert!(stderr.contains("--> tests/findings/dependencies.rs:6:20"));
+}
+
+#[test]
+fn runs_kept_in_the_findings_database_can_be_listed_and_compared() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "runs_kept_in_the_findings_database_can_be_listed_and_compared",
+    );
+    let db = scratch.join("db.jsonl");
+    let _ = std::fs::remove_file(&db);
+    for program in ["tests/findings/program.rs", "tests/findings/chains.rs"] {
+        Command::new(env!("CARGO_BIN_EXE_taint"))
+            .arg(format!("--taint-findings-db={}", db.display()))
+            .args(["--out-dir", &scratch.display().to_string()])
+            .arg(program)
+            .output()
+            .expect("taint runs");
+    }
+    let query = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+            .arg("findings")
+            .arg(format!("--db={}", db.display()))
+            .args(args)
+            .output()
+            .expect("taint runs");
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let listed = query(&["list", "1"]);
+    assert!(listed.starts_with("run 1 of `program`: 1 finding\n"));
+    let fingerprint = &listed.lines().nth(1).unwrap()[..8];
+    let shown = query(&["show", fingerprint]);
+    assert!(shown.contains("  at tests/findings/program.rs:14:5\n"));
+    assert!(shown.contains("  in `forward`, reaching `output` with label `taint`\n"));
+    assert!(shown.contains("  with confidence 100 of 100\n"));
+
+    let diff = query(&["diff", "1", "2"]);
+    assert!(diff.starts_with("run 1 to run 2: 1 new, 1 fixed, 0 unchanged\n"));
+    assert!(diff.contains("+ ") && diff.contains("tests/findings/chains.rs:26:5"));
+    assert!(diff.contains("- ") && diff.contains("tests/findings/program.rs:14:5"));
+}
+
+#[test]
+fn changed_only_analyzes_the_entry_points_reaching_changes_and_keeps_the_baseline_for_the_rest() {
+    let scratch = common::scratch_dir("findings", "changed_only_analyzes_the_entry_points_reaching_changes_and_keeps_the_baseline_for_the_rest");
+    let dir = scratch.join("changed-only");
+    fs::create_dir_all(&dir).unwrap();
+    let (baseline, merged, list) = (
+        dir.join("baseline.json"),
+        dir.join("merged.json"),
+        dir.join("changed.txt"),
+    );
+    let run = |args: &[String]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+            .args(args)
+            .args([
+                "--crate-type",
+                "lib",
+                "--out-dir",
+                &dir.display().to_string(),
+            ])
+            .arg("tests/findings/changed/lib.rs")
+            .output()
+            .expect("taint runs");
+        String::from_utf8(output.stderr).unwrap()
+    };
+    run(&[format!("--taint-findings={}", baseline.display())]);
+    assert_eq!(Report::read(&baseline).unwrap().findings.len(), 2);
+
+    fs::write(&list, "tests/findings/changed/render.rs\n").unwrap();
+    let stderr = run(&[
+        format!("--taint-changed-only={}", list.display()),
+        format!("--taint-baseline={}", baseline.display()),
+        format!("--taint-findings={}", merged.display()),
+    ]);
+    // Only `show` reaches `render::page`, and the finding of `log` comes from the baseline.
+    assert_eq!(stderr.matches("error[T0001]").count(), 1);
+    assert!(stderr.contains("--> tests/findings/changed/render.rs:2:5"));
+    let functions = Report::read(&merged)
+        .unwrap()
+        .findings
+        .into_iter()
+        .map(|finding| finding.function)
+        .collect::<Vec<_>>();
+    assert_eq!(functions, ["render::page", "log"]);
+}
+
+#[test]
+fn changed_only_reads_the_changes_from_git() {
+    let scratch = common::scratch_dir("findings", "changed_only_reads_the_changes_from_git");
+    let repo = scratch.join("changed-git");
+    fs::create_dir_all(&repo).unwrap();
+    for file in ["lib.rs", "render.rs"] {
+        fs::copy(
+            Path::new("tests/findings/changed").join(file),
+            repo.join(file),
+        )
+        .unwrap();
+    }
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args([
+                "-c",
+                "user.name=taint",
+                "-c",
+                "user.email=taint@example.com",
+            ])
+            .args(args)
+            .current_dir(&repo)
+            .status()
+            .expect("git runs");
+        assert!(status.success());
+    };
+    git(&["init", "-q"]);
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "baseline"]);
+
+    let run = || {
+        let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+            .args(["--taint-changed-only", "--crate-type", "lib", "lib.rs"])
+            .args(["--out-dir", &scratch.display().to_string()])
+            .current_dir(&repo)
+            .output()
+            .expect("taint runs");
+        String::from_utf8(output.stderr).unwrap()
+    };
+    assert_eq!(run().matches("error[T0001]").count(), 0);
+
+    let lib = fs::read_to_string(repo.join("lib.rs")).unwrap();
+    fs::write(
+        repo.join("lib.rs"),
+        lib.replace("output(text);", "output(&input(text));"),
+    )
+    .unwrap();
+    let stderr = run();
+    assert_eq!(stderr.matches("error[T0001]").count(), 1);
+    assert!(stderr.contains("--> lib.rs:15:5"));
+}
+
+#[test]
+fn findings_are_posted_to_the_report_url() {
+    let scratch = common::scratch_dir("findings", "findings_are_posted_to_the_report_url");
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = vec![];
+        let mut buffer = [0; 4096];
+        while !String::from_utf8_lossy(&request).ends_with('}') {
+            let read = stream.read(&mut buffer).unwrap();
+            request.extend_from_slice(&buffer[..read]);
+        }
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+            .unwrap();
+        String::from_utf8(request).unwrap()
+    });
+
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg(format!(
+            "--taint-report-url=http://127.0.0.1:{}/ingest",
+            port
+        ))
+        .env("TAINT_REPORT_AUTHORIZATION", "Bearer ci-token")
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/findings/program.rs")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches("error[T0001]").count(), 1);
+    assert!(!stderr.contains("invalid response"));
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /ingest HTTP/1.1\r\n"));
+    assert!(request.contains("\r\nAuthorization: Bearer ci-token\r\n"));
+    let (_, body) = request.split_once("\r\n\r\n").unwrap();
+    assert!(body.contains(r#""crate":"program""#));
+    assert_eq!(
+        Report::from_json(body).unwrap().findings[0].function,
+        "forward"
+    );
+}
+
+#[test]
+fn coverage_report_counts_sources_findings_and_sanitizers_by_label() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "coverage_report_counts_sources_findings_and_sanitizers_by_label",
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg("--taint-coverage-report")
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/findings/coverage.rs")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches("error[T0001]").count(), 3);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "coverage by label:\n  \
+         `decoded`: 0 source calls, 1 finding, never sanitized\n  \
+         `header`: 1 source call, 0 findings, sanitized by `decode` at 1 call\n  \
+         `query`: 2 source calls, 1 finding, sanitized by `escape` at 1 call\n  \
+         `taint`: 3 source calls, 1 finding, sanitized by `validate` at 2 calls\n"
+    );
+}
+
+#[test]
+fn findings_of_models_inside_dependencies_note_the_model_at_the_call_site() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "findings_of_models_inside_dependencies_note_the_model_at_the_call_site",
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/findings/models.rs")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains(
+        "error[T0001]: a secret wrapped in `Sensitive` is formatted with `Display`, which exposes it\n  \
+         --> tests/findings/models.rs:12:63"
+    ));
+    assert!(stderr.contains(
+        "note: found inside `<T as std::string::ToString>::to_string`, \
+         by the model of `Display` for `Sensitive`"
+    ));
+    // The variable formatted is one of `std`.
+    assert!(!stderr.contains("note: argument"));
+}
+
+#[test]
+fn findings_from_the_same_call_to_a_source_form_a_family() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "findings_from_the_same_call_to_a_source_form_a_family",
+    );
+    let path = scratch.join("families.json");
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg(format!("--taint-findings={}", path.display()))
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/findings/families.rs")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let note = "note: its tainted input comes from this source, like 2 other findings\n";
+    assert_eq!(stderr.matches(note).count(), 3);
+    assert_eq!(
+        stderr
+            .matches("--> tests/findings/families.rs:5:17")
+            .count(),
+        3
+    );
+
+    let report = Report::read(&path).unwrap();
+    assert_eq!(report.families.len(), 2);
+    let first = &report.families[0];
+    assert_eq!((first.source.as_str(), first.start.line), ("read_input", 5));
+    let lines = |family: &taint::report::Family| {
+        let findings = report.findings.iter();
+        let members = findings.filter(|f| f.family.as_ref() == Some(&family.id));
+        let lines = members.map(|f| f.start.line).collect::<Vec<_>>();
+        assert_eq!(
+            lines.len(),
+            family.findings.len(),
+            "every finding of the family carries its id"
+        );
+        lines
+    };
+    assert_eq!(lines(first), [7, 13, 14]);
+    assert_eq!(lines(&report.families[1]), [9]);
+    assert_ne!(first.id, report.families[1].id);
+}
+
+#[test]
+fn findings_are_less_confident_for_what_the_analysis_assumed() {
+    let scratch = common::scratch_dir(
+        "findings",
+        "findings_are_less_confident_for_what_the_analysis_assumed",
+    );
+    let path = scratch.join("confidence.json");
+    let run = |fail_level: &str| {
+        Command::new(env!("CARGO_BIN_EXE_taint"))
+            .arg(format!("--taint-findings={}", path.display()))
+            .arg(format!("--taint-fail-level={}", fail_level))
+            .args(["--out-dir", &scratch.display().to_string()])
+            .arg("tests/findings/confidence.rs")
+            .output()
+            .expect("taint runs")
+    };
+
+    let output = run("all");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches("error[T0001]").count(), 3);
+    let report = Report::read(&path).unwrap();
+    let scores = report
+        .findings
+        .iter()
+        .map(|finding| (finding.start.line, finding.confidence.score))
+        .collect::<Vec<_>>();
+    assert_eq!(scores, [(6, 100), (12, 70), (13, 75)]);
+    assert_eq!(
+        report.findings[1].confidence.doubts,
+        ["the call to `read_input` its input comes from does not run on every path to the sink"]
+    );
+    assert_eq!(
+        report.findings[2].confidence.doubts,
+        ["the analysis of `repeat` was approximated"]
+    );
+
+    // The finding whose input is tainted on every path without approximations still fails the build.
+    let output = run("high-confidence");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    assert_eq!(stderr.matches("error[T0001]").count(), 1);
+    assert_eq!(stderr.matches("warning[T0001]").count(), 2);
+    assert!(stderr.contains(
+        "note: its confidence is 75 of 100, as the analysis of `repeat` was approximated, \
+         so `--taint-fail-level=high-confidence` makes it a warning"
+    ));
+}
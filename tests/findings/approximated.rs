@@ -0,0 +1,30 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let n = input();
+    output(steps(n));
+    output(n + steps(n));
+    output(n + unsafe { abs(-1) });
+}
+
+/// The steps `n` takes to reach 1 in the Collatz sequence.
+fn steps(n: i32) -> i32 {
+    if n <= 1 {
+        return 0;
+    }
+    let next = if n % 2 == 0 { n / 2 } else { 3 * n + 1 };
+    1 + steps(next)
+}
+
+extern "C" {
+    fn abs(n: i32) -> i32;
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,35 @@
+#![crate_type = "lib"]
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+pub fn login() {
+    render();
+}
+
+pub fn logout() {
+    render();
+}
+
+pub fn admin() {
+    audit();
+}
+
+pub fn status() {
+    render();
+}
+
+fn audit() {
+    render();
+}
+
+fn render() {
+    output(input());
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,24 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+mod render;
+
+pub fn show(text: &str) {
+    render::page(&input(text));
+}
+
+pub fn log(text: &str) {
+    output(&input(text));
+}
+
+pub fn clean(text: &str) {
+    output(text);
+}
+
+#[taint::source]
+fn input(text: &str) -> String {
+    text.to_owned()
+}
+
+#[taint::sink]
+fn output(_: &str) {}
@@ -0,0 +1,3 @@
+pub fn page(body: &str) {
+    super::output(body);
+}
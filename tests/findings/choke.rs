@@ -0,0 +1,29 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let request = input();
+    handle(request);
+    audit(1, input());
+}
+
+fn handle(request: usize) {
+    store(request);
+    log(request);
+    audit(2, request);
+}
+
+fn audit(_: usize, entry: usize) {
+    log(entry);
+}
+
+#[taint::source]
+fn input() -> usize {
+    4
+}
+
+#[taint::sink]
+fn store(_: usize) {}
+
+#[taint::sink]
+fn log(_: usize) {}
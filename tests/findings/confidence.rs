@@ -0,0 +1,30 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let input = read_input();
+    execute(&input);
+    let query = if std::env::args().count() > 1 {
+        read_input()
+    } else {
+        String::from("SELECT 1")
+    };
+    execute(&query);
+    execute(&repeat(read_input(), 3));
+}
+
+fn repeat(query: String, times: usize) -> String {
+    if times == 0 {
+        query
+    } else {
+        repeat(query, times - 1)
+    }
+}
+
+#[taint::source]
+fn read_input() -> String {
+    String::from("1 OR 1=1")
+}
+
+#[taint::sink]
+fn execute(_: &str) {}
@@ -0,0 +1,24 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    store(input(), 1);
+    store(2, input());
+    forward(input());
+}
+
+fn forward(value: i32) {
+    store(value, 3);
+}
+
+fn store(key: i32, value: i32) {
+    write(key + value);
+}
+
+#[taint::source]
+fn input() -> i32 {
+    0
+}
+
+#[taint::sink]
+fn write(_: i32) {}
@@ -0,0 +1,44 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    output(escape(query()));
+    output(query());
+    output(decode(header()));
+    let _ = validate(input());
+    let _ = validate(input());
+    output(input());
+}
+
+#[taint::source(label = "query")]
+fn query() -> String {
+    String::new()
+}
+
+#[taint::source(label = "header")]
+fn header() -> String {
+    String::new()
+}
+
+#[taint::source]
+fn input() -> String {
+    String::new()
+}
+
+#[taint::sanitizer]
+fn escape(s: String) -> String {
+    s
+}
+
+#[taint::sanitizer]
+fn validate(s: String) -> String {
+    s
+}
+
+#[taint::sanitizer(from = "header", to = "decoded")]
+fn decode(s: String) -> String {
+    s
+}
+
+#[taint::sink]
+fn output(_: String) {}
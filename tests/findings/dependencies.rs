@@ -0,0 +1,15 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let query = Some(input());
+    query.as_ref().map(output);
+}
+
+#[taint::source]
+fn input() -> String {
+    String::from("<script>")
+}
+
+#[taint::sink]
+fn output(_: &String) {}
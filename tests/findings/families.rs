@@ -0,0 +1,26 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let input = read_input();
+    handle(&input);
+    log(&input);
+    let other = read_input();
+    run(&other);
+}
+
+fn handle(input: &str) {
+    run(input);
+    log(input);
+}
+
+#[taint::source]
+fn read_input() -> String {
+    String::from("1 OR 1=1")
+}
+
+#[taint::sink]
+fn run(_: &str) {}
+
+#[taint::sink]
+fn log(_: &str) {}
@@ -0,0 +1,22 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let data = input();
+    execute(1, data, 2);
+    let _ = Vec::<u8>::with_capacity(data);
+    let _ = leak();
+}
+
+#[taint::source]
+fn input() -> usize {
+    4
+}
+
+#[taint::sink]
+fn execute(_: usize, _: usize, _: usize) {}
+
+#[taint::sink(return)]
+fn leak() -> usize {
+    input()
+}
@@ -0,0 +1,34 @@
+// A secret formatted by `ToString::to_string`, which `Iterator::map` calls inside `std`,
+// is reported where the crate calls into it.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use taint_runtime::Sensitive;
+
+fn main() {
+    let key = Sensitive::new(String::from("key"));
+    let keys = vec![key];
+    let _: Vec<String> = keys.iter().map(ToString::to_string).collect();
+}
+
+/// Stands in for the `taint-runtime` crate.
+mod taint_runtime {
+    use std::fmt;
+
+    pub struct Sensitive<T> {
+        pub secret: T,
+    }
+
+    impl<T> Sensitive<T> {
+        pub fn new(secret: T) -> Self {
+            Sensitive { secret }
+        }
+    }
+
+    impl<T> fmt::Display for Sensitive<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("[redacted]")
+        }
+    }
+}
@@ -0,0 +1,23 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let data = input();
+    process(data, 1);
+}
+
+fn process(data: i32, len: i32) {
+    forward(len, data);
+}
+
+fn forward(_: i32, value: i32) {
+    output(value);
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,18 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let name = read_param();
+    let trimmed = name.trim().to_owned();
+    let limit = 10;
+    let query = format!("SELECT * FROM users WHERE name = '{}' LIMIT {}", trimmed, limit);
+    execute(&query);
+}
+
+#[taint::source(label = "sqli")]
+fn read_param() -> String {
+    String::new()
+}
+
+#[taint::sink(label = "sqli")]
+fn execute(_: &str) {}
@@ -0,0 +1,24 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let data = input();
+    let unrelated = 3;
+    let mut total = 0;
+    for i in 0..unrelated {
+        total += i;
+    }
+    let mut buffer = Vec::new();
+    buffer.push(data);
+    println!("{}", total);
+    let doubled = buffer[0] * 2;
+    output(doubled);
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,22 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let user_name = input();
+    let length = user_name.len();
+    let padding = 4;
+    render(&user_name, padding);
+    allocate(length);
+    allocate(length + padding);
+}
+
+#[taint::source]
+fn input() -> String {
+    String::new()
+}
+
+#[taint::sink]
+fn render(_: &String, _: usize) {}
+
+#[taint::sink]
+fn allocate(_: usize) {}
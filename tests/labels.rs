@@ -0,0 +1,27 @@
+//! Tests for labels declared in `taint.json`.
+
+use std::process::Command;
+
+#[test]
+fn sinks_check_the_labels_their_label_subsumes() {
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg("program.rs")
+        .current_dir("tests/labels")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let errors = stderr
+        .lines()
+        .filter(|line| line.starts_with("error"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        errors,
+        vec![
+            "error[T0001]: function `store` received tainted input labeled `header`",
+            "error[T0001]: function `log` received tainted input labeled `secret`",
+            "error[T0001]: function `log` received tainted input labeled `header`",
+            "error: aborting due to 3 previous errors",
+        ]
+    );
+}
@@ -0,0 +1,25 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    store(header());
+    store(secret());
+    log(secret());
+    log(header());
+}
+
+#[taint::source(label = "header")]
+fn header() -> i32 {
+    1
+}
+
+#[taint::source(label = "secret")]
+fn secret() -> i32 {
+    2
+}
+
+#[taint::sink(label = "user-input")]
+fn store(_: i32) {}
+
+#[taint::sink]
+fn log(_: i32) {}
@@ -0,0 +1,43 @@
+// Test that spawning a task does not taint it unless its captures or its own body are tainted.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let data = 5;
+    tokio::spawn(async move { output(data) });
+
+    let handle = tokio::spawn(async { 10 });
+    tokio::spawn(async move { output(handle.await) });
+}
+
+#[taint::sink]
+fn output(_: i32) {}
+
+/// A stand-in for the parts of `tokio` that the analysis models.
+mod tokio {
+    use std::future::Future;
+    use std::marker::PhantomData;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    pub struct JoinHandle<T> {
+        _id: usize,
+        _output: PhantomData<T>,
+    }
+
+    impl<T> Future for JoinHandle<T> {
+        type Output = T;
+
+        fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<T> {
+            Poll::Pending
+        }
+    }
+
+    pub fn spawn<F: Future>(_: F) -> JoinHandle<F::Output> {
+        JoinHandle {
+            _id: 0,
+            _output: PhantomData,
+        }
+    }
+}
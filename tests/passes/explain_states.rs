@@ -0,0 +1,36 @@
+// Test that `--taint-explain` prints the statements of a function which change the labels of a local,
+// next to the labels they change, for each context it is analyzed in.
+// compile-flags: --taint-explain=handle
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    handle(read_input(), 1);
+    handle(String::new(), read_count());
+}
+
+fn handle(input: String, retries: usize) {
+    let escaped = escape(&input);
+    let attempts = retries + 1;
+    run(&escaped);
+    let _ = attempts;
+}
+
+#[taint::source]
+fn read_input() -> String {
+    String::from("1 OR 1=1")
+}
+
+#[taint::source]
+fn read_count() -> usize {
+    3
+}
+
+#[taint::sanitizer]
+fn escape(input: &str) -> String {
+    input.replace('\'', "''")
+}
+
+#[taint::sink]
+fn run(_: &str) {}
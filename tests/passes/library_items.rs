@@ -0,0 +1,15 @@
+// Test that public items which are not functions are skipped when looking for entry points.
+// compile-flags: --taint-library
+
+#![crate_type = "lib"]
+
+pub const LIMIT: usize = 16;
+pub static GREETING: &str = "hello";
+
+pub struct Config {
+    pub limit: usize,
+}
+
+pub fn limit(config: &Config) -> usize {
+    config.limit.min(LIMIT)
+}
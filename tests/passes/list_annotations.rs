@@ -0,0 +1,62 @@
+// Test that `--taint-list-annotations` lists every annotated function and field, and where it was annotated.
+// compile-flags: --taint-list-annotations --taint-sink-macros=println --taint-add-sink=std::process::exit
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let _ = Request { body: 6 }.body;
+    render(escape_html(decode(input())));
+    log::write("done");
+    migrate();
+    let _ = taint_runtime::barrier(input());
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sanitizer(after = "decode")]
+fn escape_html(i: i32) -> i32 {
+    i
+}
+
+#[taint::sink(requires = "escape_html")]
+fn render(_: i32) {}
+
+mod log {
+    #[taint::sink]
+    pub fn write(_: &str) {}
+}
+
+struct Request {
+    #[taint::source]
+    body: i32,
+}
+
+#[taint::sanitizer(from = "encoded", to = "decoded")]
+fn decode(i: i32) -> i32 {
+    i
+}
+
+#[taint::sink(gated)]
+fn execute(_: i32) {}
+
+fn migrate() {
+    #[taint::acknowledged(reason = "the migration is run by hand")]
+    execute(input());
+}
+
+#[allow(dead_code)]
+fn backfill() {
+    // taint-ignore: T0001, T0007 reason="the backfill only runs on fixtures"
+    execute(input());
+}
+
+/// Stands in for the `taint-runtime` crate.
+mod taint_runtime {
+    pub fn barrier<T>(value: T) -> T {
+        value
+    }
+}
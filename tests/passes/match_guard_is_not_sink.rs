@@ -0,0 +1,24 @@
+// This test uses unsanitised input in a match guard, which decides the arm that runs.
+// Like an if condition, a guard is not a taint sink, and the bindings of the arm do not take its taint.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let limit = input();
+    match Some(1) {
+        Some(v) if v > limit => output(v),
+        Some(w) => output(w),
+        None => {}
+    }
+}
+
+#[taint::source]
+fn input() -> i32 {
+    3
+}
+
+#[taint::sink]
+fn output<T>(_: T) {
+    ()
+}
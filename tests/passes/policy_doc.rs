@@ -0,0 +1,73 @@
+// Test that `--taint-emit=policy-doc` documents every role, label and built-in rule, and where each was declared.
+// compile-flags: --taint-emit=policy-doc --taint-sink-macros=println --taint-sink-packs=allocation
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let _ = Request { body: 6 }.body;
+    render(escape_html(decode(input())));
+    log::write(header());
+    let _ = session();
+    migrate();
+    let _ = taint_runtime::barrier(input());
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::source(label = "header")]
+fn header() -> i32 {
+    5
+}
+
+#[taint::sanitizer(after = "decode")]
+fn escape_html(i: i32) -> i32 {
+    i
+}
+
+#[taint::sink(requires = "escape_html")]
+fn render(_: i32) {}
+
+#[taint::sink(return)]
+fn session() -> i32 {
+    0
+}
+
+mod log {
+    #[taint::sink(label = "header")]
+    pub fn write(_: i32) {}
+}
+
+struct Request {
+    #[taint::source]
+    body: i32,
+}
+
+#[taint::sanitizer(from = "encoded", to = "decoded")]
+fn decode(i: i32) -> i32 {
+    i
+}
+
+#[taint::sink(gated)]
+fn execute(_: i32) {}
+
+fn migrate() {
+    #[taint::acknowledged(reason = "the migration is run by hand")]
+    execute(input());
+}
+
+#[allow(dead_code)]
+fn backfill() {
+    // taint-ignore: T0001, T0007 reason="the backfill only runs on fixtures"
+    execute(input());
+}
+
+/// Stands in for the `taint-runtime` crate.
+mod taint_runtime {
+    pub fn barrier<T>(value: T) -> T {
+        value
+    }
+}
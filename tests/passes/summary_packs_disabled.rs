@@ -0,0 +1,17 @@
+// compile-flags: --taint-summary-packs=no
+// Test that the bundled summaries can be turned off, losing the flows through functions without MIR.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    output(input().to_uppercase());
+}
+
+#[taint::source]
+fn input() -> String {
+    "tainted".to_owned()
+}
+
+#[taint::sink]
+fn output<T>(_: T) {}
@@ -0,0 +1,33 @@
+// Test that entry points share function summaries with each other and with their callees,
+// and that `--taint-summary-report` lists them.
+// compile-flags: --taint-summary-report
+
+#![crate_type = "lib"]
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+pub fn first() -> i32 {
+    helper(1)
+}
+
+pub fn second() -> i32 {
+    helper(2)
+}
+
+pub fn third() -> i32 {
+    helper(input())
+}
+
+// `first` was already analyzed as an entry point, so its summary is reused here.
+pub fn fourth() -> i32 {
+    first()
+}
+
+fn helper(i: i32) -> i32 {
+    i + 1
+}
+
+#[taint::source]
+fn input() -> i32 {
+    15
+}
@@ -0,0 +1,28 @@
+// Test that functions with more arguments than `--taint-max-arity` are analyzed with the labels
+// of all of their arguments on each, in one context however their arguments are tainted,
+// and that `--taint-summary-report` lists them.
+// compile-flags: --taint-summary-report --taint-max-arity=2
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let a = wide(input(), 0, 0);
+    let b = wide(0, input(), 0);
+    let c = narrow(input(), 0);
+    let d = narrow(0, input());
+    let _ = a + b + c + d;
+}
+
+fn wide(a: i32, b: i32, c: i32) -> i32 {
+    a + b + c
+}
+
+fn narrow(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
@@ -0,0 +1,33 @@
+// Test that `--taint-summary-report` lists the functions with the largest points-to sets,
+// and which of them have a local pointing anywhere past `--taint-max-points-to`.
+// compile-flags: --taint-summary-report --taint-max-points-to=2
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let n = input();
+    let _ = two(n);
+    let _ = three(n);
+}
+
+fn two(n: i32) -> i32 {
+    let (a, b) = (n + 1, n + 2);
+    let r = if n > 0 { &a } else { &b };
+    *r
+}
+
+fn three(n: i32) -> i32 {
+    let (a, b, c) = (n + 1, n + 2, n + 3);
+    let r = match n {
+        0 => &a,
+        1 => &b,
+        _ => &c,
+    };
+    *r
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
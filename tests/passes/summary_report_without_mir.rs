@@ -0,0 +1,24 @@
+// Test that `--taint-summary-report` counts the calls to functions without MIR,
+// which follow the policy for unknown calls instead of being analyzed.
+// compile-flags: --taint-summary-report
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+extern "C" {
+    fn abs(i: i32) -> i32;
+}
+
+fn main() {
+    let a = unsafe { abs(input()) };
+    let b = unsafe { abs(a) };
+    output(b);
+}
+
+#[taint::source]
+fn input() -> i32 {
+    -4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,54 @@
+// compile-flags: --taint-mir=built
+// Test that zero-sized values, like typestate markers and `PhantomData`, carry no taint,
+// even when a source returns one: a builder holding one only holds the data it was given.
+// Optimized MIR already passes them around as constants, but built MIR moves them between locals.
+
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+use std::marker::PhantomData;
+
+struct Unauthenticated;
+struct Authenticated;
+
+/// Proof that the user logged in, which holds no data.
+struct Session;
+
+struct Request<S> {
+    url: String,
+    session: Option<Session>,
+    state: PhantomData<S>,
+}
+
+impl Request<Unauthenticated> {
+    fn new(url: String) -> Self {
+        Request {
+            url,
+            session: None,
+            state: PhantomData,
+        }
+    }
+
+    fn authenticate(self, session: Session) -> Request<Authenticated> {
+        Request {
+            url: self.url,
+            session: Some(session),
+            state: PhantomData,
+        }
+    }
+}
+
+fn main() {
+    let request = Request::new(String::from("https://example.com")).authenticate(login());
+    if request.session.is_some() {
+        send(&request.url);
+    }
+}
+
+#[taint::source]
+fn login() -> Session {
+    Session
+}
+
+#[taint::sink]
+fn send(_: &str) {}
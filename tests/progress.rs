@@ -0,0 +1,110 @@
+//! Tests for `--taint-progress=json`.
+
+use std::{fs, process::Command};
+
+mod common;
+
+#[test]
+fn events_are_emitted_for_every_entry_point() {
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg("--taint-progress=json")
+        .arg("tests/progress/library.rs")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let events = stderr
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        events,
+        vec![
+            r#"{"entries":2,"event":"started"}"#,
+            r#"{"entry":"leaky","event":"entry-started","index":0}"#,
+            r#"{"entry":"leaky","event":"entry-finished","findings":1,"index":0,"summaries":1}"#,
+            r#"{"entry":"clean","event":"entry-started","index":1}"#,
+            r#"{"entry":"clean","event":"entry-finished","findings":1,"index":1,"summaries":2}"#,
+            r#"{"event":"finished","findings":1,"summaries":2}"#,
+        ]
+    );
+}
+
+#[test]
+fn runs_resume_from_the_checkpoint_of_an_earlier_one() {
+    let scratch = common::scratch_dir(
+        "progress",
+        "runs_resume_from_the_checkpoint_of_an_earlier_one",
+    );
+    let dir = scratch.join("checkpoints");
+    let run = || {
+        let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+            .arg("--taint-progress=json")
+            .arg(format!("--taint-resume={}", dir.display()))
+            .args(["--out-dir", &scratch.display().to_string()])
+            .arg("tests/progress/resumed.rs")
+            .output()
+            .expect("taint runs");
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        stderr
+            .lines()
+            .filter(|line| {
+                line.starts_with("error[")
+                    || line.contains("resumed from its checkpoint")
+                    || line.starts_with(r#"{"event":"finished""#)
+            })
+            .map(str::to_owned)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        run(),
+        vec![
+            r#"{"event":"finished","findings":1,"summaries":6}"#,
+            "error[T0001]: function `output` received tainted input",
+        ]
+    );
+    let mut files = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    files.sort();
+    assert_eq!(files.len(), 2);
+    assert!(files[0].starts_with("resumed-") && files[0].ends_with(".contexts.json"));
+    assert!(files[1].starts_with("resumed-") && files[1].ends_with(".findings.json"));
+
+    // Both entry points were done, so neither is analyzed again, and the finding is the one of the checkpoint.
+    assert_eq!(
+        run(),
+        vec![
+            r#"{"event":"finished","findings":1,"summaries":0}"#,
+            "error[T0001]: function `output` received tainted input",
+            "  = note: found before the run resumed from its checkpoint, with --taint-resume",
+        ]
+    );
+
+    // Like a run killed before it was done with any entry point, but once it computed the summaries.
+    let contexts = dir.join(&files[0]);
+    let checkpoint = fs::read_to_string(&contexts).unwrap();
+    let entries = checkpoint.find(r#""entries":["#).unwrap();
+    let end = entries + checkpoint[entries..].find(']').unwrap();
+    fs::write(
+        &contexts,
+        format!(
+            "{}\"entries\":[{}",
+            &checkpoint[..entries],
+            &checkpoint[end..]
+        ),
+    )
+    .unwrap();
+
+    // `leaky` is analyzed again, since it reported the finding, which is only reported once, but `clean` and `scale`
+    // are not, so neither is `offset`.
+    assert_eq!(
+        run(),
+        vec![
+            r#"{"event":"finished","findings":1,"summaries":3}"#,
+            "error[T0001]: function `output` received tainted input",
+        ]
+    );
+}
@@ -0,0 +1,19 @@
+#![crate_type = "lib"]
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+pub fn leaky() {
+    output(input());
+}
+
+pub fn clean() {
+    output(1);
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,27 @@
+#![crate_type = "lib"]
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+pub fn leaky() {
+    output(scale(input()));
+}
+
+pub fn clean() {
+    output(scale(1));
+}
+
+fn scale(value: i32) -> i32 {
+    offset(value) * 2
+}
+
+fn offset(value: i32) -> i32 {
+    value + 1
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,30 @@
+//! Tests for propagation rules declared in `taint.json`.
+
+use std::process::Command;
+
+#[test]
+fn calls_follow_the_rules_instead_of_the_callee() {
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg("program.rs")
+        .current_dir("tests/propagation")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let lines = stderr
+        .lines()
+        .filter(|line| line.starts_with("error") || line.contains("-->"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        lines,
+        vec![
+            "error[T0001]: function `output` received tainted input",
+            " --> program.rs:5:5",
+            "error[T0001]: function `output` received tainted input",
+            " --> program.rs:9:5",
+            // The note pointing at `buffer`.
+            " --> program.rs:7:9",
+            "error: aborting due to 2 previous errors",
+        ]
+    );
+}
@@ -0,0 +1,31 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    output(concat(1, input()));
+
+    let mut buffer = 0;
+    store(&mut buffer, input());
+    output(buffer);
+
+    output(ignore(input()));
+}
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
+
+// The rules in `taint.json` describe these functions instead of their bodies.
+fn concat(_: i32, _: i32) -> i32 {
+    0
+}
+
+fn store(_: &mut i32, _: i32) {}
+
+fn ignore(_: i32) -> i32 {
+    0
+}
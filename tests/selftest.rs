@@ -0,0 +1,24 @@
+//! Tests for `taint selftest`, run on the programs in `tests/selftest`.
+
+use std::process::Command;
+
+#[test]
+fn programs_are_checked_against_their_directory() {
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg("selftest")
+        .arg("tests/selftest")
+        .output()
+        .expect("taint selftest runs");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout,
+        "program                          expected  findings  result\n\
+         tests/selftest/passes/clean.rs   none      0         ok\n\
+         tests/selftest/fails/clean.rs    some      0         FAILED\n\
+         tests/selftest/fails/leaky.rs    some      1         ok\n\
+         tests/selftest/fails/library.rs  some      1         ok\n\
+         selftest: 3 passed, 1 failed\n"
+    );
+}
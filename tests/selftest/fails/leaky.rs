@@ -0,0 +1,14 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    output(input());
+}
+
+#[taint::source]
+fn input() -> i32 {
+    4
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,13 @@
+// A library without sources only has findings when its callers are untrusted.
+// compile-flags: --taint-library
+
+#![crate_type = "lib"]
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+pub fn handle(request: i32) {
+    output(request);
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,9 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    output(4);
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,77 @@
+//! Tests for the source packs, which taint the arguments of functions exported by macros of other crates.
+
+use std::{env, path::PathBuf, process::Command};
+
+/// Builds `tests/source_packs/exporters.rs`, the stand-in for the exporting macros, in `name`.
+fn build_exporters(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    // Named like `rustc`, the driver builds the crate instead of stopping after the analysis.
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .args([
+            "rustc",
+            "tests/source_packs/exporters.rs",
+            "--edition",
+            "2018",
+        ])
+        .arg("--out-dir")
+        .arg(&dir)
+        .output()
+        .expect("taint runs");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    dir
+}
+
+/// The errors reported on `tests/source_packs/lib.rs` with `args`, sorted, building in `name`.
+fn errors(name: &str, args: &[&str]) -> Vec<String> {
+    let dir = build_exporters(name);
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg("tests/source_packs/lib.rs")
+        .args(["--edition", "2018", "--extern"])
+        .arg(format!(
+            "exporters={}",
+            dir.join("libexporters.so").display()
+        ))
+        .arg("--out-dir")
+        .arg(&dir)
+        .args(args)
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let mut lines = stderr.lines();
+    let mut errors = vec![];
+    while let Some(line) = lines.next() {
+        if line.starts_with("error[T0001]") {
+            let location = lines.next().unwrap().trim_start();
+            errors.push(format!("{} {}", line, location));
+        }
+    }
+    errors.sort();
+    errors
+}
+
+#[test]
+fn arguments_of_exported_functions_are_tainted() {
+    let errors = errors(
+        "taint-source-packs",
+        &["--taint-source-packs=wasm-bindgen,pyo3"],
+    );
+    let at = |location| {
+        format!(
+            "error[T0001]: function `render` received tainted input --> tests/source_packs/lib.rs:{}",
+            location
+        )
+    };
+    assert_eq!(errors, [at("14:5"), at("19:5"), at("27:9"), at("33:5")]);
+}
+
+#[test]
+fn exported_functions_are_not_sources_without_a_pack() {
+    let name = "taint-source-packs-unset";
+    assert_eq!(errors(name, &[]), Vec::<String>::new());
+    assert_eq!(errors(name, &["--taint-source-packs=pyo3"]).len(), 2);
+}
@@ -0,0 +1,41 @@
+// Stands in for the attribute macros of `wasm-bindgen` and `pyo3`, which emit the item they are put on
+// along with glue code of their own.
+
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+
+use proc_macro::{Ident, Span, TokenStream, TokenTree};
+
+/// The item, and a function standing in for the glue exporting it.
+fn export(item: TokenStream) -> TokenStream {
+    let mut tokens = item.clone().into_iter();
+    let name = tokens
+        .by_ref()
+        .find(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "fn" || ident.to_string() == "impl"))
+        .and_then(|_| tokens.next())
+        .map_or_else(|| "item".to_owned(), |name| name.to_string());
+    let glue = Ident::new(&format!("__generated_{}", name), Span::call_site());
+    let mut output = item;
+    output.extend(
+        format!("#[allow(dead_code, non_snake_case)] fn {}(value: String) {{ let _ = value; }}", glue)
+            .parse::<TokenStream>()
+            .unwrap(),
+    );
+    output
+}
+
+#[proc_macro_attribute]
+pub fn wasm_bindgen(_: TokenStream, item: TokenStream) -> TokenStream {
+    export(item)
+}
+
+#[proc_macro_attribute]
+pub fn pyfunction(_: TokenStream, item: TokenStream) -> TokenStream {
+    export(item)
+}
+
+#[proc_macro_attribute]
+pub fn pymethods(_: TokenStream, item: TokenStream) -> TokenStream {
+    export(item)
+}
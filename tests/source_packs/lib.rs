@@ -0,0 +1,41 @@
+// Functions exported with `#[wasm_bindgen]` and `#[pyfunction]`, and the methods of impls marked
+// `#[wasm_bindgen]` or `#[pymethods]`, whatever their visibility. The macros come from `exporters.rs`.
+
+#![crate_type = "lib"]
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+extern crate exporters;
+
+use exporters::{pyfunction, pymethods, wasm_bindgen};
+
+#[wasm_bindgen]
+pub fn greet(name: String) {
+    render(&name);
+}
+
+#[pyfunction]
+fn lookup(id: String) {
+    render(&id);
+}
+
+pub struct Counter;
+
+#[pymethods]
+impl Counter {
+    fn add(&self, amount: String) {
+        render(&amount);
+    }
+}
+
+#[exporters::wasm_bindgen]
+pub fn by_path(name: String) {
+    render(&name);
+}
+
+pub fn internal(name: String) {
+    render(&name);
+}
+
+#[taint::sink]
+fn render(_: &str) {}
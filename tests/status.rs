@@ -0,0 +1,59 @@
+//! Tests for the status line of a run, `--taint-junit` and `--taint-metrics`.
+
+use std::{fs, process::Command};
+
+mod common;
+
+#[test]
+fn runs_end_with_a_status_line_and_can_write_junit() {
+    let scratch = common::scratch_dir("status", "runs_end_with_a_status_line_and_can_write_junit");
+    let path = scratch.join("junit.xml");
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg("--taint-status-line=yes")
+        .arg(format!("--taint-junit={}", path.display()))
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/status/program.rs")
+        .output()
+        .expect("taint runs");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+
+    let status = stderr
+        .lines()
+        .find(|line| line.starts_with("taint: "))
+        .expect("the run prints a status line");
+    assert!(
+        status.starts_with("taint: 1 errors, 0 warnings, 1 entry points, "),
+        "{}",
+        status
+    );
+    assert!(status.ends_with(" seconds"), "{}", status);
+
+    let junit = fs::read_to_string(&path).unwrap();
+    assert!(junit.contains("<testsuite name=\"program\" tests=\"2\" failures=\"1\""));
+    assert!(junit.contains("<testcase name=\"analysis\" classname=\"program\"/>"));
+    assert!(junit
+        .contains("<failure type=\"T0001\" message=\"function `output` received tainted input\">"));
+}
+
+#[test]
+fn runs_can_write_their_metrics() {
+    let scratch = common::scratch_dir("status", "runs_can_write_their_metrics");
+    let path = scratch.join("metrics.prom");
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg(format!("--taint-metrics={}", path.display()))
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/status/program.rs")
+        .output()
+        .expect("taint runs");
+    assert!(!output.status.success());
+
+    let metrics = fs::read_to_string(&path).unwrap();
+    assert!(metrics.contains(
+        "taint_findings{crate=\"program\",rule=\"T0001\",severity=\"error\",label=\"taint\"} 1\n"
+    ));
+    assert!(metrics.contains("taint_errors{crate=\"program\"} 1\n"));
+    assert!(metrics.contains("taint_entry_points{crate=\"program\"} 1\n"));
+    assert!(metrics.contains("taint_coverage_ratio{crate=\"program\"} "));
+    assert!(metrics.ends_with("# EOF\n"));
+}
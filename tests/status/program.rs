@@ -0,0 +1,14 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+#[taint::source]
+fn input() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
+
+fn main() {
+    output(input());
+}
@@ -0,0 +1,51 @@
+//! Tests for `--taint-export-summaries` and `--taint-import-summaries`.
+
+use std::process::Command;
+
+use taint::summaries::{Flow, Summaries};
+
+mod common;
+
+#[test]
+fn summaries_tell_which_arguments_flow_where() {
+    let scratch = common::scratch_dir("summaries", "summaries_tell_which_arguments_flow_where");
+    let path = scratch.join("exported-summaries.json");
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg(format!("--taint-export-summaries={}", path.display()))
+        .args(["--out-dir", &scratch.display().to_string()])
+        .arg("tests/summaries/program.rs")
+        .output()
+        .expect("taint runs");
+    assert!(!output.status.success());
+
+    let summaries = Summaries::read(&path).unwrap();
+    let pick = summaries.get("pick").unwrap();
+    assert_eq!(
+        pick.returns,
+        Flow {
+            from: vec![0],
+            labels: vec![]
+        }
+    );
+    assert_eq!(pick.args.len(), 2);
+}
+
+#[test]
+fn imported_summaries_replace_missing_bodies() {
+    let scratch = common::scratch_dir("summaries", "imported_summaries_replace_missing_bodies");
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+            .args(args)
+            .args(["--out-dir", &scratch.display().to_string()])
+            .arg("tests/summaries/foreign.rs")
+            .output()
+            .expect("taint runs");
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    assert!(!run(&[]).contains("error[T0001]"));
+    assert!(
+        run(&["--taint-import-summaries=tests/summaries/foreign.json"])
+            .contains("error[T0001]: function `output` received tainted input")
+    );
+}
@@ -0,0 +1,19 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+extern "C" {
+    fn normalize(value: i32) -> i32;
+}
+
+fn main() {
+    let input = source();
+    output(unsafe { normalize(input) });
+}
+
+#[taint::source]
+fn source() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
@@ -0,0 +1,20 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    let input = source();
+    output(pick(input, 1));
+    output(pick(2, 3));
+}
+
+#[taint::source]
+fn source() -> i32 {
+    1
+}
+
+#[taint::sink]
+fn output(_: i32) {}
+
+fn pick(first: i32, _: i32) -> i32 {
+    first
+}
@@ -0,0 +1,49 @@
+//! Tests for selecting the functions `TAINT_LOG` traces.
+
+use std::{env, process::Command};
+
+/// The lines `TAINT_LOG=<filter>` makes the analysis of `tests/tracing/program.rs` log, without colors.
+fn traced(filter: &str) -> Vec<String> {
+    let out_dir = env::temp_dir().join(format!("taint-tracing-{}", std::process::id()));
+    let output = Command::new(env!("CARGO_BIN_EXE_taint"))
+        .arg("tests/tracing/program.rs")
+        .args(["--out-dir", &out_dir.display().to_string()])
+        .env("TAINT_LOG", filter)
+        .output()
+        .expect("taint runs");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    stdout.lines().map(without_colors).collect()
+}
+
+/// `line` without the escape sequences coloring it.
+fn without_colors(line: &str) -> String {
+    let mut plain = String::new();
+    let mut escaped = false;
+    for c in line.chars() {
+        match c {
+            '\u{1b}' => escaped = true,
+            'm' if escaped => escaped = false,
+            c if !escaped => plain.push(c),
+            _ => {}
+        }
+    }
+    plain
+}
+
+#[test]
+fn the_function_field_selects_a_single_function_by_its_path() {
+    let lines = traced("taint[{function=program::handler}]=trace");
+    // The spans of a line are those it is nested in, the innermost last.
+    let within = |function: &str| {
+        lines
+            .iter()
+            .filter(|line| line.contains("taint_domain"))
+            .filter_map(|line| line.rsplit("summarize{function=").next())
+            .filter(|innermost| innermost.starts_with(&format!("{}}}", function)))
+            .count()
+    };
+
+    assert!(within("program::handler") > 0);
+    assert_eq!(within("program::other"), 0);
+    assert_eq!(within("program::main"), 0);
+}
@@ -0,0 +1,20 @@
+#![feature(register_tool)]
+#![register_tool(taint)]
+
+fn main() {
+    handler(input());
+    other(input());
+}
+
+fn handler(value: u8) -> u8 {
+    value + 1
+}
+
+fn other(value: u8) -> u8 {
+    value + 2
+}
+
+#[taint::source]
+fn input() -> u8 {
+    1
+}
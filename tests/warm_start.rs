@@ -0,0 +1,147 @@
+//! Tests for carrying summaries over from one run of the analysis to the next, in the same process,
+//! like a watch mode does through the library.
+
+#![feature(rustc_private, internal_output_capture)]
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+
+use std::{
+    fs, io,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use rustc_interface::interface::Config;
+use taint::{eval::integration, options::Options, warm_start::WarmStart};
+
+mod common;
+
+/// Registers the analysis with the options of the run, like the driver of a tool does.
+struct Callbacks(Options);
+
+impl rustc_driver::Callbacks for Callbacks {
+    fn config(&mut self, config: &mut Config) {
+        integration::register(config, self.0.clone());
+    }
+}
+
+/// Compiles and analyzes `program` in this process with `options`, starting from `warm_start`, returning the lines
+/// of the summary report which tell how the summary of each function of the crate was had.
+fn analyze(program: &Path, warm_start: &WarmStart, options: Options) -> Vec<String> {
+    // The analysis is registered for the whole process, so runs of different tests cannot overlap.
+    static RUNNING: Mutex<()> = Mutex::new(());
+    let _running = RUNNING
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let options = Options {
+        summary_report: true,
+        warm_start: Some(warm_start.clone()),
+        ..options
+    };
+    let args = [
+        "rustc".to_owned(),
+        program.display().to_string(),
+        "--edition=2018".to_owned(),
+        "--emit=metadata".to_owned(),
+        "--out-dir".to_owned(),
+        program.parent().unwrap().display().to_string(),
+    ];
+    // The report is printed, so it is captured from the threads of the compiler.
+    let printed = Arc::new(Mutex::new(vec![]));
+    let captured = io::set_output_capture(Some(printed.clone()));
+    let compiled = rustc_driver::catch_fatal_errors(|| {
+        rustc_driver::RunCompiler::new(&args, &mut Callbacks(options)).run()
+    });
+    io::set_output_capture(captured);
+    assert!(matches!(compiled, Ok(Ok(()))));
+
+    let printed = String::from_utf8(printed.lock().unwrap().clone()).unwrap();
+    printed
+        .lines()
+        .filter(|line| line.starts_with("  `") && !line.contains("`std::"))
+        .map(|line| line.trim().to_owned())
+        .collect()
+}
+
+#[test]
+fn a_second_run_reuses_the_summaries_of_the_first() {
+    let scratch = common::scratch_dir(
+        "warm_start",
+        "a_second_run_reuses_the_summaries_of_the_first",
+    );
+    let program = scratch.join("program.rs");
+    fs::copy("tests/warm_start/program.rs", &program).unwrap();
+    let warm_start = WarmStart::default();
+
+    assert_eq!(
+        analyze(&program, &warm_start, Options::default()),
+        vec![
+            "`main`: 1 context, needed by 1 entry point (`main`)",
+            "`offset`: 1 context, needed by 1 entry point (`main`)",
+            "`scale`: 1 context, needed by 1 entry point (`main`)",
+        ]
+    );
+    // The summary of `main` is carried over, so the functions it calls are not even looked at.
+    assert_eq!(
+        analyze(&program, &warm_start, Options::default()),
+        vec!["`main`: 0 contexts, needed by 1 entry point (`main`), 1 context carried over from the previous run"]
+    );
+
+    // Once the file changed, its functions are analyzed again.
+    let source = fs::read_to_string(&program).unwrap();
+    fs::write(&program, source.replace("value * 2", "value * 3")).unwrap();
+    assert_eq!(
+        analyze(&program, &warm_start, Options::default()),
+        vec![
+            "`main`: 1 context, needed by 1 entry point (`main`)",
+            "`offset`: 1 context, needed by 1 entry point (`main`)",
+            "`scale`: 1 context, needed by 1 entry point (`main`)",
+        ]
+    );
+}
+
+#[test]
+fn editing_a_propagation_rule_drops_the_contexts_which_called_the_function() {
+    let scratch = common::scratch_dir(
+        "warm_start",
+        "editing_a_propagation_rule_drops_the_contexts_which_called_the_function",
+    );
+    let program = scratch.join("program.rs");
+    fs::copy("tests/warm_start/rules.rs", &program).unwrap();
+    let config = scratch.join("taint.json");
+    let rule = |result_from: &str| {
+        let rule = format!(
+            r#"{{ "version": 1, "propagation": {{ "convert": {{ "result_from": {} }} }} }}"#,
+            result_from
+        );
+        fs::write(&config, rule).unwrap();
+    };
+    let options = Options {
+        config: Some(config.clone()),
+        ..Options::default()
+    };
+    let warm_start = WarmStart::default();
+
+    rule("[0]");
+    assert_eq!(
+        analyze(&program, &warm_start, options.clone()),
+        vec![
+            "`direct`: 1 context, needed by 1 entry point (`main`)",
+            "`main`: 1 context, needed by 1 entry point (`main`)",
+            "`mapped`: 1 context, needed by 1 entry point (`main`)",
+            "`other`: 1 context, needed by 1 entry point (`main`)",
+        ]
+    );
+    // `mapped` only calls `convert` through `Option::map`, which is a callee of it all the same.
+    rule("[]");
+    assert_eq!(
+        analyze(&program, &warm_start, options),
+        vec![
+            "`direct`: 1 context, needed by 1 entry point (`main`)",
+            "`main`: 1 context, needed by 1 entry point (`main`)",
+            "`mapped`: 1 context, needed by 1 entry point (`main`)",
+            "`other`: 0 contexts, needed by 1 entry point (`main`), 1 context carried over from the previous run",
+        ]
+    );
+}
@@ -0,0 +1,11 @@
+fn main() {
+    let _ = scale(offset(1));
+}
+
+fn scale(value: i32) -> i32 {
+    value * 2
+}
+
+fn offset(value: i32) -> i32 {
+    value + 1
+}
@@ -0,0 +1,21 @@
+fn main() {
+    let _ = mapped(1);
+    let _ = direct(1);
+    let _ = other(1);
+}
+
+fn mapped(value: i32) -> Option<i32> {
+    Some(value).map(convert)
+}
+
+fn direct(value: i32) -> i32 {
+    convert(value)
+}
+
+fn other(value: i32) -> i32 {
+    value + 1
+}
+
+fn convert(value: i32) -> i32 {
+    value
+}